@@ -19,7 +19,11 @@ use deno_core::resolve_url_or_path;
 use deno_core::url::Url;
 use deno_graph::GraphKind;
 use deno_runtime::deno_permissions::parse_sys_kind;
+use deno_runtime::deno_permissions::split_ffi_symbols;
+use deno_runtime::deno_permissions::split_run_arguments;
+use deno_runtime::deno_permissions::split_write_operations;
 use deno_runtime::deno_permissions::PermissionsOptions;
+use deno_runtime::deno_permissions::WriteOperation;
 use log::debug;
 use log::Level;
 use serde::Deserialize;
@@ -39,6 +43,7 @@ use crate::args::resolve_no_prompt;
 use crate::util::fs::canonicalize_path;
 
 use super::flags_net;
+use super::import_permissions;
 use super::DENO_FUTURE;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -83,12 +88,54 @@ pub struct AddFlags {
   pub packages: Vec<String>,
 }
 
+/// How much slower than in the baseline a bench may get with
+/// `deno bench --compare=<file> --fail-threshold=<percent>%`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BenchFailThreshold {
+  /// Hundredths of a percent.
+  basis_points: u32,
+}
+
+impl BenchFailThreshold {
+  pub fn percent(&self) -> f64 {
+    self.basis_points as f64 / 100.0
+  }
+}
+
+impl std::fmt::Display for BenchFailThreshold {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}%", self.percent())
+  }
+}
+
+impl FromStr for BenchFailThreshold {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let percent = s
+      .strip_suffix('%')
+      .unwrap_or(s)
+      .parse::<f64>()
+      .ok()
+      .filter(|percent| percent.is_finite() && *percent >= 0.0)
+      .ok_or_else(|| {
+        format!("Invalid threshold '{s}', expected a percentage, ex. 5%")
+      })?;
+    Ok(Self {
+      basis_points: (percent * 100.0).round() as u32,
+    })
+  }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct BenchFlags {
   pub files: FileFlags,
   pub filter: Option<String>,
   pub json: bool,
   pub no_run: bool,
+  pub save_baseline: Option<String>,
+  pub compare: Option<String>,
+  pub fail_threshold: Option<BenchFailThreshold>,
   pub watch: Option<WatchFlags>,
 }
 
@@ -102,6 +149,8 @@ pub struct BundleFlags {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CacheFlags {
   pub files: Vec<String>,
+  pub verify_npm: bool,
+  pub repair: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -250,6 +299,21 @@ pub struct JupyterFlags {
   pub conn_file: Option<String>,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KvAction {
+  Backup,
+  Restore,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KvFlags {
+  pub action: KvAction,
+  /// The path of the database to back up or restore.
+  pub db: String,
+  /// The path of the backup file.
+  pub path: String,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UninstallFlagsGlobal {
   pub name: String,
@@ -357,13 +421,47 @@ pub struct TaskFlags {
   pub task: Option<String>,
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub enum TestReporterConfig {
   #[default]
   Pretty,
   Dot,
   Junit,
   Tap,
+  /// The path or URL of a reporter module.
+  Custom(String),
+}
+
+/// One of the parts `deno test --shard=<index>/<count>` splits the tests
+/// into.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TestShard {
+  /// Starts at 1.
+  pub index: NonZeroUsize,
+  pub count: NonZeroUsize,
+}
+
+impl std::fmt::Display for TestShard {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}/{}", self.index, self.count)
+  }
+}
+
+impl FromStr for TestShard {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || format!("Invalid shard '{s}', expected <index>/<count>");
+    let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+    let index = index.parse::<NonZeroUsize>().map_err(|_| invalid())?;
+    let count = count.parse::<NonZeroUsize>().map_err(|_| invalid())?;
+    if index > count {
+      return Err(format!(
+        "Shard index {index} is greater than the shard count {count}"
+      ));
+    }
+    Ok(Self { index, count })
+  }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -377,6 +475,10 @@ pub struct TestFlags {
   pub allow_none: bool,
   pub filter: Option<String>,
   pub shuffle: Option<u64>,
+  pub shard: Option<TestShard>,
+  pub retries: usize,
+  pub update_snapshots: bool,
+  pub isolate: bool,
   pub concurrent_jobs: Option<NonZeroUsize>,
   pub trace_leaks: bool,
   pub watch: Option<WatchFlags>,
@@ -426,6 +528,7 @@ pub enum DenoSubcommand {
   Info(InfoFlags),
   Install(InstallFlags),
   Jupyter(JupyterFlags),
+  Kv(KvFlags),
   Uninstall(UninstallFlags),
   Lsp,
   Lint(LintFlags),
@@ -546,9 +649,22 @@ pub struct UnstableConfig {
   pub bare_node_builtins: bool,  // --unstable-bare-node-builts
   pub byonm: bool,
   pub sloppy_imports: bool,
+  pub io_uring: bool,
   pub features: Vec<String>, // --unstabe-kv --unstable-cron
 }
 
+/// How the packages in a local node_modules directory are laid out.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NodeModulesDirLayout {
+  /// The files of each package are copied from the global cache.
+  #[default]
+  Copied,
+  /// The files of each package are hard linked from the global cache, which
+  /// serves as a store of packages shared by all projects, and a package can
+  /// only resolve its own dependencies. See `--node-modules-dir=isolated`.
+  Isolated,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Flags {
   /// Vector of CLI arguments - these are user script arguments, all Deno
@@ -567,6 +683,7 @@ pub struct Flags {
   pub type_check_mode: TypeCheckMode,
   pub config_flag: ConfigFlag,
   pub node_modules_dir: Option<bool>,
+  pub node_modules_dir_layout: NodeModulesDirLayout,
   pub vendor: Option<bool>,
   pub enable_op_summary_metrics: bool,
   pub enable_testing_features: bool,
@@ -615,7 +732,13 @@ pub struct PermissionFlags {
   pub deny_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<String>>,
   pub deny_write: Option<Vec<String>>,
+  pub allow_import: Option<Vec<String>>,
+  pub deny_import: Option<Vec<String>>,
   pub no_prompt: bool,
+  pub permission_audit_log: Option<String>,
+  pub permission_policy: Option<String>,
+  pub kernel_sandbox: bool,
+  pub enforce_wx: bool,
 }
 
 impl PermissionFlags {
@@ -637,6 +760,8 @@ impl PermissionFlags {
       || self.deny_sys.is_some()
       || self.allow_write.is_some()
       || self.deny_write.is_some()
+      || self.allow_import.is_some()
+      || self.deny_import.is_some()
   }
 
   pub fn to_options(
@@ -695,6 +820,7 @@ impl PermissionFlags {
         &self.deny_write,
         initial_cwd,
       )?,
+      enforce_wx: self.enforce_wx,
       prompt: !resolve_no_prompt(self),
     })
   }
@@ -826,7 +952,12 @@ impl Flags {
         args.push("--allow-run".to_string());
       }
       Some(run_allowlist) => {
-        let s = format!("--allow-run={}", run_allowlist.join(","));
+        let s = format!(
+          "--allow-run={}",
+          join_restricted_entries_last(run_allowlist, |entry| {
+            split_run_arguments(entry).is_some()
+          })
+        );
         args.push(s);
       }
       _ => {}
@@ -870,7 +1001,12 @@ impl Flags {
         args.push("--allow-ffi".to_string());
       }
       Some(ffi_allowlist) => {
-        let s = format!("--allow-ffi={}", join_paths(ffi_allowlist, ","));
+        let s = format!(
+          "--allow-ffi={}",
+          join_restricted_entries_last(ffi_allowlist, |entry| {
+            split_ffi_symbols(entry).is_some()
+          })
+        );
         args.push(s);
       }
       _ => {}
@@ -887,6 +1023,28 @@ impl Flags {
       _ => {}
     }
 
+    match &self.permissions.allow_import {
+      Some(import_allowlist) if import_allowlist.is_empty() => {
+        args.push("--allow-import".to_string());
+      }
+      Some(import_allowlist) => {
+        let s = format!("--allow-import={}", import_allowlist.join(","));
+        args.push(s);
+      }
+      _ => {}
+    }
+
+    match &self.permissions.deny_import {
+      Some(import_denylist) if import_denylist.is_empty() => {
+        args.push("--deny-import".to_string());
+      }
+      Some(import_denylist) => {
+        let s = format!("--deny-import={}", import_denylist.join(","));
+        args.push(s);
+      }
+      _ => {}
+    }
+
     if self.permissions.allow_hrtime {
       args.push("--allow-hrtime".to_string());
     }
@@ -895,6 +1053,52 @@ impl Flags {
       args.push("--deny-hrtime".to_string());
     }
 
+    if self.permissions.kernel_sandbox {
+      args.push("--sandbox=kernel".to_string());
+    }
+
+    if self.permissions.enforce_wx {
+      args.push("--enforce-wx".to_string());
+    }
+
+    args
+  }
+
+  /// Return list of arguments that configure module resolution and unstable
+  /// features, equivalent to the ones used to create `self`.
+  pub fn to_resolution_args(&self) -> Vec<String> {
+    let mut args = vec![];
+
+    match &self.config_flag {
+      ConfigFlag::Discover => {}
+      ConfigFlag::Path(path) => args.push(format!("--config={path}")),
+      ConfigFlag::Disabled => args.push("--no-config".to_string()),
+    }
+
+    if let Some(import_map_path) = &self.import_map_path {
+      args.push(format!("--import-map={import_map_path}"));
+    }
+
+    let unstable = &self.unstable_config;
+    if unstable.legacy_flag_enabled {
+      args.push("--unstable".to_string());
+    }
+    if unstable.bare_node_builtins {
+      args.push("--unstable-bare-node-builtins".to_string());
+    }
+    if unstable.byonm {
+      args.push("--unstable-byonm".to_string());
+    }
+    if unstable.sloppy_imports {
+      args.push("--unstable-sloppy-imports".to_string());
+    }
+    if unstable.io_uring {
+      args.push("--unstable-io-uring".to_string());
+    }
+    for feature in &unstable.features {
+      args.push(format!("--unstable-{feature}"));
+    }
+
     args
   }
 
@@ -994,6 +1198,8 @@ impl Flags {
         || arg.starts_with("--deny-sys")
         || arg.starts_with("--allow-write")
         || arg.starts_with("--deny-write")
+        || arg.starts_with("--allow-import")
+        || arg.starts_with("--deny-import")
     })
   }
 
@@ -1084,6 +1290,14 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
     <g>DENO_FUTURE</>          Set to "1" to enable APIs that will take effect in
                          Deno 2
 
+    <g>DENO_BROADCAST_CHANNEL_SOCKET</>
+                         Unix socket through which BroadcastChannel messages
+                         are shared with other processes using the same path
+
+    <g>DENO_CACHE_STORAGE_MAX_SIZE</>
+                         Maximum size in bytes of the Cache API storage.
+                         Least recently used entries are evicted beyond it.
+
     <g>DENO_CERT</>            Load certificate authorities from PEM encoded file
 
     <g>DENO_DIR</>             Set the cache directory
@@ -1095,6 +1309,10 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
                          flag with the test subcommand. Defaults to number
                          of available CPUs.
 
+    <g>DENO_LOCAL_STORAGE_PATH</>
+                         Path of the localStorage database, regardless of
+                         --location. Use ":memory:" to keep it in memory
+
     <g>DENO_REPL_HISTORY</>    Set REPL history file path
                          History file is disabled when the value is empty
                          (defaults to $DENO_DIR/deno_history.txt)
@@ -1115,6 +1333,10 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
 
     <g>DENO_WEBGPU_TRACE</>    Directory to use for wgpu traces
 
+    <g>DENO_WEB_STORAGE_MAX_SIZE</>
+                         Maximum size in bytes of localStorage and
+                         sessionStorage each (defaults to 10MiB)
+
     <g>HTTP_PROXY</>           Proxy address for HTTP requests
                          (module downloads, fetch)
 
@@ -1178,6 +1400,7 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
   flags.unstable_config.byonm = matches.get_flag("unstable-byonm");
   flags.unstable_config.sloppy_imports =
     matches.get_flag("unstable-sloppy-imports");
+  flags.unstable_config.io_uring = matches.get_flag("unstable-io-uring");
 
   if matches.get_flag("quiet") {
     flags.log_level = Some(Level::Error);
@@ -1207,6 +1430,7 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
       "info" => info_parse(&mut flags, &mut m),
       "install" => install_parse(&mut flags, &mut m),
       "jupyter" => jupyter_parse(&mut flags, &mut m),
+      "kv" => kv_parse(&mut flags, &mut m),
       "lint" => lint_parse(&mut flags, &mut m),
       "lsp" => lsp_parse(&mut flags, &mut m),
       "repl" => repl_parse(&mut flags, &mut m),
@@ -1323,6 +1547,17 @@ fn clap_root() -> Command {
         .value_parser(FalseyValueParser::new())
         .action(ArgAction::SetTrue)
         .global(true),
+    )
+    .arg(
+      Arg::new("unstable-io-uring")
+        .long("unstable-io-uring")
+        .help(
+          "Enable unstable io_uring backend for async file system operations on Linux",
+        )
+        .env("DENO_UNSTABLE_IO_URING")
+        .value_parser(FalseyValueParser::new())
+        .action(ArgAction::SetTrue)
+        .global(true),
     );
 
   for (flag_name, help, _) in crate::UNSTABLE_GRANULAR_FLAGS {
@@ -1378,6 +1613,7 @@ fn clap_root() -> Command {
           install_subcommand()
         })
         .subcommand(jupyter_subcommand())
+        .subcommand(kv_subcommand())
         .subcommand(uninstall_subcommand())
         .subcommand(lsp_subcommand())
         .subcommand(lint_subcommand())
@@ -1470,6 +1706,31 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
             .help("Cache bench modules, but don't run benchmarks")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("save-baseline")
+            .long("save-baseline")
+            .value_name("FILE")
+            .require_equals(true)
+            .help("Save the results to FILE, to compare later runs to them with --compare")
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("compare")
+            .long("compare")
+            .value_name("FILE")
+            .require_equals(true)
+            .help("Compare the results to a baseline saved with --save-baseline")
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("fail-threshold")
+            .long("fail-threshold")
+            .value_name("PERCENT")
+            .require_equals(true)
+            .requires("compare")
+            .help("Fail when a bench is more than PERCENT slower than in the baseline of --compare, ex. --fail-threshold=5%")
+            .value_parser(value_parser!(BenchFailThreshold)),
+        )
         .arg(watch_arg(false))
         .arg(watch_exclude_arg())
         .arg(no_clear_screen_arg())
@@ -1522,7 +1783,12 @@ them in the local cache, without running any code:
   deno cache jsr:@std/http/file-server
 
 Future runs of this module will trigger no downloads or compilation unless
---reload is specified.",
+--reload is specified.
+
+Verify the npm packages in the global cache against the tarballs from the
+registry, and restore the files that are missing or were modified:
+
+  deno cache --verify-npm --repair",
     )
     .defer(|cmd| {
       compile_args(cmd)
@@ -1530,11 +1796,24 @@ Future runs of this module will trigger no downloads or compilation unless
         .arg(
           Arg::new("file")
             .num_args(1..)
-            .required(true)
+            .required_unless_present("verify-npm")
             .value_hint(ValueHint::FilePath),
         )
         .arg(frozen_lockfile_arg())
         .arg(allow_scripts_arg())
+        .arg(
+          Arg::new("verify-npm")
+            .long("verify-npm")
+            .action(ArgAction::SetTrue)
+            .help("Verify the npm packages in the global cache"),
+        )
+        .arg(
+          Arg::new("repair")
+            .long("repair")
+            .requires("verify-npm")
+            .action(ArgAction::SetTrue)
+            .help("Restore the corrupted files found by --verify-npm"),
+        )
     })
 }
 
@@ -2315,6 +2594,48 @@ fn jupyter_subcommand() -> Command {
     .about("Deno kernel for Jupyter notebooks")
 }
 
+fn kv_subcommand() -> Command {
+  let db_arg = Arg::new("db")
+    .long("db")
+    .help("Path to the SQLite database of Deno KV")
+    .required(true)
+    .value_parser(value_parser!(String))
+    .value_hint(ValueHint::FilePath);
+  let path_arg = Arg::new("path")
+    .help("Path to the backup file")
+    .required(true)
+    .value_parser(value_parser!(String))
+    .value_hint(ValueHint::FilePath);
+  Command::new("kv")
+    .about("Back up and restore Deno KV databases")
+    .long_about(
+      "Back up and restore the SQLite databases used by Deno KV.
+
+A backup is a consistent snapshot of the database, even while it is being
+written to, and keeps the versionstamps of all entries:
+
+  deno kv backup --db=kv.sqlite3 kv.backup
+
+Restoring a backup replaces the contents of the database. It should not be
+open in other processes while it is restored:
+
+  deno kv restore --db=kv.sqlite3 kv.backup",
+    )
+    .subcommand_required(true)
+    .subcommand(
+      Command::new("backup")
+        .about("Write a consistent snapshot of a database to a file")
+        .arg(db_arg.clone())
+        .arg(path_arg.clone()),
+    )
+    .subcommand(
+      Command::new("restore")
+        .about("Replace the contents of a database with a backup")
+        .arg(db_arg)
+        .arg(path_arg),
+    )
+}
+
 fn uninstall_subcommand() -> Command {
   Command::new("uninstall")
       .about("Uninstall a script previously installed with deno install")
@@ -2703,6 +3024,34 @@ Directory arguments are expanded to all contained files matching the glob
         .require_equals(true)
         .value_parser(value_parser!(u64)),
     )
+    .arg(
+      Arg::new("shard")
+        .long("shard")
+        .value_name("INDEX/COUNT")
+        .help("Run only one of COUNT parts of the tests, ex. --shard=3/8. Tests are assigned to parts by their file and name, so that separate CI jobs can split a test suite between them")
+        .require_equals(true)
+        .value_parser(value_parser!(TestShard)),
+    )
+    .arg(
+      Arg::new("retries")
+        .long("retries")
+        .value_name("N")
+        .help("Run a failing test up to N more times. Tests that pass on a later attempt are reported as flaky. Overridden by the 'retry' option of a test")
+        .require_equals(true)
+        .value_parser(value_parser!(usize)),
+    )
+    .arg(
+      Arg::new("update-snapshots")
+        .long("update-snapshots")
+        .help("Create missing snapshots and update the mismatching ones of t.assertSnapshot() and t.assertInlineSnapshot() instead of failing")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("isolate")
+        .long("isolate")
+        .help("Run each test file with only the permissions declared in its header, ex. '// deno-test-permissions --allow-read=./fixtures', instead of all the permissions of the command. Files without the header get no permissions. Like without this flag, each file runs in its own worker in the same process: this limits what a file can do through Deno APIs, it doesn't isolate its memory or native code (FFI) from the other files")
+        .action(ArgAction::SetTrue),
+    )
     .arg(
       Arg::new("coverage")
         .long("coverage")
@@ -2764,13 +3113,33 @@ Note: running multiple `deno test --clean` calls in series or parallel for the s
     .arg(
       Arg::new("reporter")
         .long("reporter")
-        .help("Select reporter to use. Default to 'pretty'.")
-        .value_parser(["pretty", "dot", "junit", "tap"])
+        .value_name("REPORTER")
+        .help("Select reporter to use: 'pretty', 'dot', 'junit', 'tap' or the path or URL of a reporter module, ex. './reporter.ts'. Default to 'pretty'.
+
+A reporter module default exports a function, which is called with an async iterable of the test events as JSON objects. It runs with the permissions of the tests.")
+        .value_parser(test_reporter_parser)
     )
     .arg(env_file_arg())
   )
 }
 
+fn test_reporter_parser(reporter: &str) -> Result<String, String> {
+  let is_builtin = matches!(reporter, "pretty" | "dot" | "junit" | "tap");
+  // anything else has to look like a module, so that typos of the built-in
+  // reporters are caught here
+  let is_module = reporter.starts_with("./")
+    || reporter.starts_with("../")
+    || Path::new(reporter).is_absolute()
+    || Url::parse(reporter).is_ok();
+  if is_builtin || is_module {
+    Ok(reporter.to_string())
+  } else {
+    Err(format!(
+      "Expected 'pretty', 'dot', 'junit', 'tap' or the path of a reporter module, ex. './{reporter}.ts'"
+    ))
+  }
+}
+
 fn types_subcommand() -> Command {
   Command::new("types")
     .about("Print runtime TypeScript declarations")
@@ -2973,14 +3342,51 @@ static DENY_READ_HELP: &str = concat!(
   "  --deny-read=\"/etc,/var/log.txt\""
 );
 
+static PERMISSION_AUDIT_LOG_HELP: &str = concat!(
+  "Append a JSON line to the given file for every permission check,\n",
+  "recording the permission, descriptor, calling API, decision and time.\n",
+  "Examples:\n",
+  "  --permission-audit-log=permissions.jsonl"
+);
+
+static PERMISSION_POLICY_HELP: &str = concat!(
+  "Add the permissions described by a JSON policy file. Its \"allow\" and\n",
+  "\"deny\" objects map permission names to true or a list of values, and\n",
+  "\"include\" lists other policy files. Paths are relative to the file.\n",
+  "Examples:\n",
+  "  --permission-policy=policy.jsonc"
+);
+
+static SANDBOX_HELP: &str = concat!(
+  "Additionally enforce the granted permissions with the operating system.\n",
+  "\"kernel\" confines file system access with Landlock and denies starting\n",
+  "subprocesses and opening network sockets with seccomp unless allowed.\n",
+  "Permissions can't be granted by prompts in this mode. Linux only.\n",
+  "Examples:\n",
+  "  --sandbox=kernel"
+);
+
+static ENFORCE_WX_HELP: &str = concat!(
+  "Deny APIs that need memory which is both writable and executable, like\n",
+  "Deno.UnsafeCallback, and run V8 without its JIT compilers (--jitless), so\n",
+  "that JavaScript is interpreted and WebAssembly is unavailable.\n",
+  "Denied calls are recorded in the permission audit log. Native code loaded\n",
+  "with Deno.dlopen() or Node-API addons can still map such memory itself.\n",
+  "Examples:\n",
+  "  --enforce-wx"
+);
+
 static ALLOW_WRITE_HELP: &str = concat!(
   "Allow file system write access. Optionally specify allowed paths.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
+  "Append :<operations> to a path to only allow some kinds of writes under it\n",
+  "(create, append, modify, delete, rename, chmod).\n",
   "Examples:\n",
   "  --allow-write\n",
-  "  --allow-write=\"/etc,/var/log.txt\""
+  "  --allow-write=\"/etc,/var/log.txt\"\n",
+  "  --allow-write=\"logs/:create,append\""
 );
 
 static DENY_WRITE_HELP: &str = concat!(
@@ -2998,9 +3404,16 @@ static ALLOW_NET_HELP: &str = concat!(
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
+  "Hosts can be CIDR ranges or *.<domain> wildcards, and ports can be ranges\n",
+  "or \"any\". Unix domain sockets are given as unix:<path>, where a directory\n",
+  "allows every socket below it. Other sockets need --allow-read and\n",
+  "--allow-write for their path. CIDR ranges only match IP addresses, not\n",
+  "host names that resolve to addresses in them.\n",
   "Examples:\n",
   "  --allow-net\n",
-  "  --allow-net=\"localhost:8080,deno.land\""
+  "  --allow-net=\"localhost:8080,deno.land\"\n",
+  "  --allow-net=\"10.0.0.0/8:443,*.internal:8000-8100\"\n",
+  "  --allow-net=\"unix:/run/app.sock\""
 );
 
 static DENY_NET_HELP: &str = concat!(
@@ -3013,14 +3426,37 @@ static DENY_NET_HELP: &str = concat!(
   "  --deny-net=\"localhost:8080,deno.land\""
 );
 
+static ALLOW_IMPORT_HELP: &str = concat!(
+  "Allow importing remote modules only from the given hosts. Remote modules can be imported from any host by default.\n",
+  "Docs: https://deno.land/manual@v",
+  env!("CARGO_PKG_VERSION"),
+  "/basics/permissions\n",
+  "Hosts can be *.<domain> wildcards and be followed by a port and a path\n",
+  "prefix, which has to match whole path segments.\n",
+  "Examples:\n",
+  "  --allow-import=\"jsr.io,registry.internal:8443/deno/\""
+);
+
+static DENY_IMPORT_HELP: &str = concat!(
+  "Deny importing remote modules from the given hosts, or from every host without a value.\n",
+  "Docs: https://deno.land/manual@v",
+  env!("CARGO_PKG_VERSION"),
+  "/basics/permissions\n",
+  "Examples:\n",
+  "  --deny-import\n",
+  "  --deny-import=\"jsr.io/@untrusted\""
+);
+
 static ALLOW_ENV_HELP: &str = concat!(
   "Allow access to system environment information. Optionally specify accessible environment variables.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
+  "A * in a variable name matches any characters.\n",
   "Examples:\n",
   "  --allow-env\n",
-  "  --allow-env=\"PORT,HOME,PATH\""
+  "  --allow-env=\"PORT,HOME,PATH\"\n",
+  "  --allow-env=\"AWS_*\""
 );
 
 static DENY_ENV_HELP: &str = concat!(
@@ -3058,9 +3494,12 @@ static ALLOW_RUN_HELP: &str = concat!(
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
+  "Append :<arguments> to a program to only allow running it with those first\n",
+  "arguments. Programs with arguments must come last in a flag value.\n",
   "Examples:\n",
   "  --allow-run\n",
-  "  --allow-run=\"whoami,ps\""
+  "  --allow-run=\"whoami,ps\"\n",
+  "  --allow-run=\"git:status,log\""
 );
 
 static DENY_RUN_HELP: &str = concat!(
@@ -3078,9 +3517,14 @@ static ALLOW_FFI_HELP: &str = concat!(
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
+  "Append :<symbols> to a library to only allow looking up those symbols in it,\n",
+  "where * matches any characters. Libraries with symbols must come last in a\n",
+  "flag value. Raw pointers can't be read from or called while symbols are\n",
+  "restricted, as they could point anywhere.\n",
   "Examples:\n",
   "  --allow-ffi\n",
-  "  --allow-ffi=\"./libfoo.so\""
+  "  --allow-ffi=\"./libfoo.so\"\n",
+  "  --allow-ffi=\"libcrypto.so:EVP_*,RAND_bytes\""
 );
 
 static DENY_FFI_HELP: &str = concat!(
@@ -3180,6 +3624,26 @@ fn permission_args(app: Command) -> Command {
         .help(DENY_NET_HELP)
         .value_parser(flags_net::validator),
     )
+    .arg(
+      Arg::new("allow-import")
+        .long("allow-import")
+        .num_args(0..)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .value_name("HOST_AND_PATH")
+        .help(ALLOW_IMPORT_HELP)
+        .value_parser(import_permissions::validator),
+    )
+    .arg(
+      Arg::new("deny-import")
+        .long("deny-import")
+        .num_args(0..)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .value_name("HOST_AND_PATH")
+        .help(DENY_IMPORT_HELP)
+        .value_parser(import_permissions::validator),
+    )
     .arg(unsafely_ignore_certificate_errors_arg())
     .arg(
       Arg::new("allow-env")
@@ -3306,6 +3770,36 @@ fn permission_args(app: Command) -> Command {
         .action(ArgAction::SetTrue)
         .help("Always throw if required permission wasn't passed"),
     )
+    .arg(
+      Arg::new("permission-audit-log")
+        .long("permission-audit-log")
+        .require_equals(true)
+        .value_name("FILE")
+        .help(PERMISSION_AUDIT_LOG_HELP)
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("permission-policy")
+        .long("permission-policy")
+        .require_equals(true)
+        .value_name("FILE")
+        .help(PERMISSION_POLICY_HELP)
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("sandbox")
+        .long("sandbox")
+        .require_equals(true)
+        .value_name("MODE")
+        .value_parser(["kernel"])
+        .help(SANDBOX_HELP),
+    )
+    .arg(
+      Arg::new("enforce-wx")
+        .long("enforce-wx")
+        .action(ArgAction::SetTrue)
+        .help(ENFORCE_WX_HELP),
+    )
 }
 
 fn runtime_args(
@@ -3734,10 +4228,10 @@ fn node_modules_dir_arg() -> Arg {
   Arg::new("node-modules-dir")
     .long("node-modules-dir")
     .num_args(0..=1)
-    .value_parser(value_parser!(bool))
+    .value_parser(["true", "false", "isolated"])
     .default_missing_value("true")
     .require_equals(true)
-    .help("Enables or disables the use of a local node_modules folder for npm packages")
+    .help("Enables or disables the use of a local node_modules folder for npm packages. With 'isolated', the files of the packages are hard linked from the global cache instead of copied, and packages can only resolve their own dependencies. Editing a file of a package then edits it for every project")
 }
 
 fn vendor_arg() -> Arg {
@@ -3834,6 +4328,9 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     filter,
     json,
     no_run,
+    save_baseline: matches.remove_one::<String>("save-baseline"),
+    compare: matches.remove_one::<String>("compare"),
+    fail_threshold: matches.remove_one::<BenchFailThreshold>("fail-threshold"),
     watch: watch_arg_parse(matches),
   });
 }
@@ -3866,8 +4363,17 @@ fn cache_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   compile_args_parse(flags, matches);
   frozen_lockfile_arg_parse(flags, matches);
   allow_scripts_arg_parse(flags, matches);
-  let files = matches.remove_many::<String>("file").unwrap().collect();
-  flags.subcommand = DenoSubcommand::Cache(CacheFlags { files });
+  let files = matches
+    .remove_many::<String>("file")
+    .map(|f| f.collect())
+    .unwrap_or_default();
+  let verify_npm = matches.get_flag("verify-npm");
+  let repair = matches.get_flag("repair");
+  flags.subcommand = DenoSubcommand::Cache(CacheFlags {
+    files,
+    verify_npm,
+    repair,
+  });
 }
 
 fn check_parse(flags: &mut Flags, matches: &mut ArgMatches) {
@@ -4172,6 +4678,20 @@ fn jupyter_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   });
 }
 
+fn kv_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let (action, mut matches) = matches.remove_subcommand().unwrap();
+  let action = match action.as_str() {
+    "backup" => KvAction::Backup,
+    "restore" => KvAction::Restore,
+    _ => unreachable!(),
+  };
+  flags.subcommand = DenoSubcommand::Kv(KvFlags {
+    action,
+    db: matches.remove_one::<String>("db").unwrap(),
+    path: matches.remove_one::<String>("path").unwrap(),
+  });
+}
+
 fn uninstall_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   let root = matches.remove_one::<String>("root");
   let global = matches.get_flag("global");
@@ -4511,13 +5031,18 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
         "junit" => TestReporterConfig::Junit,
         "dot" => TestReporterConfig::Dot,
         "tap" => TestReporterConfig::Tap,
-        _ => unreachable!(),
+        _ => TestReporterConfig::Custom(reporter),
       }
     } else {
       TestReporterConfig::Pretty
     };
 
-  if matches!(reporter, TestReporterConfig::Dot | TestReporterConfig::Tap) {
+  if matches!(
+    reporter,
+    TestReporterConfig::Dot
+      | TestReporterConfig::Tap
+      | TestReporterConfig::Custom(_)
+  ) {
     flags.log_level = Some(Level::Error);
   }
 
@@ -4530,6 +5055,10 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     files: FileFlags { include, ignore },
     filter,
     shuffle,
+    shard: matches.remove_one::<TestShard>("shard"),
+    retries: matches.remove_one::<usize>("retries").unwrap_or_default(),
+    update_snapshots: matches.get_flag("update-snapshots"),
+    isolate: matches.get_flag("isolate"),
     allow_none,
     concurrent_jobs,
     trace_leaks,
@@ -4613,6 +5142,62 @@ fn compile_args_without_check_parse(
   ca_file_arg_parse(flags, matches);
 }
 
+/// Rejoins entries like `--allow-run=<program>:<argument>,...` whose lists
+/// were split on commas along with the other values of the flag. A list runs
+/// to the end of the flag value it starts in.
+fn join_restricted_entries(
+  occurrences: impl Iterator<Item = impl Iterator<Item = String>>,
+  is_restricted: impl Fn(&str) -> bool,
+) -> Vec<String> {
+  let mut entries: Vec<String> = Vec::new();
+  for values in occurrences {
+    let mut in_list = false;
+    for value in values {
+      if is_restricted(&value) {
+        in_list = true;
+      } else if let (true, Some(last)) = (in_list, entries.last_mut()) {
+        last.push(',');
+        last.push_str(&value);
+        continue;
+      }
+      entries.push(value);
+    }
+  }
+  entries
+}
+
+/// Joins the entries of a flag value with the entries accepted by
+/// [`join_restricted_entries`] last, since their lists run to the end of it.
+fn join_restricted_entries_last(
+  entries: &[String],
+  is_restricted: impl Fn(&str) -> bool,
+) -> String {
+  let (restricted, plain): (Vec<_>, Vec<_>) = entries
+    .iter()
+    .map(String::as_str)
+    .partition(|entry| is_restricted(entry));
+  [plain, restricted].concat().join(",")
+}
+
+/// Rejoins `--allow-write=<path>:<operation>,...` entries whose operation
+/// lists were split on commas along with the paths.
+fn join_write_operations(values: impl Iterator<Item = String>) -> Vec<String> {
+  let mut entries: Vec<String> = Vec::new();
+  for value in values {
+    if let Some(last) = entries.last_mut() {
+      if WriteOperation::from_str(&value).is_ok()
+        && split_write_operations(last).is_some()
+      {
+        last.push(',');
+        last.push_str(&value);
+        continue;
+      }
+    }
+    entries.push(value);
+  }
+  entries
+}
+
 fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   unsafely_ignore_certificate_errors_parse(flags, matches);
   if let Some(read_wl) = matches.remove_many::<String>("allow-read") {
@@ -4624,7 +5209,7 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   }
 
   if let Some(write_wl) = matches.remove_many::<String>("allow-write") {
-    flags.permissions.allow_write = Some(write_wl.collect());
+    flags.permissions.allow_write = Some(join_write_operations(write_wl));
   }
 
   if let Some(write_wl) = matches.remove_many::<String>("deny-write") {
@@ -4641,6 +5226,16 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     flags.permissions.deny_net = Some(net_denylist);
   }
 
+  if let Some(import_wl) = matches.remove_many::<String>("allow-import") {
+    flags.permissions.allow_import = Some(import_wl.collect());
+    debug!("import allowlist: {:#?}", &flags.permissions.allow_import);
+  }
+
+  if let Some(import_wl) = matches.remove_many::<String>("deny-import") {
+    flags.permissions.deny_import = Some(import_wl.collect());
+    debug!("import denylist: {:#?}", &flags.permissions.deny_import);
+  }
+
   if let Some(env_wl) = matches.remove_many::<String>("allow-env") {
     flags.permissions.allow_env = Some(env_wl.collect());
     debug!("env allowlist: {:#?}", &flags.permissions.allow_env);
@@ -4651,8 +5246,10 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     debug!("env denylist: {:#?}", &flags.permissions.deny_env);
   }
 
-  if let Some(run_wl) = matches.remove_many::<String>("allow-run") {
-    flags.permissions.allow_run = Some(run_wl.collect());
+  if let Some(run_wl) = matches.remove_occurrences::<String>("allow-run") {
+    flags.permissions.allow_run = Some(join_restricted_entries(run_wl, |v| {
+      split_run_arguments(v).is_some()
+    }));
     debug!("run allowlist: {:#?}", &flags.permissions.allow_run);
   }
 
@@ -4671,8 +5268,10 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     debug!("sys info denylist: {:#?}", &flags.permissions.deny_sys);
   }
 
-  if let Some(ffi_wl) = matches.remove_many::<String>("allow-ffi") {
-    flags.permissions.allow_ffi = Some(ffi_wl.collect());
+  if let Some(ffi_wl) = matches.remove_occurrences::<String>("allow-ffi") {
+    flags.permissions.allow_ffi = Some(join_restricted_entries(ffi_wl, |v| {
+      split_ffi_symbols(v).is_some()
+    }));
     debug!("ffi allowlist: {:#?}", &flags.permissions.allow_ffi);
   }
 
@@ -4696,6 +5295,19 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("no-prompt") {
     flags.permissions.no_prompt = true;
   }
+
+  flags.permissions.permission_audit_log =
+    matches.remove_one::<String>("permission-audit-log");
+  flags.permissions.permission_policy =
+    matches.remove_one::<String>("permission-policy");
+
+  if let Some(mode) = matches.remove_one::<String>("sandbox") {
+    flags.permissions.kernel_sandbox = mode == "kernel";
+  }
+
+  if matches.get_flag("enforce-wx") {
+    flags.permissions.enforce_wx = true;
+  }
 }
 
 fn unsafely_ignore_certificate_errors_parse(
@@ -4916,7 +5528,14 @@ fn node_modules_and_vendor_dir_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
 ) {
-  flags.node_modules_dir = matches.remove_one::<bool>("node-modules-dir");
+  match matches.remove_one::<String>("node-modules-dir").as_deref() {
+    Some("isolated") => {
+      flags.node_modules_dir = Some(true);
+      flags.node_modules_dir_layout = NodeModulesDirLayout::Isolated;
+    }
+    Some(value) => flags.node_modules_dir = Some(value == "true"),
+    None => {}
+  }
   flags.vendor = matches.remove_one::<bool>("vendor");
 }
 
@@ -6315,10 +6934,33 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          verify_npm: false,
+          repair: false,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn cache_verify_npm() {
+    let r = flags_from_vec(svec!["deno", "cache", "--verify-npm", "--repair"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          verify_npm: true,
+          repair: true,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--repair", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -6844,22 +7486,22 @@ mod tests {
   }
 
   #[test]
-  fn deny_write_denylist() {
-    use test_util::TempDir;
-    let temp_dir_guard = TempDir::new();
-    let temp_dir = temp_dir_guard.path().to_string();
-
+  fn allow_write_operations() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      format!("--deny-write=.,{}", temp_dir),
+      "--allow-write=logs/:create,append,out.txt,tmp/:delete",
       "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         permissions: PermissionFlags {
-          deny_write: Some(vec![String::from("."), temp_dir]),
+          allow_write: Some(svec![
+            "logs/:create,append",
+            "out.txt",
+            "tmp/:delete"
+          ]),
           ..Default::default()
         },
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
@@ -6872,37 +7514,322 @@ mod tests {
   }
 
   #[test]
-  fn allow_net_allowlist() {
+  fn allow_run_arguments() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--allow-net=127.0.0.1",
+      "--allow-run=curl,git:status,log",
+      "--allow-run=make",
       "script.ts"
     ]);
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
         permissions: PermissionFlags {
-          allow_net: Some(svec!["127.0.0.1"]),
+          allow_run: Some(svec!["curl", "git:status,log", "make"]),
           ..Default::default()
         },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--allow-run=curl,make,git:status,log"]
+    );
   }
 
   #[test]
-  fn deny_net_denylist() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--deny-net=127.0.0.1", "script.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+  fn allow_ffi_symbols() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-ffi=libcrypto.so:EVP_*,RAND_bytes",
+      "--allow-ffi=./libfoo.so",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        permissions: PermissionFlags {
+          allow_ffi: Some(svec![
+            "libcrypto.so:EVP_*,RAND_bytes",
+            "./libfoo.so"
+          ]),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--allow-ffi=./libfoo.so,libcrypto.so:EVP_*,RAND_bytes"]
+    );
+  }
+
+  #[test]
+  fn resolution_args() {
+    let flags = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--config=deno.jsonc",
+      "--import-map=import_map.json",
+      "--unstable-sloppy-imports",
+      "--unstable-kv",
+      "a_test.ts"
+    ])
+    .unwrap();
+    assert_eq!(
+      flags.to_resolution_args(),
+      svec![
+        "--config=deno.jsonc",
+        "--import-map=import_map.json",
+        "--unstable-sloppy-imports",
+        "--unstable-kv"
+      ]
+    );
+
+    let flags = flags_from_vec(svec!["deno", "test", "--no-config"]).unwrap();
+    assert_eq!(flags.to_resolution_args(), svec!["--no-config"]);
+  }
+
+  #[test]
+  fn permission_audit_log() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-read",
+      "--permission-audit-log=audit.jsonl",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        permissions: PermissionFlags {
+          allow_read: Some(vec![]),
+          permission_audit_log: Some("audit.jsonl".to_string()),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+  #[test]
+  fn permission_policy() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-policy=policy.jsonc",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        permissions: PermissionFlags {
+          permission_policy: Some("policy.jsonc".to_string()),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn kernel_sandbox() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-read=.",
+      "--sandbox=kernel",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        permissions: PermissionFlags {
+          allow_read: Some(svec!["."]),
+          kernel_sandbox: true,
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--allow-read=.", "--sandbox=kernel"]
+    );
+
+    let r = flags_from_vec(svec!["deno", "run", "--sandbox=vm", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn enforce_wx() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-ffi",
+      "--enforce-wx",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        permissions: PermissionFlags {
+          allow_ffi: Some(vec![]),
+          enforce_wx: true,
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--allow-ffi", "--enforce-wx"]
+    );
+  }
+
+  #[test]
+  fn unstable_io_uring() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--unstable-io-uring", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        unstable_config: UnstableConfig {
+          io_uring: true,
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn deny_write_denylist() {
+    use test_util::TempDir;
+    let temp_dir_guard = TempDir::new();
+    let temp_dir = temp_dir_guard.path().to_string();
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      format!("--deny-write=.,{}", temp_dir),
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        permissions: PermissionFlags {
+          deny_write: Some(vec![String::from("."), temp_dir]),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn allow_net_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net=127.0.0.1",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(svec!["127.0.0.1"]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn allow_import_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-import=jsr.io,registry.internal:8443/deno/",
+      "--deny-import=jsr.io/@untrusted",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        permissions: PermissionFlags {
+          allow_import: Some(svec!["jsr.io", "registry.internal:8443/deno/"]),
+          deny_import: Some(svec!["jsr.io/@untrusted"]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-import=https://jsr.io",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn deny_net_denylist() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--deny-net=127.0.0.1", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
@@ -7499,6 +8426,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          verify_npm: false,
+          repair: false,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -7588,6 +8517,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          verify_npm: false,
+          repair: false,
         }),
         ..Flags::default()
       }
@@ -8094,6 +9025,33 @@ mod tests {
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-dir=isolated",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        node_modules_dir: Some(true),
+        node_modules_dir_layout: NodeModulesDirLayout::Isolated,
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-dir=hoisted",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -8413,6 +9371,10 @@ mod tests {
             ignore: vec![],
           },
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           concurrent_jobs: None,
           trace_leaks: true,
           coverage_dir: Some("cov".to_string()),
@@ -8497,6 +9459,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -8534,6 +9500,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -8576,6 +9546,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -8671,6 +9645,27 @@ mod tests {
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=./reporter.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Custom("./reporter.ts".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        log_level: Some(Level::Error),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=prety"]);
+    assert!(r.is_err());
+
     let r = flags_from_vec(svec![
       "deno",
       "test",
@@ -8699,6 +9694,98 @@ mod tests {
     assert!(r.is_err());
   }
 
+  #[test]
+  fn test_shard() {
+    let r = flags_from_vec(svec!["deno", "test", "--shard=3/8"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          shard: Some(TestShard {
+            index: NonZeroUsize::new(3).unwrap(),
+            count: NonZeroUsize::new(8).unwrap(),
+          }),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    for shard in ["0/8", "9/8", "3", "3/0", "a/b"] {
+      let r = flags_from_vec(svec!["deno", "test", format!("--shard={shard}")]);
+      assert!(r.is_err(), "{shard}");
+    }
+  }
+
+  #[test]
+  fn test_retries() {
+    let r = flags_from_vec(svec!["deno", "test", "--retries=2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          retries: 2,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--retries=-1"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_isolate() {
+    let r = flags_from_vec(svec!["deno", "test", "--allow-read", "--isolate"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          isolate: true,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          allow_read: Some(vec![]),
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          update_snapshots: true,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_shuffle() {
     let r = flags_from_vec(svec!["deno", "test", "--shuffle=1"]);
@@ -8712,6 +9799,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: Some(1),
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -8747,6 +9838,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -8781,6 +9876,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec!["./".to_string()],
             ignore: vec![],
@@ -8817,6 +9916,10 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          retries: 0,
+          update_snapshots: false,
+          isolate: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -8921,6 +10024,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          verify_npm: false,
+          repair: false,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -9451,6 +10556,51 @@ mod tests {
     assert!(&error_message.contains("--watch[=<FILES>...]"));
   }
 
+  #[test]
+  fn kv_backup_and_restore() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "kv",
+      "backup",
+      "--db",
+      "kv.sqlite3",
+      "kv.backup"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Kv(KvFlags {
+          action: KvAction::Backup,
+          db: "kv.sqlite3".to_string(),
+          path: "kv.backup".to_string(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "kv",
+      "restore",
+      "--db=kv.sqlite3",
+      "kv.backup"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Kv(KvFlags {
+          action: KvAction::Restore,
+          db: "kv.sqlite3".to_string(),
+          path: "kv.backup".to_string(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "kv", "backup", "kv.backup"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn vendor_minimal() {
     let r = flags_from_vec(svec!["deno", "vendor", "mod.ts",]);
@@ -9742,6 +10892,9 @@ mod tests {
             include: vec!["dir1/".to_string(), "dir2/".to_string()],
             ignore: vec![],
           },
+          save_baseline: None,
+          compare: None,
+          fail_threshold: None,
           watch: Default::default(),
         }),
         unstable_config: UnstableConfig {
@@ -9777,6 +10930,9 @@ mod tests {
             include: vec![],
             ignore: vec![],
           },
+          save_baseline: None,
+          compare: None,
+          fail_threshold: None,
           watch: Some(Default::default()),
         }),
         permissions: PermissionFlags {
@@ -9789,6 +10945,45 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_baseline() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--save-baseline=new.json",
+      "--compare=main.json",
+      "--fail-threshold=2.5%"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          save_baseline: Some("new.json".to_string()),
+          compare: Some("main.json".to_string()),
+          fail_threshold: Some(BenchFailThreshold { basis_points: 250 }),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--fail-threshold=5%"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--compare=main.json",
+      "--fail-threshold=-5%"
+    ]);
+    assert!(r.is_err());
+    assert_eq!("5".parse::<BenchFailThreshold>().unwrap().percent(), 5.0);
+  }
+
   #[test]
   fn run_with_check() {
     let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
@@ -10104,6 +11299,8 @@ mod tests {
             Flags {
               subcommand: DenoSubcommand::Cache(CacheFlags {
                 files: svec!["script.ts"],
+                verify_npm: false,
+                repair: false,
               }),
               allow_scripts: value,
               ..Flags::default()
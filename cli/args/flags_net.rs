@@ -31,6 +31,7 @@ pub fn validator(host_and_port: &str) -> Result<String, String> {
   if Url::parse(&format!("internal://{host_and_port}")).is_ok()
     || host_and_port.parse::<IpAddr>().is_ok()
     || host_and_port.parse::<BarePort>().is_ok()
+    || host_and_port.parse::<NetDescriptor>().is_ok()
   {
     Ok(host_and_port.to_string())
   } else {
@@ -177,6 +178,39 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn parse_net_args_patterns() {
+    let entries = svec![
+      "10.0.0.0/8:443",
+      "[fd00::/8]:8000-8100",
+      "*.internal:any",
+      "deno.land:any"
+    ];
+    let expected = svec![
+      "10.0.0.0/8:443",
+      "[fd00::/8]:8000-8100",
+      "*.internal:any",
+      "deno.land:any"
+    ];
+    let actual = parse(entries).unwrap();
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn parse_net_args_unix_sockets() {
+    let entries = svec!["unix:/run/app.sock", "localhost:8080"];
+    let expected = svec!["unix:/run/app.sock", "localhost:8080"];
+    let actual = parse(entries).unwrap();
+    assert_eq!(actual, expected);
+    assert!(parse(svec!["unix:"]).is_err());
+  }
+
+  #[test]
+  fn parse_net_args_patterns_error() {
+    assert!(parse(svec!["10.0.0.0/40"]).is_err());
+    assert!(parse(svec!["deno.land:8100-8000"]).is_err());
+  }
+
   #[test]
   fn parse_net_args_ipv6_error1() {
     let entries = svec![":::"];
@@ -0,0 +1,250 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
+
+use super::PermissionFlags;
+
+/// The remote modules that may be imported, from `--allow-import` and
+/// `--deny-import`. Every remote module may be imported by default.
+#[derive(Debug, Default)]
+pub struct ImportPermissions {
+  allow: Option<Vec<ImportScope>>,
+  deny: Vec<ImportScope>,
+}
+
+impl ImportPermissions {
+  pub fn from_flags(flags: &PermissionFlags) -> Result<Self, AnyError> {
+    fn parse_scopes(entries: &[String]) -> Result<Vec<ImportScope>, AnyError> {
+      entries
+        .iter()
+        .map(|entry| ImportScope::parse(entry))
+        .collect()
+    }
+
+    Ok(Self {
+      allow: match &flags.allow_import {
+        // a flag without a value allows every host
+        Some(entries) if !entries.is_empty() => Some(parse_scopes(entries)?),
+        _ => None,
+      },
+      deny: match &flags.deny_import {
+        Some(entries) if entries.is_empty() => vec![ImportScope::any()],
+        Some(entries) => parse_scopes(entries)?,
+        None => vec![],
+      },
+    })
+  }
+
+  /// Checks that the remote module at `specifier` may be imported.
+  pub fn check(&self, specifier: &ModuleSpecifier) -> Result<(), AnyError> {
+    let allowed = !self.deny.iter().any(|scope| scope.matches(specifier))
+      && match &self.allow {
+        Some(allow) => allow.iter().any(|scope| scope.matches(specifier)),
+        None => true,
+      };
+    if allowed {
+      Ok(())
+    } else {
+      Err(custom_error(
+        "PermissionDenied",
+        format!("Requires import access to \"{specifier}\", run again with the --allow-import flag"),
+      ))
+    }
+  }
+}
+
+/// A host, optionally with a port and a path prefix, ex. `jsr.io`,
+/// `*.example.com` or `registry.internal:8443/deno/`.
+#[derive(Debug, PartialEq, Eq)]
+struct ImportScope {
+  /// `None` for every host.
+  host: Option<String>,
+  /// Whether `host` stands for its subdomains, as in `*.example.com`.
+  wildcard: bool,
+  port: Option<u16>,
+  path_prefix: String,
+}
+
+impl ImportScope {
+  fn any() -> Self {
+    Self {
+      host: None,
+      wildcard: false,
+      port: None,
+      path_prefix: "/".to_string(),
+    }
+  }
+
+  fn parse(entry: &str) -> Result<Self, AnyError> {
+    if entry.contains("://") {
+      bail!("Import scope '{entry}' must not include a scheme");
+    }
+    let (wildcard, rest) = match entry.strip_prefix("*.") {
+      Some(rest) => (true, rest),
+      None => (false, entry),
+    };
+    let Ok(url) = Url::parse(&format!("https://{rest}")) else {
+      bail!("Invalid import scope '{entry}'");
+    };
+    if !url.username().is_empty()
+      || url.password().is_some()
+      || url.query().is_some()
+      || url.fragment().is_some()
+    {
+      bail!("Import scope '{entry}' may only contain a host, port and path");
+    }
+    let Some(host) = url.host_str() else {
+      bail!("Import scope '{entry}' is missing a host");
+    };
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let port = match url.port() {
+      Some(port) => Some(port),
+      // `Url` drops the default port, but `jsr.io:443` still means only 443
+      None
+        if authority
+          .rsplit_once(']')
+          .map_or(authority, |(_, port)| port)
+          .contains(':') =>
+      {
+        Some(443)
+      }
+      None => None,
+    };
+    Ok(Self {
+      host: Some(host.to_string()),
+      wildcard,
+      port,
+      path_prefix: url.path().to_string(),
+    })
+  }
+
+  fn matches(&self, specifier: &ModuleSpecifier) -> bool {
+    if let Some(host) = &self.host {
+      let Some(specifier_host) = specifier.host_str() else {
+        return false;
+      };
+      let host_matches = if self.wildcard {
+        specifier_host
+          .strip_suffix(host.as_str())
+          .is_some_and(|subdomain| subdomain.ends_with('.'))
+      } else {
+        specifier_host == host
+      };
+      if !host_matches {
+        return false;
+      }
+    }
+    if let Some(port) = self.port {
+      if specifier.port_or_known_default() != Some(port) {
+        return false;
+      }
+    }
+    // the prefix has to end at a path segment, so that `/deno` does not
+    // allow `/deno-evil`
+    match specifier.path().strip_prefix(self.path_prefix.as_str()) {
+      Some(rest) => {
+        self.path_prefix.ends_with('/')
+          || rest.is_empty()
+          || rest.starts_with('/')
+      }
+      None => false,
+    }
+  }
+}
+
+pub fn validator(entry: &str) -> Result<String, String> {
+  match ImportScope::parse(entry) {
+    Ok(_) => Ok(entry.to_string()),
+    Err(err) => Err(err.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn import_permissions(
+    allow: Option<&[&str]>,
+    deny: Option<&[&str]>,
+  ) -> ImportPermissions {
+    let to_vec = |entries: &[&str]| {
+      entries
+        .iter()
+        .map(|entry| entry.to_string())
+        .collect::<Vec<_>>()
+    };
+    ImportPermissions::from_flags(&PermissionFlags {
+      allow_import: allow.map(to_vec),
+      deny_import: deny.map(to_vec),
+      ..Default::default()
+    })
+    .unwrap()
+  }
+
+  fn allowed(permissions: &ImportPermissions, specifier: &str) -> bool {
+    permissions
+      .check(&ModuleSpecifier::parse(specifier).unwrap())
+      .is_ok()
+  }
+
+  #[test]
+  fn parses_import_scopes() {
+    assert_eq!(
+      ImportScope::parse("registry.internal:8443/deno/").unwrap(),
+      ImportScope {
+        host: Some("registry.internal".to_string()),
+        wildcard: false,
+        port: Some(8443),
+        path_prefix: "/deno/".to_string(),
+      }
+    );
+    assert_eq!(ImportScope::parse("JSR.io:443").unwrap().port, Some(443));
+    assert_eq!(ImportScope::parse("jsr.io").unwrap().port, None);
+    assert!(ImportScope::parse("*.example.com").unwrap().wildcard);
+    assert_eq!(ImportScope::parse("[::1]").unwrap().port, None);
+
+    for entry in [
+      "https://jsr.io",
+      "user@jsr.io",
+      "jsr.io/?version=1",
+      "jsr.io/#x",
+      "",
+    ] {
+      assert!(ImportScope::parse(entry).is_err(), "{entry}");
+    }
+  }
+
+  #[test]
+  fn checks_imports() {
+    let permissions = import_permissions(
+      Some(&["jsr.io", "registry.internal:8443/deno", "*.example.com"]),
+      Some(&["jsr.io/@evil"]),
+    );
+    for (specifier, expected) in [
+      ("https://jsr.io/@std/path/mod.ts", true),
+      ("https://jsr.io/@evil/pkg/mod.ts", false),
+      ("https://registry.internal:8443/deno/mod.ts", true),
+      ("https://registry.internal:8443/deno", true),
+      ("https://registry.internal:8443/deno-evil/mod.ts", false),
+      ("https://registry.internal:8443/deno/../x/mod.ts", false),
+      ("https://registry.internal/deno/mod.ts", false),
+      ("https://cdn.example.com/mod.ts", true),
+      ("https://example.com/mod.ts", false),
+      ("https://notexample.com/mod.ts", false),
+      ("https://deno.land/x/mod.ts", false),
+    ] {
+      assert_eq!(allowed(&permissions, specifier), expected, "{specifier}");
+    }
+
+    let permissions = import_permissions(None, None);
+    assert!(allowed(&permissions, "https://deno.land/x/mod.ts"));
+    let permissions = import_permissions(Some(&[]), None);
+    assert!(allowed(&permissions, "https://deno.land/x/mod.ts"));
+    let permissions = import_permissions(None, Some(&[]));
+    assert!(!allowed(&permissions, "https://deno.land/x/mod.ts"));
+  }
+}
@@ -4,8 +4,10 @@ pub mod deno_json;
 mod flags;
 mod flags_net;
 mod import_map;
+mod import_permissions;
 mod lockfile;
 mod package_json;
+mod permission_policy;
 
 use deno_ast::SourceMapOption;
 use deno_config::workspace::CreateResolverOptions;
@@ -41,8 +43,11 @@ pub use deno_config::deno_json::TsConfigType;
 pub use deno_config::deno_json::TsTypeLib;
 pub use deno_config::glob::FilePatterns;
 pub use flags::*;
+pub use import_permissions::ImportPermissions;
 pub use lockfile::CliLockfile;
+pub use package_json::resolve_pkg_json_dep;
 pub use package_json::PackageJsonInstallDepsProvider;
+pub use permission_policy::apply_permission_policy;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
@@ -252,6 +257,9 @@ pub struct WorkspaceBenchOptions {
   pub filter: Option<String>,
   pub json: bool,
   pub no_run: bool,
+  pub save_baseline: Option<String>,
+  pub compare: Option<String>,
+  pub fail_threshold: Option<BenchFailThreshold>,
 }
 
 impl WorkspaceBenchOptions {
@@ -260,6 +268,9 @@ impl WorkspaceBenchOptions {
       filter: bench_flags.filter.clone(),
       json: bench_flags.json,
       no_run: bench_flags.no_run,
+      save_baseline: bench_flags.save_baseline.clone(),
+      compare: bench_flags.compare.clone(),
+      fail_threshold: bench_flags.fail_threshold,
     }
   }
 }
@@ -351,6 +362,10 @@ pub struct WorkspaceTestOptions {
   pub allow_none: bool,
   pub filter: Option<String>,
   pub shuffle: Option<u64>,
+  pub shard: Option<TestShard>,
+  pub retries: usize,
+  pub update_snapshots: bool,
+  pub isolate: bool,
   pub concurrent_jobs: NonZeroUsize,
   pub trace_leaks: bool,
   pub reporter: TestReporterConfig,
@@ -369,8 +384,12 @@ impl WorkspaceTestOptions {
       filter: test_flags.filter.clone(),
       no_run: test_flags.no_run,
       shuffle: test_flags.shuffle,
+      shard: test_flags.shard,
+      retries: test_flags.retries,
+      update_snapshots: test_flags.update_snapshots,
+      isolate: test_flags.isolate,
       trace_leaks: test_flags.trace_leaks,
-      reporter: test_flags.reporter,
+      reporter: test_flags.reporter.clone(),
       junit_path: test_flags.junit_path.clone(),
     }
   }
@@ -512,11 +531,16 @@ pub fn discover_npmrc_from_workspace(
   )
 }
 
-/// Discover `.npmrc` file - currently we only support it next to `package.json`
-/// or next to `deno.json`.
+/// Discover `.npmrc` files - the one next to `package.json`, or otherwise
+/// next to `deno.json`, and the one in the user's home directory. Options
+/// from the project's file take precedence over the user's.
 ///
-/// In the future we will need to support it in user directory or global directory
-/// as per https://docs.npmjs.com/cli/v10/configuring-npm/npmrc#files.
+/// Configured credentials are always sent to their registry, so
+/// `always-auth` has no effect. The TLS and proxy options aren't supported,
+/// a warning points to the flags and environment variables to use instead.
+///
+/// In the future we might also need to support the global file as per
+/// https://docs.npmjs.com/cli/v10/configuring-npm/npmrc#files.
 pub fn discover_npmrc(
   maybe_package_json_path: Option<PathBuf>,
   maybe_deno_json_path: Option<PathBuf>,
@@ -529,61 +553,116 @@ pub fn discover_npmrc(
 
   fn try_to_read_npmrc(
     dir: &Path,
-  ) -> Result<Option<(String, PathBuf)>, AnyError> {
+  ) -> Result<Option<(NpmRc, PathBuf)>, AnyError> {
     let path = dir.join(NPMRC_NAME);
-    let maybe_source = match std::fs::read_to_string(&path) {
-      Ok(source) => Some(source),
-      Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+    let source = match std::fs::read_to_string(&path) {
+      Ok(source) => source,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        return Ok(None)
+      }
       Err(err) => {
         bail!("Error loading .npmrc at {}. {:#}", path.display(), err)
       }
     };
-
-    Ok(maybe_source.map(|source| (source, path)))
-  }
-
-  fn try_to_parse_npmrc(
-    source: String,
-    path: &Path,
-  ) -> Result<Arc<ResolvedNpmRc>, AnyError> {
     let npmrc = NpmRc::parse(&source, &get_env_var).with_context(|| {
       format!("Failed to parse .npmrc at {}", path.display())
     })?;
-    let resolved = npmrc
-      .as_resolved(npm_registry_url())
-      .context("Failed to resolve .npmrc options")?;
-    Ok(Arc::new(resolved))
-  }
-
-  // 1. Try `.npmrc` next to `package.json`
-  if let Some(package_json_path) = maybe_package_json_path {
-    if let Some(package_json_dir) = package_json_path.parent() {
-      if let Some((source, path)) = try_to_read_npmrc(package_json_dir)? {
-        return try_to_parse_npmrc(source, &path).map(|r| (r, Some(path)));
+    let unsupported_options = find_unsupported_npmrc_options(&source);
+    if !unsupported_options.is_empty() {
+      let mut message = format!(
+        "{} Ignoring unsupported options in {}:",
+        colors::yellow("Warning"),
+        path.display()
+      );
+      for (key, hint) in unsupported_options {
+        message.push_str(&format!("\n  {key}: {hint}"));
       }
+      log::warn!("{}", message);
     }
+    Ok(Some((npmrc, path)))
   }
 
-  // 2. Try `.npmrc` next to `deno.json(c)`
-  if let Some(deno_json_path) = maybe_deno_json_path {
-    if let Some(deno_json_dir) = deno_json_path.parent() {
-      if let Some((source, path)) = try_to_read_npmrc(deno_json_dir)? {
-        return try_to_parse_npmrc(source, &path).map(|r| (r, Some(path)));
+  // 1. Try `.npmrc` next to `package.json`, then next to `deno.json(c)`
+  let mut maybe_project_npmrc = None;
+  for config_path in [maybe_package_json_path, maybe_deno_json_path]
+    .into_iter()
+    .flatten()
+  {
+    if let Some(config_dir) = config_path.parent() {
+      maybe_project_npmrc = try_to_read_npmrc(config_dir)?;
+      if maybe_project_npmrc.is_some() {
+        break;
       }
     }
   }
 
-  // TODO(bartlomieju): update to read both files - one in the project root and one and
-  // home dir and then merge them.
-  // 3. Try `.npmrc` in the user's home directory
-  if let Some(home_dir) = cache::home_dir() {
-    if let Some((source, path)) = try_to_read_npmrc(&home_dir)? {
-      return try_to_parse_npmrc(source, &path).map(|r| (r, Some(path)));
+  // 2. Try `.npmrc` in the user's home directory
+  let maybe_user_npmrc = match cache::home_dir() {
+    Some(home_dir) => try_to_read_npmrc(&home_dir)?,
+    None => None,
+  };
+
+  let (npmrc, path) = match (maybe_project_npmrc, maybe_user_npmrc) {
+    (Some((project, project_path)), Some((user, user_path))) => {
+      if project_path == user_path {
+        (project, project_path)
+      } else {
+        (merge_npmrc(user, project), project_path)
+      }
+    }
+    (Some((npmrc, path)), None) | (None, Some((npmrc, path))) => (npmrc, path),
+    (None, None) => {
+      log::debug!("No .npmrc file found");
+      return Ok((create_default_npmrc(), None));
     }
+  };
+  let resolved = npmrc
+    .as_resolved(npm_registry_url())
+    .context("Failed to resolve .npmrc options")?;
+  Ok((Arc::new(resolved), Some(path)))
+}
+
+/// Applies the options of `overrides` on top of `base`. The settings of a
+/// registry are overridden as a whole, so credentials from different files
+/// never get mixed.
+fn merge_npmrc(mut base: NpmRc, overrides: NpmRc) -> NpmRc {
+  if overrides.registry.is_some() {
+    base.registry = overrides.registry;
   }
+  base.scope_registries.extend(overrides.scope_registries);
+  base.registry_configs.extend(overrides.registry_configs);
+  base
+}
 
-  log::debug!("No .npmrc file found");
-  Ok((create_default_npmrc(), None))
+/// Returns the keys of the options in the `.npmrc` source that Deno ignores,
+/// along with what to use instead.
+fn find_unsupported_npmrc_options(source: &str) -> Vec<(&str, &'static str)> {
+  source
+    .lines()
+    .filter_map(|line| {
+      let line = line.trim();
+      if line.starts_with([';', '#', '[']) {
+        return None;
+      }
+      let (key, _) = line.split_once('=')?;
+      let key = key.trim();
+      // registry specific options are written as `//host/path/:option`
+      let option = key.rsplit(':').next().unwrap_or(key);
+      let hint = match option {
+        "ca" | "cafile" => "use --cert or DENO_CERT instead",
+        "cert" | "key" | "certfile" | "keyfile" => {
+          "client certificates are not supported"
+        }
+        "strict-ssl" => "use --unsafely-ignore-certificate-errors instead",
+        "proxy" | "http-proxy" | "https-proxy" => {
+          "use the HTTP_PROXY and HTTPS_PROXY environment variables instead"
+        }
+        "noproxy" => "use the NO_PROXY environment variable instead",
+        _ => return None,
+      };
+      Some((key, hint))
+    })
+    .collect()
 }
 
 pub fn create_default_npmrc() -> Arc<ResolvedNpmRc> {
@@ -1584,6 +1663,11 @@ impl CliOptions {
       || self.workspace().has_unstable("sloppy-imports")
   }
 
+  pub fn unstable_io_uring(&self) -> bool {
+    self.flags.unstable_config.io_uring
+      || self.workspace().has_unstable("io-uring")
+  }
+
   pub fn unstable_features(&self) -> Vec<String> {
     let mut from_config_file = self.workspace().unstable_features().to_vec();
 
@@ -1619,8 +1703,12 @@ impl CliOptions {
           .map(|granular_flag| granular_flag.0)
           .collect();
 
-      let mut another_unstable_flags =
-        Vec::from(["sloppy-imports", "byonm", "bare-node-builtins"]);
+      let mut another_unstable_flags = Vec::from([
+        "sloppy-imports",
+        "byonm",
+        "bare-node-builtins",
+        "io-uring",
+      ]);
       // add more unstable flags to the same vector holding granular flags
       all_valid_unstable_flags.append(&mut another_unstable_flags);
 
@@ -1682,6 +1770,10 @@ impl CliOptions {
     full_paths
   }
 
+  pub fn node_modules_dir_layout(&self) -> NodeModulesDirLayout {
+    self.flags.node_modules_dir_layout
+  }
+
   pub fn lifecycle_scripts_config(&self) -> LifecycleScriptsConfig {
     LifecycleScriptsConfig {
       allowed: self.flags.allow_scripts.clone(),
@@ -1796,7 +1888,8 @@ impl StorageKeyResolver {
 
 /// Resolves the no_prompt value based on the cli flags and environment.
 pub fn resolve_no_prompt(flags: &PermissionFlags) -> bool {
-  flags.no_prompt || has_flag_env_var("DENO_NO_PROMPT")
+  // permissions granted by a prompt couldn't be added to the kernel sandbox
+  flags.no_prompt || flags.kernel_sandbox || has_flag_env_var("DENO_NO_PROMPT")
 }
 
 pub fn has_flag_env_var(name: &str) -> bool {
@@ -1938,4 +2031,64 @@ mod test {
     let reg_api_url = jsr_api_url();
     assert!(reg_api_url.as_str().ends_with('/'));
   }
+
+  #[test]
+  fn merges_project_npmrc_into_user_npmrc() {
+    let parse = |source| NpmRc::parse(source, &|_| None).unwrap();
+    let user = parse(
+      "registry=https://user.example/
+@user:registry=https://user.example/
+@shared:registry=https://user.example/
+//user.example/:_authToken=USER
+//shared.example/:_authToken=USER
+//shared.example/:email=user@example.com",
+    );
+    let project = parse(
+      "registry=https://project.example/
+@shared:registry=https://shared.example/
+//shared.example/:_authToken=PROJECT",
+    );
+    let npmrc = merge_npmrc(user.clone(), project);
+    assert_eq!(npmrc.registry.as_deref(), Some("https://project.example/"));
+    assert_eq!(
+      npmrc.scope_registries,
+      HashMap::from([
+        ("user".to_string(), "https://user.example/".to_string()),
+        ("shared".to_string(), "https://shared.example/".to_string()),
+      ])
+    );
+    assert_eq!(
+      npmrc.registry_configs["user.example/"]
+        .auth_token
+        .as_deref(),
+      Some("USER")
+    );
+    // the settings of a registry are not mixed between files
+    let shared = &npmrc.registry_configs["shared.example/"];
+    assert_eq!(shared.auth_token.as_deref(), Some("PROJECT"));
+    assert_eq!(shared.email, None);
+
+    // the user's registry is kept when the project doesn't set one
+    let npmrc = merge_npmrc(user, parse("@other:registry=https://o.example/"));
+    assert_eq!(npmrc.registry.as_deref(), Some("https://user.example/"));
+  }
+
+  #[test]
+  fn finds_unsupported_npmrc_options() {
+    let options = find_unsupported_npmrc_options(
+      "registry=https://example.com/
+always-auth=true
+; cafile=./ca.pem
+# strict-ssl=false
+[section]
+cafile = ./ca.pem
+https-proxy=http://proxy.example/
+//example.com/:certfile=./cert.pem
+//example.com/:_authToken=TOKEN",
+    );
+    assert_eq!(
+      options.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+      vec!["cafile", "https-proxy", "//example.com/:certfile"]
+    );
+  }
 }
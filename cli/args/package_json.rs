@@ -1,11 +1,15 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::borrow::Cow;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_config::workspace::Workspace;
+use deno_package_json::PackageJson;
 use deno_package_json::PackageJsonDepValue;
+use deno_package_json::PackageJsonDepValueParseError;
 use deno_semver::package::PackageReq;
+use deno_semver::VersionReq;
 
 #[derive(Debug)]
 pub struct InstallNpmRemotePkg {
@@ -19,8 +23,7 @@ pub struct InstallNpmRemotePkg {
 #[derive(Debug)]
 pub struct InstallNpmWorkspacePkg {
   pub alias: String,
-  // todo(24419): use this when setting up the node_modules dir
-  #[allow(dead_code)]
+  /// The directory of the package.json that depends on the package.
   pub base_dir: PathBuf,
   pub target_dir: PathBuf,
 }
@@ -43,11 +46,12 @@ impl PackageJsonInstallDepsProvider {
     for pkg_json in workspace.package_jsons() {
       let deps = pkg_json.resolve_local_package_json_deps();
       let mut pkg_pkgs = Vec::with_capacity(deps.len());
-      for (alias, dep) in deps {
-        let Ok(dep) = dep else {
+      for (alias, dep) in &deps {
+        let Ok(dep) = resolve_pkg_json_dep(pkg_json, alias, dep) else {
           continue;
         };
-        match dep {
+        let alias = alias.clone();
+        match dep.into_owned() {
           PackageJsonDepValue::Req(pkg_req) => {
             let workspace_pkg = workspace_npm_pkgs.iter().find(|pkg| {
               pkg.matches_req(&pkg_req)
@@ -103,3 +107,84 @@ impl PackageJsonInstallDepsProvider {
     &self.workspace_pkgs
   }
 }
+
+/// Resolves the dependency `alias` of `pkg_json` that
+/// `PackageJson::resolve_local_package_json_deps` resolved to `dep_result`.
+///
+/// That fails for the `workspace:^` and `workspace:~` specifiers, which differ
+/// from `workspace:*` only in the version range they're replaced with when
+/// publishing, so like it they resolve to the workspace package of any
+/// version.
+pub fn resolve_pkg_json_dep<'a>(
+  pkg_json: &PackageJson,
+  alias: &str,
+  dep_result: &'a Result<PackageJsonDepValue, PackageJsonDepValueParseError>,
+) -> Result<Cow<'a, PackageJsonDepValue>, &'a PackageJsonDepValueParseError> {
+  let err = match dep_result {
+    Ok(dep) => return Ok(Cow::Borrowed(dep)),
+    Err(err) => err,
+  };
+  // the dependencies are favored over the dev dependencies
+  let raw_value = pkg_json
+    .dependencies
+    .as_ref()
+    .and_then(|deps| deps.get(alias))
+    .or_else(|| {
+      pkg_json
+        .dev_dependencies
+        .as_ref()
+        .and_then(|deps| deps.get(alias))
+    });
+  match raw_value.map(|value| value.as_str()) {
+    Some("workspace:^" | "workspace:~") => Ok(Cow::Owned(
+      PackageJsonDepValue::Workspace(VersionReq::parse_from_npm("*").unwrap()),
+    )),
+    _ => Err(err),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::Path;
+
+  use deno_core::serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn resolves_workspace_specifiers() {
+    let pkg_json = PackageJson::load_from_value(
+      Path::new("/project/package.json").to_path_buf(),
+      json!({
+        "dependencies": {
+          "a": "workspace:*",
+          "b": "workspace:^",
+          "c": "workspace:~",
+          "d": "workspace:^1.0.0",
+          "e": "workspace:^^",
+        },
+        "devDependencies": {
+          "b": "workspace:^^",
+          "f": "workspace:~",
+        },
+      }),
+    );
+    let deps = pkg_json.resolve_local_package_json_deps();
+    let resolve = |alias: &str| {
+      resolve_pkg_json_dep(&pkg_json, alias, deps.get(alias).unwrap())
+        .ok()
+        .map(|dep| dep.into_owned())
+    };
+    let workspace = |version_req: &str| {
+      Some(PackageJsonDepValue::Workspace(
+        VersionReq::parse_from_npm(version_req).unwrap(),
+      ))
+    };
+    assert_eq!(resolve("a"), workspace("*"));
+    assert_eq!(resolve("b"), workspace("*"));
+    assert_eq!(resolve("c"), workspace("*"));
+    assert_eq!(resolve("d"), workspace("^1.0.0"));
+    assert_eq!(resolve("e"), None);
+    assert_eq!(resolve("f"), workspace("*"));
+  }
+}
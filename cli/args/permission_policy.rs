@@ -0,0 +1,246 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use serde::Deserialize;
+
+use super::PermissionFlags;
+
+/// A permission policy file given with `--permission-policy`, ex.
+///
+/// ```jsonc
+/// {
+///   // shared between all services
+///   "include": ["./base.policy.json"],
+///   "allow": { "read": ["./data"], "net": ["api.example.com:443"] },
+///   "deny": { "read": ["./data/secrets"], "run": true }
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PermissionPolicy {
+  /// Other policy files, relative to this one, whose permissions are added
+  /// to this one.
+  include: Vec<String>,
+  allow: PermissionSet,
+  deny: PermissionSet,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PermissionSet {
+  env: Option<PermissionList>,
+  ffi: Option<PermissionList>,
+  hrtime: bool,
+  import: Option<PermissionList>,
+  net: Option<PermissionList>,
+  read: Option<PermissionList>,
+  run: Option<PermissionList>,
+  sys: Option<PermissionList>,
+  write: Option<PermissionList>,
+}
+
+/// `true` for the whole permission, like a flag without a value, or the
+/// descriptors to allow or deny.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PermissionList {
+  All(bool),
+  Descriptors(Vec<String>),
+}
+
+/// Adds the permissions described by the policy file at `path` to `flags`.
+/// Paths in the policy are relative to the file they appear in.
+pub fn apply_permission_policy(
+  flags: &mut PermissionFlags,
+  path: &Path,
+) -> Result<(), AnyError> {
+  let path = std::env::current_dir()?.join(path);
+  apply_policy_file(flags, &path, &mut HashSet::new())
+}
+
+fn apply_policy_file(
+  flags: &mut PermissionFlags,
+  path: &Path,
+  visited: &mut HashSet<PathBuf>,
+) -> Result<(), AnyError> {
+  if !visited.insert(path.to_path_buf()) {
+    bail!(
+      "Permission policy '{}' includes itself, directly or indirectly",
+      path.display()
+    );
+  }
+  let text = std::fs::read_to_string(path).with_context(|| {
+    format!("Failed to read permission policy '{}'", path.display())
+  })?;
+  let policy = parse_policy(&text).with_context(|| {
+    format!("Failed to parse permission policy '{}'", path.display())
+  })?;
+  let dir = path.parent().unwrap_or(path);
+
+  for include in &policy.include {
+    apply_policy_file(flags, &dir.join(include), visited)?;
+  }
+
+  let PermissionSet {
+    env,
+    ffi,
+    hrtime,
+    import,
+    net,
+    read,
+    run,
+    sys,
+    write,
+  } = policy.allow;
+  merge(&mut flags.allow_env, env, None);
+  merge(&mut flags.allow_ffi, ffi, Some(dir));
+  merge(&mut flags.allow_import, import, None);
+  merge(&mut flags.allow_net, net, None);
+  merge(&mut flags.allow_read, read, Some(dir));
+  merge(&mut flags.allow_run, run, None);
+  merge(&mut flags.allow_sys, sys, None);
+  merge(&mut flags.allow_write, write, Some(dir));
+  flags.allow_hrtime |= hrtime;
+
+  let PermissionSet {
+    env,
+    ffi,
+    hrtime,
+    import,
+    net,
+    read,
+    run,
+    sys,
+    write,
+  } = policy.deny;
+  merge(&mut flags.deny_env, env, None);
+  merge(&mut flags.deny_ffi, ffi, Some(dir));
+  merge(&mut flags.deny_import, import, None);
+  merge(&mut flags.deny_net, net, None);
+  merge(&mut flags.deny_read, read, Some(dir));
+  merge(&mut flags.deny_run, run, None);
+  merge(&mut flags.deny_sys, sys, None);
+  merge(&mut flags.deny_write, write, Some(dir));
+  flags.deny_hrtime |= hrtime;
+
+  visited.remove(path);
+  Ok(())
+}
+
+fn parse_policy(text: &str) -> Result<PermissionPolicy, AnyError> {
+  let value = jsonc_parser::parse_to_serde_value(text, &Default::default())?
+    .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+  Ok(serde_json::from_value(value)?)
+}
+
+/// Adds `list` to the values of a permission flag, where `Some(vec![])`
+/// stands for the whole permission. Paths are resolved against `dir`.
+fn merge(
+  flag: &mut Option<Vec<String>>,
+  list: Option<PermissionList>,
+  dir: Option<&Path>,
+) {
+  match list {
+    None | Some(PermissionList::All(false)) => {}
+    Some(PermissionList::All(true)) => *flag = Some(vec![]),
+    // an empty list would otherwise grant the whole permission
+    Some(PermissionList::Descriptors(values)) if values.is_empty() => {}
+    Some(PermissionList::Descriptors(values)) => {
+      if matches!(flag, Some(existing) if existing.is_empty()) {
+        return;
+      }
+      let values = values.into_iter().map(|value| match dir {
+        Some(dir) => dir.join(value).to_string_lossy().into_owned(),
+        None => value,
+      });
+      flag.get_or_insert_with(Vec::new).extend(values);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn applies_policy_with_includes() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(
+      "base.json",
+      r#"{
+        // shared by every service
+        "allow": { "env": ["HOME"], "hrtime": true, "import": ["jsr.io"] },
+        "deny": { "run": true }
+      }"#,
+    );
+    temp_dir.create_dir_all("service");
+    temp_dir.write(
+      "service/policy.json",
+      r#"{
+        "include": ["../base.json"],
+        "allow": {
+          "read": ["./data", "/etc/hosts"],
+          "net": ["api.example.com:443"],
+          "write": true,
+        },
+        "deny": { "read": ["./data/secrets"] },
+      }"#,
+    );
+
+    let mut flags = PermissionFlags {
+      allow_env: Some(vec!["USER".to_string()]),
+      ..Default::default()
+    };
+    apply_permission_policy(
+      &mut flags,
+      temp_dir.path().join("service/policy.json").as_path(),
+    )
+    .unwrap();
+
+    let service_dir = temp_dir.path().join("service");
+    assert_eq!(
+      flags,
+      PermissionFlags {
+        allow_env: Some(vec!["USER".to_string(), "HOME".to_string()]),
+        allow_hrtime: true,
+        allow_import: Some(vec!["jsr.io".to_string()]),
+        allow_net: Some(vec!["api.example.com:443".to_string()]),
+        allow_read: Some(vec![
+          service_dir.join("./data").to_string_lossy().into_owned(),
+          "/etc/hosts".to_string(),
+        ]),
+        allow_write: Some(vec![]),
+        deny_read: Some(vec![service_dir
+          .join("./data/secrets")
+          .to_string_lossy()
+          .into_owned()]),
+        deny_run: Some(vec![]),
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_policies() {
+    let temp_dir = TempDir::new();
+    temp_dir.write("a.json", r#"{ "include": ["./b.json"] }"#);
+    temp_dir.write("b.json", r#"{ "include": ["./a.json"] }"#);
+    temp_dir.write("unknown.json", r#"{ "allow": { "disk": true } }"#);
+
+    for file in ["a.json", "unknown.json", "missing.json"] {
+      let result = apply_permission_policy(
+        &mut PermissionFlags::default(),
+        temp_dir.path().join(file).as_path(),
+      );
+      assert!(result.is_err(), "{file}");
+    }
+  }
+}
@@ -5,6 +5,7 @@ use crate::args::CaData;
 use crate::args::CliOptions;
 use crate::args::DenoSubcommand;
 use crate::args::Flags;
+use crate::args::ImportPermissions;
 use crate::args::PackageJsonInstallDepsProvider;
 use crate::args::StorageKeyResolver;
 use crate::args::TsConfigType;
@@ -56,6 +57,7 @@ use std::path::PathBuf;
 
 use deno_config::workspace::PackageJsonDepResolution;
 use deno_config::workspace::WorkspaceResolver;
+use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_core::futures::FutureExt;
 use deno_core::FeatureChecker;
@@ -66,7 +68,9 @@ use deno_runtime::deno_node::NodeResolver;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_web::BlobStore;
+use deno_runtime::deno_webstorage::LocalStorageLocation;
 use deno_runtime::inspector_server::InspectorServer;
+use deno_runtime::sandbox::KernelSandbox;
 use log::warn;
 use once_cell::sync::OnceCell;
 use std::future::Future;
@@ -327,19 +331,28 @@ impl CliFactory {
   pub fn file_fetcher(&self) -> Result<&Arc<FileFetcher>, AnyError> {
     self.services.file_fetcher.get_or_try_init(|| {
       let cli_options = self.cli_options()?;
-      Ok(Arc::new(FileFetcher::new(
+      let mut file_fetcher = FileFetcher::new(
         self.http_cache()?.clone(),
         cli_options.cache_setting(),
         !cli_options.no_remote(),
         self.http_client_provider().clone(),
         self.blob_store().clone(),
         Some(self.text_only_progress_bar().clone()),
-      )))
+      );
+      file_fetcher.set_import_permissions(ImportPermissions::from_flags(
+        cli_options.permission_flags(),
+      )?);
+      Ok(Arc::new(file_fetcher))
     })
   }
 
   pub fn fs(&self) -> &Arc<dyn deno_fs::FileSystem> {
-    self.services.fs.get_or_init(|| Arc::new(deno_fs::RealFs))
+    self.services.fs.get_or_init(|| {
+      if let Ok(cli_options) = self.cli_options() {
+        deno_fs::set_io_uring_enabled(cli_options.unstable_io_uring());
+      }
+      Arc::new(deno_fs::RealFs)
+    })
   }
 
   pub async fn npm_resolver(
@@ -391,6 +404,7 @@ impl CliFactory {
             npm_system_info: cli_options.npm_system_info(),
             npmrc: cli_options.npmrc().clone(),
             lifecycle_scripts: cli_options.lifecycle_scripts_config(),
+            node_modules_dir_layout: cli_options.node_modules_dir_layout(),
           })
         }).await
       }.boxed_local())
@@ -772,7 +786,7 @@ impl CliFactory {
       self.maybe_inspector_server()?.clone(),
       cli_options.maybe_lockfile().cloned(),
       self.feature_checker()?.clone(),
-      self.create_cli_main_worker_options()?,
+      self.create_cli_main_worker_options(node_resolver)?,
       cli_options.node_ipc_fd(),
       cli_options.serve_port(),
       cli_options.serve_host(),
@@ -789,16 +803,34 @@ impl CliFactory {
     ))
   }
 
+  /// The limits the kernel enforces for `--sandbox=kernel`.
+  pub fn create_kernel_sandbox(&self) -> Result<KernelSandbox, AnyError> {
+    let cli_options = self.cli_options()?;
+    let mut sandbox =
+      KernelSandbox::from_permissions(&cli_options.permissions_options()?);
+    // npm packages and dynamically imported modules are loaded from here
+    let deno_dir = self.deno_dir()?.root.clone();
+    sandbox.allow_read(deno_dir.clone());
+    sandbox.allow_write(deno_dir);
+    if let Some(node_modules_dir) = cli_options.node_modules_dir_path() {
+      sandbox.allow_read(node_modules_dir.clone());
+    }
+    Ok(sandbox)
+  }
+
   fn create_cli_main_worker_options(
     &self,
+    node_resolver: &Arc<NodeResolver>,
   ) -> Result<CliMainWorkerOptions, AnyError> {
     let cli_options = self.cli_options()?;
     let create_hmr_runner = if cli_options.has_hmr() {
       let watcher_communicator = self.watcher_communicator.clone().unwrap();
       let emitter = self.emitter()?.clone();
+      let node_resolver = node_resolver.clone();
       let fn_: crate::worker::CreateHmrRunnerCb = Box::new(move |session| {
         Box::new(HmrRunner::new(
           emitter.clone(),
+          node_resolver.clone(),
           session,
           watcher_communicator.clone(),
         ))
@@ -819,6 +851,18 @@ impl CliFactory {
         None
       };
 
+    // sandboxed runs are handled by `tools::run::run_script_in_kernel_sandbox`
+    if cli_options.permission_flags().kernel_sandbox
+      && !matches!(
+        cli_options.sub_command(),
+        DenoSubcommand::Run(flags) if flags.watch.is_none() && !flags.is_stdin()
+      )
+    {
+      bail!(
+        "--sandbox=kernel is only supported by \"deno run\" of a file without --watch"
+      );
+    }
+
     Ok(CliMainWorkerOptions {
       argv: cli_options.argv().clone(),
       // This optimization is only available for "run" subcommand
@@ -843,6 +887,27 @@ impl CliFactory {
         .or(std::env::args().next()),
       node_debug: std::env::var("NODE_DEBUG").ok(),
       origin_data_folder_path: Some(self.deno_dir()?.origin_data_folder_path()),
+      cache_storage_max_size: std::env::var("DENO_CACHE_STORAGE_MAX_SIZE")
+        .ok()
+        .and_then(|size| size.parse().ok()),
+      local_storage_location: std::env::var_os("DENO_LOCAL_STORAGE_PATH")
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+          if path == ":memory:" {
+            LocalStorageLocation::InMemory
+          } else {
+            LocalStorageLocation::Path(cli_options.initial_cwd().join(path))
+          }
+        }),
+      web_storage_max_size: std::env::var("DENO_WEB_STORAGE_MAX_SIZE")
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .filter(|size| *size > 0),
+      broadcast_channel_socket: std::env::var_os(
+        "DENO_BROADCAST_CHANNEL_SOCKET",
+      )
+      .filter(|path| !path.is_empty())
+      .map(|path| cli_options.initial_cwd().join(path)),
       seed: cli_options.seed(),
       unsafely_ignore_certificate_errors: cli_options
         .unsafely_ignore_certificate_errors()
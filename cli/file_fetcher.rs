@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::CacheSetting;
+use crate::args::ImportPermissions;
 use crate::auth_tokens::AuthTokens;
 use crate::cache::HttpCache;
 use crate::colors;
@@ -168,6 +169,7 @@ pub struct FileFetcher {
   http_client_provider: Arc<HttpClientProvider>,
   blob_store: Arc<BlobStore>,
   download_log_level: log::Level,
+  import_permissions: ImportPermissions,
   progress_bar: Option<ProgressBar>,
 }
 
@@ -189,6 +191,7 @@ impl FileFetcher {
       http_client_provider,
       blob_store,
       download_log_level: log::Level::Info,
+      import_permissions: Default::default(),
       progress_bar,
     }
   }
@@ -202,6 +205,11 @@ impl FileFetcher {
     self.download_log_level = level;
   }
 
+  /// Sets the remote modules that may be fetched, from `--allow-import`.
+  pub fn set_import_permissions(&mut self, permissions: ImportPermissions) {
+    self.import_permissions = permissions;
+  }
+
   /// Fetch cached remote file.
   ///
   /// This is a recursive operation if source file has redirections.
@@ -595,6 +603,8 @@ impl FileFetcher {
         format!("A remote specifier was requested: \"{specifier}\", but --no-remote is specified."),
       ))
     } else {
+      // checked for every redirect too, since each one is fetched separately
+      self.import_permissions.check(specifier)?;
       self
         .fetch_remote_no_follow(
           specifier,
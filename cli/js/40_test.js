@@ -7,6 +7,9 @@ import { escapeName, withPermissions } from "ext:cli/40_test_common.js";
 const {
   op_register_test_step,
   op_register_test,
+  op_test_assert_inline_snapshot,
+  op_test_assert_snapshot,
+  op_test_event_attachment,
   op_test_event_step_result_failed,
   op_test_event_step_result_ignored,
   op_test_event_step_result_ok,
@@ -21,9 +24,12 @@ const {
   Map,
   MapPrototypeGet,
   MapPrototypeSet,
+  NumberIsSafeInteger,
+  ObjectPrototypeIsPrototypeOf,
   SafeArrayIterator,
   SymbolToStringTag,
   TypeError,
+  Uint8ArrayPrototype,
 } = primordials;
 
 import { setExitHandler } from "ext:runtime/30_os.js";
@@ -45,6 +51,7 @@ const DenoNs = globalThis.Deno;
  *   sanitizeResources: boolean,
  *   sanitizeExit: boolean,
  *   permissions: PermissionOptions,
+ *   retry: number | undefined,
  * }} TestDescription
  *
  * @typedef {{
@@ -67,6 +74,7 @@ const DenoNs = globalThis.Deno;
  *   context: TestContext,
  *   children: TestStepDescription[],
  *   completed: boolean,
+ *   snapshotCount: number,
  * }} TestState
  *
  * @typedef {{
@@ -74,6 +82,7 @@ const DenoNs = globalThis.Deno;
  *   children: TestStepDescription[],
  *   completed: boolean,
  *   failed: boolean,
+ *   snapshotCount: number,
  * }} TestStepState
  *
  * @typedef {{
@@ -127,6 +136,11 @@ function assertExit(fn, isTest) {
 
 function wrapOuter(fn, desc) {
   return async function outerWrapped() {
+    // A retried test starts over without the steps of its previous attempt.
+    const state = MapPrototypeGet(testStates, desc.id);
+    state.children = [];
+    state.completed = false;
+    state.snapshotCount = 0;
     try {
       if (desc.ignore) {
         return "ignored";
@@ -135,7 +149,6 @@ function wrapOuter(fn, desc) {
     } catch (error) {
       return { failed: { jsError: core.destructureError(error) } };
     } finally {
-      const state = MapPrototypeGet(testStates, desc.id);
       for (const childDesc of state.children) {
         stepReportResult(childDesc, { failed: "incomplete" }, 0);
       }
@@ -302,6 +315,13 @@ function testInner(
 
   testDesc = { ...testDesc, ...overrides };
 
+  if (
+    testDesc.retry !== undefined &&
+    !(NumberIsSafeInteger(testDesc.retry) && testDesc.retry >= 0)
+  ) {
+    throw new TypeError("The 'retry' option must be a non-negative integer.");
+  }
+
   // Delete this prop in case the user passed it. It's used to detect steps.
   delete testDesc.parent;
 
@@ -320,6 +340,7 @@ function testInner(
     testDesc.only,
     testDesc.sanitizeOps,
     testDesc.sanitizeResources,
+    testDesc.retry,
     testDesc.location.fileName,
     testDesc.location.lineNumber,
     testDesc.location.columnNumber,
@@ -331,6 +352,7 @@ function testInner(
     context: createTestContext(testDesc),
     children: [],
     completed: false,
+    snapshotCount: 0,
   });
 }
 
@@ -362,6 +384,25 @@ function getFullName(desc) {
   return desc.name;
 }
 
+/**
+ * Serializes a value for `t.assertSnapshot()` and `t.assertInlineSnapshot()`,
+ * in full and independently of the order of object keys.
+ * @param value {unknown}
+ * @returns {string}
+ */
+function serializeSnapshot(value) {
+  return DenoNs.inspect(value, {
+    depth: Infinity,
+    sorted: true,
+    trailingComma: true,
+    compact: false,
+    iterableLimit: Infinity,
+    strAbbreviateSize: Infinity,
+    breakLength: Infinity,
+    escapeSequences: false,
+  });
+}
+
 function usesSanitizer(desc) {
   return desc.sanitizeResources || desc.sanitizeOps || desc.sanitizeExit;
 }
@@ -480,6 +521,7 @@ function createTestContext(desc) {
         children: [],
         failed: false,
         completed: false,
+        snapshotCount: 0,
       };
       MapPrototypeSet(testStates, stepDesc.id, state);
       ArrayPrototypePush(
@@ -495,6 +537,59 @@ function createTestContext(desc) {
       stepReportResult(stepDesc, result, elapsed);
       return result == "ok";
     },
+    /**
+     * @param actual {unknown}
+     */
+    assertSnapshot(actual) {
+      const state = MapPrototypeGet(testStates, desc.id);
+      const name = `${getFullName(desc)} ${++state.snapshotCount}`;
+      const message = op_test_assert_snapshot(name, serializeSnapshot(actual));
+      if (message != null) {
+        throw new Error(message);
+      }
+    },
+    /**
+     * @param actual {unknown}
+     * @param snapshot {string | undefined}
+     */
+    assertInlineSnapshot(actual, snapshot) {
+      if (snapshot !== undefined && typeof snapshot !== "string") {
+        throw new TypeError("Expected the inline snapshot to be a string");
+      }
+      const location = core.currentUserCallSite();
+      const message = op_test_assert_inline_snapshot(
+        serializeSnapshot(actual),
+        snapshot,
+        location.fileName,
+        location.lineNumber,
+        location.columnNumber,
+      );
+      if (message != null) {
+        throw new Error(message);
+      }
+    },
+    /**
+     * @param name {string}
+     * @param data {string | Uint8Array}
+     * @param options {{ contentType?: string } | undefined}
+     */
+    attach(name, data, options = { __proto__: null }) {
+      if (typeof name !== "string") {
+        throw new TypeError("Expected the attachment name to be a string");
+      }
+      let contentType = options.contentType;
+      if (typeof data === "string") {
+        data = core.encode(data);
+        contentType ??= "text/plain";
+      } else if (ObjectPrototypeIsPrototypeOf(Uint8ArrayPrototype, data)) {
+        contentType ??= "application/octet-stream";
+      } else {
+        throw new TypeError(
+          "Expected the attachment data to be a string or a Uint8Array",
+        );
+      }
+      op_test_event_attachment(desc.id, name, `${contentType}`, data);
+    },
   };
 }
 
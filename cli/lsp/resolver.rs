@@ -479,6 +479,7 @@ async fn create_npm_resolver(
         .unwrap_or_else(create_default_npmrc),
       npm_system_info: NpmSystemInfo::default(),
       lifecycle_scripts: Default::default(),
+      node_modules_dir_layout: Default::default(),
     })
   };
   Some(create_cli_npm_resolver_for_lsp(options).await)
@@ -304,6 +304,9 @@ impl TestRun {
             test::TestSpecifierOptions {
               filter,
               shuffle: None,
+              shard: None,
+              retries: 0,
+              update_snapshots: false,
               trace_leaks: false,
             },
           ))
@@ -357,6 +360,9 @@ impl TestRun {
             test::TestEvent::Slow(id, elapsed) => {
               reporter.report_slow(tests.read().get(&id).unwrap(), elapsed);
             }
+            test::TestEvent::Retry(id, failure, _elapsed) => {
+              reporter.report_retry(tests.read().get(&id).unwrap(), &failure);
+            }
             test::TestEvent::Result(id, result, elapsed) => {
               if tests_with_result.insert(id) {
                 let description = tests.read().get(&id).unwrap().clone();
@@ -411,6 +417,7 @@ impl TestRun {
             test::TestEvent::Completed => {
               reporter.report_completed();
             }
+            test::TestEvent::Attachment(..) => {}
             test::TestEvent::ForceEndReport => {}
             test::TestEvent::Sigint => {}
           }
@@ -616,6 +623,20 @@ impl LspTestReporter {
 
   fn report_slow(&mut self, _desc: &test::TestDescription, _elapsed: u64) {}
 
+  fn report_retry(
+    &mut self,
+    desc: &test::TestDescription,
+    failure: &test::TestFailure,
+  ) {
+    let desc = self.tests.get(&desc.id).unwrap();
+    self.progress(lsp_custom::TestRunProgressMessage::Output {
+      value: format!("Attempt failed, retrying: {failure}\n")
+        .replace('\n', "\r\n"),
+      test: Some(desc.as_test_identifier(&self.tests)),
+      location: None,
+    })
+  }
+
   fn report_output(&mut self, output: &[u8]) {
     let test = self
       .current_test
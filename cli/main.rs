@@ -28,6 +28,7 @@ mod util;
 mod version;
 mod worker;
 
+use crate::args::apply_permission_policy;
 use crate::args::flags_from_vec;
 use crate::args::DenoSubcommand;
 use crate::args::Flags;
@@ -36,6 +37,7 @@ use crate::graph_container::ModuleGraphContainer;
 use crate::util::display;
 use crate::util::v8::get_v8_flags_from_env;
 use crate::util::v8::init_v8_flags;
+use crate::util::v8::ENFORCE_WX_V8_FLAGS;
 
 use deno_runtime::WorkerExecutionMode;
 pub use deno_runtime::UNSTABLE_GRANULAR_FLAGS;
@@ -52,6 +54,7 @@ use deno_terminal::colors;
 use factory::CliFactory;
 use std::env;
 use std::future::Future;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -114,13 +117,22 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
     }),
     DenoSubcommand::Cache(cache_flags) => spawn_subcommand(async move {
       let factory = CliFactory::from_flags(flags);
-      let emitter = factory.emitter()?;
-      let main_graph_container =
-        factory.main_module_graph_container().await?;
-      main_graph_container
-        .load_and_type_check_files(&cache_flags.files)
-        .await?;
-      emitter.cache_module_emits(&main_graph_container.graph()).await
+      if !cache_flags.files.is_empty() {
+        let emitter = factory.emitter()?;
+        let main_graph_container =
+          factory.main_module_graph_container().await?;
+        main_graph_container
+          .load_and_type_check_files(&cache_flags.files)
+          .await?;
+        emitter
+          .cache_module_emits(&main_graph_container.graph())
+          .await?;
+      }
+      if cache_flags.verify_npm {
+        tools::npm_cache::verify_npm_cache(&factory, cache_flags.repair)
+          .await?;
+      }
+      Ok(())
     }),
     DenoSubcommand::Check(check_flags) => spawn_subcommand(async move {
       let factory = CliFactory::from_flags(flags);
@@ -157,6 +169,11 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
     DenoSubcommand::Jupyter(jupyter_flags) => spawn_subcommand(async {
       tools::jupyter::kernel(flags, jupyter_flags).await
     }),
+    DenoSubcommand::Kv(kv_flags) => spawn_subcommand(async {
+      // make compiler happy since kv is sync
+      tokio::task::yield_now().await;
+      tools::kv::kv(kv_flags)
+    }),
     DenoSubcommand::Uninstall(uninstall_flags) => spawn_subcommand(async {
       tools::installer::uninstall(uninstall_flags)
     }),
@@ -325,15 +342,20 @@ pub fn main() {
   );
 
   let args: Vec<_> = env::args_os().collect();
-  let future = async move {
-    // NOTE(lucacasonato): due to new PKU feature introduced in V8 11.6 we need to
-    // initialize the V8 platform on a parent thread of all threads that will spawn
-    // V8 isolates.
-    let flags = resolve_flags_and_init(args)?;
-    run_subcommand(Arc::new(flags)).await
+  // NOTE(lucacasonato): due to new PKU feature introduced in V8 11.6 we need to
+  // initialize the V8 platform on a parent thread of all threads that will spawn
+  // V8 isolates.
+  let flags = match resolve_flags_and_init(args) {
+    Ok(flags) => Arc::new(flags),
+    Err(err) => exit_for_error(err),
+  };
+  let result = if tools::run::is_kernel_sandboxed(&flags) {
+    tools::run::run_script_in_kernel_sandbox(flags)
+  } else {
+    create_and_run_current_thread_with_maybe_metrics(run_subcommand(flags))
   };
 
-  match create_and_run_current_thread_with_maybe_metrics(future) {
+  match result {
     Ok(exit_code) => std::process::exit(exit_code),
     Err(err) => exit_for_error(err),
   }
@@ -342,7 +364,7 @@ pub fn main() {
 fn resolve_flags_and_init(
   args: Vec<std::ffi::OsString>,
 ) -> Result<Flags, AnyError> {
-  let flags = match flags_from_vec(args) {
+  let mut flags = match flags_from_vec(args) {
     Ok(flags) => flags,
     Err(err @ clap::Error { .. })
       if err.kind() == clap::error::ErrorKind::DisplayHelp
@@ -376,7 +398,7 @@ fn resolve_flags_and_init(
     }
   }
 
-  let default_v8_flags = match flags.subcommand {
+  let mut default_v8_flags = match flags.subcommand {
     // Using same default as VSCode:
     // https://github.com/microsoft/vscode/blob/48d4ba271686e8072fc6674137415bc80d936bc7/extensions/typescript-language-features/src/configuration/configuration.ts#L213-L214
     DenoSubcommand::Lsp => vec!["--max-old-space-size=3072".to_string()],
@@ -398,9 +420,25 @@ fn resolve_flags_and_init(
     }
   };
 
+  if flags.permissions.enforce_wx {
+    default_v8_flags.extend(ENFORCE_WX_V8_FLAGS.iter().map(|f| f.to_string()));
+  }
+
   init_v8_flags(&default_v8_flags, &flags.v8_flags, get_v8_flags_from_env());
-  deno_core::JsRuntime::init_platform(None);
+  // the platform's threads have to be started once the kernel sandbox is
+  // applied, see `tools::run::run_script_in_kernel_sandbox`
+  if !tools::run::is_kernel_sandboxed(&flags) {
+    deno_core::JsRuntime::init_platform(None);
+  }
   util::logger::init(flags.log_level);
 
+  if let Some(policy) = flags.permissions.permission_policy.clone() {
+    apply_permission_policy(&mut flags.permissions, Path::new(&policy))?;
+  }
+
+  if let Some(audit_log) = &flags.permissions.permission_audit_log {
+    deno_runtime::deno_permissions::set_audit_log(Path::new(audit_log))?;
+  }
+
   Ok(flags)
 }
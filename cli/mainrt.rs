@@ -91,13 +91,26 @@ fn main() {
         let (metadata, eszip) = future.await?;
         util::logger::init(metadata.log_level);
         load_env_vars(&metadata.env_vars_from_env_file);
-        let exit_code = standalone::run(eszip, metadata).await?;
-        std::process::exit(exit_code);
+        Ok(Some((metadata, eszip)))
       }
-      Ok(None) => Ok(()),
+      Ok(None) => Ok(None),
       Err(err) => Err(err),
     }
   };
 
-  unwrap_or_exit(create_and_run_current_thread_with_maybe_metrics(future));
+  let Some((metadata, eszip)) =
+    unwrap_or_exit(create_and_run_current_thread_with_maybe_metrics(future))
+  else {
+    return;
+  };
+  // the program runs on a new runtime, so that all of its threads are started
+  // once the process is confined
+  if metadata.permissions.kernel_sandbox {
+    unwrap_or_exit(standalone::apply_kernel_sandbox(&metadata));
+  }
+  let exit_code =
+    unwrap_or_exit(create_and_run_current_thread_with_maybe_metrics(
+      standalone::run(eszip, metadata),
+    ));
+  std::process::exit(exit_code);
 }
@@ -389,6 +389,55 @@ fn napi_create_buffer_copy<'s>(
   napi_ok
 }
 
+#[napi_sym]
+fn node_api_create_buffer_from_arraybuffer<'s>(
+  env: &'s mut Env,
+  arraybuffer: napi_value,
+  byte_offset: usize,
+  byte_length: usize,
+  result: *mut napi_value<'s>,
+) -> napi_status {
+  check_arg!(env, arraybuffer);
+  check_arg!(env, result);
+
+  let Some(ab) =
+    arraybuffer.and_then(|v| v8::Local::<v8::ArrayBuffer>::try_from(v).ok())
+  else {
+    return napi_arraybuffer_expected;
+  };
+
+  if byte_offset
+    .checked_add(byte_length)
+    .map_or(true, |end| end > ab.byte_length())
+  {
+    let message = v8::String::new(
+      &mut env.scope(),
+      "The byte offset + length is out of range",
+    )
+    .unwrap();
+    let exc = v8::Exception::range_error(&mut env.scope(), message);
+    env.scope().throw_exception(exc);
+    return napi_pending_exception;
+  }
+
+  let byte_offset = v8::Number::new(&mut env.scope(), byte_offset as f64);
+  let byte_length = v8::Number::new(&mut env.scope(), byte_length as f64);
+  let buffer_constructor =
+    v8::Local::new(&mut env.scope(), &env.buffer_constructor);
+  let Some(buffer) = buffer_constructor.new_instance(
+    &mut env.scope(),
+    &[ab.into(), byte_offset.into(), byte_length.into()],
+  ) else {
+    return napi_generic_failure;
+  };
+
+  unsafe {
+    *result = buffer.into();
+  }
+
+  napi_ok
+}
+
 #[napi_sym]
 fn napi_is_buffer(
   env: *mut Env,
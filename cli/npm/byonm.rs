@@ -20,6 +20,7 @@ use deno_runtime::deno_node::NpmResolver;
 use deno_runtime::deno_node::PackageJson;
 use deno_semver::package::PackageReq;
 
+use crate::args::resolve_pkg_json_dep;
 use crate::args::NpmProcessState;
 use crate::args::NpmProcessStateKind;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
@@ -93,19 +94,19 @@ impl ByonmCliNpmResolver {
       pkg_json: &PackageJson,
     ) -> Option<String> {
       let deps = pkg_json.resolve_local_package_json_deps();
-      for (key, value) in deps {
-        if let Ok(value) = value {
-          match value {
+      for (key, value) in &deps {
+        if let Ok(value) = resolve_pkg_json_dep(pkg_json, key, value) {
+          match value.as_ref() {
             PackageJsonDepValue::Req(dep_req) => {
               if dep_req.name == req.name
                 && dep_req.version_req.intersects(&req.version_req)
               {
-                return Some(key);
+                return Some(key.clone());
               }
             }
             PackageJsonDepValue::Workspace(_workspace) => {
               if key == req.name && req.version_req.tag() == Some("workspace") {
-                return Some(key);
+                return Some(key.clone());
               }
             }
           }
@@ -10,6 +10,7 @@ use deno_core::url::Url;
 use deno_npm::NpmPackageCacheFolderId;
 use deno_semver::package::PackageNv;
 use deno_semver::Version;
+use walkdir::WalkDir;
 
 use crate::util::fs::canonicalize_path;
 use crate::util::path::root_url_to_safe_local_dirname;
@@ -188,6 +189,49 @@ impl NpmCacheDir {
     })
   }
 
+  /// Finds the packages in the folders of the known registries. Copies of
+  /// packages made for peer dependencies are not included.
+  pub fn cached_packages(&self) -> Vec<PackageNv> {
+    let mut packages = Vec::new();
+    for registry_dirname in &self.known_registries_dirnames {
+      // package name folders are the ones with a registry.json file, which
+      // are at most two levels below the registry folder for scoped packages
+      let registry_dir = self.root_dir.join(registry_dirname);
+      for entry in WalkDir::new(registry_dir)
+        .max_depth(3)
+        .into_iter()
+        .flatten()
+      {
+        if entry.file_name() != "registry.json" {
+          continue;
+        }
+        let Some(name_dir) = entry.path().parent() else {
+          continue;
+        };
+        let Ok(read_dir) = std::fs::read_dir(name_dir) else {
+          continue;
+        };
+        for version_entry in read_dir.flatten() {
+          if !version_entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+          }
+          let Ok(url) = Url::from_directory_path(version_entry.path()) else {
+            continue;
+          };
+          if let Some(id) = self.resolve_package_folder_id_from_specifier(&url)
+          {
+            if id.copy_index == 0 {
+              packages.push(id.nv);
+            }
+          }
+        }
+      }
+    }
+    packages.sort();
+    packages.dedup();
+    packages
+  }
+
   pub fn get_cache_location(&self) -> PathBuf {
     self.root_dir.clone()
   }
@@ -139,6 +139,11 @@ impl NpmCache {
     self.cache_dir.root_dir().to_owned()
   }
 
+  /// Gets the packages in the cache for the known registries.
+  pub fn cached_packages(&self) -> Vec<PackageNv> {
+    self.cache_dir.cached_packages()
+  }
+
   pub fn resolve_package_folder_id_from_specifier(
     &self,
     specifier: &ModuleSpecifier,
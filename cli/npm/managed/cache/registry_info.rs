@@ -199,7 +199,7 @@ impl RegistryInfoDownloader {
       let maybe_bytes = downloader
         .http_client_provider
         .get_or_create()?
-        .download_with_progress(package_url, maybe_auth_header, &guard)
+        .download_with_progress(package_url, maybe_auth_header?, &guard)
         .await?;
       match maybe_bytes {
         Some(bytes) => {
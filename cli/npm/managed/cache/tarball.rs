@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_core::anyhow::anyhow;
@@ -25,7 +26,9 @@ use crate::npm::common::maybe_auth_header_for_npm_registry;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::sync::MultiRuntimeAsyncValueCreator;
 
+use super::tarball_extract::find_modified_extracted_files;
 use super::tarball_extract::verify_and_extract_tarball;
+use super::tarball_extract::verify_tarball_integrity;
 use super::tarball_extract::TarballExtractionMode;
 use super::NpmCache;
 
@@ -133,6 +136,42 @@ impl TarballCache {
     }
   }
 
+  /// Downloads the tarball of a package that's in the cache, verifies it
+  /// against the integrity from the registry and compares its files with the
+  /// extracted ones. Returns the paths of the files that are missing or
+  /// modified, relative to the package folder.
+  ///
+  /// When `repair` is set, these files are extracted again.
+  pub async fn verify_package(
+    &self,
+    package_nv: &PackageNv,
+    dist: &NpmPackageVersionDistInfo,
+    repair: bool,
+  ) -> Result<Vec<PathBuf>, AnyError> {
+    let Some(bytes) = self.download_tarball(package_nv, dist).await? else {
+      bail!("Could not find npm package tarball at: {}", dist.tarball);
+    };
+    let package_folder = self.cache.package_folder_for_nv(package_nv);
+    let package_nv = package_nv.clone();
+    let dist = dist.clone();
+    deno_core::unsync::spawn_blocking(move || {
+      verify_tarball_integrity(&package_nv, &bytes, &dist.integrity())?;
+      let modified_files =
+        find_modified_extracted_files(&bytes, &package_folder)?;
+      if repair && !modified_files.is_empty() {
+        verify_and_extract_tarball(
+          &package_nv,
+          &bytes,
+          &dist,
+          &package_folder,
+          TarballExtractionMode::Overwrite,
+        )?;
+      }
+      Ok(modified_files)
+    })
+    .await?
+  }
+
   fn create_setup_future(
     self: &Arc<Self>,
     package_nv: PackageNv,
@@ -158,44 +197,8 @@ impl TarballCache {
         );
       }
 
-      if dist.tarball.is_empty() {
-        bail!("Tarball URL was empty.");
-      }
-
-      // IMPORTANT: npm registries may specify tarball URLs at different URLS than the
-      // registry, so we MUST get the auth for the tarball URL and not the registry URL.
-      let tarball_uri = Url::parse(&dist.tarball)?;
-      let maybe_registry_config =
-        tarball_cache.npmrc.tarball_config(&tarball_uri);
-      let maybe_auth_header = maybe_registry_config.and_then(|c| maybe_auth_header_for_npm_registry(c));
-
-      let guard = tarball_cache.progress_bar.update(&dist.tarball);
-      let result = tarball_cache.http_client_provider
-        .get_or_create()?
-        .download_with_progress(tarball_uri, maybe_auth_header, &guard)
-        .await;
-      let maybe_bytes = match result {
-        Ok(maybe_bytes) => maybe_bytes,
-        Err(DownloadError::BadResponse(err)) => {
-          if err.status_code == StatusCode::UNAUTHORIZED
-            && maybe_registry_config.is_none()
-            && tarball_cache.npmrc.get_registry_config(&package_nv.name).auth_token.is_some()
-          {
-            bail!(
-              concat!(
-                "No auth for tarball URI, but present for scoped registry.\n\n",
-                "Tarball URI: {}\n",
-                "Scope URI: {}\n\n",
-                "More info here: https://github.com/npm/cli/wiki/%22No-auth-for-URI,-but-auth-present-for-scoped-registry%22"
-              ),
-              dist.tarball,
-              registry_url,
-            )
-          }
-          return Err(err.into())
-        },
-        Err(err) => return Err(err.into()),
-      };
+      let maybe_bytes =
+        tarball_cache.download_tarball(&package_nv, &dist).await?;
       match maybe_bytes {
         Some(bytes) => {
           let extraction_mode = if should_use_cache || !package_folder_exists {
@@ -232,4 +235,56 @@ impl TarballCache {
     .map(|r| r.map_err(Arc::new))
     .boxed_local()
   }
+
+  async fn download_tarball(
+    &self,
+    package_nv: &PackageNv,
+    dist: &NpmPackageVersionDistInfo,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    if dist.tarball.is_empty() {
+      bail!("Tarball URL was empty.");
+    }
+
+    // IMPORTANT: npm registries may specify tarball URLs at different URLS than the
+    // registry, so we MUST get the auth for the tarball URL and not the registry URL.
+    let tarball_uri = Url::parse(&dist.tarball)?;
+    let maybe_registry_config = self.npmrc.tarball_config(&tarball_uri);
+    let maybe_auth_header = match maybe_registry_config {
+      Some(config) => maybe_auth_header_for_npm_registry(config)?,
+      None => None,
+    };
+
+    let guard = self.progress_bar.update(&dist.tarball);
+    let result = self
+      .http_client_provider
+      .get_or_create()?
+      .download_with_progress(tarball_uri, maybe_auth_header, &guard)
+      .await;
+    match result {
+      Ok(maybe_bytes) => Ok(maybe_bytes),
+      Err(DownloadError::BadResponse(err)) => {
+        if err.status_code == StatusCode::UNAUTHORIZED
+          && maybe_registry_config.is_none()
+          && self
+            .npmrc
+            .get_registry_config(&package_nv.name)
+            .auth_token
+            .is_some()
+        {
+          bail!(
+            concat!(
+              "No auth for tarball URI, but present for scoped registry.\n\n",
+              "Tarball URI: {}\n",
+              "Scope URI: {}\n\n",
+              "More info here: https://github.com/npm/cli/wiki/%22No-auth-for-URI,-but-auth-present-for-scoped-registry%22"
+            ),
+            dist.tarball,
+            self.npmrc.get_registry_url(&package_nv.name),
+          )
+        }
+        Err(err.into())
+      }
+      Err(err) => Err(err.into()),
+    }
+  }
 }
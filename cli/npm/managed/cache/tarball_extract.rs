@@ -3,6 +3,7 @@
 use std::collections::HashSet;
 use std::fs;
 use std::io::ErrorKind;
+use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -90,7 +91,7 @@ fn rename_with_retries(
   }
 }
 
-fn verify_tarball_integrity(
+pub fn verify_tarball_integrity(
   package: &PackageNv,
   data: &[u8],
   npm_integrity: &NpmPackageVersionDistInfoIntegrity,
@@ -206,6 +207,38 @@ fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// Compares the regular files in a tarball with the ones extracted to
+/// `output_folder`, returning the relative paths of the files that are
+/// missing or have different contents.
+pub fn find_modified_extracted_files(
+  data: &[u8],
+  output_folder: &Path,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let tar = GzDecoder::new(data);
+  let mut archive = Archive::new(tar);
+  let mut modified_files = Vec::new();
+
+  for entry in archive.entries()? {
+    let mut entry = entry?;
+    if entry.header().entry_type() != EntryType::Regular {
+      continue;
+    }
+    // skip the first component like when extracting
+    let relative_path = entry.path()?.components().skip(1).collect::<PathBuf>();
+    let mut expected = Vec::new();
+    entry.read_to_end(&mut expected)?;
+    match fs::read(output_folder.join(&relative_path)) {
+      Ok(actual) if actual == expected => {}
+      Ok(_) => modified_files.push(relative_path),
+      Err(err) if err.kind() == ErrorKind::NotFound => {
+        modified_files.push(relative_path)
+      }
+      Err(err) => return Err(err.into()),
+    }
+  }
+  Ok(modified_files)
+}
+
 #[cfg(test)]
 mod test {
   use deno_semver::Version;
@@ -321,4 +354,46 @@ mod test {
     assert!(dest_folder.join("a.txt").exists());
     assert!(!dest_folder.join("b.txt").exists());
   }
+
+  #[test]
+  fn find_modified_extracted_files_compares_contents() {
+    fn create_tarball(files: &[(&str, &str)]) -> Vec<u8> {
+      let encoder = flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+      );
+      let mut builder = tar::Builder::new(encoder);
+      for (path, text) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(text.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+          .append_data(&mut header, format!("package/{path}"), text.as_bytes())
+          .unwrap();
+      }
+      builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    let temp_dir = TempDir::new();
+    let output_folder = temp_dir.path().join("package");
+    let data = create_tarball(&[
+      ("index.js", "export {};"),
+      ("lib/a.js", "a"),
+      ("lib/b.js", "b"),
+    ]);
+    extract_tarball(&data, output_folder.as_path()).unwrap();
+    assert!(
+      find_modified_extracted_files(&data, output_folder.as_path())
+        .unwrap()
+        .is_empty()
+    );
+
+    output_folder.join("lib/a.js").write("changed");
+    output_folder.join("lib/b.js").remove_file();
+    assert_eq!(
+      find_modified_extracted_files(&data, output_folder.as_path()).unwrap(),
+      vec![PathBuf::from("lib/a.js"), PathBuf::from("lib/b.js")],
+    );
+  }
 }
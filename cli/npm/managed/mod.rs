@@ -30,10 +30,12 @@ use resolution::AddPkgReqsResult;
 
 use crate::args::CliLockfile;
 use crate::args::LifecycleScriptsConfig;
+use crate::args::NodeModulesDirLayout;
 use crate::args::NpmProcessState;
 use crate::args::NpmProcessStateKind;
 use crate::args::PackageJsonInstallDepsProvider;
 use crate::cache::FastInsecureHasher;
+use crate::colors;
 use crate::http_util::HttpClientProvider;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
 use crate::util::progress_bar::ProgressBar;
@@ -72,6 +74,7 @@ pub struct CliNpmResolverManagedCreateOptions {
   pub package_json_deps_provider: Arc<PackageJsonInstallDepsProvider>,
   pub npmrc: Arc<ResolvedNpmRc>,
   pub lifecycle_scripts: LifecycleScriptsConfig,
+  pub node_modules_dir_layout: NodeModulesDirLayout,
 }
 
 pub async fn create_managed_npm_resolver_for_lsp(
@@ -101,6 +104,7 @@ pub async fn create_managed_npm_resolver_for_lsp(
       options.npm_system_info,
       snapshot,
       options.lifecycle_scripts,
+      options.node_modules_dir_layout,
     )
   })
   .await
@@ -126,6 +130,7 @@ pub async fn create_managed_npm_resolver(
     options.npm_system_info,
     snapshot,
     options.lifecycle_scripts,
+    options.node_modules_dir_layout,
   ))
 }
 
@@ -143,6 +148,7 @@ fn create_inner(
   npm_system_info: NpmSystemInfo,
   snapshot: Option<ValidSerializedNpmResolutionSnapshot>,
   lifecycle_scripts: LifecycleScriptsConfig,
+  node_modules_dir_layout: NodeModulesDirLayout,
 ) -> Arc<dyn CliNpmResolver> {
   let resolution = Arc::new(NpmResolution::from_serialized(
     npm_api.clone(),
@@ -166,6 +172,7 @@ fn create_inner(
     node_modules_dir_path,
     npm_system_info.clone(),
     lifecycle_scripts.clone(),
+    node_modules_dir_layout,
   );
   Arc::new(ManagedCliNpmResolver::new(
     fs,
@@ -179,6 +186,7 @@ fn create_inner(
     text_only_progress_bar,
     npm_system_info,
     lifecycle_scripts,
+    node_modules_dir_layout,
   ))
 }
 
@@ -266,6 +274,7 @@ pub struct ManagedCliNpmResolver {
   npm_system_info: NpmSystemInfo,
   top_level_install_flag: AtomicFlag,
   lifecycle_scripts: LifecycleScriptsConfig,
+  node_modules_dir_layout: NodeModulesDirLayout,
 }
 
 impl std::fmt::Debug for ManagedCliNpmResolver {
@@ -290,6 +299,7 @@ impl ManagedCliNpmResolver {
     text_only_progress_bar: ProgressBar,
     npm_system_info: NpmSystemInfo,
     lifecycle_scripts: LifecycleScriptsConfig,
+    node_modules_dir_layout: NodeModulesDirLayout,
   ) -> Self {
     Self {
       fs,
@@ -304,6 +314,7 @@ impl ManagedCliNpmResolver {
       npm_system_info,
       top_level_install_flag: Default::default(),
       lifecycle_scripts,
+      node_modules_dir_layout,
     }
   }
 
@@ -513,6 +524,42 @@ impl ManagedCliNpmResolver {
       .map_err(|err| err.into())
   }
 
+  /// Verifies the packages in the global cache against the tarballs from
+  /// the registry, returning the packages with missing or modified files
+  /// along with the paths of these files. When `repair` is set, the files
+  /// are extracted again.
+  pub async fn verify_global_cache(
+    &self,
+    repair: bool,
+  ) -> Result<Vec<(PackageNv, Vec<PathBuf>)>, AnyError> {
+    let mut corrupted_packages = Vec::new();
+    for package_nv in self.npm_cache.cached_packages() {
+      let maybe_version_info = self
+        .npm_cache
+        .load_package_info(&package_nv.name)?
+        .and_then(|info| info.versions.get(&package_nv.version).cloned());
+      let Some(version_info) = maybe_version_info else {
+        log::warn!(
+          "{} No registry information cached for npm package '{}'. Skipping.",
+          colors::yellow("Warning"),
+          package_nv,
+        );
+        continue;
+      };
+      let modified_files = self
+        .tarball_cache
+        .verify_package(&package_nv, &version_info.dist, repair)
+        .await
+        .with_context(|| {
+          format!("Failed verifying npm package '{}'.", package_nv)
+        })?;
+      if !modified_files.is_empty() {
+        corrupted_packages.push((package_nv, modified_files));
+      }
+    }
+    Ok(corrupted_packages)
+  }
+
   pub fn global_cache_root_folder(&self) -> PathBuf {
     self.npm_cache.root_folder()
   }
@@ -598,6 +645,7 @@ impl CliNpmResolver for ManagedCliNpmResolver {
         self.root_node_modules_path().map(ToOwned::to_owned),
         self.npm_system_info.clone(),
         self.lifecycle_scripts.clone(),
+        self.node_modules_dir_layout,
       ),
       self.maybe_lockfile.clone(),
       self.npm_api.clone(),
@@ -608,6 +656,7 @@ impl CliNpmResolver for ManagedCliNpmResolver {
       self.text_only_progress_bar.clone(),
       self.npm_system_info.clone(),
       self.lifecycle_scripts.clone(),
+      self.node_modules_dir_layout,
     ))
   }
 
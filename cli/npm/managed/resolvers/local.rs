@@ -17,6 +17,7 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::args::LifecycleScriptsConfig;
+use crate::args::NodeModulesDirLayout;
 use crate::args::PackagesAllowedScripts;
 use async_trait::async_trait;
 use deno_ast::ModuleSpecifier;
@@ -48,6 +49,7 @@ use crate::npm::cache_dir::mixed_case_package_name_encode;
 use crate::util::fs::atomic_write_file_with_retries;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
 use crate::util::fs::clone_dir_recursive;
+use crate::util::fs::hard_link_dir_recursive;
 use crate::util::fs::symlink_dir;
 use crate::util::fs::LaxSingleProcessFsFlag;
 use crate::util::progress_bar::ProgressBar;
@@ -74,6 +76,7 @@ pub struct LocalNpmPackageResolver {
   system_info: NpmSystemInfo,
   registry_read_permission_checker: RegistryReadPermissionChecker,
   lifecycle_scripts: LifecycleScriptsConfig,
+  layout: NodeModulesDirLayout,
 }
 
 impl LocalNpmPackageResolver {
@@ -88,6 +91,7 @@ impl LocalNpmPackageResolver {
     node_modules_folder: PathBuf,
     system_info: NpmSystemInfo,
     lifecycle_scripts: LifecycleScriptsConfig,
+    layout: NodeModulesDirLayout,
   ) -> Self {
     Self {
       cache,
@@ -105,6 +109,7 @@ impl LocalNpmPackageResolver {
       root_node_modules_path: node_modules_folder,
       system_info,
       lifecycle_scripts,
+      layout,
     }
   }
 
@@ -254,6 +259,7 @@ impl NpmPackageFsResolver for LocalNpmPackageResolver {
       &self.root_node_modules_path,
       &self.system_info,
       &self.lifecycle_scripts,
+      self.layout,
     )
     .await
   }
@@ -276,6 +282,7 @@ fn resolve_baseline_custom_commands(
   snapshot: &NpmResolutionSnapshot,
   packages: &[NpmResolutionPackage],
   local_registry_dir: &Path,
+  permission_args: &crate::task_runner::TaskPermissionArgs,
 ) -> Result<crate::task_runner::TaskCustomCommands, AnyError> {
   let mut custom_commands = crate::task_runner::TaskCustomCommands::new();
   custom_commands
@@ -284,8 +291,12 @@ fn resolve_baseline_custom_commands(
   custom_commands
     .insert("npm".to_string(), Rc::new(crate::task_runner::NpmCommand));
 
-  custom_commands
-    .insert("node".to_string(), Rc::new(crate::task_runner::NodeCommand));
+  custom_commands.insert(
+    "node".to_string(),
+    Rc::new(crate::task_runner::NodeCommand {
+      permission_args: permission_args.clone(),
+    }),
+  );
 
   custom_commands.insert(
     "node-gyp".to_string(),
@@ -301,6 +312,7 @@ fn resolve_baseline_custom_commands(
     snapshot,
     packages,
     local_registry_dir,
+    permission_args,
   )
 }
 
@@ -315,6 +327,7 @@ fn resolve_custom_commands_from_packages<
   snapshot: &'a NpmResolutionSnapshot,
   packages: P,
   local_registry_dir: &Path,
+  permission_args: &crate::task_runner::TaskPermissionArgs,
 ) -> Result<crate::task_runner::TaskCustomCommands, AnyError> {
   let mut bin_entries = bin_entries::BinEntries::new();
   for package in packages {
@@ -332,6 +345,7 @@ fn resolve_custom_commands_from_packages<
       Rc::new(crate::task_runner::NodeModulesFileRunCommand {
         command_name: bin_name,
         path: script_path,
+        permission_args: permission_args.clone(),
       }),
     );
   }
@@ -359,6 +373,7 @@ fn resolve_custom_commands_from_deps(
   package: &NpmResolutionPackage,
   snapshot: &NpmResolutionSnapshot,
   local_registry_dir: &Path,
+  permission_args: &crate::task_runner::TaskPermissionArgs,
 ) -> Result<crate::task_runner::TaskCustomCommands, AnyError> {
   resolve_custom_commands_from_packages(
     baseline,
@@ -368,9 +383,38 @@ fn resolve_custom_commands_from_deps(
       .values()
       .map(|id| snapshot.package_from_id(id).unwrap()),
     local_registry_dir,
+    permission_args,
   )
 }
 
+/// The environment variables that lifecycle scripts can read, the others
+/// may hold credentials.
+const LIFECYCLE_SCRIPT_ENV_VARS: &[&str] = &[
+  "CI", "HOME", "INIT_CWD", "NODE_ENV", "PATH", "TEMP", "TMP", "TMPDIR",
+];
+
+/// The permissions of the `deno` processes that run the JavaScript of
+/// lifecycle scripts, like in place of `node`. Scripts may use the network,
+/// to download prebuilt binaries for example, but only read and write the
+/// `node_modules` directory, which contains every package, and the temp
+/// directory, and can't run subprocesses. The commands of the scripts
+/// themselves are run by the task shell and are not restricted.
+fn lifecycle_script_permission_args(node_modules_dir: &Path) -> Vec<String> {
+  let allowed_paths = [node_modules_dir.to_path_buf(), std::env::temp_dir()]
+    .iter()
+    .map(|path| path.to_string_lossy().into_owned())
+    .collect::<Vec<_>>()
+    .join(",");
+  vec![
+    "--no-prompt".to_string(),
+    format!("--allow-read={allowed_paths}"),
+    format!("--allow-write={allowed_paths}"),
+    format!("--allow-env={}", LIFECYCLE_SCRIPT_ENV_VARS.join(",")),
+    "--allow-net".to_string(),
+    "--allow-sys".to_string(),
+  ]
+}
+
 fn can_run_scripts(
   allow_scripts: &PackagesAllowedScripts,
   package_nv: &PackageNv,
@@ -407,6 +451,23 @@ fn has_lifecycle_scripts(
     || package.scripts.contains_key("postinstall")
 }
 
+/// Hard links the files of a package in the global cache, falling back to
+/// copying them when the node_modules directory is on another device.
+fn link_package_dir(from: &Path, to: &Path) -> Result<(), AnyError> {
+  if let Err(err) = hard_link_dir_recursive(from, to) {
+    log::debug!(
+      "Failed to hard link {} to {}, copying it instead: {:#}",
+      from.display(),
+      to.display(),
+      err
+    );
+    // don't write through links that were already created
+    let _ = fs::remove_dir_all(to);
+    clone_dir_recursive(from, to)?;
+  }
+  Ok(())
+}
+
 /// Creates a pnpm style folder structure.
 #[allow(clippy::too_many_arguments)]
 async fn sync_resolution_with_fs(
@@ -418,6 +479,7 @@ async fn sync_resolution_with_fs(
   root_node_modules_dir_path: &Path,
   system_info: &NpmSystemInfo,
   lifecycle_scripts: &LifecycleScriptsConfig,
+  layout: NodeModulesDirLayout,
 ) -> Result<(), AnyError> {
   if snapshot.is_empty() && pkg_json_deps_provider.workspace_pkgs().is_empty() {
     return Ok(()); // don't create the directory
@@ -448,7 +510,8 @@ async fn sync_resolution_with_fs(
 
   // 1. Write all the packages out the .deno directory.
   //
-  // Copy (hardlink in future) <global_registry_cache>/<package_id>/ to
+  // Copy, or hard link with the isolated layout,
+  // <global_registry_cache>/<package_id>/ to
   // node_modules/.deno/<package_folder_id_folder_name>/node_modules/<package_name>
   let package_partitions =
     snapshot.all_system_packages_partitioned(system_info);
@@ -496,11 +559,20 @@ async fn sync_resolution_with_fs(
         let package_path =
           join_package_name(&sub_node_modules, &package.id.nv.name);
         let cache_folder = cache.package_folder_for_nv(&package.id.nv);
+        // lifecycle scripts could modify the linked files in the global cache
+        let hard_link = layout == NodeModulesDirLayout::Isolated
+          && !["preinstall", "install", "postinstall"]
+            .iter()
+            .any(|script| package.scripts.contains_key(*script));
 
         deno_core::unsync::spawn_blocking({
           let package_path = package_path.clone();
           move || {
-            clone_dir_recursive(&cache_folder, &package_path)?;
+            if hard_link {
+              link_package_dir(&cache_folder, &package_path)?;
+            } else {
+              clone_dir_recursive(&cache_folder, &package_path)?;
+            }
             // write out a file that indicates this folder has been initialized
             fs::write(initialized_file, "")?;
 
@@ -702,8 +774,13 @@ async fn sync_resolution_with_fs(
   }
 
   // 6. Create a node_modules/.deno/node_modules/<package-name> directory with
-  // the remaining packages
-  for package in newest_packages_by_name.values() {
+  // the remaining packages, which the isolated layout leaves out so that
+  // packages can only resolve their own dependencies
+  let hoisted_packages = match layout {
+    NodeModulesDirLayout::Copied => newest_packages_by_name.values().collect(),
+    NodeModulesDirLayout::Isolated => Vec::new(),
+  };
+  for package in hoisted_packages {
     match found_names.entry(&package.id.nv.name) {
       Entry::Occupied(_) => {
         continue; // skip, already handled
@@ -738,25 +815,54 @@ async fn sync_resolution_with_fs(
   }
 
   // 8. Create symlinks for the workspace packages
+  //
+  // The workspace packages that are depended on, with a version or with the
+  // `workspace:` protocol, are hoisted into the root node_modules folder,
+  // unless the name is taken by an npm package or a different workspace
+  // package, in which case they're linked into the node_modules folder next
+  // to the package.json that depends on them. Unlike npm, workspace members
+  // that nothing depends on aren't linked.
   {
-    // todo(#24419): this is not exactly correct because it should
-    // install correctly for a workspace (potentially in sub directories),
-    // but this is good enough for a first pass
+    let mut hoisted_workspace_pkgs: HashMap<&String, &PathBuf> = HashMap::new();
     for workspace in pkg_json_deps_provider.workspace_pkgs() {
-      symlink_package_dir(
-        &workspace.target_dir,
-        &root_node_modules_dir_path.join(&workspace.alias),
-      )?;
+      let install_in_child = found_names.contains_key(&workspace.alias)
+        || match hoisted_workspace_pkgs.entry(&workspace.alias) {
+          Entry::Occupied(target_dir) => {
+            *target_dir.get() != &workspace.target_dir
+          }
+          Entry::Vacant(entry) => {
+            entry.insert(&workspace.target_dir);
+            false
+          }
+        };
+      if install_in_child {
+        let child_node_modules_dir = workspace.base_dir.join("node_modules");
+        fs::create_dir_all(&child_node_modules_dir).with_context(|| {
+          format!("Creating '{}'", child_node_modules_dir.display())
+        })?;
+        symlink_package_dir(
+          &workspace.target_dir,
+          &join_package_name(&child_node_modules_dir, &workspace.alias),
+        )?;
+      } else {
+        symlink_package_dir(
+          &workspace.target_dir,
+          &join_package_name(root_node_modules_dir_path, &workspace.alias),
+        )?;
+      }
     }
   }
 
   if !packages_with_scripts.is_empty() {
     // get custom commands for each bin available in the node_modules dir (essentially
     // the scripts that are in `node_modules/.bin`)
+    let permission_args =
+      Rc::new(lifecycle_script_permission_args(root_node_modules_dir_path));
     let base = resolve_baseline_custom_commands(
       snapshot,
       &package_partitions.packages,
       &deno_local_registry_dir,
+      &permission_args,
     )?;
     let init_cwd = lifecycle_scripts.initial_cwd.as_deref().unwrap();
     let process_state = crate::npm::managed::npm_process_state(
@@ -778,6 +884,7 @@ async fn sync_resolution_with_fs(
         &package,
         snapshot,
         &deno_local_registry_dir,
+        &permission_args,
       )?;
       for script_name in ["preinstall", "install", "postinstall"] {
         if let Some(script) = package.scripts.get(script_name) {
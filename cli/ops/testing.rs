@@ -1,11 +1,13 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::tools::test::TestAttachment;
 use crate::tools::test::TestContainer;
 use crate::tools::test::TestDescription;
 use crate::tools::test::TestEvent;
 use crate::tools::test::TestEventSender;
 use crate::tools::test::TestFailure;
 use crate::tools::test::TestLocation;
+use crate::tools::test::TestSnapshots;
 use crate::tools::test::TestStepDescription;
 use crate::tools::test::TestStepResult;
 
@@ -34,6 +36,9 @@ deno_core::extension!(deno_test,
     op_test_event_step_result_ok,
     op_test_event_step_result_ignored,
     op_test_event_step_result_failed,
+    op_test_event_attachment,
+    op_test_assert_snapshot,
+    op_test_assert_inline_snapshot,
   ],
   options = {
     sender: TestEventSender,
@@ -105,6 +110,7 @@ fn op_register_test(
   only: bool,
   sanitize_ops: bool,
   sanitize_resources: bool,
+  #[serde] retry: Option<usize>,
   #[string] file_name: String,
   #[smi] line_number: u32,
   #[smi] column_number: u32,
@@ -125,6 +131,7 @@ fn op_register_test(
     only,
     sanitize_ops,
     sanitize_resources,
+    retry,
     origin: origin.clone(),
     location: TestLocation {
       file_name,
@@ -225,3 +232,57 @@ fn op_test_event_step_result_failed(
     ))
     .ok();
 }
+
+#[op2]
+fn op_test_event_attachment(
+  state: &mut OpState,
+  #[smi] id: usize,
+  #[string] name: String,
+  #[string] content_type: String,
+  #[buffer(copy)] data: Vec<u8>,
+) {
+  let sender = state.borrow_mut::<TestEventSender>();
+  sender
+    .send(TestEvent::Attachment(
+      id,
+      TestAttachment {
+        name,
+        content_type,
+        data,
+      },
+    ))
+    .ok();
+}
+
+/// Returns why the assertion failed, if it did.
+#[op2]
+#[string]
+fn op_test_assert_snapshot(
+  state: &mut OpState,
+  #[string] name: String,
+  #[string] actual: String,
+) -> Result<Option<String>, AnyError> {
+  state.borrow_mut::<TestSnapshots>().assert(name, actual)
+}
+
+/// Returns why the assertion failed, if it did.
+#[op2]
+#[string]
+fn op_test_assert_inline_snapshot(
+  state: &mut OpState,
+  #[string] actual: String,
+  #[serde] expected: Option<String>,
+  #[string] file_name: String,
+  #[smi] line_number: u32,
+  #[smi] column_number: u32,
+) -> Option<String> {
+  state.borrow_mut::<TestSnapshots>().assert_inline(
+    actual,
+    expected,
+    TestLocation {
+      file_name,
+      line_number,
+      column_number,
+    },
+  )
+}
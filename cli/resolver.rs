@@ -45,6 +45,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::args::resolve_pkg_json_dep;
 use crate::args::JsxImportSourceConfig;
 use crate::args::DENO_DISABLE_PEDANTIC_NODE_WARNINGS;
 use crate::colors;
@@ -579,19 +580,18 @@ impl Resolver for CliGraphResolver {
           .map_err(ResolveError::Other)
           .map(|res| res.into_url()),
         MappedResolution::PackageJson {
+          pkg_json,
           dep_result,
           alias,
           sub_path,
-          ..
         } => {
           // found a specifier in the package.json, so mark that
           // we need to do an "npm install" later
           self.found_package_json_dep_flag.raise();
 
-          dep_result
-            .as_ref()
+          resolve_pkg_json_dep(pkg_json, alias, dep_result)
             .map_err(|e| ResolveError::Other(e.clone().into()))
-            .and_then(|dep| match dep {
+            .and_then(|dep| match dep.as_ref() {
               PackageJsonDepValue::Req(req) => {
                 ModuleSpecifier::parse(&format!(
                   "npm:{}{}",
@@ -643,6 +643,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         bare_node_builtins: cli_options.unstable_bare_node_builtins(),
         byonm: cli_options.use_byonm(),
         sloppy_imports: cli_options.unstable_sloppy_imports(),
+        io_uring: cli_options.unstable_io_uring(),
         features: cli_options.unstable_features(),
       },
     };
@@ -6,9 +6,12 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use deno_runtime::deno_fs::AccessCheckCb;
+use deno_runtime::deno_fs::DirHandle;
 use deno_runtime::deno_fs::FileSystem;
 use deno_runtime::deno_fs::FsDirEntry;
 use deno_runtime::deno_fs::FsFileType;
+use deno_runtime::deno_fs::FsReadDir;
+use deno_runtime::deno_fs::FsStatfs;
 use deno_runtime::deno_fs::OpenOptions;
 use deno_runtime::deno_fs::RealFs;
 use deno_runtime::deno_io::fs::File;
@@ -98,6 +101,197 @@ impl FileSystem for DenoCompileFileSystem {
     }
   }
 
+  fn make_anonymous_temp_file_sync(
+    &self,
+    dir: &Path,
+  ) -> FsResult<Rc<dyn File>> {
+    self.error_if_in_vfs(dir)?;
+    RealFs.make_anonymous_temp_file_sync(dir)
+  }
+  async fn make_anonymous_temp_file_async(
+    &self,
+    dir: PathBuf,
+  ) -> FsResult<Rc<dyn File>> {
+    self.error_if_in_vfs(&dir)?;
+    RealFs.make_anonymous_temp_file_async(dir).await
+  }
+
+  fn persist_file_sync(&self, file: Rc<dyn File>, path: &Path) -> FsResult<()> {
+    self.error_if_in_vfs(path)?;
+    RealFs.persist_file_sync(file, path)
+  }
+  async fn persist_file_async(
+    &self,
+    file: Rc<dyn File>,
+    path: PathBuf,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.persist_file_async(file, path).await
+  }
+  fn open_dir_handle_sync(&self, path: &Path) -> FsResult<DirHandle> {
+    self.error_if_in_vfs(path)?;
+    RealFs.open_dir_handle_sync(path)
+  }
+  async fn open_dir_handle_async(&self, path: PathBuf) -> FsResult<DirHandle> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.open_dir_handle_async(path).await
+  }
+
+  fn open_dir_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+  ) -> FsResult<DirHandle> {
+    self.error_if_in_vfs(&dir.resolve(path))?;
+    RealFs.open_dir_at_sync(dir, path)
+  }
+  async fn open_dir_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+  ) -> FsResult<DirHandle> {
+    self.error_if_in_vfs(&dir.resolve(&path))?;
+    RealFs.open_dir_at_async(dir, path).await
+  }
+
+  fn open_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    self.error_if_in_vfs(&dir.resolve(path))?;
+    RealFs.open_at_sync(dir, path, options)
+  }
+  async fn open_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    self.error_if_in_vfs(&dir.resolve(&path))?;
+    RealFs.open_at_async(dir, path, options).await
+  }
+
+  fn stat_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    self.error_if_in_vfs(&dir.resolve(path))?;
+    RealFs.stat_at_sync(dir, path, follow_symlinks)
+  }
+  async fn stat_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    self.error_if_in_vfs(&dir.resolve(&path))?;
+    RealFs.stat_at_async(dir, path, follow_symlinks).await
+  }
+
+  fn rename_at_sync(
+    &self,
+    dir: &DirHandle,
+    oldpath: &Path,
+    new_dir: &DirHandle,
+    newpath: &Path,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&dir.resolve(oldpath))?;
+    self.error_if_in_vfs(&new_dir.resolve(newpath))?;
+    RealFs.rename_at_sync(dir, oldpath, new_dir, newpath)
+  }
+  async fn rename_at_async(
+    &self,
+    dir: DirHandle,
+    oldpath: PathBuf,
+    new_dir: DirHandle,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&dir.resolve(&oldpath))?;
+    self.error_if_in_vfs(&new_dir.resolve(&newpath))?;
+    RealFs.rename_at_async(dir, oldpath, new_dir, newpath).await
+  }
+
+  fn unlink_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&dir.resolve(path))?;
+    RealFs.unlink_at_sync(dir, path, is_dir)
+  }
+  async fn unlink_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&dir.resolve(&path))?;
+    RealFs.unlink_at_async(dir, path, is_dir).await
+  }
+
+  fn get_xattr_sync(
+    &self,
+    path: &Path,
+    name: &str,
+  ) -> FsResult<Option<Vec<u8>>> {
+    self.error_if_in_vfs(path)?;
+    RealFs.get_xattr_sync(path, name)
+  }
+  async fn get_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<Option<Vec<u8>>> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.get_xattr_async(path, name).await
+  }
+
+  fn set_xattr_sync(
+    &self,
+    path: &Path,
+    name: &str,
+    value: &[u8],
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(path)?;
+    RealFs.set_xattr_sync(path, name, value)
+  }
+  async fn set_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+    value: Vec<u8>,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.set_xattr_async(path, name, value).await
+  }
+
+  fn list_xattrs_sync(&self, path: &Path) -> FsResult<Vec<String>> {
+    self.error_if_in_vfs(path)?;
+    RealFs.list_xattrs_sync(path)
+  }
+  async fn list_xattrs_async(&self, path: PathBuf) -> FsResult<Vec<String>> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.list_xattrs_async(path).await
+  }
+
+  fn remove_xattr_sync(&self, path: &Path, name: &str) -> FsResult<()> {
+    self.error_if_in_vfs(path)?;
+    RealFs.remove_xattr_sync(path, name)
+  }
+  async fn remove_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<()> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.remove_xattr_async(path, name).await
+  }
+
   fn mkdir_sync(
     &self,
     path: &Path,
@@ -255,6 +449,15 @@ impl FileSystem for DenoCompileFileSystem {
     }
   }
 
+  fn statfs_sync(&self, path: &Path) -> FsResult<FsStatfs> {
+    self.error_if_in_vfs(path)?;
+    RealFs.statfs_sync(path)
+  }
+  async fn statfs_async(&self, path: PathBuf) -> FsResult<FsStatfs> {
+    self.error_if_in_vfs(&path)?;
+    RealFs.statfs_async(path).await
+  }
+
   fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
     if self.0.is_path_within(path) {
       Ok(self.0.read_dir(path)?)
@@ -269,6 +472,23 @@ impl FileSystem for DenoCompileFileSystem {
       RealFs.read_dir_async(path).await
     }
   }
+  fn open_read_dir_sync(&self, path: &Path) -> FsResult<Box<dyn FsReadDir>> {
+    if self.0.is_path_within(path) {
+      Ok(Box::new(self.0.read_dir(path)?.into_iter()))
+    } else {
+      RealFs.open_read_dir_sync(path)
+    }
+  }
+  async fn open_read_dir_async(
+    &self,
+    path: PathBuf,
+  ) -> FsResult<Box<dyn FsReadDir>> {
+    if self.0.is_path_within(&path) {
+      Ok(Box::new(self.0.read_dir(&path)?.into_iter()))
+    } else {
+      RealFs.open_read_dir_async(path).await
+    }
+  }
 
   fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
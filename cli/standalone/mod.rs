@@ -8,6 +8,7 @@
 use crate::args::create_default_npmrc;
 use crate::args::get_root_cert_store;
 use crate::args::npm_pkg_req_ref_to_binary_command;
+use crate::args::resolve_pkg_json_dep;
 use crate::args::CaData;
 use crate::args::CacheSetting;
 use crate::args::PackageJsonInstallDepsProvider;
@@ -29,6 +30,7 @@ use crate::resolver::NpmModuleLoader;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::util::v8::construct_v8_flags;
+use crate::util::v8::ENFORCE_WX_V8_FLAGS;
 use crate::worker::CliMainWorkerFactory;
 use crate::worker::CliMainWorkerOptions;
 use crate::worker::ModuleLoaderAndSourceMapGetter;
@@ -60,6 +62,7 @@ use deno_runtime::deno_permissions::Permissions;
 use deno_runtime::deno_permissions::PermissionsContainer;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::RootCertStoreProvider;
+use deno_runtime::sandbox::KernelSandbox;
 use deno_runtime::WorkerExecutionMode;
 use deno_runtime::WorkerLogLevel;
 use deno_semver::npm::NpmPackageReqReference;
@@ -182,11 +185,14 @@ impl ModuleLoader for EmbeddedModuleLoader {
           .into_url(),
       ),
       Ok(MappedResolution::PackageJson {
+        pkg_json,
         dep_result,
         sub_path,
         alias,
-        ..
-      }) => match dep_result.as_ref().map_err(|e| AnyError::from(e.clone()))? {
+      }) => match resolve_pkg_json_dep(pkg_json, alias, dep_result)
+        .map_err(|e| AnyError::from(e.clone()))?
+        .as_ref()
+      {
         PackageJsonDepValue::Req(req) => self
           .shared
           .node_resolver
@@ -415,6 +421,18 @@ impl RootCertStoreProvider for StandaloneRootCertStoreProvider {
   }
 }
 
+/// Applies `--sandbox=kernel` on the main thread before the runtime that runs
+/// the program is created, since Landlock doesn't confine the threads that
+/// were started before.
+pub fn apply_kernel_sandbox(metadata: &Metadata) -> Result<(), AnyError> {
+  let maybe_cwd = std::env::current_dir().ok();
+  let permissions = metadata.permissions.to_options(maybe_cwd.as_deref())?;
+  let mut sandbox = KernelSandbox::from_permissions(&permissions);
+  // the npm packages are read from the executable
+  sandbox.allow_read(std::env::current_exe()?);
+  sandbox.apply()
+}
+
 pub async fn run(
   mut eszip: eszip::EszipV2,
   metadata: Metadata,
@@ -491,6 +509,8 @@ pub async fn run(
               registry_configs: Default::default(),
             }),
             lifecycle_scripts: Default::default(),
+            // only used when installing packages
+            node_modules_dir_layout: Default::default(),
           },
         ))
         .await?;
@@ -537,6 +557,8 @@ pub async fn run(
             // so no need to create actual `.npmrc` configuration.
             npmrc: create_default_npmrc(),
             lifecycle_scripts: Default::default(),
+            // only used when installing packages
+            node_modules_dir_layout: Default::default(),
           },
         ))
         .await?;
@@ -689,6 +711,10 @@ pub async fn run(
         .or(std::env::args().next()),
       node_debug: std::env::var("NODE_DEBUG").ok(),
       origin_data_folder_path: None,
+      cache_storage_max_size: None,
+      local_storage_location: None,
+      web_storage_max_size: None,
+      broadcast_channel_socket: None,
       seed: metadata.seed,
       unsafely_ignore_certificate_errors: metadata
         .unsafely_ignore_certificate_errors,
@@ -709,8 +735,18 @@ pub async fn run(
   );
 
   // Initialize v8 once from the main thread.
-  v8_set_flags(construct_v8_flags(&[], &metadata.v8_flags, vec![]));
+  let default_v8_flags = if metadata.permissions.enforce_wx {
+    ENFORCE_WX_V8_FLAGS.iter().map(|f| f.to_string()).collect()
+  } else {
+    vec![]
+  };
+  v8_set_flags(construct_v8_flags(
+    &default_v8_flags,
+    &metadata.v8_flags,
+    vec![],
+  ));
   deno_core::JsRuntime::init_platform(None);
+  deno_fs::set_io_uring_enabled(metadata.unstable_config.io_uring);
 
   let mut worker = worker_factory
     .create_main_worker(WorkerExecutionMode::Run, main_module, permissions)
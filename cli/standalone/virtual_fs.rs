@@ -79,12 +79,17 @@ impl VfsBuilder {
     if path != target_path {
       self.add_symlink(path, &target_path)?;
     }
-    self.add_dir_recursive_internal(&target_path)
+    self
+      .add_dir_recursive_internal(&target_path, &mut vec![target_path.clone()])
   }
 
+  /// Adds the directory at `path`, whose canonical path is the last of
+  /// `ancestors`, which are the canonical paths of the directories being
+  /// added so that symlinks to them aren't followed forever.
   fn add_dir_recursive_internal(
     &mut self,
     path: &Path,
+    ancestors: &mut Vec<PathBuf>,
   ) -> Result<(), AnyError> {
     self.add_dir(path)?;
     let read_dir = std::fs::read_dir(path)
@@ -96,7 +101,10 @@ impl VfsBuilder {
       let path = entry.path();
 
       if file_type.is_dir() {
-        self.add_dir_recursive_internal(&path)?;
+        let canonical_path = ancestors.last().unwrap().join(entry.file_name());
+        ancestors.push(canonical_path);
+        self.add_dir_recursive_internal(&path, ancestors)?;
+        ancestors.pop();
       } else if file_type.is_file() {
         self.add_file_at_path_not_symlink(&path)?;
       } else if file_type.is_symlink() {
@@ -117,6 +125,27 @@ impl VfsBuilder {
                 let file_bytes = std::fs::read(&target)
                   .with_context(|| format!("Reading {}", path.display()))?;
                 self.add_file(&path, file_bytes)?;
+              } else if target.is_dir() && ancestors.contains(&target) {
+                log::warn!(
+                  "{} Symlink at '{}' to '{}' is a cycle. Excluding it.",
+                  crate::colors::yellow("Warning"),
+                  path.display(),
+                  target.display(),
+                );
+              } else if target.is_dir() {
+                // for example, packages linked into a node_modules directory
+                // from a store of packages elsewhere on the system
+                log::warn!(
+                  "{} Symlink target is outside '{}'. Inlining symlink at '{}' to '{}' as directory.",
+                  crate::colors::yellow("Warning"),
+                  self.root_path.display(),
+                  path.display(),
+                  target.display(),
+                );
+                // reading the directory at the symlink's path follows it
+                ancestors.push(target);
+                self.add_dir_recursive_internal(&path, ancestors)?;
+                ancestors.pop();
               } else {
                 log::warn!(
                   "{} Symlink target is outside '{}'. Excluding symlink at '{}' with target '{}'.",
@@ -715,6 +744,24 @@ impl deno_io::fs::File for FileBackedVfsFile {
     Err(FsError::NotSupported)
   }
 
+  fn allocate_sync(self: Rc<Self>, _len: u64) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn allocate_async(self: Rc<Self>, _len: u64) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+
+  fn punch_hole_sync(self: Rc<Self>, _offset: u64, _len: u64) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn punch_hole_async(
+    self: Rc<Self>,
+    _offset: u64,
+    _len: u64,
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+
   fn utime_sync(
     self: Rc<Self>,
     _atime_secs: i64,
@@ -49,6 +49,15 @@ pub struct RunTaskOptions<'a> {
 
 pub type TaskCustomCommands = HashMap<String, Rc<dyn ShellCommand>>;
 
+/// The permission flags of the `deno` processes that run JavaScript for a
+/// task, like in place of `node`.
+pub type TaskPermissionArgs = Rc<Vec<String>>;
+
+/// Grants all permissions, which is what the tasks of the user get.
+pub fn allow_all_permission_args() -> TaskPermissionArgs {
+  Rc::new(vec!["-A".to_string()])
+}
+
 pub async fn run_task(opts: RunTaskOptions<'_>) -> Result<i32, AnyError> {
   let script = get_script_with_args(opts.script, opts.argv);
   let seq_list = deno_task_shell::parser::parse(&script)
@@ -164,7 +173,9 @@ impl ShellCommand for NpmCommand {
   }
 }
 
-pub struct NodeCommand;
+pub struct NodeCommand {
+  pub permission_args: TaskPermissionArgs,
+}
 
 impl ShellCommand for NodeCommand {
   fn execute(
@@ -190,7 +201,8 @@ impl ShellCommand for NodeCommand {
       )
       .execute(context);
     }
-    args.extend(["run", "-A"].into_iter().map(|s| s.to_string()));
+    args.push("run".to_string());
+    args.extend(self.permission_args.iter().cloned());
     args.extend(context.args.iter().cloned());
 
     let mut state = context.state;
@@ -293,6 +305,7 @@ impl ShellCommand for NpmPackageBinCommand {
 pub struct NodeModulesFileRunCommand {
   pub command_name: String,
   pub path: PathBuf,
+  pub permission_args: TaskPermissionArgs,
 }
 
 impl ShellCommand for NodeModulesFileRunCommand {
@@ -300,12 +313,9 @@ impl ShellCommand for NodeModulesFileRunCommand {
     &self,
     mut context: ShellCommandContext,
   ) -> LocalBoxFuture<'static, ExecuteResult> {
-    let mut args = vec![
-      "run".to_string(),
-      "--ext=js".to_string(),
-      "-A".to_string(),
-      self.path.to_string_lossy().to_string(),
-    ];
+    let mut args = vec!["run".to_string(), "--ext=js".to_string()];
+    args.extend(self.permission_args.iter().cloned());
+    args.push(self.path.to_string_lossy().to_string());
     args.extend(context.args);
     let executable_command = deno_task_shell::ExecutableCommand::new(
       "deno".to_string(),
@@ -382,7 +392,11 @@ fn resolve_bin_dir_entry_command(
       command_name,
       path.display()
     );
-    Some(NodeModulesFileRunCommand { command_name, path })
+    Some(NodeModulesFileRunCommand {
+      command_name,
+      path,
+      permission_args: allow_all_permission_args(),
+    })
   } else {
     log::debug!("Failed resolving npx command '{}'.", command_name);
     None
@@ -0,0 +1,189 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! The results that `deno bench --save-baseline=<file>` saves, and that
+//! `deno bench --compare=<file>` compares the results of a later run to.
+
+use deno_core::anyhow::Context;
+use deno_core::serde_json;
+use deno_core::url::Url;
+
+use crate::args::BenchFailThreshold;
+use crate::tools::test::fmt::to_relative_path_or_remote_url;
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchBaseline {
+  runtime: String,
+  cpu: String,
+  benches: Vec<BaselineBench>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaselineBench {
+  /// Relative to the working directory, so that baselines can be compared
+  /// between checkouts.
+  origin: String,
+  group: Option<String>,
+  name: String,
+  stats: BenchStats,
+}
+
+/// A bench of the run that is also in the baseline.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchComparison {
+  pub origin: String,
+  pub group: Option<String>,
+  pub name: String,
+  /// The average time per iteration in the baseline, in nanoseconds.
+  pub baseline_avg: f64,
+  pub avg: f64,
+  /// How much slower the run is than the baseline, negative when it's faster.
+  pub change_percent: f64,
+  /// Whether the change exceeds `--fail-threshold`.
+  pub regressed: bool,
+}
+
+impl BenchBaseline {
+  pub fn new(
+    cwd: &Url,
+    measurements: &[(BenchDescription, BenchStats)],
+  ) -> Self {
+    Self {
+      runtime: format!("{} {}", get_user_agent(), env!("TARGET")),
+      cpu: mitata::cpu::name(),
+      benches: measurements
+        .iter()
+        .filter(|(desc, _)| !desc.warmup)
+        .map(|(desc, stats)| BaselineBench {
+          origin: to_relative_path_or_remote_url(cwd, &desc.origin),
+          group: desc.group.clone(),
+          name: desc.name.clone(),
+          stats: stats.clone(),
+        })
+        .collect(),
+    }
+  }
+
+  pub fn load(path: &Path) -> Result<Self, AnyError> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+      format!("Failed to read bench baseline {}", path.display())
+    })?;
+    serde_json::from_str(&text)
+      .with_context(|| format!("Invalid bench baseline {}", path.display()))
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), AnyError> {
+    let mut text = serde_json::to_string_pretty(self)?;
+    text.push('\n');
+    std::fs::write(path, text).with_context(|| {
+      format!("Failed to write bench baseline {}", path.display())
+    })
+  }
+
+  pub fn compare(
+    &self,
+    cwd: &Url,
+    measurements: &[(BenchDescription, BenchStats)],
+    fail_threshold: Option<BenchFailThreshold>,
+  ) -> Vec<BenchComparison> {
+    measurements
+      .iter()
+      .filter(|(desc, _)| !desc.warmup)
+      .filter_map(|(desc, stats)| {
+        let origin = to_relative_path_or_remote_url(cwd, &desc.origin);
+        let baseline = self.benches.iter().find(|bench| {
+          bench.origin == origin
+            && bench.group == desc.group
+            && bench.name == desc.name
+        })?;
+        let change_percent =
+          (stats.avg - baseline.stats.avg) / baseline.stats.avg * 100.0;
+        Some(BenchComparison {
+          origin,
+          group: desc.group.clone(),
+          name: desc.name.clone(),
+          baseline_avg: baseline.stats.avg,
+          avg: stats.avg,
+          change_percent,
+          regressed: fail_threshold
+            .is_some_and(|threshold| change_percent > threshold.percent()),
+        })
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn measurement(name: &str, avg: f64) -> (BenchDescription, BenchStats) {
+    (
+      BenchDescription {
+        id: 0,
+        name: name.to_string(),
+        origin: "file:///project/a_bench.ts".to_string(),
+        baseline: false,
+        group: None,
+        ignore: false,
+        only: false,
+        warmup: false,
+      },
+      BenchStats {
+        n: 100,
+        min: avg,
+        max: avg,
+        avg,
+        p75: avg,
+        p99: avg,
+        p995: avg,
+        p999: avg,
+        high_precision: true,
+        used_explicit_timers: false,
+      },
+    )
+  }
+
+  #[test]
+  fn compares_to_baseline() {
+    let cwd = Url::parse("file:///project/").unwrap();
+    let baseline = BenchBaseline::new(
+      &cwd,
+      &[measurement("a", 100.0), measurement("b", 100.0)],
+    );
+    assert_eq!(baseline.benches[0].origin, "./a_bench.ts");
+
+    let comparisons = baseline.compare(
+      &Url::parse("file:///checkout/").unwrap(),
+      &[
+        (
+          BenchDescription {
+            origin: "file:///checkout/a_bench.ts".to_string(),
+            ..measurement("a", 0.0).0
+          },
+          measurement("a", 110.0).1,
+        ),
+        (
+          BenchDescription {
+            origin: "file:///checkout/a_bench.ts".to_string(),
+            ..measurement("b", 0.0).0
+          },
+          measurement("b", 90.0).1,
+        ),
+        measurement("c", 100.0),
+      ],
+      Some("5%".parse().unwrap()),
+    );
+    assert_eq!(comparisons.len(), 2);
+    assert_eq!(comparisons[0].name, "a");
+    assert!((comparisons[0].change_percent - 10.0).abs() < 1e-9);
+    assert!(comparisons[0].regressed);
+    assert_eq!(comparisons[1].name, "b");
+    assert!((comparisons[1].change_percent + 10.0).abs() < 1e-9);
+    assert!(!comparisons[1].regressed);
+  }
+}
@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::BenchFailThreshold;
 use crate::args::BenchFlags;
 use crate::args::Flags;
 use crate::colors;
@@ -26,6 +27,7 @@ use deno_core::futures::StreamExt;
 use deno_core::serde_v8;
 use deno_core::unsync::spawn;
 use deno_core::unsync::spawn_blocking;
+use deno_core::url::Url;
 use deno_core::v8;
 use deno_core::ModuleSpecifier;
 use deno_core::PollEventLoopOptions;
@@ -40,23 +42,31 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashSet;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
 
+mod baseline;
 mod mitata;
 mod reporters;
 
+use baseline::BenchBaseline;
+use baseline::BenchComparison;
 use reporters::BenchReporter;
 use reporters::ConsoleReporter;
 use reporters::JsonReporter;
 
 #[derive(Debug, Clone)]
 struct BenchSpecifierOptions {
+  cwd: Url,
   filter: TestFilter,
   json: bool,
   log_level: Option<log::Level>,
+  save_baseline: Option<PathBuf>,
+  compare: Option<PathBuf>,
+  fail_threshold: Option<BenchFailThreshold>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -92,6 +102,8 @@ pub struct BenchReport {
   pub failed: usize,
   pub failures: Vec<(BenchDescription, Box<JsError>)>,
   pub measurements: Vec<(BenchDescription, BenchStats)>,
+  /// The benches that are in the baseline of `--compare`.
+  pub comparisons: Vec<BenchComparison>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
@@ -128,6 +140,7 @@ impl BenchReport {
       failed: 0,
       failures: Vec::new(),
       measurements: Vec::new(),
+      comparisons: Vec::new(),
     }
   }
 }
@@ -268,6 +281,11 @@ async fn bench_specifiers(
   specifiers: Vec<ModuleSpecifier>,
   options: BenchSpecifierOptions,
 ) -> Result<(), AnyError> {
+  let baseline = options
+    .compare
+    .as_deref()
+    .map(BenchBaseline::load)
+    .transpose()?;
   let (sender, mut receiver) = unbounded_channel::<BenchEvent>();
   let log_level = options.log_level;
   let option_for_handles = options.clone();
@@ -347,6 +365,14 @@ async fn bench_specifiers(
         }
       }
 
+      if let Some(baseline) = &baseline {
+        report.comparisons = baseline.compare(
+          &options.cwd,
+          &report.measurements,
+          options.fail_threshold,
+        );
+      }
+
       reporter.report_end(&report);
 
       if used_only {
@@ -359,6 +385,21 @@ async fn bench_specifiers(
         return Err(generic_error("Bench failed"));
       }
 
+      if let Some(path) = &options.save_baseline {
+        BenchBaseline::new(&options.cwd, &report.measurements).save(path)?;
+      }
+
+      if let Some(threshold) = options.fail_threshold {
+        let regressed =
+          report.comparisons.iter().filter(|c| c.regressed).count();
+        if regressed > 0 {
+          return Err(generic_error(format!(
+            "Bench failed because {regressed} {} more than {threshold} slower than in the baseline",
+            if regressed == 1 { "bench is" } else { "benches are" }
+          )));
+        }
+      }
+
       Ok(())
     })
   };
@@ -400,6 +441,15 @@ fn has_supported_bench_path_name(path: &Path) -> bool {
   }
 }
 
+fn cwd_url(cwd: &Path) -> Result<Url, AnyError> {
+  Url::from_directory_path(cwd).map_err(|_| {
+    generic_error(format!(
+      "Unable to construct URL from the path of cwd: {}",
+      cwd.to_string_lossy(),
+    ))
+  })
+}
+
 pub async fn run_benchmarks(
   flags: Arc<Flags>,
   bench_flags: BenchFlags,
@@ -449,9 +499,17 @@ pub async fn run_benchmarks(
     &permissions,
     specifiers,
     BenchSpecifierOptions {
+      cwd: cwd_url(cli_options.initial_cwd())?,
       filter: TestFilter::from_flag(&workspace_bench_options.filter),
       json: workspace_bench_options.json,
       log_level,
+      save_baseline: workspace_bench_options
+        .save_baseline
+        .map(|path| cli_options.initial_cwd().join(path)),
+      compare: workspace_bench_options
+        .compare
+        .map(|path| cli_options.initial_cwd().join(path)),
+      fail_threshold: workspace_bench_options.fail_threshold,
     },
   )
   .await?;
@@ -571,9 +629,17 @@ pub async fn run_benchmarks_with_watch(
           &permissions,
           specifiers,
           BenchSpecifierOptions {
+            cwd: cwd_url(cli_options.initial_cwd())?,
             filter: TestFilter::from_flag(&workspace_bench_options.filter),
             json: workspace_bench_options.json,
             log_level,
+            save_baseline: workspace_bench_options
+              .save_baseline
+              .map(|path| cli_options.initial_cwd().join(path)),
+            compare: workspace_bench_options
+              .compare
+              .map(|path| cli_options.initial_cwd().join(path)),
+            fail_threshold: workspace_bench_options.fail_threshold,
           },
         )
         .await?;
@@ -20,6 +20,8 @@ struct JsonReporterOutput {
   runtime: String,
   cpu: String,
   benches: Vec<JsonReporterBench>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  comparisons: Vec<BenchComparison>,
 }
 
 impl Default for JsonReporterOutput {
@@ -28,6 +30,7 @@ impl Default for JsonReporterOutput {
       runtime: format!("{} {}", get_user_agent(), env!("TARGET")),
       cpu: mitata::cpu::name(),
       benches: vec![],
+      comparisons: vec![],
     }
   }
 }
@@ -56,7 +59,8 @@ impl BenchReporter for JsonReporter {
   #[cold]
   fn report_plan(&mut self, _plan: &BenchPlan) {}
 
-  fn report_end(&mut self, _report: &BenchReport) {
+  fn report_end(&mut self, report: &BenchReport) {
+    self.0.comparisons.clone_from(&report.comparisons);
     match write_json_to_stdout(self) {
       Ok(_) => (),
       Err(e) => println!("{}", e),
@@ -284,8 +288,32 @@ impl BenchReporter for ConsoleReporter {
     self.group_measurements.clear();
   }
 
-  fn report_end(&mut self, _: &BenchReport) {
+  fn report_end(&mut self, report: &BenchReport) {
     self.report_group_summary();
+
+    if report.comparisons.is_empty() {
+      return;
+    }
+    println!("{}", colors::gray("compared to the baseline"));
+    for comparison in &report.comparisons {
+      let change = format!("{:+.2}%", comparison.change_percent);
+      let change = if comparison.regressed {
+        colors::red_bold(change).to_string()
+      } else if comparison.change_percent < 0.0 {
+        colors::green(change).to_string()
+      } else {
+        change
+      };
+      println!(
+        "  {} {} {} -> {} {}",
+        colors::cyan_bold(&comparison.name),
+        colors::gray(&comparison.origin),
+        mitata::fmt_duration(comparison.baseline_avg),
+        mitata::fmt_duration(comparison.avg),
+        change
+      );
+    }
+    println!();
   }
 
   fn report_uncaught_error(&mut self, _origin: &str, error: Box<JsError>) {
@@ -0,0 +1,46 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::KvAction;
+use crate::args::KvFlags;
+use crate::colors;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_runtime::deno_kv::sqlite::backup_database;
+use deno_runtime::deno_kv::sqlite::restore_database;
+use log::info;
+
+pub fn kv(kv_flags: KvFlags) -> Result<(), AnyError> {
+  let cwd =
+    std::env::current_dir().context("Can't read current working directory.")?;
+  let db = cwd.join(&kv_flags.db);
+  let path = cwd.join(&kv_flags.path);
+  let source = match kv_flags.action {
+    KvAction::Backup => &db,
+    KvAction::Restore => &path,
+  };
+  if !source.is_file() {
+    bail!("File not found: {}", source.display());
+  }
+  match kv_flags.action {
+    KvAction::Backup => {
+      backup_database(&db, &path)?;
+      info!(
+        "{} {} to {}",
+        colors::green("Backed up"),
+        db.display(),
+        path.display()
+      );
+    }
+    KvAction::Restore => {
+      restore_database(&path, &db)?;
+      info!(
+        "{} {} from {}",
+        colors::green("Restored"),
+        db.display(),
+        path.display()
+      );
+    }
+  }
+  Ok(())
+}
@@ -11,7 +11,9 @@ pub mod info;
 pub mod init;
 pub mod installer;
 pub mod jupyter;
+pub mod kv;
 pub mod lint;
+pub mod npm_cache;
 pub mod registry;
 pub mod repl;
 pub mod run;
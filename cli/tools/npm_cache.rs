@@ -0,0 +1,46 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_terminal::colors;
+
+use crate::factory::CliFactory;
+
+/// Verifies the packages in the global npm cache, printing the corrupted
+/// ones. Errors if any were found and `repair` isn't set.
+pub async fn verify_npm_cache(
+  factory: &CliFactory,
+  repair: bool,
+) -> Result<(), AnyError> {
+  let npm_resolver = factory.npm_resolver().await?;
+  let Some(npm_resolver) = npm_resolver.as_managed() else {
+    bail!("Verifying the npm cache is not supported when using a node_modules directory managed by another package manager.");
+  };
+  let corrupted_packages = npm_resolver.verify_global_cache(repair).await?;
+  if corrupted_packages.is_empty() {
+    log::info!("{} No corrupted npm packages found.", colors::green("Ok"));
+    return Ok(());
+  }
+
+  for (package_nv, modified_files) in &corrupted_packages {
+    log::info!(
+      "{} npm:{}",
+      if repair {
+        colors::green("Repaired")
+      } else {
+        colors::red("Corrupted")
+      },
+      package_nv
+    );
+    for file in modified_files {
+      log::info!("  {}", file.display());
+    }
+  }
+  if !repair {
+    bail!(
+      "Found {} corrupted npm package(s) in the cache. Run again with --repair to restore them.",
+      corrupted_packages.len()
+    );
+  }
+  Ok(())
+}
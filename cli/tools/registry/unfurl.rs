@@ -14,6 +14,7 @@ use deno_graph::TypeScriptReference;
 use deno_package_json::PackageJsonDepValue;
 use deno_runtime::deno_node::is_builtin_node_module;
 
+use crate::args::resolve_pkg_json_dep;
 use crate::resolver::SloppyImportsResolver;
 
 #[derive(Debug, Clone)]
@@ -97,12 +98,12 @@ impl SpecifierUnfurler {
           .ok()
         }
         MappedResolution::PackageJson {
+          pkg_json,
           alias,
           sub_path,
           dep_result,
-          ..
-        } => match dep_result {
-          Ok(dep) => match dep {
+        } => match resolve_pkg_json_dep(pkg_json, alias, dep_result) {
+          Ok(dep) => match dep.as_ref() {
             PackageJsonDepValue::Req(pkg_req) => {
               // todo(#24612): consider warning or error when this is an npm workspace
               // member that's also a jsr package?
@@ -11,6 +11,7 @@ use deno_core::serde_json::json;
 use deno_core::serde_json::{self};
 use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
+use deno_runtime::deno_node::NodeResolver;
 use deno_terminal::colors;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -59,6 +60,7 @@ pub struct HmrRunner {
   watcher_communicator: Arc<WatcherCommunicator>,
   script_ids: HashMap<String, String>,
   emitter: Arc<Emitter>,
+  node_resolver: Arc<NodeResolver>,
 }
 
 #[async_trait::async_trait(?Send)]
@@ -109,6 +111,7 @@ impl crate::worker::HmrRunner for HmrRunner {
             let _ = self.watcher_communicator.force_restart();
             continue;
           };
+          self.node_resolver.invalidate_realpath_cache(&changed_paths);
 
           let filtered_paths: Vec<PathBuf> = changed_paths.into_iter().filter(|p| p.extension().map_or(false, |ext| {
             let ext_str = ext.to_str().unwrap();
@@ -173,12 +176,14 @@ impl crate::worker::HmrRunner for HmrRunner {
 impl HmrRunner {
   pub fn new(
     emitter: Arc<Emitter>,
+    node_resolver: Arc<NodeResolver>,
     session: LocalInspectorSession,
     watcher_communicator: Arc<WatcherCommunicator>,
   ) -> Self {
     Self {
       session,
       emitter,
+      node_resolver,
       watcher_communicator,
       script_ids: HashMap::new(),
     }
@@ -4,10 +4,14 @@ use std::io::Read;
 use std::sync::Arc;
 
 use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
 use deno_runtime::deno_permissions::Permissions;
 use deno_runtime::deno_permissions::PermissionsContainer;
+use deno_runtime::sandbox::KernelSandbox;
+use deno_runtime::tokio_util::create_and_run_current_thread_with_maybe_metrics;
 use deno_runtime::WorkerExecutionMode;
 
+use crate::args::DenoSubcommand;
 use crate::args::EvalFlags;
 use crate::args::Flags;
 use crate::args::WatchFlagsWithPaths;
@@ -15,6 +19,7 @@ use crate::factory::CliFactory;
 use crate::file_fetcher::File;
 use crate::util;
 use crate::util::file_watcher::WatcherRestartMode;
+use crate::worker::CliMainWorkerFactory;
 
 pub mod hmr;
 
@@ -76,6 +81,84 @@ To grant permissions, set them before the script argument. For example:
   Ok(exit_code)
 }
 
+/// Whether `flags` run a program that is confined by `--sandbox=kernel`, see
+/// [`run_script_in_kernel_sandbox`].
+pub fn is_kernel_sandboxed(flags: &Flags) -> bool {
+  flags.permissions.kernel_sandbox
+    && matches!(
+      &flags.subcommand,
+      DenoSubcommand::Run(run_flags)
+        if run_flags.watch.is_none() && !run_flags.is_stdin()
+    )
+}
+
+/// Runs the script of `deno run --sandbox=kernel`.
+///
+/// Landlock only confines the thread that applies it and the threads that
+/// thread starts afterwards, so the program can't run on the runtime that
+/// loads its modules: the blocking pool of that runtime would keep
+/// unconfined threads around to perform its async ops. Instead the modules
+/// are loaded on a runtime of their own, the sandbox is applied on the main
+/// thread once that runtime is gone, and the program runs on a new runtime,
+/// whose threads and those of the V8 platform are all started afterwards.
+pub fn run_script_in_kernel_sandbox(
+  flags: Arc<Flags>,
+) -> Result<i32, AnyError> {
+  let (worker_factory, main_module, permissions, sandbox) =
+    create_and_run_current_thread_with_maybe_metrics(
+      load_kernel_sandboxed_script(flags),
+    )?;
+  sandbox.apply()?;
+  deno_core::JsRuntime::init_platform(None);
+  create_and_run_current_thread_with_maybe_metrics(async move {
+    let mut worker = worker_factory
+      .create_main_worker(WorkerExecutionMode::Run, main_module, permissions)
+      .await?;
+    worker.run().await
+  })
+}
+
+async fn load_kernel_sandboxed_script(
+  flags: Arc<Flags>,
+) -> Result<
+  (
+    CliMainWorkerFactory,
+    ModuleSpecifier,
+    PermissionsContainer,
+    KernelSandbox,
+  ),
+  AnyError,
+> {
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  let main_module = cli_options.resolve_main_module()?;
+
+  maybe_npm_install(&factory).await?;
+
+  // Downloads the remote modules to the module cache and finds the local
+  // ones, which the program loads again once it's confined.
+  let graph = factory
+    .module_graph_creator()
+    .await?
+    .create_graph(
+      cli_options.type_check_mode().as_graph_kind(),
+      vec![main_module.clone()],
+    )
+    .await?;
+  let mut sandbox = factory.create_kernel_sandbox()?;
+  for module in graph.modules() {
+    if let Ok(path) = module.specifier().to_file_path() {
+      sandbox.allow_read(path);
+    }
+  }
+
+  let permissions = PermissionsContainer::new(Permissions::from_options(
+    &cli_options.permissions_options()?,
+  )?);
+  let worker_factory = factory.create_cli_main_worker_factory().await?;
+  Ok((worker_factory, main_module, permissions, sandbox))
+}
+
 pub async fn run_from_stdin(flags: Arc<Flags>) -> Result<i32, AnyError> {
   let factory = CliFactory::from_flags(flags);
   let cli_options = factory.cli_options()?;
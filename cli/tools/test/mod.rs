@@ -4,6 +4,8 @@ use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::TestFlags;
 use crate::args::TestReporterConfig;
+use crate::args::TestShard;
+use crate::cache::FastInsecureHasher;
 use crate::colors;
 use crate::display;
 use crate::factory::CliFactory;
@@ -37,6 +39,9 @@ use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
 use deno_core::located_script_name;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
 use deno_core::serde_v8;
 use deno_core::stats::RuntimeActivity;
 use deno_core::stats::RuntimeActivityDiff;
@@ -53,6 +58,8 @@ use deno_core::OpState;
 use deno_core::PollEventLoopOptions;
 use deno_runtime::deno_io::Stdio;
 use deno_runtime::deno_io::StdioPipe;
+use deno_runtime::deno_permissions::create_child_permissions;
+use deno_runtime::deno_permissions::ChildPermissionsArg;
 use deno_runtime::deno_permissions::Permissions;
 use deno_runtime::deno_permissions::PermissionsContainer;
 use deno_runtime::fmt_errors::format_js_error;
@@ -91,6 +98,7 @@ use tokio::signal;
 mod channel;
 pub mod fmt;
 pub mod reporters;
+mod snapshot;
 
 pub use channel::create_single_test_event_channel;
 pub use channel::create_test_event_channel;
@@ -99,12 +107,15 @@ pub use channel::TestEventSender;
 pub use channel::TestEventWorkerSender;
 use fmt::format_sanitizer_diff;
 pub use fmt::format_test_error;
+use fmt::to_relative_path_or_remote_url;
 use reporters::CompoundTestReporter;
+use reporters::CustomTestReporter;
 use reporters::DotTestReporter;
 use reporters::JunitTestReporter;
 use reporters::PrettyTestReporter;
 use reporters::TapTestReporter;
 use reporters::TestReporter;
+pub use snapshot::TestSnapshots;
 
 /// How many times we're allowed to spin the event loop before considering something a leak.
 const MAX_SANITIZER_LOOP_SPINS: usize = 16;
@@ -265,6 +276,9 @@ pub struct TestDescription {
   pub location: TestLocation,
   pub sanitize_ops: bool,
   pub sanitize_resources: bool,
+  /// The `retry` option of the test, which takes precedence over
+  /// `--retries`.
+  pub retry: Option<usize>,
 }
 
 /// May represent a failure of a test or test step.
@@ -455,11 +469,15 @@ pub enum TestEvent {
   Wait(usize),
   Output(Vec<u8>),
   Slow(usize, u64),
+  /// A failed attempt of a test that is going to be run again.
+  Retry(usize, TestFailure, u64),
   Result(usize, TestResult, u64),
   UncaughtError(String, Box<JsError>),
   StepRegister(TestStepDescription),
   StepWait(usize),
   StepResult(usize, TestStepResult, u64),
+  /// A file attached to the test or step with the id with `t.attach()`.
+  Attachment(usize, TestAttachment),
   /// Indicates that this worker has completed running tests.
   Completed,
   /// Indicates that the user has cancelled the test run with Ctrl+C and
@@ -477,9 +495,11 @@ impl TestEvent {
     matches!(
       self,
       TestEvent::Plan(..)
+        | TestEvent::Retry(..)
         | TestEvent::Result(..)
         | TestEvent::StepWait(..)
         | TestEvent::StepResult(..)
+        | TestEvent::Attachment(..)
         | TestEvent::UncaughtError(..)
         | TestEvent::ForceEndReport
         | TestEvent::Completed
@@ -487,6 +507,15 @@ impl TestEvent {
   }
 }
 
+/// A file attached to a test or step, for reporters to include in their
+/// output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestAttachment {
+  pub name: String,
+  pub content_type: String,
+  pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TestSummary {
   pub total: usize,
@@ -499,9 +528,18 @@ pub struct TestSummary {
   pub filtered_out: usize,
   pub measured: usize,
   pub failures: Vec<(TestFailureDescription, TestFailure)>,
+  pub flaky: Vec<FlakyTest>,
   pub uncaught_errors: Vec<(String, Box<JsError>)>,
 }
 
+/// A test that passed after failing, see `--retries`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlakyTest {
+  pub description: TestFailureDescription,
+  /// The failures of every failed attempt, including those of its steps.
+  pub attempts: Vec<Vec<(TestFailureDescription, TestFailure)>>,
+}
+
 #[derive(Debug, Clone)]
 struct TestSpecifiersOptions {
   cwd: Url,
@@ -512,15 +550,46 @@ struct TestSpecifiersOptions {
   specifier: TestSpecifierOptions,
   reporter: TestReporterConfig,
   junit_path: Option<String>,
+  /// A custom reporter module runs with the permissions, configuration and
+  /// unstable features of the tests.
+  reporter_args: Vec<String>,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct TestSpecifierOptions {
   pub shuffle: Option<u64>,
+  pub shard: Option<TestShardFilter>,
+  /// How many times a failing test is run again, unless the test sets its
+  /// own `retry` option.
+  pub retries: usize,
+  /// `--update-snapshots`.
+  pub update_snapshots: bool,
   pub filter: TestFilter,
   pub trace_leaks: bool,
 }
 
+/// Selects the tests of one `--shard` by a hash of their file and name.
+#[derive(Debug, Clone)]
+pub struct TestShardFilter {
+  pub shard: TestShard,
+  /// Files are hashed relative to this, so that tests are split the same way
+  /// on machines with different checkout directories.
+  pub cwd: Url,
+}
+
+impl TestShardFilter {
+  pub fn includes(&self, specifier: &ModuleSpecifier, name: &str) -> bool {
+    let file = to_relative_path_or_remote_url(&self.cwd, specifier.as_str());
+    let hash = FastInsecureHasher::new_without_deno_version()
+      .write_str(&file)
+      .write_u8(0)
+      .write_str(name)
+      .finish();
+    let count = self.shard.count.get() as u64;
+    hash % count == self.shard.index.get() as u64 - 1
+  }
+}
+
 impl TestSummary {
   pub fn new() -> TestSummary {
     TestSummary {
@@ -534,6 +603,7 @@ impl TestSummary {
       filtered_out: 0,
       measured: 0,
       failures: Vec::new(),
+      flaky: Vec::new(),
       uncaught_errors: Vec::new(),
     }
   }
@@ -541,39 +611,76 @@ impl TestSummary {
   fn has_failed(&self) -> bool {
     self.failed > 0 || !self.failures.is_empty()
   }
+
+  /// Removes the failed steps of an attempt of the test `id` that is
+  /// retried, so that only the last attempt fails the run.
+  fn take_step_failures(
+    &mut self,
+    id: usize,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> Vec<(TestFailureDescription, TestFailure)> {
+    let (step_failures, failures) = std::mem::take(&mut self.failures)
+      .into_iter()
+      .partition::<Vec<_>, _>(|(description, _)| {
+        test_steps
+          .get(&description.id)
+          .is_some_and(|step| step.root_id == id)
+      });
+    self.failures = failures;
+    self.failed_steps -= step_failures.len();
+    step_failures
+  }
 }
 
-fn get_test_reporter(options: &TestSpecifiersOptions) -> Box<dyn TestReporter> {
+fn get_test_reporter(
+  options: &TestSpecifiersOptions,
+) -> Result<Box<dyn TestReporter>, AnyError> {
   let parallel = options.concurrent_jobs.get() > 1;
+  let shard = options.specifier.shard.as_ref().map(|filter| filter.shard);
   let reporter: Box<dyn TestReporter> = match &options.reporter {
     TestReporterConfig::Dot => {
-      Box::new(DotTestReporter::new(options.cwd.clone()))
+      Box::new(DotTestReporter::new(options.cwd.clone(), shard))
     }
-    TestReporterConfig::Pretty => Box::new(PrettyTestReporter::new(
-      parallel,
-      options.log_level != Some(Level::Error),
-      options.filter,
-      false,
+    TestReporterConfig::Pretty => Box::new(
+      PrettyTestReporter::new(
+        parallel,
+        options.log_level != Some(Level::Error),
+        options.filter,
+        false,
+        options.cwd.clone(),
+      )
+      .with_shard(shard),
+    ),
+    TestReporterConfig::Junit => Box::new(JunitTestReporter::new(
       options.cwd.clone(),
+      "-".to_string(),
+      shard,
     )),
-    TestReporterConfig::Junit => {
-      Box::new(JunitTestReporter::new(options.cwd.clone(), "-".to_string()))
-    }
     TestReporterConfig::Tap => Box::new(TapTestReporter::new(
       options.cwd.clone(),
       options.concurrent_jobs > NonZeroUsize::new(1).unwrap(),
+      shard,
     )),
+    TestReporterConfig::Custom(module) => {
+      let cwd = options.cwd.to_file_path().unwrap();
+      Box::new(CustomTestReporter::new(
+        options.cwd.clone(),
+        &resolve_url_or_path(module, &cwd)?,
+        &options.reporter_args,
+      )?)
+    }
   };
 
   if let Some(junit_path) = &options.junit_path {
     let junit = Box::new(JunitTestReporter::new(
       options.cwd.clone(),
       junit_path.to_string(),
+      shard,
     ));
-    return Box::new(CompoundTestReporter::new(vec![reporter, junit]));
+    return Ok(Box::new(CompoundTestReporter::new(vec![reporter, junit])));
   }
 
-  reporter
+  Ok(reporter)
 }
 
 async fn configure_main_worker(
@@ -771,6 +878,11 @@ pub async fn run_tests_for_worker(
   let TestContainer(tests, test_functions) =
     std::mem::take(&mut *state_rc.borrow_mut().borrow_mut::<TestContainer>());
 
+  state_rc.borrow_mut().put(TestSnapshots::new(
+    specifier.clone(),
+    options.update_snapshots,
+  ));
+
   let tests: Arc<TestDescriptions> = tests.into();
   send_test_event(&state_rc, TestEvent::Register(tests.clone()))?;
   let res = run_tests_for_worker_inner(
@@ -783,6 +895,11 @@ pub async fn run_tests_for_worker(
   )
   .await;
 
+  // the snapshots are written even when a test failed, so that one run with
+  // `--update-snapshots` updates all of them
+  let snapshots = state_rc.borrow_mut().take::<TestSnapshots>();
+  let res = res.and(snapshots.write());
+
   _ = send_test_event(&state_rc, TestEvent::Completed);
   res
 }
@@ -805,6 +922,11 @@ async fn run_tests_for_worker_inner(
     if !options.filter.includes(&d.name) {
       continue;
     }
+    if let Some(shard) = &options.shard {
+      if !shard.includes(specifier, &d.name) {
+        continue;
+      }
+    }
 
     // If we've seen an "only: true" test, the remaining tests must be "only: true" to be added
     if used_only && !d.only {
@@ -902,128 +1024,122 @@ async fn run_tests_for_worker_inner(
     }
     send_test_event(&state_rc, TestEvent::Wait(desc.id))?;
 
-    // Poll event loop once, to allow all ops that are already resolved, but haven't
-    // responded to settle.
-    // TODO(mmastrac): we should provide an API to poll the event loop until no further
-    // progress is made.
-    poll_event_loop(worker).await?;
+    let retries = desc.retry.unwrap_or(options.retries);
+    let mut attempt = 0;
+    let (result, elapsed) = loop {
+      // Poll event loop once, to allow all ops that are already resolved, but haven't
+      // responded to settle.
+      // TODO(mmastrac): we should provide an API to poll the event loop until no further
+      // progress is made.
+      poll_event_loop(worker).await?;
+
+      // We always capture stats, regardless of sanitization state
+      let before = stats.clone().capture(&filter);
+
+      let earlier = Instant::now();
+      let call = worker.js_runtime.call(&function);
+
+      let slow_state_rc = state_rc.clone();
+      let slow_test_id = desc.id;
+      let slow_test_warning = spawn(async move {
+        // The slow test warning should pop up every DENO_SLOW_TEST_TIMEOUT*(2**n) seconds,
+        // with a duration that is doubling each time. So for a warning time of 60s,
+        // we should get a warning at 60s, 120s, 240s, etc.
+        let base_timeout =
+          env::var("DENO_SLOW_TEST_TIMEOUT").unwrap_or_default();
+        let base_timeout = base_timeout.parse().unwrap_or(60).max(1);
+        let mut multiplier = 1;
+        let mut elapsed = 0;
+        loop {
+          tokio::time::sleep(Duration::from_secs(
+            base_timeout * (multiplier - elapsed),
+          ))
+          .await;
+          if send_test_event(
+            &slow_state_rc,
+            TestEvent::Slow(
+              slow_test_id,
+              Duration::from_secs(base_timeout * multiplier).as_millis() as _,
+            ),
+          )
+          .is_err()
+          {
+            break;
+          }
+          multiplier *= 2;
+          elapsed += 1;
+        }
+      });
 
-    // We always capture stats, regardless of sanitization state
-    let before = stats.clone().capture(&filter);
-
-    let earlier = Instant::now();
-    let call = worker.js_runtime.call(&function);
-
-    let slow_state_rc = state_rc.clone();
-    let slow_test_id = desc.id;
-    let slow_test_warning = spawn(async move {
-      // The slow test warning should pop up every DENO_SLOW_TEST_TIMEOUT*(2**n) seconds,
-      // with a duration that is doubling each time. So for a warning time of 60s,
-      // we should get a warning at 60s, 120s, 240s, etc.
-      let base_timeout = env::var("DENO_SLOW_TEST_TIMEOUT").unwrap_or_default();
-      let base_timeout = base_timeout.parse().unwrap_or(60).max(1);
-      let mut multiplier = 1;
-      let mut elapsed = 0;
-      loop {
-        tokio::time::sleep(Duration::from_secs(
-          base_timeout * (multiplier - elapsed),
-        ))
+      let result = worker
+        .js_runtime
+        .with_event_loop_promise(call, PollEventLoopOptions::default())
         .await;
-        if send_test_event(
-          &slow_state_rc,
-          TestEvent::Slow(
-            slow_test_id,
-            Duration::from_secs(base_timeout * multiplier).as_millis() as _,
-          ),
+      slow_test_warning.abort();
+      let result = match result {
+        Ok(r) => r,
+        Err(error) => {
+          if error.is::<JsError>() {
+            send_test_event(
+              &state_rc,
+              TestEvent::UncaughtError(
+                specifier.to_string(),
+                Box::new(error.downcast::<JsError>().unwrap()),
+              ),
+            )?;
+            had_uncaught_error = true;
+            break (TestResult::Cancelled, 0);
+          } else {
+            return Err(error);
+          }
+        }
+      };
+
+      // Check the result before we check for leaks
+      let mut result = {
+        let scope = &mut worker.js_runtime.handle_scope();
+        let result = v8::Local::new(scope, result);
+        serde_v8::from_v8::<TestResult>(scope, result)?
+      };
+      if !matches!(result, TestResult::Failed(_)) {
+        // Await activity stabilization
+        if let Some(diff) = wait_for_activity_to_stabilize(
+          worker,
+          &stats,
+          &filter,
+          &top_level,
+          before,
+          desc.sanitize_ops,
+          desc.sanitize_resources,
         )
-        .is_err()
+        .await?
         {
-          break;
+          let (formatted, trailer_notes) = format_sanitizer_diff(diff);
+          if !formatted.is_empty() {
+            result =
+              TestResult::Failed(TestFailure::Leaked(formatted, trailer_notes));
+          }
         }
-        multiplier *= 2;
-        elapsed += 1;
       }
-    });
 
-    let result = worker
-      .js_runtime
-      .with_event_loop_promise(call, PollEventLoopOptions::default())
-      .await;
-    slow_test_warning.abort();
-    let result = match result {
-      Ok(r) => r,
-      Err(error) => {
-        if error.is::<JsError>() {
-          send_test_event(
-            &state_rc,
-            TestEvent::UncaughtError(
-              specifier.to_string(),
-              Box::new(error.downcast::<JsError>().unwrap()),
-            ),
-          )?;
-          fail_fast_tracker.add_failure();
+      let elapsed = earlier.elapsed().as_millis() as u64;
+      match result {
+        TestResult::Failed(failure) if attempt < retries => {
+          attempt += 1;
           send_test_event(
             &state_rc,
-            TestEvent::Result(desc.id, TestResult::Cancelled, 0),
+            TestEvent::Retry(desc.id, failure, elapsed),
           )?;
-          had_uncaught_error = true;
-          continue;
-        } else {
-          return Err(error);
         }
+        result => break (result, elapsed),
       }
     };
 
-    // Check the result before we check for leaks
-    let result = {
-      let scope = &mut worker.js_runtime.handle_scope();
-      let result = v8::Local::new(scope, result);
-      serde_v8::from_v8::<TestResult>(scope, result)?
-    };
-    if matches!(result, TestResult::Failed(_)) {
+    // only the last attempt counts towards `--fail-fast`
+    if matches!(result, TestResult::Failed(_) | TestResult::Cancelled) {
       fail_fast_tracker.add_failure();
-      let elapsed = earlier.elapsed().as_millis();
-      send_test_event(
-        &state_rc,
-        TestEvent::Result(desc.id, result, elapsed as u64),
-      )?;
-      continue;
-    }
-
-    // Await activity stabilization
-    if let Some(diff) = wait_for_activity_to_stabilize(
-      worker,
-      &stats,
-      &filter,
-      &top_level,
-      before,
-      desc.sanitize_ops,
-      desc.sanitize_resources,
-    )
-    .await?
-    {
-      let (formatted, trailer_notes) = format_sanitizer_diff(diff);
-      if !formatted.is_empty() {
-        let failure = TestFailure::Leaked(formatted, trailer_notes);
-        fail_fast_tracker.add_failure();
-        let elapsed = earlier.elapsed().as_millis();
-        send_test_event(
-          &state_rc,
-          TestEvent::Result(
-            desc.id,
-            TestResult::Failed(failure),
-            elapsed as u64,
-          ),
-        )?;
-        continue;
-      }
     }
-
-    let elapsed = earlier.elapsed().as_millis();
-    send_test_event(
-      &state_rc,
-      TestEvent::Result(desc.id, result, elapsed as u64),
-    )?;
+    send_test_event(&state_rc, TestEvent::Result(desc.id, result, elapsed))?;
   }
   Ok(())
 }
@@ -1387,17 +1503,98 @@ pub async fn check_specifiers(
 
 static HAS_TEST_RUN_SIGINT_HANDLER: AtomicBool = AtomicBool::new(false);
 
+/// The leading comment that declares the permissions of a test file for
+/// `--isolate`, ex. `// deno-test-permissions --allow-read=./fixtures`.
+const PERMISSIONS_HEADER: &str = "deno-test-permissions";
+
+/// Parses the permissions declared in the leading comments of a test file.
+/// A file without the header gets no permissions.
+fn parse_permissions_header(
+  text: &str,
+) -> Result<ChildPermissionsArg, AnyError> {
+  for line in text.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with("#!") {
+      continue;
+    }
+    let Some(comment) = line.strip_prefix("//") else {
+      break;
+    };
+    let Some(args) = comment.trim_start().strip_prefix(PERMISSIONS_HEADER)
+    else {
+      continue;
+    };
+    if !args.is_empty() && !args.starts_with(char::is_whitespace) {
+      continue;
+    }
+    let mut permissions = serde_json::Map::new();
+    for arg in args.split_whitespace() {
+      if arg == "-A" || arg == "--allow-all" {
+        return Ok(ChildPermissionsArg::inherit());
+      }
+      let Some(permission) = arg.strip_prefix("--allow-") else {
+        bail!("Expected --allow-* flags in the {PERMISSIONS_HEADER} header, found '{arg}'");
+      };
+      let (name, value) = match permission.split_once('=') {
+        Some((name, list)) => {
+          (name, json!(list.split(',').collect::<Vec<_>>()))
+        }
+        None => (permission, json!(true)),
+      };
+      permissions.insert(name.to_string(), value);
+    }
+    return serde_json::from_value(serde_json::Value::Object(permissions))
+      .map_err(|err| {
+        generic_error(format!("Invalid {PERMISSIONS_HEADER} header: {err}"))
+      });
+  }
+  Ok(ChildPermissionsArg::none())
+}
+
+/// Pairs each test file with its own copy of the permissions. With
+/// `--isolate` these are only the permissions declared in the header of the
+/// file, which can't exceed the permissions of the command.
+///
+/// The files still run in workers of this process, so `--isolate` is a
+/// permission boundary only: a file granted `--allow-ffi` or `--allow-run`
+/// can escape it.
+async fn resolve_test_permissions(
+  file_fetcher: &FileFetcher,
+  permissions: &Permissions,
+  specifiers: Vec<ModuleSpecifier>,
+  isolate: bool,
+) -> Result<Vec<(ModuleSpecifier, Permissions)>, AnyError> {
+  let mut specifiers_with_permissions = Vec::with_capacity(specifiers.len());
+  for specifier in specifiers {
+    let permissions = if isolate {
+      let file = file_fetcher
+        .fetch(&specifier, &PermissionsContainer::allow_all())
+        .await?
+        .into_text_decoded()?;
+      let child_permissions = parse_permissions_header(&file.source)
+        .with_context(|| format!("Failed to parse {specifier}"))?;
+      create_child_permissions(&mut permissions.clone(), child_permissions)
+        .with_context(|| {
+          format!("The permissions declared by {specifier} were not granted")
+        })?
+    } else {
+      permissions.clone()
+    };
+    specifiers_with_permissions.push((specifier, permissions));
+  }
+  Ok(specifiers_with_permissions)
+}
+
 /// Test a collection of specifiers with test modes concurrently.
 async fn test_specifiers(
   worker_factory: Arc<CliMainWorkerFactory>,
-  permissions: &Permissions,
-  specifiers: Vec<ModuleSpecifier>,
+  specifiers: Vec<(ModuleSpecifier, Permissions)>,
   options: TestSpecifiersOptions,
 ) -> Result<(), AnyError> {
   let specifiers = if let Some(seed) = options.specifier.shuffle {
     let mut rng = SmallRng::seed_from_u64(seed);
     let mut specifiers = specifiers;
-    specifiers.sort();
+    specifiers.sort_by(|(a, _), (b, _)| a.cmp(b));
     specifiers.shuffle(&mut rng);
     specifiers
   } else {
@@ -1413,26 +1610,26 @@ async fn test_specifiers(
     cancel_sender.send(TestEvent::Sigint).ok();
   });
   HAS_TEST_RUN_SIGINT_HANDLER.store(true, Ordering::Relaxed);
-  let reporter = get_test_reporter(&options);
+  let reporter = get_test_reporter(&options)?;
   let fail_fast_tracker = FailFastTracker::new(options.fail_fast);
 
-  let join_handles = specifiers.into_iter().map(move |specifier| {
-    let worker_factory = worker_factory.clone();
-    let permissions = permissions.clone();
-    let worker_sender = test_event_sender_factory.worker();
-    let fail_fast_tracker = fail_fast_tracker.clone();
-    let specifier_options = options.specifier.clone();
-    spawn_blocking(move || {
-      create_and_run_current_thread(test_specifier(
-        worker_factory,
-        permissions,
-        specifier,
-        worker_sender,
-        fail_fast_tracker,
-        specifier_options,
-      ))
-    })
-  });
+  let join_handles =
+    specifiers.into_iter().map(move |(specifier, permissions)| {
+      let worker_factory = worker_factory.clone();
+      let worker_sender = test_event_sender_factory.worker();
+      let fail_fast_tracker = fail_fast_tracker.clone();
+      let specifier_options = options.specifier.clone();
+      spawn_blocking(move || {
+        create_and_run_current_thread(test_specifier(
+          worker_factory,
+          permissions,
+          specifier,
+          worker_sender,
+          fail_fast_tracker,
+          specifier_options,
+        ))
+      })
+    });
 
   let join_stream = stream::iter(join_handles)
     .buffer_unordered(concurrent_jobs.get())
@@ -1495,6 +1692,14 @@ pub async fn report_tests(
       TestEvent::Slow(id, elapsed) => {
         reporter.report_slow(tests.get(&id).unwrap(), elapsed);
       }
+      TestEvent::Retry(id, failure, elapsed) => {
+        reporter.report_retry(
+          tests.get(&id).unwrap(),
+          &failure,
+          elapsed,
+          &test_steps,
+        );
+      }
       TestEvent::Result(id, result, elapsed) => {
         if tests_with_result.insert(id) {
           match result {
@@ -1530,6 +1735,9 @@ pub async fn report_tests(
           );
         }
       }
+      TestEvent::Attachment(id, attachment) => {
+        reporter.report_attachment(id, &attachment);
+      }
       TestEvent::ForceEndReport => {
         break;
       }
@@ -1740,6 +1948,8 @@ pub async fn run_tests(
   flags: Arc<Flags>,
   test_flags: TestFlags,
 ) -> Result<(), AnyError> {
+  let reporter_args =
+    [flags.to_permission_args(), flags.to_resolution_args()].concat();
   let factory = CliFactory::from_flags(flags);
   let cli_options = factory.cli_options()?;
   let workspace_test_options =
@@ -1782,8 +1992,15 @@ pub async fn run_tests(
   let worker_factory =
     Arc::new(factory.create_cli_main_worker_factory().await?);
 
-  test_specifiers(
-    worker_factory,
+  let cwd =
+    Url::from_directory_path(cli_options.initial_cwd()).map_err(|_| {
+      generic_error(format!(
+        "Unable to construct URL from the path of cwd: {}",
+        cli_options.initial_cwd().to_string_lossy(),
+      ))
+    })?;
+  let specifiers_with_permissions = resolve_test_permissions(
+    file_fetcher,
     &permissions,
     specifiers_with_mode
       .into_iter()
@@ -1792,24 +2009,30 @@ pub async fn run_tests(
         _ => Some(s),
       })
       .collect(),
+    workspace_test_options.isolate,
+  )
+  .await?;
+  test_specifiers(
+    worker_factory,
+    specifiers_with_permissions,
     TestSpecifiersOptions {
-      cwd: Url::from_directory_path(cli_options.initial_cwd()).map_err(
-        |_| {
-          generic_error(format!(
-            "Unable to construct URL from the path of cwd: {}",
-            cli_options.initial_cwd().to_string_lossy(),
-          ))
-        },
-      )?,
+      cwd: cwd.clone(),
       concurrent_jobs: workspace_test_options.concurrent_jobs,
       fail_fast: workspace_test_options.fail_fast,
       log_level,
-      filter: workspace_test_options.filter.is_some(),
+      filter: workspace_test_options.filter.is_some()
+        || workspace_test_options.shard.is_some(),
       reporter: workspace_test_options.reporter,
       junit_path: workspace_test_options.junit_path,
+      reporter_args,
       specifier: TestSpecifierOptions {
         filter: TestFilter::from_flag(&workspace_test_options.filter),
         shuffle: workspace_test_options.shuffle,
+        shard: workspace_test_options
+          .shard
+          .map(|shard| TestShardFilter { shard, cwd }),
+        retries: workspace_test_options.retries,
+        update_snapshots: workspace_test_options.update_snapshots,
         trace_leaks: workspace_test_options.trace_leaks,
       },
     },
@@ -1849,6 +2072,8 @@ pub async fn run_tests_with_watch(
     move |flags, watcher_communicator, changed_paths| {
       let test_flags = test_flags.clone();
       Ok(async move {
+        let reporter_args =
+          [flags.to_permission_args(), flags.to_resolution_args()].concat();
         let factory = CliFactory::from_flags_for_watcher(
           flags,
           watcher_communicator.clone(),
@@ -1948,8 +2173,16 @@ pub async fn run_tests_with_watch(
           return Ok(());
         }
 
-        test_specifiers(
-          worker_factory,
+        let cwd = Url::from_directory_path(cli_options.initial_cwd()).map_err(
+          |_| {
+            generic_error(format!(
+              "Unable to construct URL from the path of cwd: {}",
+              cli_options.initial_cwd().to_string_lossy(),
+            ))
+          },
+        )?;
+        let specifiers_with_permissions = resolve_test_permissions(
+          file_fetcher,
           &permissions,
           specifiers_with_mode
             .into_iter()
@@ -1958,24 +2191,30 @@ pub async fn run_tests_with_watch(
               _ => Some(s),
             })
             .collect(),
+          workspace_test_options.isolate,
+        )
+        .await?;
+        test_specifiers(
+          worker_factory,
+          specifiers_with_permissions,
           TestSpecifiersOptions {
-            cwd: Url::from_directory_path(cli_options.initial_cwd()).map_err(
-              |_| {
-                generic_error(format!(
-                  "Unable to construct URL from the path of cwd: {}",
-                  cli_options.initial_cwd().to_string_lossy(),
-                ))
-              },
-            )?,
+            cwd: cwd.clone(),
             concurrent_jobs: workspace_test_options.concurrent_jobs,
             fail_fast: workspace_test_options.fail_fast,
             log_level,
-            filter: workspace_test_options.filter.is_some(),
+            filter: workspace_test_options.filter.is_some()
+              || workspace_test_options.shard.is_some(),
             reporter: workspace_test_options.reporter,
             junit_path: workspace_test_options.junit_path,
+            reporter_args,
             specifier: TestSpecifierOptions {
               filter: TestFilter::from_flag(&workspace_test_options.filter),
               shuffle: workspace_test_options.shuffle,
+              shard: workspace_test_options
+                .shard
+                .map(|shard| TestShardFilter { shard, cwd }),
+              retries: workspace_test_options.retries,
+              update_snapshots: workspace_test_options.update_snapshots,
               trace_leaks: workspace_test_options.trace_leaks,
             },
           },
@@ -2101,4 +2340,81 @@ mod inner_test {
     assert!(!is_supported_test_path(Path::new("notatest.js")));
     assert!(!is_supported_test_path(Path::new("NotAtest.ts")));
   }
+
+  #[test]
+  fn test_shard_filter() {
+    let shard_filters = |cwd: &str| {
+      (1..=4)
+        .map(|index| TestShardFilter {
+          shard: TestShard {
+            index: NonZeroUsize::new(index).unwrap(),
+            count: NonZeroUsize::new(4).unwrap(),
+          },
+          cwd: Url::parse(cwd).unwrap(),
+        })
+        .collect::<Vec<_>>()
+    };
+    let ci_filters = shard_filters("file:///ci/checkout/");
+    let ci_specifier = Url::parse("file:///ci/checkout/a_test.ts").unwrap();
+    let local_filters = shard_filters("file:///home/user/project/");
+    let local_specifier =
+      Url::parse("file:///home/user/project/a_test.ts").unwrap();
+
+    let mut shard_sizes = [0; 4];
+    for i in 0..100 {
+      let name = format!("test {i}");
+      let shards = (0..4)
+        .filter(|shard| ci_filters[*shard].includes(&ci_specifier, &name))
+        .collect::<Vec<_>>();
+      assert_eq!(shards.len(), 1, "{name}");
+      // the same shard in a different checkout directory
+      assert!(local_filters[shards[0]].includes(&local_specifier, &name));
+      shard_sizes[shards[0]] += 1;
+    }
+    assert!(shard_sizes.iter().all(|size| *size > 0));
+  }
+
+  #[test]
+  fn test_parse_permissions_header() {
+    fn permissions(value: serde_json::Value) -> ChildPermissionsArg {
+      serde_json::from_value(value).unwrap()
+    }
+
+    assert_eq!(
+      parse_permissions_header("Deno.test(\"a\", () => {});").unwrap(),
+      ChildPermissionsArg::none()
+    );
+    assert_eq!(
+      parse_permissions_header(
+        "#!/usr/bin/env -S deno test\n\n// Copyright\n// deno-test-permissions --allow-read=./fixtures,./data --allow-net\n",
+      )
+      .unwrap(),
+      permissions(json!({ "read": ["./fixtures", "./data"], "net": true }))
+    );
+    assert_eq!(
+      parse_permissions_header("// deno-test-permissions -A").unwrap(),
+      ChildPermissionsArg::inherit()
+    );
+    // only the leading comments are the header
+    assert_eq!(
+      parse_permissions_header(
+        "import \"./a.ts\";\n// deno-test-permissions --allow-read\n"
+      )
+      .unwrap(),
+      ChildPermissionsArg::none()
+    );
+    assert_eq!(
+      parse_permissions_header("// deno-test-permissions-foo --allow-read")
+        .unwrap(),
+      ChildPermissionsArg::none()
+    );
+    assert!(parse_permissions_header("// deno-test-permissions read").is_err());
+    assert!(
+      parse_permissions_header("// deno-test-permissions --allow-foo").is_err()
+    );
+    assert!(parse_permissions_header(
+      "// deno-test-permissions --allow-hrtime=a"
+    )
+    .is_err());
+  }
 }
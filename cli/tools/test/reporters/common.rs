@@ -104,6 +104,7 @@ pub(super) fn report_summary(
   writer: &mut dyn std::io::Write,
   cwd: &Url,
   summary: &TestSummary,
+  shard: Option<TestShard>,
   elapsed: &Duration,
 ) {
   if !summary.failures.is_empty() || !summary.uncaught_errors.is_empty() {
@@ -168,6 +169,37 @@ pub(super) fn report_summary(
     }
   }
 
+  if !summary.flaky.is_empty() {
+    writeln!(writer, "\n{}\n", colors::yellow_bold("FLAKY")).unwrap();
+    for flaky_test in &summary.flaky {
+      writeln!(
+        writer,
+        "{}",
+        format_test_for_summary(cwd, &flaky_test.description)
+      )
+      .unwrap();
+      for (attempt, failures) in flaky_test.attempts.iter().enumerate() {
+        writeln!(
+          writer,
+          "{}",
+          colors::yellow_bold(format!("attempt {} failed", attempt + 1))
+        )
+        .unwrap();
+        for (description, failure) in failures {
+          if !failure.hide_in_summary() {
+            if description.id != flaky_test.description.id {
+              writeln!(writer, "{}", format_test_for_summary(cwd, description))
+                .unwrap();
+            }
+            writeln!(writer, "{}: {}", colors::red_bold("error"), failure)
+              .unwrap();
+          }
+        }
+      }
+      writeln!(writer).unwrap();
+    }
+  }
+
   let status = if summary.has_failed() {
     colors::red("FAILED").to_string()
   } else {
@@ -196,6 +228,10 @@ pub(super) fn report_summary(
   )
   .unwrap();
 
+  if !summary.flaky.is_empty() {
+    write!(summary_result, " | {} flaky", summary.flaky.len()).unwrap();
+  }
+
   let ignored_steps = get_steps_text(summary.ignored_steps);
   if summary.ignored > 0 || !ignored_steps.is_empty() {
     write!(
@@ -214,6 +250,10 @@ pub(super) fn report_summary(
     write!(summary_result, " | {} filtered out", summary.filtered_out).unwrap()
   };
 
+  if let Some(shard) = shard {
+    write!(summary_result, " | shard {shard}").unwrap();
+  }
+
   writeln!(
     writer,
     "\n{} | {} {}",
@@ -43,6 +43,24 @@ impl TestReporter for CompoundTestReporter {
     }
   }
 
+  fn report_attachment(&mut self, id: usize, attachment: &TestAttachment) {
+    for reporter in &mut self.test_reporters {
+      reporter.report_attachment(id, attachment);
+    }
+  }
+
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    failure: &TestFailure,
+    elapsed: u64,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    for reporter in &mut self.test_reporters {
+      reporter.report_retry(description, failure, elapsed, test_steps);
+    }
+  }
+
   fn report_result(
     &mut self,
     description: &TestDescription,
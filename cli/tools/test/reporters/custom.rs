@@ -0,0 +1,360 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::Command;
+use std::process::Stdio;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use deno_core::anyhow::Context;
+use deno_core::serde_json;
+use serde::Serialize;
+
+use super::fmt::format_test_error;
+use super::fmt::to_relative_path_or_remote_url;
+use super::*;
+
+/// Calls the default export of the reporter module with the events, which
+/// it reads as JSON lines from stdin.
+const HOST_SOURCE: &str = include_str!("custom_host.js");
+
+/// A reporter that streams the test events to a user provided module, see
+/// `deno test --reporter=./reporter.ts`. The module runs in a subprocess, so
+/// that it can't interfere with the tests.
+pub struct CustomTestReporter {
+  cwd: Url,
+  child: Child,
+  stdin: Option<ChildStdin>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReporterLocation {
+  file: String,
+  line: u32,
+  column: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReporterTest<'a> {
+  id: usize,
+  name: &'a str,
+  origin: String,
+  location: ReporterLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReporterStep<'a> {
+  id: usize,
+  name: &'a str,
+  parent_id: usize,
+  root_id: usize,
+  level: usize,
+  location: ReporterLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReporterError {
+  message: String,
+  detail: String,
+}
+
+impl From<&TestFailure> for ReporterError {
+  fn from(failure: &TestFailure) -> Self {
+    Self {
+      message: failure.overview(),
+      detail: failure.detail(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ReporterStatus {
+  Passed,
+  Ignored,
+  Failed,
+  Cancelled,
+}
+
+/// Keep in sync with `Deno.TestReporterEvent` in `lib.deno.ns.d.ts`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ReporterEvent<'a> {
+  #[serde(rename_all = "camelCase")]
+  Plan {
+    origin: String,
+    total: usize,
+    filtered_out: usize,
+    used_only: bool,
+  },
+  TestStart {
+    test: ReporterTest<'a>,
+  },
+  Output {
+    text: String,
+  },
+  #[serde(rename_all = "camelCase")]
+  Attachment {
+    test_id: usize,
+    name: &'a str,
+    content_type: &'a str,
+    /// Base64 encoded, decoded by the reporter host.
+    data: String,
+  },
+  TestRetry {
+    test: ReporterTest<'a>,
+    error: ReporterError,
+    duration: u64,
+  },
+  TestResult {
+    test: ReporterTest<'a>,
+    status: ReporterStatus,
+    duration: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ReporterError>,
+  },
+  UncaughtError {
+    origin: String,
+    error: ReporterError,
+  },
+  StepStart {
+    step: ReporterStep<'a>,
+  },
+  StepResult {
+    step: ReporterStep<'a>,
+    status: ReporterStatus,
+    duration: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ReporterError>,
+  },
+  Interrupted {
+    pending: Vec<usize>,
+  },
+  End {
+    duration: u64,
+  },
+}
+
+impl CustomTestReporter {
+  pub fn new(
+    cwd: Url,
+    module: &ModuleSpecifier,
+    reporter_args: &[String],
+  ) -> Result<Self, AnyError> {
+    let source = format!(
+      "import reporter from {};\n{HOST_SOURCE}",
+      serde_json::to_string(module.as_str())?
+    );
+    let mut child = Command::new(std::env::current_exe()?)
+      .arg("run")
+      .arg("--no-prompt")
+      .args(reporter_args)
+      .arg(format!(
+        "data:application/javascript;base64,{}",
+        BASE64_STANDARD.encode(source)
+      ))
+      .stdin(Stdio::piped())
+      .spawn()
+      .with_context(|| format!("Failed to start test reporter {module}"))?;
+    let stdin = child.stdin.take();
+    Ok(Self { cwd, child, stdin })
+  }
+
+  fn send(&mut self, event: ReporterEvent) {
+    let Some(stdin) = &mut self.stdin else {
+      return;
+    };
+    let mut line = serde_json::to_vec(&event).unwrap();
+    line.push(b'\n');
+    // the reporter may stop reading early, its exit code tells what happened
+    if stdin.write_all(&line).is_err() {
+      self.stdin = None;
+    }
+  }
+
+  fn location(&self, location: &TestLocation) -> ReporterLocation {
+    ReporterLocation {
+      file: to_relative_path_or_remote_url(&self.cwd, &location.file_name),
+      line: location.line_number,
+      column: location.column_number,
+    }
+  }
+
+  fn test<'a>(&self, description: &'a TestDescription) -> ReporterTest<'a> {
+    ReporterTest {
+      id: description.id,
+      name: &description.name,
+      origin: to_relative_path_or_remote_url(&self.cwd, &description.origin),
+      location: self.location(&description.location),
+    }
+  }
+
+  fn step<'a>(&self, description: &'a TestStepDescription) -> ReporterStep<'a> {
+    ReporterStep {
+      id: description.id,
+      name: &description.name,
+      parent_id: description.parent_id,
+      root_id: description.root_id,
+      level: description.level,
+      location: self.location(&description.location),
+    }
+  }
+}
+
+impl TestReporter for CustomTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.send(ReporterEvent::Plan {
+      origin: to_relative_path_or_remote_url(&self.cwd, &plan.origin),
+      total: plan.total,
+      filtered_out: plan.filtered_out,
+      used_only: plan.used_only,
+    });
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    let test = self.test(description);
+    self.send(ReporterEvent::TestStart { test });
+  }
+
+  fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
+
+  fn report_output(&mut self, output: &[u8]) {
+    self.send(ReporterEvent::Output {
+      text: String::from_utf8_lossy(output).into_owned(),
+    });
+  }
+
+  fn report_attachment(&mut self, id: usize, attachment: &TestAttachment) {
+    self.send(ReporterEvent::Attachment {
+      test_id: id,
+      name: &attachment.name,
+      content_type: &attachment.content_type,
+      data: BASE64_STANDARD.encode(&attachment.data),
+    });
+  }
+
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    failure: &TestFailure,
+    elapsed: u64,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let test = self.test(description);
+    self.send(ReporterEvent::TestRetry {
+      test,
+      error: failure.into(),
+      duration: elapsed,
+    });
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let (status, error) = match result {
+      TestResult::Ok => (ReporterStatus::Passed, None),
+      TestResult::Ignored => (ReporterStatus::Ignored, None),
+      TestResult::Failed(failure) => {
+        (ReporterStatus::Failed, Some(failure.into()))
+      }
+      TestResult::Cancelled => (ReporterStatus::Cancelled, None),
+    };
+    let test = self.test(description);
+    self.send(ReporterEvent::TestResult {
+      test,
+      status,
+      duration: elapsed,
+      error,
+    });
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
+    self.send(ReporterEvent::UncaughtError {
+      origin: to_relative_path_or_remote_url(&self.cwd, origin),
+      error: ReporterError {
+        message: error.exception_message.clone(),
+        detail: format_test_error(&error),
+      },
+    });
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    let step = self.step(description);
+    self.send(ReporterEvent::StepStart { step });
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let (status, error) = match result {
+      TestStepResult::Ok => (ReporterStatus::Passed, None),
+      TestStepResult::Ignored => (ReporterStatus::Ignored, None),
+      TestStepResult::Failed(failure) => {
+        (ReporterStatus::Failed, Some(failure.into()))
+      }
+    };
+    let step = self.step(description);
+    self.send(ReporterEvent::StepResult {
+      step,
+      status,
+      duration: elapsed,
+      error,
+    });
+  }
+
+  fn report_summary(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+  }
+
+  fn report_sigint(
+    &mut self,
+    tests_pending: &HashSet<usize>,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let mut pending = tests_pending.iter().copied().collect::<Vec<_>>();
+    pending.sort();
+    self.send(ReporterEvent::Interrupted { pending });
+  }
+
+  fn report_completed(&mut self) {}
+
+  fn flush_report(
+    &mut self,
+    elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> anyhow::Result<()> {
+    self.send(ReporterEvent::End {
+      duration: elapsed.as_millis() as u64,
+    });
+    // closing stdin ends the events of the reporter
+    self.stdin = None;
+    let status = self.child.wait()?;
+    if !status.success() {
+      bail!("Test reporter exited with {status}");
+    }
+    Ok(())
+  }
+}
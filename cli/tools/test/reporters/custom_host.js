@@ -0,0 +1,38 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+// The `reporter` import is prepended by `custom.rs`.
+
+function parseEvent(line) {
+  const event = JSON.parse(line);
+  if (event.type === "attachment") {
+    const binary = atob(event.data);
+    const data = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+      data[i] = binary.charCodeAt(i);
+    }
+    event.data = data;
+  }
+  return event;
+}
+
+async function* readEvents() {
+  const decoder = new TextDecoder();
+  let buffer = "";
+  for await (const chunk of Deno.stdin.readable) {
+    buffer += decoder.decode(chunk, { stream: true });
+    const lines = buffer.split("\n");
+    buffer = lines.pop();
+    for (const line of lines) {
+      if (line) {
+        yield parseEvent(line);
+      }
+    }
+  }
+}
+
+if (typeof reporter !== "function") {
+  throw new TypeError(
+    "The default export of a test reporter module must be a function",
+  );
+}
+await reporter(readEvents());
@@ -8,12 +8,16 @@ pub struct DotTestReporter {
   n: usize,
   width: usize,
   cwd: Url,
+  shard: Option<TestShard>,
+  /// The failed attempts of tests that are being retried.
+  attempt_failures:
+    HashMap<usize, Vec<Vec<(TestFailureDescription, TestFailure)>>>,
   summary: TestSummary,
 }
 
 #[allow(clippy::print_stdout)]
 impl DotTestReporter {
-  pub fn new(cwd: Url) -> DotTestReporter {
+  pub fn new(cwd: Url, shard: Option<TestShard>) -> DotTestReporter {
     let console_width = if let Some(size) = crate::util::console::console_size()
     {
       size.cols as usize
@@ -25,6 +29,8 @@ impl DotTestReporter {
       n: 0,
       width: console_width,
       cwd,
+      shard,
+      attempt_failures: Default::default(),
       summary: TestSummary::new(),
     }
   }
@@ -98,15 +104,41 @@ impl TestReporter for DotTestReporter {
   fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
   fn report_output(&mut self, _output: &[u8]) {}
 
+  fn report_attachment(&mut self, _id: usize, _attachment: &TestAttachment) {}
+
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    failure: &TestFailure,
+    _elapsed: u64,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let mut failures =
+      self.summary.take_step_failures(description.id, test_steps);
+    failures.insert(0, (description.into(), failure.clone()));
+    self
+      .attempt_failures
+      .entry(description.id)
+      .or_default()
+      .push(failures);
+  }
+
   fn report_result(
     &mut self,
     description: &TestDescription,
     result: &TestResult,
     _elapsed: u64,
   ) {
+    let attempt_failures = self.attempt_failures.remove(&description.id);
     match &result {
       TestResult::Ok => {
         self.summary.passed += 1;
+        if let Some(attempts) = attempt_failures {
+          self.summary.flaky.push(FlakyTest {
+            description: description.into(),
+            attempts,
+          });
+        }
       }
       TestResult::Ignored => {
         self.summary.ignored += 1;
@@ -189,6 +221,7 @@ impl TestReporter for DotTestReporter {
       &mut std::io::stdout(),
       &self.cwd,
       &self.summary,
+      self.shard,
       elapsed,
     );
     println!();
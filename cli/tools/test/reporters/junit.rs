@@ -9,8 +9,11 @@ use super::*;
 pub struct JunitTestReporter {
   cwd: Url,
   output_path: String,
+  shard: Option<TestShard>,
   // Stores TestCases (i.e. Tests) by the Test ID
   cases: IndexMap<usize, quick_junit::TestCase>,
+  // Stores the failed attempts of tests that are being retried by the Test ID
+  reruns: HashMap<usize, Vec<quick_junit::TestRerun>>,
   // Stores nodes representing test cases in such a way that can be traversed
   // from child to parent to build the full test name that reflects the test
   // hierarchy.
@@ -18,11 +21,13 @@ pub struct JunitTestReporter {
 }
 
 impl JunitTestReporter {
-  pub fn new(cwd: Url, output_path: String) -> Self {
+  pub fn new(cwd: Url, output_path: String, shard: Option<TestShard>) -> Self {
     Self {
       cwd,
       output_path,
+      shard,
       cases: IndexMap::new(),
+      reruns: HashMap::new(),
       test_name_tree: TestNameTree::new(),
     }
   }
@@ -104,14 +109,51 @@ impl TestReporter for JunitTestReporter {
     */
   }
 
+  fn report_attachment(&mut self, _id: usize, _attachment: &TestAttachment) {}
+
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    failure: &TestFailure,
+    elapsed: u64,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    // only the steps of the last attempt are reported
+    self.cases.retain(|id, _| {
+      !test_steps
+        .get(id)
+        .is_some_and(|step| step.root_id == description.id)
+    });
+    let mut rerun =
+      quick_junit::TestRerun::new(quick_junit::NonSuccessKind::Failure);
+    rerun
+      .set_message(failure.overview())
+      .set_description(failure.detail())
+      .set_time(Duration::from_millis(elapsed));
+    self.reruns.entry(description.id).or_default().push(rerun);
+  }
+
   fn report_result(
     &mut self,
     description: &TestDescription,
     result: &TestResult,
     elapsed: u64,
   ) {
+    let reruns = self.reruns.remove(&description.id).unwrap_or_default();
     if let Some(case) = self.cases.get_mut(&description.id) {
       case.status = Self::convert_status(result);
+      match &mut case.status {
+        quick_junit::TestCaseStatus::Success { flaky_runs } => {
+          *flaky_runs = reruns;
+        }
+        quick_junit::TestCaseStatus::NonSuccess {
+          reruns: case_reruns,
+          ..
+        } => {
+          *case_reruns = reruns;
+        }
+        quick_junit::TestCaseStatus::Skipped { .. } => {}
+      }
       case.set_time(Duration::from_millis(elapsed));
     }
   }
@@ -214,7 +256,12 @@ impl TestReporter for JunitTestReporter {
         });
     }
 
-    let mut report = quick_junit::Report::new("deno test");
+    let mut report = match self.shard {
+      Some(shard) => {
+        quick_junit::Report::new(format!("deno test (shard {shard})"))
+      }
+      None => quick_junit::Report::new("deno test"),
+    };
     report
       .set_time(*elapsed)
       .add_test_suites(suites.into_values());
@@ -4,12 +4,14 @@ use super::*;
 
 mod common;
 mod compound;
+mod custom;
 mod dot;
 mod junit;
 mod pretty;
 mod tap;
 
 pub use compound::CompoundTestReporter;
+pub use custom::CustomTestReporter;
 pub use dot::DotTestReporter;
 pub use junit::JunitTestReporter;
 pub use pretty::PrettyTestReporter;
@@ -21,6 +23,14 @@ pub trait TestReporter {
   fn report_wait(&mut self, description: &TestDescription);
   fn report_slow(&mut self, description: &TestDescription, elapsed: u64);
   fn report_output(&mut self, output: &[u8]);
+  fn report_attachment(&mut self, id: usize, attachment: &TestAttachment);
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    failure: &TestFailure,
+    elapsed: u64,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  );
   fn report_result(
     &mut self,
     description: &TestDescription,
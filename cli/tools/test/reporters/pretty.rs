@@ -18,7 +18,11 @@ pub struct PrettyTestReporter {
   ended_tests: bool,
   child_results_buffer:
     HashMap<usize, IndexMap<usize, (TestStepDescription, TestStepResult, u64)>>,
+  /// The failed attempts of tests that are being retried.
+  attempt_failures:
+    HashMap<usize, Vec<Vec<(TestFailureDescription, TestFailure)>>>,
   summary: TestSummary,
+  shard: Option<TestShard>,
   writer: Box<dyn std::io::Write>,
 }
 
@@ -43,7 +47,9 @@ impl PrettyTestReporter {
       started_tests: false,
       ended_tests: false,
       child_results_buffer: Default::default(),
+      attempt_failures: Default::default(),
       summary: TestSummary::new(),
+      shard: None,
       writer: Box::new(std::io::stdout()),
     }
   }
@@ -52,6 +58,10 @@ impl PrettyTestReporter {
     Self { writer, ..self }
   }
 
+  pub fn with_shard(self, shard: Option<TestShard>) -> Self {
+    Self { shard, ..self }
+  }
+
   fn force_report_wait(&mut self, description: &TestDescription) {
     if !self.in_new_line {
       writeln!(&mut self.writer).unwrap();
@@ -240,15 +250,67 @@ impl TestReporter for PrettyTestReporter {
     std::io::stdout().write_all(output).unwrap();
   }
 
+  fn report_attachment(&mut self, _id: usize, _attachment: &TestAttachment) {}
+
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    failure: &TestFailure,
+    elapsed: u64,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let mut failures =
+      self.summary.take_step_failures(description.id, test_steps);
+    failures.insert(0, (description.into(), failure.clone()));
+    let attempt_failures =
+      self.attempt_failures.entry(description.id).or_default();
+    attempt_failures.push(failures);
+    let next_attempt = attempt_failures.len() + 1;
+
+    if self.parallel {
+      self.force_report_wait(description);
+    }
+
+    self.write_output_end();
+    if self.in_new_line || self.scope_test_id != Some(description.id) {
+      self.force_report_wait(description);
+    }
+
+    write!(&mut self.writer, " {}", failure.format_label()).unwrap();
+    if let Some(inline_summary) = failure.format_inline_summary() {
+      write!(&mut self.writer, " ({})", inline_summary).unwrap();
+    }
+    writeln!(
+      &mut self.writer,
+      " {} {}",
+      colors::gray(format!("({})", display::human_elapsed(elapsed.into()))),
+      colors::yellow(format!("retrying, attempt {next_attempt}"))
+    )
+    .unwrap();
+    self.in_new_line = true;
+    self.scope_test_id = None;
+    if !self.parallel {
+      self.force_report_wait(description);
+    }
+  }
+
   fn report_result(
     &mut self,
     description: &TestDescription,
     result: &TestResult,
     elapsed: u64,
   ) {
+    let attempt_failures = self.attempt_failures.remove(&description.id);
+    let flaky = attempt_failures.is_some();
     match &result {
       TestResult::Ok => {
         self.summary.passed += 1;
+        if let Some(attempts) = attempt_failures {
+          self.summary.flaky.push(FlakyTest {
+            description: description.into(),
+            attempts,
+          });
+        }
       }
       TestResult::Ignored => {
         self.summary.ignored += 1;
@@ -275,6 +337,9 @@ impl TestReporter for PrettyTestReporter {
     }
 
     let status = match result {
+      TestResult::Ok if flaky => {
+        format!("{} {}", colors::green("ok"), colors::yellow("(flaky)"))
+      }
       TestResult::Ok => colors::green("ok").to_string(),
       TestResult::Ignored => colors::yellow("ignored").to_string(),
       TestResult::Failed(failure) => failure.format_label(),
@@ -395,7 +460,13 @@ impl TestReporter for PrettyTestReporter {
     _test_steps: &IndexMap<usize, TestStepDescription>,
   ) {
     self.write_output_end();
-    common::report_summary(&mut self.writer, &self.cwd, &self.summary, elapsed);
+    common::report_summary(
+      &mut self.writer,
+      &self.cwd,
+      &self.summary,
+      self.shard,
+      elapsed,
+    );
     if !self.repl {
       writeln!(&mut self.writer).unwrap();
     }
@@ -15,6 +15,7 @@ const VERSION_HEADER: &str = "TAP version 14";
 pub struct TapTestReporter {
   cwd: Url,
   is_concurrent: bool,
+  shard: Option<TestShard>,
   header: bool,
   planned: usize,
   n: usize,
@@ -24,10 +25,15 @@ pub struct TapTestReporter {
 
 #[allow(clippy::print_stdout)]
 impl TapTestReporter {
-  pub fn new(cwd: Url, is_concurrent: bool) -> TapTestReporter {
+  pub fn new(
+    cwd: Url,
+    is_concurrent: bool,
+    shard: Option<TestShard>,
+  ) -> TapTestReporter {
     TapTestReporter {
       cwd,
       is_concurrent,
+      shard,
       header: false,
       planned: 0,
       n: 0,
@@ -121,6 +127,9 @@ impl TestReporter for TapTestReporter {
   fn report_plan(&mut self, plan: &TestPlan) {
     if !self.header {
       println!("{}", VERSION_HEADER);
+      if let Some(shard) = self.shard {
+        println!("# shard {shard}");
+      }
       self.header = true;
     }
     self.planned += plan.total;
@@ -143,6 +152,18 @@ impl TestReporter for TapTestReporter {
   fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
   fn report_output(&mut self, _output: &[u8]) {}
 
+  fn report_attachment(&mut self, _id: usize, _attachment: &TestAttachment) {}
+
+  fn report_retry(
+    &mut self,
+    description: &TestDescription,
+    _failure: &TestFailure,
+    _elapsed: u64,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    println!("# {} failed, retrying", description.name);
+  }
+
   fn report_result(
     &mut self,
     description: &TestDescription,
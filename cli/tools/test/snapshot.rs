@@ -0,0 +1,342 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! The snapshots of `t.assertSnapshot()`, which are stored in
+//! `__snapshots__/<test file>.snap` next to the test file, and of
+//! `t.assertInlineSnapshot()`, which are stored in the test file itself.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+use deno_ast::TextChange;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+
+use super::TestLocation;
+use crate::util::diff::diff;
+
+pub struct TestSnapshots {
+  specifier: ModuleSpecifier,
+  /// Whether missing and mismatching snapshots are written instead of failing
+  /// the assertion, see `--update-snapshots`.
+  update: bool,
+  /// Loaded on the first `t.assertSnapshot()`.
+  entries: Option<BTreeMap<String, String>>,
+  changed: bool,
+  /// Inline snapshots to write, by file and by the line and column of the
+  /// `assertInlineSnapshot` call.
+  inline_updates: HashMap<String, HashMap<(usize, usize), String>>,
+}
+
+impl TestSnapshots {
+  pub fn new(specifier: ModuleSpecifier, update: bool) -> Self {
+    Self {
+      specifier,
+      update,
+      entries: None,
+      changed: false,
+      inline_updates: HashMap::new(),
+    }
+  }
+
+  fn snapshot_file_path(&self) -> Result<PathBuf, AnyError> {
+    let Ok(path) = self.specifier.to_file_path() else {
+      bail!("Snapshots are only supported for local test files");
+    };
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    Ok(
+      path
+        .parent()
+        .unwrap()
+        .join("__snapshots__")
+        .join(format!("{file_name}.snap")),
+    )
+  }
+
+  /// Returns the reason of the failure when `actual` doesn't match the
+  /// snapshot `name`.
+  pub fn assert(
+    &mut self,
+    name: String,
+    actual: String,
+  ) -> Result<Option<String>, AnyError> {
+    if self.entries.is_none() {
+      let path = self.snapshot_file_path()?;
+      let entries = match std::fs::read_to_string(&path) {
+        Ok(text) => parse_snapshot_file(&text).with_context(|| {
+          format!("Invalid snapshot file {}", path.display())
+        })?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+          BTreeMap::new()
+        }
+        Err(err) => return Err(err.into()),
+      };
+      self.entries = Some(entries);
+    }
+    let entries = self.entries.as_mut().unwrap();
+
+    match entries.get(&name) {
+      Some(expected) if *expected == actual => Ok(None),
+      _ if self.update => {
+        entries.insert(name, actual);
+        self.changed = true;
+        Ok(None)
+      }
+      Some(expected) => Ok(Some(format!(
+        "Snapshot \"{name}\" does not match:\n\n{}\nRun again with --update-snapshots to update it.",
+        diff(expected, &actual)
+      ))),
+      None => Ok(Some(format!(
+        "Missing snapshot \"{name}\". Run again with --update-snapshots to create it."
+      ))),
+    }
+  }
+
+  /// Returns the reason of the failure when `actual` doesn't match the inline
+  /// snapshot `expected` of the call at `location`.
+  pub fn assert_inline(
+    &mut self,
+    actual: String,
+    expected: Option<String>,
+    location: TestLocation,
+  ) -> Option<String> {
+    match expected {
+      Some(expected) if expected == actual => None,
+      _ if self.update => {
+        self.inline_updates.entry(location.file_name).or_default().insert(
+          (location.line_number as usize, location.column_number as usize),
+          actual,
+        );
+        None
+      }
+      Some(expected) => Some(format!(
+        "Inline snapshot does not match:\n\n{}\nRun again with --update-snapshots to update it.",
+        diff(&expected, &actual)
+      )),
+      None => Some(
+        "Missing inline snapshot. Run again with --update-snapshots to create it."
+          .to_string(),
+      ),
+    }
+  }
+
+  /// Writes the snapshots that were created or updated.
+  pub fn write(self) -> Result<(), AnyError> {
+    if self.changed {
+      let path = self.snapshot_file_path()?;
+      std::fs::create_dir_all(path.parent().unwrap())?;
+      std::fs::write(&path, format_snapshot_file(&self.entries.unwrap()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    for (file_name, snapshots) in self.inline_updates {
+      let specifier = ModuleSpecifier::parse(&file_name)?;
+      let Ok(path) = specifier.to_file_path() else {
+        bail!("Inline snapshots are only supported in local files");
+      };
+      let text = std::fs::read_to_string(&path)?;
+      let text = update_inline_snapshots(specifier, text, snapshots)?;
+      std::fs::write(&path, text)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+  }
+}
+
+/// Escapes `text` for a template literal.
+fn escape_template(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('`', "\\`")
+    .replace("${", "\\${")
+}
+
+/// Reads the template literal at the start of `text`, without its opening
+/// backtick, and returns its content and the text after it.
+fn read_template(text: &str) -> Result<(String, &str), AnyError> {
+  let mut content = String::new();
+  let mut chars = text.char_indices();
+  while let Some((index, char)) = chars.next() {
+    match char {
+      '\\' => match chars.next() {
+        Some((_, escaped)) => content.push(escaped),
+        None => break,
+      },
+      '`' => return Ok((content, &text[index + 1..])),
+      char => content.push(char),
+    }
+  }
+  bail!("Unterminated template literal")
+}
+
+fn parse_snapshot_file(
+  text: &str,
+) -> Result<BTreeMap<String, String>, AnyError> {
+  let mut entries = BTreeMap::new();
+  let mut rest = text;
+  while let Some(start) = rest.find("snapshot[`") {
+    let (name, after_name) = read_template(&rest[start + 10..])?;
+    let Some(after_name) = after_name.strip_prefix("] = `") else {
+      bail!("Expected a template literal after snapshot[`{name}`]");
+    };
+    let (value, after_value) = read_template(after_name)?;
+    // the value is on its own lines, for readable diffs of snapshot files
+    let value = value
+      .strip_prefix('\n')
+      .and_then(|value| value.strip_suffix('\n'))
+      .unwrap_or(value.as_str());
+    entries.insert(name, value.to_string());
+    rest = after_value;
+  }
+  Ok(entries)
+}
+
+fn format_snapshot_file(entries: &BTreeMap<String, String>) -> String {
+  let mut text = "export const snapshot = {};\n".to_string();
+  for (name, value) in entries {
+    write!(
+      text,
+      "\nsnapshot[`{}`] = `\n{}\n`;\n",
+      escape_template(name),
+      escape_template(value)
+    )
+    .unwrap();
+  }
+  text
+}
+
+/// Sets the second argument of the `assertInlineSnapshot` calls at the lines
+/// and columns of `snapshots`.
+fn update_inline_snapshots(
+  specifier: ModuleSpecifier,
+  text: String,
+  mut snapshots: HashMap<(usize, usize), String>,
+) -> Result<String, AnyError> {
+  let media_type = MediaType::from_specifier(&specifier);
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier,
+    text: text.into(),
+    media_type,
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  })?;
+  let text_info = parsed_source.text_info_lazy();
+  let mut collector = InlineSnapshotCollector {
+    text_info,
+    snapshots: &mut snapshots,
+    changes: Vec::new(),
+  };
+  parsed_source.module().visit_with(&mut collector);
+  let changes = collector.changes;
+  Ok(deno_ast::apply_text_changes(text_info.text_str(), changes))
+}
+
+struct InlineSnapshotCollector<'a> {
+  text_info: &'a SourceTextInfo,
+  snapshots: &'a mut HashMap<(usize, usize), String>,
+  changes: Vec<TextChange>,
+}
+
+impl Visit for InlineSnapshotCollector<'_> {
+  fn visit_call_expr(&mut self, call_expr: &ast::CallExpr) {
+    call_expr.visit_children_with(self);
+
+    // the location of a call is the one of the called property
+    let ast::Callee::Expr(callee) = &call_expr.callee else {
+      return;
+    };
+    let ast::Expr::Member(member_expr) = &**callee else {
+      return;
+    };
+    let ast::MemberProp::Ident(prop) = &member_expr.prop else {
+      return;
+    };
+    let position = self.text_info.line_and_column_display(prop.start());
+    let Some(snapshot) = self
+      .snapshots
+      .remove(&(position.line_number, position.column_number))
+    else {
+      return;
+    };
+
+    let file_start = self.text_info.range().start;
+    let snapshot = format!("`{}`", escape_template(&snapshot));
+    match call_expr.args.as_slice() {
+      [actual] => {
+        let end = actual.expr.end().as_byte_index(file_start);
+        self.changes.push(TextChange {
+          range: end..end,
+          new_text: format!(", {snapshot}"),
+        });
+      }
+      [_, expected, ..] => self.changes.push(TextChange {
+        range: expected.expr.range().as_byte_range(file_start),
+        new_text: snapshot,
+      }),
+      [] => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn snapshot_file_roundtrip() {
+    let entries = BTreeMap::from([
+      ("a 1".to_string(), "{\n  a: 1,\n}".to_string()),
+      ("b `c` ${d} 1".to_string(), "\"\\n\"".to_string()),
+    ]);
+    let text = format_snapshot_file(&entries);
+    assert_eq!(
+      text,
+      r#"export const snapshot = {};
+
+snapshot[`a 1`] = `
+{
+  a: 1,
+}
+`;
+
+snapshot[`b \`c\` \${d} 1`] = `
+"\\n"
+`;
+"#
+    );
+    assert_eq!(parse_snapshot_file(&text).unwrap(), entries);
+    assert!(parse_snapshot_file("snapshot[`a 1`] = `\n1").is_err());
+  }
+
+  #[test]
+  fn updates_inline_snapshots() {
+    let text = r#"Deno.test("a", (t) => {
+  t.assertInlineSnapshot(1);
+  t.assertInlineSnapshot("`", `2`);
+});
+"#;
+    let text = update_inline_snapshots(
+      ModuleSpecifier::parse("file:///a_test.ts").unwrap(),
+      text.to_string(),
+      HashMap::from([((2, 5), "1".to_string()), ((3, 5), "\"`\"".to_string())]),
+    )
+    .unwrap();
+    assert_eq!(
+      text,
+      r#"Deno.test("a", (t) => {
+  t.assertInlineSnapshot(1, `1`);
+  t.assertInlineSnapshot("`", `"\`"`);
+});
+"#
+    );
+  }
+}
@@ -35,21 +35,29 @@ delete Object.prototype.__proto__;
     "CreateHttpClientOptions",
     "DatagramConn",
     "HttpClient",
+    "IcmpSocket",
     "Kv",
     "KvListIterator",
     "KvU64",
+    "RetryPolicy",
     "UnsafeCallback",
     "UnsafePointer",
     "UnsafePointerView",
     "UnsafeFnPointer",
     "UnixConnectOptions",
     "UnixListenOptions",
+    "UnixPeerCredentials",
+    "VsockConn",
+    "VsockListener",
+    "VsockOptions",
+    "createCapabilityToken",
     "createHttpClient",
     "dlopen",
     "flock",
     "flockSync",
     "funlock",
     "funlockSync",
+    "icmpSocket",
     "listen",
     "listenDatagram",
     "openKv",
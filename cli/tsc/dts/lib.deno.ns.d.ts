@@ -762,6 +762,59 @@ declare namespace Deno {
      * ```
      */
     step(fn: (t: TestContext) => void | Promise<void>): Promise<boolean>;
+
+    /** Assert that `actual` matches the snapshot stored in
+     * `__snapshots__/<test file>.snap`, next to the test file. Snapshots are
+     * keyed by the name of the test and by the number of the assertion within
+     * it.
+     *
+     * Missing and mismatching snapshots fail the assertion, unless the tests
+     * are run with `--update-snapshots`, which writes them instead.
+     *
+     * ```ts
+     * Deno.test("a test", (t) => {
+     *   t.assertSnapshot({ a: 1, b: [2, 3] });
+     * });
+     * ```
+     */
+    assertSnapshot(actual: unknown): void;
+
+    /** Assert that `actual` matches `snapshot`, which is stored in the test
+     * file itself.
+     *
+     * A missing or mismatching `snapshot` fails the assertion, unless the
+     * tests are run with `--update-snapshots`, which rewrites the call with the
+     * current value instead.
+     *
+     * ```ts
+     * Deno.test("a test", (t) => {
+     *   t.assertInlineSnapshot([1, 2], `[
+     *   1,
+     *   2,
+     * ]`);
+     * });
+     * ```
+     */
+    assertInlineSnapshot(actual: unknown, snapshot?: string): void;
+
+    /** Attach a file to the test or step, such as a screenshot or a log, for
+     * test reporters to include in their output. The content type defaults
+     * to `text/plain` for strings and to `application/octet-stream`
+     * otherwise.
+     *
+     * ```ts
+     * Deno.test("a test", (t) => {
+     *   t.attach("response.json", JSON.stringify({ ok: true }), {
+     *     contentType: "application/json",
+     *   });
+     * });
+     * ```
+     */
+    attach(
+      name: string,
+      data: string | Uint8Array,
+      options?: { contentType?: string },
+    ): void;
   }
 
   /** @category Testing */
@@ -840,6 +893,12 @@ declare namespace Deno {
      *
      * @default {"inherit"} */
     permissions?: PermissionOptions;
+    /** How many more times to run the test if it fails. A test that passes
+     * on a later attempt is reported as flaky. Takes precedence over the
+     * `--retries` flag.
+     *
+     * @default {0} */
+    retry?: number;
   }
 
   /** Register a test which will be run when `deno test` is used on the command
@@ -1165,6 +1224,145 @@ declare namespace Deno {
     ): void;
   }
 
+  /** The location of a test or test step, relative to the current working
+   * directory for local files.
+   *
+   * @category Testing */
+  export interface TestReporterLocation {
+    file: string;
+    line: number;
+    column: number;
+  }
+
+  /** A test in the events of a {@linkcode TestReporter}.
+   *
+   * @category Testing */
+  export interface TestReporterTest {
+    id: number;
+    name: string;
+    /** The module that registered the test. */
+    origin: string;
+    location: TestReporterLocation;
+  }
+
+  /** A test step in the events of a {@linkcode TestReporter}.
+   *
+   * @category Testing */
+  export interface TestReporterStep {
+    id: number;
+    name: string;
+    /** The id of the test or step this step belongs to. */
+    parentId: number;
+    /** The id of the test this step belongs to. */
+    rootId: number;
+    level: number;
+    location: TestReporterLocation;
+  }
+
+  /** A test failure or uncaught error.
+   *
+   * @category Testing */
+  export interface TestReporterError {
+    /** A one line summary of the failure. */
+    message: string;
+    /** The full failure, including stack traces. */
+    detail: string;
+  }
+
+  /** @category Testing */
+  export type TestReporterStatus =
+    | "passed"
+    | "ignored"
+    | "failed"
+    | "cancelled";
+
+  /** An event of `deno test`, as passed to a {@linkcode TestReporter}.
+   *
+   * @category Testing */
+  export type TestReporterEvent =
+    | {
+      /** The tests of a module are about to run. */
+      type: "plan";
+      origin: string;
+      total: number;
+      filteredOut: number;
+      usedOnly: boolean;
+    }
+    | { type: "testStart"; test: TestReporterTest }
+    | {
+      /** Output of the tests, which isn't attributed to a single test when
+       * tests run in parallel. */
+      type: "output";
+      text: string;
+    }
+    | {
+      /** A file attached to the test or step with the id with
+       * {@linkcode TestContext.attach}. */
+      type: "attachment";
+      testId: number;
+      name: string;
+      contentType: string;
+      data: Uint8Array;
+    }
+    | {
+      /** A failed attempt of a test that is run again, see `--retries`. */
+      type: "testRetry";
+      test: TestReporterTest;
+      error: TestReporterError;
+      duration: number;
+    }
+    | {
+      type: "testResult";
+      test: TestReporterTest;
+      status: TestReporterStatus;
+      duration: number;
+      error?: TestReporterError;
+    }
+    | {
+      /** An error outside of a test, which cancels the tests of a module. */
+      type: "uncaughtError";
+      origin: string;
+      error: TestReporterError;
+    }
+    | { type: "stepStart"; step: TestReporterStep }
+    | {
+      type: "stepResult";
+      step: TestReporterStep;
+      status: "passed" | "ignored" | "failed";
+      duration: number;
+      error?: TestReporterError;
+    }
+    | {
+      /** The run was cancelled with Ctrl+C, with the ids of the tests and
+       * steps that were still running. */
+      type: "interrupted";
+      pending: number[];
+    }
+    | { type: "end"; duration: number };
+
+  /** The default export of a custom reporter module, which is selected with
+   * `deno test --reporter=./reporter.ts`. It runs in a separate process with
+   * the permissions of the tests, and `deno test` waits for the returned
+   * promise before it exits.
+   *
+   * ```ts
+   * export default async function reporter(
+   *   events: AsyncIterable<Deno.TestReporterEvent>,
+   * ) {
+   *   for await (const event of events) {
+   *     if (event.type === "testResult" && event.status === "failed") {
+   *       const { file, line } = event.test.location;
+   *       console.log(`::error file=${file},line=${line}::${event.test.name}`);
+   *     }
+   *   }
+   * }
+   * ```
+   *
+   * @category Testing */
+  export type TestReporter = (
+    events: AsyncIterable<TestReporterEvent>,
+  ) => void | Promise<void>;
+
   /**
    * Context that is passed to a benchmarked function. The instance is shared
    * between iterations of the benchmark. Its methods can be used for example
@@ -1952,6 +2150,172 @@ declare namespace Deno {
    */
   export function openSync(path: string | URL, options?: OpenOptions): FsFile;
 
+  /** Open a directory and return a {@linkcode Deno.DirHandle} for operating
+   * on its entries with paths relative to it.
+   *
+   * A directory handle keeps referring to the same directory even if it is
+   * moved, or its path is replaced by a symlink, after it was opened. This
+   * makes it possible to walk and modify a tree of files without racing
+   * against other processes changing it, and avoids resolving the full path
+   * of every entry again.
+   *
+   * ```ts
+   * using dir = await Deno.openDir("/foo");
+   * using file = await dir.openAt("bar.txt");
+   * ```
+   *
+   * Directory handles are not supported on Windows, where this rejects with
+   * {@linkcode Deno.errors.NotSupported}.
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function openDir(path: string | URL): Promise<DirHandle>;
+
+  /** Synchronously open a directory and return a {@linkcode Deno.DirHandle}
+   * for operating on its entries with paths relative to it.
+   *
+   * ```ts
+   * using dir = Deno.openDirSync("/foo");
+   * using file = dir.openAtSync("bar.txt");
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function openDirSync(path: string | URL): DirHandle;
+
+  /** Options for {@linkcode Deno.DirHandle.unlinkAt}.
+   *
+   * @category File System
+   */
+  export interface UnlinkAtOptions {
+    /** Remove an empty directory instead of a file.
+     *
+     * @default {false} */
+    directory?: boolean;
+  }
+
+  /** An open directory, returned by {@linkcode Deno.openDir} and
+   * {@linkcode Deno.openDirSync}.
+   *
+   * Relative paths given to its methods are resolved from the directory
+   * itself rather than from the current working directory. Permissions are
+   * checked against the path of the directory joined with the given path.
+   * On Linux and macOS that is where the directory is now, elsewhere it is
+   * the path the directory was opened at, even after it was moved.
+   *
+   * @category File System
+   */
+  export class DirHandle implements Disposable {
+    /** Open the file at `path` relative to this directory.
+     *
+     * ```ts
+     * using dir = await Deno.openDir("/foo");
+     * using file = await dir.openAt("bar.txt", { write: true, create: true });
+     * ```
+     *
+     * @tags allow-read, allow-write
+     */
+    openAt(path: string, options?: OpenOptions): Promise<FsFile>;
+    /** Synchronously open the file at `path` relative to this directory.
+     *
+     * @tags allow-read, allow-write
+     */
+    openAtSync(path: string, options?: OpenOptions): FsFile;
+    /** Open the directory at `path` relative to this directory.
+     *
+     * ```ts
+     * using dir = await Deno.openDir("/foo");
+     * using child = await dir.openDirAt("bar");
+     * ```
+     *
+     * @tags allow-read
+     */
+    openDirAt(path: string): Promise<DirHandle>;
+    /** Synchronously open the directory at `path` relative to this
+     * directory.
+     *
+     * @tags allow-read
+     */
+    openDirAtSync(path: string): DirHandle;
+    /** Resolves to a {@linkcode Deno.FileInfo} for the entry at `path`
+     * relative to this directory, following symlinks.
+     *
+     * @tags allow-read
+     */
+    statAt(path: string): Promise<FileInfo>;
+    /** Synchronously returns a {@linkcode Deno.FileInfo} for the entry at
+     * `path` relative to this directory, following symlinks.
+     *
+     * @tags allow-read
+     */
+    statAtSync(path: string): FileInfo;
+    /** Resolves to a {@linkcode Deno.FileInfo} for the entry at `path`
+     * relative to this directory. If it is a symlink, information about the
+     * symlink itself is returned.
+     *
+     * @tags allow-read
+     */
+    lstatAt(path: string): Promise<FileInfo>;
+    /** Synchronously returns a {@linkcode Deno.FileInfo} for the entry at
+     * `path` relative to this directory. If it is a symlink, information
+     * about the symlink itself is returned.
+     *
+     * @tags allow-read
+     */
+    lstatAtSync(path: string): FileInfo;
+    /** Rename the entry at `oldpath` relative to this directory to `newpath`
+     * relative to `newDir`, which defaults to this directory. Like
+     * {@linkcode Deno.rename}, an existing file at `newpath` is replaced.
+     *
+     * ```ts
+     * using dir = await Deno.openDir("/foo");
+     * await dir.renameAt("bar.txt.tmp", "bar.txt");
+     * ```
+     *
+     * @tags allow-read, allow-write
+     */
+    renameAt(
+      oldpath: string,
+      newpath: string,
+      newDir?: DirHandle,
+    ): Promise<void>;
+    /** Synchronously rename the entry at `oldpath` relative to this directory
+     * to `newpath` relative to `newDir`, which defaults to this directory.
+     *
+     * @tags allow-read, allow-write
+     */
+    renameAtSync(oldpath: string, newpath: string, newDir?: DirHandle): void;
+    /** Remove the file at `path` relative to this directory, or the empty
+     * directory if `options.directory` is set.
+     *
+     * ```ts
+     * using dir = await Deno.openDir("/foo");
+     * await dir.unlinkAt("bar.txt");
+     * await dir.unlinkAt("baz", { directory: true });
+     * ```
+     *
+     * @tags allow-write
+     */
+    unlinkAt(path: string, options?: UnlinkAtOptions): Promise<void>;
+    /** Synchronously remove the file at `path` relative to this directory,
+     * or the empty directory if `options.directory` is set.
+     *
+     * @tags allow-write
+     */
+    unlinkAtSync(path: string, options?: UnlinkAtOptions): void;
+    /** Close the directory handle. Files and directories opened through it
+     * stay open. */
+    close(): void;
+
+    [Symbol.dispose](): void;
+  }
+
   /** Creates a file if none exists or truncates an existing file and resolves to
    *  an instance of {@linkcode Deno.FsFile}.
    *
@@ -2307,6 +2671,15 @@ declare namespace Deno {
    * }
    * ```
    *
+   * A file can also be used directly as the body of a {@linkcode Response}.
+   * The file is then streamed from its current position without being read
+   * into JavaScript, sent with a `Content-Length` header when it is a regular
+   * file, and closed once the body has been consumed.
+   *
+   * ```ts
+   * Deno.serve(async () => new Response(await Deno.open("./index.html")));
+   * ```
+   *
    * @category File System
    */
   export class FsFile
@@ -2443,6 +2816,147 @@ declare namespace Deno {
      * ```
      */
     truncateSync(len?: number): void;
+    /** Ensures that disk space is allocated for the first `len` bytes of the
+     * file, extending the file with zeros if it is shorter. Writes to the
+     * allocated range will not fail because the disk is full.
+     *
+     * ```ts
+     * using file = await Deno.open("my_file.db", { create: true, write: true });
+     * await file.allocate(64 * 1024 * 1024);
+     * ```
+     *
+     * @category File System
+     */
+    allocate(len: number): Promise<void>;
+    /** Synchronously ensures that disk space is allocated for the first `len`
+     * bytes of the file, extending the file with zeros if it is shorter.
+     *
+     * ```ts
+     * using file = Deno.openSync("my_file.db", { create: true, write: true });
+     * file.allocateSync(64 * 1024 * 1024);
+     * ```
+     *
+     * @category File System
+     */
+    allocateSync(len: number): void;
+    /** Frees the disk space used by `len` bytes of the file starting at
+     * `offset`, turning the range into a hole that reads as zeros. The size
+     * of the file does not change.
+     *
+     * Supported on Linux, macOS and Windows. On macOS the range has to be
+     * aligned to the block size of the file system. On Windows the file is
+     * marked as sparse first.
+     *
+     * ```ts
+     * using file = await Deno.open("my_file.db", { read: true, write: true });
+     * await file.punchHole(4096, 8192);
+     * ```
+     *
+     * @category File System
+     */
+    punchHole(offset: number, len: number): Promise<void>;
+    /** Synchronously frees the disk space used by `len` bytes of the file
+     * starting at `offset`, turning the range into a hole that reads as zeros.
+     * The size of the file does not change.
+     *
+     * ```ts
+     * using file = Deno.openSync("my_file.db", { read: true, write: true });
+     * file.punchHoleSync(4096, 8192);
+     * ```
+     *
+     * @category File System
+     */
+    punchHoleSync(offset: number, len: number): void;
+    /** Resolves to the value of the extended attribute `name` of the file,
+     * or `null` if it is not set.
+     *
+     * Supported on Linux and macOS. On other platforms this rejects with
+     * {@linkcode Deno.errors.NotSupported}.
+     *
+     * ```ts
+     * using file = await Deno.open("my_file.txt");
+     * const value = await file.getXattr("user.checksum");
+     * ```
+     *
+     * @category File System
+     */
+    getXattr(name: string): Promise<Uint8Array | null>;
+    /** Synchronously returns the value of the extended attribute `name` of
+     * the file, or `null` if it is not set.
+     *
+     * ```ts
+     * using file = Deno.openSync("my_file.txt");
+     * const value = file.getXattrSync("user.checksum");
+     * ```
+     *
+     * @category File System
+     */
+    getXattrSync(name: string): Uint8Array | null;
+    /** Sets the extended attribute `name` of the file, replacing any previous
+     * value. A string value is encoded as UTF-8.
+     *
+     * ```ts
+     * using file = await Deno.open("my_file.txt", { write: true });
+     * await file.setXattr("user.checksum", "abc123");
+     * ```
+     *
+     * @category File System
+     */
+    setXattr(name: string, value: string | Uint8Array): Promise<void>;
+    /** Synchronously sets the extended attribute `name` of the file,
+     * replacing any previous value. A string value is encoded as UTF-8.
+     *
+     * ```ts
+     * using file = Deno.openSync("my_file.txt", { write: true });
+     * file.setXattrSync("user.checksum", "abc123");
+     * ```
+     *
+     * @category File System
+     */
+    setXattrSync(name: string, value: string | Uint8Array): void;
+    /** Resolves to the names of the extended attributes set on the file.
+     *
+     * ```ts
+     * using file = await Deno.open("my_file.txt");
+     * console.log(await file.listXattrs());
+     * ```
+     *
+     * @category File System
+     */
+    listXattrs(): Promise<string[]>;
+    /** Synchronously returns the names of the extended attributes set on the
+     * file.
+     *
+     * ```ts
+     * using file = Deno.openSync("my_file.txt");
+     * console.log(file.listXattrsSync());
+     * ```
+     *
+     * @category File System
+     */
+    listXattrsSync(): string[];
+    /** Removes the extended attribute `name` of the file. Rejects with
+     * {@linkcode Deno.errors.NotFound} if it is not set.
+     *
+     * ```ts
+     * using file = await Deno.open("my_file.txt", { write: true });
+     * await file.removeXattr("user.checksum");
+     * ```
+     *
+     * @category File System
+     */
+    removeXattr(name: string): Promise<void>;
+    /** Synchronously removes the extended attribute `name` of the file.
+     * Throws {@linkcode Deno.errors.NotFound} if it is not set.
+     *
+     * ```ts
+     * using file = Deno.openSync("my_file.txt", { write: true });
+     * file.removeXattrSync("user.checksum");
+     * ```
+     *
+     * @category File System
+     */
+    removeXattrSync(name: string): void;
     /** Read the file into an array buffer (`p`).
      *
      * Resolves to either the number of bytes read during the operation or EOF
@@ -2648,6 +3162,45 @@ declare namespace Deno {
      * @category I/O
      */
     syncDataSync(): void;
+    /**
+     * Flushes the file to disk and makes its contents available at `path`,
+     * atomically replacing any file that exists there. Intended for files
+     * created with {@linkcode Deno.makeTempFile} and `anonymous: true`.
+     *
+     * The file is first given a temporary name in the directory of `path`,
+     * and then renamed. If it cannot be given a name directly, its contents
+     * are copied instead.
+     *
+     * ```ts
+     * using file = await Deno.makeTempFile({ dir: ".", anonymous: true });
+     * await file.write(new TextEncoder().encode("Hello World"));
+     * await file.persist("my_file.txt");
+     * console.log(await Deno.readTextFile("my_file.txt")); // Hello World
+     * ```
+     *
+     * Requires `allow-write` permission.
+     *
+     * @tags allow-write
+     * @category File System
+     */
+    persist(path: string | URL): Promise<void>;
+    /**
+     * Synchronously flushes the file to disk and makes its contents available
+     * at `path`, atomically replacing any file that exists there.
+     *
+     * ```ts
+     * using file = Deno.makeTempFileSync({ dir: ".", anonymous: true });
+     * file.writeSync(new TextEncoder().encode("Hello World"));
+     * file.persistSync("my_file.txt");
+     * console.log(Deno.readTextFileSync("my_file.txt")); // Hello World
+     * ```
+     *
+     * Requires `allow-write` permission.
+     *
+     * @tags allow-write
+     * @category File System
+     */
+    persistSync(path: string | URL): void;
     /**
      * Changes the access (`atime`) and modification (`mtime`) times of the
      * file stream resource. Given times are either in seconds (UNIX epoch
@@ -2700,13 +3253,35 @@ declare namespace Deno {
      */
     setRaw(mode: boolean, options?: SetRawOptions): void;
     /**
-     * Acquire an advisory file-system lock for the file.
+     * Acquire an advisory file-system lock for the file, waiting until no
+     * other process holds a conflicting lock. A shared lock can be held by
+     * several processes at once, while an exclusive lock can only be held by
+     * one. Waiting for the lock doesn't block the event loop.
+     *
+     * Uses `flock` on Unix and `LockFileEx` on Windows, where the lock is
+     * mandatory rather than advisory. The lock is released when the file is
+     * closed.
+     *
+     * ```ts
+     * using file = await Deno.open("app.lock", { write: true, create: true });
+     * await file.lock(true);
+     * // Only one process gets here at a time.
+     * await file.unlock();
+     * ```
      *
      * @param [exclusive=false]
      */
     lock(exclusive?: boolean): Promise<void>;
     /**
-     * Synchronously acquire an advisory file-system lock synchronously for the file.
+     * Synchronously acquire an advisory file-system lock for the file,
+     * blocking until no other process holds a conflicting lock.
+     *
+     * ```ts
+     * using file = Deno.openSync("app.lock", { write: true, create: true });
+     * file.lockSync(true);
+     * // Only one process gets here at a time.
+     * file.unlockSync();
+     * ```
      *
      * @param [exclusive=false]
      */
@@ -3186,6 +3761,21 @@ declare namespace Deno {
     suffix?: string;
   }
 
+  /** Options which can be set when calling {@linkcode Deno.makeTempFile} to
+   * create a file without a name.
+   *
+   * @category File System */
+  export interface MakeAnonymousTempFileOptions {
+    /** Directory where the file should be created (defaults to the env
+     * variable `TMPDIR`, or the system's default, usually `/tmp`). To be able
+     * to persist the file with {@linkcode Deno.FsFile.persist}, use a
+     * directory on the same file system as the file's final path. */
+    dir?: string;
+    /** Create a file without a name, which is removed once it is closed
+     * unless it is persisted. */
+    anonymous: true;
+  }
+
   /** Creates a new temporary directory in the default directory for temporary
    * files, unless `dir` is specified. Other optional options include
    * prefixing and suffixing the directory name with `prefix` and `suffix`
@@ -3257,6 +3847,33 @@ declare namespace Deno {
    * @category File System
    */
   export function makeTempFile(options?: MakeTempOptions): Promise<string>;
+  /** Creates a new temporary file without a name in the default directory for
+   * temporary files, unless `dir` is specified, and resolves to the file
+   * opened for reading and writing.
+   *
+   * Other programs cannot open the file, and it is removed once it is closed,
+   * even if the program crashes. It can be given a name with
+   * {@linkcode Deno.FsFile.persist}, which makes it possible to write a new
+   * version of a file and then replace the old version in a single step.
+   *
+   * On Linux the file is created with `O_TMPFILE` where the file system
+   * supports it. Elsewhere, the name of the file is removed right after it is
+   * created, or on Windows, once it is closed.
+   *
+   * ```ts
+   * using file = await Deno.makeTempFile({ dir: ".", anonymous: true });
+   * await file.write(new TextEncoder().encode("new contents"));
+   * await file.persist("config.json");
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category File System
+   */
+  export function makeTempFile(
+    options: MakeAnonymousTempFileOptions,
+  ): Promise<FsFile>;
 
   /** Synchronously creates a new temporary file in the default directory for
    * temporary files, unless `dir` is specified.
@@ -3281,6 +3898,28 @@ declare namespace Deno {
    * @category File System
    */
   export function makeTempFileSync(options?: MakeTempOptions): string;
+  /** Synchronously creates a new temporary file without a name in the default
+   * directory for temporary files, unless `dir` is specified, and returns the
+   * file opened for reading and writing.
+   *
+   * Other programs cannot open the file, and it is removed once it is closed,
+   * even if the program crashes. It can be given a name with
+   * {@linkcode Deno.FsFile.persistSync}.
+   *
+   * ```ts
+   * using file = Deno.makeTempFileSync({ dir: ".", anonymous: true });
+   * file.writeSync(new TextEncoder().encode("new contents"));
+   * file.persistSync("config.json");
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category File System
+   */
+  export function makeTempFileSync(
+    options: MakeAnonymousTempFileOptions,
+  ): FsFile;
 
   /** Changes the permission of a specific file/directory of specified path.
    * Ignores the process's umask.
@@ -3702,6 +4341,10 @@ declare namespace Deno {
   /** Reads the directory given by `path` and returns an async iterable of
    * {@linkcode Deno.DirEntry}. The order of entries is not guaranteed.
    *
+   * The directory is opened when iteration starts and read in batches while
+   * iterating, so large directories are not held in memory at once. Entries
+   * added or removed during iteration may or may not be returned.
+   *
    * ```ts
    * for await (const dirEntry of Deno.readDir("/")) {
    *   console.log(dirEntry.name);
@@ -3874,6 +4517,67 @@ declare namespace Deno {
    */
   export function statSync(path: string | URL): FileInfo;
 
+  /** Information about a file system, returned by {@linkcode Deno.statfs}
+   * and {@linkcode Deno.statfsSync}.
+   *
+   * @category File System
+   */
+  export interface StatfsInfo {
+    /** The type of the file system, like `"ext4"`, `"apfs"` or `"NTFS"`. On
+     * Linux, file systems that are not recognized are reported by their
+     * magic number in hexadecimal, like `"0x4244"`. */
+    type: string;
+    /** The size of a block of the file system, in bytes. */
+    blockSize: number;
+    /** The size of the file system, in bytes. */
+    totalBytes: number;
+    /** The free space on the file system, in bytes. */
+    freeBytes: number;
+    /** The free space on the file system that is available to the current
+     * user, in bytes. This can be less than `freeBytes` because of space
+     * that is reserved or of quotas. */
+    availableBytes: number;
+    /** The number of inodes of the file system.
+     *
+     * _Linux/Mac OS only._ */
+    files: number | null;
+    /** The number of free inodes of the file system.
+     *
+     * _Linux/Mac OS only._ */
+    freeFiles: number | null;
+  }
+
+  /** Resolves to a {@linkcode Deno.StatfsInfo} describing the size and free
+   * space of the file system that contains `path`.
+   *
+   * ```ts
+   * const { availableBytes } = await Deno.statfs("/var/cache");
+   * if (availableBytes < 1024 * 1024 * 1024) {
+   *   console.log("Less than 1 GiB left");
+   * }
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function statfs(path: string | URL): Promise<StatfsInfo>;
+
+  /** Synchronously returns a {@linkcode Deno.StatfsInfo} describing the size
+   * and free space of the file system that contains `path`.
+   *
+   * ```ts
+   * const { availableBytes } = Deno.statfsSync("/var/cache");
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function statfsSync(path: string | URL): StatfsInfo;
+
   /** Options for writing to a file.
    *
    * @category File System
@@ -4148,8 +4852,19 @@ declare namespace Deno {
    *
    * @category File System */
   export interface FsEvent {
-    /** The kind/type of the file system event. */
-    kind: "any" | "access" | "create" | "modify" | "remove" | "other";
+    /** The kind/type of the file system event.
+     *
+     * `"rename"` events are only yielded when
+     * {@linkcode Deno.WatchFsOptions.pairRenames} is set. Their `paths` are
+     * the old path followed by the new path. */
+    kind:
+      | "any"
+      | "access"
+      | "create"
+      | "modify"
+      | "remove"
+      | "rename"
+      | "other";
     /** An array of paths that are associated with the file system event. */
     paths: string[];
     /** Any additional flags associated with the event. */
@@ -4185,6 +4900,33 @@ declare namespace Deno {
     [Symbol.asyncIterator](): AsyncIterableIterator<FsEvent>;
   }
 
+  /**
+   * Options which can be set when calling {@linkcode Deno.watchFs}.
+   *
+   * @category File System
+   */
+  export interface WatchFsOptions {
+    /** For directories, also watch all sub directories.
+     *
+     * @default {true} */
+    recursive?: boolean;
+    /** Glob patterns of paths to ignore, such as `"**\/node_modules/**"` or
+     * `"*.tmp"`. A pattern is matched against both the path of an event and
+     * the path relative to the watched path. Events whose paths are all
+     * excluded are not yielded. */
+    exclude?: string[];
+    /** Collect the events that occur within this many milliseconds of each
+     * other into a batch, dropping duplicate events from the batch. */
+    debounce?: number;
+    /** Yield a single `"rename"` event with the old and the new path when a
+     * file is renamed, instead of separate events for each path. A rename
+     * whose counterpart is outside of the watched paths is yielded as a
+     * `"modify"` event.
+     *
+     * @default {false} */
+    pairRenames?: boolean;
+  }
+
   /** Watch for file system events against one or more `paths`, which can be
    * files or directories. These paths must exist already. One user action (e.g.
    * `touch test.file`) can generate multiple file system events. Likewise,
@@ -4205,6 +4947,22 @@ declare namespace Deno {
    * }
    * ```
    *
+   * Editors and build tools often touch a file several times in a row. Use
+   * the `exclude`, `debounce` and `pairRenames` options to reduce the events
+   * to the ones that matter:
+   *
+   * ```ts
+   * const watcher = Deno.watchFs("./src", {
+   *   exclude: ["*.swp"],
+   *   debounce: 100,
+   *   pairRenames: true,
+   * });
+   * for await (const event of watcher) {
+   *    console.log(">>>> event", event);
+   *    // { kind: "rename", paths: [ "/src/a.ts", "/src/b.ts" ] }
+   * }
+   * ```
+   *
    * Call `watcher.close()` to stop watching.
    *
    * ```ts
@@ -4226,7 +4984,7 @@ declare namespace Deno {
    */
   export function watchFs(
     paths: string | string[],
-    options?: { recursive: boolean },
+    options?: WatchFsOptions,
   ): FsWatcher;
 
   /**
@@ -4930,10 +5688,12 @@ declare namespace Deno {
    * @category Permissions */
   export interface NetPermissionDescriptor {
     name: "net";
-    /** Optional host string of the form `"<hostname>[:<port>]"`. Examples:
+    /** Optional host string of the form `"<hostname>[:<port>]"`, or
+     * `"unix:<path>"` for a Unix domain socket. Examples:
      *
      *      "github.com"
      *      "deno.land:8080"
+     *      "unix:/run/app.sock"
      */
     host?: string;
   }
@@ -5019,6 +5779,21 @@ declare namespace Deno {
     | FfiPermissionDescriptor
     | HrtimePermissionDescriptor;
 
+  /** Options for {@linkcode Permissions.revoke} and
+   * {@linkcode Permissions.revokeSync}.
+   *
+   * @category Permissions */
+  export interface PermissionRevokeOptions {
+    /** Also revoke the permission in the Web Workers that were started with
+     * their own set of permissions, and in the workers they started.
+     *
+     * Workers that share the permissions of their parent always observe
+     * revocations.
+     *
+     * @default {false} */
+    propagate?: boolean;
+  }
+
   /** The interface which defines what event types are supported by
    * {@linkcode PermissionStatus} instances.
    *
@@ -5129,7 +5904,10 @@ declare namespace Deno {
      * assert(status.state !== "granted")
      * ```
      */
-    revoke(desc: PermissionDescriptor): Promise<PermissionStatus>;
+    revoke(
+      desc: PermissionDescriptor,
+      options?: PermissionRevokeOptions,
+    ): Promise<PermissionStatus>;
 
     /** Revokes a permission, and returns the state of the permission.
      *
@@ -5140,7 +5918,10 @@ declare namespace Deno {
      * assert(status.state !== "granted")
      * ```
      */
-    revokeSync(desc: PermissionDescriptor): PermissionStatus;
+    revokeSync(
+      desc: PermissionDescriptor,
+      options?: PermissionRevokeOptions,
+    ): PermissionStatus;
 
     /** Requests the permission, and resolves to the state of the permission.
      *
@@ -5622,6 +6403,149 @@ declare namespace Deno {
     mtime: number | Date,
   ): Promise<void>;
 
+  /**
+   * Synchronously returns the value of the extended attribute `name` of the
+   * file at `path`, or `null` if it is not set. Symlinks are followed.
+   *
+   * Extended attributes are supported on Linux and macOS. On other platforms
+   * this throws {@linkcode Deno.errors.NotSupported}. On Linux, the attributes
+   * that unprivileged programs can set are in the `user.` namespace.
+   *
+   * ```ts
+   * const value = Deno.getXattrSync("myfile.txt", "user.checksum");
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function getXattrSync(
+    path: string | URL,
+    name: string,
+  ): Uint8Array | null;
+
+  /**
+   * Resolves to the value of the extended attribute `name` of the file at
+   * `path`, or `null` if it is not set. Symlinks are followed.
+   *
+   * Extended attributes are supported on Linux and macOS. On other platforms
+   * this rejects with {@linkcode Deno.errors.NotSupported}. On Linux, the
+   * attributes that unprivileged programs can set are in the `user.`
+   * namespace.
+   *
+   * ```ts
+   * const value = await Deno.getXattr("myfile.txt", "user.checksum");
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function getXattr(
+    path: string | URL,
+    name: string,
+  ): Promise<Uint8Array | null>;
+
+  /**
+   * Synchronously sets the extended attribute `name` of the file at `path`,
+   * replacing any previous value. A string value is encoded as UTF-8.
+   *
+   * ```ts
+   * Deno.setXattrSync("myfile.txt", "user.checksum", "abc123");
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category File System
+   */
+  export function setXattrSync(
+    path: string | URL,
+    name: string,
+    value: string | Uint8Array,
+  ): void;
+
+  /**
+   * Sets the extended attribute `name` of the file at `path`, replacing any
+   * previous value. A string value is encoded as UTF-8.
+   *
+   * ```ts
+   * await Deno.setXattr("myfile.txt", "user.checksum", "abc123");
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category File System
+   */
+  export function setXattr(
+    path: string | URL,
+    name: string,
+    value: string | Uint8Array,
+  ): Promise<void>;
+
+  /**
+   * Synchronously returns the names of the extended attributes set on the
+   * file at `path`.
+   *
+   * ```ts
+   * console.log(Deno.listXattrsSync("myfile.txt"));
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function listXattrsSync(path: string | URL): string[];
+
+  /**
+   * Resolves to the names of the extended attributes set on the file at
+   * `path`.
+   *
+   * ```ts
+   * console.log(await Deno.listXattrs("myfile.txt"));
+   * ```
+   *
+   * Requires `allow-read` permission.
+   *
+   * @tags allow-read
+   * @category File System
+   */
+  export function listXattrs(path: string | URL): Promise<string[]>;
+
+  /**
+   * Synchronously removes the extended attribute `name` of the file at
+   * `path`. Throws {@linkcode Deno.errors.NotFound} if it is not set.
+   *
+   * ```ts
+   * Deno.removeXattrSync("myfile.txt", "user.checksum");
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category File System
+   */
+  export function removeXattrSync(path: string | URL, name: string): void;
+
+  /**
+   * Removes the extended attribute `name` of the file at `path`. Rejects
+   * with {@linkcode Deno.errors.NotFound} if it is not set.
+   *
+   * ```ts
+   * await Deno.removeXattr("myfile.txt", "user.checksum");
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category File System
+   */
+  export function removeXattr(path: string | URL, name: string): Promise<void>;
+
   /** The event yielded from an {@linkcode HttpConn} which represents an HTTP
    * request from a remote client.
    *
@@ -6241,6 +7165,76 @@ declare namespace Deno {
   export interface ServeHandlerInfo {
     /** The remote address of the connection. */
     remoteAddr: Deno.NetAddr;
+
+    /** The priority the client requested for this request. */
+    priority: ServeRequestPriority;
+
+    /** The connection the request was received on. */
+    connection: ServeConnectionInfo<Deno.NetAddr>;
+  }
+
+  /** Information about a connection accepted by {@linkcode Deno.serve}.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeConnectionInfo<A extends Deno.Addr = Deno.Addr> {
+    /** Identifies the connection among all connections accepted by the same
+     * server. Requests made over the same connection share the same id. */
+    id: number;
+
+    /** The remote address of the connection. */
+    remoteAddr: A;
+
+    /** Details of the TLS session, or `null` if the connection is not
+     * encrypted. */
+    tls: ServeTlsConnectionInfo | null;
+  }
+
+  /** Details of the TLS session of a connection accepted by
+   * {@linkcode Deno.serve}.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeTlsConnectionInfo {
+    /** The protocol negotiated through ALPN, or `null` if the client did not
+     * negotiate one. */
+    alpnProtocol: string | null;
+  }
+
+  /** Passed to the `onConnectionClose` callback of {@linkcode Deno.serve}.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeConnectionCloseInfo<A extends Deno.Addr = Deno.Addr> {
+    /** The id of the closed connection, matching
+     * {@linkcode ServeConnectionInfo.id}. */
+    id: number;
+
+    /** The remote address of the connection. */
+    remoteAddr: A;
+
+    /** The protocol or I/O error that caused the connection to close, such as
+     * a malformed request or a failed TLS handshake. Absent if the connection
+     * was closed cleanly or by shutting down the server. */
+    error?: Error;
+  }
+
+  /** The priority of an HTTP request, as signalled by the client through the
+   * `Priority` header defined in
+   * {@link https://www.rfc-editor.org/rfc/rfc9218.html | RFC 9218}.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeRequestPriority {
+    /** From `0` (highest priority) to `7` (lowest priority).
+     *
+     * @default {3} */
+    urgency: number;
+
+    /** Whether the client can make use of a partially received response.
+     *
+     * @default {false} */
+    incremental: boolean;
   }
 
   /** A handler for HTTP requests. Consumes a request and returns a response.
@@ -6256,6 +7250,87 @@ declare namespace Deno {
     info: ServeHandlerInfo,
   ) => Response | Promise<Response>;
 
+  /** Per-connection timeouts for {@linkcode Deno.serve}, in milliseconds.
+   *
+   * When a timeout elapses the underlying connection is closed. Timeouts that
+   * are not set are disabled.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeTimeouts {
+    /** The maximum time a client may take to send the request headers. Only
+     * applies to HTTP/1.1 connections. */
+    headerRead?: number;
+
+    /** The maximum time to wait for each chunk of a request body. Reading the
+     * body rejects with {@linkcode Deno.errors.TimedOut} when it elapses. */
+    bodyRead?: number;
+
+    /** The maximum time between a handler returning a response and the
+     * response being fully written to the client. */
+    responseWrite?: number;
+
+    /** The maximum time a connection may stay open without any requests in
+     * flight, including keep-alive connections between requests. */
+    idle?: number;
+  }
+
+  /** Server-side HTTP/2 settings for {@linkcode Deno.serve}. Settings that
+   * are not set keep their default values.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeHttp2Settings {
+    /** The initial flow control window size of each stream, in bytes. Must be
+     * at most `2 ** 31 - 1`.
+     *
+     * @default {65535} */
+    initialStreamWindowSize?: number;
+
+    /** The initial flow control window size of each connection, in bytes.
+     * Must be at most `2 ** 31 - 1`.
+     *
+     * @default {65535} */
+    initialConnectionWindowSize?: number;
+
+    /** The largest frame payload the server is willing to receive, in bytes.
+     * Must be between `16384` and `16777215`.
+     *
+     * @default {16384} */
+    maxFrameSize?: number;
+
+    /** The maximum size of the request header list, in bytes.
+     *
+     * @default {16384} */
+    maxHeaderListSize?: number;
+
+    /** The maximum number of concurrent streams per connection.
+     *
+     * @default {200} */
+    maxConcurrentStreams?: number;
+  }
+
+  /** Limits on the request head accepted by {@linkcode Deno.serve}. Requests
+   * exceeding a limit are rejected with a `414 URI Too Long` or
+   * `431 Request Header Fields Too Large` response without invoking the
+   * handler. Limits that are not set keep their default values.
+   *
+   * @category HTTP Server
+   */
+  export interface ServeLimits {
+    /** The maximum combined size of all header names and values, in bytes. */
+    maxHeaderBytes?: number;
+
+    /** The maximum length of the request target (path and query), in
+     * bytes. */
+    maxUriLength?: number;
+
+    /** The maximum number of header fields.
+     *
+     * @default {100} */
+    maxHeaderCount?: number;
+  }
+
   /** Options which can be set when calling {@linkcode Deno.serve}.
    *
    * @category HTTP Server
@@ -6284,8 +7359,27 @@ declare namespace Deno {
     /** Sets `SO_REUSEPORT` on POSIX systems. */
     reusePort?: boolean;
 
-    /** The handler to invoke when route handlers throw an error. */
-    onError?: (error: unknown) => Response | Promise<Response>;
+    /** Timeouts applied to each connection accepted by the server. */
+    timeouts?: ServeTimeouts;
+
+    /** HTTP/2 settings advertised to clients on HTTP/2 connections. */
+    http2?: ServeHttp2Settings;
+
+    /** Limits on the size of request heads. */
+    limits?: ServeLimits;
+
+    /** The handler to invoke when route handlers throw an error. The request
+     * being handled is passed along unless the error occurred before it could
+     * be constructed. */
+    onError?: (
+      error: unknown,
+      request?: Request,
+    ) => Response | Promise<Response>;
+
+    /** The callback which is called whenever a connection accepted by the
+     * server is closed, including connections closed due to protocol errors
+     * that never reach a handler. */
+    onConnectionClose?: (info: ServeConnectionCloseInfo<Deno.NetAddr>) => void;
 
     /** The callback which is called when the server starts listening. */
     onListen?: (localAddr: Deno.NetAddr) => void;
@@ -6339,8 +7433,29 @@ declare namespace Deno {
     /** An {@linkcode AbortSignal} to close the server and all connections. */
     signal?: AbortSignal;
 
-    /** The handler to invoke when route handlers throw an error. */
-    onError?: (error: unknown) => Response | Promise<Response>;
+    /** Timeouts applied to each connection accepted by the server. */
+    timeouts?: ServeTimeouts;
+
+    /** HTTP/2 settings advertised to clients on HTTP/2 connections. */
+    http2?: ServeHttp2Settings;
+
+    /** Limits on the size of request heads. */
+    limits?: ServeLimits;
+
+    /** The handler to invoke when route handlers throw an error. The request
+     * being handled is passed along unless the error occurred before it could
+     * be constructed. */
+    onError?: (
+      error: unknown,
+      request?: Request,
+    ) => Response | Promise<Response>;
+
+    /** The callback which is called whenever a connection accepted by the
+     * server is closed, including connections closed due to protocol errors
+     * that never reach a handler. */
+    onConnectionClose?: (
+      info: ServeConnectionCloseInfo<Deno.UnixAddr>,
+    ) => void;
 
     /** The callback which is called when the server starts listening. */
     onListen?: (localAddr: Deno.UnixAddr) => void;
@@ -6353,6 +7468,12 @@ declare namespace Deno {
   export interface ServeUnixHandlerInfo {
     /** The remote address of the connection. */
     remoteAddr: Deno.UnixAddr;
+
+    /** The priority the client requested for this request. */
+    priority: ServeRequestPriority;
+
+    /** The connection the request was received on. */
+    connection: ServeConnectionInfo<Deno.UnixAddr>;
   }
 
   /** A handler for unix domain socket HTTP requests. Consumes a request and returns a response.
@@ -871,6 +871,96 @@ declare namespace Deno {
     uid?: number;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Restrictions applied to a subprocess before its program is executed, to
+   * contain untrusted tools.
+   *
+   * ```ts
+   * const command = new Deno.Command("./tool", {
+   *   sandbox: {
+   *     permissions: { read: ["./src"], write: false, net: false },
+   *     cpu: 10,
+   *     memory: 512 * 1024 * 1024,
+   *     openFiles: 64,
+   *     noNewPrivileges: true,
+   *   },
+   * });
+   * ```
+   *
+   * @category Sub Process
+   * @experimental
+   */
+  export interface CommandSandboxOptions {
+    /** The permissions the subprocess is confined to with Landlock and
+     * seccomp, which must be a subset of the current permissions. Unless
+     * `run` is fully granted, the subprocess can only execute its own
+     * program, and only the environment variables granted by `env` are
+     * passed on. `sys` and `ffi` can't be set, and permissions that deny
+     * something they would otherwise grant, ex. with `--deny-read`, are
+     * rejected. Only supported on Linux. */
+    permissions?: PermissionOptions;
+    /** The CPU time in seconds after which the subprocess is killed. */
+    cpu?: number;
+    /** The size of the address space of the subprocess in bytes. */
+    memory?: number;
+    /** The number of files the subprocess can have open at once. */
+    openFiles?: number;
+    /** Prevents the subprocess from gaining privileges, ex. by executing
+     * setuid binaries. Only supported on Linux.
+     *
+     * @default {false} */
+    noNewPrivileges?: boolean;
+  }
+
+  /**
+   * @category Sub Process
+   * @experimental
+   */
+  export interface CommandOptions {
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Restrictions applied to the subprocess. Not supported on Windows. */
+    sandbox?: CommandSandboxOptions;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A signed token from {@linkcode Deno.createCapabilityToken} that carries
+   * a subset of the permissions of the code that created it. It is only
+   * valid in the process that created it.
+   *
+   * @category Permissions
+   * @experimental
+   */
+  export type CapabilityToken = string & { [brand]: "CapabilityToken" };
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Creates a token for `permissions`, which must be a subset of the current
+   * permissions, that a worker can be started with instead of the permission
+   * options themselves. Code that only receives the token can start workers
+   * with exactly these permissions, and every token that is created or used
+   * is recorded in the `--permission-audit-log` with its id.
+   *
+   * ```ts
+   * const token = Deno.createCapabilityToken({ read: ["./data"] });
+   * const worker = new Worker(import.meta.resolve("./worker.ts"), {
+   *   type: "module",
+   *   deno: { permissions: token },
+   * });
+   * ```
+   *
+   * Throws `Deno.errors.PermissionDenied` if the permissions are not a
+   * subset of the current permissions.
+   *
+   * @category Permissions
+   * @experimental
+   */
+  export function createCapabilityToken(
+    permissions: PermissionOptions,
+  ): CapabilityToken;
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Spawns new subprocess. RunOptions must contain at a minimum the `opt.cmd`,
@@ -981,6 +1071,49 @@ declare namespace Deno {
      * @default {false}
      */
     allowHost?: boolean;
+    /** Retry idempotent requests that fail with a network error or receive
+     * a retryable response status, such as after a kept-alive connection was
+     * closed by the server. Disabled by default. */
+    retry?: RetryPolicy;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The retry policy of a {@linkcode Deno.HttpClient}, specified with
+   * {@linkcode Deno.CreateHttpClientOptions}.
+   *
+   * Only requests with an idempotent method (`GET`, `HEAD`, `OPTIONS`,
+   * `TRACE`, `PUT` and `DELETE`) and without a streaming body are retried.
+   * Retries are delayed by a random backoff, or by the delay given in the
+   * `Retry-After` header of the response.
+   *
+   * @category Fetch
+   * @experimental
+   */
+  export interface RetryPolicy {
+    /** The maximum number of attempts, including the first one.
+     *
+     * @default {3}
+     */
+    maxAttempts?: number;
+    /** The maximum delay before the first retry, in milliseconds. It doubles
+     * with each further retry.
+     *
+     * @default {100}
+     */
+    initialBackoff?: number;
+    /** The maximum delay between two attempts, in milliseconds. Responses
+     * with a `Retry-After` header asking for a longer delay are returned
+     * without being retried.
+     *
+     * @default {10000}
+     */
+    maxBackoff?: number;
+    /** The response statuses that cause a request to be retried.
+     *
+     * @default {[429, 503]}
+     */
+    retryOnStatus?: number[];
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -1206,9 +1339,10 @@ declare namespace Deno {
    * });
    * ```
    *
-   * Requires `allow-read` and `allow-write` permission.
+   * Requires `allow-read` and `allow-write` permission, or `allow-net`
+   * permission for the socket path, ex. `--allow-net=unix:/foo/bar.sock`.
    *
-   * @tags allow-read, allow-write
+   * @tags allow-read, allow-write, allow-net
    * @category Network
    * @experimental
    */
@@ -1216,6 +1350,211 @@ declare namespace Deno {
     options: UnixListenOptions & { transport: "unixpacket" },
   ): DatagramConn;
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The credentials of the process on the other end of a unix socket
+   * connection, as returned by {@linkcode Deno.UnixConn.peerCredentials}.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface UnixPeerCredentials {
+    /** The effective user ID of the peer process. */
+    uid: number;
+    /** The effective group ID of the peer process. */
+    gid: number;
+    /** The process ID of the peer, or `null` if the platform does not report
+     * it. */
+    pid: number | null;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface UnixConn {
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Returns the credentials of the process on the other end of the
+     * connection (`SO_PEERCRED` on Linux, `getpeereid` on macOS and the BSDs).
+     * The kernel captures them when the connection is established, so they
+     * can be trusted to authorize local clients.
+     *
+     * ```ts
+     * const listener = Deno.listen({ transport: "unix", path: "/tmp/app.sock" });
+     * for await (const conn of listener) {
+     *   if (conn.peerCredentials().uid !== 0) {
+     *     conn.close();
+     *   }
+     * }
+     * ```
+     *
+     * @experimental
+     */
+    peerCredentials(): UnixPeerCredentials;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options which can be set when opening a vsock listener via
+   * {@linkcode Deno.listen} or connecting via {@linkcode Deno.connect}.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface VsockOptions {
+    transport: "vsock";
+    /** The context ID of the socket. When listening, use `-1`
+     * (`VMADDR_CID_ANY`) to accept connections on any context ID. */
+    cid: number;
+    /** The port of the socket. */
+    port: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface VsockConn extends Conn<VsockAddr> {}
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * @category Network
+   * @experimental
+   */
+  export type VsockListener = Listener<VsockConn, VsockAddr>;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Listen announces on the given vsock address, for communication between
+   * virtual machines and their host. Only available on Linux.
+   *
+   * ```ts
+   * const listener = Deno.listen({ transport: "vsock", cid: -1, port: 1234 });
+   * ```
+   *
+   * Requires `allow-net` permission.
+   *
+   * @tags allow-net
+   * @category Network
+   * @experimental
+   */
+  export function listen(options: VsockOptions): VsockListener;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Connects to the given vsock address, for communication between virtual
+   * machines and their host. Only available on Linux.
+   *
+   * ```ts
+   * // Connect to port 1234 of the host (CID 2)
+   * const conn = await Deno.connect({ transport: "vsock", cid: 2, port: 1234 });
+   * ```
+   *
+   * Requires `allow-net` permission.
+   *
+   * @tags allow-net
+   * @category Network
+   * @experimental
+   */
+  export function connect(options: VsockOptions): Promise<VsockConn>;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The address of the peer of an {@linkcode IcmpSocket} message.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface IcmpAddr {
+    transport: "icmp";
+    hostname: string;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options which can be set when opening a raw ICMP socket via
+   * {@linkcode Deno.icmpSocket}.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface IcmpSocketOptions {
+    /** The IP version of the socket. ICMPv6 is used for `"ipv6"` sockets.
+     *
+     * @default {"ipv4"} */
+    family?: "ipv4" | "ipv6";
+
+    /** The time-to-live (hop limit for IPv6) of outgoing packets. */
+    ttl?: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A raw ICMP socket, opened with {@linkcode Deno.icmpSocket}.
+   *
+   * @category Network
+   * @experimental
+   */
+  export interface IcmpSocket
+    extends AsyncIterable<[Uint8Array, IcmpAddr]>, Disposable {
+    /** The IP version of the socket. */
+    readonly family: "ipv4" | "ipv6";
+
+    /** Waits for and resolves to the next ICMP message received by the
+     * socket.
+     *
+     * For IPv4 sockets the message includes the IP header. For IPv6 sockets
+     * only the ICMPv6 message is returned.
+     */
+    receive(p?: Uint8Array): Promise<[Uint8Array, IcmpAddr]>;
+    /** Sends an ICMP message, including its header, to the given host. The
+     * checksum of ICMPv6 messages is computed by the kernel. Resolves with the
+     * number of bytes sent.
+     *
+     * Requires `allow-net` permission for the destination host. */
+    send(p: Uint8Array, hostname: string): Promise<number>;
+    /** Sets the time-to-live (hop limit for IPv6) of outgoing packets. */
+    setTtl(ttl: number): void;
+    /** Closes the socket. Any pending message promises will be rejected with
+     * errors. */
+    close(): void;
+    /** Make the socket block the event loop from finishing.
+     *
+     * Note: the socket blocks the event loop from finishing by default.
+     * This method is only meaningful after `.unref()` is called.
+     */
+    ref(): void;
+    /** Make the socket not block the event loop from finishing. */
+    unref(): void;
+    [Symbol.asyncIterator](): AsyncIterableIterator<[Uint8Array, IcmpAddr]>;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Opens a raw ICMP socket, which can be used to implement tools such as
+   * `ping` and `traceroute`.
+   *
+   * ```ts
+   * const socket = Deno.icmpSocket();
+   * // ICMP echo request with identifier 1 and sequence number 1
+   * const request = new Uint8Array([8, 0, 0xf7, 0xfd, 0, 1, 0, 1]);
+   * await socket.send(request, "127.0.0.1");
+   * const [reply, addr] = await socket.receive();
+   * socket.close();
+   * ```
+   *
+   * Requires `allow-net` permission, the `--unstable-raw-sockets` flag, and
+   * privileges to open raw sockets (for example `CAP_NET_RAW` on Linux).
+   *
+   * @tags allow-net
+   * @category Network
+   * @experimental
+   */
+  export function icmpSocket(options?: IcmpSocketOptions): IcmpSocket;
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Acquire an advisory file-system lock for the provided file.
@@ -1265,13 +1604,88 @@ declare namespace Deno {
    * the current script. This location is persistent across script runs and is
    * keyed on the origin storage key (the same key that is used to determine
    * `localStorage` persistence). More information about the origin storage key
-   * can be found in the Deno Manual.
+   * can be found in the Deno Manual. Compiled executables don't have an origin
+   * storage key, so they default to an in-memory database unless a path is
+   * provided.
+   *
+   * The `options` argument allows tuning databases that are backed by a local
+   * SQLite file:
+   *
+   * ```ts
+   * const kv = await Deno.openKv("./app.sqlite3", {
+   *   sqlite: { journalMode: "wal", mmapSize: 256 * 1024 * 1024 },
+   * });
+   * ```
    *
    * @tags allow-read, allow-write
    * @category Cloud
    * @experimental
    */
-  export function openKv(path?: string): Promise<Deno.Kv>;
+  export function openKv(
+    path?: string,
+    options?: KvOpenOptions,
+  ): Promise<Deno.Kv>;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options for {@linkcode Deno.openKv}.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface KvOpenOptions {
+    /** Options for databases backed by a local SQLite file. Setting these
+     * when opening a remote database is an error. */
+    sqlite?: {
+      /** The SQLite `journal_mode`.
+       *
+       * @default {"wal"} */
+      journalMode?:
+        | "delete"
+        | "truncate"
+        | "persist"
+        | "memory"
+        | "wal"
+        | "off";
+      /** The SQLite `mmap_size` in bytes. */
+      mmapSize?: number;
+      /** How long to wait for a locked database, in milliseconds. */
+      busyTimeout?: number;
+      /** How often keys whose `expireIn` has passed are deleted from the
+       * database file, in milliseconds. Expired keys are never returned by
+       * reads, but they keep taking up space until they are swept. Set to `0`
+       * to disable the sweeps. Databases opened with `":memory:"` are not
+       * swept.
+       *
+       * @default {60000} */
+      expirySweepInterval?: number;
+    };
+    /** Limits enforced on atomic operations, including `set()` and
+     * `enqueue()`. Each limit may be lowered or raised up to a fixed cap. The
+     * server of a remote database may enforce lower limits of its own. */
+    limits?: {
+      /** The maximum number of mutations and enqueues in one atomic
+       * operation, at most 10,000.
+       *
+       * @default {1000} */
+      maxMutations?: number;
+      /** The maximum size of a single value or enqueued message in bytes, at
+       * most 1 MiB.
+       *
+       * @default {65536} */
+      maxValueSize?: number;
+      /** The maximum total size of the keys written by one atomic operation
+       * in bytes, at most 8 MiB.
+       *
+       * @default {81920} */
+      maxTotalKeySize?: number;
+      /** The maximum total size of the keys, values and enqueued messages of
+       * one atomic operation in bytes, at most 64 MiB.
+       *
+       * @default {819200} */
+      maxTotalMutationSize?: number;
+    };
+  }
 
   /** **UNSTABLE**: New API, yet to be vetted.
    *
@@ -1352,7 +1766,19 @@ declare namespace Deno {
    * executions. Each element in the array represents the number of milliseconds
    * to wait before retrying the execution. For example, `[1000, 5000, 10000]`
    * means that a failed execution will be retried at most 3 times, with 1
-   * second, 5 seconds, and 10 seconds delay between each retry.
+   * second, 5 seconds, and 10 seconds delay between each retry. It can also be
+   * given as a {@linkcode CronExponentialBackoff}, ex.
+   * `{ retries: 3, initialDelay: 1000 }` for delays of 1, 2 and 4 seconds.
+   *
+   * `overlap` option controls what happens when the cron is due while a
+   * previous execution is still running:
+   *
+   * - `"skip"` (default): the tick is skipped, and the next execution is
+   *   scheduled once the running one finishes.
+   * - `"queue"`: another execution starts as soon as the running one
+   *   finishes. Several missed ticks result in a single execution.
+   * - `"concurrent"`: a new execution starts on every tick. Executions are
+   *   independent, so `backoffSchedule` can't be used with this policy.
    *
    * @category Cloud
    * @experimental
@@ -1360,10 +1786,35 @@ declare namespace Deno {
   export function cron(
     name: string,
     schedule: string | CronSchedule,
-    options: { backoffSchedule?: number[]; signal?: AbortSignal },
+    options: {
+      backoffSchedule?: number[] | CronExponentialBackoff;
+      overlap?: "skip" | "queue" | "concurrent";
+      signal?: AbortSignal;
+    },
     handler: () => Promise<void> | void,
   ): Promise<void>;
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * An exponential retry policy for {@linkcode Deno.cron}. The delay before
+   * the first retry is `initialDelay`, and each following delay is multiplied
+   * by `factor`, up to `maxDelay`. Delays are in milliseconds.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface CronExponentialBackoff {
+    /** The number of retries, at most 5. */
+    retries: number;
+    initialDelay: number;
+    /** @default {2} */
+    factor?: number;
+    /** At most one hour.
+     *
+     * @default {3600000} */
+    maxDelay?: number;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * A key to be persisted in a {@linkcode Deno.Kv}. A key is a sequence
@@ -1741,6 +2192,7 @@ declare namespace Deno {
         delay?: number;
         keysIfUndelivered?: Deno.KvKey[];
         backoffSchedule?: number[];
+        maxAttempts?: number;
       },
     ): this;
     /**
@@ -1884,6 +2336,107 @@ declare namespace Deno {
      */
     delete(key: KvKey): Promise<void>;
 
+    /**
+     * Store a binary value that may be larger than the maximum value size of
+     * the database. The value is split into chunks of at most `maxValueSize`
+     * bytes that are written by a single atomic operation, under the keys
+     * `[...key, 0n]`, `[...key, 1n]`, and so on. Other entries should not be
+     * stored directly under `key` with a bigint as the last key part.
+     *
+     * The size of a chunked value is still bounded by the `maxMutations` and
+     * `maxTotalMutationSize` limits of the database, see
+     * {@linkcode Deno.KvOpenOptions}. If other writes to the same value keep
+     * getting in between, the write is given up after a few attempts and the
+     * returned promise rejects.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const data = await Deno.readFile("./dump.bin");
+     * await db.setChunked(["dumps", "latest"], data);
+     * ```
+     */
+    setChunked(
+      key: KvKey,
+      value: Uint8Array,
+      options?: { expireIn?: number },
+    ): Promise<KvCommitResult>;
+
+    /**
+     * Retrieve a value stored with {@linkcode Deno.Kv.setChunked}. If no
+     * value exists for the key, the returned entry will have a `null` value
+     * and versionstamp.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const result = await db.getChunked(["dumps", "latest"]);
+     * result.value; // Uint8Array or null
+     * ```
+     */
+    getChunked(
+      key: KvKey,
+      options?: { consistency?: KvConsistencyLevel },
+    ): Promise<KvEntryMaybe<Uint8Array>>;
+
+    /**
+     * Delete a value stored with {@linkcode Deno.Kv.setChunked}, including all
+     * of its chunks. If no value exists for the key, this operation is a
+     * no-op.
+     */
+    deleteChunked(key: KvKey): Promise<void>;
+
+    /**
+     * Store a binary value read from a `Uint8Array`, a `Blob` or a
+     * `ReadableStream` under the given key. The value is stored in the same
+     * format as {@linkcode Deno.Kv.setChunked}, so it can also be read with
+     * {@linkcode Deno.Kv.getChunked}, and is subject to the same limits.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const file = await Deno.open("./avatar.png");
+     * await db.setBlob(["avatars", "alice"], file.readable);
+     * ```
+     */
+    setBlob(
+      key: KvKey,
+      value: Uint8Array | Blob | ReadableStream<Uint8Array>,
+      options?: { expireIn?: number },
+    ): Promise<KvCommitResult>;
+
+    /**
+     * Retrieve a blob stored with {@linkcode Deno.Kv.setBlob} as a single
+     * `Uint8Array`. If no value exists for the key, the returned entry will
+     * have a `null` value and versionstamp.
+     */
+    getBlob(
+      key: KvKey,
+      options?: { consistency?: KvConsistencyLevel },
+    ): Promise<KvEntryMaybe<Uint8Array>>;
+
+    /**
+     * Retrieve a blob stored with {@linkcode Deno.Kv.setBlob} as a stream of
+     * its chunks. All chunks are read by the time the promise resolves, so
+     * the stream is not affected by later writes to the key. Resolves to
+     * `null` if no value exists for the key.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const avatar = await db.getBlobStream(["avatars", "alice"]);
+     * if (avatar) {
+     *   return new Response(avatar);
+     * }
+     * ```
+     */
+    getBlobStream(
+      key: KvKey,
+      options?: { consistency?: KvConsistencyLevel },
+    ): Promise<ReadableStream<Uint8Array> | null>;
+
+    /**
+     * Delete a blob stored with {@linkcode Deno.Kv.setBlob}, including all of
+     * its chunks. If no value exists for the key, this operation is a no-op.
+     */
+    deleteBlob(key: KvKey): Promise<void>;
+
     /**
      * Retrieve a list of keys in the database. The returned list is an
      * {@linkcode Deno.KvListIterator} which can be used to iterate over the
@@ -1965,6 +2518,20 @@ declare namespace Deno {
      *   backoffSchedule: [1000, 5000, 10000],
      * });
      * ```
+     *
+     * The `maxAttempts` option limits the number of delivery attempts,
+     * including the first one, to at most 6. It keeps the first
+     * `maxAttempts - 1` intervals of `backoffSchedule`, or of the default
+     * schedule of `[100, 1000, 5000, 30000, 60000]`. Combined with
+     * `keysIfUndelivered`, this acts as a dead-letter queue:
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * await db.enqueue({ job: "resize" }, {
+     *   maxAttempts: 3,
+     *   keysIfUndelivered: [["dead_letters", crypto.randomUUID()]],
+     * });
+     * ```
      */
     enqueue(
       value: unknown,
@@ -1972,6 +2539,7 @@ declare namespace Deno {
         delay?: number;
         keysIfUndelivered?: Deno.KvKey[];
         backoffSchedule?: number[];
+        maxAttempts?: number;
       },
     ): Promise<KvCommitResult>;
 
@@ -1988,9 +2556,26 @@ declare namespace Deno {
      *   await db.set(["foo"], msg);
      * });
      * ```
+     *
+     * The `visibilityTimeout` option is the number of milliseconds, at most
+     * one hour, an asynchronous handler may take. A delivery whose handler
+     * takes longer counts as failed and is retried like any other failed
+     * delivery, while the handler keeps running. The `batchSize` option
+     * limits how many values are handled at once; no further values are
+     * dequeued until a handler finishes. By default there is no limit.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * db.listenQueue(async (msg: unknown) => {
+     *   await db.set(["jobs", "last"], msg);
+     * }, { visibilityTimeout: 30_000, batchSize: 10 });
+     * ```
      */
-    // deno-lint-ignore no-explicit-any
-    listenQueue(handler: (value: any) => Promise<void> | void): Promise<void>;
+    listenQueue(
+      // deno-lint-ignore no-explicit-any
+      handler: (value: any) => Promise<void> | void,
+      options?: { visibilityTimeout?: number; batchSize?: number },
+    ): Promise<void>;
 
     /**
      * Create a new {@linkcode Deno.AtomicOperation} object which can be used to
@@ -2043,6 +2628,93 @@ declare namespace Deno {
       options?: { raw?: boolean },
     ): ReadableStream<{ [K in keyof T]: KvEntryMaybe<T[K]> }>;
 
+    /**
+     * Watch for changes to all keys under the given prefix. The returned
+     * stream emits a {@linkcode Deno.KvWatchBatch} containing every entry
+     * under the prefix that was set or deleted since the previous batch,
+     * ordered by key. Deleted entries have a `null` value and versionstamp.
+     *
+     * The first batch is emitted right away and contains all entries currently
+     * under the prefix, or only those changed after `options.resumeToken` when
+     * it is given. Each batch carries a `resumeToken` that can be passed to a
+     * later call to continue watching without missing any sets, for example
+     * after a reconnect. Deletions that happen while no watcher is running are
+     * not reported on resume.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     *
+     * let resumeToken = localStorage.getItem("feedToken") ?? undefined;
+     * const stream = db.watch({ prefix: ["feed"] }, { resumeToken });
+     * for await (const batch of stream) {
+     *   for (const entry of batch.changes) {
+     *     console.log(entry.key, entry.value);
+     *   }
+     *   localStorage.setItem("feedToken", batch.resumeToken);
+     * }
+     * ```
+     *
+     * Prefix watches poll the database, and at most 10,000 entries may exist
+     * under a watched prefix.
+     */
+    watch<T = unknown>(
+      selector: KvWatchPrefixSelector,
+      options?: { resumeToken?: string },
+    ): ReadableStream<KvWatchBatch<T>>;
+
+    /**
+     * Subscribe to the atomic operations committed to this database. The
+     * returned stream emits a {@linkcode Deno.KvCommit} for every successful
+     * commit made from now on, in the order they complete, including the
+     * mutations made by `set()`, `delete()` and atomic operations. This can
+     * be used to replicate the database into an external system.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     *
+     * for await (const commit of db.changes()) {
+     *   for (const change of commit.changes) {
+     *     console.log(commit.versionstamp, change.type, change.key);
+     *   }
+     * }
+     * ```
+     *
+     * Only commits made by the current process are observed, including those
+     * made by its workers. The stream errors if the consumer falls more than
+     * 1024 commits behind.
+     */
+    changes(): ReadableStream<KvCommit>;
+
+    /**
+     * Take a snapshot of an in-memory database opened with
+     * `Deno.openKv(":memory:")`, including its queued messages. The snapshot
+     * can later be restored with {@linkcode Deno.Kv.restore}, which makes it
+     * cheap to reset a database to a known state between tests.
+     *
+     * ```ts
+     * const db = await Deno.openKv(":memory:");
+     * await db.set(["users", "alice"], { name: "Alice" });
+     * using fixture = await db.snapshot();
+     *
+     * await db.delete(["users", "alice"]);
+     * await db.restore(fixture);
+     * // ["users", "alice"] is back
+     * ```
+     *
+     * Rejects with a `TypeError` if the database is not in-memory.
+     */
+    snapshot(): Promise<KvSnapshot>;
+
+    /**
+     * Replace the contents of this in-memory database with a snapshot taken
+     * by {@linkcode Deno.Kv.snapshot}, possibly from another in-memory
+     * database. Entries keep the versionstamps they had in the snapshot.
+     *
+     * Watches and queue listeners are not notified of the changes made by a
+     * restore.
+     */
+    restore(snapshot: KvSnapshot): Promise<void>;
+
     /**
      * Close the database connection. This will prevent any further operations
      * from being performed on the database, and interrupt any in-flight
@@ -2061,6 +2733,84 @@ declare namespace Deno {
     [Symbol.dispose](): void;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A selector for {@linkcode Deno.Kv.prototype.watch} that watches all keys
+   * under the given prefix.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface KvWatchPrefixSelector {
+    prefix: KvKey;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A set of changes emitted by a prefix watch, along with a token to resume
+   * watching from after this batch.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface KvWatchBatch<T = unknown> {
+    changes: KvEntryMaybe<T>[];
+    resumeToken: string;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * An atomic operation committed to a {@linkcode Deno.Kv}, as emitted by
+   * {@linkcode Deno.Kv.prototype.changes}.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface KvCommit {
+    /** The versionstamp of the commit. */
+    versionstamp: string;
+    /** The mutations of the commit, in the order they were made. */
+    changes: KvChange[];
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A single mutation of a {@linkcode Deno.KvCommit}. `value` is the operand
+   * of the mutation, or `null` for deletions. For `sum`, `min` and `max` it
+   * is the operand rather than the resulting value. `expireAt` is the time in
+   * milliseconds since the epoch at which a set key expires.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface KvChange {
+    key: KvKey;
+    type:
+      | "set"
+      | "delete"
+      | "sum"
+      | "min"
+      | "max"
+      | "setSuffixVersionstampedKey";
+    value: unknown;
+    expireAt?: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A copy of an in-memory {@linkcode Deno.Kv}, taken by
+   * {@linkcode Deno.Kv.prototype.snapshot}. The copy is held in memory until
+   * the snapshot is closed.
+   *
+   * @category Cloud
+   * @experimental
+   */
+  export interface KvSnapshot {
+    /** Release the memory held by this snapshot. */
+    close(): void;
+    [Symbol.dispose](): void;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Wrapper type for 64-bit unsigned integers for use as values in a
@@ -2291,6 +3041,61 @@ declare function fetch(
   init?: RequestInit & { client: Deno.HttpClient },
 ): Promise<Response>;
 
+/** **UNSTABLE**: New API, yet to be vetted.
+ *
+ * @category Fetch
+ * @experimental
+ */
+declare interface RequestInit {
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Called as the request body is sent, with the number of bytes handed to
+   * the connection so far and, if known, the total size of the body. Bytes
+   * are only counted once the connection is ready to send them, so a slow
+   * peer slows down the reported progress as well.
+   *
+   * @experimental
+   */
+  onUploadProgress?: (progress: { loaded: number; total?: number }) => void;
+}
+
+/** **UNSTABLE**: New API, yet to be vetted.
+ *
+ * @category Fetch
+ * @experimental
+ */
+declare interface ResponseInit {
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Trailers to send after the response body when the response is returned
+   * from a {@linkcode Deno.serve} handler. Passing a promise allows the
+   * trailers to depend on the body, for example to report a gRPC status once
+   * the body stream has finished.
+   *
+   * Over HTTP/1.1, trailers are only sent if the response is chunked and the
+   * client sent `TE: trailers`.
+   *
+   * @experimental
+   */
+  trailers?: HeadersInit | Promise<HeadersInit>;
+}
+
+/** **UNSTABLE**: New API, yet to be vetted.
+ *
+ * @category Fetch
+ * @experimental
+ */
+declare interface Response {
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Resolves with the trailers of the response once its body has been fully
+   * read. Resolves with empty headers if the response has no trailers.
+   *
+   * @experimental
+   */
+  readonly trailers: Promise<Headers>;
+}
+
 /** **UNSTABLE**: New API, yet to be vetted.
  *
  * @category Workers
@@ -2327,8 +3132,9 @@ declare interface WorkerOptions {
    * ```
    */
   deno?: {
-    /** Set to `"none"` to disable all the permissions in the worker. */
-    permissions?: Deno.PermissionOptions;
+    /** Set to `"none"` to disable all the permissions in the worker, or to
+     * a token from `Deno.createCapabilityToken()`. */
+    permissions?: Deno.PermissionOptions | Deno.CapabilityToken;
   };
 }
 
@@ -2341,6 +3147,9 @@ declare interface WebSocketStreamOptions {
   protocols?: string[];
   signal?: AbortSignal;
   headers?: HeadersInit;
+  /** An HTTP client used to open the connection, to connect through its
+   * proxy or with its CA certificates and client certificate. */
+  client?: Deno.HttpClient;
 }
 
 /** **UNSTABLE**: New API, yet to be vetted.
@@ -2353,6 +3162,8 @@ declare interface WebSocketConnection {
   writable: WritableStream<string | Uint8Array>;
   extensions: string;
   protocol: string;
+  /** The headers of the server's handshake response. */
+  headers: Headers;
 }
 
 /** **UNSTABLE**: New API, yet to be vetted.
@@ -2,6 +2,12 @@
 
 pub mod convert;
 
+/// V8 flags added by `--enforce-wx`. Without its JIT compilers, V8 doesn't
+/// generate code at runtime, so it never needs memory that is writable and
+/// executable. This also disables WebAssembly, which V8 can only compile, and
+/// makes JavaScript slower as it's only interpreted.
+pub const ENFORCE_WX_V8_FLAGS: &[&str] = &["--jitless"];
+
 #[inline(always)]
 pub fn get_v8_flags_from_env() -> Vec<String> {
   std::env::var("DENO_V8_FLAGS")
@@ -21,6 +21,7 @@ use deno_core::SharedArrayBufferStore;
 use deno_runtime::code_cache;
 use deno_runtime::deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_runtime::deno_fs;
+use deno_runtime::deno_kv::changes::KvChangeFeed;
 use deno_runtime::deno_node;
 use deno_runtime::deno_node::NodeResolution;
 use deno_runtime::deno_node::NodeResolutionMode;
@@ -28,6 +29,7 @@ use deno_runtime::deno_node::NodeResolver;
 use deno_runtime::deno_permissions::PermissionsContainer;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_web::BlobStore;
+use deno_runtime::deno_webstorage::LocalStorageLocation;
 use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::inspector_server::InspectorServer;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
@@ -109,6 +111,12 @@ pub struct CliMainWorkerOptions {
   pub argv0: Option<String>,
   pub node_debug: Option<String>,
   pub origin_data_folder_path: Option<PathBuf>,
+  pub cache_storage_max_size: Option<u64>,
+  pub local_storage_location: Option<LocalStorageLocation>,
+  pub web_storage_max_size: Option<usize>,
+  /// Relay `BroadcastChannel` messages to other processes through this unix
+  /// socket.
+  pub broadcast_channel_socket: Option<PathBuf>,
   pub seed: Option<u64>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub unstable: bool,
@@ -125,6 +133,7 @@ struct SharedWorkerState {
   node_resolver: Arc<NodeResolver>,
   blob_store: Arc<BlobStore>,
   broadcast_channel: InMemoryBroadcastChannel,
+  kv_change_feed: KvChangeFeed,
   shared_array_buffer_store: SharedArrayBufferStore,
   compiled_wasm_module_store: CompiledWasmModuleStore,
   module_loader_factory: Box<dyn ModuleLoaderFactory>,
@@ -388,6 +397,33 @@ impl CliMainWorker {
   }
 }
 
+fn create_broadcast_channel(
+  socket: Option<&PathBuf>,
+) -> InMemoryBroadcastChannel {
+  let broadcast_channel = InMemoryBroadcastChannel::default();
+  if let Some(socket) = socket {
+    #[cfg(unix)]
+    {
+      let result = broadcast_channel.relay_over_unix_socket(socket.clone());
+      if let Err(err) = result {
+        log::warn!(
+          "{} Failed to relay BroadcastChannel messages over {}: {:#}",
+          colors::yellow("Warning"),
+          socket.display(),
+          err
+        );
+      }
+    }
+    #[cfg(not(unix))]
+    log::warn!(
+      "{} Relaying BroadcastChannel messages over {} requires Unix sockets.",
+      colors::yellow("Warning"),
+      socket.display(),
+    );
+  }
+  broadcast_channel
+}
+
 pub struct CliMainWorkerFactory {
   shared: Arc<SharedWorkerState>,
 }
@@ -416,6 +452,8 @@ impl CliMainWorkerFactory {
     verbose_deprecated_api_warning: bool,
     code_cache: Option<Arc<dyn code_cache::CodeCache>>,
   ) -> Self {
+    let broadcast_channel =
+      create_broadcast_channel(options.broadcast_channel_socket.as_ref());
     Self {
       shared: Arc::new(SharedWorkerState {
         options,
@@ -424,7 +462,8 @@ impl CliMainWorkerFactory {
         npm_resolver,
         node_resolver,
         blob_store,
-        broadcast_channel: Default::default(),
+        broadcast_channel,
+        kv_change_feed: Default::default(),
         shared_array_buffer_store: Default::default(),
         compiled_wasm_module_store: Default::default(),
         module_loader_factory,
@@ -603,7 +642,13 @@ impl CliMainWorkerFactory {
       npm_resolver: Some(shared.npm_resolver.clone().into_npm_resolver()),
       get_error_class_fn: Some(&errors::get_error_class_name),
       cache_storage_dir,
+      cache_storage_max_size: shared.options.cache_storage_max_size,
+      cache_backend: None,
       origin_storage_dir,
+      local_storage_location: shared.options.local_storage_location.clone(),
+      web_storage_max_size: shared.options.web_storage_max_size,
+      kv_backends: Vec::new(),
+      kv_change_feed: shared.kv_change_feed.clone(),
       blob_store: shared.blob_store.clone(),
       broadcast_channel: shared.broadcast_channel.clone(),
       shared_array_buffer_store: Some(shared.shared_array_buffer_store.clone()),
@@ -806,6 +851,10 @@ fn create_web_worker_callback(
       ),
       stdio: stdio.clone(),
       cache_storage_dir,
+      cache_storage_max_size: shared.options.cache_storage_max_size,
+      cache_backend: None,
+      kv_backends: Vec::new(),
+      kv_change_feed: shared.kv_change_feed.clone(),
       feature_checker,
       strace_ops: shared.options.strace_ops.clone(),
       close_on_idle: args.close_on_idle,
@@ -12,7 +12,9 @@ use uuid::Uuid;
 use crate::BroadcastChannel;
 
 #[derive(Clone)]
-pub struct InMemoryBroadcastChannel(Arc<Mutex<broadcast::Sender<Message>>>);
+pub struct InMemoryBroadcastChannel(
+  pub(crate) Arc<Mutex<broadcast::Sender<Message>>>,
+);
 
 pub struct InMemoryBroadcastChannelResource {
   rx: tokio::sync::Mutex<(
@@ -24,10 +26,11 @@ pub struct InMemoryBroadcastChannelResource {
 }
 
 #[derive(Clone, Debug)]
-struct Message {
-  name: Arc<String>,
-  data: Arc<Vec<u8>>,
-  uuid: Uuid,
+pub(crate) struct Message {
+  pub(crate) name: Arc<String>,
+  pub(crate) data: Arc<Vec<u8>>,
+  /// The resource that sent the message.
+  pub(crate) uuid: Uuid,
 }
 
 impl Default for InMemoryBroadcastChannel {
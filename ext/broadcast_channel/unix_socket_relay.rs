@@ -0,0 +1,319 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Relays the messages of an [`InMemoryBroadcastChannel`] to the other
+//! processes on the same machine through a unix socket.
+//!
+//! The process holding the lock file next to the socket is the hub: it listens
+//! on the socket and forwards every message to all other connected processes.
+//! The other processes connect to the hub, and elect a new one if it exits.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use deno_core::error::AnyError;
+use fs3::FileExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::in_memory_broadcast_channel::Message;
+use crate::InMemoryBroadcastChannel;
+
+/// Messages larger than this are not relayed to other processes.
+const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+impl InMemoryBroadcastChannel {
+  /// Starts relaying the messages of this channel to every other process that
+  /// relays to the same socket `path`, so that `BroadcastChannel`s of the same
+  /// name receive each other's messages across processes. A lock file with
+  /// the `.lock` extension is created next to the socket.
+  ///
+  /// The relay runs on its own thread for the rest of the process.
+  pub fn relay_over_unix_socket(&self, path: PathBuf) -> Result<(), AnyError> {
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    let relay = Relay {
+      channel: self.clone(),
+      uuid: Uuid::new_v4(),
+      lock_path: path.with_extension("lock"),
+      path,
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_all()
+      .build()?;
+    std::thread::Builder::new()
+      .name("broadcast-channel-relay".to_string())
+      .spawn(move || runtime.block_on(relay.run()))?;
+    Ok(())
+  }
+}
+
+#[derive(Clone)]
+struct Frame {
+  /// The connection the frame was received from, `0` for the local channel.
+  origin: u64,
+  name: Arc<String>,
+  data: Arc<Vec<u8>>,
+}
+
+struct Relay {
+  channel: InMemoryBroadcastChannel,
+  /// Identifies the messages that the relay delivered to the local channel,
+  /// which must not be relayed back.
+  uuid: Uuid,
+  path: PathBuf,
+  lock_path: PathBuf,
+}
+
+impl Relay {
+  async fn run(self) {
+    loop {
+      // Any error means the hub went away or isn't listening yet, so the
+      // election is simply retried.
+      let _ = match self.try_lock_hub() {
+        Ok(Some(lock)) => self.serve_hub(lock).await,
+        Ok(None) => match UnixStream::connect(&self.path).await {
+          Ok(stream) => self.serve_client(stream).await,
+          Err(err) => Err(err),
+        },
+        Err(err) => Err(err),
+      };
+      tokio::time::sleep(RETRY_DELAY).await;
+    }
+  }
+
+  fn try_lock_hub(&self) -> io::Result<Option<std::fs::File>> {
+    let file = std::fs::OpenOptions::new()
+      .create(true)
+      .truncate(false)
+      .write(true)
+      .open(&self.lock_path)?;
+    match file.try_lock_exclusive() {
+      Ok(()) => Ok(Some(file)),
+      Err(err)
+        if err.raw_os_error() == fs3::lock_contended_error().raw_os_error() =>
+      {
+        Ok(None)
+      }
+      Err(err) => Err(err),
+    }
+  }
+
+  /// Listens on the socket while holding the hub `_lock`.
+  async fn serve_hub(&self, _lock: std::fs::File) -> io::Result<()> {
+    // a socket left behind by a previous hub can't be bound over
+    match std::fs::remove_file(&self.path) {
+      Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+      _ => {}
+    }
+    let listener = UnixListener::bind(&self.path)?;
+    let (frames_tx, _) = broadcast::channel::<Frame>(256);
+    // dropping the set aborts the connections if the hub fails
+    let mut connections = JoinSet::new();
+    let mut next_origin = 1;
+
+    let mut local_rx = self.channel.0.lock().subscribe();
+    let forward_local = async {
+      loop {
+        match local_rx.recv().await {
+          Ok(message) if message.uuid != self.uuid => {
+            let _ = frames_tx.send(Frame {
+              origin: 0,
+              name: message.name,
+              data: message.data,
+            });
+          }
+          Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+          Err(broadcast::error::RecvError::Closed) => {
+            return Ok::<(), io::Error>(())
+          }
+        }
+      }
+    };
+    let accept = async {
+      loop {
+        let (stream, _) = match listener.accept().await {
+          Ok(connection) => connection,
+          Err(err) => return Err::<(), io::Error>(err),
+        };
+        let origin = next_origin;
+        next_origin += 1;
+        let channel = self.channel.clone();
+        let uuid = self.uuid;
+        let frames_tx = frames_tx.clone();
+        connections.spawn(serve_hub_connection(
+          stream, origin, channel, uuid, frames_tx,
+        ));
+      }
+    };
+    tokio::select! {
+      result = forward_local => result,
+      result = accept => result,
+    }
+  }
+
+  async fn serve_client(&self, stream: UnixStream) -> io::Result<()> {
+    let (mut reader, mut writer) = stream.into_split();
+    let mut local_rx = self.channel.0.lock().subscribe();
+    let incoming = async {
+      while let Some((name, data)) = read_frame(&mut reader).await? {
+        deliver(&self.channel, self.uuid, Arc::new(name), Arc::new(data));
+      }
+      Ok::<(), io::Error>(())
+    };
+    let outgoing = async {
+      loop {
+        match local_rx.recv().await {
+          Ok(message) if message.uuid != self.uuid => {
+            write_frame(&mut writer, &message.name, &message.data).await?;
+          }
+          Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+          Err(broadcast::error::RecvError::Closed) => {
+            return Ok::<(), io::Error>(())
+          }
+        }
+      }
+    };
+    tokio::select! {
+      result = incoming => result,
+      result = outgoing => result,
+    }
+  }
+}
+
+/// Relays between the hub and the process connected through `stream`.
+async fn serve_hub_connection(
+  stream: UnixStream,
+  origin: u64,
+  channel: InMemoryBroadcastChannel,
+  uuid: Uuid,
+  frames_tx: broadcast::Sender<Frame>,
+) -> io::Result<()> {
+  let (mut reader, mut writer) = stream.into_split();
+  let mut frames_rx = frames_tx.subscribe();
+  let incoming = async {
+    while let Some((name, data)) = read_frame(&mut reader).await? {
+      let frame = Frame {
+        origin,
+        name: Arc::new(name),
+        data: Arc::new(data),
+      };
+      deliver(&channel, uuid, frame.name.clone(), frame.data.clone());
+      let _ = frames_tx.send(frame);
+    }
+    Ok::<(), io::Error>(())
+  };
+  let outgoing = async {
+    loop {
+      match frames_rx.recv().await {
+        Ok(frame) if frame.origin != origin => {
+          write_frame(&mut writer, &frame.name, &frame.data).await?;
+        }
+        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+        Err(broadcast::error::RecvError::Closed) => {
+          return Ok::<(), io::Error>(())
+        }
+      }
+    }
+  };
+  tokio::select! {
+    result = incoming => result,
+    result = outgoing => result,
+  }
+}
+
+/// Delivers a message from another process to the subscribers of `channel`.
+fn deliver(
+  channel: &InMemoryBroadcastChannel,
+  uuid: Uuid,
+  name: Arc<String>,
+  data: Arc<Vec<u8>>,
+) {
+  // there may be no subscribers in this process
+  let _ = channel.0.lock().send(Message { name, data, uuid });
+}
+
+async fn read_frame(
+  reader: &mut (impl AsyncRead + Unpin),
+) -> io::Result<Option<(String, Vec<u8>)>> {
+  let name_len = match reader.read_u32().await {
+    Ok(len) => len as usize,
+    Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+    Err(err) => return Err(err),
+  };
+  let data_len = reader.read_u32().await? as usize;
+  if name_len + data_len > MAX_FRAME_SIZE {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      "broadcast channel message too large",
+    ));
+  }
+  let mut name = vec![0; name_len];
+  reader.read_exact(&mut name).await?;
+  let mut data = vec![0; data_len];
+  reader.read_exact(&mut data).await?;
+  let name = String::from_utf8(name)
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+  Ok(Some((name, data)))
+}
+
+async fn write_frame(
+  writer: &mut (impl AsyncWrite + Unpin),
+  name: &str,
+  data: &[u8],
+) -> io::Result<()> {
+  if name.len() + data.len() > MAX_FRAME_SIZE {
+    return Ok(());
+  }
+  let mut frame = Vec::with_capacity(8 + name.len() + data.len());
+  frame.extend_from_slice(&(name.len() as u32).to_be_bytes());
+  frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+  frame.extend_from_slice(name.as_bytes());
+  frame.extend_from_slice(data);
+  writer.write_all(&frame).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::BroadcastChannel;
+
+  #[tokio::test]
+  async fn relays_messages_between_channels() {
+    let path = std::env::temp_dir()
+      .join(format!("deno_broadcast_channel_{}.sock", Uuid::new_v4()));
+    let a = InMemoryBroadcastChannel::default();
+    let b = InMemoryBroadcastChannel::default();
+    a.relay_over_unix_socket(path.clone()).unwrap();
+    b.relay_over_unix_socket(path.clone()).unwrap();
+    let resource_a = a.subscribe().unwrap();
+    let resource_b = b.subscribe().unwrap();
+
+    // the relays connect in the background, so send until a message arrives
+    for _ in 0..50 {
+      a.send(&resource_a, "channel".to_string(), vec![1, 2, 3])
+        .await
+        .unwrap();
+      let received =
+        tokio::time::timeout(RETRY_DELAY, b.recv(&resource_b)).await;
+      if let Ok(message) = received {
+        assert_eq!(
+          message.unwrap(),
+          Some(("channel".to_string(), vec![1, 2, 3]))
+        );
+        return;
+      }
+    }
+    panic!("message was not relayed");
+  }
+}
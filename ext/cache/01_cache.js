@@ -7,6 +7,7 @@ import {
   op_cache_storage_delete,
   op_cache_storage_has,
   op_cache_storage_open,
+  op_cache_storage_usage,
 } from "ext:core/ops";
 const {
   ArrayPrototypePush,
@@ -65,6 +66,12 @@ class CacheStorage {
     return await op_cache_storage_delete(cacheName);
   }
 
+  async usage() {
+    webidl.assertBranded(this, CacheStoragePrototype);
+    const { usage, quota, entries } = await op_cache_storage_usage();
+    return { usage, quota: quota ?? undefined, entries };
+  }
+
   [SymbolFor("Deno.privateCustomInspect")](inspect, inspectOptions) {
     return `${this.constructor.name} ${inspect({}, inspectOptions)}`;
   }
@@ -0,0 +1,418 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use deno_core::error::AnyError;
+use deno_core::AsyncResult;
+use deno_core::BufMutView;
+use deno_core::BufView;
+use deno_core::Resource;
+
+use crate::Cache;
+use crate::CacheDeleteRequest;
+use crate::CacheMatchRequest;
+use crate::CacheMatchResponseMeta;
+use crate::CachePutRequest;
+use crate::CacheStorageUsage;
+
+/// A storage backend for the Cache API provided by an embedder, for example
+/// one that stores responses in object storage or a shared cache tier.
+///
+/// Any [`Cache`] can back `caches`; wrap it with [`RcDynamicCache::new`].
+pub trait CacheBackend: Send + Sync {
+  /// Creates the cache used by a new worker.
+  fn create_cache(&self) -> RcDynamicCache;
+}
+
+#[async_trait(?Send)]
+pub trait DynamicCache {
+  async fn dyn_storage_open(&self, cache_name: String)
+    -> Result<i64, AnyError>;
+
+  async fn dyn_storage_has(&self, cache_name: String)
+    -> Result<bool, AnyError>;
+
+  async fn dyn_storage_delete(
+    &self,
+    cache_name: String,
+  ) -> Result<bool, AnyError>;
+
+  async fn dyn_storage_usage(&self) -> Result<CacheStorageUsage, AnyError>;
+
+  async fn dyn_put(
+    &self,
+    request_response: CachePutRequest,
+    resource: Option<Rc<dyn Resource>>,
+  ) -> Result<(), AnyError>;
+
+  async fn dyn_match(
+    &self,
+    request: CacheMatchRequest,
+  ) -> Result<
+    Option<(CacheMatchResponseMeta, Option<Rc<dyn Resource>>)>,
+    AnyError,
+  >;
+
+  async fn dyn_delete(
+    &self,
+    request: CacheDeleteRequest,
+  ) -> Result<bool, AnyError>;
+}
+
+/// A [`Cache`] whose implementation is chosen at runtime.
+#[derive(Clone)]
+pub struct RcDynamicCache(Rc<dyn DynamicCache>);
+
+impl RcDynamicCache {
+  pub fn new<C: Cache>(cache: C) -> Self {
+    Self(Rc::new(cache))
+  }
+}
+
+/// The body of a response matched by an [`RcDynamicCache`].
+pub struct DynamicCacheMatchResource(Rc<dyn Resource>);
+
+impl Resource for DynamicCacheMatchResource {
+  fn name(&self) -> Cow<str> {
+    self.0.name()
+  }
+
+  fn read(self: Rc<Self>, limit: usize) -> AsyncResult<BufView> {
+    self.0.clone().read(limit)
+  }
+
+  fn read_byob(
+    self: Rc<Self>,
+    buf: BufMutView,
+  ) -> AsyncResult<(usize, BufMutView)> {
+    self.0.clone().read_byob(buf)
+  }
+
+  fn close(self: Rc<Self>) {
+    self.0.clone().close()
+  }
+
+  fn size_hint(&self) -> (u64, Option<u64>) {
+    self.0.size_hint()
+  }
+}
+
+#[async_trait(?Send)]
+impl Cache for RcDynamicCache {
+  type CacheMatchResourceType = DynamicCacheMatchResource;
+
+  async fn storage_open(&self, cache_name: String) -> Result<i64, AnyError> {
+    self.0.dyn_storage_open(cache_name).await
+  }
+
+  async fn storage_has(&self, cache_name: String) -> Result<bool, AnyError> {
+    self.0.dyn_storage_has(cache_name).await
+  }
+
+  async fn storage_delete(&self, cache_name: String) -> Result<bool, AnyError> {
+    self.0.dyn_storage_delete(cache_name).await
+  }
+
+  async fn storage_usage(&self) -> Result<CacheStorageUsage, AnyError> {
+    self.0.dyn_storage_usage().await
+  }
+
+  async fn put(
+    &self,
+    request_response: CachePutRequest,
+    resource: Option<Rc<dyn Resource>>,
+  ) -> Result<(), AnyError> {
+    self.0.dyn_put(request_response, resource).await
+  }
+
+  async fn r#match(
+    &self,
+    request: CacheMatchRequest,
+  ) -> Result<
+    Option<(CacheMatchResponseMeta, Option<Self::CacheMatchResourceType>)>,
+    AnyError,
+  > {
+    let matched = self.0.dyn_match(request).await?;
+    Ok(
+      matched.map(|(meta, resource)| {
+        (meta, resource.map(DynamicCacheMatchResource))
+      }),
+    )
+  }
+
+  async fn delete(
+    &self,
+    request: CacheDeleteRequest,
+  ) -> Result<bool, AnyError> {
+    self.0.dyn_delete(request).await
+  }
+}
+
+#[async_trait(?Send)]
+impl<T: Cache> DynamicCache for T {
+  async fn dyn_storage_open(
+    &self,
+    cache_name: String,
+  ) -> Result<i64, AnyError> {
+    Ok(self.storage_open(cache_name).await?)
+  }
+
+  async fn dyn_storage_has(
+    &self,
+    cache_name: String,
+  ) -> Result<bool, AnyError> {
+    Ok(self.storage_has(cache_name).await?)
+  }
+
+  async fn dyn_storage_delete(
+    &self,
+    cache_name: String,
+  ) -> Result<bool, AnyError> {
+    Ok(self.storage_delete(cache_name).await?)
+  }
+
+  async fn dyn_storage_usage(&self) -> Result<CacheStorageUsage, AnyError> {
+    Ok(self.storage_usage().await?)
+  }
+
+  async fn dyn_put(
+    &self,
+    request_response: CachePutRequest,
+    resource: Option<Rc<dyn Resource>>,
+  ) -> Result<(), AnyError> {
+    Ok(self.put(request_response, resource).await?)
+  }
+
+  async fn dyn_match(
+    &self,
+    request: CacheMatchRequest,
+  ) -> Result<
+    Option<(CacheMatchResponseMeta, Option<Rc<dyn Resource>>)>,
+    AnyError,
+  > {
+    let matched = self.r#match(request).await?;
+    Ok(matched.map(|(meta, resource)| {
+      (meta, resource.map(|r| Rc::new(r) as Rc<dyn Resource>))
+    }))
+  }
+
+  async fn dyn_delete(
+    &self,
+    request: CacheDeleteRequest,
+  ) -> Result<bool, AnyError> {
+    Ok(self.delete(request).await?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+  use std::collections::HashMap;
+
+  use deno_core::ByteString;
+
+  use super::*;
+
+  struct BodyResource(RefCell<Vec<u8>>);
+
+  impl Resource for BodyResource {
+    fn name(&self) -> Cow<str> {
+      "body".into()
+    }
+
+    fn read(self: Rc<Self>, _limit: usize) -> AsyncResult<BufView> {
+      let body = std::mem::take(&mut *self.0.borrow_mut());
+      Box::pin(async move { Ok(BufView::from(body)) })
+    }
+  }
+
+  struct MemoryEntry {
+    status: u16,
+    status_text: String,
+    response_headers: Vec<(ByteString, ByteString)>,
+    body: Vec<u8>,
+  }
+
+  /// A cache that keeps responses in memory, as an embedder could.
+  #[derive(Clone, Default)]
+  struct MemoryCache {
+    names: Rc<RefCell<Vec<String>>>,
+    entries: Rc<RefCell<HashMap<(i64, String), MemoryEntry>>>,
+  }
+
+  struct MemoryCacheBackend;
+
+  impl CacheBackend for MemoryCacheBackend {
+    fn create_cache(&self) -> RcDynamicCache {
+      RcDynamicCache::new(MemoryCache::default())
+    }
+  }
+
+  #[async_trait(?Send)]
+  impl Cache for MemoryCache {
+    type CacheMatchResourceType = BodyResource;
+
+    async fn storage_open(&self, cache_name: String) -> Result<i64, AnyError> {
+      let mut names = self.names.borrow_mut();
+      let index = match names.iter().position(|name| *name == cache_name) {
+        Some(index) => index,
+        None => {
+          names.push(cache_name);
+          names.len() - 1
+        }
+      };
+      Ok(index as i64)
+    }
+
+    async fn storage_has(&self, cache_name: String) -> Result<bool, AnyError> {
+      Ok(self.names.borrow().contains(&cache_name))
+    }
+
+    async fn storage_delete(
+      &self,
+      cache_name: String,
+    ) -> Result<bool, AnyError> {
+      let mut names = self.names.borrow_mut();
+      let len = names.len();
+      names.retain(|name| *name != cache_name);
+      Ok(names.len() != len)
+    }
+
+    async fn storage_usage(&self) -> Result<CacheStorageUsage, AnyError> {
+      let entries = self.entries.borrow();
+      Ok(CacheStorageUsage {
+        usage: entries.values().map(|entry| entry.body.len() as u64).sum(),
+        quota: None,
+        entries: entries.len() as u64,
+      })
+    }
+
+    async fn put(
+      &self,
+      request_response: CachePutRequest,
+      resource: Option<Rc<dyn Resource>>,
+    ) -> Result<(), AnyError> {
+      let mut body = Vec::new();
+      if let Some(resource) = resource {
+        loop {
+          let chunk = resource.clone().read(64 * 1024).await?;
+          if chunk.is_empty() {
+            break;
+          }
+          body.extend_from_slice(&chunk);
+        }
+      }
+      self.entries.borrow_mut().insert(
+        (request_response.cache_id, request_response.request_url),
+        MemoryEntry {
+          status: request_response.response_status,
+          status_text: request_response.response_status_text,
+          response_headers: request_response.response_headers,
+          body,
+        },
+      );
+      Ok(())
+    }
+
+    async fn r#match(
+      &self,
+      request: CacheMatchRequest,
+    ) -> Result<Option<(CacheMatchResponseMeta, Option<BodyResource>)>, AnyError>
+    {
+      let entries = self.entries.borrow();
+      let Some(entry) = entries.get(&(request.cache_id, request.request_url))
+      else {
+        return Ok(None);
+      };
+      let meta = CacheMatchResponseMeta {
+        response_status: entry.status,
+        response_status_text: entry.status_text.clone(),
+        request_headers: request.request_headers,
+        response_headers: entry.response_headers.clone(),
+      };
+      let body = BodyResource(RefCell::new(entry.body.clone()));
+      Ok(Some((meta, Some(body))))
+    }
+
+    async fn delete(
+      &self,
+      request: CacheDeleteRequest,
+    ) -> Result<bool, AnyError> {
+      let key = (request.cache_id, request.request_url);
+      Ok(self.entries.borrow_mut().remove(&key).is_some())
+    }
+  }
+
+  fn match_request(cache_id: i64) -> CacheMatchRequest {
+    CacheMatchRequest {
+      cache_id,
+      request_url: "https://deno.land/".to_string(),
+      request_headers: vec![],
+    }
+  }
+
+  fn delete_request(cache_id: i64) -> CacheDeleteRequest {
+    CacheDeleteRequest {
+      cache_id,
+      request_url: "https://deno.land/".to_string(),
+    }
+  }
+
+  #[tokio::test(flavor = "current_thread")]
+  async fn puts_matches_and_deletes_through_backend() {
+    let cache = MemoryCacheBackend.create_cache();
+    let cache_id = cache.storage_open("v1".to_string()).await.unwrap();
+    assert!(cache.storage_has("v1".to_string()).await.unwrap());
+
+    let body = BodyResource(RefCell::new(b"hello".to_vec()));
+    cache
+      .put(
+        CachePutRequest {
+          cache_id,
+          request_url: "https://deno.land/".to_string(),
+          request_headers: vec![],
+          response_headers: vec![(
+            ByteString::from("content-type"),
+            ByteString::from("text/plain"),
+          )],
+          response_status: 200,
+          response_status_text: "OK".to_string(),
+          response_rid: None,
+        },
+        Some(Rc::new(body)),
+      )
+      .await
+      .unwrap();
+    let usage = cache.storage_usage().await.unwrap();
+    assert_eq!((usage.usage, usage.entries), (5, 1));
+
+    let (meta, body) = cache
+      .r#match(match_request(cache_id))
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(meta.response_status, 200);
+    assert_eq!(meta.response_status_text, "OK");
+    assert_eq!(
+      meta.response_headers,
+      vec![(
+        ByteString::from("content-type"),
+        ByteString::from("text/plain"),
+      )]
+    );
+    let body = Rc::new(body.unwrap());
+    assert_eq!(&*body.read(1024).await.unwrap(), b"hello");
+
+    assert!(cache.delete(delete_request(cache_id)).await.unwrap());
+    assert!(cache
+      .r#match(match_request(cache_id))
+      .await
+      .unwrap()
+      .is_none());
+    assert!(!cache.delete(delete_request(cache_id)).await.unwrap());
+    assert!(cache.storage_delete("v1".to_string()).await.unwrap());
+    assert!(!cache.storage_has("v1".to_string()).await.unwrap());
+  }
+}
@@ -16,6 +16,22 @@ declare interface CacheStorage {
   has(cacheName: string): Promise<boolean>;
   /** Delete cache storage for the provided name. */
   delete(cacheName: string): Promise<boolean>;
+  /** Return how much space the entries of all caches take up.
+   *
+   * This API is specific to Deno. */
+  usage(): Promise<CacheStorageUsage>;
+}
+
+/** @category Cache */
+declare interface CacheStorageUsage {
+  /** The total size of the cached requests and responses, in bytes. */
+  usage: number;
+  /** The maximum total size of the cached entries, in bytes. The least
+   * recently used entries are evicted to stay below it. `undefined` if the
+   * size isn't limited. */
+  quota?: number;
+  /** The number of cached entries. */
+  entries: number;
 }
 
 /** @category Cache */
@@ -16,7 +16,12 @@ use deno_core::OpState;
 use deno_core::Resource;
 use deno_core::ResourceId;
 
+mod dynamic;
 mod sqlite;
+pub use dynamic::CacheBackend;
+pub use dynamic::DynamicCache;
+pub use dynamic::DynamicCacheMatchResource;
+pub use dynamic::RcDynamicCache;
 pub use sqlite::SqliteBackedCache;
 
 #[derive(Clone)]
@@ -29,6 +34,7 @@ deno_core::extension!(deno_cache,
     op_cache_storage_open<CA>,
     op_cache_storage_has<CA>,
     op_cache_storage_delete<CA>,
+    op_cache_storage_usage<CA>,
     op_cache_put<CA>,
     op_cache_match<CA>,
     op_cache_delete<CA>,
@@ -88,6 +94,17 @@ pub struct CacheDeleteRequest {
   pub request_url: String,
 }
 
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStorageUsage {
+  /// The total size of the cached entries, in bytes.
+  pub usage: u64,
+  /// The maximum total size of the cached entries, if limited.
+  pub quota: Option<u64>,
+  /// The number of cached entries.
+  pub entries: u64,
+}
+
 #[async_trait(?Send)]
 pub trait Cache: Clone + 'static {
   type CacheMatchResourceType: Resource;
@@ -95,6 +112,7 @@ pub trait Cache: Clone + 'static {
   async fn storage_open(&self, cache_name: String) -> Result<i64, AnyError>;
   async fn storage_has(&self, cache_name: String) -> Result<bool, AnyError>;
   async fn storage_delete(&self, cache_name: String) -> Result<bool, AnyError>;
+  async fn storage_usage(&self) -> Result<CacheStorageUsage, AnyError>;
 
   /// Put a resource into the cache.
   async fn put(
@@ -151,6 +169,18 @@ where
   cache.storage_delete(cache_name).await
 }
 
+#[op2(async)]
+#[serde]
+pub async fn op_cache_storage_usage<CA>(
+  state: Rc<RefCell<OpState>>,
+) -> Result<CacheStorageUsage, AnyError>
+where
+  CA: Cache,
+{
+  let cache = get_cache::<CA>(&state)?;
+  cache.storage_usage().await
+}
+
 #[op2(async)]
 pub async fn op_cache_put<CA>(
   state: Rc<RefCell<OpState>>,
@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 use std::borrow::Cow;
+use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
@@ -33,11 +34,19 @@ use crate::CacheDeleteRequest;
 use crate::CacheMatchRequest;
 use crate::CacheMatchResponseMeta;
 use crate::CachePutRequest;
+use crate::CacheStorageUsage;
+
+/// The size of an entry: its response body and serialized headers.
+const ENTRY_SIZE: &str =
+  "response_body_size + length(request_headers) + length(response_headers)";
 
 #[derive(Clone)]
 pub struct SqliteBackedCache {
   pub connection: Arc<Mutex<Connection>>,
   pub cache_storage_dir: PathBuf,
+  /// The maximum total size of the cached entries in bytes. The least
+  /// recently used entries are evicted to stay below it.
+  pub max_size: Option<u64>,
 }
 
 impl SqliteBackedCache {
@@ -87,12 +96,49 @@ impl SqliteBackedCache {
           (),
         )
         .expect("failed to create request_response_list table");
+      // Columns added after the table was first introduced.
+      for (column, definition) in [
+        ("response_body_size", "INTEGER NOT NULL DEFAULT 0"),
+        ("last_accessed_at", "INTEGER NOT NULL DEFAULT 0"),
+      ] {
+        add_column_if_missing(&connection, column, definition)
+          .expect("failed to migrate request_response_list table");
+      }
       SqliteBackedCache {
         connection: Arc::new(Mutex::new(connection)),
         cache_storage_dir,
+        max_size: None,
       }
     }
   }
+
+  /// Limits the total size of the cached entries, see
+  /// [`SqliteBackedCache::max_size`].
+  pub fn with_max_size(mut self, max_size: Option<u64>) -> Self {
+    self.max_size = max_size;
+    self
+  }
+}
+
+fn add_column_if_missing(
+  connection: &Connection,
+  column: &str,
+  definition: &str,
+) -> rusqlite::Result<()> {
+  let exists = connection.query_row(
+    "SELECT count(*) FROM pragma_table_info('request_response_list') WHERE name = ?1",
+    params![column],
+    |row| row.get::<_, i64>(0),
+  )? > 0;
+  if !exists {
+    connection.execute(
+      &format!(
+        "ALTER TABLE request_response_list ADD COLUMN {column} {definition}"
+      ),
+      (),
+    )?;
+  }
+  Ok(())
 }
 
 #[async_trait(?Send)]
@@ -179,25 +225,28 @@ impl Cache for SqliteBackedCache {
   ) -> Result<(), AnyError> {
     let db = self.connection.clone();
     let cache_storage_dir = self.cache_storage_dir.clone();
+    let max_size = self.max_size;
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
 
-    if let Some(resource) = resource {
+    let (entry_id, replaced_body_key) = if let Some(resource) = resource {
       let body_key = hash(&format!(
         "{}_{}",
         &request_response.request_url,
         now.as_nanos()
       ));
       let responses_dir =
-        get_responses_dir(cache_storage_dir, request_response.cache_id);
+        get_responses_dir(cache_storage_dir.clone(), request_response.cache_id);
       let response_path = responses_dir.join(&body_key);
       let mut file = tokio::fs::File::create(response_path).await?;
       let mut buf = BufMutView::new(64 * 1024);
+      let mut body_size = 0;
       loop {
         let (size, buf2) = resource.clone().read_byob(buf).await?;
         if size == 0 {
           break;
         }
         buf = buf2;
+        body_size += size as u64;
 
         // Use poll_write to avoid holding a slice across await points
         poll_fn(|cx| Pin::new(&mut file).poll_write(cx, &buf[..size])).await?;
@@ -206,17 +255,35 @@ impl Cache for SqliteBackedCache {
       file.flush().await?;
       file.sync_all().await?;
 
-      assert_eq!(
-        insert_cache_asset(db, request_response, Some(body_key.clone()),)
-          .await?,
-        Some(body_key)
-      );
+      insert_cache_asset(
+        db.clone(),
+        request_response,
+        Some(body_key),
+        body_size,
+      )
+      .await?
     } else {
-      assert!(insert_cache_asset(db, request_response, None)
-        .await?
-        .is_none());
-    }
-    Ok(())
+      insert_cache_asset(db.clone(), request_response, None, 0).await?
+    };
+
+    spawn_blocking(move || {
+      if let Some((cache_id, body_key)) = replaced_body_key {
+        remove_response_body(&cache_storage_dir, cache_id, &body_key)?;
+      }
+      if let Some(max_size) = max_size {
+        let evicted = evict_lru(&db.lock(), &cache_storage_dir, max_size)?;
+        if evicted.contains(&entry_id) {
+          return Err(
+            deno_web::DomExceptionQuotaExceededError::new(
+              "The response is larger than the cache storage quota",
+            )
+            .into(),
+          );
+        }
+      }
+      Ok::<(), AnyError>(())
+    })
+    .await?
   }
 
   async fn r#match(
@@ -231,10 +298,10 @@ impl Cache for SqliteBackedCache {
     let (query_result, request) = spawn_blocking(move || {
       let db = db.lock();
       let result = db.query_row(
-        "SELECT response_body_key, response_headers, response_status, response_status_text, request_headers
-             FROM request_response_list
-             WHERE cache_id = ?1 AND request_url = ?2",
-        (request.cache_id, &request.request_url),
+        "UPDATE request_response_list SET last_accessed_at = ?3
+             WHERE cache_id = ?1 AND request_url = ?2
+             RETURNING response_body_key, response_headers, response_status, response_status_text, request_headers",
+        (request.cache_id, &request.request_url, now_millis()),
         |row| {
           let response_body_key: Option<String> = row.get(0)?;
           let response_headers: Vec<u8> = row.get(1)?;
@@ -304,51 +371,159 @@ impl Cache for SqliteBackedCache {
     request: CacheDeleteRequest,
   ) -> Result<bool, AnyError> {
     let db = self.connection.clone();
+    let cache_storage_dir = self.cache_storage_dir.clone();
+    spawn_blocking(move || {
+      let db = db.lock();
+      let deleted = db
+        .query_row(
+          "DELETE FROM request_response_list WHERE cache_id = ?1 AND request_url = ?2
+               RETURNING response_body_key",
+          (request.cache_id, &request.request_url),
+          |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()?;
+      if let Some(Some(body_key)) = &deleted {
+        remove_response_body(&cache_storage_dir, request.cache_id, body_key)?;
+      }
+      Ok::<bool, AnyError>(deleted.is_some())
+    })
+    .await?
+  }
+
+  async fn storage_usage(&self) -> Result<CacheStorageUsage, AnyError> {
+    let db = self.connection.clone();
+    let quota = self.max_size;
     spawn_blocking(move || {
-      // TODO(@satyarohith): remove the response body from disk if one exists
       let db = db.lock();
-      let rows_effected = db.execute(
-        "DELETE FROM request_response_list WHERE cache_id = ?1 AND request_url = ?2",
-        (request.cache_id, &request.request_url),
+      let (usage, entries) = db.query_row(
+        &format!(
+          "SELECT coalesce(sum({ENTRY_SIZE}), 0), count(*) FROM request_response_list"
+        ),
+        (),
+        |row| Ok((row.get::<_, u64>(0)?, row.get::<_, u64>(1)?)),
       )?;
-      Ok::<bool, AnyError>(rows_effected > 0)
+      Ok::<_, AnyError>(CacheStorageUsage {
+        usage,
+        quota,
+        entries,
+      })
     })
     .await?
   }
 }
 
+/// Deletes the least recently used entries until the total size of the cache
+/// storage is at most `max_size`, returning the ids of the evicted entries.
+fn evict_lru(
+  db: &Connection,
+  cache_storage_dir: &Path,
+  max_size: u64,
+) -> Result<Vec<i64>, AnyError> {
+  let mut usage: u64 = db.query_row(
+    &format!(
+      "SELECT coalesce(sum({ENTRY_SIZE}), 0) FROM request_response_list"
+    ),
+    (),
+    |row| row.get(0),
+  )?;
+  if usage <= max_size {
+    return Ok(vec![]);
+  }
+
+  let mut evicted = Vec::new();
+  let mut stmt = db.prepare(&format!(
+    "SELECT id, cache_id, response_body_key, {ENTRY_SIZE}
+         FROM request_response_list
+         ORDER BY last_accessed_at ASC, id ASC"
+  ))?;
+  let mut rows = stmt.query(())?;
+  while usage > max_size {
+    let Some(row) = rows.next()? else {
+      break;
+    };
+    let id: i64 = row.get(0)?;
+    let cache_id: i64 = row.get(1)?;
+    let body_key: Option<String> = row.get(2)?;
+    let size: u64 = row.get(3)?;
+    evicted.push((id, cache_id, body_key));
+    usage = usage.saturating_sub(size);
+  }
+  drop(rows);
+
+  for (id, cache_id, body_key) in &evicted {
+    db.execute("DELETE FROM request_response_list WHERE id = ?1", [id])?;
+    if let Some(body_key) = body_key {
+      remove_response_body(cache_storage_dir, *cache_id, body_key)?;
+    }
+  }
+  Ok(evicted.into_iter().map(|(id, _, _)| id).collect())
+}
+
+fn remove_response_body(
+  cache_storage_dir: &Path,
+  cache_id: i64,
+  body_key: &str,
+) -> Result<(), AnyError> {
+  let path =
+    get_responses_dir(cache_storage_dir.to_path_buf(), cache_id).join(body_key);
+  match std::fs::remove_file(path) {
+    Ok(()) => Ok(()),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(err) => Err(err.into()),
+  }
+}
+
+fn now_millis() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default()
+}
+
+/// Inserts or replaces an entry, returning its id and the cache id and body
+/// key of the entry it replaced, whose body should be removed.
 async fn insert_cache_asset(
   db: Arc<Mutex<rusqlite::Connection>>,
   put: CachePutRequest,
   response_body_key: Option<String>,
-) -> Result<Option<String>, deno_core::anyhow::Error> {
+  response_body_size: u64,
+) -> Result<(i64, Option<(i64, String)>), deno_core::anyhow::Error> {
   spawn_blocking(move || {
-    let maybe_response_body = {
-      let db = db.lock();
-      db.query_row(
-        "INSERT OR REPLACE INTO request_response_list
-             (cache_id, request_url, request_headers, response_headers,
-              response_body_key, response_status, response_status_text, last_inserted_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             RETURNING response_body_key",
-        (
-          put.cache_id,
-          put.request_url,
-          serialize_headers(&put.request_headers),
-          serialize_headers(&put.response_headers),
-          response_body_key,
-          put.response_status,
-          put.response_status_text,
-          SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
-        ),
-        |row| {
-          let response_body_key: Option<String> = row.get(0)?;
-          Ok(response_body_key)
-        },
-      )?
-    };
-      Ok::<Option<String>, AnyError>(maybe_response_body)
-  }).await?
+    let db = db.lock();
+    let replaced_body_key = db
+      .query_row(
+        "SELECT response_body_key FROM request_response_list
+             WHERE cache_id = ?1 AND request_url = ?2",
+        (put.cache_id, &put.request_url),
+        |row| row.get::<_, Option<String>>(0),
+      )
+      .optional()?
+      .flatten()
+      .map(|body_key| (put.cache_id, body_key));
+    let id = db.query_row(
+      "INSERT OR REPLACE INTO request_response_list
+           (cache_id, request_url, request_headers, response_headers,
+            response_body_key, response_status, response_status_text, last_inserted_at,
+            response_body_size, last_accessed_at)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+           RETURNING id",
+      (
+        put.cache_id,
+        put.request_url,
+        serialize_headers(&put.request_headers),
+        serialize_headers(&put.response_headers),
+        response_body_key,
+        put.response_status,
+        put.response_status_text,
+        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        response_body_size,
+        now_millis(),
+      ),
+      |row| row.get::<_, i64>(0),
+    )?;
+    Ok::<_, AnyError>((id, replaced_body_key))
+  })
+  .await?
 }
 
 #[inline]
@@ -6,11 +6,53 @@ const {
 } = core;
 import { op_cron_create, op_cron_next } from "ext:core/ops";
 const {
+  ArrayIsArray,
+  ArrayPrototypeIncludes,
   ArrayPrototypeJoin,
+  ArrayPrototypePush,
+  MathMin,
+  MathRound,
   NumberPrototypeToString,
+  PromisePrototypeThen,
   TypeError,
 } = primordials;
 
+type CronOverlapPolicy = "skip" | "queue" | "concurrent";
+
+interface CronOptions {
+  backoffSchedule?: number[] | Deno.CronExponentialBackoff;
+  overlap?: CronOverlapPolicy;
+  signal?: AbortSignal;
+}
+
+const overlapPolicies: CronOverlapPolicy[] = ["skip", "queue", "concurrent"];
+const maxBackoffDelay = 60 * 60 * 1000;
+
+/**
+ * Expands an exponential backoff into the delays of its retries, ex.
+ * `{ retries: 3, initialDelay: 100 }` into `[100, 200, 400]`.
+ */
+export function expandBackoffSchedule(
+  backoff: Deno.CronExponentialBackoff,
+): number[] {
+  const {
+    retries,
+    initialDelay,
+    factor = 2,
+    maxDelay = maxBackoffDelay,
+  } = backoff;
+  if (typeof retries !== "number" || typeof initialDelay !== "number") {
+    throw new TypeError("Invalid backoff schedule");
+  }
+  const schedule: number[] = [];
+  let delay = initialDelay;
+  for (let i = 0; i < retries; i++) {
+    ArrayPrototypePush(schedule, MathRound(MathMin(delay, maxDelay)));
+    delay *= factor;
+  }
+  return schedule;
+}
+
 export function formatToCronSchedule(
   value?: number | { exact: number | number[] } | {
     start?: number;
@@ -99,7 +141,7 @@ function cron(
   schedule: string | Deno.CronSchedule,
   handlerOrOptions1:
     | (() => Promise<void> | void)
-    | ({ backoffSchedule?: number[]; signal?: AbortSignal }),
+    | CronOptions,
   handler2?: () => Promise<void> | void,
 ) {
   if (name === undefined) {
@@ -112,9 +154,7 @@ function cron(
   schedule = parseScheduleToString(schedule);
 
   let handler: () => Promise<void> | void;
-  let options:
-    | { backoffSchedule?: number[]; signal?: AbortSignal }
-    | undefined = undefined;
+  let options: CronOptions | undefined = undefined;
 
   if (typeof handlerOrOptions1 === "function") {
     handler = handlerOrOptions1;
@@ -128,10 +168,28 @@ function cron(
     throw new TypeError("Deno.cron requires a handler");
   }
 
+  const overlap = options?.overlap ?? "skip";
+  if (!ArrayPrototypeIncludes(overlapPolicies, overlap)) {
+    throw new TypeError(`Invalid overlap policy: ${overlap}`);
+  }
+  let backoffSchedule = options?.backoffSchedule;
+  if (backoffSchedule !== undefined && overlap === "concurrent") {
+    throw new TypeError(
+      "backoffSchedule cannot be used with the concurrent overlap policy",
+    );
+  }
+  if (
+    backoffSchedule !== undefined && typeof backoffSchedule === "object" &&
+    !ArrayIsArray(backoffSchedule)
+  ) {
+    backoffSchedule = expandBackoffSchedule(backoffSchedule);
+  }
+
   const rid = op_cron_create(
     name,
     schedule,
-    options?.backoffSchedule,
+    backoffSchedule,
+    overlap,
   );
 
   if (options?.signal) {
@@ -145,6 +203,10 @@ function cron(
     );
   }
 
+  const onError = (error: unknown) => {
+    console.error(`Exception in cron handler ${name}`, error);
+  };
+
   return (async () => {
     let success = true;
     while (true) {
@@ -152,12 +214,24 @@ function cron(
       if (r === false) {
         break;
       }
+      if (overlap === "concurrent") {
+        // Runs are independent of each other, so failures aren't retried.
+        try {
+          const result = handler();
+          if (isPromise(result)) {
+            PromisePrototypeThen(result, undefined, onError);
+          }
+        } catch (error) {
+          onError(error);
+        }
+        continue;
+      }
       try {
         const result = handler();
         const _res = isPromise(result) ? (await result) : result;
         success = true;
       } catch (error) {
-        console.error(`Exception in cron handler ${name}`, error);
+        onError(error);
         success = false;
       }
     }
@@ -167,5 +241,6 @@ function cron(
 // For testing
 internals.formatToCronSchedule = formatToCronSchedule;
 internals.parseScheduleToString = parseScheduleToString;
+internals.expandBackoffSchedule = expandBackoffSchedule;
 
 export { cron };
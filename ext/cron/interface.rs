@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use deno_core::error::AnyError;
+use serde::Deserialize;
 
 pub trait CronHandler {
   type EH: CronHandle + 'static;
@@ -20,4 +21,20 @@ pub struct CronSpec {
   pub name: String,
   pub cron_schedule: String,
   pub backoff_schedule: Option<Vec<u32>>,
+  pub overlap_policy: CronOverlapPolicy,
+}
+
+/// What happens when a cron is due while its previous run is still active.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CronOverlapPolicy {
+  /// Ticks that pass during a run are skipped, and the next run is scheduled
+  /// once the current one finishes.
+  #[default]
+  Skip,
+  /// A tick that passes during a run starts another run as soon as the
+  /// current one finishes. Multiple missed ticks start a single run.
+  Queue,
+  /// Every tick starts a new run, even if previous runs are still active.
+  Concurrent,
 }
@@ -56,6 +56,7 @@ fn op_cron_create<C>(
   #[string] name: String,
   #[string] cron_schedule: String,
   #[serde] backoff_schedule: Option<Vec<u32>>,
+  #[serde] overlap_policy: Option<CronOverlapPolicy>,
 ) -> Result<ResourceId, AnyError>
 where
   C: CronHandler + 'static,
@@ -76,6 +77,7 @@ where
     name,
     cron_schedule,
     backoff_schedule,
+    overlap_policy: overlap_policy.unwrap_or_default(),
   })?;
 
   let handle_rid = {
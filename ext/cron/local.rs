@@ -23,6 +23,7 @@ use tokio::sync::Semaphore;
 
 use crate::CronHandle;
 use crate::CronHandler;
+use crate::CronOverlapPolicy;
 use crate::CronSpec;
 
 const MAX_CRONS: usize = 100;
@@ -47,6 +48,9 @@ struct Cron {
   spec: CronSpec,
   next_tx: mpsc::WeakSender<()>,
   current_execution_retries: u32,
+  /// The deadline of the next run following the cron schedule, as opposed to
+  /// a retry of a failed run.
+  next_scheduled_deadline: Option<u64>,
 }
 
 impl Cron {
@@ -123,17 +127,28 @@ impl LocalCronHandler {
               backoff_schedule[cron.current_execution_retries as usize];
             let now = chrono::Utc::now().timestamp_millis() as u64;
             cron.current_execution_retries += 1;
-            now + backoff_ms as u64
+            Some(now + backoff_ms as u64)
           } else {
-            let next_ts = compute_next_deadline(&cron.spec.cron_schedule)?;
             cron.current_execution_retries = 0;
-            next_ts
+            // Unless ticks are skipped while the cron runs, the next tick was
+            // already scheduled when this run was dispatched.
+            if cron.spec.overlap_policy != CronOverlapPolicy::Skip
+              && cron.next_scheduled_deadline.is_some()
+            {
+              None
+            } else {
+              let next_ts = compute_next_deadline(&cron.spec.cron_schedule)?;
+              cron.next_scheduled_deadline = Some(next_ts);
+              Some(next_ts)
+            }
           };
-          runtime_state
-            .scheduled_deadlines
-            .entry(next_deadline)
-            .or_default()
-            .push(name.to_string());
+          if let Some(next_deadline) = next_deadline {
+            runtime_state
+              .scheduled_deadlines
+              .entry(next_deadline)
+              .or_default()
+              .push(name.to_string());
+          }
         }
       }
 
@@ -144,7 +159,9 @@ impl LocalCronHandler {
       };
       for (_, tx) in crons_to_execute {
         if let Some(tx) = tx.upgrade() {
-          let _ = tx.send(()).await;
+          // A full channel means a run is already queued, so that the missed
+          // ticks of a queued cron start a single run.
+          let _ = tx.try_send(());
         }
       }
     }
@@ -174,16 +191,32 @@ impl RuntimeState {
             .map(move |name| (*ts, name.clone()))
             .collect::<Vec<_>>()
         })
-        .filter_map(|(_, name)| {
-          self
-            .crons
-            .get(&name)
-            .map(|c| (name.clone(), c.next_tx.clone()))
-        })
         .collect::<Vec<_>>()
     };
 
-    Ok(ready)
+    let mut crons_to_execute = Vec::with_capacity(ready.len());
+    for (ts, name) in ready {
+      let Some(cron) = self.crons.get_mut(&name) else {
+        continue;
+      };
+      if cron.next_scheduled_deadline == Some(ts) {
+        cron.next_scheduled_deadline = None;
+        // Keep ticking while the cron runs, instead of waiting for the run to
+        // finish before scheduling the next one.
+        if cron.spec.overlap_policy != CronOverlapPolicy::Skip {
+          let next_ts = compute_next_deadline(&cron.spec.cron_schedule)?;
+          cron.next_scheduled_deadline = Some(next_ts);
+          self
+            .scheduled_deadlines
+            .entry(next_ts)
+            .or_default()
+            .push(name.clone());
+        }
+      }
+      crons_to_execute.push((name, cron.next_tx.clone()));
+    }
+
+    Ok(crons_to_execute)
   }
 }
 
@@ -230,6 +263,7 @@ impl CronHandler for LocalCronHandler {
       spec: spec.clone(),
       next_tx: next_tx.downgrade(),
       current_execution_retries: 0,
+      next_scheduled_deadline: None,
     };
     runtime_state.crons.insert(spec.name.clone(), cron);
 
@@ -25,6 +25,7 @@ const {
   JSONParse,
   ObjectDefineProperties,
   ObjectPrototypeIsPrototypeOf,
+  SymbolFor,
   TypedArrayPrototypeGetBuffer,
   TypedArrayPrototypeGetByteLength,
   TypedArrayPrototypeGetByteOffset,
@@ -384,6 +385,21 @@ function packageData(bytes, type, mimeType) {
   }
 }
 
+/**
+ * Objects like `Deno.FsFile` can be used as a body through the readable
+ * stream of their underlying resource.
+ */
+const bodyStreamSymbol = SymbolFor("Deno.internal.bodyStream");
+
+/**
+ * @param {unknown} object
+ * @returns {boolean}
+ */
+function hasBodyStream(object) {
+  return typeof object === "object" && object !== null &&
+    bodyStreamSymbol in object;
+}
+
 /**
  * @param {BodyInit} object
  * @returns {{body: InnerBody, contentType: string | null}}
@@ -394,6 +410,9 @@ function extractBody(object) {
   let source = null;
   let length = null;
   let contentType = null;
+  if (hasBodyStream(object)) {
+    object = object[bodyStreamSymbol];
+  }
   if (typeof object === "string") {
     source = object;
     contentType = "text/plain;charset=UTF-8";
@@ -472,6 +491,8 @@ webidl.converters["BodyInit_DOMString"] = (V, prefix, context, opts) => {
     return webidl.converters["FormData"](V, prefix, context, opts);
   } else if (ObjectPrototypeIsPrototypeOf(URLSearchParamsPrototype, V)) {
     return webidl.converters["URLSearchParams"](V, prefix, context, opts);
+  } else if (hasBodyStream(V)) {
+    return V;
   }
   if (typeof V === "object") {
     if (isAnyArrayBuffer(V)) {
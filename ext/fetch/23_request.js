@@ -88,6 +88,8 @@ function processUrlList(urlList, urlListProcessed) {
  * @property {(() => string)[]} urlList
  * @property {string[]} urlListProcessed
  * @property {number | null} clientRid NOTE: non standard extension for `Deno.HttpClient`.
+ * @property {"half" | "full" | null} duplex
+ * @property {((progress: { loaded: number, total?: number }) => void) | null} onUploadProgress NOTE: non standard extension.
  * @property {Blob | null} blobUrlEntry
  */
 
@@ -136,6 +138,8 @@ function newInnerRequest(method, url, headerList, body, maybeBlob) {
     urlList: [typeof url === "string" ? () => url : url],
     urlListProcessed: [],
     clientRid: null,
+    duplex: null,
+    onUploadProgress: null,
     blobUrlEntry,
     url() {
       if (this.urlListProcessed[0] === undefined) {
@@ -187,6 +191,8 @@ function cloneInnerRequest(request, skipBody = false) {
     urlList: [() => request.url()],
     urlListProcessed: [request.url()],
     clientRid: request.clientRid,
+    duplex: request.duplex,
+    onUploadProgress: request.onUploadProgress,
     blobUrlEntry: request.blobUrlEntry,
     url() {
       if (this.urlListProcessed[0] === undefined) {
@@ -381,6 +387,26 @@ class Request {
       request.clientRid = init.client?.[internalRidSymbol] ?? null;
     }
 
+    // NOTE: non standard extension. Reports the progress of the upload.
+    if (init.onUploadProgress !== undefined) {
+      if (
+        init.onUploadProgress !== null &&
+        typeof init.onUploadProgress !== "function"
+      ) {
+        throw webidl.makeException(
+          TypeError,
+          "`onUploadProgress` must be a function",
+          prefix,
+          "Argument 2",
+        );
+      }
+      request.onUploadProgress = init.onUploadProgress;
+    }
+
+    if (init.duplex !== undefined) {
+      request.duplex = init.duplex;
+    }
+
     // 28.
     this[_request] = request;
 
@@ -554,6 +580,13 @@ webidl.converters["RequestRedirect"] = webidl.createEnumConverter(
     "manual",
   ],
 );
+webidl.converters["RequestDuplex"] = webidl.createEnumConverter(
+  "RequestDuplex",
+  [
+    "half",
+    "full",
+  ],
+);
 webidl.converters["RequestInit"] = webidl.createDictionaryConverter(
   "RequestInit",
   [
@@ -572,7 +605,9 @@ webidl.converters["RequestInit"] = webidl.createDictionaryConverter(
         webidl.converters["AbortSignal"],
       ),
     },
+    { key: "duplex", converter: webidl.converters["RequestDuplex"] },
     { key: "client", converter: webidl.converters.any },
+    { key: "onUploadProgress", converter: webidl.converters.any },
   ],
 );
 
@@ -35,6 +35,9 @@ const {
   ArrayPrototypePush,
   ObjectDefineProperties,
   ObjectPrototypeIsPrototypeOf,
+  PromisePrototype,
+  PromisePrototypeThen,
+  PromiseResolve,
   RangeError,
   RegExpPrototypeExec,
   SafeArrayIterator,
@@ -71,6 +74,7 @@ const _brand = webidl.brand;
  * @property {[string, string][]} headerList
  * @property {null | typeof __window.bootstrap.fetchBody.InnerBody} body
  * @property {boolean} aborted
+ * @property {HeadersInit | Promise<HeadersInit> | null} [trailers]
  * @property {string} [error]
  */
 
@@ -116,6 +120,7 @@ function cloneInnerResponse(response) {
     status: response.status,
     statusMessage: response.statusMessage,
     aborted: response.aborted,
+    trailers: response.trailers,
     url() {
       if (this.urlList.length == 0) return null;
       return this.urlList[this.urlList.length - 1];
@@ -195,6 +200,9 @@ function initializeAResponse(response, init, bodyWithType) {
   if (init.headers) {
     fillHeaders(headers, init.headers);
   }
+  if (init.trailers !== undefined) {
+    response[_response].trailers = init.trailers;
+  }
 
   // 6.
   if (bodyWithType !== null) {
@@ -389,6 +397,22 @@ class Response {
     return this[_headers];
   }
 
+  /**
+   * Resolves with the trailers of the response once its body has been read.
+   * @returns {Promise<Headers>}
+   */
+  get trailers() {
+    webidl.assertBranded(this, ResponsePrototype);
+    return PromisePrototypeThen(
+      PromiseResolve(this[_response].trailers ?? []),
+      (trailers) => {
+        const headers = headersFromHeaderList([], "none");
+        fillHeaders(headers, trailers);
+        return headers;
+      },
+    );
+  }
+
   /**
    * @returns {Response}
    */
@@ -441,6 +465,14 @@ webidl.converters["Response"] = webidl.createInterfaceConverter(
   "Response",
   ResponsePrototype,
 );
+// Deno extension: trailers sent by `Deno.serve` after the response body. A
+// promise allows trailers to depend on the body, as with gRPC status codes.
+webidl.converters["ResponseTrailersInit"] = (V, prefix, context, opts) => {
+  if (ObjectPrototypeIsPrototypeOf(PromisePrototype, V)) {
+    return V;
+  }
+  return webidl.converters["HeadersInit"](V, prefix, context, opts);
+};
 webidl.converters["ResponseInit"] = webidl.createDictionaryConverter(
   "ResponseInit",
   [{
@@ -454,6 +486,9 @@ webidl.converters["ResponseInit"] = webidl.createDictionaryConverter(
   }, {
     key: "headers",
     converter: webidl.converters["HeadersInit"],
+  }, {
+    key: "trailers",
+    converter: webidl.converters["ResponseTrailersInit"],
   }],
 );
 webidl.converters["ResponseInit_fast"] = function (
@@ -463,7 +498,12 @@ webidl.converters["ResponseInit_fast"] = function (
   opts,
 ) {
   if (init === undefined || init === null) {
-    return { status: 200, statusText: "", headers: undefined };
+    return {
+      status: 200,
+      statusText: "",
+      headers: undefined,
+      trailers: undefined,
+    };
   }
   // Fast path, if not a proxy
   if (typeof init === "object" && !core.isProxy(init)) {
@@ -477,7 +517,10 @@ webidl.converters["ResponseInit_fast"] = function (
     const headers = init.headers !== undefined
       ? webidl.converters["HeadersInit"](init.headers)
       : undefined;
-    return { status, statusText, headers };
+    const trailers = init.trailers !== undefined
+      ? webidl.converters["ResponseTrailersInit"](init.trailers)
+      : undefined;
+    return { status, statusText, headers, trailers };
   }
   // Slow default path
   return webidl.converters["ResponseInit"](init, prefix, context, opts);
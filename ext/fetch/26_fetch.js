@@ -13,7 +13,10 @@
 import { core, primordials } from "ext:core/mod.js";
 import {
   op_fetch,
+  op_fetch_response_take_trailers,
+  op_fetch_response_trailers,
   op_fetch_send,
+  op_fetch_upload_progress,
   op_wasm_streaming_feed,
   op_wasm_streaming_set_url,
 } from "ext:core/ops";
@@ -23,15 +26,18 @@ const {
   ArrayPrototypeFilter,
   ArrayPrototypeIncludes,
   Error,
+  ObjectDefineProperty,
   ObjectPrototypeIsPrototypeOf,
   Promise,
   PromisePrototypeThen,
   PromisePrototypeCatch,
+  PromiseResolve,
   SafeArrayIterator,
   String,
   StringPrototypeStartsWith,
   StringPrototypeToLowerCase,
   TypeError,
+  TypedArrayPrototypeGetByteLength,
   TypedArrayPrototypeGetSymbolToStringTag,
 } = primordials;
 
@@ -55,6 +61,7 @@ import {
   toInnerResponse,
 } from "ext:deno_fetch/23_response.js";
 import * as abortSignal from "ext:deno_web/03_abort_signal.js";
+import { reportException } from "ext:deno_web/02_event.js";
 
 const REQUEST_BODY_HEADER_NAMES = [
   "content-encoding",
@@ -74,10 +81,11 @@ function opFetchSend(rid) {
 /**
  * @param {number} responseBodyRid
  * @param {AbortSignal} [terminator]
+ * @param {() => void} [onEof]
  * @returns {ReadableStream<Uint8Array>}
  */
-function createResponseBodyStream(responseBodyRid, terminator) {
-  const readable = readableStreamForRid(responseBodyRid);
+function createResponseBodyStream(responseBodyRid, terminator, onEof) {
+  const readable = readableStreamForRid(responseBodyRid, true, onEof);
 
   function onAbort() {
     errorReadableStream(readable, terminator.reason);
@@ -90,6 +98,48 @@ function createResponseBodyStream(responseBodyRid, terminator) {
   return readable;
 }
 
+/**
+ * @param {number} responseBodyRid
+ * @returns {Promise<[string, string][]>}
+ */
+function requestResponseTrailers(responseBodyRid) {
+  const promise = op_fetch_response_trailers(responseBodyRid);
+  // Waiting for trailers should not keep the event loop alive on its own.
+  core.unrefOpPromise(promise);
+  return PromisePrototypeCatch(
+    PromisePrototypeThen(promise, (trailers) => trailers ?? []),
+    () => [],
+  );
+}
+
+/**
+ * Reports the progress of a request body upload until the body has been
+ * sent, or the request has failed.
+ * @param {number} rid
+ * @param {number | null} total
+ * @param {((progress: { loaded: number, total?: number }) => void) | null} onProgress
+ * @returns {Promise<void>}
+ */
+async function trackUploadProgress(rid, total, onProgress) {
+  try {
+    while (true) {
+      const loaded = await op_fetch_upload_progress(rid);
+      if (loaded === null) {
+        return;
+      }
+      if (onProgress !== null) {
+        try {
+          onProgress({ loaded, total: total ?? undefined });
+        } catch (error) {
+          reportException(error);
+        }
+      }
+    }
+  } finally {
+    core.tryClose(rid);
+  }
+}
+
 /**
  * @param {InnerRequest} req
  * @param {boolean} recursive
@@ -149,16 +199,30 @@ async function mainFetch(req, recursive, terminator) {
     }
   }
 
-  const { requestRid, cancelHandleRid } = op_fetch(
+  const hasBody = reqBody !== null || reqRid !== null;
+  const { requestRid, cancelHandleRid, uploadProgressRid } = op_fetch(
     req.method,
     req.currentUrl(),
     req.headerList,
     req.clientRid,
-    reqBody !== null || reqRid !== null,
+    hasBody,
     reqBody,
     reqRid,
+    hasBody && (req.onUploadProgress !== null || req.duplex === "half"),
   );
 
+  let uploaded = null;
+  if (uploadProgressRid !== null) {
+    const total = reqBody !== null
+      ? TypedArrayPrototypeGetByteLength(reqBody)
+      : req.body.length;
+    uploaded = trackUploadProgress(
+      uploadProgressRid,
+      total,
+      req.onUploadProgress,
+    );
+  }
+
   function onAbort() {
     if (cancelHandleRid !== null) {
       core.tryClose(cancelHandleRid);
@@ -180,6 +244,11 @@ async function mainFetch(req, recursive, terminator) {
   if (resp.error) {
     throw new TypeError("body failed", { cause: new Error(resp.error) });
   }
+  // In half duplex mode, the response is only exposed once the request body
+  // has been sent completely.
+  if (req.duplex === "half" && uploaded !== null) {
+    await uploaded;
+  }
   if (terminator.aborted) return abortedNetworkError();
 
   processUrlList(req.urlList, req.urlListProcessed);
@@ -219,8 +288,29 @@ async function mainFetch(req, recursive, terminator) {
       response.body = null;
       core.close(resp.responseRid);
     } else {
+      const responseRid = resp.responseRid;
+      // The trailers are only requested from the resource once they are
+      // accessed, or taken before the resource is closed at the end of the
+      // body so that they can still be accessed afterwards.
+      let trailers = null;
+      let takenTrailers = null;
+      ObjectDefineProperty(response, "trailers", {
+        __proto__: null,
+        get() {
+          trailers ??= takenTrailers !== null
+            ? PromiseResolve(takenTrailers)
+            : requestResponseTrailers(responseRid);
+          return trailers;
+        },
+        configurable: true,
+        enumerable: true,
+      });
       response.body = new InnerBody(
-        createResponseBodyStream(resp.responseRid, terminator),
+        createResponseBodyStream(responseRid, terminator, () => {
+          if (trailers === null) {
+            takenTrailers = op_fetch_response_take_trailers(responseRid) ?? [];
+          }
+        }),
       );
     }
   }
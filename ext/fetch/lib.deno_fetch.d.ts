@@ -163,6 +163,7 @@ declare type BodyInit =
   | FormData
   | URLSearchParams
   | ReadableStream<Uint8Array>
+  | Deno.FsFile
   | string;
 /** @category Fetch */
 declare type RequestDestination =
@@ -185,6 +186,9 @@ declare type RequestDestination =
   | "worker"
   | "xslt";
 
+/** @category Fetch */
+declare type RequestDuplex = "half" | "full";
+
 /** @category Fetch */
 declare interface RequestInit {
   /**
@@ -202,6 +206,15 @@ declare interface RequestInit {
    * credentials.
    */
   credentials?: RequestCredentials;
+  /**
+   * How a streaming request body and the response are exchanged. With
+   * `"half"`, the response is only returned once the request body has been
+   * sent completely. With `"full"`, the response is returned as soon as its
+   * headers arrive, while the request body may still be streaming, which
+   * allows bidirectional protocols over a single request. Defaults to
+   * `"full"`.
+   */
+  duplex?: RequestDuplex;
   /**
    * A Headers object, an object literal, or an array of two-item arrays to set
    * request's headers.
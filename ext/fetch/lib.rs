@@ -2,8 +2,10 @@
 
 mod fs_fetch_handler;
 mod proxy;
+mod retry;
 #[cfg(test)]
 mod tests;
+mod upload;
 
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -26,6 +28,7 @@ use deno_core::futures::Future;
 use deno_core::futures::FutureExt;
 use deno_core::futures::Stream;
 use deno_core::futures::StreamExt;
+use deno_core::futures::TryStreamExt;
 use deno_core::op2;
 use deno_core::unsync::spawn;
 use deno_core::url::Url;
@@ -65,20 +68,33 @@ use http::Method;
 use http::Uri;
 use http_body_util::BodyExt;
 use hyper::body::Frame;
+use hyper_rustls::MaybeHttpsStream;
 use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::rt::TokioExecutor;
 use hyper_util::rt::TokioIo;
 use hyper_util::rt::TokioTimer;
 use serde::Deserialize;
 use serde::Serialize;
+use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
 use tokio::io::AsyncWriteExt;
+use tokio::io::ReadBuf;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
 use tower::ServiceExt;
 use tower_http::decompression::Decompression;
 
 // Re-export data_url
 pub use data_url;
 pub use proxy::basic_auth;
+use retry::replay_request;
+use retry::ReplayableBody;
+pub use retry::RetryPolicy;
+use upload::op_fetch_upload_progress;
+use upload::ProgressBody;
+use upload::UploadProgress;
+use upload::UploadProgressResource;
 
 pub use fs_fetch_handler::FsFetchHandler;
 
@@ -124,7 +140,10 @@ deno_core::extension!(deno_fetch,
   ops = [
     op_fetch<FP>,
     op_fetch_send,
+    op_fetch_response_trailers,
+    op_fetch_response_take_trailers,
     op_fetch_response_upgrade,
+    op_fetch_upload_progress,
     op_utf8_to_byte_string,
     op_fetch_custom_client<FP>,
   ],
@@ -189,6 +208,7 @@ pub fn get_declaration() -> PathBuf {
 pub struct FetchReturn {
   pub request_rid: ResourceId,
   pub cancel_handle_rid: Option<ResourceId>,
+  pub upload_progress_rid: Option<ResourceId>,
 }
 
 pub fn get_or_create_client_from_state(
@@ -225,6 +245,7 @@ pub fn create_client_from_options(
       pool_idle_timeout: None,
       http1: true,
       http2: true,
+      retry: None,
     },
   )
 }
@@ -339,6 +360,7 @@ pub fn op_fetch<FP>(
   has_body: bool,
   #[buffer] data: Option<JsBuffer>,
   #[smi] resource: Option<ResourceId>,
+  report_upload_progress: bool,
 ) -> Result<FetchReturn, AnyError>
 where
   FP: FetchPermissions + 'static,
@@ -353,6 +375,8 @@ where
   let method = Method::from_bytes(&method)?;
   let mut url = Url::parse(&url)?;
 
+  let mut upload_progress_rid = None;
+
   // Check scheme before asking for net permission
   let scheme = url.scheme();
   let (request_rid, cancel_handle_rid) = match scheme {
@@ -394,13 +418,16 @@ where
         .map_err(|_| type_error("Invalid URL"))?;
 
       let mut con_len = None;
+      let mut replayable_body = None;
       let body = if has_body {
         match (data, resource) {
           (Some(data), _) => {
             // If a body is passed, we use it, and don't return a body for streaming.
             con_len = Some(data.len() as u64);
 
-            http_body_util::Full::new(data.to_vec().into())
+            let data = Bytes::from(data.to_vec());
+            replayable_body = Some(ReplayableBody(data.clone()));
+            http_body_util::Full::new(data)
               .map_err(|never| match never {})
               .boxed()
           }
@@ -427,9 +454,24 @@ where
           .boxed()
       };
 
+      let body = if has_body && report_upload_progress {
+        let progress = Rc::new(UploadProgress::default());
+        upload_progress_rid = Some(
+          state
+            .resource_table
+            .add(UploadProgressResource::new(progress.clone())),
+        );
+        ReqBody::new(ProgressBody::new(body, progress))
+      } else {
+        body
+      };
+
       let mut request = http::Request::new(body);
       *request.method_mut() = method.clone();
       *request.uri_mut() = uri;
+      if let Some(replayable_body) = replayable_body {
+        request.extensions_mut().insert(replayable_body);
+      }
 
       if let Some((username, password)) = maybe_authority {
         request.headers_mut().insert(
@@ -518,6 +560,7 @@ where
   Ok(FetchReturn {
     request_rid,
     cancel_handle_rid,
+    upload_progress_rid,
   })
 }
 
@@ -611,6 +654,58 @@ pub async fn op_fetch_send(
   })
 }
 
+/// Resolves with the trailers of a response once its body has been read, or
+/// `None` if the response has no trailers.
+#[op2(async)]
+#[serde]
+pub async fn op_fetch_response_trailers(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<Vec<(ByteString, ByteString)>>, AnyError> {
+  let trailers_rx = state
+    .borrow()
+    .resource_table
+    .get::<FetchResponseResource>(rid)?
+    .trailers_rx
+    .borrow_mut()
+    .take();
+  let Some(trailers_rx) = trailers_rx else {
+    return Ok(None);
+  };
+  let Ok(trailers) = trailers_rx.await else {
+    return Ok(None);
+  };
+  Ok(Some(trailer_list(&trailers)))
+}
+
+/// Takes the trailers of a response whose body has been read to its end,
+/// before its resource is closed, or `None` if the response has no trailers.
+#[op2]
+#[serde]
+pub fn op_fetch_response_take_trailers(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<Option<Vec<(ByteString, ByteString)>>, AnyError> {
+  let trailers_rx = state
+    .resource_table
+    .get::<FetchResponseResource>(rid)?
+    .trailers_rx
+    .borrow_mut()
+    .take();
+  Ok(
+    trailers_rx
+      .and_then(|mut trailers_rx| trailers_rx.try_recv().ok())
+      .map(|trailers| trailer_list(&trailers)),
+  )
+}
+
+fn trailer_list(trailers: &http::HeaderMap) -> Vec<(ByteString, ByteString)> {
+  trailers
+    .iter()
+    .map(|(key, val)| (key.as_str().into(), val.as_bytes().into()))
+    .collect()
+}
+
 #[op2(async)]
 #[smi]
 pub async fn op_fetch_response_upgrade(
@@ -764,14 +859,21 @@ pub struct FetchResponseResource {
   pub response_reader: AsyncRefCell<FetchResponseReader>,
   pub cancel: CancelHandle,
   pub size: Option<u64>,
+  /// Receives the trailers of the response once its body has been read. The
+  /// sender is dropped without sending if the response has no trailers.
+  trailers_tx: RefCell<Option<oneshot::Sender<http::HeaderMap>>>,
+  trailers_rx: RefCell<Option<oneshot::Receiver<http::HeaderMap>>>,
 }
 
 impl FetchResponseResource {
   pub fn new(response: http::Response<ResBody>, size: Option<u64>) -> Self {
+    let (trailers_tx, trailers_rx) = oneshot::channel();
     Self {
       response_reader: AsyncRefCell::new(FetchResponseReader::Start(response)),
       cancel: CancelHandle::default(),
       size,
+      trailers_tx: RefCell::new(Some(trailers_tx)),
+      trailers_rx: RefCell::new(Some(trailers_rx)),
     }
   }
 
@@ -802,12 +904,27 @@ impl Resource for FetchResponseResource {
 
         match std::mem::take(&mut *reader) {
           FetchResponseReader::Start(resp) => {
-            let stream: BytesStream =
-              Box::pin(resp.into_body().into_data_stream().map(|r| {
-                r.map_err(|err| {
+            let mut trailers_tx = self.trailers_tx.borrow_mut().take();
+            let stream: BytesStream = Box::pin(
+              http_body_util::BodyStream::new(resp.into_body())
+                .map_err(|err| {
                   std::io::Error::new(std::io::ErrorKind::Other, err)
                 })
-              }));
+                .try_filter_map(move |frame| {
+                  let data = match frame.into_data() {
+                    Ok(data) => Some(data),
+                    Err(frame) => {
+                      if let (Ok(trailers), Some(tx)) =
+                        (frame.into_trailers(), trailers_tx.take())
+                      {
+                        let _ = tx.send(trailers);
+                      }
+                      None
+                    }
+                  };
+                  std::future::ready(Ok(data))
+                }),
+            );
             *reader = FetchResponseReader::BodyReader(stream.peekable());
           }
           FetchResponseReader::BodyReader(_) => unreachable!(),
@@ -881,6 +998,8 @@ pub struct CreateHttpClientArgs {
   http2: bool,
   #[serde(default)]
   allow_host: bool,
+  #[serde(default)]
+  retry: Option<RetryPolicy>,
 }
 
 fn default_true() -> bool {
@@ -903,6 +1022,10 @@ where
     permissions.check_net_url(&url, "Deno.createHttpClient()")?;
   }
 
+  if let Some(retry) = &args.retry {
+    retry.validate()?;
+  }
+
   let options = state.borrow::<Options>();
   let ca_certs = args
     .ca_certs
@@ -933,6 +1056,7 @@ where
       ),
       http1: args.http1,
       http2: args.http2,
+      retry: args.retry,
     },
   )?;
 
@@ -953,6 +1077,8 @@ pub struct CreateHttpClientOptions {
   pub pool_idle_timeout: Option<Option<u64>>,
   pub http1: bool,
   pub http2: bool,
+  /// Retry idempotent requests after transport errors or retryable statuses.
+  pub retry: Option<RetryPolicy>,
 }
 
 impl Default for CreateHttpClientOptions {
@@ -967,6 +1093,7 @@ impl Default for CreateHttpClientOptions {
       pool_idle_timeout: None,
       http1: true,
       http2: true,
+      retry: None,
     }
   }
 }
@@ -997,6 +1124,8 @@ pub fn create_http_client(
     alpn_protocols.push("http/1.1".into());
   }
   tls_config.alpn_protocols = alpn_protocols;
+  let mut upgrade_tls_config = tls_config.clone();
+  upgrade_tls_config.alpn_protocols = vec!["http/1.1".into()];
   let tls_config = Arc::from(tls_config);
 
   let mut http_connector = HttpConnector::new();
@@ -1028,6 +1157,11 @@ pub fn create_http_client(
     tls_proxy: proxy_tls_config,
     user_agent: Some(user_agent.clone()),
   };
+  // Connection upgrades (eg. WebSockets) require HTTP/1.1.
+  let upgrade_connector = proxy::ProxyConnector {
+    tls: Arc::new(upgrade_tls_config),
+    ..connector.clone()
+  };
 
   if let Some(pool_max_idle_per_host) = options.pool_max_idle_per_host {
     builder.pool_max_idle_per_host(pool_max_idle_per_host);
@@ -1055,8 +1189,10 @@ pub fn create_http_client(
 
   Ok(Client {
     inner: decompress,
+    upgrade_connector,
     proxies,
     user_agent,
+    retry: options.retry.map(Arc::new),
   })
 }
 
@@ -1071,9 +1207,12 @@ pub fn op_utf8_to_byte_string(
 #[derive(Clone, Debug)]
 pub struct Client {
   inner: Decompression<hyper_util::client::legacy::Client<Connector, ReqBody>>,
+  // Used to open raw HTTP/1.1 connections for protocol upgrades
+  upgrade_connector: Connector,
   // Used to check whether to include a proxy-authorization header
   proxies: Arc<proxy::Proxies>,
   user_agent: HeaderValue,
+  retry: Option<Arc<RetryPolicy>>,
 }
 
 type Connector = proxy::ProxyConnector<HttpConnector>;
@@ -1098,9 +1237,117 @@ impl Client {
       req.headers_mut().insert(PROXY_AUTHORIZATION, auth.clone());
     }
 
-    let resp = self.inner.oneshot(req).await?;
+    let Some(retry) = self.retry.clone().filter(|retry| retry.can_retry(&req))
+    else {
+      return self.send_once(req).await;
+    };
+
+    let (parts, _) = req.into_parts();
+    let body = parts.extensions.get::<ReplayableBody>();
+    let mut attempt = 1;
+    loop {
+      let res = self.send_once(replay_request(&parts, body)).await;
+      if attempt >= retry.max_attempts {
+        return res;
+      }
+      let delay = match &res {
+        Ok(resp) => match retry.response_delay(resp, attempt) {
+          Some(delay) => delay,
+          None => return res,
+        },
+        // Most likely a pooled connection that was closed by the server.
+        Err(_) => retry.backoff(attempt),
+      };
+      drop(res);
+      tokio::time::sleep(delay).await;
+      attempt += 1;
+    }
+  }
+
+  async fn send_once(
+    &self,
+    req: http::Request<ReqBody>,
+  ) -> Result<http::Response<ResBody>, AnyError> {
+    let resp = self.inner.clone().oneshot(req).await?;
     Ok(resp.map(|b| b.map_err(|e| anyhow!(e)).boxed()))
   }
+
+  /// Opens a raw connection to `dst` using this client's proxy and TLS
+  /// settings, for protocols that upgrade an HTTP/1.1 connection (such as
+  /// WebSockets) rather than sending a regular request.
+  pub async fn connect_upgrade(
+    &self,
+    dst: Uri,
+  ) -> Result<UpgradeConnection, AnyError> {
+    let forward_auth = self.proxies.http_forward_auth(&dst).cloned();
+    let io = self
+      .upgrade_connector
+      .clone()
+      .oneshot(dst)
+      .await
+      .map_err(|e| anyhow!(e))?;
+    let forwarded = matches!(io, proxy::Proxied::HttpForward(_));
+    Ok(UpgradeConnection {
+      io: TokioIo::new(io),
+      forwarded,
+      proxy_authorization: forward_auth.filter(|_| forwarded),
+    })
+  }
+}
+
+/// A connection opened by [`Client::connect_upgrade`].
+pub struct UpgradeConnection {
+  io: TokioIo<proxy::Proxied<MaybeHttpsStream<TokioIo<TcpStream>>>>,
+  forwarded: bool,
+  proxy_authorization: Option<HeaderValue>,
+}
+
+impl UpgradeConnection {
+  /// Whether this connection goes through an HTTP forwarding proxy, in which
+  /// case requests must use the absolute form of the target URI.
+  pub fn is_forwarded(&self) -> bool {
+    self.forwarded
+  }
+
+  /// The `Proxy-Authorization` header to send with requests over a forwarded
+  /// connection, if the proxy requires one.
+  pub fn proxy_authorization(&self) -> Option<&HeaderValue> {
+    self.proxy_authorization.as_ref()
+  }
+}
+
+impl AsyncRead for UpgradeConnection {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    Pin::new(&mut self.io).poll_read(cx, buf)
+  }
+}
+
+impl AsyncWrite for UpgradeConnection {
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, std::io::Error>> {
+    Pin::new(&mut self.io).poll_write(cx, buf)
+  }
+
+  fn poll_flush(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<(), std::io::Error>> {
+    Pin::new(&mut self.io).poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<(), std::io::Error>> {
+    Pin::new(&mut self.io).poll_shutdown(cx)
+  }
 }
 
 pub type ReqBody = http_body_util::combinators::BoxBody<Bytes, Error>;
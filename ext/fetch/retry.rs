@@ -0,0 +1,153 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+//! Opt-in retries for requests sent through a `Deno.HttpClient`.
+
+use bytes::Bytes;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use http::header::RETRY_AFTER;
+use http::HeaderValue;
+use http::Method;
+use http_body_util::BodyExt;
+use hyper::body::Body;
+use rand::Rng;
+use serde::Deserialize;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::ReqBody;
+
+/// Controls how requests are retried after a transport error or a retryable
+/// response status. Only idempotent requests whose body can be sent again are
+/// ever retried.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryPolicy {
+  /// Total number of attempts, including the first one.
+  pub max_attempts: u32,
+  /// Upper bound of the delay before the first retry, in milliseconds. The
+  /// bound doubles with every further retry.
+  pub initial_backoff: u64,
+  /// Upper bound of any delay between two attempts, in milliseconds. A
+  /// `Retry-After` asking for a longer delay is not honored, and the response
+  /// is returned as is.
+  pub max_backoff: u64,
+  /// Response statuses that are retried, in addition to transport errors.
+  pub retry_on_status: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      initial_backoff: 100,
+      max_backoff: 10_000,
+      retry_on_status: vec![429, 503],
+    }
+  }
+}
+
+/// The body of a request that can be sent again, attached to the request as
+/// an extension.
+#[derive(Clone, Debug)]
+pub struct ReplayableBody(pub Bytes);
+
+impl RetryPolicy {
+  pub fn validate(&self) -> Result<(), AnyError> {
+    if self.max_attempts == 0 {
+      return Err(type_error(
+        "Invalid 'retry.maxAttempts': must be at least 1",
+      ));
+    }
+    if self.initial_backoff > self.max_backoff {
+      return Err(type_error(
+        "Invalid 'retry.initialBackoff': must not exceed 'retry.maxBackoff'",
+      ));
+    }
+    Ok(())
+  }
+
+  /// Whether `req` may be sent more than once.
+  pub fn can_retry(&self, req: &http::Request<ReqBody>) -> bool {
+    self.max_attempts > 1
+      && is_idempotent(req.method())
+      && (req.body().is_end_stream()
+        || req.extensions().get::<ReplayableBody>().is_some())
+  }
+
+  /// A random delay before retry number `retry` (starting at 1), using "full
+  /// jitter" so that clients failing together don't retry together.
+  pub fn backoff(&self, retry: u32) -> Duration {
+    let cap = self
+      .initial_backoff
+      .saturating_mul(1 << retry.saturating_sub(1).min(31))
+      .min(self.max_backoff);
+    Duration::from_millis(rand::thread_rng().gen_range(0..=cap))
+  }
+
+  /// The delay before retrying a request that received `resp`, or `None` if
+  /// the response should be returned to the caller.
+  pub fn response_delay<B>(
+    &self,
+    resp: &http::Response<B>,
+    retry: u32,
+  ) -> Option<Duration> {
+    if !self.retry_on_status.contains(&resp.status().as_u16()) {
+      return None;
+    }
+    let Some(retry_after) = resp.headers().get(RETRY_AFTER) else {
+      return Some(self.backoff(retry));
+    };
+    let delay = parse_retry_after(retry_after, SystemTime::now())?;
+    (delay <= Duration::from_millis(self.max_backoff)).then_some(delay)
+  }
+}
+
+/// Idempotent methods as defined by RFC 9110, section 9.2.2.
+fn is_idempotent(method: &Method) -> bool {
+  matches!(
+    *method,
+    Method::GET
+      | Method::HEAD
+      | Method::OPTIONS
+      | Method::TRACE
+      | Method::PUT
+      | Method::DELETE
+  )
+}
+
+/// Parses a `Retry-After` header, which is either a number of seconds or an
+/// HTTP date.
+pub fn parse_retry_after(
+  value: &HeaderValue,
+  now: SystemTime,
+) -> Option<Duration> {
+  let value = value.to_str().ok()?.trim();
+  if let Ok(seconds) = value.parse::<u64>() {
+    return Some(Duration::from_secs(seconds));
+  }
+  let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+  let date = SystemTime::from(date);
+  // A date in the past means the request can be retried right away.
+  Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// Builds a new copy of a request that was split into its parts.
+pub fn replay_request(
+  parts: &http::request::Parts,
+  body: Option<&ReplayableBody>,
+) -> http::Request<ReqBody> {
+  let body = match body {
+    Some(ReplayableBody(bytes)) => http_body_util::Full::new(bytes.clone())
+      .map_err(|never| match never {})
+      .boxed(),
+    None => http_body_util::Empty::new()
+      .map_err(|never| match never {})
+      .boxed(),
+  };
+  let mut req = http::Request::new(body);
+  *req.method_mut() = parts.method.clone();
+  *req.uri_mut() = parts.uri.clone();
+  *req.version_mut() = parts.version;
+  *req.headers_mut() = parts.headers.clone();
+  req
+}
@@ -1,7 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::net::SocketAddr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use bytes::Bytes;
 use http_body_util::BodyExt;
@@ -9,7 +13,9 @@ use tokio::io::AsyncReadExt;
 use tokio::io::AsyncWriteExt;
 
 use super::create_http_client;
+use super::retry::parse_retry_after;
 use super::CreateHttpClientOptions;
+use super::RetryPolicy;
 
 static EXAMPLE_CRT: &[u8] = include_bytes!("../tls/testdata/example1_cert.der");
 static EXAMPLE_KEY: &[u8] =
@@ -57,6 +63,7 @@ async fn run_test_client(
       pool_idle_timeout: None,
       http1: true,
       http2: true,
+      retry: None,
     },
   )
   .unwrap();
@@ -186,3 +193,90 @@ async fn create_https_proxy(src_addr: SocketAddr) -> SocketAddr {
 
   prx_addr
 }
+
+#[tokio::test]
+async fn test_retry_unavailable() {
+  let requests = Arc::new(AtomicUsize::new(0));
+  let src_addr = create_flaky_http_server(requests.clone()).await;
+  let client = create_http_client(
+    "fetch/test",
+    CreateHttpClientOptions {
+      retry: Some(RetryPolicy::default()),
+      ..Default::default()
+    },
+  )
+  .unwrap();
+
+  let req = http::Request::builder()
+    .uri(format!("http://{}/foo", src_addr))
+    .body(
+      http_body_util::Empty::new()
+        .map_err(|err| match err {})
+        .boxed(),
+    )
+    .unwrap();
+  let resp = client.clone().send(req).await.unwrap();
+  assert_eq!(resp.status(), http::StatusCode::OK);
+  assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+  // Requests with non-idempotent methods are never retried.
+  requests.store(0, Ordering::SeqCst);
+  let req = http::Request::builder()
+    .method(http::Method::POST)
+    .uri(format!("http://{}/foo", src_addr))
+    .body(
+      http_body_util::Empty::new()
+        .map_err(|err| match err {})
+        .boxed(),
+    )
+    .unwrap();
+  let resp = client.send(req).await.unwrap();
+  assert_eq!(resp.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+  assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_parse_retry_after() {
+  let now = SystemTime::UNIX_EPOCH + Duration::from_secs(784111777);
+  let parse =
+    |value| parse_retry_after(&http::HeaderValue::from_static(value), now);
+  assert_eq!(parse("120"), Some(Duration::from_secs(120)));
+  assert_eq!(
+    parse("Sun, 06 Nov 1994 08:49:47 GMT"),
+    Some(Duration::from_secs(10))
+  );
+  assert_eq!(parse("Sun, 06 Nov 1994 08:49:27 GMT"), Some(Duration::ZERO));
+  assert_eq!(parse("soon"), None);
+}
+
+/// Answers every odd request with a 503 and every even one with a 200.
+async fn create_flaky_http_server(requests: Arc<AtomicUsize>) -> SocketAddr {
+  let src_tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let src_addr = src_tcp.local_addr().unwrap();
+
+  tokio::spawn(async move {
+    while let Ok((sock, _)) = src_tcp.accept().await {
+      let requests = requests.clone();
+      let fut = hyper::server::conn::http1::Builder::new().serve_connection(
+        hyper_util::rt::TokioIo::new(sock),
+        hyper::service::service_fn(move |_req| {
+          let n = requests.fetch_add(1, Ordering::SeqCst);
+          async move {
+            let mut resp =
+              http::Response::new(http_body_util::Empty::<Bytes>::new());
+            if n % 2 == 0 {
+              *resp.status_mut() = http::StatusCode::SERVICE_UNAVAILABLE;
+              resp
+                .headers_mut()
+                .insert(http::header::RETRY_AFTER, "0".parse().unwrap());
+            }
+            Ok::<_, std::convert::Infallible>(resp)
+          }
+        }),
+      );
+      tokio::spawn(fut);
+    }
+  });
+
+  src_addr
+}
@@ -0,0 +1,148 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+//! Progress reporting for request bodies sent by `fetch`.
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+
+use bytes::Bytes;
+use deno_core::anyhow::Error;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use hyper::body::Body;
+use hyper::body::Frame;
+use hyper::body::SizeHint;
+use tokio::sync::Notify;
+
+use crate::ReqBody;
+
+/// How much of a request body has been handed to the connection.
+#[derive(Default)]
+pub struct UploadProgress {
+  sent: Cell<u64>,
+  done: Cell<bool>,
+  changed: Notify,
+}
+
+impl UploadProgress {
+  fn advance(&self, n: u64) {
+    self.sent.set(self.sent.get() + n);
+    self.changed.notify_waiters();
+  }
+
+  fn finish(&self) {
+    self.done.set(true);
+    self.changed.notify_waiters();
+  }
+}
+
+/// A request body that records every chunk polled from it. hyper only polls
+/// for more data once the connection is ready to write it, so the progress
+/// follows the backpressure applied by the peer.
+pub struct ProgressBody {
+  inner: ReqBody,
+  progress: Rc<UploadProgress>,
+}
+
+impl ProgressBody {
+  pub fn new(inner: ReqBody, progress: Rc<UploadProgress>) -> Self {
+    Self { inner, progress }
+  }
+}
+
+// SAFETY: we only use this on a single-threaded executor
+unsafe impl Send for ProgressBody {}
+// SAFETY: we only use this on a single-threaded executor
+unsafe impl Sync for ProgressBody {}
+
+impl Body for ProgressBody {
+  type Data = Bytes;
+  type Error = Error;
+
+  fn poll_frame(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+    let frame = ready!(Pin::new(&mut self.inner).poll_frame(cx));
+    match &frame {
+      Some(Ok(frame)) => {
+        if let Some(data) = frame.data_ref() {
+          self.progress.advance(data.len() as u64);
+        }
+      }
+      Some(Err(_)) | None => self.progress.finish(),
+    }
+    Poll::Ready(frame)
+  }
+
+  fn is_end_stream(&self) -> bool {
+    self.inner.is_end_stream()
+  }
+
+  fn size_hint(&self) -> SizeHint {
+    self.inner.size_hint()
+  }
+}
+
+impl Drop for ProgressBody {
+  fn drop(&mut self) {
+    // The body is dropped without being finished if the request fails.
+    self.progress.finish();
+  }
+}
+
+pub struct UploadProgressResource {
+  progress: Rc<UploadProgress>,
+  reported: Cell<u64>,
+}
+
+impl UploadProgressResource {
+  pub fn new(progress: Rc<UploadProgress>) -> Self {
+    Self {
+      progress,
+      reported: Cell::new(0),
+    }
+  }
+}
+
+impl Resource for UploadProgressResource {
+  fn name(&self) -> Cow<str> {
+    "fetchUploadProgress".into()
+  }
+}
+
+/// Resolves with the number of request body bytes sent so far once it has
+/// changed, or `None` once the body has been sent or dropped.
+#[op2(async)]
+#[serde]
+pub async fn op_fetch_upload_progress(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<u64>, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<UploadProgressResource>(rid)?;
+  let progress = &resource.progress;
+  loop {
+    // Register for changes before checking, so none are missed.
+    let changed = progress.changed.notified();
+    let sent = progress.sent.get();
+    if sent != resource.reported.get() {
+      resource.reported.set(sent);
+      return Ok(Some(sent));
+    }
+    if progress.done.get() {
+      return Ok(None);
+    }
+    changed.await;
+  }
+}
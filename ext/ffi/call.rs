@@ -290,6 +290,7 @@ where
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<FP>();
     permissions.check_partial(None)?;
+    permissions.check_raw_pointers("Deno.UnsafeFnPointer#call")?;
   };
 
   let symbol = PtrSymbol::new(pointer, &def)?;
@@ -386,6 +387,7 @@ where
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<FP>();
     permissions.check_partial(None)?;
+    permissions.check_raw_pointers("Deno.UnsafeFnPointer#call")?;
   };
 
   let symbol = PtrSymbol::new(pointer, &def)?;
@@ -560,6 +560,8 @@ where
   check_unstable(state, "Deno.UnsafeCallback");
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  // libffi closures run from memory that is writable and executable
+  permissions.check_wx_mapping("Deno.UnsafeCallback")?;
 
   let thread_id: u32 = LOCAL_THREAD_ID.with(|s| {
     let value = *s.borrow();
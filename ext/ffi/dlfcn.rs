@@ -18,6 +18,7 @@ use serde_value::ValueDeserializer;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::ffi::c_void;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
@@ -69,13 +70,12 @@ fn default_optional() -> bool {
   false
 }
 
-// ForeignStatic's name and type fields are read and used by
-// serde_v8 to determine which variant a ForeignSymbol is.
-// They are not used beyond that and are thus marked with underscores.
+// ForeignStatic's type field is read and used by serde_v8 to
+// determine which variant a ForeignSymbol is. It is not used beyond
+// that and is thus marked with an underscore.
 #[derive(Deserialize, Debug)]
 struct ForeignStatic {
-  #[serde(rename(deserialize = "name"))]
-  _name: Option<String>,
+  name: Option<String>,
   #[serde(rename(deserialize = "type"))]
   _type: String,
 }
@@ -83,7 +83,19 @@ struct ForeignStatic {
 #[derive(Debug)]
 enum ForeignSymbol {
   ForeignFunction(ForeignFunction),
-  ForeignStatic(#[allow(dead_code)] ForeignStatic),
+  ForeignStatic(ForeignStatic),
+}
+
+impl ForeignSymbol {
+  /// The name of the symbol in the library, if it differs from its key.
+  fn name(&self) -> Option<&str> {
+    match self {
+      ForeignSymbol::ForeignFunction(foreign_fn) => foreign_fn.name.as_deref(),
+      ForeignSymbol::ForeignStatic(foreign_static) => {
+        foreign_static.name.as_deref()
+      }
+    }
+  }
 }
 
 impl<'de> Deserialize<'de> for ForeignSymbol {
@@ -125,6 +137,10 @@ where
   check_unstable(state, "Deno.dlopen");
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(Some(&PathBuf::from(&path)))?;
+  for (symbol_key, foreign_symbol) in &args.symbols {
+    let symbol = foreign_symbol.name().unwrap_or(symbol_key);
+    permissions.check_symbol(Path::new(&path), symbol)?;
+  }
 
   let lib = Library::open(&path).map_err(|e| {
     dlopen2::Error::OpeningLibraryError(std::io::Error::new(
@@ -51,6 +51,25 @@ fn check_unstable(state: &OpState, api_name: &str) {
 
 pub trait FfiPermissions {
   fn check_partial(&mut self, path: Option<&Path>) -> Result<(), AnyError>;
+  /// Checks that `symbol` may be looked up in the library at `path`, after
+  /// access to the library was checked.
+  fn check_symbol(
+    &mut self,
+    _path: &Path,
+    _symbol: &str,
+  ) -> Result<(), AnyError> {
+    Ok(())
+  }
+  /// Checks that memory may be mapped both writable and executable, which
+  /// callbacks need for their trampolines.
+  fn check_wx_mapping(&mut self, _api_name: &str) -> Result<(), AnyError> {
+    Ok(())
+  }
+  /// Checks that raw pointers may be read from or called, which gets around
+  /// the symbols allowed by `check_symbol`.
+  fn check_raw_pointers(&mut self, _api_name: &str) -> Result<(), AnyError> {
+    Ok(())
+  }
 }
 
 impl FfiPermissions for deno_permissions::PermissionsContainer {
@@ -58,6 +77,32 @@ impl FfiPermissions for deno_permissions::PermissionsContainer {
   fn check_partial(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
     deno_permissions::PermissionsContainer::check_ffi_partial(self, path)
   }
+
+  #[inline(always)]
+  fn check_symbol(
+    &mut self,
+    path: &Path,
+    symbol: &str,
+  ) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_ffi_symbol(
+      self,
+      path,
+      symbol,
+      "Deno.dlopen()",
+    )
+  }
+
+  #[inline(always)]
+  fn check_wx_mapping(&mut self, api_name: &str) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_wx_mapping(self, api_name)
+  }
+
+  #[inline(always)]
+  fn check_raw_pointers(&mut self, api_name: &str) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_ffi_raw_pointers(
+      self, api_name,
+    )
+  }
 }
 
 deno_core::extension!(deno_ffi,
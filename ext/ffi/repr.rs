@@ -142,6 +142,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getArrayBuffer")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid ArrayBuffer pointer, pointer is null"));
@@ -176,6 +177,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#copyInto")?;
 
   if src.is_null() {
     Err(type_error("Invalid ArrayBuffer pointer, pointer is null"))
@@ -209,6 +211,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getCString")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid CString pointer, pointer is null"));
@@ -237,6 +240,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getBool")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid bool pointer, pointer is null"));
@@ -259,6 +263,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getUint8")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid u8 pointer, pointer is null"));
@@ -283,6 +288,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getInt8")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid i8 pointer, pointer is null"));
@@ -307,6 +313,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getUint16")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid u16 pointer, pointer is null"));
@@ -331,6 +338,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getInt16")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid i16 pointer, pointer is null"));
@@ -355,6 +363,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getUint32")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid u32 pointer, pointer is null"));
@@ -377,6 +386,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getInt32")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid i32 pointer, pointer is null"));
@@ -402,6 +412,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getBigUint64")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid u64 pointer, pointer is null"));
@@ -430,6 +441,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getBigInt64")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid i64 pointer, pointer is null"));
@@ -455,6 +467,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getFloat32")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid f32 pointer, pointer is null"));
@@ -477,6 +490,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getFloat64")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid f64 pointer, pointer is null"));
@@ -499,6 +513,7 @@ where
 
   let permissions = state.borrow_mut::<FP>();
   permissions.check_partial(None)?;
+  permissions.check_raw_pointers("Deno.UnsafePointerView#getPointer")?;
 
   if ptr.is_null() {
     return Err(type_error("Invalid pointer pointer, pointer is null"));
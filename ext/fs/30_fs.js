@@ -15,14 +15,28 @@ import {
   op_fs_copy_file_async,
   op_fs_copy_file_sync,
   op_fs_cwd,
+  op_fs_fallocate_async,
+  op_fs_fallocate_sync,
   op_fs_fdatasync_async,
   op_fs_fdatasync_sync,
+  op_fs_fget_xattr_async,
+  op_fs_fget_xattr_sync,
+  op_fs_file_persist_async,
+  op_fs_file_persist_sync,
   op_fs_file_stat_async,
   op_fs_file_stat_sync,
+  op_fs_flist_xattrs_async,
+  op_fs_flist_xattrs_sync,
   op_fs_flock_async,
   op_fs_flock_async_unstable,
   op_fs_flock_sync,
   op_fs_flock_sync_unstable,
+  op_fs_fpunch_hole_async,
+  op_fs_fpunch_hole_sync,
+  op_fs_fremove_xattr_async,
+  op_fs_fremove_xattr_sync,
+  op_fs_fset_xattr_async,
+  op_fs_fset_xattr_sync,
   op_fs_fsync_async,
   op_fs_fsync_sync,
   op_fs_ftruncate_async,
@@ -33,19 +47,32 @@ import {
   op_fs_funlock_sync_unstable,
   op_fs_futime_async,
   op_fs_futime_sync,
+  op_fs_get_xattr_async,
+  op_fs_get_xattr_sync,
   op_fs_link_async,
   op_fs_link_sync,
+  op_fs_list_xattrs_async,
+  op_fs_list_xattrs_sync,
   op_fs_lstat_async,
   op_fs_lstat_sync,
   op_fs_make_temp_dir_async,
   op_fs_make_temp_dir_sync,
+  op_fs_make_temp_file_anonymous_async,
+  op_fs_make_temp_file_anonymous_sync,
   op_fs_make_temp_file_async,
   op_fs_make_temp_file_sync,
   op_fs_mkdir_async,
   op_fs_mkdir_sync,
   op_fs_open_async,
+  op_fs_open_at_async,
+  op_fs_open_at_sync,
+  op_fs_open_dir_async,
+  op_fs_open_dir_at_async,
+  op_fs_open_dir_at_sync,
+  op_fs_open_dir_sync,
   op_fs_open_sync,
-  op_fs_read_dir_async,
+  op_fs_read_dir_next_async,
+  op_fs_read_dir_open_async,
   op_fs_read_dir_sync,
   op_fs_read_file_async,
   op_fs_read_file_sync,
@@ -57,17 +84,29 @@ import {
   op_fs_realpath_sync,
   op_fs_remove_async,
   op_fs_remove_sync,
+  op_fs_remove_xattr_async,
+  op_fs_remove_xattr_sync,
   op_fs_rename_async,
+  op_fs_rename_at_async,
+  op_fs_rename_at_sync,
   op_fs_rename_sync,
   op_fs_seek_async,
   op_fs_seek_sync,
+  op_fs_set_xattr_async,
+  op_fs_set_xattr_sync,
   op_fs_stat_async,
+  op_fs_stat_at_async,
+  op_fs_stat_at_sync,
   op_fs_stat_sync,
+  op_fs_statfs_async,
+  op_fs_statfs_sync,
   op_fs_symlink_async,
   op_fs_symlink_sync,
   op_fs_truncate_async,
   op_fs_truncate_sync,
   op_fs_umask,
+  op_fs_unlink_at_async,
+  op_fs_unlink_at_sync,
   op_fs_utime_async,
   op_fs_utime_sync,
   op_fs_write_file_async,
@@ -176,6 +215,10 @@ function makeTempDir(options = { __proto__: null }) {
 }
 
 function makeTempFileSync(options = { __proto__: null }) {
+  if (options.anonymous) {
+    const rid = op_fs_make_temp_file_anonymous_sync(options.dir);
+    return new FsFile(rid, SymbolFor("Deno.internal.FsFile"));
+  }
   return op_fs_make_temp_file_sync(
     options.dir,
     options.prefix,
@@ -183,7 +226,11 @@ function makeTempFileSync(options = { __proto__: null }) {
   );
 }
 
-function makeTempFile(options = { __proto__: null }) {
+async function makeTempFile(options = { __proto__: null }) {
+  if (options.anonymous) {
+    const rid = await op_fs_make_temp_file_anonymous_async(options.dir);
+    return new FsFile(rid, SymbolFor("Deno.internal.FsFile"));
+  }
   return op_fs_make_temp_file_async(
     options.dir,
     options.prefix,
@@ -214,14 +261,22 @@ function readDirSync(path) {
 }
 
 function readDir(path) {
-  const array = op_fs_read_dir_async(
-    pathFromURL(path),
-  );
+  path = pathFromURL(path);
   return {
     async *[SymbolAsyncIterator]() {
-      const dir = await array;
-      for (let i = 0; i < dir.length; ++i) {
-        yield dir[i];
+      // The directory is read in batches, so that large directories don't
+      // have to be held in memory at once.
+      const rid = await op_fs_read_dir_open_async(path);
+      try {
+        while (true) {
+          const entries = await op_fs_read_dir_next_async(rid);
+          if (entries.length === 0) break;
+          for (let i = 0; i < entries.length; ++i) {
+            yield entries[i];
+          }
+        }
+      } finally {
+        core.tryClose(rid);
       }
     },
   };
@@ -426,6 +481,14 @@ function statSync(path) {
   return statStruct(statBuf);
 }
 
+function statfs(path) {
+  return op_fs_statfs_async(pathFromURL(path));
+}
+
+function statfsSync(path) {
+  return op_fs_statfs_sync(pathFromURL(path));
+}
+
 function coerceLen(len) {
   if (len == null || len < 0) {
     return 0;
@@ -540,6 +603,42 @@ async function utime(
   );
 }
 
+function xattrValue(value) {
+  return typeof value === "string" ? core.encode(value) : value;
+}
+
+function getXattrSync(path, name) {
+  return op_fs_get_xattr_sync(pathFromURL(path), name);
+}
+
+function getXattr(path, name) {
+  return op_fs_get_xattr_async(pathFromURL(path), name);
+}
+
+function setXattrSync(path, name, value) {
+  op_fs_set_xattr_sync(pathFromURL(path), name, xattrValue(value));
+}
+
+async function setXattr(path, name, value) {
+  await op_fs_set_xattr_async(pathFromURL(path), name, xattrValue(value));
+}
+
+function listXattrsSync(path) {
+  return op_fs_list_xattrs_sync(pathFromURL(path));
+}
+
+function listXattrs(path) {
+  return op_fs_list_xattrs_async(pathFromURL(path));
+}
+
+function removeXattrSync(path, name) {
+  op_fs_remove_xattr_sync(pathFromURL(path), name);
+}
+
+async function removeXattr(path, name) {
+  await op_fs_remove_xattr_async(pathFromURL(path), name);
+}
+
 function symlinkSync(
   oldpath,
   newpath,
@@ -707,6 +806,26 @@ class FsFile {
     return ftruncateSync(this.#rid, len);
   }
 
+  async allocate(len) {
+    await op_fs_fallocate_async(this.#rid, coerceLen(len));
+  }
+
+  allocateSync(len) {
+    op_fs_fallocate_sync(this.#rid, coerceLen(len));
+  }
+
+  async punchHole(offset, len) {
+    await op_fs_fpunch_hole_async(
+      this.#rid,
+      coerceLen(offset),
+      coerceLen(len),
+    );
+  }
+
+  punchHoleSync(offset, len) {
+    op_fs_fpunch_hole_sync(this.#rid, coerceLen(offset), coerceLen(len));
+  }
+
   read(p) {
     return read(this.#rid, p);
   }
@@ -739,6 +858,14 @@ class FsFile {
     op_fs_fdatasync_sync(this.#rid);
   }
 
+  async persist(path) {
+    await op_fs_file_persist_async(this.#rid, pathFromURL(path));
+  }
+
+  persistSync(path) {
+    op_fs_file_persist_sync(this.#rid, pathFromURL(path));
+  }
+
   close() {
     core.close(this.#rid);
   }
@@ -757,6 +884,12 @@ class FsFile {
     return this.#writable;
   }
 
+  // Allows `new Response(file)`, which streams the file from its resource
+  // without reading it into JavaScript.
+  get [SymbolFor("Deno.internal.bodyStream")]() {
+    return this.readable;
+  }
+
   async sync() {
     await op_fs_fsync_async(this.#rid);
   }
@@ -773,6 +906,38 @@ class FsFile {
     futimeSync(this.#rid, atime, mtime);
   }
 
+  getXattr(name) {
+    return op_fs_fget_xattr_async(this.#rid, name);
+  }
+
+  getXattrSync(name) {
+    return op_fs_fget_xattr_sync(this.#rid, name);
+  }
+
+  async setXattr(name, value) {
+    await op_fs_fset_xattr_async(this.#rid, name, xattrValue(value));
+  }
+
+  setXattrSync(name, value) {
+    op_fs_fset_xattr_sync(this.#rid, name, xattrValue(value));
+  }
+
+  listXattrs() {
+    return op_fs_flist_xattrs_async(this.#rid);
+  }
+
+  listXattrsSync() {
+    return op_fs_flist_xattrs_sync(this.#rid);
+  }
+
+  async removeXattr(name) {
+    await op_fs_fremove_xattr_async(this.#rid, name);
+  }
+
+  removeXattrSync(name) {
+    op_fs_fremove_xattr_sync(this.#rid, name);
+  }
+
   isTerminal() {
     return core.isTerminal(this.#rid);
   }
@@ -830,6 +995,99 @@ function checkOpenOptions(options) {
 
 const File = FsFile;
 
+async function openDir(path) {
+  const rid = await op_fs_open_dir_async(pathFromURL(path));
+  return new DirHandle(rid, SymbolFor("Deno.internal.DirHandle"));
+}
+
+function openDirSync(path) {
+  const rid = op_fs_open_dir_sync(pathFromURL(path));
+  return new DirHandle(rid, SymbolFor("Deno.internal.DirHandle"));
+}
+
+class DirHandle {
+  #rid = 0;
+
+  constructor(rid, symbol) {
+    if (symbol !== SymbolFor("Deno.internal.DirHandle")) {
+      throw new TypeError(
+        "`Deno.DirHandle` cannot be constructed, use `Deno.openDir()` or `Deno.openDirSync()` instead.",
+      );
+    }
+    ObjectDefineProperty(this, internalRidSymbol, {
+      enumerable: false,
+      value: rid,
+    });
+    this.#rid = rid;
+  }
+
+  async openAt(path, options) {
+    if (options) checkOpenOptions(options);
+    const rid = await op_fs_open_at_async(this.#rid, path, options);
+    return new FsFile(rid, SymbolFor("Deno.internal.FsFile"));
+  }
+
+  openAtSync(path, options) {
+    if (options) checkOpenOptions(options);
+    const rid = op_fs_open_at_sync(this.#rid, path, options);
+    return new FsFile(rid, SymbolFor("Deno.internal.FsFile"));
+  }
+
+  async openDirAt(path) {
+    const rid = await op_fs_open_dir_at_async(this.#rid, path);
+    return new DirHandle(rid, SymbolFor("Deno.internal.DirHandle"));
+  }
+
+  openDirAtSync(path) {
+    const rid = op_fs_open_dir_at_sync(this.#rid, path);
+    return new DirHandle(rid, SymbolFor("Deno.internal.DirHandle"));
+  }
+
+  async statAt(path) {
+    const res = await op_fs_stat_at_async(this.#rid, path, true);
+    return parseFileInfo(res);
+  }
+
+  statAtSync(path) {
+    op_fs_stat_at_sync(this.#rid, path, true, statBuf);
+    return statStruct(statBuf);
+  }
+
+  async lstatAt(path) {
+    const res = await op_fs_stat_at_async(this.#rid, path, false);
+    return parseFileInfo(res);
+  }
+
+  lstatAtSync(path) {
+    op_fs_stat_at_sync(this.#rid, path, false, statBuf);
+    return statStruct(statBuf);
+  }
+
+  async renameAt(oldpath, newpath, newDir = this) {
+    await op_fs_rename_at_async(this.#rid, oldpath, newDir.#rid, newpath);
+  }
+
+  renameAtSync(oldpath, newpath, newDir = this) {
+    op_fs_rename_at_sync(this.#rid, oldpath, newDir.#rid, newpath);
+  }
+
+  async unlinkAt(path, options) {
+    await op_fs_unlink_at_async(this.#rid, path, !!options?.directory);
+  }
+
+  unlinkAtSync(path, options) {
+    op_fs_unlink_at_sync(this.#rid, path, !!options?.directory);
+  }
+
+  close() {
+    core.close(this.#rid);
+  }
+
+  [SymbolDispose]() {
+    core.tryClose(this.#rid);
+  }
+}
+
 function readFileSync(path) {
   return op_fs_read_file_sync(pathFromURL(path));
 }
@@ -990,6 +1248,7 @@ export {
   create,
   createSync,
   cwd,
+  DirHandle,
   fdatasync,
   fdatasyncSync,
   File,
@@ -1006,8 +1265,12 @@ export {
   funlockSync,
   futime,
   futimeSync,
+  getXattr,
+  getXattrSync,
   link,
   linkSync,
+  listXattrs,
+  listXattrsSync,
   lstat,
   lstatSync,
   makeTempDir,
@@ -1017,6 +1280,8 @@ export {
   mkdir,
   mkdirSync,
   open,
+  openDir,
+  openDirSync,
   openSync,
   readDir,
   readDirSync,
@@ -1030,11 +1295,17 @@ export {
   realPathSync,
   remove,
   removeSync,
+  removeXattr,
+  removeXattrSync,
   rename,
   renameSync,
   seek,
   seekSync,
+  setXattr,
+  setXattrSync,
   stat,
+  statfs,
+  statfsSync,
   statSync,
   symlink,
   symlinkSync,
@@ -0,0 +1,386 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Handles to open directories, for operating on the entries of a directory
+//! without resolving its path again.
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::normalize_path;
+use deno_core::Resource;
+use deno_io::fs::FsResult;
+use deno_io::fs::FsStat;
+
+use crate::OpenOptions;
+
+/// An open directory. Relative paths given to its methods are resolved from
+/// the directory itself, so they keep referring to its entries even if the
+/// directory is moved or its path is replaced by a symlink in the meantime.
+#[derive(Debug)]
+pub struct DirHandle {
+  /// The absolute path the directory was opened at. It is only used for
+  /// permission checks and error messages, see [`DirHandle::resolve`].
+  path: PathBuf,
+  #[cfg(unix)]
+  dir: std::fs::File,
+}
+
+impl DirHandle {
+  /// Opens the directory at the absolute path `path`.
+  pub fn open(path: PathBuf) -> FsResult<Self> {
+    sys::open(path)
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// The path that `path` refers to when resolved from this directory, for
+  /// permission checks. It is resolved from where the directory is now, so
+  /// that the checks follow the directory when it is moved. Where that can't
+  /// be queried, it is resolved from the path the directory was opened at,
+  /// which goes stale once the directory is moved.
+  pub fn resolve(&self, path: &Path) -> PathBuf {
+    let dir_path = sys::current_path(self);
+    let dir_path = dir_path.as_deref().unwrap_or(&self.path);
+    normalize_path(dir_path.join(path))
+  }
+
+  pub fn try_clone(&self) -> FsResult<Self> {
+    sys::try_clone(self)
+  }
+
+  /// Opens the directory at `path` relative to this one.
+  pub fn open_dir_at(&self, path: &Path) -> FsResult<Self> {
+    sys::open_dir_at(self, path)
+  }
+
+  /// Opens the file at `path` relative to this directory.
+  pub fn open_at(
+    &self,
+    path: &Path,
+    options: &OpenOptions,
+  ) -> FsResult<std::fs::File> {
+    sys::open_at(self, path, options)
+  }
+
+  /// Returns information about the entry at `path` relative to this
+  /// directory, or about the symlink itself if `follow_symlinks` is false.
+  pub fn stat_at(
+    &self,
+    path: &Path,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    sys::stat_at(self, path, follow_symlinks)
+  }
+
+  /// Renames the entry at `oldpath` relative to this directory to `newpath`
+  /// relative to `new_dir`.
+  pub fn rename_at(
+    &self,
+    oldpath: &Path,
+    new_dir: &DirHandle,
+    newpath: &Path,
+  ) -> FsResult<()> {
+    sys::rename_at(self, oldpath, new_dir, newpath)
+  }
+
+  /// Removes the entry at `path` relative to this directory. Directories are
+  /// only removed if `is_dir` is true, and only if they are empty.
+  pub fn unlink_at(&self, path: &Path, is_dir: bool) -> FsResult<()> {
+    sys::unlink_at(self, path, is_dir)
+  }
+}
+
+pub struct DirHandleResource(pub DirHandle);
+
+impl Resource for DirHandleResource {
+  fn name(&self) -> Cow<str> {
+    "fsDir".into()
+  }
+}
+
+#[cfg(unix)]
+mod sys {
+  use std::ffi::CString;
+  use std::fs::File;
+  use std::io;
+  use std::os::fd::AsRawFd;
+  use std::os::fd::FromRawFd;
+  use std::os::unix::ffi::OsStrExt;
+  use std::os::unix::fs::OpenOptionsExt;
+  use std::path::Path;
+  use std::path::PathBuf;
+
+  use deno_io::fs::FsResult;
+  use deno_io::fs::FsStat;
+
+  use super::DirHandle;
+  use crate::OpenOptions;
+
+  fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+  }
+
+  fn cvt(ret: libc::c_int) -> io::Result<libc::c_int> {
+    if ret < 0 {
+      Err(io::Error::last_os_error())
+    } else {
+      Ok(ret)
+    }
+  }
+
+  pub fn open(path: PathBuf) -> FsResult<DirHandle> {
+    let dir = std::fs::OpenOptions::new()
+      .read(true)
+      .custom_flags(libc::O_DIRECTORY)
+      .open(&path)?;
+    Ok(DirHandle { path, dir })
+  }
+
+  /// Where the directory is now, which differs from where it was opened if
+  /// it was moved since.
+  #[cfg(target_os = "linux")]
+  #[allow(clippy::disallowed_methods)]
+  pub fn current_path(handle: &DirHandle) -> Option<PathBuf> {
+    let path =
+      std::fs::read_link(format!("/proc/self/fd/{}", handle.dir.as_raw_fd()))
+        .ok()?;
+    // The link of a removed directory ends in " (deleted)".
+    let deleted = path.as_os_str().as_bytes().ends_with(b" (deleted)");
+    (path.is_absolute() && !deleted).then_some(path)
+  }
+
+  #[cfg(target_os = "macos")]
+  pub fn current_path(handle: &DirHandle) -> Option<PathBuf> {
+    use std::ffi::OsStr;
+
+    let mut buf = vec![0u8; libc::PATH_MAX as usize];
+    // SAFETY: the directory fd is open and `buf` has room for the
+    // `PATH_MAX` bytes that `F_GETPATH` writes at most.
+    cvt(unsafe {
+      libc::fcntl(handle.dir.as_raw_fd(), libc::F_GETPATH, buf.as_mut_ptr())
+    })
+    .ok()?;
+    let len = buf.iter().position(|b| *b == 0)?;
+    Some(PathBuf::from(OsStr::from_bytes(&buf[..len])))
+  }
+
+  #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+  pub fn current_path(_handle: &DirHandle) -> Option<PathBuf> {
+    None
+  }
+
+  pub fn try_clone(handle: &DirHandle) -> FsResult<DirHandle> {
+    Ok(DirHandle {
+      path: handle.path.clone(),
+      dir: handle.dir.try_clone()?,
+    })
+  }
+
+  fn openat(
+    handle: &DirHandle,
+    path: &Path,
+    flags: libc::c_int,
+    mode: libc::c_uint,
+  ) -> io::Result<File> {
+    let path = c_path(path)?;
+    // SAFETY: the directory fd is open and `path` is a valid C string.
+    let fd = cvt(unsafe {
+      libc::openat(
+        handle.dir.as_raw_fd(),
+        path.as_ptr(),
+        flags | libc::O_CLOEXEC,
+        mode,
+      )
+    })?;
+    // SAFETY: `fd` was just opened and is owned by nothing else.
+    Ok(unsafe { File::from_raw_fd(fd) })
+  }
+
+  pub fn open_dir_at(handle: &DirHandle, path: &Path) -> FsResult<DirHandle> {
+    let dir = openat(handle, path, libc::O_RDONLY | libc::O_DIRECTORY, 0)?;
+    Ok(DirHandle {
+      path: handle.resolve(path),
+      dir,
+    })
+  }
+
+  pub fn open_at(
+    handle: &DirHandle,
+    path: &Path,
+    options: &OpenOptions,
+  ) -> FsResult<File> {
+    let (flags, mode) = options.to_flags_and_mode();
+    Ok(openat(handle, path, flags, mode as libc::c_uint)?)
+  }
+
+  pub fn stat_at(
+    handle: &DirHandle,
+    path: &Path,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    let path = c_path(path)?;
+    let flags = if follow_symlinks {
+      0
+    } else {
+      libc::AT_SYMLINK_NOFOLLOW
+    };
+    let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+    // SAFETY: the directory fd is open, `path` is a valid C string and
+    // `stat` is valid for writes.
+    cvt(unsafe {
+      libc::fstatat(
+        handle.dir.as_raw_fd(),
+        path.as_ptr(),
+        stat.as_mut_ptr(),
+        flags,
+      )
+    })?;
+    // SAFETY: `fstatat` succeeded, so it initialized `stat`.
+    Ok(fs_stat(unsafe { &stat.assume_init() }))
+  }
+
+  #[allow(clippy::unnecessary_cast)]
+  fn fs_stat(stat: &libc::stat) -> FsStat {
+    fn to_msec(secs: i64, nsecs: i64) -> Option<u64> {
+      let msecs = secs as i128 * 1000 + nsecs as i128 / 1_000_000;
+      // Times before the epoch are reported as their distance from it, like
+      // `FsStat::from_std` does.
+      Some(msecs.unsigned_abs() as u64)
+    }
+
+    #[cfg(target_os = "macos")]
+    let birthtime =
+      to_msec(stat.st_birthtime as i64, stat.st_birthtime_nsec as i64);
+    #[cfg(not(target_os = "macos"))]
+    let birthtime = None;
+
+    let file_type = stat.st_mode as u32 & libc::S_IFMT as u32;
+    FsStat {
+      is_file: file_type == libc::S_IFREG as u32,
+      is_directory: file_type == libc::S_IFDIR as u32,
+      is_symlink: file_type == libc::S_IFLNK as u32,
+      size: stat.st_size as u64,
+
+      mtime: to_msec(stat.st_mtime as i64, stat.st_mtime_nsec as i64),
+      atime: to_msec(stat.st_atime as i64, stat.st_atime_nsec as i64),
+      birthtime,
+
+      dev: stat.st_dev as u64,
+      ino: stat.st_ino as u64,
+      mode: stat.st_mode as u32,
+      nlink: stat.st_nlink as u64,
+      uid: stat.st_uid as u32,
+      gid: stat.st_gid as u32,
+      rdev: stat.st_rdev as u64,
+      blksize: stat.st_blksize as u64,
+      blocks: stat.st_blocks as u64,
+      is_block_device: file_type == libc::S_IFBLK as u32,
+      is_char_device: file_type == libc::S_IFCHR as u32,
+      is_fifo: file_type == libc::S_IFIFO as u32,
+      is_socket: file_type == libc::S_IFSOCK as u32,
+    }
+  }
+
+  pub fn rename_at(
+    handle: &DirHandle,
+    oldpath: &Path,
+    new_dir: &DirHandle,
+    newpath: &Path,
+  ) -> FsResult<()> {
+    let oldpath = c_path(oldpath)?;
+    let newpath = c_path(newpath)?;
+    // SAFETY: both directory fds are open and both paths are valid C
+    // strings.
+    cvt(unsafe {
+      libc::renameat(
+        handle.dir.as_raw_fd(),
+        oldpath.as_ptr(),
+        new_dir.dir.as_raw_fd(),
+        newpath.as_ptr(),
+      )
+    })?;
+    Ok(())
+  }
+
+  pub fn unlink_at(
+    handle: &DirHandle,
+    path: &Path,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    let path = c_path(path)?;
+    let flags = if is_dir { libc::AT_REMOVEDIR } else { 0 };
+    // SAFETY: the directory fd is open and `path` is a valid C string.
+    cvt(unsafe {
+      libc::unlinkat(handle.dir.as_raw_fd(), path.as_ptr(), flags)
+    })?;
+    Ok(())
+  }
+}
+
+/// Windows has no equivalent of the `*at` functions that works on arbitrary
+/// paths, so directory handles are not supported there.
+#[cfg(not(unix))]
+mod sys {
+  use std::path::Path;
+  use std::path::PathBuf;
+
+  use deno_io::fs::FsError;
+  use deno_io::fs::FsResult;
+  use deno_io::fs::FsStat;
+
+  use super::DirHandle;
+  use crate::OpenOptions;
+
+  pub fn open(_path: PathBuf) -> FsResult<DirHandle> {
+    Err(FsError::NotSupported)
+  }
+
+  pub fn current_path(_handle: &DirHandle) -> Option<PathBuf> {
+    None
+  }
+
+  pub fn try_clone(_handle: &DirHandle) -> FsResult<DirHandle> {
+    Err(FsError::NotSupported)
+  }
+
+  pub fn open_dir_at(_handle: &DirHandle, _path: &Path) -> FsResult<DirHandle> {
+    Err(FsError::NotSupported)
+  }
+
+  pub fn open_at(
+    _handle: &DirHandle,
+    _path: &Path,
+    _options: &OpenOptions,
+  ) -> FsResult<std::fs::File> {
+    Err(FsError::NotSupported)
+  }
+
+  pub fn stat_at(
+    _handle: &DirHandle,
+    _path: &Path,
+    _follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    Err(FsError::NotSupported)
+  }
+
+  pub fn rename_at(
+    _handle: &DirHandle,
+    _oldpath: &Path,
+    _new_dir: &DirHandle,
+    _newpath: &Path,
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+
+  pub fn unlink_at(
+    _handle: &DirHandle,
+    _path: &Path,
+    _is_dir: bool,
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+}
@@ -0,0 +1,1282 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! A `FileSystem` that reports every operation to a hook, so that embedders
+//! can audit, limit or deny file system access without implementing the
+//! whole trait themselves.
+
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
+
+use deno_core::BufMutView;
+use deno_core::BufView;
+use deno_core::ResourceHandleFd;
+use deno_core::WriteOutcome;
+use deno_io::fs::File;
+use deno_io::fs::FsError;
+use deno_io::fs::FsResult;
+use deno_io::fs::FsStat;
+
+use crate::dir_handle::DirHandle;
+use crate::interface::AccessCheckCb;
+use crate::interface::FileSystemRc;
+use crate::interface::FsDirEntry;
+use crate::interface::FsFileType;
+use crate::interface::FsReadDir;
+use crate::interface::FsStatfs;
+use crate::sync::MaybeArc;
+use crate::sync::MaybeSend;
+use crate::sync::MaybeSync;
+use crate::FileSystem;
+use crate::OpenOptions;
+
+/// The kind of an operation reported to an `FsHook`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsOp {
+  Chdir,
+  Open,
+  MakeTempFile,
+  PersistFile,
+  OpenDir,
+  Mkdir,
+  Chmod,
+  Chown,
+  Lchown,
+  Remove,
+  CopyFile,
+  Cp,
+  Stat,
+  Lstat,
+  Realpath,
+  Statfs,
+  ReadDir,
+  Rename,
+  Link,
+  Symlink,
+  ReadLink,
+  Truncate,
+  Utime,
+  Lutime,
+  GetXattr,
+  SetXattr,
+  ListXattrs,
+  RemoveXattr,
+  WriteFile,
+  ReadFile,
+  /// A read from an open file.
+  Read,
+  /// A write to an open file.
+  Write,
+  Allocate,
+  PunchHole,
+}
+
+/// An operation reported to an `FsHook`.
+#[derive(Debug, Clone, Copy)]
+pub struct FsOperation<'a> {
+  pub op: FsOp,
+  pub path: &'a Path,
+  /// The second path of operations like `rename`, `link` and `symlink`.
+  pub new_path: Option<&'a Path>,
+  /// Whether the operation may modify the file system.
+  pub write: bool,
+}
+
+impl<'a> FsOperation<'a> {
+  fn read(op: FsOp, path: &'a Path) -> Self {
+    Self {
+      op,
+      path,
+      new_path: None,
+      write: false,
+    }
+  }
+
+  fn write(op: FsOp, path: &'a Path) -> Self {
+    Self {
+      op,
+      path,
+      new_path: None,
+      write: true,
+    }
+  }
+
+  fn open(op: FsOp, path: &'a Path, options: &OpenOptions) -> Self {
+    Self {
+      op,
+      path,
+      new_path: None,
+      write: options.write
+        || options.append
+        || options.create
+        || options.create_new
+        || options.truncate,
+    }
+  }
+
+  fn with_new_path(self, new_path: &'a Path) -> Self {
+    Self {
+      new_path: Some(new_path),
+      ..self
+    }
+  }
+}
+
+/// Receives the operations done through a `HookedFs`.
+///
+/// Operations on paths are reported, including those on the entries of a
+/// `DirHandle`, with the path they resolve to. So are the reads, writes and
+/// changes of the files opened through it, with the path they were opened
+/// at. Uses of the raw file descriptor of an open file, like the xattr ops
+/// and the stdio of subprocesses, are not.
+pub trait FsHook: std::fmt::Debug + MaybeSend + MaybeSync {
+  /// Called before an operation is done. Returning an error fails the
+  /// operation with it, without doing it.
+  fn before(&self, _operation: &FsOperation) -> FsResult<()> {
+    Ok(())
+  }
+
+  /// Called after an operation was done, with its outcome and how long it
+  /// took.
+  fn after(
+    &self,
+    _operation: &FsOperation,
+    _result: Result<(), &FsError>,
+    _duration: Duration,
+  ) {
+  }
+}
+
+fn report<H: FsHook, T>(
+  hook: &H,
+  operation: FsOperation,
+  f: impl FnOnce() -> FsResult<T>,
+) -> FsResult<T> {
+  hook.before(&operation)?;
+  let start = Instant::now();
+  let result = f();
+  hook.after(&operation, result.as_ref().map(|_| ()), start.elapsed());
+  result
+}
+
+async fn report_async<H: FsHook, T>(
+  hook: &H,
+  operation: FsOperation<'_>,
+  f: impl Future<Output = FsResult<T>>,
+) -> FsResult<T> {
+  hook.before(&operation)?;
+  let start = Instant::now();
+  let result = f.await;
+  hook.after(&operation, result.as_ref().map(|_| ()), start.elapsed());
+  result
+}
+
+/// A `FileSystem` that passes every operation on to `inner`, reporting it to
+/// `hook`.
+#[derive(Debug)]
+pub struct HookedFs<H: FsHook> {
+  inner: FileSystemRc,
+  hook: MaybeArc<H>,
+}
+
+impl<H: FsHook + 'static> HookedFs<H> {
+  pub fn new(inner: FileSystemRc, hook: H) -> Self {
+    Self {
+      inner,
+      hook: MaybeArc::new(hook),
+    }
+  }
+
+  pub fn hook(&self) -> &H {
+    &self.hook
+  }
+
+  fn run<T>(
+    &self,
+    operation: FsOperation,
+    f: impl FnOnce() -> FsResult<T>,
+  ) -> FsResult<T> {
+    report(&*self.hook, operation, f)
+  }
+
+  async fn run_async<T>(
+    &self,
+    operation: FsOperation<'_>,
+    f: impl Future<Output = FsResult<T>>,
+  ) -> FsResult<T> {
+    report_async(&*self.hook, operation, f).await
+  }
+
+  /// Reports the operations on `file`, which was opened at `path`.
+  fn hook_file(&self, file: Rc<dyn File>, path: &Path) -> Rc<dyn File> {
+    Rc::new(HookedFile {
+      inner: file,
+      path: path.to_path_buf(),
+      hook: self.hook.clone(),
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl<H: FsHook + 'static> FileSystem for HookedFs<H> {
+  fn cwd(&self) -> FsResult<PathBuf> {
+    self.inner.cwd()
+  }
+
+  fn tmp_dir(&self) -> FsResult<PathBuf> {
+    self.inner.tmp_dir()
+  }
+
+  fn chdir(&self, path: &Path) -> FsResult<()> {
+    let operation = FsOperation::read(FsOp::Chdir, path);
+    self.run(operation, || self.inner.chdir(path))
+  }
+
+  fn umask(&self, mask: Option<u32>) -> FsResult<u32> {
+    self.inner.umask(mask)
+  }
+
+  fn open_sync(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+    access_check: Option<AccessCheckCb>,
+  ) -> FsResult<Rc<dyn File>> {
+    let operation = FsOperation::open(FsOp::Open, path, &options);
+    let file = self.run(operation, || {
+      self.inner.open_sync(path, options, access_check)
+    })?;
+    Ok(self.hook_file(file, path))
+  }
+  async fn open_async<'a>(
+    &'a self,
+    path: PathBuf,
+    options: OpenOptions,
+    access_check: Option<AccessCheckCb<'a>>,
+  ) -> FsResult<Rc<dyn File>> {
+    let operation = FsOperation::open(FsOp::Open, &path, &options);
+    let f = self.inner.open_async(path.clone(), options, access_check);
+    let file = self.run_async(operation, f).await?;
+    Ok(self.hook_file(file, &path))
+  }
+
+  fn make_anonymous_temp_file_sync(
+    &self,
+    dir: &Path,
+  ) -> FsResult<Rc<dyn File>> {
+    let operation = FsOperation::write(FsOp::MakeTempFile, dir);
+    let file =
+      self.run(operation, || self.inner.make_anonymous_temp_file_sync(dir))?;
+    Ok(self.hook_file(file, dir))
+  }
+  async fn make_anonymous_temp_file_async(
+    &self,
+    dir: PathBuf,
+  ) -> FsResult<Rc<dyn File>> {
+    let operation = FsOperation::write(FsOp::MakeTempFile, &dir);
+    let f = self.inner.make_anonymous_temp_file_async(dir.clone());
+    let file = self.run_async(operation, f).await?;
+    Ok(self.hook_file(file, &dir))
+  }
+
+  fn persist_file_sync(&self, file: Rc<dyn File>, path: &Path) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::PersistFile, path);
+    self.run(operation, || self.inner.persist_file_sync(file, path))
+  }
+  async fn persist_file_async(
+    &self,
+    file: Rc<dyn File>,
+    path: PathBuf,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::PersistFile, &path);
+    let f = self.inner.persist_file_async(file, path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn open_dir_handle_sync(&self, path: &Path) -> FsResult<DirHandle> {
+    let operation = FsOperation::read(FsOp::OpenDir, path);
+    self.run(operation, || self.inner.open_dir_handle_sync(path))
+  }
+  async fn open_dir_handle_async(&self, path: PathBuf) -> FsResult<DirHandle> {
+    let operation = FsOperation::read(FsOp::OpenDir, &path);
+    let f = self.inner.open_dir_handle_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn open_dir_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+  ) -> FsResult<DirHandle> {
+    let full_path = dir.resolve(path);
+    let operation = FsOperation::read(FsOp::OpenDir, &full_path);
+    self.run(operation, || self.inner.open_dir_at_sync(dir, path))
+  }
+  async fn open_dir_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+  ) -> FsResult<DirHandle> {
+    let full_path = dir.resolve(&path);
+    let operation = FsOperation::read(FsOp::OpenDir, &full_path);
+    let f = self.inner.open_dir_at_async(dir, path);
+    self.run_async(operation, f).await
+  }
+
+  fn open_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    let full_path = dir.resolve(path);
+    let operation = FsOperation::open(FsOp::Open, &full_path, &options);
+    let file =
+      self.run(operation, || self.inner.open_at_sync(dir, path, options))?;
+    Ok(self.hook_file(file, &full_path))
+  }
+  async fn open_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    let full_path = dir.resolve(&path);
+    let operation = FsOperation::open(FsOp::Open, &full_path, &options);
+    let f = self.inner.open_at_async(dir, path, options);
+    let file = self.run_async(operation, f).await?;
+    Ok(self.hook_file(file, &full_path))
+  }
+
+  fn stat_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    let full_path = dir.resolve(path);
+    let op = if follow_symlinks {
+      FsOp::Stat
+    } else {
+      FsOp::Lstat
+    };
+    let operation = FsOperation::read(op, &full_path);
+    self.run(operation, || {
+      self.inner.stat_at_sync(dir, path, follow_symlinks)
+    })
+  }
+  async fn stat_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    let full_path = dir.resolve(&path);
+    let op = if follow_symlinks {
+      FsOp::Stat
+    } else {
+      FsOp::Lstat
+    };
+    let operation = FsOperation::read(op, &full_path);
+    let f = self.inner.stat_at_async(dir, path, follow_symlinks);
+    self.run_async(operation, f).await
+  }
+
+  fn rename_at_sync(
+    &self,
+    dir: &DirHandle,
+    oldpath: &Path,
+    new_dir: &DirHandle,
+    newpath: &Path,
+  ) -> FsResult<()> {
+    let full_oldpath = dir.resolve(oldpath);
+    let full_newpath = new_dir.resolve(newpath);
+    let operation = FsOperation::write(FsOp::Rename, &full_oldpath)
+      .with_new_path(&full_newpath);
+    self.run(operation, || {
+      self.inner.rename_at_sync(dir, oldpath, new_dir, newpath)
+    })
+  }
+  async fn rename_at_async(
+    &self,
+    dir: DirHandle,
+    oldpath: PathBuf,
+    new_dir: DirHandle,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    let full_oldpath = dir.resolve(&oldpath);
+    let full_newpath = new_dir.resolve(&newpath);
+    let operation = FsOperation::write(FsOp::Rename, &full_oldpath)
+      .with_new_path(&full_newpath);
+    let f = self.inner.rename_at_async(dir, oldpath, new_dir, newpath);
+    self.run_async(operation, f).await
+  }
+
+  fn unlink_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    let full_path = dir.resolve(path);
+    let operation = FsOperation::write(FsOp::Remove, &full_path);
+    self.run(operation, || self.inner.unlink_at_sync(dir, path, is_dir))
+  }
+  async fn unlink_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    let full_path = dir.resolve(&path);
+    let operation = FsOperation::write(FsOp::Remove, &full_path);
+    let f = self.inner.unlink_at_async(dir, path, is_dir);
+    self.run_async(operation, f).await
+  }
+
+  fn mkdir_sync(
+    &self,
+    path: &Path,
+    recursive: bool,
+    mode: u32,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Mkdir, path);
+    self.run(operation, || self.inner.mkdir_sync(path, recursive, mode))
+  }
+  async fn mkdir_async(
+    &self,
+    path: PathBuf,
+    recursive: bool,
+    mode: u32,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Mkdir, &path);
+    let f = self.inner.mkdir_async(path.clone(), recursive, mode);
+    self.run_async(operation, f).await
+  }
+
+  fn chmod_sync(&self, path: &Path, mode: u32) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Chmod, path);
+    self.run(operation, || self.inner.chmod_sync(path, mode))
+  }
+  async fn chmod_async(&self, path: PathBuf, mode: u32) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Chmod, &path);
+    let f = self.inner.chmod_async(path.clone(), mode);
+    self.run_async(operation, f).await
+  }
+
+  fn chown_sync(
+    &self,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Chown, path);
+    self.run(operation, || self.inner.chown_sync(path, uid, gid))
+  }
+  async fn chown_async(
+    &self,
+    path: PathBuf,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Chown, &path);
+    let f = self.inner.chown_async(path.clone(), uid, gid);
+    self.run_async(operation, f).await
+  }
+
+  fn lchown_sync(
+    &self,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Lchown, path);
+    self.run(operation, || self.inner.lchown_sync(path, uid, gid))
+  }
+  async fn lchown_async(
+    &self,
+    path: PathBuf,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Lchown, &path);
+    let f = self.inner.lchown_async(path.clone(), uid, gid);
+    self.run_async(operation, f).await
+  }
+
+  fn remove_sync(&self, path: &Path, recursive: bool) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Remove, path);
+    self.run(operation, || self.inner.remove_sync(path, recursive))
+  }
+  async fn remove_async(&self, path: PathBuf, recursive: bool) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Remove, &path);
+    let f = self.inner.remove_async(path.clone(), recursive);
+    self.run_async(operation, f).await
+  }
+
+  fn copy_file_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::CopyFile, oldpath).with_new_path(newpath);
+    self.run(operation, || self.inner.copy_file_sync(oldpath, newpath))
+  }
+  async fn copy_file_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::CopyFile, &oldpath).with_new_path(&newpath);
+    let f = self.inner.copy_file_async(oldpath.clone(), newpath.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn cp_sync(&self, path: &Path, new_path: &Path) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Cp, path).with_new_path(new_path);
+    self.run(operation, || self.inner.cp_sync(path, new_path))
+  }
+  async fn cp_async(&self, path: PathBuf, new_path: PathBuf) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Cp, &path).with_new_path(&new_path);
+    let f = self.inner.cp_async(path.clone(), new_path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn stat_sync(&self, path: &Path) -> FsResult<FsStat> {
+    let operation = FsOperation::read(FsOp::Stat, path);
+    self.run(operation, || self.inner.stat_sync(path))
+  }
+  async fn stat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+    let operation = FsOperation::read(FsOp::Stat, &path);
+    let f = self.inner.stat_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn lstat_sync(&self, path: &Path) -> FsResult<FsStat> {
+    let operation = FsOperation::read(FsOp::Lstat, path);
+    self.run(operation, || self.inner.lstat_sync(path))
+  }
+  async fn lstat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+    let operation = FsOperation::read(FsOp::Lstat, &path);
+    let f = self.inner.lstat_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn realpath_sync(&self, path: &Path) -> FsResult<PathBuf> {
+    let operation = FsOperation::read(FsOp::Realpath, path);
+    self.run(operation, || self.inner.realpath_sync(path))
+  }
+  async fn realpath_async(&self, path: PathBuf) -> FsResult<PathBuf> {
+    let operation = FsOperation::read(FsOp::Realpath, &path);
+    let f = self.inner.realpath_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn statfs_sync(&self, path: &Path) -> FsResult<FsStatfs> {
+    let operation = FsOperation::read(FsOp::Statfs, path);
+    self.run(operation, || self.inner.statfs_sync(path))
+  }
+  async fn statfs_async(&self, path: PathBuf) -> FsResult<FsStatfs> {
+    let operation = FsOperation::read(FsOp::Statfs, &path);
+    let f = self.inner.statfs_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
+    let operation = FsOperation::read(FsOp::ReadDir, path);
+    self.run(operation, || self.inner.read_dir_sync(path))
+  }
+  async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>> {
+    let operation = FsOperation::read(FsOp::ReadDir, &path);
+    let f = self.inner.read_dir_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn open_read_dir_sync(&self, path: &Path) -> FsResult<Box<dyn FsReadDir>> {
+    let operation = FsOperation::read(FsOp::ReadDir, path);
+    self.run(operation, || self.inner.open_read_dir_sync(path))
+  }
+  async fn open_read_dir_async(
+    &self,
+    path: PathBuf,
+  ) -> FsResult<Box<dyn FsReadDir>> {
+    let operation = FsOperation::read(FsOp::ReadDir, &path);
+    let f = self.inner.open_read_dir_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Rename, oldpath).with_new_path(newpath);
+    self.run(operation, || self.inner.rename_sync(oldpath, newpath))
+  }
+  async fn rename_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Rename, &oldpath).with_new_path(&newpath);
+    let f = self.inner.rename_async(oldpath.clone(), newpath.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn link_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Link, oldpath).with_new_path(newpath);
+    self.run(operation, || self.inner.link_sync(oldpath, newpath))
+  }
+  async fn link_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Link, &oldpath).with_new_path(&newpath);
+    let f = self.inner.link_async(oldpath.clone(), newpath.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn symlink_sync(
+    &self,
+    oldpath: &Path,
+    newpath: &Path,
+    file_type: Option<FsFileType>,
+  ) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Symlink, oldpath).with_new_path(newpath);
+    self.run(operation, || {
+      self.inner.symlink_sync(oldpath, newpath, file_type)
+    })
+  }
+  async fn symlink_async(
+    &self,
+    oldpath: PathBuf,
+    newpath: PathBuf,
+    file_type: Option<FsFileType>,
+  ) -> FsResult<()> {
+    let operation =
+      FsOperation::write(FsOp::Symlink, &oldpath).with_new_path(&newpath);
+    let f =
+      self
+        .inner
+        .symlink_async(oldpath.clone(), newpath.clone(), file_type);
+    self.run_async(operation, f).await
+  }
+
+  fn read_link_sync(&self, path: &Path) -> FsResult<PathBuf> {
+    let operation = FsOperation::read(FsOp::ReadLink, path);
+    self.run(operation, || self.inner.read_link_sync(path))
+  }
+  async fn read_link_async(&self, path: PathBuf) -> FsResult<PathBuf> {
+    let operation = FsOperation::read(FsOp::ReadLink, &path);
+    let f = self.inner.read_link_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn truncate_sync(&self, path: &Path, len: u64) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Truncate, path);
+    self.run(operation, || self.inner.truncate_sync(path, len))
+  }
+  async fn truncate_async(&self, path: PathBuf, len: u64) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Truncate, &path);
+    let f = self.inner.truncate_async(path.clone(), len);
+    self.run_async(operation, f).await
+  }
+
+  fn utime_sync(
+    &self,
+    path: &Path,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Utime, path);
+    self.run(operation, || {
+      self.inner.utime_sync(
+        path,
+        atime_secs,
+        atime_nanos,
+        mtime_secs,
+        mtime_nanos,
+      )
+    })
+  }
+  async fn utime_async(
+    &self,
+    path: PathBuf,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Utime, &path);
+    let f = self.inner.utime_async(
+      path.clone(),
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    );
+    self.run_async(operation, f).await
+  }
+
+  fn lutime_sync(
+    &self,
+    path: &Path,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Lutime, path);
+    self.run(operation, || {
+      self.inner.lutime_sync(
+        path,
+        atime_secs,
+        atime_nanos,
+        mtime_secs,
+        mtime_nanos,
+      )
+    })
+  }
+  async fn lutime_async(
+    &self,
+    path: PathBuf,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::Lutime, &path);
+    let f = self.inner.lutime_async(
+      path.clone(),
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    );
+    self.run_async(operation, f).await
+  }
+
+  fn get_xattr_sync(
+    &self,
+    path: &Path,
+    name: &str,
+  ) -> FsResult<Option<Vec<u8>>> {
+    let operation = FsOperation::read(FsOp::GetXattr, path);
+    self.run(operation, || self.inner.get_xattr_sync(path, name))
+  }
+  async fn get_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<Option<Vec<u8>>> {
+    let operation = FsOperation::read(FsOp::GetXattr, &path);
+    let f = self.inner.get_xattr_async(path.clone(), name);
+    self.run_async(operation, f).await
+  }
+
+  fn set_xattr_sync(
+    &self,
+    path: &Path,
+    name: &str,
+    value: &[u8],
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::SetXattr, path);
+    self.run(operation, || self.inner.set_xattr_sync(path, name, value))
+  }
+  async fn set_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+    value: Vec<u8>,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::SetXattr, &path);
+    let f = self.inner.set_xattr_async(path.clone(), name, value);
+    self.run_async(operation, f).await
+  }
+
+  fn list_xattrs_sync(&self, path: &Path) -> FsResult<Vec<String>> {
+    let operation = FsOperation::read(FsOp::ListXattrs, path);
+    self.run(operation, || self.inner.list_xattrs_sync(path))
+  }
+  async fn list_xattrs_async(&self, path: PathBuf) -> FsResult<Vec<String>> {
+    let operation = FsOperation::read(FsOp::ListXattrs, &path);
+    let f = self.inner.list_xattrs_async(path.clone());
+    self.run_async(operation, f).await
+  }
+
+  fn remove_xattr_sync(&self, path: &Path, name: &str) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::RemoveXattr, path);
+    self.run(operation, || self.inner.remove_xattr_sync(path, name))
+  }
+  async fn remove_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::RemoveXattr, &path);
+    let f = self.inner.remove_xattr_async(path.clone(), name);
+    self.run_async(operation, f).await
+  }
+
+  fn write_file_sync(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+    access_check: Option<AccessCheckCb>,
+    data: &[u8],
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::WriteFile, path);
+    self.run(operation, || {
+      self
+        .inner
+        .write_file_sync(path, options, access_check, data)
+    })
+  }
+  async fn write_file_async<'a>(
+    &'a self,
+    path: PathBuf,
+    options: OpenOptions,
+    access_check: Option<AccessCheckCb<'a>>,
+    data: Vec<u8>,
+  ) -> FsResult<()> {
+    let operation = FsOperation::write(FsOp::WriteFile, &path);
+    let f =
+      self
+        .inner
+        .write_file_async(path.clone(), options, access_check, data);
+    self.run_async(operation, f).await
+  }
+
+  fn read_file_sync(
+    &self,
+    path: &Path,
+    access_check: Option<AccessCheckCb>,
+  ) -> FsResult<Vec<u8>> {
+    let operation = FsOperation::read(FsOp::ReadFile, path);
+    self.run(operation, || self.inner.read_file_sync(path, access_check))
+  }
+  async fn read_file_async<'a>(
+    &'a self,
+    path: PathBuf,
+    access_check: Option<AccessCheckCb<'a>>,
+  ) -> FsResult<Vec<u8>> {
+    let operation = FsOperation::read(FsOp::ReadFile, &path);
+    let f = self.inner.read_file_async(path.clone(), access_check);
+    self.run_async(operation, f).await
+  }
+
+  fn read_text_file_lossy_sync(
+    &self,
+    path: &Path,
+    access_check: Option<AccessCheckCb>,
+  ) -> FsResult<String> {
+    let operation = FsOperation::read(FsOp::ReadFile, path);
+    self.run(operation, || {
+      self.inner.read_text_file_lossy_sync(path, access_check)
+    })
+  }
+  async fn read_text_file_lossy_async<'a>(
+    &'a self,
+    path: PathBuf,
+    access_check: Option<AccessCheckCb<'a>>,
+  ) -> FsResult<String> {
+    let operation = FsOperation::read(FsOp::ReadFile, &path);
+    let f = self
+      .inner
+      .read_text_file_lossy_async(path.clone(), access_check);
+    self.run_async(operation, f).await
+  }
+}
+
+/// A file opened through a `HookedFs`, which reports its reads, writes and
+/// changes to the hook.
+struct HookedFile<H: FsHook> {
+  inner: Rc<dyn File>,
+  /// The path the file was opened at.
+  path: PathBuf,
+  hook: MaybeArc<H>,
+}
+
+impl<H: FsHook> HookedFile<H> {
+  fn read_op(&self) -> FsOperation {
+    FsOperation::read(FsOp::Read, &self.path)
+  }
+
+  fn write_op(&self, op: FsOp) -> FsOperation {
+    FsOperation::write(op, &self.path)
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl<H: FsHook + 'static> File for HookedFile<H> {
+  fn read_sync(self: Rc<Self>, buf: &mut [u8]) -> FsResult<usize> {
+    report(&*self.hook, self.read_op(), || {
+      self.inner.clone().read_sync(buf)
+    })
+  }
+  async fn read(self: Rc<Self>, limit: usize) -> FsResult<BufView> {
+    let f = self.inner.clone().read(limit);
+    report_async(&*self.hook, self.read_op(), f).await
+  }
+  async fn read_byob(
+    self: Rc<Self>,
+    buf: BufMutView,
+  ) -> FsResult<(usize, BufMutView)> {
+    let f = self.inner.clone().read_byob(buf);
+    report_async(&*self.hook, self.read_op(), f).await
+  }
+
+  fn write_sync(self: Rc<Self>, buf: &[u8]) -> FsResult<usize> {
+    report(&*self.hook, self.write_op(FsOp::Write), || {
+      self.inner.clone().write_sync(buf)
+    })
+  }
+  async fn write(self: Rc<Self>, buf: BufView) -> FsResult<WriteOutcome> {
+    let f = self.inner.clone().write(buf);
+    report_async(&*self.hook, self.write_op(FsOp::Write), f).await
+  }
+
+  fn write_all_sync(self: Rc<Self>, buf: &[u8]) -> FsResult<()> {
+    report(&*self.hook, self.write_op(FsOp::Write), || {
+      self.inner.clone().write_all_sync(buf)
+    })
+  }
+  async fn write_all(self: Rc<Self>, buf: BufView) -> FsResult<()> {
+    let f = self.inner.clone().write_all(buf);
+    report_async(&*self.hook, self.write_op(FsOp::Write), f).await
+  }
+
+  fn read_all_sync(self: Rc<Self>) -> FsResult<Vec<u8>> {
+    report(&*self.hook, self.read_op(), || {
+      self.inner.clone().read_all_sync()
+    })
+  }
+  async fn read_all_async(self: Rc<Self>) -> FsResult<Vec<u8>> {
+    let f = self.inner.clone().read_all_async();
+    report_async(&*self.hook, self.read_op(), f).await
+  }
+
+  fn chmod_sync(self: Rc<Self>, mode: u32) -> FsResult<()> {
+    report(&*self.hook, self.write_op(FsOp::Chmod), || {
+      self.inner.clone().chmod_sync(mode)
+    })
+  }
+  async fn chmod_async(self: Rc<Self>, mode: u32) -> FsResult<()> {
+    let f = self.inner.clone().chmod_async(mode);
+    report_async(&*self.hook, self.write_op(FsOp::Chmod), f).await
+  }
+
+  fn seek_sync(self: Rc<Self>, pos: io::SeekFrom) -> FsResult<u64> {
+    self.inner.clone().seek_sync(pos)
+  }
+  async fn seek_async(self: Rc<Self>, pos: io::SeekFrom) -> FsResult<u64> {
+    self.inner.clone().seek_async(pos).await
+  }
+
+  fn datasync_sync(self: Rc<Self>) -> FsResult<()> {
+    self.inner.clone().datasync_sync()
+  }
+  async fn datasync_async(self: Rc<Self>) -> FsResult<()> {
+    self.inner.clone().datasync_async().await
+  }
+
+  fn sync_sync(self: Rc<Self>) -> FsResult<()> {
+    self.inner.clone().sync_sync()
+  }
+  async fn sync_async(self: Rc<Self>) -> FsResult<()> {
+    self.inner.clone().sync_async().await
+  }
+
+  fn stat_sync(self: Rc<Self>) -> FsResult<FsStat> {
+    let operation = FsOperation::read(FsOp::Stat, &self.path);
+    report(&*self.hook, operation, || self.inner.clone().stat_sync())
+  }
+  async fn stat_async(self: Rc<Self>) -> FsResult<FsStat> {
+    let operation = FsOperation::read(FsOp::Stat, &self.path);
+    let f = self.inner.clone().stat_async();
+    report_async(&*self.hook, operation, f).await
+  }
+
+  fn lock_sync(self: Rc<Self>, exclusive: bool) -> FsResult<()> {
+    self.inner.clone().lock_sync(exclusive)
+  }
+  async fn lock_async(self: Rc<Self>, exclusive: bool) -> FsResult<()> {
+    self.inner.clone().lock_async(exclusive).await
+  }
+
+  fn unlock_sync(self: Rc<Self>) -> FsResult<()> {
+    self.inner.clone().unlock_sync()
+  }
+  async fn unlock_async(self: Rc<Self>) -> FsResult<()> {
+    self.inner.clone().unlock_async().await
+  }
+
+  fn truncate_sync(self: Rc<Self>, len: u64) -> FsResult<()> {
+    report(&*self.hook, self.write_op(FsOp::Truncate), || {
+      self.inner.clone().truncate_sync(len)
+    })
+  }
+  async fn truncate_async(self: Rc<Self>, len: u64) -> FsResult<()> {
+    let f = self.inner.clone().truncate_async(len);
+    report_async(&*self.hook, self.write_op(FsOp::Truncate), f).await
+  }
+
+  fn allocate_sync(self: Rc<Self>, len: u64) -> FsResult<()> {
+    report(&*self.hook, self.write_op(FsOp::Allocate), || {
+      self.inner.clone().allocate_sync(len)
+    })
+  }
+  async fn allocate_async(self: Rc<Self>, len: u64) -> FsResult<()> {
+    let f = self.inner.clone().allocate_async(len);
+    report_async(&*self.hook, self.write_op(FsOp::Allocate), f).await
+  }
+
+  fn punch_hole_sync(self: Rc<Self>, offset: u64, len: u64) -> FsResult<()> {
+    report(&*self.hook, self.write_op(FsOp::PunchHole), || {
+      self.inner.clone().punch_hole_sync(offset, len)
+    })
+  }
+  async fn punch_hole_async(
+    self: Rc<Self>,
+    offset: u64,
+    len: u64,
+  ) -> FsResult<()> {
+    let f = self.inner.clone().punch_hole_async(offset, len);
+    report_async(&*self.hook, self.write_op(FsOp::PunchHole), f).await
+  }
+
+  fn utime_sync(
+    self: Rc<Self>,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    report(&*self.hook, self.write_op(FsOp::Utime), || {
+      self.inner.clone().utime_sync(
+        atime_secs,
+        atime_nanos,
+        mtime_secs,
+        mtime_nanos,
+      )
+    })
+  }
+  async fn utime_async(
+    self: Rc<Self>,
+    atime_secs: i64,
+    atime_nanos: u32,
+    mtime_secs: i64,
+    mtime_nanos: u32,
+  ) -> FsResult<()> {
+    let f = self.inner.clone().utime_async(
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    );
+    report_async(&*self.hook, self.write_op(FsOp::Utime), f).await
+  }
+
+  fn as_stdio(self: Rc<Self>) -> FsResult<std::process::Stdio> {
+    self.inner.clone().as_stdio()
+  }
+  fn backing_fd(self: Rc<Self>) -> Option<ResourceHandleFd> {
+    self.inner.clone().backing_fd()
+  }
+  fn try_clone_inner(self: Rc<Self>) -> FsResult<Rc<dyn File>> {
+    let inner = self.inner.clone().try_clone_inner()?;
+    Ok(Rc::new(HookedFile {
+      inner,
+      path: self.path.clone(),
+      hook: self.hook.clone(),
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use deno_core::parking_lot::Mutex;
+
+  use super::*;
+  use crate::RealFs;
+
+  #[derive(Debug, Default)]
+  struct RecordingHook {
+    operations: Mutex<Vec<(FsOp, PathBuf, bool)>>,
+    deny: Option<FsOp>,
+  }
+
+  impl FsHook for RecordingHook {
+    fn before(&self, operation: &FsOperation) -> FsResult<()> {
+      self.operations.lock().push((
+        operation.op,
+        operation.path.to_path_buf(),
+        operation.write,
+      ));
+      if self.deny == Some(operation.op) {
+        return Err(FsError::PermissionDenied("hook"));
+      }
+      Ok(())
+    }
+  }
+
+  impl RecordingHook {
+    fn take(&self) -> Vec<(FsOp, PathBuf, bool)> {
+      std::mem::take(&mut *self.operations.lock())
+    }
+  }
+
+  fn hooked_fs(deny: Option<FsOp>) -> HookedFs<RecordingHook> {
+    HookedFs::new(
+      MaybeArc::new(RealFs),
+      RecordingHook {
+        operations: Default::default(),
+        deny,
+      },
+    )
+  }
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = RealFs
+      .tmp_dir()
+      .unwrap()
+      .join(format!("deno_hooked_fs_{}_{name}", std::process::id()));
+    let _ = RealFs.remove_sync(&dir, true);
+    RealFs.mkdir_sync(&dir, true, 0o755).unwrap();
+    RealFs.realpath_sync(&dir).unwrap()
+  }
+
+  #[test]
+  fn reports_path_operations() {
+    let dir = temp_dir("paths");
+    let a = dir.join("a.txt");
+    let b = dir.join("b.txt");
+    let fs = hooked_fs(None);
+    fs.write_file_sync(
+      &a,
+      OpenOptions::write(true, false, false, None),
+      None,
+      b"a",
+    )
+    .unwrap();
+    fs.rename_sync(&a, &b).unwrap();
+    assert!(fs.stat_sync(&b).unwrap().is_file);
+    assert_eq!(
+      fs.hook().take(),
+      vec![
+        (FsOp::WriteFile, a, true),
+        (FsOp::Rename, dir.join("a.txt"), true),
+        (FsOp::Stat, b, false),
+      ]
+    );
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+
+  #[test]
+  fn vetoes_operations() {
+    let dir = temp_dir("veto");
+    let a = dir.join("a.txt");
+    RealFs
+      .write_file_sync(
+        &a,
+        OpenOptions::write(true, false, false, None),
+        None,
+        b"a",
+      )
+      .unwrap();
+    let fs = hooked_fs(Some(FsOp::Remove));
+    let err = fs.remove_sync(&a, false).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(RealFs.stat_sync(&a).is_ok());
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+
+  #[test]
+  fn reports_open_file_operations() {
+    let dir = temp_dir("files");
+    let a = dir.join("a.txt");
+    let fs = hooked_fs(None);
+    let file = fs
+      .open_sync(&a, OpenOptions::write(true, false, false, None), None)
+      .unwrap();
+    file.clone().write_all_sync(b"hello").unwrap();
+    file.clone().truncate_sync(4).unwrap();
+    drop(file);
+    let file = fs.open_sync(&a, OpenOptions::read(), None).unwrap();
+    assert_eq!(file.clone().read_all_sync().unwrap(), b"hell");
+    assert_eq!(
+      fs.hook().take(),
+      vec![
+        (FsOp::Open, a.clone(), true),
+        (FsOp::Write, a.clone(), true),
+        (FsOp::Truncate, a.clone(), true),
+        (FsOp::Open, a.clone(), false),
+        (FsOp::Read, a, false),
+      ]
+    );
+
+    let fs = hooked_fs(Some(FsOp::Write));
+    let file = fs
+      .open_sync(
+        &dir.join("b.txt"),
+        OpenOptions::write(true, false, false, None),
+        None,
+      )
+      .unwrap();
+    let err = file.clone().write_all_sync(b"hello").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn reports_dir_handle_operations() {
+    let dir = temp_dir("dir_handle");
+    let fs = hooked_fs(None);
+    let handle = fs.open_dir_handle_sync(&dir).unwrap();
+    let file = fs
+      .open_at_sync(
+        &handle,
+        Path::new("a.txt"),
+        OpenOptions::write(true, false, false, None),
+      )
+      .unwrap();
+    file.clone().write_all_sync(b"a").unwrap();
+    fs.rename_at_sync(&handle, Path::new("a.txt"), &handle, Path::new("b.txt"))
+      .unwrap();
+    fs.unlink_at_sync(&handle, Path::new("b.txt"), false)
+      .unwrap();
+    assert_eq!(
+      fs.hook().take(),
+      vec![
+        (FsOp::OpenDir, dir.clone(), false),
+        (FsOp::Open, dir.join("a.txt"), true),
+        (FsOp::Write, dir.join("a.txt"), true),
+        (FsOp::Rename, dir.join("a.txt"), true),
+        (FsOp::Remove, dir.join("b.txt"), true),
+      ]
+    );
+
+    let fs = hooked_fs(Some(FsOp::Remove));
+    RealFs
+      .write_file_sync(
+        &dir.join("c.txt"),
+        OpenOptions::write(true, false, false, None),
+        None,
+        b"c",
+      )
+      .unwrap();
+    let err = fs
+      .unlink_at_sync(&handle, Path::new("c.txt"), false)
+      .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+}
@@ -9,9 +9,11 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use deno_io::fs::File;
+use deno_io::fs::FsError;
 use deno_io::fs::FsResult;
 use deno_io::fs::FsStat;
 
+use crate::dir_handle::DirHandle;
 use crate::sync::MaybeSend;
 use crate::sync::MaybeSync;
 
@@ -57,6 +59,32 @@ impl OpenOptions {
       mode,
     }
   }
+
+  /// The `open(2)` flags and mode that open a file like these options do.
+  /// `O_CLOEXEC` is not included.
+  #[cfg(unix)]
+  pub(crate) fn to_flags_and_mode(self) -> (libc::c_int, u32) {
+    let write = self.write || self.append;
+    let mut flags = match (self.read, write) {
+      (true, true) => libc::O_RDWR,
+      (false, true) => libc::O_WRONLY,
+      (_, false) => libc::O_RDONLY,
+    };
+    if self.append {
+      flags |= libc::O_APPEND;
+    }
+    if self.truncate {
+      flags |= libc::O_TRUNC;
+    }
+    if self.create_new {
+      flags |= libc::O_CREAT | libc::O_EXCL;
+    } else if self.create {
+      flags |= libc::O_CREAT;
+    }
+    // Like `std::fs::OpenOptions`, default to 0o666 before the umask.
+    let mode = self.mode.map(|mode| mode & 0o777).unwrap_or(0o666);
+    (flags, mode)
+  }
 }
 
 #[derive(Deserialize)]
@@ -79,6 +107,40 @@ pub struct FsDirEntry {
   pub is_symlink: bool,
 }
 
+/// WARNING: This is part of the public JS Deno API.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsStatfs {
+  #[serde(rename = "type")]
+  pub fs_type: String,
+  pub block_size: u64,
+  pub total_bytes: u64,
+  pub free_bytes: u64,
+  pub available_bytes: u64,
+  pub files: Option<u64>,
+  pub free_files: Option<u64>,
+}
+
+/// The entries of an open directory, read a batch at a time.
+#[async_trait::async_trait(?Send)]
+pub trait FsReadDir {
+  /// Returns up to `max` entries, or no entries once all of them were read.
+  fn next_batch_sync(&mut self, max: usize) -> FsResult<Vec<FsDirEntry>>;
+  async fn next_batch_async(
+    &mut self,
+    max: usize,
+  ) -> FsResult<Vec<FsDirEntry>> {
+    self.next_batch_sync(max)
+  }
+}
+
+/// Entries that were already read in full.
+impl FsReadDir for std::vec::IntoIter<FsDirEntry> {
+  fn next_batch_sync(&mut self, max: usize) -> FsResult<Vec<FsDirEntry>> {
+    Ok(self.by_ref().take(max).collect())
+  }
+}
+
 #[allow(clippy::disallowed_types)]
 pub type FileSystemRc = crate::sync::MaybeArc<dyn FileSystem>;
 
@@ -121,6 +183,140 @@ pub trait FileSystem: std::fmt::Debug + MaybeSend + MaybeSync {
     access_check: Option<AccessCheckCb<'a>>,
   ) -> FsResult<Rc<dyn File>>;
 
+  /// Creates a file without a name in `dir`, which is removed once it is
+  /// closed unless it is given one with `persist_file_sync`.
+  fn make_anonymous_temp_file_sync(
+    &self,
+    _dir: &Path,
+  ) -> FsResult<Rc<dyn File>> {
+    Err(FsError::NotSupported)
+  }
+  async fn make_anonymous_temp_file_async(
+    &self,
+    dir: PathBuf,
+  ) -> FsResult<Rc<dyn File>> {
+    self.make_anonymous_temp_file_sync(&dir)
+  }
+
+  /// Makes the contents of `file` available at `path`, atomically replacing
+  /// any file that exists there.
+  fn persist_file_sync(
+    &self,
+    _file: Rc<dyn File>,
+    _path: &Path,
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn persist_file_async(
+    &self,
+    file: Rc<dyn File>,
+    path: PathBuf,
+  ) -> FsResult<()> {
+    self.persist_file_sync(file, &path)
+  }
+
+  /// Opens the directory at `path` for operating on its entries with
+  /// relative paths.
+  fn open_dir_handle_sync(&self, _path: &Path) -> FsResult<DirHandle> {
+    Err(FsError::NotSupported)
+  }
+  async fn open_dir_handle_async(&self, path: PathBuf) -> FsResult<DirHandle> {
+    self.open_dir_handle_sync(&path)
+  }
+
+  /// Opens the directory at `path` relative to `dir`.
+  fn open_dir_at_sync(
+    &self,
+    _dir: &DirHandle,
+    _path: &Path,
+  ) -> FsResult<DirHandle> {
+    Err(FsError::NotSupported)
+  }
+  async fn open_dir_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+  ) -> FsResult<DirHandle> {
+    self.open_dir_at_sync(&dir, &path)
+  }
+
+  /// Opens the file at `path` relative to `dir`.
+  fn open_at_sync(
+    &self,
+    _dir: &DirHandle,
+    _path: &Path,
+    _options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    Err(FsError::NotSupported)
+  }
+  async fn open_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    self.open_at_sync(&dir, &path, options)
+  }
+
+  /// Returns information about the entry at `path` relative to `dir`, or
+  /// about the symlink itself if `follow_symlinks` is false.
+  fn stat_at_sync(
+    &self,
+    _dir: &DirHandle,
+    _path: &Path,
+    _follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    Err(FsError::NotSupported)
+  }
+  async fn stat_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    self.stat_at_sync(&dir, &path, follow_symlinks)
+  }
+
+  /// Renames the entry at `oldpath` relative to `dir` to `newpath` relative
+  /// to `new_dir`.
+  fn rename_at_sync(
+    &self,
+    _dir: &DirHandle,
+    _oldpath: &Path,
+    _new_dir: &DirHandle,
+    _newpath: &Path,
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn rename_at_async(
+    &self,
+    dir: DirHandle,
+    oldpath: PathBuf,
+    new_dir: DirHandle,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    self.rename_at_sync(&dir, &oldpath, &new_dir, &newpath)
+  }
+
+  /// Removes the entry at `path` relative to `dir`. Directories are only
+  /// removed if `is_dir` is true, and only if they are empty.
+  fn unlink_at_sync(
+    &self,
+    _dir: &DirHandle,
+    _path: &Path,
+    _is_dir: bool,
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn unlink_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    self.unlink_at_sync(&dir, &path, is_dir)
+  }
+
   fn mkdir_sync(&self, path: &Path, recursive: bool, mode: u32)
     -> FsResult<()>;
   async fn mkdir_async(
@@ -181,9 +377,29 @@ pub trait FileSystem: std::fmt::Debug + MaybeSend + MaybeSync {
   fn realpath_sync(&self, path: &Path) -> FsResult<PathBuf>;
   async fn realpath_async(&self, path: PathBuf) -> FsResult<PathBuf>;
 
+  /// Returns the size and free space of the file system containing `path`.
+  fn statfs_sync(&self, _path: &Path) -> FsResult<FsStatfs> {
+    Err(FsError::NotSupported)
+  }
+  async fn statfs_async(&self, path: PathBuf) -> FsResult<FsStatfs> {
+    self.statfs_sync(&path)
+  }
+
   fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>>;
   async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>>;
 
+  /// Opens the directory at `path` for reading its entries in batches, so
+  /// that large directories don't have to be read at once.
+  fn open_read_dir_sync(&self, path: &Path) -> FsResult<Box<dyn FsReadDir>> {
+    Ok(Box::new(self.read_dir_sync(path)?.into_iter()))
+  }
+  async fn open_read_dir_async(
+    &self,
+    path: PathBuf,
+  ) -> FsResult<Box<dyn FsReadDir>> {
+    Ok(Box::new(self.read_dir_async(path).await?.into_iter()))
+  }
+
   fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()>;
   async fn rename_async(
     &self,
@@ -251,6 +467,58 @@ pub trait FileSystem: std::fmt::Debug + MaybeSend + MaybeSync {
     mtime_nanos: u32,
   ) -> FsResult<()>;
 
+  /// Returns the value of the extended attribute `name` of the file at
+  /// `path`, or `None` if it is not set.
+  fn get_xattr_sync(
+    &self,
+    _path: &Path,
+    _name: &str,
+  ) -> FsResult<Option<Vec<u8>>> {
+    Err(FsError::NotSupported)
+  }
+  async fn get_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<Option<Vec<u8>>> {
+    self.get_xattr_sync(&path, &name)
+  }
+
+  fn set_xattr_sync(
+    &self,
+    _path: &Path,
+    _name: &str,
+    _value: &[u8],
+  ) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn set_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+    value: Vec<u8>,
+  ) -> FsResult<()> {
+    self.set_xattr_sync(&path, &name, &value)
+  }
+
+  fn list_xattrs_sync(&self, _path: &Path) -> FsResult<Vec<String>> {
+    Err(FsError::NotSupported)
+  }
+  async fn list_xattrs_async(&self, path: PathBuf) -> FsResult<Vec<String>> {
+    self.list_xattrs_sync(&path)
+  }
+
+  fn remove_xattr_sync(&self, _path: &Path, _name: &str) -> FsResult<()> {
+    Err(FsError::NotSupported)
+  }
+  async fn remove_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<()> {
+    self.remove_xattr_sync(&path, &name)
+  }
+
   fn write_file_sync(
     &self,
     path: &Path,
@@ -1,11 +1,23 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+mod dir_handle;
+mod hooked_fs;
 mod in_memory_fs;
 mod interface;
 mod ops;
+mod realpath_cache;
+mod statfs;
 mod std_fs;
 pub mod sync;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+mod uring;
+mod xattr;
 
+pub use crate::dir_handle::DirHandle;
+pub use crate::hooked_fs::FsHook;
+pub use crate::hooked_fs::FsOp;
+pub use crate::hooked_fs::FsOperation;
+pub use crate::hooked_fs::HookedFs;
 pub use crate::in_memory_fs::InMemoryFs;
 pub use crate::interface::AccessCheckCb;
 pub use crate::interface::AccessCheckFn;
@@ -13,7 +25,10 @@ pub use crate::interface::FileSystem;
 pub use crate::interface::FileSystemRc;
 pub use crate::interface::FsDirEntry;
 pub use crate::interface::FsFileType;
+pub use crate::interface::FsReadDir;
+pub use crate::interface::FsStatfs;
 pub use crate::interface::OpenOptions;
+pub use crate::realpath_cache::RealpathCache;
 pub use crate::std_fs::RealFs;
 pub use crate::sync::MaybeSend;
 pub use crate::sync::MaybeSync;
@@ -23,6 +38,7 @@ use crate::ops::*;
 use deno_core::error::AnyError;
 use deno_core::OpState;
 use deno_io::fs::FsError;
+pub use deno_permissions::WriteOperation;
 use std::borrow::Cow;
 use std::path::Path;
 
@@ -61,6 +77,25 @@ pub trait FsPermissions {
     display: &str,
     api_name: &str,
   ) -> Result<(), AnyError>;
+  /// Checks that `operation` is allowed on `path`, after write access to it
+  /// was checked.
+  fn check_write_operation(
+    &mut self,
+    _path: &Path,
+    _operation: WriteOperation,
+    _api_name: &str,
+  ) -> Result<(), AnyError> {
+    Ok(())
+  }
+
+  /// Whether `operation` is allowed on `path`, without recording the check.
+  fn allows_write_operation(
+    &self,
+    _path: &Path,
+    _operation: WriteOperation,
+  ) -> bool {
+    true
+  }
 
   fn check<'a>(
     &mut self,
@@ -69,13 +104,108 @@ pub trait FsPermissions {
     path: &'a Path,
     api_name: &str,
   ) -> Result<std::borrow::Cow<'a, Path>, FsError> {
-    self.check_open(
+    let path = self.check_open(
       resolved,
       open_options.read,
       open_options.write || open_options.append,
       path,
       api_name,
-    )
+    )?;
+    if !resolved {
+      for operation in open_write_operations(open_options) {
+        self
+          .check_write_operation(&path, operation, api_name)
+          .map_err(|_| FsError::PermissionDenied("write"))?;
+      }
+    }
+    Ok(path)
+  }
+}
+
+/// The write operations performed by opening a file with `options`. An open
+/// that may either create the file or write to an existing one performs
+/// both, see [`restrict_open_options`].
+fn open_write_operations(options: &OpenOptions) -> Vec<WriteOperation> {
+  let mut operations = Vec::new();
+  if options.create || options.create_new {
+    operations.push(WriteOperation::Create);
+  }
+  // Writing to a file that is being created is part of creating it.
+  if options.append {
+    operations.push(WriteOperation::Append);
+  } else if (options.write || options.truncate) && !options.create_new {
+    operations.push(WriteOperation::Modify);
+  }
+  operations
+}
+
+/// Restricts an open of `path` that may either create the file or write to
+/// an existing one to what the write operations allowed on `path` permit,
+/// so that opening the file decides whether it exists instead of a check
+/// that could race with it. Returns the operation that is denied when the
+/// open fails because the file does or doesn't exist.
+pub(crate) fn restrict_open_options<P: FsPermissions>(
+  permissions: &P,
+  path: &Path,
+  options: &mut OpenOptions,
+) -> Option<WriteOperation> {
+  if !options.create || options.create_new {
+    return None;
+  }
+  let existing = if options.append {
+    WriteOperation::Append
+  } else if options.write || options.truncate {
+    WriteOperation::Modify
+  } else {
+    return None;
+  };
+  match (
+    permissions.allows_write_operation(path, WriteOperation::Create),
+    permissions.allows_write_operation(path, existing),
+  ) {
+    (true, false) => {
+      options.create_new = true;
+      Some(existing)
+    }
+    (false, true) => {
+      options.create = false;
+      Some(WriteOperation::Create)
+    }
+    _ => None,
+  }
+}
+
+/// Checks that writing a whole file to `path` is allowed. When only creating
+/// or only modifying it is allowed, returns the options to open the file
+/// with before writing it, which fail if it does or doesn't exist.
+pub(crate) fn check_create_or_modify<P: FsPermissions>(
+  permissions: &mut P,
+  path: &Path,
+  api_name: &str,
+) -> Result<Option<OpenOptions>, AnyError> {
+  let create = permissions.allows_write_operation(path, WriteOperation::Create);
+  let modify = permissions.allows_write_operation(path, WriteOperation::Modify);
+  let mut options = OpenOptions::write(false, false, false, None);
+  options.truncate = false;
+  match (create, modify) {
+    (true, true) => Ok(None),
+    (true, false) => {
+      permissions.check_write_operation(
+        path,
+        WriteOperation::Create,
+        api_name,
+      )?;
+      options.create_new = true;
+      Ok(Some(options))
+    }
+    (false, _) => {
+      permissions.check_write_operation(
+        path,
+        WriteOperation::Modify,
+        api_name,
+      )?;
+      Ok(Some(options))
+    }
   }
 }
 
@@ -156,6 +286,27 @@ impl FsPermissions for deno_permissions::PermissionsContainer {
     )
   }
 
+  fn allows_write_operation(
+    &self,
+    path: &Path,
+    operation: WriteOperation,
+  ) -> bool {
+    deno_permissions::PermissionsContainer::allows_write_operation(
+      self, path, operation,
+    )
+  }
+
+  fn check_write_operation(
+    &mut self,
+    path: &Path,
+    operation: WriteOperation,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_write_operation(
+      self, path, operation, api_name,
+    )
+  }
+
   fn check_read_all(&mut self, api_name: &str) -> Result<(), AnyError> {
     deno_permissions::PermissionsContainer::check_read_all(self, api_name)
   }
@@ -167,6 +318,15 @@ impl FsPermissions for deno_permissions::PermissionsContainer {
 
 pub const UNSTABLE_FEATURE_NAME: &str = "fs";
 
+/// Makes the async operations of `RealFs` use io_uring on Linux, when this
+/// crate is built with the `io_uring` feature. Otherwise it does nothing.
+pub fn set_io_uring_enabled(enabled: bool) {
+  #[cfg(all(target_os = "linux", feature = "io_uring"))]
+  uring::set_enabled(enabled);
+  #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+  let _ = enabled;
+}
+
 /// Helper for checking unstable features. Used for sync ops.
 fn check_unstable(state: &OpState, api_name: &str) {
   // TODO(bartlomieju): replace with `state.feature_checker.check_or_exit`
@@ -186,6 +346,18 @@ deno_core::extension!(deno_fs,
 
     op_fs_open_sync<P>,
     op_fs_open_async<P>,
+    op_fs_open_dir_sync<P>,
+    op_fs_open_dir_async<P>,
+    op_fs_open_dir_at_sync<P>,
+    op_fs_open_dir_at_async<P>,
+    op_fs_open_at_sync<P>,
+    op_fs_open_at_async<P>,
+    op_fs_stat_at_sync<P>,
+    op_fs_stat_at_async<P>,
+    op_fs_rename_at_sync<P>,
+    op_fs_rename_at_async<P>,
+    op_fs_unlink_at_sync<P>,
+    op_fs_unlink_at_async<P>,
     op_fs_mkdir_sync<P>,
     op_fs_mkdir_async<P>,
     op_fs_chmod_sync<P>,
@@ -202,8 +374,11 @@ deno_core::extension!(deno_fs,
     op_fs_lstat_async<P>,
     op_fs_realpath_sync<P>,
     op_fs_realpath_async<P>,
+    op_fs_statfs_sync<P>,
+    op_fs_statfs_async<P>,
     op_fs_read_dir_sync<P>,
-    op_fs_read_dir_async<P>,
+    op_fs_read_dir_open_async<P>,
+    op_fs_read_dir_next_async,
     op_fs_rename_sync<P>,
     op_fs_rename_async<P>,
     op_fs_link_sync<P>,
@@ -220,6 +395,16 @@ deno_core::extension!(deno_fs,
     op_fs_make_temp_dir_async<P>,
     op_fs_make_temp_file_sync<P>,
     op_fs_make_temp_file_async<P>,
+    op_fs_make_temp_file_anonymous_sync<P>,
+    op_fs_make_temp_file_anonymous_async<P>,
+    op_fs_get_xattr_sync<P>,
+    op_fs_get_xattr_async<P>,
+    op_fs_set_xattr_sync<P>,
+    op_fs_set_xattr_async<P>,
+    op_fs_list_xattrs_sync<P>,
+    op_fs_list_xattrs_async<P>,
+    op_fs_remove_xattr_sync<P>,
+    op_fs_remove_xattr_async<P>,
     op_fs_write_file_sync<P>,
     op_fs_write_file_async<P>,
     op_fs_read_file_sync<P>,
@@ -233,6 +418,8 @@ deno_core::extension!(deno_fs,
     op_fs_fdatasync_async,
     op_fs_fsync_sync,
     op_fs_fsync_async,
+    op_fs_file_persist_sync<P>,
+    op_fs_file_persist_async<P>,
     op_fs_file_stat_sync,
     op_fs_file_stat_async,
     op_fs_flock_sync_unstable,
@@ -245,7 +432,19 @@ deno_core::extension!(deno_fs,
     op_fs_funlock_sync,
     op_fs_ftruncate_sync,
     op_fs_ftruncate_async,
+    op_fs_fallocate_sync,
+    op_fs_fallocate_async,
+    op_fs_fpunch_hole_sync,
+    op_fs_fpunch_hole_async,
     op_fs_futime_sync,
+    op_fs_fget_xattr_sync,
+    op_fs_fget_xattr_async,
+    op_fs_fset_xattr_sync,
+    op_fs_fset_xattr_async,
+    op_fs_flist_xattrs_sync,
+    op_fs_flist_xattrs_async,
+    op_fs_fremove_xattr_sync,
+    op_fs_fremove_xattr_async,
     op_fs_futime_async,
 
   ],
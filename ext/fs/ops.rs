@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::io;
 use std::io::SeekFrom;
@@ -12,10 +13,14 @@ use deno_core::error::custom_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
+use deno_core::unsync::spawn_blocking;
+use deno_core::AsyncRefCell;
 use deno_core::CancelFuture;
 use deno_core::CancelHandle;
 use deno_core::JsBuffer;
 use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_core::ToJsBuffer;
 use deno_io::fs::FileResource;
@@ -26,13 +31,26 @@ use rand::thread_rng;
 use rand::Rng;
 use serde::Serialize;
 
+use crate::check_create_or_modify;
 use crate::check_unstable;
+use crate::dir_handle::DirHandleResource;
 use crate::interface::AccessCheckFn;
 use crate::interface::FileSystemRc;
 use crate::interface::FsDirEntry;
 use crate::interface::FsFileType;
+use crate::interface::FsReadDir;
+use crate::interface::FsStatfs;
+use crate::realpath_cache::record_path_mutation;
+use crate::restrict_open_options;
+use crate::std_fs::clone_backing_file;
+use crate::xattr::get_xattr;
+use crate::xattr::list_xattrs;
+use crate::xattr::remove_xattr;
+use crate::xattr::set_xattr;
+use crate::xattr::XattrTarget;
 use crate::FsPermissions;
 use crate::OpenOptions;
+use crate::WriteOperation;
 
 fn sync_permission_check<'a, P: FsPermissions + 'static>(
   permissions: &'a mut P,
@@ -76,6 +94,53 @@ fn map_permission_error(
   }
 }
 
+/// Maps the error of an open whose options were restricted by
+/// [`restrict_open_options`], reporting the operation that was denied when
+/// the file does or doesn't exist.
+fn map_restricted_open_error<P: FsPermissions + 'static>(
+  state: &mut OpState,
+  restricted: Option<WriteOperation>,
+  operation: &'static str,
+  error: FsError,
+  path: &Path,
+  api_name: &str,
+) -> AnyError {
+  if let Some(denied) = restricted {
+    if matches!(
+      error.kind(),
+      std::io::ErrorKind::AlreadyExists | std::io::ErrorKind::NotFound
+    ) {
+      let permissions = state.borrow_mut::<P>();
+      if let Err(err) =
+        permissions.check_write_operation(path, denied, api_name)
+      {
+        return err;
+      }
+    }
+  }
+  map_permission_error(operation, error, path)
+}
+
+/// The resource of a file opened at `path` with `options`, which is
+/// append-only when the contents at `path` can't be modified.
+fn open_file_resource<P: FsPermissions + 'static>(
+  state: &OpState,
+  file: Rc<dyn deno_io::fs::File>,
+  path: &Path,
+  options: OpenOptions,
+) -> FileResource {
+  let resource = FileResource::new(file, "fsFile".to_string());
+  if options.append
+    && !state
+      .borrow::<P>()
+      .allows_write_operation(path, WriteOperation::Modify)
+  {
+    resource.append_only()
+  } else {
+    resource
+  }
+}
+
 #[op2]
 #[string]
 pub fn op_fs_cwd<P>(state: &mut OpState) -> Result<String, AnyError>
@@ -130,18 +195,27 @@ where
 {
   let path = PathBuf::from(path);
 
-  let options = options.unwrap_or_else(OpenOptions::read);
+  let mut options = options.unwrap_or_else(OpenOptions::read);
+  let restricted =
+    restrict_open_options(state.borrow::<P>(), &path, &mut options);
 
   let fs = state.borrow::<FileSystemRc>().clone();
   let mut access_check =
     sync_permission_check::<P>(state.borrow_mut(), "Deno.openSync()");
-  let file = fs
-    .open_sync(&path, options, Some(&mut access_check))
-    .map_err(|error| map_permission_error("open", error, &path))?;
+  let result = fs.open_sync(&path, options, Some(&mut access_check));
   drop(access_check);
-  let rid = state
-    .resource_table
-    .add(FileResource::new(file, "fsFile".to_string()));
+  let file = result.map_err(|error| {
+    map_restricted_open_error::<P>(
+      state,
+      restricted,
+      "open",
+      error,
+      &path,
+      "Deno.openSync()",
+    )
+  })?;
+  let resource = open_file_resource::<P>(state, file, &path, options);
+  let rid = state.resource_table.add(resource);
   Ok(rid)
 }
 
@@ -157,19 +231,29 @@ where
 {
   let path = PathBuf::from(path);
 
-  let options = options.unwrap_or_else(OpenOptions::read);
+  let mut options = options.unwrap_or_else(OpenOptions::read);
+  let restricted =
+    restrict_open_options(state.borrow().borrow::<P>(), &path, &mut options);
   let mut access_check =
     async_permission_check::<P>(state.clone(), "Deno.open()");
   let fs = state.borrow().borrow::<FileSystemRc>().clone();
   let file = fs
     .open_async(path.clone(), options, Some(&mut access_check))
     .await
-    .map_err(|error| map_permission_error("open", error, &path))?;
+    .map_err(|error| {
+      map_restricted_open_error::<P>(
+        &mut state.borrow_mut(),
+        restricted,
+        "open",
+        error,
+        &path,
+        "Deno.open()",
+      )
+    })?;
 
-  let rid = state
-    .borrow_mut()
-    .resource_table
-    .add(FileResource::new(file, "fsFile".to_string()));
+  let mut state = state.borrow_mut();
+  let resource = open_file_resource::<P>(&state, file, &path, options);
+  let rid = state.resource_table.add(resource);
   Ok(rid)
 }
 
@@ -187,9 +271,13 @@ where
 
   let mode = mode.unwrap_or(0o777) & 0o777;
 
-  state
-    .borrow_mut::<P>()
-    .check_write(&path, "Deno.mkdirSync()")?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.mkdirSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Create,
+    "Deno.mkdirSync()",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.mkdir_sync(&path, recursive, mode)
@@ -214,7 +302,13 @@ where
 
   let fs = {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<P>().check_write(&path, "Deno.mkdir()")?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.mkdir()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Create,
+      "Deno.mkdir()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
 
@@ -235,9 +329,13 @@ where
   P: FsPermissions + 'static,
 {
   let path = PathBuf::from(path);
-  state
-    .borrow_mut::<P>()
-    .check_write(&path, "Deno.chmodSync()")?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.chmodSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Chmod,
+    "Deno.chmodSync()",
+  )?;
   let fs = state.borrow::<FileSystemRc>();
   fs.chmod_sync(&path, mode).context_path("chmod", &path)?;
   Ok(())
@@ -255,7 +353,13 @@ where
   let path = PathBuf::from(path);
   let fs = {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<P>().check_write(&path, "Deno.chmod()")?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.chmod()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Chmod,
+      "Deno.chmod()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
   fs.chmod_async(path.clone(), mode)
@@ -275,9 +379,13 @@ where
   P: FsPermissions + 'static,
 {
   let path = PathBuf::from(path);
-  state
-    .borrow_mut::<P>()
-    .check_write(&path, "Deno.chownSync()")?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.chownSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Chmod,
+    "Deno.chownSync()",
+  )?;
   let fs = state.borrow::<FileSystemRc>();
   fs.chown_sync(&path, uid, gid)
     .context_path("chown", &path)?;
@@ -297,7 +405,13 @@ where
   let path = PathBuf::from(path);
   let fs = {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<P>().check_write(&path, "Deno.chown()")?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.chown()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Chmod,
+      "Deno.chown()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
   fs.chown_async(path.clone(), uid, gid)
@@ -317,13 +431,18 @@ where
 {
   let path = PathBuf::from(path);
 
-  state
-    .borrow_mut::<P>()
-    .check_write(&path, "Deno.removeSync()")?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.removeSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Delete,
+    "Deno.removeSync()",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.remove_sync(&path, recursive)
     .context_path("remove", &path)?;
+  record_path_mutation();
 
   Ok(())
 }
@@ -342,13 +461,21 @@ where
   let fs = {
     let mut state = state.borrow_mut();
     if recursive {
-      state
-        .borrow_mut::<P>()
-        .check_write(&path, "Deno.remove()")?;
+      let permissions = state.borrow_mut::<P>();
+      permissions.check_write(&path, "Deno.remove()")?;
+      permissions.check_write_operation(
+        &path,
+        WriteOperation::Delete,
+        "Deno.remove()",
+      )?;
     } else {
-      state
-        .borrow_mut::<P>()
-        .check_write_partial(&path, "Deno.remove()")?;
+      let permissions = state.borrow_mut::<P>();
+      permissions.check_write_partial(&path, "Deno.remove()")?;
+      permissions.check_write_operation(
+        &path,
+        WriteOperation::Delete,
+        "Deno.remove()",
+      )?;
     }
 
     state.borrow::<FileSystemRc>().clone()
@@ -357,6 +484,7 @@ where
   fs.remove_async(path.clone(), recursive)
     .await
     .context_path("remove", &path)?;
+  record_path_mutation();
 
   Ok(())
 }
@@ -376,8 +504,13 @@ where
   let permissions = state.borrow_mut::<P>();
   permissions.check_read(&from, "Deno.copyFileSync()")?;
   permissions.check_write(&to, "Deno.copyFileSync()")?;
+  let reserve =
+    check_create_or_modify(permissions, &to, "Deno.copyFileSync()")?;
 
   let fs = state.borrow::<FileSystemRc>();
+  if let Some(options) = reserve {
+    fs.open_sync(&to, options, None).context_path("copy", &to)?;
+  }
   fs.copy_file_sync(&from, &to)
     .context_two_path("copy", &from, &to)?;
 
@@ -396,14 +529,20 @@ where
   let from = PathBuf::from(from);
   let to = PathBuf::from(to);
 
-  let fs = {
+  let (fs, reserve) = {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<P>();
     permissions.check_read(&from, "Deno.copyFile()")?;
     permissions.check_write(&to, "Deno.copyFile()")?;
-    state.borrow::<FileSystemRc>().clone()
+    let reserve = check_create_or_modify(permissions, &to, "Deno.copyFile()")?;
+    (state.borrow::<FileSystemRc>().clone(), reserve)
   };
 
+  if let Some(options) = reserve {
+    fs.open_async(to.clone(), options, None)
+      .await
+      .context_path("copy", &to)?;
+  }
   fs.copy_file_async(from.clone(), to.clone())
     .await
     .context_two_path("copy", &from, &to)?;
@@ -552,6 +691,50 @@ where
   Ok(path_string)
 }
 
+#[op2]
+#[serde]
+pub fn op_fs_statfs_sync<P>(
+  state: &mut OpState,
+  #[string] path: String,
+) -> Result<FsStatfs, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  state
+    .borrow_mut::<P>()
+    .check_read(&path, "Deno.statfsSync()")?;
+
+  let fs = state.borrow::<FileSystemRc>();
+  let statfs = fs.statfs_sync(&path).context_path("statfs", &path)?;
+  Ok(statfs)
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_fs_statfs_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] path: String,
+) -> Result<FsStatfs, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let fs = {
+    let mut state = state.borrow_mut();
+    state.borrow_mut::<P>().check_read(&path, "Deno.statfs()")?;
+    state.borrow::<FileSystemRc>().clone()
+  };
+
+  let statfs = fs
+    .statfs_async(path.clone())
+    .await
+    .context_path("statfs", &path)?;
+  Ok(statfs)
+}
+
 #[op2]
 #[serde]
 pub fn op_fs_read_dir_sync<P>(
@@ -573,12 +756,26 @@ where
   Ok(entries)
 }
 
+/// How many entries `Deno.readDir` reads from the directory at a time.
+const READ_DIR_BATCH_SIZE: usize = 1024;
+
+struct ReadDirResource {
+  path: PathBuf,
+  dir: AsyncRefCell<Box<dyn FsReadDir>>,
+}
+
+impl Resource for ReadDirResource {
+  fn name(&self) -> Cow<str> {
+    "fsReadDir".into()
+  }
+}
+
 #[op2(async)]
-#[serde]
-pub async fn op_fs_read_dir_async<P>(
+#[smi]
+pub async fn op_fs_read_dir_open_async<P>(
   state: Rc<RefCell<OpState>>,
   #[string] path: String,
-) -> Result<Vec<FsDirEntry>, AnyError>
+) -> Result<ResourceId, AnyError>
 where
   P: FsPermissions + 'static,
 {
@@ -592,11 +789,31 @@ where
     state.borrow::<FileSystemRc>().clone()
   };
 
-  let entries = fs
-    .read_dir_async(path.clone())
+  let dir = fs
+    .open_read_dir_async(path.clone())
     .await
     .context_path("readdir", &path)?;
 
+  Ok(state.borrow_mut().resource_table.add(ReadDirResource {
+    path,
+    dir: AsyncRefCell::new(dir),
+  }))
+}
+
+/// Returns the next batch of entries of a directory opened with
+/// `op_fs_read_dir_open_async`, or no entries once all of them were read.
+#[op2(async)]
+#[serde]
+pub async fn op_fs_read_dir_next_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Vec<FsDirEntry>, AnyError> {
+  let resource = state.borrow().resource_table.get::<ReadDirResource>(rid)?;
+  let mut dir = RcRef::map(&resource, |r| &r.dir).borrow_mut().await;
+  let entries = dir
+    .next_batch_async(READ_DIR_BATCH_SIZE)
+    .await
+    .context_path("readdir", &resource.path)?;
   Ok(entries)
 }
 
@@ -615,11 +832,22 @@ where
   let permissions = state.borrow_mut::<P>();
   permissions.check_read(&oldpath, "Deno.renameSync()")?;
   permissions.check_write(&oldpath, "Deno.renameSync()")?;
+  permissions.check_write_operation(
+    &oldpath,
+    WriteOperation::Rename,
+    "Deno.renameSync()",
+  )?;
   permissions.check_write(&newpath, "Deno.renameSync()")?;
+  permissions.check_write_operation(
+    &newpath,
+    WriteOperation::Rename,
+    "Deno.renameSync()",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.rename_sync(&oldpath, &newpath)
     .context_two_path("rename", &oldpath, &newpath)?;
+  record_path_mutation();
 
   Ok(())
 }
@@ -641,13 +869,24 @@ where
     let permissions = state.borrow_mut::<P>();
     permissions.check_read(&oldpath, "Deno.rename()")?;
     permissions.check_write(&oldpath, "Deno.rename()")?;
+    permissions.check_write_operation(
+      &oldpath,
+      WriteOperation::Rename,
+      "Deno.rename()",
+    )?;
     permissions.check_write(&newpath, "Deno.rename()")?;
+    permissions.check_write_operation(
+      &newpath,
+      WriteOperation::Rename,
+      "Deno.rename()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
 
   fs.rename_async(oldpath.clone(), newpath.clone())
     .await
     .context_two_path("rename", &oldpath, &newpath)?;
+  record_path_mutation();
 
   Ok(())
 }
@@ -669,6 +908,11 @@ where
   permissions.check_write(&oldpath, "Deno.linkSync()")?;
   permissions.check_read(&newpath, "Deno.linkSync()")?;
   permissions.check_write(&newpath, "Deno.linkSync()")?;
+  permissions.check_write_operation(
+    &newpath,
+    WriteOperation::Create,
+    "Deno.linkSync()",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.link_sync(&oldpath, &newpath)
@@ -696,6 +940,11 @@ where
     permissions.check_write(&oldpath, "Deno.link()")?;
     permissions.check_read(&newpath, "Deno.link()")?;
     permissions.check_write(&newpath, "Deno.link()")?;
+    permissions.check_write_operation(
+      &newpath,
+      WriteOperation::Create,
+      "Deno.link()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
 
@@ -726,6 +975,7 @@ where
   let fs = state.borrow::<FileSystemRc>();
   fs.symlink_sync(&oldpath, &newpath, file_type)
     .context_two_path("symlink", &oldpath, &newpath)?;
+  record_path_mutation();
 
   Ok(())
 }
@@ -754,6 +1004,7 @@ where
   fs.symlink_async(oldpath.clone(), newpath.clone(), file_type)
     .await
     .context_two_path("symlink", &oldpath, &newpath)?;
+  record_path_mutation();
 
   Ok(())
 }
@@ -818,9 +1069,13 @@ where
 {
   let path = PathBuf::from(path);
 
-  state
-    .borrow_mut::<P>()
-    .check_write(&path, "Deno.truncateSync()")?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.truncateSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Modify,
+    "Deno.truncateSync()",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.truncate_sync(&path, len)
@@ -842,9 +1097,13 @@ where
 
   let fs = {
     let mut state = state.borrow_mut();
-    state
-      .borrow_mut::<P>()
-      .check_write(&path, "Deno.truncate()")?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.truncate()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Modify,
+      "Deno.truncate()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
 
@@ -869,7 +1128,13 @@ where
 {
   let path = PathBuf::from(path);
 
-  state.borrow_mut::<P>().check_write(&path, "Deno.utime()")?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.utime()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Modify,
+    "Deno.utime()",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.utime_sync(&path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
@@ -894,7 +1159,13 @@ where
 
   let fs = {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<P>().check_write(&path, "Deno.utime()")?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.utime()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Modify,
+      "Deno.utime()",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
 
@@ -1062,6 +1333,48 @@ where
   .context("tmpfile")
 }
 
+#[op2]
+#[smi]
+pub fn op_fs_make_temp_file_anonymous_sync<P>(
+  state: &mut OpState,
+  #[string] dir: Option<String>,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let (dir, fs) =
+    make_temp_check_sync::<P>(state, dir, "Deno.makeTempFileSync()")?;
+  let file = fs
+    .make_anonymous_temp_file_sync(&dir)
+    .context_path("tmpfile", &dir)?;
+  let rid = state
+    .resource_table
+    .add(FileResource::new(file, "fsFile".to_string()));
+  Ok(rid)
+}
+
+#[op2(async)]
+#[smi]
+pub async fn op_fs_make_temp_file_anonymous_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] dir: Option<String>,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let (dir, fs) =
+    make_temp_check_async::<P>(state.clone(), dir, "Deno.makeTempFile()")?;
+  let file = fs
+    .make_anonymous_temp_file_async(dir.clone())
+    .await
+    .context_path("tmpfile", &dir)?;
+  let rid = state
+    .borrow_mut()
+    .resource_table
+    .add(FileResource::new(file, "fsFile".to_string()));
+  Ok(rid)
+}
+
 fn make_temp_check_sync<P>(
   state: &mut OpState,
   dir: Option<String>,
@@ -1074,7 +1387,13 @@ where
   let dir = match dir {
     Some(dir) => {
       let dir = PathBuf::from(dir);
-      state.borrow_mut::<P>().check_write(&dir, api_name)?;
+      let permissions = state.borrow_mut::<P>();
+      permissions.check_write(&dir, api_name)?;
+      permissions.check_write_operation(
+        &dir,
+        WriteOperation::Create,
+        api_name,
+      )?;
       dir
     }
     None => {
@@ -1101,7 +1420,13 @@ where
   let dir = match dir {
     Some(dir) => {
       let dir = PathBuf::from(dir);
-      state.borrow_mut::<P>().check_write(&dir, api_name)?;
+      let permissions = state.borrow_mut::<P>();
+      permissions.check_write(&dir, api_name)?;
+      permissions.check_write_operation(
+        &dir,
+        WriteOperation::Create,
+        api_name,
+      )?;
       dir
     }
     None => {
@@ -1176,128 +1501,359 @@ fn tmp_name(
 }
 
 #[op2]
-pub fn op_fs_write_file_sync<P>(
+#[serde]
+pub fn op_fs_get_xattr_sync<P>(
   state: &mut OpState,
   #[string] path: String,
-  mode: Option<u32>,
-  append: bool,
-  create: bool,
-  create_new: bool,
-  #[buffer] data: JsBuffer,
-) -> Result<(), AnyError>
+  #[string] name: String,
+) -> Result<Option<ToJsBuffer>, AnyError>
 where
   P: FsPermissions + 'static,
 {
   let path = PathBuf::from(path);
 
-  let options = OpenOptions::write(create, append, create_new, mode);
-  let fs = state.borrow::<FileSystemRc>().clone();
-  let mut access_check =
-    sync_permission_check::<P>(state.borrow_mut(), "Deno.writeFileSync()");
-
-  fs.write_file_sync(&path, options, Some(&mut access_check), &data)
-    .map_err(|error| map_permission_error("writefile", error, &path))?;
+  state
+    .borrow_mut::<P>()
+    .check_read(&path, "Deno.getXattrSync()")?;
 
-  Ok(())
+  let fs = state.borrow::<FileSystemRc>();
+  let value = fs
+    .get_xattr_sync(&path, &name)
+    .context_path("getxattr", &path)?;
+  Ok(value.map(Into::into))
 }
 
 #[op2(async)]
-#[allow(clippy::too_many_arguments)]
-pub async fn op_fs_write_file_async<P>(
+#[serde]
+pub async fn op_fs_get_xattr_async<P>(
   state: Rc<RefCell<OpState>>,
   #[string] path: String,
-  #[smi] mode: Option<u32>,
-  append: bool,
-  create: bool,
-  create_new: bool,
-  #[buffer] data: JsBuffer,
-  #[smi] cancel_rid: Option<ResourceId>,
-) -> Result<(), AnyError>
+  #[string] name: String,
+) -> Result<Option<ToJsBuffer>, AnyError>
 where
   P: FsPermissions + 'static,
 {
   let path = PathBuf::from(path);
 
-  let options = OpenOptions::write(create, append, create_new, mode);
-
-  let mut access_check =
-    async_permission_check::<P>(state.clone(), "Deno.writeFile()");
-  let (fs, cancel_handle) = {
-    let state = state.borrow_mut();
-    let cancel_handle = cancel_rid
-      .and_then(|rid| state.resource_table.get::<CancelHandle>(rid).ok());
-    (state.borrow::<FileSystemRc>().clone(), cancel_handle)
+  let fs = {
+    let mut state = state.borrow_mut();
+    state
+      .borrow_mut::<P>()
+      .check_read(&path, "Deno.getXattr()")?;
+    state.borrow::<FileSystemRc>().clone()
   };
 
-  let fut = fs.write_file_async(
-    path.clone(),
-    options,
-    Some(&mut access_check),
-    data.to_vec(),
-  );
+  let value = fs
+    .get_xattr_async(path.clone(), name)
+    .await
+    .context_path("getxattr", &path)?;
+  Ok(value.map(Into::into))
+}
 
-  if let Some(cancel_handle) = cancel_handle {
-    let res = fut.or_cancel(cancel_handle).await;
+#[op2]
+pub fn op_fs_set_xattr_sync<P>(
+  state: &mut OpState,
+  #[string] path: String,
+  #[string] name: String,
+  #[buffer] value: JsBuffer,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
 
-    if let Some(cancel_rid) = cancel_rid {
-      if let Ok(res) = state.borrow_mut().resource_table.take_any(cancel_rid) {
-        res.close();
-      }
-    };
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.setXattrSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Modify,
+    "Deno.setXattrSync()",
+  )?;
 
-    res?.map_err(|error| map_permission_error("writefile", error, &path))?;
-  } else {
-    fut
-      .await
-      .map_err(|error| map_permission_error("writefile", error, &path))?;
-  }
+  let fs = state.borrow::<FileSystemRc>();
+  fs.set_xattr_sync(&path, &name, &value)
+    .context_path("setxattr", &path)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_set_xattr_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] path: String,
+  #[string] name: String,
+  #[buffer] value: JsBuffer,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let fs = {
+    let mut state = state.borrow_mut();
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.setXattr()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Modify,
+      "Deno.setXattr()",
+    )?;
+    state.borrow::<FileSystemRc>().clone()
+  };
 
+  fs.set_xattr_async(path.clone(), name, value.to_vec())
+    .await
+    .context_path("setxattr", &path)?;
   Ok(())
 }
 
 #[op2]
 #[serde]
-pub fn op_fs_read_file_sync<P>(
+pub fn op_fs_list_xattrs_sync<P>(
   state: &mut OpState,
   #[string] path: String,
-) -> Result<ToJsBuffer, AnyError>
+) -> Result<Vec<String>, AnyError>
 where
   P: FsPermissions + 'static,
 {
   let path = PathBuf::from(path);
 
-  let fs = state.borrow::<FileSystemRc>().clone();
-  let mut access_check =
-    sync_permission_check::<P>(state.borrow_mut(), "Deno.readFileSync()");
-  let buf = fs
-    .read_file_sync(&path, Some(&mut access_check))
-    .map_err(|error| map_permission_error("readfile", error, &path))?;
+  state
+    .borrow_mut::<P>()
+    .check_read(&path, "Deno.listXattrsSync()")?;
 
-  Ok(buf.into())
+  let fs = state.borrow::<FileSystemRc>();
+  let names = fs
+    .list_xattrs_sync(&path)
+    .context_path("listxattr", &path)?;
+  Ok(names)
 }
 
 #[op2(async)]
 #[serde]
-pub async fn op_fs_read_file_async<P>(
+pub async fn op_fs_list_xattrs_async<P>(
   state: Rc<RefCell<OpState>>,
   #[string] path: String,
-  #[smi] cancel_rid: Option<ResourceId>,
-) -> Result<ToJsBuffer, AnyError>
+) -> Result<Vec<String>, AnyError>
 where
   P: FsPermissions + 'static,
 {
   let path = PathBuf::from(path);
 
-  let mut access_check =
-    async_permission_check::<P>(state.clone(), "Deno.readFile()");
-  let (fs, cancel_handle) = {
-    let state = state.borrow();
-    let cancel_handle = cancel_rid
-      .and_then(|rid| state.resource_table.get::<CancelHandle>(rid).ok());
-    (state.borrow::<FileSystemRc>().clone(), cancel_handle)
+  let fs = {
+    let mut state = state.borrow_mut();
+    state
+      .borrow_mut::<P>()
+      .check_read(&path, "Deno.listXattrs()")?;
+    state.borrow::<FileSystemRc>().clone()
   };
 
-  let fut = fs.read_file_async(path.clone(), Some(&mut access_check));
+  let names = fs
+    .list_xattrs_async(path.clone())
+    .await
+    .context_path("listxattr", &path)?;
+  Ok(names)
+}
+
+#[op2]
+pub fn op_fs_remove_xattr_sync<P>(
+  state: &mut OpState,
+  #[string] path: String,
+  #[string] name: String,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.removeXattrSync()")?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Modify,
+    "Deno.removeXattrSync()",
+  )?;
+
+  let fs = state.borrow::<FileSystemRc>();
+  fs.remove_xattr_sync(&path, &name)
+    .context_path("removexattr", &path)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_remove_xattr_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] path: String,
+  #[string] name: String,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let fs = {
+    let mut state = state.borrow_mut();
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.removeXattr()")?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Modify,
+      "Deno.removeXattr()",
+    )?;
+    state.borrow::<FileSystemRc>().clone()
+  };
+
+  fs.remove_xattr_async(path.clone(), name)
+    .await
+    .context_path("removexattr", &path)?;
+  Ok(())
+}
+
+#[op2]
+pub fn op_fs_write_file_sync<P>(
+  state: &mut OpState,
+  #[string] path: String,
+  mode: Option<u32>,
+  append: bool,
+  create: bool,
+  create_new: bool,
+  #[buffer] data: JsBuffer,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let mut options = OpenOptions::write(create, append, create_new, mode);
+  let restricted =
+    restrict_open_options(state.borrow::<P>(), &path, &mut options);
+  let fs = state.borrow::<FileSystemRc>().clone();
+  let mut access_check =
+    sync_permission_check::<P>(state.borrow_mut(), "Deno.writeFileSync()");
+
+  let result =
+    fs.write_file_sync(&path, options, Some(&mut access_check), &data);
+  drop(access_check);
+  result.map_err(|error| {
+    map_restricted_open_error::<P>(
+      state,
+      restricted,
+      "writefile",
+      error,
+      &path,
+      "Deno.writeFileSync()",
+    )
+  })?;
+
+  Ok(())
+}
+
+#[op2(async)]
+#[allow(clippy::too_many_arguments)]
+pub async fn op_fs_write_file_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] path: String,
+  #[smi] mode: Option<u32>,
+  append: bool,
+  create: bool,
+  create_new: bool,
+  #[buffer] data: JsBuffer,
+  #[smi] cancel_rid: Option<ResourceId>,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let mut options = OpenOptions::write(create, append, create_new, mode);
+  let restricted =
+    restrict_open_options(state.borrow().borrow::<P>(), &path, &mut options);
+
+  let mut access_check =
+    async_permission_check::<P>(state.clone(), "Deno.writeFile()");
+  let (fs, cancel_handle) = {
+    let state = state.borrow_mut();
+    let cancel_handle = cancel_rid
+      .and_then(|rid| state.resource_table.get::<CancelHandle>(rid).ok());
+    (state.borrow::<FileSystemRc>().clone(), cancel_handle)
+  };
+
+  let fut = fs.write_file_async(
+    path.clone(),
+    options,
+    Some(&mut access_check),
+    data.to_vec(),
+  );
+
+  let result = if let Some(cancel_handle) = cancel_handle {
+    let res = fut.or_cancel(cancel_handle).await;
+
+    if let Some(cancel_rid) = cancel_rid {
+      if let Ok(res) = state.borrow_mut().resource_table.take_any(cancel_rid) {
+        res.close();
+      }
+    };
+
+    res?
+  } else {
+    fut.await
+  };
+  result.map_err(|error| {
+    map_restricted_open_error::<P>(
+      &mut state.borrow_mut(),
+      restricted,
+      "writefile",
+      error,
+      &path,
+      "Deno.writeFile()",
+    )
+  })?;
+
+  Ok(())
+}
+
+#[op2]
+#[serde]
+pub fn op_fs_read_file_sync<P>(
+  state: &mut OpState,
+  #[string] path: String,
+) -> Result<ToJsBuffer, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let fs = state.borrow::<FileSystemRc>().clone();
+  let mut access_check =
+    sync_permission_check::<P>(state.borrow_mut(), "Deno.readFileSync()");
+  let buf = fs
+    .read_file_sync(&path, Some(&mut access_check))
+    .map_err(|error| map_permission_error("readfile", error, &path))?;
+
+  Ok(buf.into())
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_fs_read_file_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] path: String,
+  #[smi] cancel_rid: Option<ResourceId>,
+) -> Result<ToJsBuffer, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+
+  let mut access_check =
+    async_permission_check::<P>(state.clone(), "Deno.readFile()");
+  let (fs, cancel_handle) = {
+    let state = state.borrow();
+    let cancel_handle = cancel_rid
+      .and_then(|rid| state.resource_table.get::<CancelHandle>(rid).ok());
+    (state.borrow::<FileSystemRc>().clone(), cancel_handle)
+  };
+
+  let fut = fs.read_file_async(path.clone(), Some(&mut access_check));
 
   let buf = if let Some(cancel_handle) = cancel_handle {
     let res = fut.or_cancel(cancel_handle).await;
@@ -1462,6 +2018,61 @@ pub async fn op_fs_fsync_async(
   Ok(())
 }
 
+#[op2(fast)]
+pub fn op_fs_file_persist_sync<P>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] path: &str,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&path, "Deno.FsFile.persistSync()")?;
+  let reserve =
+    check_create_or_modify(permissions, &path, "Deno.FsFile.persistSync()")?;
+  let file = FileResource::get_file(state, rid)?;
+  let fs = state.borrow::<FileSystemRc>();
+  if let Some(options) = reserve {
+    fs.open_sync(&path, options, None)
+      .context_path("persist", &path)?;
+  }
+  fs.persist_file_sync(file, &path)
+    .context_path("persist", &path)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_file_persist_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let (file, fs, reserve) = {
+    let mut state = state.borrow_mut();
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&path, "Deno.FsFile.persist()")?;
+    let reserve =
+      check_create_or_modify(permissions, &path, "Deno.FsFile.persist()")?;
+    let file = FileResource::get_file(&state, rid)?;
+    (file, state.borrow::<FileSystemRc>().clone(), reserve)
+  };
+  if let Some(options) = reserve {
+    fs.open_async(path.clone(), options, None)
+      .await
+      .context_path("persist", &path)?;
+  }
+  fs.persist_file_async(file, path.clone())
+    .await
+    .context_path("persist", &path)?;
+  Ok(())
+}
+
 #[op2(fast)]
 pub fn op_fs_file_stat_sync(
   state: &mut OpState,
@@ -1580,7 +2191,7 @@ pub fn op_fs_ftruncate_sync(
   #[smi] rid: ResourceId,
   #[number] len: u64,
 ) -> Result<(), AnyError> {
-  let file = FileResource::get_file(state, rid)?;
+  let file = FileResource::get_file_for_modify(state, rid)?;
   file.truncate_sync(len)?;
   Ok(())
 }
@@ -1591,11 +2202,561 @@ pub async fn op_fs_ftruncate_async(
   #[smi] rid: ResourceId,
   #[number] len: u64,
 ) -> Result<(), AnyError> {
-  let file = FileResource::get_file(&state.borrow(), rid)?;
+  let file = FileResource::get_file_for_modify(&state.borrow(), rid)?;
   file.truncate_async(len).await?;
   Ok(())
 }
 
+#[op2(fast)]
+pub fn op_fs_fallocate_sync(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[number] len: u64,
+) -> Result<(), AnyError> {
+  let file = FileResource::get_file_for_modify(state, rid)?;
+  file.allocate_sync(len)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_fallocate_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[number] len: u64,
+) -> Result<(), AnyError> {
+  let file = FileResource::get_file_for_modify(&state.borrow(), rid)?;
+  file.allocate_async(len).await?;
+  Ok(())
+}
+
+#[op2(fast)]
+pub fn op_fs_fpunch_hole_sync(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[number] offset: u64,
+  #[number] len: u64,
+) -> Result<(), AnyError> {
+  let file = FileResource::get_file_for_modify(state, rid)?;
+  file.punch_hole_sync(offset, len)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_fpunch_hole_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[number] offset: u64,
+  #[number] len: u64,
+) -> Result<(), AnyError> {
+  let file = FileResource::get_file_for_modify(&state.borrow(), rid)?;
+  file.punch_hole_async(offset, len).await?;
+  Ok(())
+}
+
+/// Opens a second handle to the file of a resource, for the xattr ops.
+fn xattr_file(
+  state: &OpState,
+  rid: ResourceId,
+  modify: bool,
+) -> Result<std::fs::File, AnyError> {
+  let file = if modify {
+    FileResource::get_file_for_modify(state, rid)?
+  } else {
+    FileResource::get_file(state, rid)?
+  };
+  Ok(clone_backing_file(file)?)
+}
+
+#[op2]
+#[serde]
+pub fn op_fs_fget_xattr_sync(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] name: String,
+) -> Result<Option<ToJsBuffer>, AnyError> {
+  let file = xattr_file(state, rid, false)?;
+  let value = get_xattr(XattrTarget::File(&file), &name)?;
+  Ok(value.map(Into::into))
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_fs_fget_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] name: String,
+) -> Result<Option<ToJsBuffer>, AnyError> {
+  let file = xattr_file(&state.borrow(), rid, false)?;
+  let value =
+    spawn_blocking(move || get_xattr(XattrTarget::File(&file), &name))
+      .await??;
+  Ok(value.map(Into::into))
+}
+
+#[op2]
+pub fn op_fs_fset_xattr_sync(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] name: String,
+  #[buffer] value: JsBuffer,
+) -> Result<(), AnyError> {
+  let file = xattr_file(state, rid, true)?;
+  set_xattr(XattrTarget::File(&file), &name, &value)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_fset_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] name: String,
+  #[buffer] value: JsBuffer,
+) -> Result<(), AnyError> {
+  let file = xattr_file(&state.borrow(), rid, true)?;
+  let value = value.to_vec();
+  spawn_blocking(move || set_xattr(XattrTarget::File(&file), &name, &value))
+    .await??;
+  Ok(())
+}
+
+#[op2]
+#[serde]
+pub fn op_fs_flist_xattrs_sync(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<Vec<String>, AnyError> {
+  let file = xattr_file(state, rid, false)?;
+  Ok(list_xattrs(XattrTarget::File(&file))?)
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_fs_flist_xattrs_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Vec<String>, AnyError> {
+  let file = xattr_file(&state.borrow(), rid, false)?;
+  let names =
+    spawn_blocking(move || list_xattrs(XattrTarget::File(&file))).await??;
+  Ok(names)
+}
+
+#[op2]
+pub fn op_fs_fremove_xattr_sync(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] name: String,
+) -> Result<(), AnyError> {
+  let file = xattr_file(state, rid, true)?;
+  remove_xattr(XattrTarget::File(&file), &name)?;
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_fremove_xattr_async(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] name: String,
+) -> Result<(), AnyError> {
+  let file = xattr_file(&state.borrow(), rid, true)?;
+  spawn_blocking(move || remove_xattr(XattrTarget::File(&file), &name))
+    .await??;
+  Ok(())
+}
+
+#[op2]
+#[smi]
+pub fn op_fs_open_dir_sync<P>(
+  state: &mut OpState,
+  #[string] path: String,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  state
+    .borrow_mut::<P>()
+    .check_read(&path, "Deno.openDirSync()")?;
+  let fs = state.borrow::<FileSystemRc>();
+  let dir = fs
+    .open_dir_handle_sync(&path)
+    .context_path("opendir", &path)?;
+  Ok(state.resource_table.add(DirHandleResource(dir)))
+}
+
+#[op2(async)]
+#[smi]
+pub async fn op_fs_open_dir_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[string] path: String,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let fs = {
+    let mut state = state.borrow_mut();
+    state
+      .borrow_mut::<P>()
+      .check_read(&path, "Deno.openDir()")?;
+    state.borrow::<FileSystemRc>().clone()
+  };
+  let dir = fs
+    .open_dir_handle_async(path.clone())
+    .await
+    .context_path("opendir", &path)?;
+  Ok(
+    state
+      .borrow_mut()
+      .resource_table
+      .add(DirHandleResource(dir)),
+  )
+}
+
+fn dir_handle(
+  state: &OpState,
+  rid: ResourceId,
+) -> Result<Rc<DirHandleResource>, AnyError> {
+  state.resource_table.get::<DirHandleResource>(rid)
+}
+
+#[op2]
+#[smi]
+pub fn op_fs_open_dir_at_sync<P>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let dir = dir_handle(state, rid)?;
+  let full_path = dir.0.resolve(&path);
+  state
+    .borrow_mut::<P>()
+    .check_read(&full_path, "Deno.DirHandle.openDirAtSync()")?;
+  let fs = state.borrow::<FileSystemRc>();
+  let child = fs
+    .open_dir_at_sync(&dir.0, &path)
+    .context_path("opendir", &full_path)?;
+  Ok(state.resource_table.add(DirHandleResource(child)))
+}
+
+#[op2(async)]
+#[smi]
+pub async fn op_fs_open_dir_at_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let (fs, dir, full_path) = {
+    let mut state = state.borrow_mut();
+    let dir = dir_handle(&state, rid)?;
+    let full_path = dir.0.resolve(&path);
+    state
+      .borrow_mut::<P>()
+      .check_read(&full_path, "Deno.DirHandle.openDirAt()")?;
+    let fs = state.borrow::<FileSystemRc>().clone();
+    (fs, dir.0.try_clone()?, full_path)
+  };
+  let child = fs
+    .open_dir_at_async(dir, path)
+    .await
+    .context_path("opendir", &full_path)?;
+  Ok(
+    state
+      .borrow_mut()
+      .resource_table
+      .add(DirHandleResource(child)),
+  )
+}
+
+#[op2]
+#[smi]
+pub fn op_fs_open_at_sync<P>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+  #[serde] options: Option<OpenOptions>,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let mut options = options.unwrap_or_else(OpenOptions::read);
+  let dir = dir_handle(state, rid)?;
+  let full_path = dir.0.resolve(&path);
+  let api_name = "Deno.DirHandle.openAtSync()";
+  let permissions = state.borrow_mut::<P>();
+  let restricted = restrict_open_options(permissions, &full_path, &mut options);
+  permissions
+    .check(false, &options, &full_path, api_name)
+    .map_err(|error| map_permission_error("open", error, &full_path))?;
+  let fs = state.borrow::<FileSystemRc>().clone();
+  let file = fs.open_at_sync(&dir.0, &path, options).map_err(|error| {
+    map_restricted_open_error::<P>(
+      state, restricted, "open", error, &full_path, api_name,
+    )
+  })?;
+  let resource = open_file_resource::<P>(state, file, &full_path, options);
+  Ok(state.resource_table.add(resource))
+}
+
+#[op2(async)]
+#[smi]
+pub async fn op_fs_open_at_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+  #[serde] options: Option<OpenOptions>,
+) -> Result<ResourceId, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let mut options = options.unwrap_or_else(OpenOptions::read);
+  let api_name = "Deno.DirHandle.openAt()";
+  let (fs, dir, full_path, restricted) = {
+    let mut state = state.borrow_mut();
+    let dir = dir_handle(&state, rid)?;
+    let full_path = dir.0.resolve(&path);
+    let permissions = state.borrow_mut::<P>();
+    let restricted =
+      restrict_open_options(permissions, &full_path, &mut options);
+    permissions
+      .check(false, &options, &full_path, api_name)
+      .map_err(|error| map_permission_error("open", error, &full_path))?;
+    let fs = state.borrow::<FileSystemRc>().clone();
+    (fs, dir.0.try_clone()?, full_path, restricted)
+  };
+  let file = fs
+    .open_at_async(dir, path, options)
+    .await
+    .map_err(|error| {
+      map_restricted_open_error::<P>(
+        &mut state.borrow_mut(),
+        restricted,
+        "open",
+        error,
+        &full_path,
+        api_name,
+      )
+    })?;
+  let mut state = state.borrow_mut();
+  let resource = open_file_resource::<P>(&state, file, &full_path, options);
+  Ok(state.resource_table.add(resource))
+}
+
+#[op2(fast)]
+pub fn op_fs_stat_at_sync<P>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+  follow_symlinks: bool,
+  #[buffer] stat_out_buf: &mut [u32],
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let dir = dir_handle(state, rid)?;
+  let full_path = dir.0.resolve(&path);
+  state
+    .borrow_mut::<P>()
+    .check_read(&full_path, "Deno.DirHandle.statAtSync()")?;
+  let fs = state.borrow::<FileSystemRc>();
+  let stat = fs
+    .stat_at_sync(&dir.0, &path, follow_symlinks)
+    .context_path("stat", &full_path)?;
+  let serializable_stat = SerializableStat::from(stat);
+  serializable_stat.write(stat_out_buf);
+  Ok(())
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_fs_stat_at_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+  follow_symlinks: bool,
+) -> Result<SerializableStat, AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let (fs, dir, full_path) = {
+    let mut state = state.borrow_mut();
+    let dir = dir_handle(&state, rid)?;
+    let full_path = dir.0.resolve(&path);
+    state
+      .borrow_mut::<P>()
+      .check_read(&full_path, "Deno.DirHandle.statAt()")?;
+    let fs = state.borrow::<FileSystemRc>().clone();
+    (fs, dir.0.try_clone()?, full_path)
+  };
+  let stat = fs
+    .stat_at_async(dir, path, follow_symlinks)
+    .await
+    .context_path("stat", &full_path)?;
+  Ok(SerializableStat::from(stat))
+}
+
+#[op2]
+pub fn op_fs_rename_at_sync<P>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] oldpath: String,
+  #[smi] new_rid: ResourceId,
+  #[string] newpath: String,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let oldpath = PathBuf::from(oldpath);
+  let newpath = PathBuf::from(newpath);
+  let dir = dir_handle(state, rid)?;
+  let new_dir = dir_handle(state, new_rid)?;
+  let full_oldpath = dir.0.resolve(&oldpath);
+  let full_newpath = new_dir.0.resolve(&newpath);
+
+  let permissions = state.borrow_mut::<P>();
+  let api_name = "Deno.DirHandle.renameAtSync()";
+  permissions.check_read(&full_oldpath, api_name)?;
+  permissions.check_write(&full_oldpath, api_name)?;
+  permissions.check_write_operation(
+    &full_oldpath,
+    WriteOperation::Rename,
+    api_name,
+  )?;
+  permissions.check_write(&full_newpath, api_name)?;
+  permissions.check_write_operation(
+    &full_newpath,
+    WriteOperation::Rename,
+    api_name,
+  )?;
+
+  let fs = state.borrow::<FileSystemRc>();
+  fs.rename_at_sync(&dir.0, &oldpath, &new_dir.0, &newpath)
+    .context_two_path("rename", &full_oldpath, &full_newpath)?;
+  record_path_mutation();
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_rename_at_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] oldpath: String,
+  #[smi] new_rid: ResourceId,
+  #[string] newpath: String,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let oldpath = PathBuf::from(oldpath);
+  let newpath = PathBuf::from(newpath);
+  let (fs, dir, new_dir, full_oldpath, full_newpath) = {
+    let mut state = state.borrow_mut();
+    let dir = dir_handle(&state, rid)?;
+    let new_dir = dir_handle(&state, new_rid)?;
+    let full_oldpath = dir.0.resolve(&oldpath);
+    let full_newpath = new_dir.0.resolve(&newpath);
+
+    let permissions = state.borrow_mut::<P>();
+    let api_name = "Deno.DirHandle.renameAt()";
+    permissions.check_read(&full_oldpath, api_name)?;
+    permissions.check_write(&full_oldpath, api_name)?;
+    permissions.check_write_operation(
+      &full_oldpath,
+      WriteOperation::Rename,
+      api_name,
+    )?;
+    permissions.check_write(&full_newpath, api_name)?;
+    permissions.check_write_operation(
+      &full_newpath,
+      WriteOperation::Rename,
+      api_name,
+    )?;
+    (
+      state.borrow::<FileSystemRc>().clone(),
+      dir.0.try_clone()?,
+      new_dir.0.try_clone()?,
+      full_oldpath,
+      full_newpath,
+    )
+  };
+  fs.rename_at_async(dir, oldpath, new_dir, newpath)
+    .await
+    .context_two_path("rename", &full_oldpath, &full_newpath)?;
+  record_path_mutation();
+  Ok(())
+}
+
+#[op2]
+pub fn op_fs_unlink_at_sync<P>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+  is_dir: bool,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let dir = dir_handle(state, rid)?;
+  let full_path = dir.0.resolve(&path);
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write(&full_path, "Deno.DirHandle.unlinkAtSync()")?;
+  permissions.check_write_operation(
+    &full_path,
+    WriteOperation::Delete,
+    "Deno.DirHandle.unlinkAtSync()",
+  )?;
+  let fs = state.borrow::<FileSystemRc>();
+  fs.unlink_at_sync(&dir.0, &path, is_dir)
+    .context_path("remove", &full_path)?;
+  record_path_mutation();
+  Ok(())
+}
+
+#[op2(async)]
+pub async fn op_fs_unlink_at_async<P>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] path: String,
+  is_dir: bool,
+) -> Result<(), AnyError>
+where
+  P: FsPermissions + 'static,
+{
+  let path = PathBuf::from(path);
+  let (fs, dir, full_path) = {
+    let mut state = state.borrow_mut();
+    let dir = dir_handle(&state, rid)?;
+    let full_path = dir.0.resolve(&path);
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write(&full_path, "Deno.DirHandle.unlinkAt()")?;
+    permissions.check_write_operation(
+      &full_path,
+      WriteOperation::Delete,
+      "Deno.DirHandle.unlinkAt()",
+    )?;
+    let fs = state.borrow::<FileSystemRc>().clone();
+    (fs, dir.0.try_clone()?, full_path)
+  };
+  fs.unlink_at_async(dir, path, is_dir)
+    .await
+    .context_path("remove", &full_path)?;
+  record_path_mutation();
+  Ok(())
+}
+
 #[op2(fast)]
 pub fn op_fs_futime_sync(
   state: &mut OpState,
@@ -1605,7 +2766,7 @@ pub fn op_fs_futime_sync(
   #[number] mtime_secs: i64,
   #[smi] mtime_nanos: u32,
 ) -> Result<(), AnyError> {
-  let file = FileResource::get_file(state, rid)?;
+  let file = FileResource::get_file_for_modify(state, rid)?;
   file.utime_sync(atime_secs, atime_nanos, mtime_secs, mtime_nanos)?;
   Ok(())
 }
@@ -1619,7 +2780,7 @@ pub async fn op_fs_futime_async(
   #[number] mtime_secs: i64,
   #[smi] mtime_nanos: u32,
 ) -> Result<(), AnyError> {
-  let file = FileResource::get_file(&state.borrow(), rid)?;
+  let file = FileResource::get_file_for_modify(&state.borrow(), rid)?;
   file
     .utime_async(atime_secs, atime_nanos, mtime_secs, mtime_nanos)
     .await?;
@@ -0,0 +1,349 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! A cache of canonical paths, for callers like module resolution that
+//! canonicalize the same directories over and over.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use deno_io::fs::FsResult;
+use deno_io::fs::FsStat;
+
+use crate::sync::MaybeArcMutex;
+use crate::FileSystem;
+
+/// Bumped by the ops that can change which directory a path leads to, so
+/// that caches forget the directories they have seen.
+static PATH_MUTATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Records that a directory entry was removed, renamed or symlinked.
+pub(crate) fn record_path_mutation() {
+  PATH_MUTATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Caches the canonical paths of directories by the path they were looked up
+/// with, so that canonicalizing a path to a directory, or to a file in it,
+/// takes two `stat` calls instead of one per path component.
+///
+/// Entries are keyed by path rather than by device and inode, as a directory
+/// can have several canonical paths through bind mounts. An entry is only
+/// used while the path and its cached canonical path still lead to the
+/// directory it was created for, which catches directories that were moved,
+/// and inodes that were reused after a directory was removed. All entries
+/// are dropped when the `Deno.rename()`, `Deno.remove()` or `Deno.symlink()`
+/// ops change the file system, and callers report changes that file
+/// watchers see with `invalidate`.
+///
+/// The paths of files are derived from their directory only if the file
+/// system is case sensitive, as otherwise the file name may not be spelled
+/// like it is on disk. Whether it is, is probed once per device. File systems
+/// without inode numbers, like the ones on Windows, are not cached.
+#[derive(Debug)]
+pub struct RealpathCache {
+  dirs: MaybeArcMutex<CachedDirs>,
+  case_sensitive: MaybeArcMutex<HashMap<u64, bool>>,
+}
+
+#[derive(Debug, Default)]
+struct CachedDirs {
+  /// The value of `PATH_MUTATIONS` when the entries were created.
+  mutations: u64,
+  entries: HashMap<PathBuf, CachedDir>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedDir {
+  realpath: PathBuf,
+  dev: u64,
+  ino: u64,
+}
+
+impl CachedDir {
+  fn is(&self, stat: &FsStat) -> bool {
+    self.dev == stat.dev && self.ino == stat.ino
+  }
+}
+
+impl Default for RealpathCache {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl RealpathCache {
+  pub fn new() -> Self {
+    Self {
+      dirs: MaybeArcMutex::new(CachedDirs::default()),
+      case_sensitive: MaybeArcMutex::new(HashMap::new()),
+    }
+  }
+
+  /// Returns the canonical path of `path` like `fs.realpath_sync` does.
+  pub fn realpath(
+    &self,
+    fs: &dyn FileSystem,
+    path: &Path,
+  ) -> FsResult<PathBuf> {
+    let lstat = fs.lstat_sync(path)?;
+    if lstat.is_directory {
+      return self.dir_realpath(fs, path, &lstat);
+    }
+    if lstat.is_symlink {
+      let stat = fs.stat_sync(path)?;
+      if stat.is_directory {
+        return self.dir_realpath(fs, path, &stat);
+      }
+      return fs.realpath_sync(path);
+    }
+
+    let Some(Component::Normal(name)) = path.components().next_back() else {
+      return fs.realpath_sync(path);
+    };
+    let parent = match path.parent() {
+      Some(parent) if !parent.as_os_str().is_empty() => parent,
+      _ => Path::new("."),
+    };
+    let parent_stat = fs.stat_sync(parent)?;
+    if !self.is_case_sensitive(fs, parent, &parent_stat) {
+      return fs.realpath_sync(path);
+    }
+    Ok(self.dir_realpath(fs, parent, &parent_stat)?.join(name))
+  }
+
+  /// Returns the canonical path of the directory at `path`, which `stat`
+  /// describes.
+  fn dir_realpath(
+    &self,
+    fs: &dyn FileSystem,
+    path: &Path,
+    stat: &FsStat,
+  ) -> FsResult<PathBuf> {
+    if stat.ino == 0 {
+      return fs.realpath_sync(path);
+    }
+    let cached = {
+      let mut dirs = self.dirs.lock();
+      let mutations = PATH_MUTATIONS.load(Ordering::Relaxed);
+      if dirs.mutations != mutations {
+        dirs.mutations = mutations;
+        dirs.entries.clear();
+      }
+      dirs.entries.get(path).cloned()
+    };
+    if let Some(cached) = cached {
+      if cached.is(stat)
+        && fs
+          .stat_sync(&cached.realpath)
+          .is_ok_and(|stat| cached.is(&stat))
+      {
+        return Ok(cached.realpath);
+      }
+    }
+    let realpath = fs.realpath_sync(path)?;
+    self.dirs.lock().entries.insert(
+      path.to_path_buf(),
+      CachedDir {
+        realpath: realpath.clone(),
+        dev: stat.dev,
+        ino: stat.ino,
+      },
+    );
+    Ok(realpath)
+  }
+
+  /// Whether names in the directory at `dir` are case sensitive. Unknown if
+  /// the directory has no ancestor on the same device with a name that has
+  /// case, in which case it is treated as case insensitive.
+  fn is_case_sensitive(
+    &self,
+    fs: &dyn FileSystem,
+    dir: &Path,
+    stat: &FsStat,
+  ) -> bool {
+    if stat.ino == 0 {
+      return false;
+    }
+    if let Some(case_sensitive) = self.case_sensitive.lock().get(&stat.dev) {
+      return *case_sensitive;
+    }
+    let case_sensitive = match self.dir_realpath(fs, dir, stat) {
+      Ok(dir) => probe_case_sensitive(fs, &dir, stat.dev).unwrap_or(false),
+      Err(_) => return false,
+    };
+    self.case_sensitive.lock().insert(stat.dev, case_sensitive);
+    case_sensitive
+  }
+
+  /// Forgets the directories at or below any of `changed_paths`, which are
+  /// canonical paths reported by a file watcher.
+  pub fn invalidate(&self, changed_paths: &[PathBuf]) {
+    self.dirs.lock().entries.retain(|path, cached| {
+      !changed_paths.iter().any(|changed| {
+        cached.realpath.starts_with(changed) || path.starts_with(changed)
+      })
+    });
+  }
+
+  pub fn clear(&self) {
+    self.dirs.lock().entries.clear();
+    self.case_sensitive.lock().clear();
+  }
+}
+
+/// Looks up the first ancestor of the canonical path `dir` that has a name
+/// with case under a differently cased name, and checks whether that finds
+/// the same directory. Returns `None` if there is no such ancestor on device
+/// `dev`.
+fn probe_case_sensitive(
+  fs: &dyn FileSystem,
+  dir: &Path,
+  dev: u64,
+) -> Option<bool> {
+  for ancestor in dir.ancestors() {
+    let name = ancestor.file_name()?.to_str()?;
+    let swapped = swap_case(name);
+    if swapped == name {
+      continue;
+    }
+    let stat = fs.stat_sync(ancestor).ok()?;
+    if stat.dev != dev {
+      return None;
+    }
+    return match fs.stat_sync(&ancestor.with_file_name(swapped)) {
+      Ok(other) => Some(other.dev != stat.dev || other.ino != stat.ino),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Some(true),
+      Err(_) => None,
+    };
+  }
+  None
+}
+
+fn swap_case(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_lowercase() {
+        c.to_ascii_uppercase()
+      } else {
+        c.to_ascii_lowercase()
+      }
+    })
+    .collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use super::*;
+  use crate::OpenOptions;
+  use crate::RealFs;
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = RealFs
+      .tmp_dir()
+      .unwrap()
+      .join(format!("deno_realpath_cache_{}_{name}", std::process::id()));
+    let _ = RealFs.remove_sync(&dir, true);
+    RealFs.mkdir_sync(&dir, true, 0o755).unwrap();
+    RealFs.realpath_sync(&dir).unwrap()
+  }
+
+  fn is_cached(cache: &RealpathCache, path: &Path) -> bool {
+    cache.dirs.lock().entries.contains_key(path)
+  }
+
+  #[test]
+  fn caches_dirs_through_symlinks() {
+    let dir = temp_dir("symlinks");
+    RealFs.mkdir_sync(&dir.join("a"), false, 0o755).unwrap();
+    RealFs
+      .symlink_sync(Path::new("a"), &dir.join("link"), None)
+      .unwrap();
+    let cache = RealpathCache::new();
+    let link = dir.join("link");
+    assert_eq!(cache.realpath(&RealFs, &link).unwrap(), dir.join("a"));
+    assert!(is_cached(&cache, &link));
+    assert_eq!(cache.realpath(&RealFs, &link).unwrap(), dir.join("a"));
+    RealFs
+      .write_file_sync(
+        &dir.join("a/b.js"),
+        OpenOptions::write(true, false, false, None),
+        None,
+        b"",
+      )
+      .unwrap();
+    assert_eq!(
+      cache.realpath(&RealFs, &link.join("b.js")).unwrap(),
+      dir.join("a/b.js")
+    );
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+
+  #[test]
+  fn revalidates_moved_dirs() {
+    let dir = temp_dir("moved");
+    let link = dir.join("link");
+    RealFs.mkdir_sync(&dir.join("a"), false, 0o755).unwrap();
+    RealFs.symlink_sync(Path::new("a"), &link, None).unwrap();
+    let cache = RealpathCache::new();
+    assert_eq!(cache.realpath(&RealFs, &link).unwrap(), dir.join("a"));
+
+    // the link still leads to the same directory, under another name
+    RealFs.rename_sync(&dir.join("a"), &dir.join("b")).unwrap();
+    RealFs.remove_sync(&link, false).unwrap();
+    RealFs.symlink_sync(Path::new("b"), &link, None).unwrap();
+    assert_eq!(cache.realpath(&RealFs, &link).unwrap(), dir.join("b"));
+
+    // the cached canonical path leads to a new directory
+    RealFs.mkdir_sync(&dir.join("a"), false, 0o755).unwrap();
+    RealFs.remove_sync(&link, false).unwrap();
+    RealFs.symlink_sync(Path::new("a"), &link, None).unwrap();
+    assert_eq!(cache.realpath(&RealFs, &link).unwrap(), dir.join("a"));
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+
+  #[test]
+  fn keys_dirs_by_path() {
+    let dir = temp_dir("keys");
+    RealFs.mkdir_sync(&dir.join("a"), false, 0o755).unwrap();
+    let cache = RealpathCache::new();
+    assert_eq!(
+      cache.realpath(&RealFs, &dir.join("a")).unwrap(),
+      dir.join("a")
+    );
+    // like a bind mount would, the same directory under another path
+    // resolves to that path instead of the cached one
+    RealFs.rename_sync(&dir.join("a"), &dir.join("b")).unwrap();
+    assert_eq!(
+      cache.realpath(&RealFs, &dir.join("b")).unwrap(),
+      dir.join("b")
+    );
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+
+  #[test]
+  fn forgets_dirs() {
+    let dir = temp_dir("forget");
+    let a = dir.join("a");
+    let b = dir.join("b");
+    RealFs.mkdir_sync(&a, false, 0o755).unwrap();
+    RealFs.mkdir_sync(&b, false, 0o755).unwrap();
+    let cache = RealpathCache::new();
+    cache.realpath(&RealFs, &a).unwrap();
+    cache.realpath(&RealFs, &b).unwrap();
+
+    cache.invalidate(&[a.clone()]);
+    assert!(!is_cached(&cache, &a));
+    assert!(is_cached(&cache, &b));
+
+    record_path_mutation();
+    cache.realpath(&RealFs, &a).unwrap();
+    assert!(is_cached(&cache, &a));
+    assert!(!is_cached(&cache, &b));
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+}
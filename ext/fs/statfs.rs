@@ -0,0 +1,245 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Usage information about the file system containing a path.
+
+use std::io;
+use std::path::Path;
+
+use crate::interface::FsStatfs;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+  use std::ffi::CString;
+  use std::io;
+  use std::os::unix::ffi::OsStrExt;
+  use std::path::Path;
+
+  use crate::interface::FsStatfs;
+
+  /// Names of common file systems by their `f_type` magic number, see
+  /// `statfs(2)`.
+  const FS_TYPES: &[(u32, &str)] = &[
+    (0x0000_4d44, "vfat"),
+    (0x0000_6969, "nfs"),
+    (0x0000_9660, "iso9660"),
+    (0x0000_9fa0, "proc"),
+    (0x0000_ef53, "ext4"),
+    (0x0102_1994, "tmpfs"),
+    (0x2011_bab0, "exfat"),
+    (0x2fc1_2fc1, "zfs"),
+    (0x3153_464a, "jfs"),
+    (0x5265_4973, "reiserfs"),
+    (0x5346_544e, "ntfs"),
+    (0x5846_5342, "xfs"),
+    (0x6265_6572, "sysfs"),
+    (0x6367_7270, "cgroup2"),
+    (0x6573_5546, "fuse"),
+    (0x7371_7368, "squashfs"),
+    (0x794c_7630, "overlayfs"),
+    (0x8584_58f6, "ramfs"),
+    (0x9123_683e, "btrfs"),
+    (0xf2f5_2010, "f2fs"),
+    (0xff53_4d42, "cifs"),
+  ];
+
+  #[allow(clippy::unnecessary_cast)]
+  pub fn statfs(path: &Path) -> io::Result<FsStatfs> {
+    let path = CString::new(path.as_os_str().as_bytes())
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: `path` is a valid C string and `stat` is valid for writes.
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `statfs` succeeded, so it initialized `stat`.
+    let stat = unsafe { stat.assume_init() };
+
+    let magic = stat.f_type as u32;
+    let fs_type = FS_TYPES
+      .iter()
+      .find(|(m, _)| *m == magic)
+      .map(|(_, name)| name.to_string())
+      .unwrap_or_else(|| format!("{magic:#x}"));
+    // Block counts are in units of the fragment size, which is the block
+    // size on most file systems.
+    let block_size = if stat.f_frsize > 0 {
+      stat.f_frsize as u64
+    } else {
+      stat.f_bsize as u64
+    };
+    Ok(FsStatfs {
+      fs_type,
+      block_size,
+      total_bytes: stat.f_blocks as u64 * block_size,
+      free_bytes: stat.f_bfree as u64 * block_size,
+      available_bytes: stat.f_bavail as u64 * block_size,
+      files: Some(stat.f_files as u64),
+      free_files: Some(stat.f_ffree as u64),
+    })
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+  use std::ffi::CStr;
+  use std::ffi::CString;
+  use std::io;
+  use std::os::unix::ffi::OsStrExt;
+  use std::path::Path;
+
+  use crate::interface::FsStatfs;
+
+  #[allow(clippy::unnecessary_cast)]
+  pub fn statfs(path: &Path) -> io::Result<FsStatfs> {
+    let path = CString::new(path.as_os_str().as_bytes())
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: `path` is a valid C string and `stat` is valid for writes.
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `statfs` succeeded, so it initialized `stat`.
+    let stat = unsafe { stat.assume_init() };
+
+    // SAFETY: `f_fstypename` is a nul-terminated string.
+    let fs_type = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) }
+      .to_string_lossy()
+      .into_owned();
+    let block_size = stat.f_bsize as u64;
+    Ok(FsStatfs {
+      fs_type,
+      block_size,
+      total_bytes: stat.f_blocks as u64 * block_size,
+      free_bytes: stat.f_bfree as u64 * block_size,
+      available_bytes: stat.f_bavail as u64 * block_size,
+      files: Some(stat.f_files as u64),
+      free_files: Some(stat.f_ffree as u64),
+    })
+  }
+}
+
+#[cfg(windows)]
+mod sys {
+  use std::io;
+  use std::os::windows::ffi::OsStrExt;
+  use std::os::windows::ffi::OsStringExt;
+  use std::path::Path;
+
+  use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+  use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceW;
+  use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+  use windows_sys::Win32::Storage::FileSystem::GetVolumePathNameW;
+
+  use crate::interface::FsStatfs;
+
+  const MAX_PATH: usize = 261;
+
+  pub fn statfs(path: &Path) -> io::Result<FsStatfs> {
+    let path: Vec<u16> =
+      path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut root = vec![0u16; path.len().max(MAX_PATH)];
+    // SAFETY: `path` is nul-terminated and `root` is valid for writes of
+    // its length.
+    if unsafe {
+      GetVolumePathNameW(path.as_ptr(), root.as_mut_ptr(), root.len() as u32)
+    } == 0
+    {
+      return Err(io::Error::last_os_error());
+    }
+
+    let mut available_bytes = 0;
+    let mut total_bytes = 0;
+    let mut free_bytes = 0;
+    // SAFETY: `path` is nul-terminated and the out pointers are valid.
+    if unsafe {
+      GetDiskFreeSpaceExW(
+        path.as_ptr(),
+        &mut available_bytes,
+        &mut total_bytes,
+        &mut free_bytes,
+      )
+    } == 0
+    {
+      return Err(io::Error::last_os_error());
+    }
+
+    let mut sectors_per_cluster = 0;
+    let mut bytes_per_sector = 0;
+    let mut free_clusters = 0;
+    let mut total_clusters = 0;
+    // SAFETY: `root` is nul-terminated and the out pointers are valid.
+    if unsafe {
+      GetDiskFreeSpaceW(
+        root.as_ptr(),
+        &mut sectors_per_cluster,
+        &mut bytes_per_sector,
+        &mut free_clusters,
+        &mut total_clusters,
+      )
+    } == 0
+    {
+      return Err(io::Error::last_os_error());
+    }
+
+    let mut fs_name = [0u16; MAX_PATH];
+    // SAFETY: `root` is nul-terminated and `fs_name` is valid for writes of
+    // its length. The other out parameters are optional.
+    if unsafe {
+      GetVolumeInformationW(
+        root.as_ptr(),
+        std::ptr::null_mut(),
+        0,
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+        fs_name.as_mut_ptr(),
+        fs_name.len() as u32,
+      )
+    } == 0
+    {
+      return Err(io::Error::last_os_error());
+    }
+    let len = fs_name
+      .iter()
+      .position(|c| *c == 0)
+      .unwrap_or(fs_name.len());
+    let fs_type = std::ffi::OsString::from_wide(&fs_name[..len])
+      .to_string_lossy()
+      .into_owned();
+
+    Ok(FsStatfs {
+      fs_type,
+      block_size: sectors_per_cluster as u64 * bytes_per_sector as u64,
+      total_bytes,
+      free_bytes,
+      available_bytes,
+      files: None,
+      free_files: None,
+    })
+  }
+}
+
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "android",
+  target_os = "macos",
+  windows
+)))]
+mod sys {
+  use std::io;
+  use std::path::Path;
+
+  use crate::interface::FsStatfs;
+
+  pub fn statfs(_path: &Path) -> io::Result<FsStatfs> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "statfs is not supported on this platform",
+    ))
+  }
+}
+
+/// Returns the size and free space of the file system containing `path`.
+pub fn statfs(path: &Path) -> io::Result<FsStatfs> {
+  sys::statfs(path)
+}
@@ -19,9 +19,20 @@ use deno_io::fs::FsResult;
 use deno_io::fs::FsStat;
 use deno_io::StdFileResourceInner;
 
+use crate::dir_handle::DirHandle;
 use crate::interface::AccessCheckCb;
 use crate::interface::FsDirEntry;
 use crate::interface::FsFileType;
+use crate::interface::FsReadDir;
+use crate::interface::FsStatfs;
+use crate::statfs::statfs;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+use crate::uring;
+use crate::xattr::get_xattr;
+use crate::xattr::list_xattrs;
+use crate::xattr::remove_xattr;
+use crate::xattr::set_xattr;
+use crate::xattr::XattrTarget;
 use crate::FileSystem;
 use crate::OpenOptions;
 
@@ -93,10 +104,140 @@ impl FileSystem for RealFs {
     options: OpenOptions,
     access_check: Option<AccessCheckCb<'a>>,
   ) -> FsResult<Rc<dyn File>> {
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    if let Some(ring) = uring::current() {
+      let std_file =
+        uring_open_with_access_check(&ring, options, &path, access_check)
+          .await?;
+      return Ok(Rc::new(StdFileResourceInner::file(std_file)));
+    }
     let std_file = open_with_access_check(options, &path, access_check)?;
     Ok(Rc::new(StdFileResourceInner::file(std_file)))
   }
 
+  fn make_anonymous_temp_file_sync(
+    &self,
+    dir: &Path,
+  ) -> FsResult<Rc<dyn File>> {
+    let std_file = anonymous_temp_file(dir)?;
+    Ok(Rc::new(StdFileResourceInner::file(std_file)))
+  }
+  async fn make_anonymous_temp_file_async(
+    &self,
+    dir: PathBuf,
+  ) -> FsResult<Rc<dyn File>> {
+    let std_file = spawn_blocking(move || anonymous_temp_file(&dir)).await??;
+    Ok(Rc::new(StdFileResourceInner::file(std_file)))
+  }
+
+  fn persist_file_sync(&self, file: Rc<dyn File>, path: &Path) -> FsResult<()> {
+    let std_file = clone_backing_file(file)?;
+    persist_file(std_file, path)
+  }
+  async fn persist_file_async(
+    &self,
+    file: Rc<dyn File>,
+    path: PathBuf,
+  ) -> FsResult<()> {
+    let std_file = clone_backing_file(file)?;
+    spawn_blocking(move || persist_file(std_file, &path)).await?
+  }
+  fn open_dir_handle_sync(&self, path: &Path) -> FsResult<DirHandle> {
+    DirHandle::open(absolute_path(path)?)
+  }
+  async fn open_dir_handle_async(&self, path: PathBuf) -> FsResult<DirHandle> {
+    let path = absolute_path(&path)?;
+    spawn_blocking(move || DirHandle::open(path)).await?
+  }
+
+  fn open_dir_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+  ) -> FsResult<DirHandle> {
+    dir.open_dir_at(path)
+  }
+  async fn open_dir_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+  ) -> FsResult<DirHandle> {
+    spawn_blocking(move || dir.open_dir_at(&path)).await?
+  }
+
+  fn open_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    let std_file = dir.open_at(path, &options)?;
+    Ok(Rc::new(StdFileResourceInner::file(std_file)))
+  }
+  async fn open_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    options: OpenOptions,
+  ) -> FsResult<Rc<dyn File>> {
+    let std_file =
+      spawn_blocking(move || dir.open_at(&path, &options)).await??;
+    Ok(Rc::new(StdFileResourceInner::file(std_file)))
+  }
+
+  fn stat_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    dir.stat_at(path, follow_symlinks)
+  }
+  async fn stat_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    follow_symlinks: bool,
+  ) -> FsResult<FsStat> {
+    spawn_blocking(move || dir.stat_at(&path, follow_symlinks)).await?
+  }
+
+  fn rename_at_sync(
+    &self,
+    dir: &DirHandle,
+    oldpath: &Path,
+    new_dir: &DirHandle,
+    newpath: &Path,
+  ) -> FsResult<()> {
+    dir.rename_at(oldpath, new_dir, newpath)
+  }
+  async fn rename_at_async(
+    &self,
+    dir: DirHandle,
+    oldpath: PathBuf,
+    new_dir: DirHandle,
+    newpath: PathBuf,
+  ) -> FsResult<()> {
+    spawn_blocking(move || dir.rename_at(&oldpath, &new_dir, &newpath)).await?
+  }
+
+  fn unlink_at_sync(
+    &self,
+    dir: &DirHandle,
+    path: &Path,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    dir.unlink_at(path, is_dir)
+  }
+  async fn unlink_at_async(
+    &self,
+    dir: DirHandle,
+    path: PathBuf,
+    is_dir: bool,
+  ) -> FsResult<()> {
+    spawn_blocking(move || dir.unlink_at(&path, is_dir)).await?
+  }
+
   fn mkdir_sync(
     &self,
     path: &Path,
@@ -163,6 +304,10 @@ impl FileSystem for RealFs {
     stat(path).map(Into::into)
   }
   async fn stat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    if let Some(ring) = uring::current() {
+      return Ok(ring.stat(&path, true).await?);
+    }
     spawn_blocking(move || stat(&path)).await?.map(Into::into)
   }
 
@@ -170,6 +315,10 @@ impl FileSystem for RealFs {
     lstat(path).map(Into::into)
   }
   async fn lstat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    if let Some(ring) = uring::current() {
+      return Ok(ring.stat(&path, false).await?);
+    }
     spawn_blocking(move || lstat(&path)).await?.map(Into::into)
   }
 
@@ -189,12 +338,29 @@ impl FileSystem for RealFs {
     spawn_blocking(move || realpath(&path)).await?
   }
 
+  fn statfs_sync(&self, path: &Path) -> FsResult<FsStatfs> {
+    Ok(statfs(path)?)
+  }
+  async fn statfs_async(&self, path: PathBuf) -> FsResult<FsStatfs> {
+    Ok(spawn_blocking(move || statfs(&path)).await??)
+  }
+
   fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
     read_dir(path)
   }
   async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>> {
     spawn_blocking(move || read_dir(&path)).await?
   }
+  fn open_read_dir_sync(&self, path: &Path) -> FsResult<Box<dyn FsReadDir>> {
+    Ok(Box::new(StdReadDir(Some(fs::read_dir(path)?))))
+  }
+  async fn open_read_dir_async(
+    &self,
+    path: PathBuf,
+  ) -> FsResult<Box<dyn FsReadDir>> {
+    let dir = spawn_blocking(move || fs::read_dir(path)).await??;
+    Ok(Box::new(StdReadDir(Some(dir))))
+  }
 
   fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     fs::rename(oldpath, newpath).map_err(Into::into)
@@ -330,6 +496,61 @@ impl FileSystem for RealFs {
     spawn_blocking(move || lchown(&path, uid, gid)).await?
   }
 
+  fn get_xattr_sync(
+    &self,
+    path: &Path,
+    name: &str,
+  ) -> FsResult<Option<Vec<u8>>> {
+    Ok(get_xattr(XattrTarget::Path(path), name)?)
+  }
+  async fn get_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<Option<Vec<u8>>> {
+    spawn_blocking(move || Ok(get_xattr(XattrTarget::Path(&path), &name)?))
+      .await?
+  }
+
+  fn set_xattr_sync(
+    &self,
+    path: &Path,
+    name: &str,
+    value: &[u8],
+  ) -> FsResult<()> {
+    Ok(set_xattr(XattrTarget::Path(path), name, value)?)
+  }
+  async fn set_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+    value: Vec<u8>,
+  ) -> FsResult<()> {
+    spawn_blocking(move || {
+      Ok(set_xattr(XattrTarget::Path(&path), &name, &value)?)
+    })
+    .await?
+  }
+
+  fn list_xattrs_sync(&self, path: &Path) -> FsResult<Vec<String>> {
+    Ok(list_xattrs(XattrTarget::Path(path))?)
+  }
+  async fn list_xattrs_async(&self, path: PathBuf) -> FsResult<Vec<String>> {
+    spawn_blocking(move || Ok(list_xattrs(XattrTarget::Path(&path))?)).await?
+  }
+
+  fn remove_xattr_sync(&self, path: &Path, name: &str) -> FsResult<()> {
+    Ok(remove_xattr(XattrTarget::Path(path), name)?)
+  }
+  async fn remove_xattr_async(
+    &self,
+    path: PathBuf,
+    name: String,
+  ) -> FsResult<()> {
+    spawn_blocking(move || Ok(remove_xattr(XattrTarget::Path(&path), &name)?))
+      .await?
+  }
+
   fn write_file_sync(
     &self,
     path: &Path,
@@ -354,6 +575,17 @@ impl FileSystem for RealFs {
     access_check: Option<AccessCheckCb<'a>>,
     data: Vec<u8>,
   ) -> FsResult<()> {
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    if let Some(ring) = uring::current() {
+      let file =
+        uring_open_with_access_check(&ring, options, &path, access_check)
+          .await?;
+      if let Some(mode) = options.mode {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
+      }
+      return Ok(ring.write_all(file, data).await?);
+    }
     let mut file = open_with_access_check(options, &path, access_check)?;
     spawn_blocking(move || {
       #[cfg(unix)]
@@ -389,6 +621,17 @@ impl FileSystem for RealFs {
     path: PathBuf,
     access_check: Option<AccessCheckCb<'a>>,
   ) -> FsResult<Vec<u8>> {
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    if let Some(ring) = uring::current() {
+      let file = uring_open_with_access_check(
+        &ring,
+        OpenOptions::read(),
+        &path,
+        access_check,
+      )
+      .await?;
+      return Ok(ring.read_to_end(file).await?);
+    }
     let mut file = open_with_access_check(
       OpenOptions {
         read: true,
@@ -516,6 +759,31 @@ fn remove(path: &Path, recursive: bool) -> FsResult<()> {
   res.map_err(Into::into)
 }
 
+/// `_IOW(0x94, 9, int)`, which is not exposed by all supported versions of
+/// libc.
+#[cfg(all(
+  target_os = "linux",
+  any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc64"
+  )
+))]
+const FICLONE: libc::c_ulong = 0x80049409;
+#[cfg(all(
+  target_os = "linux",
+  not(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc64"
+  ))
+))]
+const FICLONE: libc::c_ulong = 0x40049409;
+
 fn copy_file(from: &Path, to: &Path) -> FsResult<()> {
   #[cfg(target_os = "macos")]
   {
@@ -584,11 +852,223 @@ fn copy_file(from: &Path, to: &Path) -> FsResult<()> {
     // clonefile() failed, fall back to std::fs::copy().
   }
 
+  #[cfg(target_os = "linux")]
+  {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    // Try to share the data of `from` with `to` first, which copy-on-write
+    // file systems such as btrfs and XFS support within the same file system.
+    // Otherwise std::fs::copy() uses copy_file_range(), which copies the data
+    // without passing it through user space.
+    let from_file = fs::File::open(from)?;
+    let from_metadata = from_file.metadata()?;
+    if from_metadata.is_file() {
+      let perm = from_metadata.permissions();
+      let to_file = fs::OpenOptions::new()
+        // create the file with the correct mode right away
+        .mode(perm.mode())
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(to)?;
+      // SAFETY: both fds are open for the duration of the call.
+      let ret = unsafe {
+        libc::ioctl(to_file.as_raw_fd(), FICLONE as _, from_file.as_raw_fd())
+      };
+      if ret == 0 {
+        if to_file.metadata()?.is_file() {
+          // Set the correct file permissions, in case the file already existed.
+          to_file.set_permissions(perm)?;
+        }
+        return Ok(());
+      }
+    }
+  }
+
   fs::copy(from, to)?;
 
   Ok(())
 }
 
+/// A name for a file in the same directory as another file, which is only
+/// used while the file is being created.
+fn temp_file_name(dir: &Path) -> PathBuf {
+  dir.join(format!(".deno-tmp-{:016x}", rand::random::<u64>()))
+}
+
+fn anonymous_temp_file(dir: &Path) -> FsResult<fs::File> {
+  #[cfg(target_os = "linux")]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    let res = fs::OpenOptions::new()
+      .read(true)
+      .write(true)
+      .mode(0o600)
+      .custom_flags(libc::O_TMPFILE)
+      .open(dir);
+    match res {
+      Ok(file) => return Ok(file),
+      // The file system or the kernel does not support O_TMPFILE.
+      Err(err)
+        if matches!(
+          err.raw_os_error(),
+          Some(libc::EOPNOTSUPP | libc::EISDIR)
+        ) => {}
+      Err(err) => return Err(err.into()),
+    }
+  }
+
+  // Elsewhere, create a file with a random name and remove the name right
+  // away. On Windows a file cannot be removed while it is open, so it is
+  // removed once it is closed instead.
+  let mut opts = fs::OpenOptions::new();
+  opts.read(true).write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    opts.mode(0o600);
+  }
+  #[cfg(windows)]
+  {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Storage::FileSystem::FILE_FLAG_DELETE_ON_CLOSE;
+    use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_DELETE;
+    use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_READ;
+    use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_WRITE;
+    opts.custom_flags(FILE_FLAG_DELETE_ON_CLOSE);
+    opts.share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE);
+  }
+
+  const MAX_TRIES: u32 = 10;
+  for _ in 0..MAX_TRIES {
+    let path = temp_file_name(dir);
+    match opts.open(&path) {
+      Ok(file) => {
+        #[cfg(unix)]
+        fs::remove_file(&path)?;
+        return Ok(file);
+      }
+      Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+      Err(err) => return Err(err.into()),
+    }
+  }
+  Err(
+    io::Error::new(io::ErrorKind::AlreadyExists, "too many temp files exist")
+      .into(),
+  )
+}
+
+/// Opens a second handle to the file backing `file`, which can be moved to
+/// another thread.
+pub(crate) fn clone_backing_file(file: Rc<dyn File>) -> FsResult<fs::File> {
+  let Some(fd) = file.clone().backing_fd() else {
+    return Err(FsError::NotSupported);
+  };
+  #[cfg(unix)]
+  {
+    use std::os::fd::BorrowedFd;
+    // SAFETY: the fd stays open while `file` is alive.
+    let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+    Ok(fs::File::from(fd.try_clone_to_owned()?))
+  }
+  #[cfg(windows)]
+  {
+    use std::os::windows::io::BorrowedHandle;
+    // SAFETY: the handle stays open while `file` is alive.
+    let handle = unsafe { BorrowedHandle::borrow_raw(fd) };
+    Ok(fs::File::from(handle.try_clone_to_owned()?))
+  }
+}
+
+fn persist_file(file: fs::File, path: &Path) -> FsResult<()> {
+  let dir = match path.parent() {
+    Some(dir) if !dir.as_os_str().is_empty() => dir,
+    _ => Path::new("."),
+  };
+  file.sync_all()?;
+
+  // Give the file a temporary name next to `path` first, so that replacing
+  // `path` is a single rename.
+  let temp_path = temp_file_name(dir);
+  #[cfg(target_os = "linux")]
+  let linked = link_file(&file, &temp_path).is_ok();
+  #[cfg(not(target_os = "linux"))]
+  let linked = false;
+  if !linked {
+    copy_to_new_file(&file, &temp_path)?;
+  }
+  if let Err(err) = fs::rename(&temp_path, path) {
+    let _ = fs::remove_file(&temp_path);
+    return Err(err.into());
+  }
+
+  // Make the rename itself durable.
+  #[cfg(unix)]
+  if let Ok(dir) = fs::File::open(dir) {
+    let _ = dir.sync_all();
+  }
+  Ok(())
+}
+
+/// Gives a name to an open file, which works for files without one that were
+/// created with O_TMPFILE.
+#[cfg(target_os = "linux")]
+fn link_file(file: &fs::File, path: &Path) -> io::Result<()> {
+  use std::ffi::CString;
+  use std::os::fd::AsRawFd;
+
+  let fd_path = CString::new(format!("/proc/self/fd/{}", file.as_raw_fd()))?;
+  let path = CString::new(path.as_os_str().as_encoded_bytes())?;
+  // SAFETY: `fd_path` and `path` are valid C strings.
+  let ret = unsafe {
+    libc::linkat(
+      libc::AT_FDCWD,
+      fd_path.as_ptr(),
+      libc::AT_FDCWD,
+      path.as_ptr(),
+      libc::AT_SYMLINK_FOLLOW,
+    )
+  };
+  if ret != 0 {
+    return Err(io::Error::last_os_error());
+  }
+  Ok(())
+}
+
+/// Copies the contents and permissions of `file` to a new file at `path`.
+fn copy_to_new_file(file: &fs::File, path: &Path) -> FsResult<()> {
+  let mut new_file = fs::OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(path)?;
+  let res = (|| {
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut offset = 0;
+    loop {
+      #[cfg(unix)]
+      let nread = std::os::unix::fs::FileExt::read_at(file, &mut buf, offset)?;
+      // Unlike `read_at`, this moves the cursor of the file.
+      #[cfg(windows)]
+      let nread =
+        std::os::windows::fs::FileExt::seek_read(file, &mut buf, offset)?;
+      if nread == 0 {
+        break;
+      }
+      new_file.write_all(&buf[..nread])?;
+      offset += nread as u64;
+    }
+    new_file.set_permissions(file.metadata()?.permissions())?;
+    new_file.sync_all()
+  })();
+  if let Err(err) = res {
+    let _ = fs::remove_file(path);
+    return Err(err.into());
+  }
+  Ok(())
+}
+
 fn cp(from: &Path, to: &Path) -> FsResult<()> {
   fn cp_(source_meta: fs::Metadata, from: &Path, to: &Path) -> FsResult<()> {
     use rayon::prelude::IntoParallelIterator;
@@ -932,33 +1412,62 @@ fn realpath(path: &Path) -> FsResult<PathBuf> {
   Ok(deno_core::strip_unc_prefix(path.canonicalize()?))
 }
 
-fn read_dir(path: &Path) -> FsResult<Vec<FsDirEntry>> {
-  let entries = fs::read_dir(path)?
-    .filter_map(|entry| {
-      let entry = entry.ok()?;
-      let name = entry.file_name().into_string().ok()?;
-      let metadata = entry.file_type();
-      macro_rules! method_or_false {
-        ($method:ident) => {
-          if let Ok(metadata) = &metadata {
-            metadata.$method()
-          } else {
-            false
-          }
-        };
+fn dir_entry(entry: io::Result<fs::DirEntry>) -> Option<FsDirEntry> {
+  let entry = entry.ok()?;
+  let name = entry.file_name().into_string().ok()?;
+  let metadata = entry.file_type();
+  macro_rules! method_or_false {
+    ($method:ident) => {
+      if let Ok(metadata) = &metadata {
+        metadata.$method()
+      } else {
+        false
       }
-      Some(FsDirEntry {
-        name,
-        is_file: method_or_false!(is_file),
-        is_directory: method_or_false!(is_dir),
-        is_symlink: method_or_false!(is_symlink),
-      })
-    })
-    .collect();
+    };
+  }
+  Some(FsDirEntry {
+    name,
+    is_file: method_or_false!(is_file),
+    is_directory: method_or_false!(is_dir),
+    is_symlink: method_or_false!(is_symlink),
+  })
+}
 
+fn read_dir(path: &Path) -> FsResult<Vec<FsDirEntry>> {
+  let entries = fs::read_dir(path)?.filter_map(dir_entry).collect();
   Ok(entries)
 }
 
+/// A directory being read by `RealFs`. The iterator is taken out while a
+/// batch is read on the blocking thread pool.
+struct StdReadDir(Option<fs::ReadDir>);
+
+fn read_dir_batch(dir: &mut fs::ReadDir, max: usize) -> Vec<FsDirEntry> {
+  dir.filter_map(dir_entry).take(max).collect()
+}
+
+#[async_trait::async_trait(?Send)]
+impl FsReadDir for StdReadDir {
+  fn next_batch_sync(&mut self, max: usize) -> FsResult<Vec<FsDirEntry>> {
+    let dir = self.0.as_mut().ok_or(FsError::FileBusy)?;
+    Ok(read_dir_batch(dir, max))
+  }
+
+  async fn next_batch_async(
+    &mut self,
+    max: usize,
+  ) -> FsResult<Vec<FsDirEntry>> {
+    let mut dir = self.0.take().ok_or(FsError::FileBusy)?;
+    let (dir, entries) = spawn_blocking(move || {
+      let entries = read_dir_batch(&mut dir, max);
+      (dir, entries)
+    })
+    .await?;
+    self.0 = Some(dir);
+    Ok(entries)
+  }
+}
+
 #[cfg(not(windows))]
 fn symlink(
   oldpath: &Path,
@@ -1046,6 +1555,59 @@ fn open_options(options: OpenOptions) -> fs::OpenOptions {
   open_options
 }
 
+fn absolute_path(path: &Path) -> FsResult<PathBuf> {
+  if path.is_absolute() {
+    Ok(normalize_path(path))
+  } else {
+    Ok(normalize_path(current_dir()?.join(path)))
+  }
+}
+
+/// Runs the access checks for opening `path` with `options`. Returns the path
+/// to open, and whether a symlink at the end of it must not be followed.
+fn check_open_access(
+  options: OpenOptions,
+  path: &Path,
+  access_check: AccessCheckCb,
+) -> FsResult<(PathBuf, bool)> {
+  let path_bytes = path.as_os_str().as_encoded_bytes();
+  let is_windows_device_path = cfg!(windows)
+    && path_bytes.starts_with(br"\\.\")
+    && !path_bytes.contains(&b':');
+  let path = if is_windows_device_path {
+    // On Windows, normalize_path doesn't work with device-prefix-style
+    // paths. We pass these through.
+    path.to_owned()
+  } else if path.is_absolute() {
+    normalize_path(path)
+  } else {
+    let cwd = current_dir()?;
+    normalize_path(cwd.join(path))
+  };
+  (*access_check)(false, &path, &options)?;
+  // On Linux, /proc may contain magic links that we don't want to resolve
+  let needs_canonicalization = !is_windows_device_path
+    && (!cfg!(target_os = "linux") || path.starts_with("/proc"));
+  let path = if needs_canonicalization {
+    match path.canonicalize() {
+      Ok(path) => path,
+      Err(_) => {
+        if let (Some(parent), Some(filename)) =
+          (path.parent(), path.file_name())
+        {
+          parent.canonicalize()?.join(filename)
+        } else {
+          return Err(std::io::ErrorKind::NotFound.into());
+        }
+      }
+    }
+  } else {
+    path
+  };
+  (*access_check)(true, &path, &options)?;
+  Ok((path, needs_canonicalization))
+}
+
 #[inline(always)]
 fn open_with_access_check(
   options: OpenOptions,
@@ -1053,41 +1615,7 @@ fn open_with_access_check(
   access_check: Option<AccessCheckCb>,
 ) -> FsResult<std::fs::File> {
   if let Some(access_check) = access_check {
-    let path_bytes = path.as_os_str().as_encoded_bytes();
-    let is_windows_device_path = cfg!(windows)
-      && path_bytes.starts_with(br"\\.\")
-      && !path_bytes.contains(&b':');
-    let path = if is_windows_device_path {
-      // On Windows, normalize_path doesn't work with device-prefix-style
-      // paths. We pass these through.
-      path.to_owned()
-    } else if path.is_absolute() {
-      normalize_path(path)
-    } else {
-      let cwd = current_dir()?;
-      normalize_path(cwd.join(path))
-    };
-    (*access_check)(false, &path, &options)?;
-    // On Linux, /proc may contain magic links that we don't want to resolve
-    let needs_canonicalization = !is_windows_device_path
-      && (!cfg!(target_os = "linux") || path.starts_with("/proc"));
-    let path = if needs_canonicalization {
-      match path.canonicalize() {
-        Ok(path) => path,
-        Err(_) => {
-          if let (Some(parent), Some(filename)) =
-            (path.parent(), path.file_name())
-          {
-            parent.canonicalize()?.join(filename)
-          } else {
-            return Err(std::io::ErrorKind::NotFound.into());
-          }
-        }
-      }
-    } else {
-      path
-    };
-    (*access_check)(true, &path, &options)?;
+    let (path, no_follow) = check_open_access(options, path, access_check)?;
 
     let mut opts: fs::OpenOptions = open_options(options);
     #[cfg(windows)]
@@ -1103,10 +1631,12 @@ fn open_with_access_check(
       // with the exception of /proc/ which is too special, and /dev/std* which might point to
       // proc.
       use std::os::unix::fs::OpenOptionsExt;
-      if needs_canonicalization {
+      if no_follow {
         opts.custom_flags(libc::O_NOFOLLOW);
       }
     }
+    #[cfg(not(unix))]
+    let _ = no_follow;
 
     Ok(opts.open(&path)?)
   } else {
@@ -1122,3 +1652,17 @@ fn open_with_access_check(
     Ok(opts.open(path)?)
   }
 }
+
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+async fn uring_open_with_access_check(
+  ring: &Rc<uring::Uring>,
+  options: OpenOptions,
+  path: &Path,
+  access_check: Option<AccessCheckCb<'_>>,
+) -> FsResult<std::fs::File> {
+  let (path, no_follow) = match access_check {
+    Some(access_check) => check_open_access(options, path, access_check)?,
+    None => (path.to_owned(), false),
+  };
+  Ok(ring.open(&path, options, no_follow).await?)
+}
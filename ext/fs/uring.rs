@@ -0,0 +1,505 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! An experimental io_uring backend for the async operations of `RealFs` on
+//! Linux. Opening, reading, writing and stat-ing files is submitted to a ring
+//! that is driven by the event loop of the current thread, instead of being
+//! sent to the blocking thread pool.
+//!
+//! The backend is only compiled with the `io_uring` feature, and only used
+//! once enabled with `set_io_uring_enabled`, which the CLI does for
+//! `--unstable-io-uring`. If the kernel doesn't support the operations, or
+//! io_uring is disabled by a seccomp filter, the thread pool is used as
+//! before.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs::File;
+use std::future::Future;
+use std::io;
+use std::marker::PhantomData;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::RawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::rc::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+
+use deno_core::unsync::spawn;
+use deno_io::fs::FsStat;
+use io_uring::opcode;
+use io_uring::squeue;
+use io_uring::types;
+use io_uring::IoUring;
+use io_uring::Probe;
+use tokio::io::unix::AsyncFd;
+
+use crate::OpenOptions;
+
+const RING_ENTRIES: u32 = 256;
+
+/// Reads of files of unknown size, e.g. in `/proc`, start with this buffer.
+const MIN_READ_BUFFER: usize = 8 * 1024;
+
+/// Reads and writes are split into chunks of at most this many bytes, as the
+/// kernel takes the length as a 32-bit number.
+const MAX_CHUNK: usize = 1 << 30;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+  ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+thread_local! {
+  /// The ring of the current thread. The only strong reference is held by the
+  /// task that drives it, so a new ring is created once the event loop that
+  /// the previous one was registered with is gone.
+  static RING: RefCell<Weak<Uring>> = const { RefCell::new(Weak::new()) };
+  /// Set once creating a ring failed, so it isn't attempted on every call.
+  static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns the ring of the current thread, or `None` if io_uring is not
+/// enabled or not available.
+pub fn current() -> Option<Rc<Uring>> {
+  if !ENABLED.load(Ordering::Relaxed) || UNAVAILABLE.with(Cell::get) {
+    return None;
+  }
+  RING.with(|current| {
+    if let Some(ring) = current.borrow().upgrade() {
+      return Some(ring);
+    }
+    match Uring::new() {
+      Ok(ring) => {
+        let ring = Rc::new(ring);
+        *current.borrow_mut() = Rc::downgrade(&ring);
+        spawn(drive(ring.clone()));
+        Some(ring)
+      }
+      Err(_) => {
+        UNAVAILABLE.with(|unavailable| unavailable.set(true));
+        None
+      }
+    }
+  })
+}
+
+/// Reaps completions whenever the kernel signals that there are some.
+async fn drive(ring: Rc<Uring>) {
+  loop {
+    let Ok(mut guard) = ring.fd.readable().await else {
+      return;
+    };
+    guard.clear_ready();
+    ring.reap();
+  }
+}
+
+struct Op {
+  result: Option<i32>,
+  waker: Option<Waker>,
+  /// Everything the kernel may access until the operation completes.
+  resources: Box<dyn Any>,
+  /// The future waiting for the operation was dropped.
+  abandoned: bool,
+  /// The operation returns a file descriptor, which must be closed if the
+  /// operation was abandoned.
+  returns_fd: bool,
+}
+
+pub struct Uring {
+  ring: RefCell<IoUring>,
+  fd: AsyncFd<RawFd>,
+  ops: RefCell<HashMap<u64, Op>>,
+  next_id: Cell<u64>,
+}
+
+impl Uring {
+  fn new() -> io::Result<Self> {
+    let ring = IoUring::new(RING_ENTRIES)?;
+    // Reads and writes at the current file position need Linux 5.6, which is
+    // also when the other operations were added.
+    if !ring.params().is_feature_rw_cur_pos() {
+      return Err(io::ErrorKind::Unsupported.into());
+    }
+    let mut probe = Probe::new();
+    ring.submitter().register_probe(&mut probe)?;
+    for code in [
+      opcode::OpenAt::CODE,
+      opcode::Statx::CODE,
+      opcode::Read::CODE,
+      opcode::Write::CODE,
+    ] {
+      if !probe.is_supported(code) {
+        return Err(io::ErrorKind::Unsupported.into());
+      }
+    }
+    let fd = AsyncFd::new(ring.as_raw_fd())?;
+    Ok(Self {
+      ring: RefCell::new(ring),
+      fd,
+      ops: RefCell::new(HashMap::new()),
+      next_id: Cell::new(0),
+    })
+  }
+
+  /// Submits `entry`. The kernel may access the memory `entry` points to
+  /// until the operation completes, so it must be owned by `resources`.
+  fn submit<T: 'static>(
+    self: &Rc<Self>,
+    entry: squeue::Entry,
+    resources: T,
+    returns_fd: bool,
+  ) -> io::Result<Completion<T>> {
+    let id = self.next_id.get();
+    self.next_id.set(id.wrapping_add(1));
+    let entry = entry.user_data(id);
+
+    let mut ring = self.ring.borrow_mut();
+    // SAFETY: the memory the entry points to is kept alive by `resources`,
+    // which is stored with the operation until it completes.
+    while unsafe { ring.submission().push(&entry) }.is_err() {
+      ring.submit()?;
+    }
+    self.ops.borrow_mut().insert(
+      id,
+      Op {
+        result: None,
+        waker: None,
+        resources: Box::new(resources),
+        abandoned: false,
+        returns_fd,
+      },
+    );
+    let completion = Completion {
+      ring: self.clone(),
+      id,
+      _resources: PhantomData,
+    };
+    // If this fails, the entry stays queued and is submitted with the next
+    // one. Dropping the completion makes sure its resources are kept alive
+    // until then.
+    ring.submit()?;
+    Ok(completion)
+  }
+
+  fn reap(&self) {
+    let mut ring = self.ring.borrow_mut();
+    let mut ops = self.ops.borrow_mut();
+    for cqe in ring.completion() {
+      let id = cqe.user_data();
+      let Some(op) = ops.get_mut(&id) else {
+        continue;
+      };
+      if op.abandoned {
+        if op.returns_fd && cqe.result() >= 0 {
+          // SAFETY: the file descriptor was opened for the abandoned
+          // operation and is owned by nothing else.
+          unsafe { libc::close(cqe.result()) };
+        }
+        ops.remove(&id);
+        continue;
+      }
+      op.result = Some(cqe.result());
+      if let Some(waker) = op.waker.take() {
+        waker.wake();
+      }
+    }
+  }
+
+  /// Opens the file at `path` with `options`, not following a symlink at the
+  /// end of the path if `no_follow` is true.
+  pub async fn open(
+    self: &Rc<Self>,
+    path: &Path,
+    options: OpenOptions,
+    no_follow: bool,
+  ) -> io::Result<File> {
+    let path = c_path(path)?;
+    let (mut flags, mode) = options.to_flags_and_mode();
+    flags |= libc::O_CLOEXEC;
+    if no_follow {
+      flags |= libc::O_NOFOLLOW;
+    }
+    let entry = opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), path.as_ptr())
+      .flags(flags)
+      .mode(mode)
+      .build();
+    let (res, _) = self.submit(entry, path, true)?.await;
+    // SAFETY: the file descriptor was just opened and is owned by nothing
+    // else.
+    Ok(unsafe { File::from_raw_fd(res? as RawFd) })
+  }
+
+  /// Returns information about the file at `path`, or about the symlink
+  /// itself if `follow_symlinks` is false.
+  pub async fn stat(
+    self: &Rc<Self>,
+    path: &Path,
+    follow_symlinks: bool,
+  ) -> io::Result<FsStat> {
+    let flags = if follow_symlinks {
+      0
+    } else {
+      libc::AT_SYMLINK_NOFOLLOW
+    };
+    self.statx(libc::AT_FDCWD, c_path(path)?, flags, ()).await
+  }
+
+  async fn statx<T: 'static>(
+    self: &Rc<Self>,
+    dirfd: RawFd,
+    path: CString,
+    flags: i32,
+    keep_alive: T,
+  ) -> io::Result<FsStat> {
+    // SAFETY: `statx` is plain data, for which all zeroes is a valid value.
+    let mut buf = Box::new(unsafe { std::mem::zeroed::<libc::statx>() });
+    let entry = opcode::Statx::new(
+      types::Fd(dirfd),
+      path.as_ptr(),
+      (&mut *buf as *mut libc::statx).cast(),
+    )
+    .flags(flags)
+    .mask(libc::STATX_BASIC_STATS | libc::STATX_BTIME)
+    .build();
+    let (res, (_, buf, _)) =
+      self.submit(entry, (path, buf, keep_alive), false)?.await;
+    res?;
+    Ok(fs_stat(&buf))
+  }
+
+  /// Reads the rest of `file` from its current position.
+  pub async fn read_to_end(self: &Rc<Self>, file: File) -> io::Result<Vec<u8>> {
+    let fd = file.as_raw_fd();
+    let file = Rc::new(file);
+    // The size is only a hint, files in e.g. `/proc` report a size of 0.
+    let size = self
+      .statx(fd, CString::default(), libc::AT_EMPTY_PATH, file.clone())
+      .await
+      .map(|stat| stat.size as usize)
+      .unwrap_or(0);
+    let mut buf = Vec::<u8>::with_capacity(size.max(MIN_READ_BUFFER));
+    loop {
+      if buf.len() == buf.capacity() {
+        buf.reserve(buf.capacity());
+      }
+      let len = (buf.capacity() - buf.len()).min(MAX_CHUNK);
+      // SAFETY: the pointer is within the allocation of `buf`, which is not
+      // reallocated until the read completes.
+      let ptr = unsafe { buf.as_mut_ptr().add(buf.len()) };
+      let entry = opcode::Read::new(types::Fd(fd), ptr, len as u32)
+        .offset(u64::MAX)
+        .build();
+      let (res, (_, returned)) =
+        self.submit(entry, (file.clone(), buf), false)?.await;
+      buf = returned;
+      match res {
+        Ok(0) => return Ok(buf),
+        // SAFETY: the kernel initialized `n` bytes after the previous end.
+        Ok(n) => unsafe { buf.set_len(buf.len() + n as usize) },
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Err(err) => return Err(err),
+      }
+    }
+  }
+
+  /// Writes all of `data` to `file` at its current position.
+  pub async fn write_all(
+    self: &Rc<Self>,
+    file: File,
+    data: Vec<u8>,
+  ) -> io::Result<()> {
+    let fd = file.as_raw_fd();
+    let mut resources = (file, data);
+    let mut written = 0;
+    while written < resources.1.len() {
+      let len = (resources.1.len() - written).min(MAX_CHUNK);
+      // SAFETY: the pointer is within the bounds of the data.
+      let ptr = unsafe { resources.1.as_ptr().add(written) };
+      let entry = opcode::Write::new(types::Fd(fd), ptr, len as u32)
+        .offset(u64::MAX)
+        .build();
+      let (res, returned) = self.submit(entry, resources, false)?.await;
+      resources = returned;
+      match res {
+        Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+        Ok(n) => written += n as usize,
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Err(err) => return Err(err),
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Drop for Uring {
+  fn drop(&mut self) {
+    // Closing the ring doesn't wait for operations that are still running,
+    // so the memory they access must never be freed.
+    for (_, op) in self.ops.get_mut().drain() {
+      if op.result.is_none() {
+        std::mem::forget(op.resources);
+      }
+    }
+  }
+}
+
+/// Resolves to the result of an operation and the resources that were
+/// submitted with it.
+struct Completion<T: 'static> {
+  ring: Rc<Uring>,
+  id: u64,
+  _resources: PhantomData<T>,
+}
+
+impl<T: 'static> Future for Completion<T> {
+  type Output = (io::Result<u32>, T);
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let mut ops = self.ring.ops.borrow_mut();
+    let op = ops.get_mut(&self.id).unwrap();
+    let Some(res) = op.result else {
+      op.waker = Some(cx.waker().clone());
+      return Poll::Pending;
+    };
+    let op = ops.remove(&self.id).unwrap();
+    let res = if res < 0 {
+      Err(io::Error::from_raw_os_error(-res))
+    } else {
+      Ok(res as u32)
+    };
+    Poll::Ready((res, *op.resources.downcast::<T>().unwrap()))
+  }
+}
+
+impl<T: 'static> Drop for Completion<T> {
+  fn drop(&mut self) {
+    let mut ops = self.ring.ops.borrow_mut();
+    let Some(op) = ops.get_mut(&self.id) else {
+      return;
+    };
+    match op.result {
+      Some(res) => {
+        if op.returns_fd && res >= 0 {
+          // SAFETY: the file descriptor was opened for this operation and
+          // nobody took ownership of it.
+          unsafe { libc::close(res) };
+        }
+        ops.remove(&self.id);
+      }
+      None => op.abandoned = true,
+    }
+  }
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+  CString::new(path.as_os_str().as_bytes())
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn fs_stat(stat: &libc::statx) -> FsStat {
+  fn to_msec(time: &libc::statx_timestamp) -> Option<u64> {
+    let msecs = time.tv_sec as i128 * 1000 + time.tv_nsec as i128 / 1_000_000;
+    // Times before the epoch are reported as their distance from it, like
+    // `FsStat::from_std` does.
+    Some(msecs.unsigned_abs() as u64)
+  }
+
+  let birthtime = if stat.stx_mask & libc::STATX_BTIME != 0 {
+    to_msec(&stat.stx_btime)
+  } else {
+    None
+  };
+  let file_type = stat.stx_mode as u32 & libc::S_IFMT;
+  FsStat {
+    is_file: file_type == libc::S_IFREG,
+    is_directory: file_type == libc::S_IFDIR,
+    is_symlink: file_type == libc::S_IFLNK,
+    size: stat.stx_size,
+
+    mtime: to_msec(&stat.stx_mtime),
+    atime: to_msec(&stat.stx_atime),
+    birthtime,
+
+    dev: libc::makedev(stat.stx_dev_major, stat.stx_dev_minor) as u64,
+    ino: stat.stx_ino,
+    mode: stat.stx_mode as u32,
+    nlink: stat.stx_nlink as u64,
+    uid: stat.stx_uid,
+    gid: stat.stx_gid,
+    rdev: libc::makedev(stat.stx_rdev_major, stat.stx_rdev_minor) as u64,
+    blksize: stat.stx_blksize as u64,
+    blocks: stat.stx_blocks,
+    is_block_device: file_type == libc::S_IFBLK,
+    is_char_device: file_type == libc::S_IFCHR,
+    is_fifo: file_type == libc::S_IFIFO,
+    is_socket: file_type == libc::S_IFSOCK,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::FileSystem;
+  use crate::RealFs;
+
+  #[tokio::test(flavor = "current_thread")]
+  async fn real_fs_async_operations() {
+    set_enabled(true);
+    if current().is_none() {
+      // io_uring isn't available, ex. it's disabled by a seccomp filter
+      return;
+    }
+    let dir = RealFs
+      .tmp_dir()
+      .unwrap()
+      .join(format!("deno_uring_{}", std::process::id()));
+    let _ = RealFs.remove_sync(&dir, true);
+    RealFs.mkdir_sync(&dir, true, 0o755).unwrap();
+    let path = dir.join("a.txt");
+
+    RealFs
+      .write_file_async(
+        path.clone(),
+        OpenOptions::write(true, false, false, None),
+        None,
+        b"hello".to_vec(),
+      )
+      .await
+      .unwrap();
+    assert_eq!(
+      RealFs.read_file_async(path.clone(), None).await.unwrap(),
+      b"hello"
+    );
+    let stat = RealFs.stat_async(path.clone()).await.unwrap();
+    assert!(stat.is_file);
+    assert_eq!(stat.size, 5);
+    assert!(RealFs.lstat_async(dir.clone()).await.unwrap().is_directory);
+    assert!(matches!(
+      RealFs.stat_async(dir.join("missing")).await,
+      Err(err) if err.kind() == io::ErrorKind::NotFound
+    ));
+    assert!(RealFs
+      .open_async(
+        dir.join("a.txt"),
+        OpenOptions::write(false, false, true, None),
+        None
+      )
+      .await
+      .is_err());
+
+    RealFs.remove_sync(&dir, true).unwrap();
+  }
+}
@@ -0,0 +1,325 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Extended attributes of files, on the platforms that support them.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// What to read or write the extended attributes of.
+#[derive(Clone, Copy)]
+pub enum XattrTarget<'a> {
+  /// The file at a path, following symlinks.
+  Path(&'a Path),
+  /// An open file.
+  File(&'a File),
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+mod sys {
+  use std::ffi::CString;
+  use std::io;
+  use std::os::fd::AsRawFd;
+
+  use super::XattrTarget;
+
+  #[cfg(not(target_os = "macos"))]
+  use libc::fgetxattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::flistxattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::fremovexattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::fsetxattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::getxattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::listxattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::removexattr;
+  #[cfg(not(target_os = "macos"))]
+  use libc::setxattr;
+
+  #[cfg(target_os = "macos")]
+  use macos::*;
+
+  /// The Linux signatures of the xattr functions. On macOS they take an
+  /// additional offset into resource forks, and options, where 0 follows
+  /// symlinks like the Linux functions do.
+  #[cfg(target_os = "macos")]
+  mod macos {
+    use libc::c_char;
+    use libc::c_int;
+    use libc::c_void;
+    use libc::size_t;
+    use libc::ssize_t;
+
+    pub unsafe fn getxattr(
+      path: *const c_char,
+      name: *const c_char,
+      value: *mut c_void,
+      size: size_t,
+    ) -> ssize_t {
+      libc::getxattr(path, name, value, size, 0, 0)
+    }
+
+    pub unsafe fn fgetxattr(
+      fd: c_int,
+      name: *const c_char,
+      value: *mut c_void,
+      size: size_t,
+    ) -> ssize_t {
+      libc::fgetxattr(fd, name, value, size, 0, 0)
+    }
+
+    pub unsafe fn setxattr(
+      path: *const c_char,
+      name: *const c_char,
+      value: *const c_void,
+      size: size_t,
+      flags: c_int,
+    ) -> c_int {
+      libc::setxattr(path, name, value, size, 0, flags)
+    }
+
+    pub unsafe fn fsetxattr(
+      fd: c_int,
+      name: *const c_char,
+      value: *const c_void,
+      size: size_t,
+      flags: c_int,
+    ) -> c_int {
+      libc::fsetxattr(fd, name, value, size, 0, flags)
+    }
+
+    pub unsafe fn listxattr(
+      path: *const c_char,
+      list: *mut c_char,
+      size: size_t,
+    ) -> ssize_t {
+      libc::listxattr(path, list, size, 0)
+    }
+
+    pub unsafe fn flistxattr(
+      fd: c_int,
+      list: *mut c_char,
+      size: size_t,
+    ) -> ssize_t {
+      libc::flistxattr(fd, list, size, 0)
+    }
+
+    pub unsafe fn removexattr(
+      path: *const c_char,
+      name: *const c_char,
+    ) -> c_int {
+      libc::removexattr(path, name, 0)
+    }
+
+    pub unsafe fn fremovexattr(fd: c_int, name: *const c_char) -> c_int {
+      libc::fremovexattr(fd, name, 0)
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  const ENOATTR: i32 = libc::ENOATTR;
+  #[cfg(not(target_os = "macos"))]
+  const ENOATTR: i32 = libc::ENODATA;
+
+  fn c_string(value: &[u8]) -> io::Result<CString> {
+    CString::new(value)
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+  }
+
+  fn path_c_string(path: &std::path::Path) -> io::Result<CString> {
+    c_string(path.as_os_str().as_encoded_bytes())
+  }
+
+  fn result(ret: libc::ssize_t) -> io::Result<usize> {
+    if ret < 0 {
+      Err(io::Error::last_os_error())
+    } else {
+      Ok(ret as usize)
+    }
+  }
+
+  /// Calls `f` with a buffer that is large enough for the value. `f` is
+  /// called without a buffer first to get the size of the value.
+  fn read_value(
+    f: impl Fn(*mut libc::c_void, usize) -> libc::ssize_t,
+  ) -> io::Result<Vec<u8>> {
+    loop {
+      let len = result(f(std::ptr::null_mut(), 0))?;
+      let mut buf = vec![0u8; len];
+      match result(f(buf.as_mut_ptr().cast(), buf.len())) {
+        Ok(len) => {
+          buf.truncate(len);
+          return Ok(buf);
+        }
+        // The value grew in the meantime.
+        Err(err) if err.raw_os_error() == Some(libc::ERANGE) => continue,
+        Err(err) => return Err(err),
+      }
+    }
+  }
+
+  pub fn get(target: XattrTarget, name: &str) -> io::Result<Option<Vec<u8>>> {
+    let name = c_string(name.as_bytes())?;
+    let res = match target {
+      XattrTarget::Path(path) => {
+        let path = path_c_string(path)?;
+        // SAFETY: `path` and `name` are valid C strings, and the buffer is
+        // either null or valid for writes of its length.
+        read_value(|buf, len| unsafe {
+          getxattr(path.as_ptr(), name.as_ptr(), buf, len)
+        })
+      }
+      XattrTarget::File(file) => {
+        let fd = file.as_raw_fd();
+        // SAFETY: `fd` is open, `name` is a valid C string, and the buffer
+        // is either null or valid for writes of its length.
+        read_value(|buf, len| unsafe { fgetxattr(fd, name.as_ptr(), buf, len) })
+      }
+    };
+    match res {
+      Ok(value) => Ok(Some(value)),
+      Err(err) if err.raw_os_error() == Some(ENOATTR) => Ok(None),
+      Err(err) => Err(err),
+    }
+  }
+
+  pub fn set(target: XattrTarget, name: &str, value: &[u8]) -> io::Result<()> {
+    let name = c_string(name.as_bytes())?;
+    let buf = value.as_ptr().cast();
+    let ret = match target {
+      XattrTarget::Path(path) => {
+        let path = path_c_string(path)?;
+        // SAFETY: `path` and `name` are valid C strings, and `buf` is valid
+        // for reads of `value.len()` bytes.
+        unsafe { setxattr(path.as_ptr(), name.as_ptr(), buf, value.len(), 0) }
+      }
+      // SAFETY: the fd is open, `name` is a valid C string, and `buf` is
+      // valid for reads of `value.len()` bytes.
+      XattrTarget::File(file) => unsafe {
+        fsetxattr(file.as_raw_fd(), name.as_ptr(), buf, value.len(), 0)
+      },
+    };
+    result(ret as libc::ssize_t).map(|_| ())
+  }
+
+  pub fn list(target: XattrTarget) -> io::Result<Vec<String>> {
+    let names = match target {
+      XattrTarget::Path(path) => {
+        let path = path_c_string(path)?;
+        // SAFETY: `path` is a valid C string, and the buffer is either null
+        // or valid for writes of its length.
+        read_value(|buf, len| unsafe {
+          listxattr(path.as_ptr(), buf.cast(), len)
+        })?
+      }
+      XattrTarget::File(file) => {
+        let fd = file.as_raw_fd();
+        // SAFETY: `fd` is open, and the buffer is either null or valid for
+        // writes of its length.
+        read_value(|buf, len| unsafe { flistxattr(fd, buf.cast(), len) })?
+      }
+    };
+    // Each name is followed by a nul byte.
+    Ok(
+      names
+        .split(|b| *b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| String::from_utf8_lossy(name).into_owned())
+        .collect(),
+    )
+  }
+
+  pub fn remove(target: XattrTarget, name: &str) -> io::Result<()> {
+    let name = c_string(name.as_bytes())?;
+    let ret = match target {
+      XattrTarget::Path(path) => {
+        let path = path_c_string(path)?;
+        // SAFETY: `path` and `name` are valid C strings.
+        unsafe { removexattr(path.as_ptr(), name.as_ptr()) }
+      }
+      // SAFETY: the fd is open and `name` is a valid C string.
+      XattrTarget::File(file) => unsafe {
+        fremovexattr(file.as_raw_fd(), name.as_ptr())
+      },
+    };
+    match result(ret as libc::ssize_t) {
+      Ok(_) => Ok(()),
+      Err(err) if err.raw_os_error() == Some(ENOATTR) => Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "extended attribute not found",
+      )),
+      Err(err) => Err(err),
+    }
+  }
+}
+
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "android",
+  target_os = "macos"
+)))]
+mod sys {
+  use std::io;
+
+  use super::XattrTarget;
+
+  fn unsupported() -> io::Error {
+    io::Error::new(
+      io::ErrorKind::Unsupported,
+      "extended attributes are not supported on this platform",
+    )
+  }
+
+  pub fn get(_target: XattrTarget, _name: &str) -> io::Result<Option<Vec<u8>>> {
+    Err(unsupported())
+  }
+
+  pub fn set(
+    _target: XattrTarget,
+    _name: &str,
+    _value: &[u8],
+  ) -> io::Result<()> {
+    Err(unsupported())
+  }
+
+  pub fn list(_target: XattrTarget) -> io::Result<Vec<String>> {
+    Err(unsupported())
+  }
+
+  pub fn remove(_target: XattrTarget, _name: &str) -> io::Result<()> {
+    Err(unsupported())
+  }
+}
+
+/// Returns the value of the extended attribute `name`, or `None` if it is not
+/// set.
+pub fn get_xattr(
+  target: XattrTarget,
+  name: &str,
+) -> io::Result<Option<Vec<u8>>> {
+  sys::get(target, name)
+}
+
+/// Sets the extended attribute `name`, replacing any previous value.
+pub fn set_xattr(
+  target: XattrTarget,
+  name: &str,
+  value: &[u8],
+) -> io::Result<()> {
+  sys::set(target, name, value)
+}
+
+/// Returns the names of the extended attributes that are set.
+pub fn list_xattrs(target: XattrTarget) -> io::Result<Vec<String>> {
+  sys::list(target)
+}
+
+/// Removes the extended attribute `name`, failing with `NotFound` if it is not
+/// set.
+pub fn remove_xattr(target: XattrTarget, name: &str) -> io::Result<()> {
+  sys::remove(target, name)
+}
@@ -11,8 +11,10 @@ import {
   op_http_cancel,
   op_http_close,
   op_http_close_after_finish,
+  op_http_get_request_connection,
   op_http_get_request_headers,
   op_http_get_request_method_and_url,
+  op_http_get_request_priority,
   op_http_read_request_body,
   op_http_serve,
   op_http_serve_on,
@@ -27,11 +29,17 @@ import {
   op_http_upgrade_raw,
   op_http_upgrade_websocket_next,
   op_http_wait,
+  op_http_wait_connection_closed,
 } from "ext:core/ops";
 const {
   ArrayPrototypePush,
+  MathCeil,
+  NumberIsFinite,
+  NumberIsInteger,
+  NumberIsSafeInteger,
   ObjectHasOwn,
   ObjectPrototypeIsPrototypeOf,
+  PromisePrototype,
   PromisePrototypeCatch,
   PromisePrototypeThen,
   StringPrototypeIncludes,
@@ -43,6 +51,7 @@ const {
 } = primordials;
 
 import { InnerBody } from "ext:deno_fetch/22_body.js";
+import { headerListFromHeaders, Headers } from "ext:deno_fetch/20_headers.js";
 import { Event } from "ext:deno_web/02_event.js";
 import {
   fromInnerResponse,
@@ -75,6 +84,7 @@ import {
   readableStreamForRid,
   ReadableStreamPrototype,
   resourceForReadableStream,
+  TransformStream,
 } from "ext:deno_web/06_streams.js";
 import { listen, listenOptionApiName, TcpConn } from "ext:deno_net/01_net.js";
 import { hasTlsKeyPairOptions, listenTls } from "ext:deno_net/02_tls.js";
@@ -131,6 +141,24 @@ function addTrailers(resp, headerList) {
   op_http_set_response_trailers(inner.external, headerList);
 }
 
+function trailerList(trailers) {
+  return headerListFromHeaders(new Headers(trailers));
+}
+
+/**
+ * Wraps a response body stream so that trailers which are only known once the
+ * body has been produced are handed to the connection before the body ends.
+ */
+function streamWithTrailers(req, stream, trailers) {
+  return stream.pipeThrough(
+    new TransformStream({
+      async flush() {
+        op_http_set_response_trailers(req, trailerList(await trailers));
+      },
+    }),
+  );
+}
+
 class InnerRequest {
   #external;
   #context;
@@ -305,6 +333,30 @@ class InnerRequest {
     return this.#completed.promise;
   }
 
+  get priority() {
+    if (this.#external === null) {
+      throw new TypeError("request closed");
+    }
+    const { 0: urgency, 1: incremental } = op_http_get_request_priority(
+      this.#external,
+    );
+    return { urgency, incremental };
+  }
+
+  get connection() {
+    if (this.#external === null) {
+      throw new TypeError("request closed");
+    }
+    const { 0: id, 1: tls, 2: alpnProtocol } = op_http_get_request_connection(
+      this.#external,
+    );
+    return {
+      id,
+      remoteAddr: this.remoteAddr,
+      tls: tls ? { alpnProtocol } : null,
+    };
+  }
+
   get remoteAddr() {
     const transport = this.#context.listener?.addr.transport;
     if (transport === "unix" || transport === "unixpacket") {
@@ -419,6 +471,12 @@ class ServeHandlerInfo {
   get completed() {
     return this.#inner.completed;
   }
+  get priority() {
+    return this.#inner.priority;
+  }
+  get connection() {
+    return this.#inner.connection;
+  }
 }
 
 function fastSyncResponseOrStream(
@@ -489,10 +547,11 @@ function mapToCallback(context, callback, onError) {
     // Get the response from the user-provided callback. If that fails, use onError. If that fails, return a fallback
     // 500 error.
     let innerRequest;
+    let request;
     let response;
     try {
       innerRequest = new InnerRequest(req, context);
-      const request = fromInnerRequest(innerRequest, "immutable");
+      request = fromInnerRequest(innerRequest, "immutable");
       innerRequest.request = request;
       response = await callback(
         request,
@@ -507,7 +566,7 @@ function mapToCallback(context, callback, onError) {
       }
     } catch (error) {
       try {
-        response = await onError(error);
+        response = await onError(error, request);
         if (!ObjectPrototypeIsPrototypeOf(ResponsePrototype, response)) {
           throw TypeError(
             "Return value from onError handler must be a response or a promise resolving to a response",
@@ -547,7 +606,34 @@ function mapToCallback(context, callback, onError) {
       }
     }
 
-    fastSyncResponseOrStream(req, inner.body, status, innerRequest);
+    let body = inner.body;
+    const trailers = inner.trailers;
+    if (trailers !== undefined && trailers !== null) {
+      if (!ObjectPrototypeIsPrototypeOf(PromisePrototype, trailers)) {
+        op_http_set_response_trailers(req, trailerList(trailers));
+      } else if (
+        ObjectPrototypeIsPrototypeOf(
+          ReadableStreamPrototype,
+          body?.streamOrStatic,
+        )
+      ) {
+        body = new InnerBody(
+          streamWithTrailers(req, body.streamOrStatic, trailers),
+        );
+      } else {
+        // The body is sent in one go, so the trailers must be ready first.
+        try {
+          op_http_set_response_trailers(req, trailerList(await trailers));
+        } catch (error) {
+          console.error("Exception while resolving response trailers", error);
+          innerRequest?.close();
+          op_http_set_promise_complete(req, 500);
+          return;
+        }
+      }
+    }
+
+    fastSyncResponseOrStream(req, body, status, innerRequest);
   };
 }
 
@@ -556,6 +642,33 @@ type RawHandler = (
   info: ServeHandlerInfo,
 ) => Response | Promise<Response>;
 
+type ServeTimeouts = {
+  headerRead?: number;
+  bodyRead?: number;
+  responseWrite?: number;
+  idle?: number;
+};
+
+type ServeHttp2Settings = {
+  initialStreamWindowSize?: number;
+  initialConnectionWindowSize?: number;
+  maxFrameSize?: number;
+  maxHeaderListSize?: number;
+  maxConcurrentStreams?: number;
+};
+
+type ServeLimits = {
+  maxHeaderBytes?: number;
+  maxUriLength?: number;
+  maxHeaderCount?: number;
+};
+
+type ServeConnectionCloseInfo = {
+  id: number;
+  remoteAddr: Deno.Addr;
+  error?: Error;
+};
+
 type RawServeOptions = {
   port?: number;
   hostname?: string;
@@ -563,11 +676,111 @@ type RawServeOptions = {
   reusePort?: boolean;
   key?: string;
   cert?: string;
-  onError?: (error: unknown) => Response | Promise<Response>;
+  timeouts?: ServeTimeouts;
+  http2?: ServeHttp2Settings;
+  limits?: ServeLimits;
+  onError?: (
+    error: unknown,
+    request?: Request,
+  ) => Response | Promise<Response>;
+  onConnectionClose?: (info: ServeConnectionCloseInfo) => void;
   onListen?: (params: { hostname: string; port: number }) => void;
   handler?: RawHandler;
 };
 
+const SERVE_TIMEOUT_KEYS = ["headerRead", "bodyRead", "responseWrite", "idle"];
+
+/**
+ * Validates the user-provided timeouts and converts them into the shape
+ * expected by `op_http_serve`.
+ */
+function serveTimeouts(timeouts: ServeTimeouts | undefined) {
+  const result = { __proto__: null };
+  if (timeouts === undefined) {
+    return result;
+  }
+  for (let i = 0; i < SERVE_TIMEOUT_KEYS.length; ++i) {
+    const key = SERVE_TIMEOUT_KEYS[i];
+    const value = timeouts[key];
+    if (value === undefined) {
+      continue;
+    }
+    if (typeof value !== "number" || !NumberIsFinite(value) || value <= 0) {
+      throw new TypeError(
+        `Invalid 'timeouts.${key}': must be a positive number of milliseconds`,
+      );
+    }
+    result[key] = MathCeil(value);
+  }
+  return result;
+}
+
+const MAX_HTTP2_WINDOW_SIZE = 2 ** 31 - 1;
+
+/** Valid ranges for each HTTP/2 setting, as `[key, min, max]`. */
+const SERVE_HTTP2_SETTINGS = [
+  ["initialStreamWindowSize", 0, MAX_HTTP2_WINDOW_SIZE],
+  ["initialConnectionWindowSize", 0, MAX_HTTP2_WINDOW_SIZE],
+  ["maxFrameSize", 16384, 16777215],
+  ["maxHeaderListSize", 0, 0xffffffff],
+  ["maxConcurrentStreams", 0, 0xffffffff],
+];
+
+/**
+ * Validates the user-provided HTTP/2 settings and converts them into the shape
+ * expected by `op_http_serve`.
+ */
+function serveHttp2Settings(settings: ServeHttp2Settings | undefined) {
+  const result = { __proto__: null };
+  if (settings === undefined) {
+    return result;
+  }
+  for (let i = 0; i < SERVE_HTTP2_SETTINGS.length; ++i) {
+    const { 0: key, 1: min, 2: max } = SERVE_HTTP2_SETTINGS[i];
+    const value = settings[key];
+    if (value === undefined) {
+      continue;
+    }
+    if (
+      typeof value !== "number" || !NumberIsInteger(value) || value < min ||
+      value > max
+    ) {
+      throw new TypeError(
+        `Invalid 'http2.${key}': must be an integer between ${min} and ${max}`,
+      );
+    }
+    result[key] = value;
+  }
+  return result;
+}
+
+const SERVE_LIMIT_KEYS = ["maxHeaderBytes", "maxUriLength", "maxHeaderCount"];
+
+/**
+ * Validates the user-provided request limits and converts them into the shape
+ * expected by `op_http_serve`.
+ */
+function serveLimits(limits: ServeLimits | undefined) {
+  const result = { __proto__: null };
+  if (limits === undefined) {
+    return result;
+  }
+  for (let i = 0; i < SERVE_LIMIT_KEYS.length; ++i) {
+    const key = SERVE_LIMIT_KEYS[i];
+    const value = limits[key];
+    if (value === undefined) {
+      continue;
+    }
+    if (!NumberIsSafeInteger(value) || value <= 0) {
+      throw new TypeError(
+        `Invalid 'limits.${key}': must be a positive integer`,
+      );
+    }
+    result[key] = value;
+  }
+  return result;
+}
+
 function serve(arg1, arg2) {
   let options: RawServeOptions | undefined;
   let handler: RawHandler | undefined;
@@ -602,6 +815,19 @@ function serve(arg1, arg2) {
     console.error(error);
     return internalServerError();
   };
+  const onConnectionClose = options.onConnectionClose;
+  if (
+    onConnectionClose !== undefined && typeof onConnectionClose !== "function"
+  ) {
+    throw new TypeError("'onConnectionClose' must be a function");
+  }
+  const serveOptions = {
+    __proto__: null,
+    timeouts: serveTimeouts(options.timeouts),
+    http2: serveHttp2Settings(options.http2),
+    limits: serveLimits(options.limits),
+    reportConnectionClose: onConnectionClose !== undefined,
+  };
 
   if (wantsUnix) {
     const listener = listen({
@@ -610,13 +836,21 @@ function serve(arg1, arg2) {
       [listenOptionApiName]: "Deno.serve",
     });
     const path = listener.addr.path;
-    return serveHttpOnListener(listener, signal, handler, onError, () => {
-      if (options.onListen) {
-        options.onListen(listener.addr);
-      } else {
-        console.log(`Listening on ${path}`);
-      }
-    });
+    return serveHttpOnListener(
+      listener,
+      signal,
+      handler,
+      onError,
+      () => {
+        if (options.onListen) {
+          options.onListen(listener.addr);
+        } else {
+          console.log(`Listening on ${path}`);
+        }
+      },
+      serveOptions,
+      onConnectionClose,
+    );
   }
 
   const listenOpts = {
@@ -673,44 +907,113 @@ function serve(arg1, arg2) {
     }
   };
 
-  return serveHttpOnListener(listener, signal, handler, onError, onListen);
+  return serveHttpOnListener(
+    listener,
+    signal,
+    handler,
+    onError,
+    onListen,
+    serveOptions,
+    onConnectionClose,
+  );
 }
 
 /**
  * Serve HTTP/1.1 and/or HTTP/2 on an arbitrary listener.
  */
-function serveHttpOnListener(listener, signal, handler, onError, onListen) {
+function serveHttpOnListener(
+  listener,
+  signal,
+  handler,
+  onError,
+  onListen,
+  serveOptions = { __proto__: null },
+  onConnectionClose = undefined,
+) {
   const context = new CallbackContext(
     signal,
-    op_http_serve(listener[internalRidSymbol]),
+    op_http_serve(listener[internalRidSymbol], serveOptions),
     listener,
   );
   const callback = mapToCallback(context, handler, onError);
 
   onListen(context.scheme);
 
-  return serveHttpOn(context, listener.addr, callback);
+  return serveHttpOn(context, listener.addr, callback, onConnectionClose);
 }
 
 /**
  * Serve HTTP/1.1 and/or HTTP/2 on an arbitrary connection.
  */
-function serveHttpOnConnection(connection, signal, handler, onError, onListen) {
+function serveHttpOnConnection(
+  connection,
+  signal,
+  handler,
+  onError,
+  onListen,
+  serveOptions = { __proto__: null },
+  onConnectionClose = undefined,
+) {
   const context = new CallbackContext(
     signal,
-    op_http_serve_on(connection[internalRidSymbol]),
+    op_http_serve_on(connection[internalRidSymbol], serveOptions),
     null,
   );
   const callback = mapToCallback(context, handler, onError);
 
   onListen(context.scheme);
 
-  return serveHttpOn(context, connection.localAddr, callback);
+  return serveHttpOn(
+    context,
+    connection.localAddr,
+    callback,
+    onConnectionClose,
+  );
+}
+
+/**
+ * Reports closed connections to `onConnectionClose` until the server and all
+ * of its connections have shut down.
+ */
+async function reportClosedConnections(context, onConnectionClose) {
+  const transport = context.listener?.addr.transport;
+  while (true) {
+    let closed;
+    try {
+      const promise = op_http_wait_connection_closed(context.serverRid);
+      // The server loop keeps the event loop alive, not this one.
+      core.unrefOpPromise(promise);
+      closed = await promise;
+    } catch (error) {
+      if (ObjectPrototypeIsPrototypeOf(BadResourcePrototype, error)) {
+        return;
+      }
+      throw error;
+    }
+    if (closed === null) {
+      return;
+    }
+    const remoteAddr = transport === "unix" || transport === "unixpacket"
+      ? { transport, path: context.listener.addr.path }
+      : { transport: "tcp", hostname: closed.hostname, port: closed.port };
+    const info = { id: closed.id, remoteAddr };
+    if (closed.error !== null) {
+      info.error = new Deno.errors.Http(closed.error);
+    }
+    try {
+      onConnectionClose(info);
+    } catch (error) {
+      console.error("Exception in onConnectionClose", error);
+    }
+  }
 }
 
-function serveHttpOn(context, addr, callback) {
+function serveHttpOn(context, addr, callback, onConnectionClose = undefined) {
   let ref = true;
   let currentPromise = null;
+  const connectionsClosed = onConnectionClose !== undefined
+    ? reportClosedConnections(context, onConnectionClose)
+    : undefined;
 
   const promiseErrorHandler = (error) => {
     // Abnormal exit
@@ -772,6 +1075,7 @@ function serveHttpOn(context, addr, callback) {
     } finally {
       context.close();
       context.closed = true;
+      await connectionsClosed;
     }
   })();
 
@@ -0,0 +1,155 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+use crate::request_properties::HttpConnectionProperties;
+use deno_core::error::AnyError;
+use deno_core::Canceled;
+use serde::Serialize;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use tokio::sync::mpsc;
+
+/// Metadata about a single connection accepted by `Deno.serve`, shared by all
+/// requests made over that connection.
+pub struct HttpConnectionInfo {
+  /// Identifies the connection among all connections of the same server.
+  pub id: u32,
+  pub peer_address: Rc<str>,
+  pub peer_port: Option<u16>,
+  pub tls: bool,
+  /// The protocol negotiated during the TLS handshake, if any.
+  pub alpn_protocol: RefCell<Option<String>>,
+}
+
+impl HttpConnectionInfo {
+  pub fn new(
+    id: u32,
+    properties: &HttpConnectionProperties,
+    tls: bool,
+  ) -> Rc<Self> {
+    Rc::new(Self {
+      id,
+      peer_address: properties.peer_address.clone(),
+      peer_port: properties.peer_port,
+      tls,
+      alpn_protocol: RefCell::new(None),
+    })
+  }
+}
+
+/// Reported to JavaScript once a connection has been closed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpConnectionClosed {
+  pub id: u32,
+  pub hostname: String,
+  pub port: Option<u16>,
+  /// The protocol or I/O error that caused the connection to close, if any.
+  pub error: Option<String>,
+}
+
+/// Hands out connection ids for a server and, if requested, reports closed
+/// connections back to JavaScript.
+pub(crate) struct HttpConnections {
+  next_id: Cell<u32>,
+  closed_tx: Option<mpsc::UnboundedSender<HttpConnectionClosed>>,
+}
+
+impl HttpConnections {
+  pub fn new(
+    report_closed: bool,
+  ) -> (
+    Rc<Self>,
+    Option<mpsc::UnboundedReceiver<HttpConnectionClosed>>,
+  ) {
+    let (closed_tx, closed_rx) = if report_closed {
+      let (tx, rx) = mpsc::unbounded_channel();
+      (Some(tx), Some(rx))
+    } else {
+      (None, None)
+    };
+    let connections = Rc::new(Self {
+      next_id: Cell::new(0),
+      closed_tx,
+    });
+    (connections, closed_rx)
+  }
+
+  pub fn open(
+    &self,
+    properties: &HttpConnectionProperties,
+    tls: bool,
+  ) -> Rc<HttpConnectionInfo> {
+    let id = self.next_id.get();
+    self.next_id.set(id.wrapping_add(1));
+    HttpConnectionInfo::new(id, properties, tls)
+  }
+
+  /// Drives a connection future to completion, then reports the closed
+  /// connection.
+  pub async fn track(
+    self: Rc<Self>,
+    info: Rc<HttpConnectionInfo>,
+    fut: impl Future<Output = Result<(), AnyError>>,
+  ) -> Result<(), AnyError> {
+    let res = fut.await;
+    if let Some(closed_tx) = &self.closed_tx {
+      // Connections closed by shutting down the server are not errors.
+      let error = match &res {
+        Err(err) if err.downcast_ref::<Canceled>().is_none() => {
+          Some(err.to_string())
+        }
+        _ => None,
+      };
+      let _ = closed_tx.send(HttpConnectionClosed {
+        id: info.id,
+        hostname: info.peer_address.to_string(),
+        port: info.peer_port,
+        error,
+      });
+    }
+    res
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::error::generic_error;
+  use deno_net::raw::NetworkStreamType;
+
+  #[tokio::test]
+  async fn test_report_closed() {
+    let (connections, closed_rx) = HttpConnections::new(true);
+    let mut closed_rx = closed_rx.unwrap();
+    let properties = HttpConnectionProperties {
+      peer_address: "127.0.0.1".into(),
+      peer_port: Some(1234),
+      local_port: None,
+      stream_type: NetworkStreamType::Tcp,
+    };
+
+    let first = connections.open(&properties, false);
+    let second = connections.open(&properties, true);
+    assert_eq!((first.id, second.id), (0, 1));
+
+    let res = connections.clone().track(first, async { Ok(()) }).await;
+    assert!(res.is_ok());
+    let res = connections
+      .clone()
+      .track(second, async { Err(generic_error("oops")) })
+      .await;
+    assert!(res.is_err());
+
+    let closed = closed_rx.recv().await.unwrap();
+    assert_eq!((closed.id, closed.error), (0, None));
+    let closed = closed_rx.recv().await.unwrap();
+    assert_eq!(closed.id, 1);
+    assert_eq!(closed.hostname, "127.0.0.1");
+    assert_eq!(closed.port, Some(1234));
+    assert_eq!(closed.error.as_deref(), Some("oops"));
+
+    drop(connections);
+    assert!(closed_rx.recv().await.is_none());
+  }
+}
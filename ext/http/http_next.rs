@@ -1,7 +1,12 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 use crate::compressible::is_content_compressible;
+use crate::connection::HttpConnectionClosed;
+use crate::connection::HttpConnectionInfo;
+use crate::connection::HttpConnections;
 use crate::extract_network_stream;
+use crate::limits::HttpRequestLimits;
 use crate::network_buffered_stream::NetworkStreamPrefixCheck;
+use crate::priority::RequestPriority;
 use crate::request_body::HttpRequestBody;
 use crate::request_properties::HttpConnectionProperties;
 use crate::request_properties::HttpListenProperties;
@@ -16,9 +21,13 @@ use crate::service::HttpRecordResponse;
 use crate::service::HttpRequestBodyAutocloser;
 use crate::service::HttpServerState;
 use crate::service::SignallingRc;
+use crate::timeouts::with_watchdog;
+use crate::timeouts::ConnectionWatchdog;
+use crate::timeouts::HttpServeTimeouts;
 use crate::websocket_upgrade::WebSocketUpgrade;
 use crate::LocalExecutor;
 use cache_control::CacheControl;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::external;
 use deno_core::futures::future::poll_fn;
@@ -41,6 +50,7 @@ use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
+use deno_io::fs::FileResource;
 use deno_net::ops_tls::TlsStream;
 use deno_net::raw::NetworkStream;
 use deno_websocket::ws_create_server_stream;
@@ -61,7 +71,9 @@ use hyper::service::service_fn;
 use hyper::service::HttpService;
 use hyper::StatusCode;
 use hyper_util::rt::TokioIo;
+use hyper_util::rt::TokioTimer;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use smallvec::SmallVec;
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -71,6 +83,7 @@ use std::io;
 use std::pin::Pin;
 use std::ptr::null;
 use std::rc::Rc;
+use std::time::Duration;
 
 use super::fly_accept_encoding;
 use fly_accept_encoding::Encoding;
@@ -117,6 +130,63 @@ const TLS_ALPN_HTTP_2: &[u8] = b"h2";
 /// ALPN negotiation for "http/1.1"
 const TLS_ALPN_HTTP_11: &[u8] = b"http/1.1";
 
+/// Connection-level options passed from `Deno.serve`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpServeOptions {
+  pub timeouts: HttpServeTimeouts,
+  pub http2: Http2Settings,
+  pub limits: HttpRequestLimits,
+  /// Whether closed connections should be reported through
+  /// `op_http_wait_connection_closed`.
+  pub report_connection_close: bool,
+}
+
+/// Server-side HTTP/2 settings advertised to clients. A missing value keeps
+/// hyper's default.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Http2Settings {
+  pub initial_stream_window_size: Option<u32>,
+  pub initial_connection_window_size: Option<u32>,
+  pub max_frame_size: Option<u32>,
+  pub max_header_list_size: Option<u32>,
+  pub max_concurrent_streams: Option<u32>,
+}
+
+impl Http2Settings {
+  /// The h2 crate panics on values outside of the ranges permitted by
+  /// RFC 9113, so reject them before building a connection.
+  fn validate(&self) -> Result<(), AnyError> {
+    const MAX_WINDOW_SIZE: u32 = (1 << 31) - 1;
+    const FRAME_SIZE_RANGE: std::ops::RangeInclusive<u32> =
+      (1 << 14)..=((1 << 24) - 1);
+    for (name, value) in [
+      ("initialStreamWindowSize", self.initial_stream_window_size),
+      (
+        "initialConnectionWindowSize",
+        self.initial_connection_window_size,
+      ),
+    ] {
+      if value.is_some_and(|value| value > MAX_WINDOW_SIZE) {
+        return Err(type_error(format!(
+          "http2.{name} must be at most {MAX_WINDOW_SIZE}"
+        )));
+      }
+    }
+    if let Some(max_frame_size) = self.max_frame_size {
+      if !FRAME_SIZE_RANGE.contains(&max_frame_size) {
+        return Err(type_error(format!(
+          "http2.maxFrameSize must be between {} and {}",
+          FRAME_SIZE_RANGE.start(),
+          FRAME_SIZE_RANGE.end()
+        )));
+      }
+    }
+    Ok(())
+  }
+}
+
 /// Name a trait for streams we can serve HTTP over.
 trait HttpServeStream:
   tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
@@ -353,6 +423,32 @@ pub fn op_http_get_request_header(
   value.map(|value| value.as_bytes().into())
 }
 
+/// Returns the RFC 9218 `(urgency, incremental)` priority of the request.
+#[op2]
+#[serde]
+pub fn op_http_get_request_priority(external: *const c_void) -> (u8, bool) {
+  let http =
+    // SAFETY: op is called with external.
+    unsafe { clone_external!(external, "op_http_get_request_priority") };
+  let priority = RequestPriority::from_headers(&http.request_parts().headers);
+  (priority.urgency, priority.incremental)
+}
+
+/// Returns the `(id, tls, alpn_protocol)` of the connection the request was
+/// received on.
+#[op2]
+#[serde]
+pub fn op_http_get_request_connection(
+  external: *const c_void,
+) -> (u32, bool, Option<String>) {
+  let http =
+    // SAFETY: op is called with external.
+    unsafe { clone_external!(external, "op_http_get_request_connection") };
+  let connection = http.connection();
+  let alpn_protocol = connection.alpn_protocol.borrow().clone();
+  (connection.id, connection.tls, alpn_protocol)
+}
+
 #[op2]
 pub fn op_http_get_request_headers<'scope>(
   scope: &mut v8::HandleScope<'scope>,
@@ -433,7 +529,8 @@ pub fn op_http_read_request_body(
     // SAFETY: op is called with external.
     unsafe { clone_external!(external, "op_http_read_request_body") };
   let rid = if let Some(incoming) = http.take_request_body() {
-    let body_resource = Rc::new(HttpRequestBody::new(incoming));
+    let body_resource =
+      Rc::new(HttpRequestBody::new(incoming, http.body_read_timeout()));
     state.borrow_mut().resource_table.add_rc(body_resource)
   } else {
     // This should not be possible, but rather than panicking we'll return an invalid
@@ -704,11 +801,18 @@ pub async fn op_http_set_response_body_resource(
     }
   };
 
+  // The length of a file is looked up asynchronously, rather than with the
+  // blocking syscalls of `size_hint`.
+  let size = match resource.downcast_rc::<FileResource>() {
+    Some(file) => file.remaining_len().await,
+    None => resource.size_hint().1,
+  };
+
   *http.needs_close_after_finish() = true;
 
   set_response(
     http.clone(),
-    resource.size_hint().1.map(|s| s as usize),
+    size.map(|s| s as usize),
     status,
     true,
     move |compression| {
@@ -767,10 +871,25 @@ fn serve_http11_unconditional(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = HttpRecordResponse> + 'static,
   cancel: Rc<CancelHandle>,
+  header_read_timeout: Option<Duration>,
+  limits: HttpRequestLimits,
 ) -> impl Future<Output = Result<(), hyper::Error>> + 'static {
-  let conn = http1::Builder::new()
-    .keep_alive(true)
-    .writev(*USE_WRITEV)
+  let mut builder = http1::Builder::new();
+  builder.keep_alive(true).writev(*USE_WRITEV);
+  if let Some(max_buf_size) = limits.http1_max_buf_size() {
+    builder.max_buf_size(max_buf_size);
+  }
+  if let Some(max_header_count) = limits.max_header_count {
+    builder.max_headers(max_header_count);
+  }
+  // Hyper applies a default header read timeout as soon as a timer is
+  // configured, so only install one when a timeout was requested.
+  if let Some(header_read_timeout) = header_read_timeout {
+    builder
+      .timer(TokioTimer::new())
+      .header_read_timeout(header_read_timeout);
+  }
+  let conn = builder
     .serve_connection(TokioIo::new(io), svc)
     .with_upgrades();
 
@@ -789,9 +908,31 @@ fn serve_http2_unconditional(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = HttpRecordResponse> + 'static,
   cancel: Rc<CancelHandle>,
+  settings: Http2Settings,
+  limits: HttpRequestLimits,
 ) -> impl Future<Output = Result<(), hyper::Error>> + 'static {
-  let conn =
-    http2::Builder::new(LocalExecutor).serve_connection(TokioIo::new(io), svc);
+  let mut builder = http2::Builder::new(LocalExecutor);
+  builder
+    .initial_stream_window_size(settings.initial_stream_window_size)
+    .initial_connection_window_size(settings.initial_connection_window_size)
+    .max_frame_size(settings.max_frame_size);
+  // Unlike the settings above, `None` means "unlimited" for these.
+  let max_header_list_size = settings.max_header_list_size.or_else(|| {
+    // Header list sizes include 32 bytes of overhead for every header, so
+    // this only bounds the header bytes loosely; the exact limit is checked
+    // when handling the request.
+    let max_header_bytes = limits.max_header_bytes?;
+    let max_header_count = limits.max_header_count.unwrap_or(100);
+    let max_list_size = max_header_bytes.saturating_add(32 * max_header_count);
+    Some(u32::try_from(max_list_size).unwrap_or(u32::MAX))
+  });
+  if let Some(max_header_list_size) = max_header_list_size {
+    builder.max_header_list_size(max_header_list_size);
+  }
+  if let Some(max_concurrent_streams) = settings.max_concurrent_streams {
+    builder.max_concurrent_streams(max_concurrent_streams);
+  }
+  let conn = builder.serve_connection(TokioIo::new(io), svc);
   async {
     match conn.or_abort(cancel).await {
       Err(mut conn) => {
@@ -807,17 +948,24 @@ async fn serve_http2_autodetect(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = HttpRecordResponse> + 'static,
   cancel: Rc<CancelHandle>,
+  options: Rc<HttpServeOptions>,
 ) -> Result<(), AnyError> {
   let prefix = NetworkStreamPrefixCheck::new(io, HTTP2_PREFIX);
   let (matches, io) = prefix.match_prefix().await?;
   if matches {
-    serve_http2_unconditional(io, svc, cancel)
+    serve_http2_unconditional(io, svc, cancel, options.http2, options.limits)
       .await
       .map_err(|e| e.into())
   } else {
-    serve_http11_unconditional(io, svc, cancel)
-      .await
-      .map_err(|e| e.into())
+    serve_http11_unconditional(
+      io,
+      svc,
+      cancel,
+      options.timeouts.header_read(),
+      options.limits,
+    )
+    .await
+    .map_err(|e| e.into())
   }
 }
 
@@ -831,31 +979,61 @@ fn serve_https(
     server_state,
     connection_cancel_handle,
     listen_cancel_handle,
+    options,
+    connections,
   } = lifetime;
 
+  let connection = connections.open(&request_info, true);
+  let svc_connection = connection.clone();
+  let watchdog = ConnectionWatchdog::new(&options.timeouts);
+  let svc_watchdog = watchdog.clone();
+  let limits = options.limits;
   let svc = service_fn(move |req: Request| {
-    handle_request(req, request_info.clone(), server_state.clone(), tx.clone())
+    handle_request(
+      req,
+      request_info.clone(),
+      svc_connection.clone(),
+      server_state.clone(),
+      tx.clone(),
+      svc_watchdog.clone(),
+      limits,
+    )
   });
-  spawn(
-    async {
-      let handshake = io.handshake().await?;
-      // If the client specifically negotiates a protocol, we will use it. If not, we'll auto-detect
-      // based on the prefix bytes
-      let handshake = handshake.alpn;
-      if Some(TLS_ALPN_HTTP_2) == handshake.as_deref() {
-        serve_http2_unconditional(io, svc, listen_cancel_handle)
-          .await
-          .map_err(|e| e.into())
-      } else if Some(TLS_ALPN_HTTP_11) == handshake.as_deref() {
-        serve_http11_unconditional(io, svc, listen_cancel_handle)
-          .await
-          .map_err(|e| e.into())
-      } else {
-        serve_http2_autodetect(io, svc, listen_cancel_handle).await
-      }
+  let handshake_connection = connection.clone();
+  let fut = with_watchdog(watchdog, async move {
+    let handshake = io.handshake().await?;
+    // If the client specifically negotiates a protocol, we will use it. If not, we'll auto-detect
+    // based on the prefix bytes
+    let handshake = handshake.alpn;
+    *handshake_connection.alpn_protocol.borrow_mut() = handshake
+      .as_deref()
+      .map(|alpn| String::from_utf8_lossy(alpn).into_owned());
+    if Some(TLS_ALPN_HTTP_2) == handshake.as_deref() {
+      serve_http2_unconditional(
+        io,
+        svc,
+        listen_cancel_handle,
+        options.http2,
+        options.limits,
+      )
+      .await
+      .map_err(|e| e.into())
+    } else if Some(TLS_ALPN_HTTP_11) == handshake.as_deref() {
+      serve_http11_unconditional(
+        io,
+        svc,
+        listen_cancel_handle,
+        options.timeouts.header_read(),
+        options.limits,
+      )
+      .await
+      .map_err(|e| e.into())
+    } else {
+      serve_http2_autodetect(io, svc, listen_cancel_handle, options).await
     }
-    .try_or_cancel(connection_cancel_handle),
-  )
+  })
+  .try_or_cancel(connection_cancel_handle);
+  spawn(connections.track(connection, fut))
 }
 
 fn serve_http(
@@ -868,15 +1046,32 @@ fn serve_http(
     server_state,
     connection_cancel_handle,
     listen_cancel_handle,
+    options,
+    connections,
   } = lifetime;
 
+  let connection = connections.open(&request_info, false);
+  let svc_connection = connection.clone();
+  let watchdog = ConnectionWatchdog::new(&options.timeouts);
+  let svc_watchdog = watchdog.clone();
+  let limits = options.limits;
   let svc = service_fn(move |req: Request| {
-    handle_request(req, request_info.clone(), server_state.clone(), tx.clone())
+    handle_request(
+      req,
+      request_info.clone(),
+      svc_connection.clone(),
+      server_state.clone(),
+      tx.clone(),
+      svc_watchdog.clone(),
+      limits,
+    )
   });
-  spawn(
-    serve_http2_autodetect(io, svc, listen_cancel_handle)
-      .try_or_cancel(connection_cancel_handle),
+  let fut = with_watchdog(
+    watchdog,
+    serve_http2_autodetect(io, svc, listen_cancel_handle, options),
   )
+  .try_or_cancel(connection_cancel_handle);
+  spawn(connections.track(connection, fut))
 }
 
 fn serve_http_on<HTTP>(
@@ -912,6 +1107,8 @@ struct HttpLifetime {
   connection_cancel_handle: Rc<CancelHandle>,
   listen_cancel_handle: Rc<CancelHandle>,
   server_state: SignallingRc<HttpServerState>,
+  options: Rc<HttpServeOptions>,
+  connections: Rc<HttpConnections>,
 }
 
 struct HttpJoinHandle {
@@ -919,25 +1116,45 @@ struct HttpJoinHandle {
   connection_cancel_handle: Rc<CancelHandle>,
   listen_cancel_handle: Rc<CancelHandle>,
   rx: AsyncRefCell<tokio::sync::mpsc::Receiver<Rc<HttpRecord>>>,
+  closed_rx: Option<
+    AsyncRefCell<tokio::sync::mpsc::UnboundedReceiver<HttpConnectionClosed>>,
+  >,
   server_state: SignallingRc<HttpServerState>,
+  options: Rc<HttpServeOptions>,
 }
 
 impl HttpJoinHandle {
-  fn new(rx: tokio::sync::mpsc::Receiver<Rc<HttpRecord>>) -> Self {
-    Self {
+  /// Creates the handle along with the connection tracker that must be passed
+  /// to [`HttpJoinHandle::lifetime`]. The tracker is not owned by the handle so
+  /// that closed connection reporting ends once every connection is gone.
+  fn new(
+    rx: tokio::sync::mpsc::Receiver<Rc<HttpRecord>>,
+    options: HttpServeOptions,
+  ) -> (Self, Rc<HttpConnections>) {
+    let (connections, closed_rx) =
+      HttpConnections::new(options.report_connection_close);
+    let handle = Self {
       join_handle: AsyncRefCell::new(None),
       connection_cancel_handle: CancelHandle::new_rc(),
       listen_cancel_handle: CancelHandle::new_rc(),
       rx: AsyncRefCell::new(rx),
+      closed_rx: closed_rx.map(AsyncRefCell::new),
       server_state: HttpServerState::new(),
-    }
+      options: Rc::new(options),
+    };
+    (handle, connections)
   }
 
-  fn lifetime(self: &Rc<Self>) -> HttpLifetime {
+  fn lifetime(
+    self: &Rc<Self>,
+    connections: Rc<HttpConnections>,
+  ) -> HttpLifetime {
     HttpLifetime {
       connection_cancel_handle: self.connection_cancel_handle.clone(),
       listen_cancel_handle: self.listen_cancel_handle.clone(),
       server_state: self.server_state.clone(),
+      options: self.options.clone(),
+      connections,
     }
   }
 
@@ -975,20 +1192,24 @@ impl Drop for HttpJoinHandle {
 pub fn op_http_serve<HTTP>(
   state: Rc<RefCell<OpState>>,
   #[smi] listener_rid: ResourceId,
+  #[serde] options: HttpServeOptions,
 ) -> Result<(ResourceId, &'static str, String), AnyError>
 where
   HTTP: HttpPropertyExtractor,
 {
+  options.http2.validate()?;
+  options.limits.validate()?;
   let listener =
     HTTP::get_listener_for_rid(&mut state.borrow_mut(), listener_rid)?;
 
   let listen_properties = HTTP::listen_properties_from_listener(&listener)?;
 
   let (tx, rx) = tokio::sync::mpsc::channel(10);
-  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle::new(rx));
+  let (resource, connections) = HttpJoinHandle::new(rx, options);
+  let resource = Rc::new(resource);
   let listen_cancel_clone = resource.listen_cancel_handle();
 
-  let lifetime = resource.lifetime();
+  let lifetime = resource.lifetime(connections);
 
   let listen_properties_clone: HttpListenProperties = listen_properties.clone();
   let handle = spawn(async move {
@@ -1024,23 +1245,27 @@ where
 pub fn op_http_serve_on<HTTP>(
   state: Rc<RefCell<OpState>>,
   #[smi] connection_rid: ResourceId,
+  #[serde] options: HttpServeOptions,
 ) -> Result<(ResourceId, &'static str, String), AnyError>
 where
   HTTP: HttpPropertyExtractor,
 {
+  options.http2.validate()?;
+  options.limits.validate()?;
   let connection =
     HTTP::get_connection_for_rid(&mut state.borrow_mut(), connection_rid)?;
 
   let listen_properties = HTTP::listen_properties_from_connection(&connection)?;
 
   let (tx, rx) = tokio::sync::mpsc::channel(10);
-  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle::new(rx));
+  let (resource, connections) = HttpJoinHandle::new(rx, options);
+  let resource = Rc::new(resource);
 
   let handle: JoinHandle<Result<(), deno_core::anyhow::Error>> =
     serve_http_on::<HTTP>(
       connection,
       &listen_properties,
-      resource.lifetime(),
+      resource.lifetime(connections),
       tx,
     );
 
@@ -1133,6 +1358,28 @@ pub async fn op_http_wait(
   Ok(null())
 }
 
+/// Resolves with the next connection that was closed, or `None` once the
+/// server and all of its connections have shut down.
+#[op2(async)]
+#[serde]
+pub async fn op_http_wait_connection_closed(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<HttpConnectionClosed>, AnyError> {
+  let join_handle = state
+    .borrow_mut()
+    .resource_table
+    .get::<HttpJoinHandle>(rid)?;
+  if join_handle.closed_rx.is_none() {
+    return Ok(None);
+  }
+  let mut closed_rx =
+    RcRef::map(&join_handle, |this| this.closed_rx.as_ref().unwrap())
+      .borrow_mut()
+      .await;
+  Ok(closed_rx.recv().await)
+}
+
 /// Cancels the HTTP handle.
 #[op2(fast)]
 pub fn op_http_cancel(
@@ -78,14 +78,18 @@ use crate::reader_stream::ExternallyAbortableReaderStream;
 use crate::reader_stream::ShutdownHandle;
 
 pub mod compressible;
+mod connection;
 mod fly_accept_encoding;
 mod http_next;
+mod limits;
 mod network_buffered_stream;
+mod priority;
 mod reader_stream;
 mod request_body;
 mod request_properties;
 mod response_body;
 mod service;
+mod timeouts;
 mod websocket_upgrade;
 
 use fly_accept_encoding::Encoding;
@@ -109,9 +113,11 @@ deno_core::extension!(
     op_http_write_resource,
     op_http_write,
     http_next::op_http_close_after_finish,
+    http_next::op_http_get_request_connection,
     http_next::op_http_get_request_header,
     http_next::op_http_get_request_headers,
     http_next::op_http_get_request_method_and_url<HTTP>,
+    http_next::op_http_get_request_priority,
     http_next::op_http_read_request_body,
     http_next::op_http_serve_on<HTTP>,
     http_next::op_http_serve<HTTP>,
@@ -128,6 +134,7 @@ deno_core::extension!(
     http_next::op_can_write_vectored,
     http_next::op_http_try_wait,
     http_next::op_http_wait,
+    http_next::op_http_wait_connection_closed,
     http_next::op_http_close,
     http_next::op_http_cancel,
   ],
@@ -0,0 +1,108 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use hyper::StatusCode;
+use serde::Deserialize;
+
+/// hyper refuses to configure an HTTP/1.1 read buffer smaller than this.
+const MIN_HTTP1_BUF_SIZE: usize = 8192;
+
+/// Limits on the request head accepted by `Deno.serve`. A missing value keeps
+/// hyper's default.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpRequestLimits {
+  /// Maximum combined size of all header names and values, in bytes.
+  pub max_header_bytes: Option<usize>,
+  /// Maximum length of the request target, in bytes.
+  pub max_uri_length: Option<usize>,
+  /// Maximum number of header fields.
+  pub max_header_count: Option<usize>,
+}
+
+impl HttpRequestLimits {
+  pub fn validate(&self) -> Result<(), AnyError> {
+    for (name, value) in [
+      ("maxHeaderBytes", self.max_header_bytes),
+      ("maxUriLength", self.max_uri_length),
+      ("maxHeaderCount", self.max_header_count),
+    ] {
+      if value == Some(0) {
+        return Err(type_error(format!("limits.{name} must be positive")));
+      }
+    }
+    Ok(())
+  }
+
+  /// The read buffer size for HTTP/1.1 connections. Requests whose head does
+  /// not fit are rejected by hyper before they are parsed.
+  pub fn http1_max_buf_size(&self) -> Option<usize> {
+    // Leave room for the request line, which is limited separately.
+    let request_line = self.max_uri_length.unwrap_or(MIN_HTTP1_BUF_SIZE);
+    let max_head_bytes = self.max_header_bytes?.saturating_add(request_line);
+    Some(max_head_bytes.max(MIN_HTTP1_BUF_SIZE))
+  }
+
+  /// Checks a parsed request head, returning the status to reject it with if
+  /// it exceeds one of the limits.
+  ///
+  /// hyper only enforces some of these limits for HTTP/1.1, and only
+  /// approximately, so every request is checked again here.
+  pub fn check<B>(&self, request: &hyper::Request<B>) -> Option<StatusCode> {
+    if let Some(max_uri_length) = self.max_uri_length {
+      let uri_length = request
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str().len())
+        .unwrap_or_default();
+      if uri_length > max_uri_length {
+        return Some(StatusCode::URI_TOO_LONG);
+      }
+    }
+    if let Some(max_header_count) = self.max_header_count {
+      if request.headers().len() > max_header_count {
+        return Some(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+      }
+    }
+    if let Some(max_header_bytes) = self.max_header_bytes {
+      let header_bytes: usize = request
+        .headers()
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum();
+      if header_bytes > max_header_bytes {
+        return Some(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+      }
+    }
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn check(limits: HttpRequestLimits, uri: &str, headers: usize) -> u16 {
+    let mut request = hyper::Request::builder().uri(uri);
+    for i in 0..headers {
+      request = request.header(format!("x-header-{i}"), "value");
+    }
+    let request = request.body(()).unwrap();
+    limits.check(&request).map_or(200, |status| status.as_u16())
+  }
+
+  #[test]
+  fn test_check_limits() {
+    let limits = HttpRequestLimits {
+      max_header_bytes: Some(50),
+      max_uri_length: Some(10),
+      max_header_count: Some(4),
+    };
+    assert_eq!(check(limits, "/short", 3), 200);
+    assert_eq!(check(limits, "/a?b=cdefgh", 0), 414);
+    assert_eq!(check(limits, "/", 5), 431);
+    // Each header is 15 bytes
+    assert_eq!(check(limits, "/", 4), 431);
+    assert_eq!(check(HttpRequestLimits::default(), "/a?b=cdefgh", 5), 200);
+  }
+}
@@ -0,0 +1,102 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+//! Extensible prioritization scheme for HTTP ([RFC 9218]).
+//!
+//! HTTP/2 `PRIORITY` frames were deprecated by RFC 9113 and are discarded by
+//! the `h2` crate, so the `Priority` request header is the only priority
+//! signal we can surface to handlers.
+//!
+//! [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+use hyper::header::HeaderMap;
+use hyper::header::HeaderName;
+
+static PRIORITY: HeaderName = HeaderName::from_static("priority");
+
+/// The default urgency when none is specified.
+const DEFAULT_URGENCY: u8 = 3;
+
+/// The lowest-priority urgency.
+const MAX_URGENCY: u8 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestPriority {
+  /// From 0 (highest priority) to 7 (lowest priority).
+  pub urgency: u8,
+  /// Whether the response can be processed incrementally.
+  pub incremental: bool,
+}
+
+impl Default for RequestPriority {
+  fn default() -> Self {
+    Self {
+      urgency: DEFAULT_URGENCY,
+      incremental: false,
+    }
+  }
+}
+
+impl RequestPriority {
+  /// Extracts the priority from the `Priority` headers of a request. Unknown
+  /// or malformed parameters are ignored, as required by the RFC.
+  pub fn from_headers(headers: &HeaderMap) -> Self {
+    let mut priority = Self::default();
+    for value in headers.get_all(&PRIORITY) {
+      let Ok(value) = value.to_str() else {
+        continue;
+      };
+      for member in value.split(',') {
+        let (key, value) = match member.split_once('=') {
+          Some((key, value)) => (key.trim(), Some(value.trim())),
+          None => (member.trim(), None),
+        };
+        match (key, value) {
+          ("u", Some(value)) => {
+            if let Ok(urgency) = value.parse::<u8>() {
+              if urgency <= MAX_URGENCY {
+                priority.urgency = urgency;
+              }
+            }
+          }
+          ("i", None | Some("?1")) => priority.incremental = true,
+          ("i", Some("?0")) => priority.incremental = false,
+          _ => {}
+        }
+      }
+    }
+    priority
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use hyper::header::HeaderValue;
+
+  fn parse(values: &[&'static str]) -> RequestPriority {
+    let mut headers = HeaderMap::new();
+    for value in values {
+      headers.append(&PRIORITY, HeaderValue::from_static(value));
+    }
+    RequestPriority::from_headers(&headers)
+  }
+
+  #[test]
+  fn test_default_priority() {
+    assert_eq!(parse(&[]), RequestPriority::default());
+  }
+
+  #[test]
+  fn test_parse_priority() {
+    let expected = |urgency, incremental| RequestPriority {
+      urgency,
+      incremental,
+    };
+    assert_eq!(parse(&["u=0"]), expected(0, false));
+    assert_eq!(parse(&["u=5, i"]), expected(5, true));
+    assert_eq!(parse(&["i=?1"]), expected(3, true));
+    assert_eq!(parse(&["u=1, i=?0"]), expected(1, false));
+    assert_eq!(parse(&["u=2", "i"]), expected(2, true));
+    // Out of range or malformed values are ignored
+    assert_eq!(parse(&["u=8"]), expected(3, false));
+    assert_eq!(parse(&["u=high, x=1"]), expected(3, false));
+  }
+}
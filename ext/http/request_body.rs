@@ -13,10 +13,12 @@ use hyper::body::Body;
 use hyper::body::Incoming;
 use hyper::body::SizeHint;
 use std::borrow::Cow;
+use std::io;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::task::ready;
 use std::task::Poll;
+use std::time::Duration;
 
 /// Converts a hyper incoming body stream into a stream of [`Bytes`] that we can use to read in V8.
 struct ReadFuture(Incoming);
@@ -50,18 +52,41 @@ impl Stream for ReadFuture {
   }
 }
 
-pub struct HttpRequestBody(AsyncRefCell<Peekable<ReadFuture>>, SizeHint);
+pub struct HttpRequestBody(
+  AsyncRefCell<Peekable<ReadFuture>>,
+  SizeHint,
+  Option<Duration>,
+);
 
 impl HttpRequestBody {
-  pub fn new(body: Incoming) -> Self {
+  /// Wraps a hyper body. If `read_timeout` is set, each read fails with
+  /// [`io::ErrorKind::TimedOut`] when no data arrives within that time.
+  pub fn new(body: Incoming, read_timeout: Option<Duration>) -> Self {
     let size_hint = body.size_hint();
-    Self(AsyncRefCell::new(ReadFuture(body).peekable()), size_hint)
+    Self(
+      AsyncRefCell::new(ReadFuture(body).peekable()),
+      size_hint,
+      read_timeout,
+    )
   }
 
   async fn read(self: Rc<Self>, limit: usize) -> Result<BufView, AnyError> {
+    let read_timeout = self.2;
     let peekable = RcRef::map(self, |this| &this.0);
     let mut peekable = peekable.borrow_mut().await;
-    match Pin::new(&mut *peekable).peek_mut().await {
+    let peek = Pin::new(&mut *peekable).peek_mut();
+    let peeked = match read_timeout {
+      Some(read_timeout) => tokio::time::timeout(read_timeout, peek)
+        .await
+        .map_err(|_| {
+          io::Error::new(
+            io::ErrorKind::TimedOut,
+            "timed out reading request body",
+          )
+        })?,
+      None => peek.await,
+    };
+    match peeked {
       None => Ok(BufView::empty()),
       Some(Err(_)) => Err(peekable.next().await.unwrap().err().unwrap()),
       Some(Ok(bytes)) => {
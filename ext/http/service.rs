@@ -1,7 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+use crate::connection::HttpConnectionInfo;
+use crate::limits::HttpRequestLimits;
 use crate::request_properties::HttpConnectionProperties;
 use crate::response_body::ResponseBytesInner;
 use crate::response_body::ResponseStreamResult;
+use crate::timeouts::ConnectionWatchdog;
+use crate::timeouts::RequestGuard;
 use deno_core::error::AnyError;
 use deno_core::futures::ready;
 use deno_core::BufView;
@@ -28,6 +32,7 @@ use std::rc::Rc;
 use std::task::Context;
 use std::task::Poll;
 use std::task::Waker;
+use std::time::Duration;
 
 pub type Request = hyper::Request<Incoming>;
 pub type Response = hyper::Response<HttpRecordResponse>;
@@ -179,15 +184,40 @@ impl Drop for HttpRequestBodyAutocloser {
 pub(crate) async fn handle_request(
   request: Request,
   request_info: HttpConnectionProperties,
+  connection: Rc<HttpConnectionInfo>,
   server_state: SignallingRc<HttpServerState>, // Keep server alive for duration of this future.
   tx: tokio::sync::mpsc::Sender<Rc<HttpRecord>>,
+  watchdog: Option<Rc<ConnectionWatchdog>>,
+  limits: HttpRequestLimits,
 ) -> Result<Response, hyper_v014::Error> {
   // If the underlying TCP connection is closed, this future will be dropped
   // and execution could stop at any await point.
   // The HttpRecord must live until JavaScript is done processing so is wrapped
   // in an Rc. The guard ensures unneeded resources are freed at cancellation.
+  let request_guard = watchdog.map(|watchdog| watchdog.request_started());
+
+  // Requests exceeding the limits are answered without involving JavaScript.
+  if let Some(status) = limits.check(&request) {
+    let record = HttpRecord::new(
+      request,
+      request_info,
+      connection,
+      server_state,
+      request_guard,
+    );
+    record.response_parts().status = status;
+    record.clone().complete();
+    return Ok(record.into_response());
+  }
+
   let guarded_record = guard(
-    HttpRecord::new(request, request_info, server_state),
+    HttpRecord::new(
+      request,
+      request_info,
+      connection,
+      server_state,
+      request_guard,
+    ),
     HttpRecord::cancel,
   );
 
@@ -209,6 +239,7 @@ pub(crate) async fn handle_request(
 struct HttpRecordInner {
   server_state: SignallingRc<HttpServerState>,
   request_info: HttpConnectionProperties,
+  connection: Rc<HttpConnectionInfo>,
   request_parts: http::request::Parts,
   request_body: Option<RequestBodyState>,
   response_parts: Option<http::response::Parts>,
@@ -218,6 +249,7 @@ struct HttpRecordInner {
   response_body_finished: bool,
   response_body_waker: Option<Waker>,
   trailers: Option<HeaderMap>,
+  request_guard: Option<RequestGuard>,
   been_dropped: bool,
   finished: bool,
   needs_close_after_finish: bool,
@@ -240,7 +272,9 @@ impl HttpRecord {
   fn new(
     request: Request,
     request_info: HttpConnectionProperties,
+    connection: Rc<HttpConnectionInfo>,
     server_state: SignallingRc<HttpServerState>,
+    request_guard: Option<RequestGuard>,
   ) -> Rc<Self> {
     let (request_parts, request_body) = request.into_parts();
     let request_body = Some(request_body.into());
@@ -264,6 +298,7 @@ impl HttpRecord {
     *record.0.borrow_mut() = Some(HttpRecordInner {
       server_state,
       request_info,
+      connection,
       request_parts,
       request_body,
       response_parts: Some(response_parts),
@@ -273,6 +308,7 @@ impl HttpRecord {
       response_body_finished: false,
       response_body_waker: None,
       trailers: None,
+      request_guard,
       been_dropped: false,
       finished: false,
       needs_close_after_finish: false,
@@ -284,6 +320,7 @@ impl HttpRecord {
     http_trace!(self, "HttpRecord::finish");
     let mut inner = self.self_mut();
     inner.response_body_finished = true;
+    inner.request_guard.take();
     let response_body_waker = inner.response_body_waker.take();
     let needs_close_after_finish = inner.needs_close_after_finish;
     drop(inner);
@@ -385,6 +422,7 @@ impl HttpRecord {
       return;
     }
     inner.been_dropped = true;
+    inner.request_guard.take();
     // The request body might include actual resources.
     inner.request_body.take();
   }
@@ -442,9 +480,23 @@ impl HttpRecord {
     inner.response_body = response_body;
   }
 
+  /// The maximum time to wait for each chunk of the request body, if any.
+  pub fn body_read_timeout(&self) -> Option<Duration> {
+    self
+      .self_ref()
+      .request_guard
+      .as_ref()
+      .and_then(|guard| guard.body_read_timeout())
+  }
+
   /// Take the response.
   fn into_response(self: Rc<Self>) -> Response {
-    let parts = self.self_mut().response_parts.take().unwrap();
+    let mut inner = self.self_mut();
+    if let Some(request_guard) = &mut inner.request_guard {
+      request_guard.start_writing();
+    }
+    let parts = inner.response_parts.take().unwrap();
+    drop(inner);
     let body = HttpRecordResponse(ManuallyDrop::new(self));
     Response::from_parts(parts, body)
   }
@@ -454,6 +506,11 @@ impl HttpRecord {
     Ref::map(self.self_ref(), |inner| &inner.request_info)
   }
 
+  /// Get the connection this request was received on.
+  pub fn connection(&self) -> Rc<HttpConnectionInfo> {
+    self.self_ref().connection.clone()
+  }
+
   /// Get a reference to the request parts.
   pub fn request_parts(&self) -> Ref<'_, Parts> {
     Ref::map(self.self_ref(), |inner| &inner.request_parts)
@@ -650,12 +707,16 @@ mod tests {
       local_port: None,
       stream_type: NetworkStreamType::Tcp,
     };
+    let connection = HttpConnectionInfo::new(0, &request_info, false);
     let svc = service_fn(move |req: hyper::Request<Incoming>| {
       handle_request(
         req,
         request_info.clone(),
+        connection.clone(),
         server_state.clone(),
         tx.clone(),
+        None,
+        Default::default(),
       )
     });
 
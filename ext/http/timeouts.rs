@@ -0,0 +1,238 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+use deno_core::futures::future::select;
+use deno_core::futures::future::Either;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Per-connection timeouts for `Deno.serve`, in milliseconds. A missing value
+/// disables the corresponding timeout.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpServeTimeouts {
+  /// Maximum time a client may take to send the complete request head
+  /// (HTTP/1.1 only).
+  pub header_read: Option<u64>,
+  /// Maximum time to wait for each chunk of a request body.
+  pub body_read: Option<u64>,
+  /// Maximum time between handing a response to the connection and finishing
+  /// writing it to the client.
+  pub response_write: Option<u64>,
+  /// Maximum time a connection may sit without any in-flight requests.
+  pub idle: Option<u64>,
+}
+
+impl HttpServeTimeouts {
+  pub fn header_read(&self) -> Option<Duration> {
+    self.header_read.map(Duration::from_millis)
+  }
+
+  pub fn body_read(&self) -> Option<Duration> {
+    self.body_read.map(Duration::from_millis)
+  }
+
+  pub fn response_write(&self) -> Option<Duration> {
+    self.response_write.map(Duration::from_millis)
+  }
+
+  pub fn idle(&self) -> Option<Duration> {
+    self.idle.map(Duration::from_millis)
+  }
+
+  /// Do any of these timeouts require a [`ConnectionWatchdog`]?
+  fn needs_watchdog(&self) -> bool {
+    self.body_read.is_some()
+      || self.response_write.is_some()
+      || self.idle.is_some()
+  }
+}
+
+struct WatchdogState {
+  inflight: usize,
+  writing: usize,
+  last_activity: Instant,
+  write_started: Option<Instant>,
+}
+
+/// Tracks the requests in flight on a single connection and resolves
+/// [`ConnectionWatchdog::expired`] once the connection has been idle, or has
+/// been stuck writing a response, for longer than the configured timeouts.
+pub(crate) struct ConnectionWatchdog {
+  timeouts: HttpServeTimeouts,
+  state: RefCell<WatchdogState>,
+  changed: Notify,
+}
+
+impl ConnectionWatchdog {
+  /// Creates a watchdog for a new connection, or `None` if no timeout
+  /// configured in `timeouts` needs one.
+  pub fn new(timeouts: &HttpServeTimeouts) -> Option<Rc<Self>> {
+    if !timeouts.needs_watchdog() {
+      return None;
+    }
+    Some(Rc::new(Self {
+      timeouts: *timeouts,
+      state: RefCell::new(WatchdogState {
+        inflight: 0,
+        writing: 0,
+        last_activity: Instant::now(),
+        write_started: None,
+      }),
+      changed: Notify::new(),
+    }))
+  }
+
+  /// Registers a new in-flight request. The request is considered complete
+  /// when the returned guard is dropped.
+  pub fn request_started(self: &Rc<Self>) -> RequestGuard {
+    self.state.borrow_mut().inflight += 1;
+    self.changed.notify_waiters();
+    RequestGuard {
+      watchdog: self.clone(),
+      writing: false,
+    }
+  }
+
+  fn deadline(&self) -> Option<Instant> {
+    let state = self.state.borrow();
+    if state.inflight == 0 {
+      return self.timeouts.idle().map(|idle| state.last_activity + idle);
+    }
+    let write_started = state.write_started?;
+    self
+      .timeouts
+      .response_write()
+      .map(|timeout| write_started + timeout)
+  }
+
+  /// Resolves when the connection should be dropped.
+  pub async fn expired(&self) {
+    loop {
+      // Create the notification future before checking the deadline so that
+      // we can't miss a state change that happens in between.
+      let changed = self.changed.notified();
+      match self.deadline() {
+        Some(deadline) if deadline <= Instant::now() => return,
+        Some(deadline) => {
+          let sleep = pin!(tokio::time::sleep_until(deadline));
+          select(sleep, pin!(changed)).await;
+        }
+        None => changed.await,
+      }
+    }
+  }
+}
+
+/// Races a connection future against the connection's watchdog, if any. When
+/// the watchdog expires the connection future is dropped, closing the
+/// underlying stream.
+pub(crate) async fn with_watchdog<E>(
+  watchdog: Option<Rc<ConnectionWatchdog>>,
+  fut: impl Future<Output = Result<(), E>>,
+) -> Result<(), E> {
+  let Some(watchdog) = watchdog else {
+    return fut.await;
+  };
+  match select(pin!(fut), pin!(watchdog.expired())).await {
+    Either::Left((res, _)) => res,
+    Either::Right(_) => Ok(()),
+  }
+}
+
+/// Keeps a request registered with its [`ConnectionWatchdog`] until dropped.
+pub(crate) struct RequestGuard {
+  watchdog: Rc<ConnectionWatchdog>,
+  writing: bool,
+}
+
+impl RequestGuard {
+  /// Marks the response for this request as handed to the connection, which
+  /// starts the response write timeout.
+  pub fn start_writing(&mut self) {
+    if self.writing {
+      return;
+    }
+    self.writing = true;
+    let mut state = self.watchdog.state.borrow_mut();
+    state.writing += 1;
+    if state.write_started.is_none() {
+      state.write_started = Some(Instant::now());
+    }
+    drop(state);
+    self.watchdog.changed.notify_waiters();
+  }
+
+  pub fn body_read_timeout(&self) -> Option<Duration> {
+    self.watchdog.timeouts.body_read()
+  }
+}
+
+impl Drop for RequestGuard {
+  fn drop(&mut self) {
+    let now = Instant::now();
+    let mut state = self.watchdog.state.borrow_mut();
+    state.inflight -= 1;
+    state.last_activity = now;
+    if self.writing {
+      state.writing -= 1;
+      // Restart the clock for any remaining responses now that one of them
+      // has made progress.
+      state.write_started = (state.writing > 0).then_some(now);
+    }
+    drop(state);
+    self.watchdog.changed.notify_waiters();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn test_idle_timeout() {
+    let watchdog = ConnectionWatchdog::new(&HttpServeTimeouts {
+      idle: Some(50),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let guard = watchdog.request_started();
+    assert!(watchdog.deadline().is_none());
+
+    let start = Instant::now();
+    drop(guard);
+    watchdog.expired().await;
+    assert!(start.elapsed() >= Duration::from_millis(50));
+  }
+
+  #[tokio::test]
+  async fn test_response_write_timeout() {
+    let watchdog = ConnectionWatchdog::new(&HttpServeTimeouts {
+      response_write: Some(50),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let mut guard = watchdog.request_started();
+    assert!(watchdog.deadline().is_none());
+    let start = Instant::now();
+    guard.start_writing();
+    watchdog.expired().await;
+    assert!(start.elapsed() >= Duration::from_millis(50));
+  }
+
+  #[test]
+  fn test_no_watchdog_needed() {
+    assert!(ConnectionWatchdog::new(&HttpServeTimeouts::default()).is_none());
+    assert!(ConnectionWatchdog::new(&HttpServeTimeouts {
+      header_read: Some(1000),
+      ..Default::default()
+    })
+    .is_none());
+  }
+}
@@ -231,6 +231,16 @@ pub trait File {
   fn truncate_sync(self: Rc<Self>, len: u64) -> FsResult<()>;
   async fn truncate_async(self: Rc<Self>, len: u64) -> FsResult<()>;
 
+  fn allocate_sync(self: Rc<Self>, len: u64) -> FsResult<()>;
+  async fn allocate_async(self: Rc<Self>, len: u64) -> FsResult<()>;
+
+  fn punch_hole_sync(self: Rc<Self>, offset: u64, len: u64) -> FsResult<()>;
+  async fn punch_hole_async(
+    self: Rc<Self>,
+    offset: u64,
+    len: u64,
+  ) -> FsResult<()>;
+
   fn utime_sync(
     self: Rc<Self>,
     atime_secs: i64,
@@ -255,11 +265,41 @@ pub trait File {
 pub struct FileResource {
   name: String,
   file: Rc<dyn File>,
+  /// Whether the existing contents and metadata of the file can't be changed
+  /// through the resource, see [`FileResource::get_file_for_modify`].
+  append_only: bool,
 }
 
 impl FileResource {
   pub fn new(file: Rc<dyn File>, name: String) -> Self {
-    Self { name, file }
+    Self {
+      name,
+      file,
+      append_only: false,
+    }
+  }
+
+  /// The remaining length of a regular file from its current position, which
+  /// lets response bodies streamed from it be sent with a `Content-Length`.
+  pub async fn remaining_len(&self) -> Option<u64> {
+    let stat = self.file.clone().stat_async().await.ok()?;
+    if !stat.is_file {
+      return None;
+    }
+    let position = self
+      .file
+      .clone()
+      .seek_async(io::SeekFrom::Current(0))
+      .await
+      .ok()?;
+    Some(stat.size.saturating_sub(position))
+  }
+
+  /// Only allows appending to the file, ex. because it was opened at a path
+  /// whose contents can't be modified.
+  pub fn append_only(mut self) -> Self {
+    self.append_only = true;
+    self
   }
 
   fn with_resource<F, R>(
@@ -282,6 +322,22 @@ impl FileResource {
     Ok(resource.file())
   }
 
+  /// Gets the file of a resource to change its existing contents or
+  /// metadata, which isn't allowed for append-only files.
+  pub fn get_file_for_modify(
+    state: &OpState,
+    rid: ResourceId,
+  ) -> Result<Rc<dyn File>, AnyError> {
+    let resource = state.resource_table.get::<FileResource>(rid)?;
+    if resource.append_only {
+      return Err(custom_error(
+        "PermissionDenied",
+        "The file was opened for appending only and can't be modified",
+      ));
+    }
+    Ok(resource.file())
+  }
+
   pub fn with_file<F, R>(
     state: &OpState,
     rid: ResourceId,
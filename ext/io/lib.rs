@@ -806,6 +806,28 @@ impl crate::fs::File for StdFileResourceInner {
       .await
   }
 
+  fn allocate_sync(self: Rc<Self>, len: u64) -> FsResult<()> {
+    self.with_sync(|file| Ok(file.allocate(len)?))
+  }
+  async fn allocate_async(self: Rc<Self>, len: u64) -> FsResult<()> {
+    self
+      .with_inner_blocking_task(move |file| Ok(file.allocate(len)?))
+      .await
+  }
+
+  fn punch_hole_sync(self: Rc<Self>, offset: u64, len: u64) -> FsResult<()> {
+    self.with_sync(|file| punch_hole(file, offset, len))
+  }
+  async fn punch_hole_async(
+    self: Rc<Self>,
+    offset: u64,
+    len: u64,
+  ) -> FsResult<()> {
+    self
+      .with_inner_blocking_task(move |file| punch_hole(file, offset, len))
+      .await
+  }
+
   fn utime_sync(
     self: Rc<Self>,
     atime_secs: i64,
@@ -888,6 +910,129 @@ impl crate::fs::File for StdFileResourceInner {
   }
 }
 
+/// Deallocates the disk space of a range of the file, which reads as zeros
+/// afterwards. The size of the file does not change.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn punch_hole(file: &StdFile, offset: u64, len: u64) -> FsResult<()> {
+  use std::os::unix::io::AsRawFd;
+  let (Ok(offset), Ok(len)) = (offset.try_into(), len.try_into()) else {
+    return Err(io::Error::from(ErrorKind::InvalidInput).into());
+  };
+  // SAFETY: the fd is open for the duration of the call.
+  let ret = unsafe {
+    libc::fallocate(
+      file.as_raw_fd(),
+      libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE,
+      offset,
+      len,
+    )
+  };
+  if ret != 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+/// Deallocates the disk space of a range of the file, which reads as zeros
+/// afterwards. The size of the file does not change.
+///
+/// The range must be aligned to the block size of the file system.
+#[cfg(target_os = "macos")]
+fn punch_hole(file: &StdFile, offset: u64, len: u64) -> FsResult<()> {
+  use std::os::unix::io::AsRawFd;
+  let (Ok(fp_offset), Ok(fp_length)) = (offset.try_into(), len.try_into())
+  else {
+    return Err(io::Error::from(ErrorKind::InvalidInput).into());
+  };
+  let args = libc::fpunchhole_t {
+    fp_flags: 0,
+    reserved: 0,
+    fp_offset,
+    fp_length,
+  };
+  // SAFETY: the fd is open and `args` is valid for the duration of the call.
+  let ret = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_PUNCHHOLE, &args) };
+  if ret != 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+/// Deallocates the disk space of a range of the file, which reads as zeros
+/// afterwards. The size of the file does not change.
+///
+/// The file is marked as sparse first, which NTFS requires to free the range.
+#[cfg(windows)]
+fn punch_hole(file: &StdFile, offset: u64, len: u64) -> FsResult<()> {
+  use std::os::windows::io::AsRawHandle;
+  use winapi::um::ioapiset::DeviceIoControl;
+  use winapi::um::winioctl::FILE_ZERO_DATA_INFORMATION;
+  use winapi::um::winioctl::FSCTL_SET_SPARSE;
+  use winapi::um::winioctl::FSCTL_SET_ZERO_DATA;
+
+  let Some(end) = offset
+    .checked_add(len)
+    .filter(|end| *end <= i64::MAX as u64)
+  else {
+    return Err(io::Error::from(ErrorKind::InvalidInput).into());
+  };
+  let handle = file.as_raw_handle();
+  let mut bytes_returned = 0;
+  // SAFETY: the handle is open for the duration of the call, and no input or
+  // output buffers are passed.
+  let ret = unsafe {
+    DeviceIoControl(
+      handle,
+      FSCTL_SET_SPARSE,
+      std::ptr::null_mut(),
+      0,
+      std::ptr::null_mut(),
+      0,
+      &mut bytes_returned,
+      std::ptr::null_mut(),
+    )
+  };
+  if ret == 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+
+  // SAFETY: FILE_ZERO_DATA_INFORMATION is a plain C struct.
+  let mut info: FILE_ZERO_DATA_INFORMATION = unsafe { std::mem::zeroed() };
+  // SAFETY: LARGE_INTEGER is a union of integers of the same size.
+  unsafe {
+    *info.FileOffset.QuadPart_mut() = offset as i64;
+    *info.BeyondFinalZero.QuadPart_mut() = end as i64;
+  }
+  // SAFETY: the handle is open and `info` is valid for the duration of the
+  // call.
+  let ret = unsafe {
+    DeviceIoControl(
+      handle,
+      FSCTL_SET_ZERO_DATA,
+      &mut info as *mut _ as *mut _,
+      std::mem::size_of::<FILE_ZERO_DATA_INFORMATION>() as u32,
+      std::ptr::null_mut(),
+      0,
+      &mut bytes_returned,
+      std::ptr::null_mut(),
+    )
+  };
+  if ret == 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "android",
+  target_os = "macos",
+  windows
+)))]
+fn punch_hole(_file: &StdFile, _offset: u64, _len: u64) -> FsResult<()> {
+  Err(FsError::NotSupported)
+}
+
 // override op_print to use the stdout and stderr in the resource table
 #[op2(fast)]
 pub fn op_print(
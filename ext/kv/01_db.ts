@@ -6,16 +6,23 @@ const {
 } = core;
 import {
   op_kv_atomic_write,
+  op_kv_changes_next,
+  op_kv_changes_subscribe,
   op_kv_database_open,
+  op_kv_database_restore,
+  op_kv_database_snapshot,
   op_kv_dequeue_next_message,
   op_kv_encode_cursor,
   op_kv_finish_dequeued_message,
   op_kv_snapshot_read,
   op_kv_watch,
   op_kv_watch_next,
+  op_kv_watch_prefix,
+  op_kv_watch_prefix_next,
 } from "ext:core/ops";
 const {
   ArrayFrom,
+  ArrayIsArray,
   ArrayPrototypeMap,
   ArrayPrototypePush,
   ArrayPrototypeReverse,
@@ -24,12 +31,19 @@ const {
   BigInt,
   BigIntPrototypeToString,
   Error,
+  MathCeil,
+  MathMax,
+  MathMin,
+  NumberIsInteger,
   NumberIsNaN,
   Object,
   ObjectFreeze,
   ObjectGetPrototypeOf,
   ObjectHasOwn,
   ObjectPrototypeIsPrototypeOf,
+  Promise,
+  PromisePrototypeCatch,
+  PromisePrototypeThen,
   RangeError,
   SafeMap,
   SafeMapIterator,
@@ -40,10 +54,18 @@ const {
   SymbolToStringTag,
   TypeError,
   TypedArrayPrototypeGetSymbolToStringTag,
+  TypedArrayPrototypeSet,
+  TypedArrayPrototypeSubarray,
+  Uint8Array,
 } = primordials;
 
 import { SymbolDispose } from "ext:deno_web/00_infra.js";
-import { ReadableStream } from "ext:deno_web/06_streams.js";
+import {
+  ReadableStream,
+  ReadableStreamPrototype,
+} from "ext:deno_web/06_streams.js";
+import { BlobPrototype } from "ext:deno_web/09_file.js";
+import { clearTimeout, setTimeout } from "ext:deno_web/02_timers.js";
 
 const encodeCursor: (
   selector: [Deno.KvKey | null, Deno.KvKey | null, Deno.KvKey | null],
@@ -51,9 +73,9 @@ const encodeCursor: (
 ) => string = (selector, boundaryKey) =>
   op_kv_encode_cursor(selector, boundaryKey);
 
-async function openKv(path: string) {
-  const rid = await op_kv_database_open(path);
-  return new Kv(rid, kvSymbol);
+async function openKv(path: string, options?: Deno.KvOpenOptions) {
+  const rid = await op_kv_database_open(path, options);
+  return new Kv(rid, kvSymbol, options?.limits?.maxValueSize);
 }
 
 const maxQueueDelay = 30 * 24 * 60 * 60 * 1000;
@@ -88,6 +110,78 @@ function validateBackoffSchedule(backoffSchedule: number[]) {
   }
 }
 
+// The retry policy of the backend when no backoffSchedule is given.
+const defaultQueueBackoffSchedule = [100, 1000, 5000, 30000, 60000];
+const maxQueueDeliveryAttempts = maxQueueBackoffIntervals + 1;
+
+interface EnqueueOptions {
+  delay?: number;
+  keysIfUndelivered?: Deno.KvKey[];
+  backoffSchedule?: number[];
+  maxAttempts?: number;
+}
+
+function queueBackoffSchedule(opts?: EnqueueOptions): number[] | null {
+  const backoffSchedule = opts?.backoffSchedule;
+  if (backoffSchedule !== undefined) {
+    validateBackoffSchedule(backoffSchedule);
+  }
+  const maxAttempts = opts?.maxAttempts;
+  if (maxAttempts === undefined) {
+    return backoffSchedule ?? null;
+  }
+  if (
+    !NumberIsInteger(maxAttempts) || maxAttempts < 1 ||
+    maxAttempts > maxQueueDeliveryAttempts
+  ) {
+    throw new TypeError("invalid maxAttempts");
+  }
+  return ArrayPrototypeSlice(
+    backoffSchedule ?? defaultQueueBackoffSchedule,
+    0,
+    maxAttempts - 1,
+  );
+}
+
+function validateListenQueueOptions(
+  options?: { visibilityTimeout?: number; batchSize?: number },
+) {
+  const visibilityTimeout = options?.visibilityTimeout;
+  if (
+    visibilityTimeout !== undefined &&
+    !(visibilityTimeout > 0 && visibilityTimeout <= maxQueueBackoffInterval)
+  ) {
+    throw new TypeError("invalid visibilityTimeout");
+  }
+  const batchSize = options?.batchSize;
+  if (
+    batchSize !== undefined && !(NumberIsInteger(batchSize) && batchSize >= 1)
+  ) {
+    throw new TypeError("invalid batchSize");
+  }
+}
+
+/** Rejects if `result` does not settle within `timeout` milliseconds. */
+function withVisibilityTimeout(
+  result: Promise<void>,
+  timeout: number,
+): Promise<void> {
+  return new Promise((resolve, reject) => {
+    const timer = setTimeout(() => {
+      reject(
+        new Error(`Queue handler did not finish within ${timeout}ms`),
+      );
+    }, timeout);
+    PromisePrototypeThen(result, (value) => {
+      clearTimeout(timer);
+      resolve(value);
+    }, (error) => {
+      clearTimeout(timer);
+      reject(error);
+    });
+  });
+}
+
 interface RawKvEntry {
   key: Deno.KvKey;
   value: RawValue;
@@ -107,12 +201,81 @@ type RawValue = {
 
 const kvSymbol = Symbol("KvRid");
 const commitVersionstampSymbol = Symbol("KvCommitVersionstamp");
+const snapshotRidSymbol = Symbol("KvSnapshotRid");
+
+const defaultMaxValueSize = 65536;
+const maxChunkedReadAttempts = 10;
+const maxChunkedWriteAttempts = 10;
+
+function chunkKey(key: Deno.KvKey, index: number): Deno.KvKey {
+  const chunk = ArrayPrototypeSlice(key);
+  ArrayPrototypePush(chunk, BigInt(index));
+  return chunk;
+}
+
+/**
+ * Splits a blob value into chunks of `chunkSize` bytes, the last of which may
+ * be shorter, and passes them to `fn` one at a time.
+ */
+async function forEachBlobChunk(
+  value: Blob | ReadableStream<Uint8Array>,
+  chunkSize: number,
+  fn: (chunk: Uint8Array) => Promise<void>,
+) {
+  let stream;
+  if (ObjectPrototypeIsPrototypeOf(BlobPrototype, value)) {
+    stream = value.stream();
+  } else if (ObjectPrototypeIsPrototypeOf(ReadableStreamPrototype, value)) {
+    stream = value;
+  } else {
+    throw new TypeError("value must be a Uint8Array, Blob or ReadableStream");
+  }
+  const reader = stream.getReader();
+  try {
+    let buffer = new Uint8Array(chunkSize);
+    let filled = 0;
+    while (true) {
+      const { done, value: data } = await reader.read();
+      if (done) break;
+      if (TypedArrayPrototypeGetSymbolToStringTag(data) !== "Uint8Array") {
+        throw new TypeError("stream must only contain Uint8Array chunks");
+      }
+      let offset = 0;
+      while (offset < data.byteLength) {
+        const length = MathMin(chunkSize - filled, data.byteLength - offset);
+        TypedArrayPrototypeSet(
+          buffer,
+          TypedArrayPrototypeSubarray(data, offset, offset + length),
+          filled,
+        );
+        filled += length;
+        offset += length;
+        if (filled === chunkSize) {
+          await fn(buffer);
+          buffer = new Uint8Array(chunkSize);
+          filled = 0;
+        }
+      }
+    }
+    if (filled > 0) {
+      await fn(TypedArrayPrototypeSubarray(buffer, 0, filled));
+    }
+  } catch (err) {
+    await PromisePrototypeCatch(reader.cancel(err), () => {});
+    throw err;
+  }
+}
 
 class Kv {
   #rid: number;
   #isClosed: boolean;
+  #maxValueSize: number;
 
-  constructor(rid: number = undefined, symbol: symbol = undefined) {
+  constructor(
+    rid: number = undefined,
+    symbol: symbol = undefined,
+    maxValueSize: number = defaultMaxValueSize,
+  ) {
     if (kvSymbol !== symbol) {
       throw new TypeError(
         "Deno.Kv can not be constructed, use Deno.openKv instead.",
@@ -120,6 +283,7 @@ class Kv {
     }
     this.#rid = rid;
     this.#isClosed = false;
+    this.#maxValueSize = maxValueSize;
   }
 
   atomic() {
@@ -202,6 +366,197 @@ class Kv {
     if (!result) throw new TypeError("Failed to set value");
   }
 
+  async getChunked(
+    key: Deno.KvKey,
+    opts?: { consistency?: Deno.KvConsistencyLevel },
+  ): Promise<Deno.KvEntryMaybe<Uint8Array>> {
+    const { chunks, versionstamp } = await this.#readChunks(
+      key,
+      opts?.consistency ?? "strong",
+    );
+    if (versionstamp === null) {
+      return { key, value: null, versionstamp: null };
+    }
+    let byteLength = 0;
+    for (let i = 0; i < chunks.length; ++i) {
+      byteLength += chunks[i].byteLength;
+    }
+    const value = new Uint8Array(byteLength);
+    let offset = 0;
+    for (let i = 0; i < chunks.length; ++i) {
+      TypedArrayPrototypeSet(value, chunks[i], offset);
+      offset += chunks[i].byteLength;
+    }
+    return { key, value, versionstamp };
+  }
+
+  async setChunked(
+    key: Deno.KvKey,
+    value: Uint8Array,
+    options?: { expireIn?: number },
+  ) {
+    if (TypedArrayPrototypeGetSymbolToStringTag(value) !== "Uint8Array") {
+      throw new TypeError("value must be a Uint8Array");
+    }
+    const chunkSize = this.#maxValueSize;
+    const count = MathMax(1, MathCeil(value.byteLength / chunkSize));
+    const chunks = [];
+    for (let i = 0; i < count; ++i) {
+      ArrayPrototypePush(
+        chunks,
+        TypedArrayPrototypeSubarray(value, i * chunkSize, (i + 1) * chunkSize),
+      );
+    }
+    return await this.#writeChunks(key, chunks, options?.expireIn);
+  }
+
+  async deleteChunked(key: Deno.KvKey) {
+    await this.#writeChunks(key, [], undefined);
+  }
+
+  async setBlob(
+    key: Deno.KvKey,
+    value: Uint8Array | Blob | ReadableStream<Uint8Array>,
+    options?: { expireIn?: number },
+  ) {
+    if (TypedArrayPrototypeGetSymbolToStringTag(value) === "Uint8Array") {
+      return await this.setChunked(key, value, options);
+    }
+    const chunks = [];
+    await forEachBlobChunk(value, this.#maxValueSize, async (chunk) => {
+      ArrayPrototypePush(chunks, chunk);
+    });
+    if (chunks.length === 0) {
+      ArrayPrototypePush(chunks, new Uint8Array(0));
+    }
+    return await this.#writeChunks(key, chunks, options?.expireIn);
+  }
+
+  async getBlob(
+    key: Deno.KvKey,
+    opts?: { consistency?: Deno.KvConsistencyLevel },
+  ): Promise<Deno.KvEntryMaybe<Uint8Array>> {
+    return await this.getChunked(key, opts);
+  }
+
+  async getBlobStream(
+    key: Deno.KvKey,
+    opts?: { consistency?: Deno.KvConsistencyLevel },
+  ): Promise<ReadableStream<Uint8Array> | null> {
+    const { chunks, versionstamp } = await this.#readChunks(
+      key,
+      opts?.consistency ?? "strong",
+    );
+    if (versionstamp === null) return null;
+    let index = 0;
+    return new ReadableStream({
+      pull(controller) {
+        if (index === chunks.length) {
+          controller.close();
+        } else {
+          controller.enqueue(chunks[index++]);
+        }
+      },
+    });
+  }
+
+  async deleteBlob(key: Deno.KvKey) {
+    await this.deleteChunked(key);
+  }
+
+  /**
+   * Replaces the chunks of a chunked value with `chunks` in a single atomic
+   * operation, deleting the chunks of the previous value that are left over.
+   * The write is retried a bounded number of times if another write to the
+   * same value gets in between.
+   */
+  async #writeChunks(
+    key: Deno.KvKey,
+    chunks: Uint8Array[],
+    expireIn: number | undefined,
+  ) {
+    for (let attempt = 0; attempt < maxChunkedWriteAttempts; ++attempt) {
+      const existing = await this.#readChunks(key, "strong");
+      const mutations = [];
+      for (let i = 0; i < chunks.length; ++i) {
+        ArrayPrototypePush(mutations, [
+          chunkKey(key, i),
+          "set",
+          serializeValue(chunks[i]),
+          expireIn,
+        ]);
+      }
+      for (let i = chunks.length; i < existing.chunks.length; ++i) {
+        ArrayPrototypePush(mutations, [
+          chunkKey(key, i),
+          "delete",
+          null,
+          undefined,
+        ]);
+      }
+      const versionstamp = await doAtomicWriteInPlace(
+        this.#rid,
+        [[chunkKey(key, 0), existing.versionstamp]],
+        mutations,
+        [],
+      );
+      if (versionstamp !== null) return { ok: true, versionstamp };
+    }
+    throw new Error(
+      "Failed to write chunked value: it is being modified concurrently",
+    );
+  }
+
+  /**
+   * Reads the chunks of a value written by `setChunked()`. All chunks are
+   * written by a single atomic operation, so they share one versionstamp; a
+   * read that spans several batches and observes a concurrent write is
+   * retried.
+   */
+  async #readChunks(
+    key: Deno.KvKey,
+    consistency: Deno.KvConsistencyLevel,
+  ): Promise<{ chunks: Uint8Array[]; versionstamp: string | null }> {
+    for (let attempt = 0; attempt < maxChunkedReadAttempts; ++attempt) {
+      const iter = this.list({ prefix: key }, {
+        batchSize: 1000,
+        consistency,
+      });
+      const chunks: Uint8Array[] = [];
+      let versionstamp = null;
+      let consistent = true;
+      while (true) {
+        const { done, value: entry } = await iter.next();
+        if (done) break;
+        if (
+          entry.key.length !== key.length + 1 ||
+          typeof entry.key[key.length] !== "bigint"
+        ) {
+          continue;
+        }
+        if (
+          TypedArrayPrototypeGetSymbolToStringTag(entry.value) !==
+            "Uint8Array"
+        ) {
+          throw new TypeError("Entry is not a chunk of a chunked value");
+        }
+        if (
+          entry.key[key.length] !== BigInt(chunks.length) ||
+          (versionstamp !== null && entry.versionstamp !== versionstamp)
+        ) {
+          consistent = false;
+          break;
+        }
+        versionstamp = entry.versionstamp;
+        ArrayPrototypePush(chunks, entry.value);
+      }
+      if (consistent) return { chunks, versionstamp };
+    }
+    throw new Error(
+      "Failed to read chunked value: chunks are missing or are being modified",
+    );
+  }
+
   list(
     selector: Deno.KvListSelector,
     options: {
@@ -255,20 +610,11 @@ class Kv {
     };
   }
 
-  async enqueue(
-    message: unknown,
-    opts?: {
-      delay?: number;
-      keysIfUndelivered?: Deno.KvKey[];
-      backoffSchedule?: number[];
-    },
-  ) {
+  async enqueue(message: unknown, opts?: EnqueueOptions) {
     if (opts?.delay !== undefined) {
       validateQueueDelay(opts?.delay);
     }
-    if (opts?.backoffSchedule !== undefined) {
-      validateBackoffSchedule(opts?.backoffSchedule);
-    }
+    const backoffSchedule = queueBackoffSchedule(opts);
 
     const versionstamp = await doAtomicWriteInPlace(
       this.#rid,
@@ -279,7 +625,7 @@ class Kv {
           core.serialize(message, { forStorage: true }),
           opts?.delay ?? 0,
           opts?.keysIfUndelivered ?? [],
-          opts?.backoffSchedule ?? null,
+          backoffSchedule,
         ],
       ],
     );
@@ -289,12 +635,25 @@ class Kv {
 
   async listenQueue(
     handler: (message: unknown) => Promise<void> | void,
+    options?: { visibilityTimeout?: number; batchSize?: number },
   ): Promise<void> {
     if (this.#isClosed) {
       throw new Error("already closed");
     }
+    validateListenQueueOptions(options);
+    const visibilityTimeout = options?.visibilityTimeout;
+    const batchSize = options?.batchSize ?? Infinity;
     const finishMessageOps = new SafeMap<number, Promise<void>>();
+    let handling = 0;
+    let onHandled: (() => void) | null = null;
     while (true) {
+      // Wait until fewer than `batchSize` messages are being handled.
+      while (handling >= batchSize) {
+        await new Promise<void>((resolve) => {
+          onHandled = resolve;
+        });
+      }
+
       // Wait for the next message.
       const next: { 0: Uint8Array; 1: number } =
         await op_kv_dequeue_next_message(
@@ -311,15 +670,23 @@ class Kv {
       });
 
       // Dispatch the payload.
+      handling++;
       (async () => {
         let success = false;
         try {
           const result = handler(deserializedPayload);
-          const _res = isPromise(result) ? (await result) : result;
+          if (isPromise(result)) {
+            await (visibilityTimeout === undefined
+              ? result
+              : withVisibilityTimeout(result, visibilityTimeout));
+          }
           success = true;
         } catch (error) {
           console.error("Exception in queue handler", error);
         } finally {
+          handling--;
+          onHandled?.();
+          onHandled = null;
           const promise: Promise<void> = op_kv_finish_dequeued_message(
             handleId,
             success,
@@ -340,7 +707,13 @@ class Kv {
     finishMessageOps.clear();
   }
 
-  watch(keys: Deno.KvKey[], options = { __proto__: null }) {
+  watch(
+    keys: Deno.KvKey[] | Deno.KvWatchPrefixSelector,
+    options = { __proto__: null },
+  ) {
+    if (!ArrayIsArray(keys)) {
+      return this.#watchPrefix(keys.prefix, options.resumeToken);
+    }
     const raw = options.raw ?? false;
     const rid = op_kv_watch(this.#rid, keys);
     const lastEntries: (Deno.KvEntryMaybe<unknown> | undefined)[] = ArrayFrom(
@@ -408,6 +781,86 @@ class Kv {
     });
   }
 
+  #watchPrefix(prefix: Deno.KvKey, resumeToken?: string) {
+    const rid = op_kv_watch_prefix(this.#rid, prefix, resumeToken);
+    return new ReadableStream({
+      async pull(controller) {
+        let batch;
+        try {
+          batch = await op_kv_watch_prefix_next(rid);
+        } catch (err) {
+          core.tryClose(rid);
+          controller.error(err);
+          return;
+        }
+        if (batch === null) {
+          core.tryClose(rid);
+          controller.close();
+          return;
+        }
+        const changes = ArrayPrototypeMap(
+          batch.changes,
+          (entry) =>
+            entry.versionstamp === null
+              ? { key: entry.key, value: null, versionstamp: null }
+              : deserializeValue(entry),
+        );
+        controller.enqueue({ changes, resumeToken: batch.resumeToken });
+      },
+      cancel() {
+        core.tryClose(rid);
+      },
+    });
+  }
+
+  changes(): ReadableStream<Deno.KvCommit> {
+    const rid = op_kv_changes_subscribe(this.#rid);
+    return new ReadableStream({
+      async pull(controller) {
+        let commit;
+        try {
+          commit = await op_kv_changes_next(rid);
+        } catch (err) {
+          core.tryClose(rid);
+          controller.error(err);
+          return;
+        }
+        if (commit === null) {
+          core.tryClose(rid);
+          controller.close();
+          return;
+        }
+        const changes = ArrayPrototypeMap(
+          commit.changes,
+          (change) => ({
+            key: change.key,
+            type: change.type,
+            value: change.value === null
+              ? null
+              : deserializeValue(change).value,
+            expireAt: change.expireAt ?? undefined,
+          }),
+        );
+        controller.enqueue({ versionstamp: commit.versionstamp, changes });
+      },
+      cancel() {
+        core.tryClose(rid);
+      },
+    });
+  }
+
+  async snapshot(): Promise<Deno.KvSnapshot> {
+    const rid = await op_kv_database_snapshot(this.#rid);
+    return new KvSnapshot(rid);
+  }
+
+  async restore(snapshot: Deno.KvSnapshot): Promise<void> {
+    if (!ObjectPrototypeIsPrototypeOf(KvSnapshotPrototype, snapshot)) {
+      throw new TypeError("snapshot must be a Deno.KvSnapshot");
+    }
+    await op_kv_database_restore(this.#rid, snapshot[snapshotRidSymbol]);
+  }
+
   close() {
     core.close(this.#rid);
     this.#isClosed = true;
@@ -418,6 +871,27 @@ class Kv {
   }
 }
 
+class KvSnapshot {
+  [snapshotRidSymbol]: number;
+
+  constructor(rid: number) {
+    this[snapshotRidSymbol] = rid;
+  }
+
+  close() {
+    core.close(this[snapshotRidSymbol]);
+  }
+
+  [SymbolDispose]() {
+    core.tryClose(this[snapshotRidSymbol]);
+  }
+
+  get [SymbolToStringTag]() {
+    return "Deno.KvSnapshot";
+  }
+}
+const KvSnapshotPrototype = KvSnapshot.prototype;
+
 class AtomicOperation {
   #rid: number;
 
@@ -522,25 +996,16 @@ class AtomicOperation {
     return this;
   }
 
-  enqueue(
-    message: unknown,
-    opts?: {
-      delay?: number;
-      keysIfUndelivered?: Deno.KvKey[];
-      backoffSchedule?: number[];
-    },
-  ): this {
+  enqueue(message: unknown, opts?: EnqueueOptions): this {
     if (opts?.delay !== undefined) {
       validateQueueDelay(opts?.delay);
     }
-    if (opts?.backoffSchedule !== undefined) {
-      validateBackoffSchedule(opts?.backoffSchedule);
-    }
+    const backoffSchedule = queueBackoffSchedule(opts);
     ArrayPrototypePush(this.#enqueues, [
       core.serialize(message, { forStorage: true }),
       opts?.delay ?? 0,
       opts?.keysIfUndelivered ?? [],
-      opts?.backoffSchedule ?? null,
+      backoffSchedule,
     ]);
     return this;
   }
@@ -0,0 +1,120 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Change data capture for `Deno.Kv`. Every atomic operation committed through
+//! this extension is published to a [`KvChangeFeed`], so that it can be
+//! replicated into external systems.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Utc;
+use denokv_proto::KvValue;
+use denokv_proto::Mutation;
+use denokv_proto::MutationKind;
+use tokio::sync::broadcast;
+
+/// How many commits a subscriber can fall behind before it misses some.
+const CHANGE_FEED_CAPACITY: usize = 1024;
+
+/// An atomic operation that was committed to a database.
+#[derive(Clone, Debug)]
+pub struct KvCommit {
+  /// The path the database was opened with, or `None` for the default
+  /// database. In-memory databases are named `:memory:#<n>`, so that commits
+  /// to different ones can be told apart.
+  pub database: Option<String>,
+  pub versionstamp: [u8; 10],
+  pub changes: Vec<KvChange>,
+}
+
+/// A single mutation of a committed atomic operation.
+#[derive(Clone, Debug)]
+pub struct KvChange {
+  /// The encoded key.
+  pub key: Vec<u8>,
+  pub op: KvChangeOp,
+  pub expire_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug)]
+pub enum KvChangeOp {
+  Set(KvValue),
+  Delete,
+  Sum(KvValue),
+  Min(KvValue),
+  Max(KvValue),
+  /// Sets the key suffixed with the versionstamp of the commit.
+  SetSuffixVersionstampedKey(KvValue),
+}
+
+impl From<&Mutation> for KvChange {
+  fn from(mutation: &Mutation) -> Self {
+    let op = match &mutation.kind {
+      MutationKind::Set(value) => KvChangeOp::Set(value.clone()),
+      MutationKind::Delete => KvChangeOp::Delete,
+      MutationKind::Sum { value, .. } => KvChangeOp::Sum(value.clone()),
+      MutationKind::Min(value) => KvChangeOp::Min(value.clone()),
+      MutationKind::Max(value) => KvChangeOp::Max(value.clone()),
+      MutationKind::SetSuffixVersionstampedKey(value) => {
+        KvChangeOp::SetSuffixVersionstampedKey(value.clone())
+      }
+    };
+    KvChange {
+      key: mutation.key.clone(),
+      op,
+      expire_at: mutation.expire_at,
+    }
+  }
+}
+
+/// Publishes the atomic operations committed through `Deno.Kv` to
+/// subscribers. Clones share their subscribers, so a single feed can be given
+/// to every worker of a process.
+///
+/// Only commits made through this process are published; writes by other
+/// processes to the same database are not observed.
+#[derive(Clone)]
+pub struct KvChangeFeed {
+  sender: broadcast::Sender<Arc<KvCommit>>,
+}
+
+impl Default for KvChangeFeed {
+  fn default() -> Self {
+    let (sender, _) = broadcast::channel(CHANGE_FEED_CAPACITY);
+    Self { sender }
+  }
+}
+
+impl KvChangeFeed {
+  /// Subscribes to the commits made from now on, in the order they complete.
+  /// A receiver that falls too far behind gets a
+  /// [`broadcast::error::RecvError::Lagged`] error and misses the oldest
+  /// commits.
+  pub fn subscribe(&self) -> broadcast::Receiver<Arc<KvCommit>> {
+    self.sender.subscribe()
+  }
+
+  pub(crate) fn has_subscribers(&self) -> bool {
+    self.sender.receiver_count() > 0
+  }
+
+  pub(crate) fn publish(&self, commit: KvCommit) {
+    // there may be no subscribers left, which is fine
+    let _ = self.sender.send(Arc::new(commit));
+  }
+}
+
+/// The name under which commits to a database opened with `path` are
+/// published.
+pub(crate) fn database_name(path: Option<&str>) -> Option<String> {
+  static NEXT_MEMORY_DATABASE: AtomicU64 = AtomicU64::new(0);
+  match path {
+    Some(":memory:") => Some(format!(
+      ":memory:#{}",
+      NEXT_MEMORY_DATABASE.fetch_add(1, Ordering::Relaxed)
+    )),
+    path => path.map(ToOwned::to_owned),
+  }
+}
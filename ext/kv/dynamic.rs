@@ -1,14 +1,18 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 
+use crate::remote::RemoteDbHandler;
 use crate::remote::RemoteDbHandlerPermissions;
 use crate::sqlite::SqliteDbHandler;
 use crate::sqlite::SqliteDbHandlerPermissions;
 use crate::AtomicWrite;
 use crate::Database;
 use crate::DatabaseHandler;
+use crate::OpenOptions;
 use crate::QueueMessageHandle;
 use crate::ReadRange;
 use crate::SnapshotReadOptions;
@@ -20,14 +24,13 @@ use denokv_proto::CommitResult;
 use denokv_proto::ReadRangeOutput;
 use denokv_proto::WatchStream;
 
+/// Opens each database with the first backend that handles its path.
 pub struct MultiBackendDbHandler {
-  backends: Vec<(&'static [&'static str], Box<dyn DynamicDbHandler>)>,
+  backends: Vec<Arc<dyn KvBackend>>,
 }
 
 impl MultiBackendDbHandler {
-  pub fn new(
-    backends: Vec<(&'static [&'static str], Box<dyn DynamicDbHandler>)>,
-  ) -> Self {
+  pub fn new(backends: Vec<Arc<dyn KvBackend>>) -> Self {
     Self { backends }
   }
 
@@ -39,111 +42,131 @@ impl MultiBackendDbHandler {
     http_options: crate::remote::HttpOptions,
   ) -> Self {
     Self::new(vec![
-      (
-        &["https://", "http://"],
-        Box::new(crate::remote::RemoteDbHandler::<P>::new(http_options)),
-      ),
-      (
-        &[""],
-        Box::new(SqliteDbHandler::<P>::new(
-          default_storage_dir,
-          versionstamp_rng_seed,
-        )),
-      ),
+      Arc::new(RemoteDbHandler::<P>::new(http_options)),
+      Arc::new(SqliteDbHandler::<P>::new(
+        default_storage_dir,
+        versionstamp_rng_seed,
+      )),
     ])
   }
-}
 
-#[async_trait(?Send)]
-impl DatabaseHandler for MultiBackendDbHandler {
-  type DB = RcDynamicDb;
-
-  async fn open(
-    &self,
-    state: Rc<RefCell<OpState>>,
-    path: Option<String>,
-  ) -> Result<Self::DB, AnyError> {
-    for (prefixes, handler) in &self.backends {
-      for &prefix in *prefixes {
-        if prefix.is_empty() {
-          return handler.dyn_open(state.clone(), path.clone()).await;
-        }
-        let Some(path) = &path else {
-          continue;
-        };
-        if path.starts_with(prefix) {
-          return handler.dyn_open(state.clone(), Some(path.clone())).await;
-        }
-      }
-    }
-    Err(type_error(format!(
-      "No backend supports the given path: {:?}",
-      path
-    )))
+  /// Adds backends provided by an embedder. They take precedence over the
+  /// backends that were already registered, so that they can claim paths the
+  /// built-in SQLite backend would otherwise handle.
+  pub fn with_backends(
+    mut self,
+    backends: &[Arc<dyn KvBackend + Send + Sync>],
+  ) -> Self {
+    let backends = backends
+      .iter()
+      .map(|backend| backend.clone() as Arc<dyn KvBackend>);
+    self.backends.splice(0..0, backends);
+    self
   }
 }
 
+/// A storage engine for `Deno.openKv()`, like the built-in SQLite and remote
+/// backends, or one that an embedder provides to store data in Postgres or
+/// FoundationDB. Embedders register them with the `kv_backends` option of
+/// the workers.
 #[async_trait(?Send)]
-pub trait DynamicDbHandler {
-  async fn dyn_open(
-    &self,
-    state: Rc<RefCell<OpState>>,
-    path: Option<String>,
-  ) -> Result<RcDynamicDb, AnyError>;
-}
-
-#[async_trait(?Send)]
-impl DatabaseHandler for Box<dyn DynamicDbHandler> {
-  type DB = RcDynamicDb;
+pub trait KvBackend {
+  /// The path prefixes handled by this backend, ex. `&["postgres://"]`. The
+  /// empty prefix handles every path, and `Deno.openKv()` without a path.
+  fn prefixes(&self) -> &'static [&'static str];
 
+  /// Opens the database at `path`. Backends check the permissions they need
+  /// against the permissions in `state`.
   async fn open(
     &self,
     state: Rc<RefCell<OpState>>,
     path: Option<String>,
-  ) -> Result<Self::DB, AnyError> {
-    (**self).dyn_open(state, path).await
-  }
+    options: OpenOptions,
+  ) -> Result<Box<dyn KvDatabase>, AnyError>;
 }
 
+/// A database opened by a [`KvBackend`].
+///
+/// Keys and values are passed in their encoded form, and the backend only
+/// needs to order keys by their bytes.
 #[async_trait(?Send)]
-impl<T, DB> DynamicDbHandler for T
-where
-  T: DatabaseHandler<DB = DB>,
-  DB: Database + 'static,
-{
-  async fn dyn_open(
-    &self,
-    state: Rc<RefCell<OpState>>,
-    path: Option<String>,
-  ) -> Result<RcDynamicDb, AnyError> {
-    Ok(RcDynamicDb(Rc::new(self.open(state, path).await?)))
-  }
-}
-
-#[async_trait(?Send)]
-pub trait DynamicDb {
-  async fn dyn_snapshot_read(
+pub trait KvDatabase {
+  /// Reads the entries of every range from the same snapshot of the
+  /// database. Entries whose mutation set an `expire_at` that has passed
+  /// must not be returned, even if the backend deletes them later.
+  async fn snapshot_read(
     &self,
     requests: Vec<ReadRange>,
     options: SnapshotReadOptions,
   ) -> Result<Vec<ReadRangeOutput>, AnyError>;
 
-  async fn dyn_atomic_write(
+  /// Applies the mutations and enqueues of `write` atomically if all of its
+  /// checks pass, and returns the new versionstamp of the database, which
+  /// must be greater than any it returned before. Returns `None` if a check
+  /// failed, in which case nothing is written.
+  async fn atomic_write(
     &self,
     write: AtomicWrite,
   ) -> Result<Option<CommitResult>, AnyError>;
 
-  async fn dyn_dequeue_next_message(
+  /// Waits for the next message of the queue whose deadline has passed.
+  /// Returns `None` once the database is closed.
+  async fn dequeue_next_message(
     &self,
   ) -> Result<Option<Box<dyn QueueMessageHandle>>, AnyError>;
 
-  fn dyn_watch(&self, keys: Vec<Vec<u8>>) -> WatchStream;
+  /// Returns a stream that yields the entries of `keys` once, and then every
+  /// time one of them changes.
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream;
 
-  fn dyn_close(&self);
+  /// Stops the background work of the database and ends its watch streams
+  /// and pending dequeues.
+  fn close(&self);
+
+  /// Returns the database as `Any`, for features that only some backends
+  /// support, see [`RcDynamicDb::downcast_ref`].
+  fn as_any(&self) -> &dyn Any;
+}
+
+#[async_trait(?Send)]
+impl DatabaseHandler for MultiBackendDbHandler {
+  type DB = RcDynamicDb;
+
+  async fn open(
+    &self,
+    state: Rc<RefCell<OpState>>,
+    path: Option<String>,
+    options: OpenOptions,
+  ) -> Result<Self::DB, AnyError> {
+    for backend in &self.backends {
+      for &prefix in backend.prefixes() {
+        let handles_path = match &path {
+          Some(path) => path.starts_with(prefix),
+          None => prefix.is_empty(),
+        };
+        if handles_path {
+          let db = backend.open(state, path, options).await?;
+          return Ok(RcDynamicDb(Rc::from(db)));
+        }
+      }
+    }
+    Err(type_error(format!(
+      "No backend supports the given path: {:?}",
+      path
+    )))
+  }
 }
 
 #[derive(Clone)]
-pub struct RcDynamicDb(Rc<dyn DynamicDb>);
+pub struct RcDynamicDb(Rc<dyn KvDatabase>);
+
+impl RcDynamicDb {
+  /// Returns the underlying database if it is a `T`, for features that only
+  /// some backends support.
+  pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+    (*self.0).as_any().downcast_ref()
+  }
+}
 
 #[async_trait(?Send)]
 impl Database for RcDynamicDb {
@@ -154,68 +177,251 @@ impl Database for RcDynamicDb {
     requests: Vec<ReadRange>,
     options: SnapshotReadOptions,
   ) -> Result<Vec<ReadRangeOutput>, AnyError> {
-    (*self.0).dyn_snapshot_read(requests, options).await
+    (*self.0).snapshot_read(requests, options).await
   }
 
   async fn atomic_write(
     &self,
     write: AtomicWrite,
   ) -> Result<Option<CommitResult>, AnyError> {
-    (*self.0).dyn_atomic_write(write).await
+    (*self.0).atomic_write(write).await
   }
 
   async fn dequeue_next_message(
     &self,
   ) -> Result<Option<Box<dyn QueueMessageHandle>>, AnyError> {
-    (*self.0).dyn_dequeue_next_message().await
+    (*self.0).dequeue_next_message().await
   }
 
   fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
-    (*self.0).dyn_watch(keys)
+    (*self.0).watch(keys)
   }
 
   fn close(&self) {
-    (*self.0).dyn_close()
+    (*self.0).close()
   }
 }
 
-#[async_trait(?Send)]
-impl<T, QMH> DynamicDb for T
-where
-  T: Database<QMH = QMH>,
-  QMH: QueueMessageHandle + 'static,
-{
-  async fn dyn_snapshot_read(
-    &self,
-    requests: Vec<ReadRange>,
-    options: SnapshotReadOptions,
-  ) -> Result<Vec<ReadRangeOutput>, AnyError> {
-    Ok(self.snapshot_read(requests, options).await?)
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+  use std::num::NonZeroU32;
+
+  use chrono::DateTime;
+  use chrono::Utc;
+  use deno_core::futures::stream;
+  use denokv_proto::Check;
+  use denokv_proto::Consistency;
+  use denokv_proto::KvEntry;
+  use denokv_proto::KvValue;
+  use denokv_proto::Mutation;
+  use denokv_proto::MutationKind;
+
+  use super::*;
+
+  /// A backend that keeps `Bytes` values in memory.
+  struct MemoryBackend {
+    prefixes: &'static [&'static str],
   }
 
-  async fn dyn_atomic_write(
-    &self,
-    write: AtomicWrite,
-  ) -> Result<Option<CommitResult>, AnyError> {
-    Ok(self.atomic_write(write).await?)
+  struct MemoryDb {
+    prefixes: &'static [&'static str],
+    entries: RefCell<BTreeMap<Vec<u8>, MemoryEntry>>,
+    version: RefCell<u64>,
   }
 
-  async fn dyn_dequeue_next_message(
-    &self,
-  ) -> Result<Option<Box<dyn QueueMessageHandle>>, AnyError> {
-    Ok(
+  struct MemoryEntry {
+    value: Vec<u8>,
+    versionstamp: [u8; 10],
+    expire_at: Option<DateTime<Utc>>,
+  }
+
+  #[async_trait(?Send)]
+  impl KvBackend for MemoryBackend {
+    fn prefixes(&self) -> &'static [&'static str] {
+      self.prefixes
+    }
+
+    async fn open(
+      &self,
+      _state: Rc<RefCell<OpState>>,
+      _path: Option<String>,
+      _options: OpenOptions,
+    ) -> Result<Box<dyn KvDatabase>, AnyError> {
+      Ok(Box::new(MemoryDb {
+        prefixes: self.prefixes,
+        entries: Default::default(),
+        version: Default::default(),
+      }))
+    }
+  }
+
+  #[async_trait(?Send)]
+  impl KvDatabase for MemoryDb {
+    async fn snapshot_read(
+      &self,
+      requests: Vec<ReadRange>,
+      _options: SnapshotReadOptions,
+    ) -> Result<Vec<ReadRangeOutput>, AnyError> {
+      let entries = self.entries.borrow();
+      let now = Utc::now();
+      let outputs = requests.into_iter().map(|request| {
+        let range = entries
+          .range(request.start..request.end)
+          .filter(|(_, entry)| entry.expire_at.map_or(true, |at| at > now))
+          .map(|(key, entry)| KvEntry {
+            key: key.clone(),
+            value: KvValue::Bytes(entry.value.clone()),
+            versionstamp: entry.versionstamp,
+          });
+        let entries = if request.reverse {
+          range.rev().take(request.limit.get() as usize).collect()
+        } else {
+          range.take(request.limit.get() as usize).collect()
+        };
+        ReadRangeOutput { entries }
+      });
+      Ok(outputs.collect())
+    }
+
+    async fn atomic_write(
+      &self,
+      write: AtomicWrite,
+    ) -> Result<Option<CommitResult>, AnyError> {
+      let mut entries = self.entries.borrow_mut();
+      for check in write.checks {
+        let versionstamp = entries.get(&check.key).map(|e| e.versionstamp);
+        if versionstamp != check.versionstamp {
+          return Ok(None);
+        }
+      }
+      let mut version = self.version.borrow_mut();
+      *version += 1;
+      let mut versionstamp = [0; 10];
+      versionstamp[..8].copy_from_slice(&version.to_be_bytes());
+      for mutation in write.mutations {
+        match mutation.kind {
+          MutationKind::Set(KvValue::Bytes(value)) => {
+            entries.insert(
+              mutation.key,
+              MemoryEntry {
+                value,
+                versionstamp,
+                expire_at: mutation.expire_at,
+              },
+            );
+          }
+          MutationKind::Delete => {
+            entries.remove(&mutation.key);
+          }
+          _ => return Err(type_error("Unsupported mutation")),
+        }
+      }
+      Ok(Some(CommitResult { versionstamp }))
+    }
+
+    async fn dequeue_next_message(
+      &self,
+    ) -> Result<Option<Box<dyn QueueMessageHandle>>, AnyError> {
+      Ok(None)
+    }
+
+    fn watch(&self, _keys: Vec<Vec<u8>>) -> WatchStream {
+      Box::pin(stream::empty())
+    }
+
+    fn close(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
       self
-        .dequeue_next_message()
-        .await?
-        .map(|x| Box::new(x) as Box<dyn QueueMessageHandle>),
-    )
+    }
   }
 
-  fn dyn_watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
-    self.watch(keys)
+  fn set(key: &[u8], value: &[u8]) -> Mutation {
+    Mutation {
+      key: key.to_vec(),
+      kind: MutationKind::Set(KvValue::Bytes(value.to_vec())),
+      expire_at: None,
+    }
   }
 
-  fn dyn_close(&self) {
-    self.close()
+  async fn read_all(db: &RcDynamicDb) -> Vec<KvEntry> {
+    let mut outputs = db
+      .snapshot_read(
+        vec![ReadRange {
+          start: vec![],
+          end: vec![0xff],
+          limit: NonZeroU32::new(10).unwrap(),
+          reverse: false,
+        }],
+        SnapshotReadOptions {
+          consistency: Consistency::Strong,
+        },
+      )
+      .await
+      .unwrap();
+    outputs.remove(0).entries
+  }
+
+  #[tokio::test(flavor = "current_thread")]
+  async fn opens_databases_of_custom_backends() {
+    let handler = MultiBackendDbHandler::new(vec![Arc::new(MemoryBackend {
+      prefixes: &[""],
+    })])
+    .with_backends(&[Arc::new(MemoryBackend {
+      prefixes: &["memory://"],
+    })]);
+    let state = Rc::new(RefCell::new(OpState::new(None)));
+    let open = |path: Option<&str>| {
+      let db =
+        handler.open(state.clone(), path.map(String::from), Default::default());
+      async move { db.await.unwrap() }
+    };
+
+    let db = open(Some("memory://a")).await;
+    let memory_db = db.downcast_ref::<MemoryDb>().unwrap();
+    assert_eq!(memory_db.prefixes, ["memory://"]);
+    for path in [None, Some("./a.db")] {
+      let db = open(path).await;
+      assert_eq!(db.downcast_ref::<MemoryDb>().unwrap().prefixes, [""]);
+    }
+
+    let commit = db
+      .atomic_write(AtomicWrite {
+        checks: vec![Check {
+          key: b"a".to_vec(),
+          versionstamp: None,
+        }],
+        mutations: vec![
+          set(b"a", b"1"),
+          Mutation {
+            expire_at: Some(Utc::now() - chrono::Duration::seconds(1)),
+            ..set(b"b", b"2")
+          },
+        ],
+        enqueues: vec![],
+      })
+      .await
+      .unwrap()
+      .unwrap();
+    let entries = read_all(&db).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].key, b"a");
+    assert_eq!(entries[0].versionstamp, commit.versionstamp);
+
+    // the check fails now that the key exists
+    let write = AtomicWrite {
+      checks: vec![Check {
+        key: b"a".to_vec(),
+        versionstamp: None,
+      }],
+      mutations: vec![set(b"a", b"3")],
+      enqueues: vec![],
+    };
+    assert!(db.atomic_write(write).await.unwrap().is_none());
+    assert!(matches!(
+      &read_all(&db).await[0].value,
+      KvValue::Bytes(value) if value == b"1"
+    ));
   }
 }
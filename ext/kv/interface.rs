@@ -7,6 +7,44 @@ use async_trait::async_trait;
 use deno_core::error::AnyError;
 use deno_core::OpState;
 use denokv_proto::Database;
+use serde::Deserialize;
+
+/// Options passed as the second argument of `Deno.openKv()`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOptions {
+  /// Tuning for databases backed by a local SQLite file.
+  pub sqlite: Option<SqliteOpenOptions>,
+  /// Overrides for the limits enforced on atomic operations.
+  pub limits: Option<LimitsOpenOptions>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqliteOpenOptions {
+  /// The `journal_mode` pragma, defaults to `wal`.
+  pub journal_mode: Option<String>,
+  /// The `mmap_size` pragma in bytes.
+  pub mmap_size: Option<i64>,
+  /// How long to wait for a locked database, in milliseconds.
+  pub busy_timeout: Option<u64>,
+  /// How often expired keys are deleted from the database file, in
+  /// milliseconds. `0` disables the sweeps.
+  pub expiry_sweep_interval: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitsOpenOptions {
+  /// The maximum number of mutations and enqueues in one atomic operation.
+  pub max_mutations: Option<usize>,
+  /// The maximum size of a single value or enqueued message, in bytes.
+  pub max_value_size: Option<usize>,
+  /// The maximum total size of the keys written by one atomic operation.
+  pub max_total_key_size: Option<usize>,
+  /// The maximum total size of one atomic operation, in bytes.
+  pub max_total_mutation_size: Option<usize>,
+}
 
 #[async_trait(?Send)]
 pub trait DatabaseHandler {
@@ -16,5 +54,6 @@ pub trait DatabaseHandler {
     &self,
     state: Rc<RefCell<OpState>>,
     path: Option<String>,
+    options: OpenOptions,
   ) -> Result<Self::DB, AnyError>;
 }
@@ -1,14 +1,18 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+pub mod changes;
 pub mod dynamic;
 mod interface;
 pub mod remote;
 pub mod sqlite;
 
+use std::any::Any;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::bail;
@@ -55,8 +59,16 @@ use denokv_proto::WatchStream;
 use log::debug;
 use serde::Deserialize;
 use serde::Serialize;
+use tokio::sync::broadcast;
 
+use crate::changes::KvChange;
+use crate::changes::KvChangeFeed;
+use crate::changes::KvChangeOp;
+use crate::changes::KvCommit;
+use crate::dynamic::RcDynamicDb;
 pub use crate::interface::*;
+use crate::sqlite::SqliteDb;
+use crate::sqlite::SqliteSnapshot;
 
 pub const UNSTABLE_FEATURE_NAME: &str = "kv";
 
@@ -64,13 +76,19 @@ const MAX_WRITE_KEY_SIZE_BYTES: usize = 2048;
 // range selectors can contain 0x00 or 0xff suffixes
 const MAX_READ_KEY_SIZE_BYTES: usize = MAX_WRITE_KEY_SIZE_BYTES + 1;
 const MAX_VALUE_SIZE_BYTES: usize = 65536;
+const MAX_VALUE_SIZE_BYTES_CAP: usize = 1024 * 1024;
 const MAX_READ_RANGES: usize = 10;
 const MAX_READ_ENTRIES: usize = 1000;
 const MAX_CHECKS: usize = 100;
 const MAX_MUTATIONS: usize = 1000;
+const MAX_MUTATIONS_CAP: usize = 10_000;
 const MAX_WATCHED_KEYS: usize = 10;
+const MAX_WATCHED_PREFIX_ENTRIES: usize = 10_000;
+const WATCH_PREFIX_POLL_INTERVAL: Duration = Duration::from_millis(250);
 const MAX_TOTAL_MUTATION_SIZE_BYTES: usize = 800 * 1024;
+const MAX_TOTAL_MUTATION_SIZE_BYTES_CAP: usize = 64 * 1024 * 1024;
 const MAX_TOTAL_KEY_SIZE_BYTES: usize = 80 * 1024;
+const MAX_TOTAL_KEY_SIZE_BYTES_CAP: usize = 8 * 1024 * 1024;
 
 deno_core::extension!(deno_kv,
   deps = [ deno_console, deno_web ],
@@ -84,18 +102,87 @@ deno_core::extension!(deno_kv,
     op_kv_finish_dequeued_message<DBH>,
     op_kv_watch<DBH>,
     op_kv_watch_next,
+    op_kv_watch_prefix<DBH>,
+    op_kv_watch_prefix_next<DBH>,
+    op_kv_changes_subscribe<DBH>,
+    op_kv_changes_next,
+    op_kv_database_snapshot<DBH>,
+    op_kv_database_restore<DBH>,
   ],
   esm = [ "01_db.ts" ],
   options = {
     handler: DBH,
+    change_feed: KvChangeFeed,
   },
   state = |state, options| {
     state.put(Rc::new(options.handler));
+    state.put(options.change_feed);
   }
 );
 
+/// The limits enforced on the atomic operations of a database, which can be
+/// changed through the `limits` option of `Deno.openKv()`.
+#[derive(Clone, Copy, Debug)]
+struct KvLimits {
+  max_mutations: usize,
+  max_value_size_bytes: usize,
+  max_total_key_size_bytes: usize,
+  max_total_mutation_size_bytes: usize,
+}
+
+impl KvLimits {
+  fn new(options: Option<&LimitsOpenOptions>) -> Result<Self, AnyError> {
+    fn limit(
+      name: &str,
+      value: Option<usize>,
+      default: usize,
+      cap: usize,
+    ) -> Result<usize, AnyError> {
+      match value {
+        None => Ok(default),
+        Some(0) => Err(type_error(format!("{name} must be greater than 0"))),
+        Some(value) if value > cap => Err(type_error(format!(
+          "{name} must be at most {cap} (got {value})"
+        ))),
+        Some(value) => Ok(value),
+      }
+    }
+
+    let options = options.cloned().unwrap_or_default();
+    Ok(KvLimits {
+      max_mutations: limit(
+        "maxMutations",
+        options.max_mutations,
+        MAX_MUTATIONS,
+        MAX_MUTATIONS_CAP,
+      )?,
+      max_value_size_bytes: limit(
+        "maxValueSize",
+        options.max_value_size,
+        MAX_VALUE_SIZE_BYTES,
+        MAX_VALUE_SIZE_BYTES_CAP,
+      )?,
+      max_total_key_size_bytes: limit(
+        "maxTotalKeySize",
+        options.max_total_key_size,
+        MAX_TOTAL_KEY_SIZE_BYTES,
+        MAX_TOTAL_KEY_SIZE_BYTES_CAP,
+      )?,
+      max_total_mutation_size_bytes: limit(
+        "maxTotalMutationSize",
+        options.max_total_mutation_size,
+        MAX_TOTAL_MUTATION_SIZE_BYTES,
+        MAX_TOTAL_MUTATION_SIZE_BYTES_CAP,
+      )?,
+    })
+  }
+}
+
 struct DatabaseResource<DB: Database + 'static> {
   db: DB,
+  /// The name under which commits are published to the [`KvChangeFeed`].
+  name: Option<String>,
+  limits: KvLimits,
   cancel_handle: Rc<CancelHandle>,
 }
 
@@ -131,6 +218,7 @@ impl Resource for DatabaseWatcherResource {
 async fn op_kv_database_open<DBH>(
   state: Rc<RefCell<OpState>>,
   #[string] path: Option<String>,
+  #[serde] options: Option<OpenOptions>,
 ) -> Result<ResourceId, AnyError>
 where
   DBH: DatabaseHandler + 'static,
@@ -144,9 +232,14 @@ where
       .check_or_exit_with_legacy_fallback(UNSTABLE_FEATURE_NAME, "Deno.openKv");
     state.borrow::<Rc<DBH>>().clone()
   };
-  let db = handler.open(state.clone(), path).await?;
+  let options = options.unwrap_or_default();
+  let limits = KvLimits::new(options.limits.as_ref())?;
+  let name = changes::database_name(path.as_deref());
+  let db = handler.open(state.clone(), path, options).await?;
   let rid = state.borrow_mut().resource_table.add(DatabaseResource {
     db,
+    name,
+    limits,
     cancel_handle: CancelHandle::new_rc(),
   });
   Ok(rid)
@@ -477,6 +570,406 @@ async fn op_kv_watch_next(
   Ok(Some(entries))
 }
 
+struct PrefixWatcherResource<DB: Database + 'static> {
+  db: DB,
+  start: Vec<u8>,
+  end: Vec<u8>,
+  /// The versionstamp of every key seen under the prefix on the last poll,
+  /// or `None` before the first poll.
+  seen: RefCell<Option<BTreeMap<Vec<u8>, [u8; 10]>>>,
+  resume_from: Option<[u8; 10]>,
+  db_cancel_handle: Rc<CancelHandle>,
+  cancel_handle: Rc<CancelHandle>,
+}
+
+impl<DB: Database + 'static> Resource for PrefixWatcherResource<DB> {
+  fn name(&self) -> Cow<str> {
+    "databasePrefixWatcher".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel_handle.cancel()
+  }
+}
+
+#[op2]
+#[smi]
+fn op_kv_watch_prefix<DBH>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[serde] prefix: KvKey,
+  #[string] resume_token: Option<String>,
+) -> Result<ResourceId, AnyError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let resource = state.resource_table.get::<DatabaseResource<DBH::DB>>(rid)?;
+
+  let selector = RawSelector::from_tuple(Some(prefix), None, None)?;
+  let start = selector.range_start_key();
+  let end = selector.range_end_key();
+  check_read_key_size(&start)?;
+  check_read_key_size(&end)?;
+
+  let resume_from = resume_token
+    .map(|token| {
+      let mut versionstamp = [0; 10];
+      faster_hex::hex_decode(token.as_bytes(), &mut versionstamp)
+        .map_err(|_| type_error("invalid resume token"))?;
+      Ok::<_, AnyError>(versionstamp)
+    })
+    .transpose()?;
+
+  let rid = state.resource_table.add(PrefixWatcherResource {
+    db: resource.db.clone(),
+    start,
+    end,
+    seen: RefCell::new(None),
+    resume_from,
+    db_cancel_handle: resource.cancel_handle.clone(),
+    cancel_handle: CancelHandle::new_rc(),
+  });
+
+  Ok(rid)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PrefixWatchBatch {
+  changes: Vec<PrefixWatchChange>,
+  resume_token: String,
+}
+
+#[derive(Serialize)]
+struct PrefixWatchChange {
+  key: KvKey,
+  value: Option<ToV8Value>,
+  versionstamp: Option<ByteString>,
+}
+
+impl PrefixWatchChange {
+  fn deleted(key: &[u8]) -> Result<Self, AnyError> {
+    Ok(PrefixWatchChange {
+      key: decode_key(key)?.0.into_iter().map(key_part_to_v8).collect(),
+      value: None,
+      versionstamp: None,
+    })
+  }
+}
+
+impl TryFrom<KvEntry> for PrefixWatchChange {
+  type Error = AnyError;
+  fn try_from(entry: KvEntry) -> Result<Self, AnyError> {
+    let entry = ToV8KvEntry::try_from(entry)?;
+    Ok(PrefixWatchChange {
+      key: entry.key,
+      value: Some(entry.value),
+      versionstamp: Some(entry.versionstamp),
+    })
+  }
+}
+
+/// Reads every entry under the watched prefix, paging through the range.
+async fn read_watched_prefix<DB: Database>(
+  db: &DB,
+  start: &[u8],
+  end: &[u8],
+) -> Result<Vec<KvEntry>, AnyError> {
+  let mut entries: Vec<KvEntry> = Vec::new();
+  let mut start = start.to_vec();
+  loop {
+    let range = ReadRange {
+      start: start.clone(),
+      end: end.to_vec(),
+      limit: NonZeroU32::new(MAX_READ_ENTRIES as u32).unwrap(),
+      reverse: false,
+    };
+    let opts = SnapshotReadOptions {
+      consistency: Consistency::Strong,
+    };
+    let output = db.snapshot_read(vec![range], opts).await?;
+    let page = output.into_iter().next().map(|r| r.entries);
+    let page = page.unwrap_or_default();
+    let done = page.len() < MAX_READ_ENTRIES;
+    if let Some(last) = page.last() {
+      start = last.key.iter().copied().chain(Some(0)).collect();
+    }
+    entries.extend(page);
+    if entries.len() > MAX_WATCHED_PREFIX_ENTRIES {
+      return Err(type_error(format!(
+        "too many entries under watched prefix (max {})",
+        MAX_WATCHED_PREFIX_ENTRIES
+      )));
+    }
+    if done {
+      return Ok(entries);
+    }
+  }
+}
+
+#[op2(async)]
+#[serde]
+async fn op_kv_watch_prefix_next<DBH>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<PrefixWatchBatch>, AnyError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let resource = {
+    let state = state.borrow();
+    state
+      .resource_table
+      .get::<PrefixWatcherResource<DBH::DB>>(rid)?
+  };
+
+  loop {
+    let Ok(Ok(entries)) =
+      read_watched_prefix(&resource.db, &resource.start, &resource.end)
+        .or_cancel(resource.db_cancel_handle.clone())
+        .or_cancel(resource.cancel_handle.clone())
+        .await
+    else {
+      return Ok(None);
+    };
+    let entries = entries?;
+
+    let mut seen = resource.seen.borrow_mut();
+    let previous = seen.take();
+    let mut changes = Vec::new();
+    let mut resume_token = resource.resume_from.unwrap_or_default();
+    let mut current = BTreeMap::new();
+    for entry in entries {
+      resume_token = resume_token.max(entry.versionstamp);
+      current.insert(entry.key.clone(), entry.versionstamp);
+      let changed = match &previous {
+        Some(previous) => previous.get(&entry.key) != Some(&entry.versionstamp),
+        None => resource
+          .resume_from
+          .map_or(true, |resume_from| entry.versionstamp > resume_from),
+      };
+      if changed {
+        changes.push(PrefixWatchChange::try_from(entry)?);
+      }
+    }
+    // Deletions are only observable between polls of the same watcher; keys
+    // deleted before a watch was resumed can't be reported.
+    if let Some(previous) = &previous {
+      for key in previous.keys() {
+        if !current.contains_key(key) {
+          changes.push(PrefixWatchChange::deleted(key)?);
+        }
+      }
+    }
+    // the initial batch is always delivered, so that callers get a resume
+    // token even if nothing changed
+    let is_initial = previous.is_none();
+    *seen = Some(current);
+    drop(seen);
+
+    if !changes.is_empty() || is_initial {
+      return Ok(Some(PrefixWatchBatch {
+        changes,
+        resume_token: faster_hex::hex_string(&resume_token),
+      }));
+    }
+
+    let Ok(Ok(())) = tokio::time::sleep(WATCH_PREFIX_POLL_INTERVAL)
+      .or_cancel(resource.db_cancel_handle.clone())
+      .or_cancel(resource.cancel_handle.clone())
+      .await
+    else {
+      return Ok(None);
+    };
+  }
+}
+
+struct ChangeStreamResource {
+  receiver: AsyncRefCell<broadcast::Receiver<Arc<KvCommit>>>,
+  database: Option<String>,
+  db_cancel_handle: Rc<CancelHandle>,
+  cancel_handle: Rc<CancelHandle>,
+}
+
+impl Resource for ChangeStreamResource {
+  fn name(&self) -> Cow<str> {
+    "databaseChangeStream".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel_handle.cancel()
+  }
+}
+
+#[op2]
+#[smi]
+fn op_kv_changes_subscribe<DBH>(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<ResourceId, AnyError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let resource = state.resource_table.get::<DatabaseResource<DBH::DB>>(rid)?;
+  let receiver = state.borrow::<KvChangeFeed>().subscribe();
+  let rid = state.resource_table.add(ChangeStreamResource {
+    receiver: AsyncRefCell::new(receiver),
+    database: resource.name.clone(),
+    db_cancel_handle: resource.cancel_handle.clone(),
+    cancel_handle: CancelHandle::new_rc(),
+  });
+  Ok(rid)
+}
+
+#[derive(Serialize)]
+struct ToV8KvCommit {
+  versionstamp: ByteString,
+  changes: Vec<ToV8KvChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToV8KvChange {
+  key: KvKey,
+  #[serde(rename = "type")]
+  op: &'static str,
+  value: Option<ToV8Value>,
+  expire_at: Option<i64>,
+}
+
+impl TryFrom<&KvChange> for ToV8KvChange {
+  type Error = AnyError;
+  fn try_from(change: &KvChange) -> Result<Self, AnyError> {
+    let (op, value) = match &change.op {
+      KvChangeOp::Set(value) => ("set", Some(value)),
+      KvChangeOp::Delete => ("delete", None),
+      KvChangeOp::Sum(value) => ("sum", Some(value)),
+      KvChangeOp::Min(value) => ("min", Some(value)),
+      KvChangeOp::Max(value) => ("max", Some(value)),
+      KvChangeOp::SetSuffixVersionstampedKey(value) => {
+        ("setSuffixVersionstampedKey", Some(value))
+      }
+    };
+    Ok(ToV8KvChange {
+      key: decode_key(&change.key)?
+        .0
+        .into_iter()
+        .map(key_part_to_v8)
+        .collect(),
+      op,
+      value: value.cloned().map(Into::into),
+      expire_at: change.expire_at.map(|t| t.timestamp_millis()),
+    })
+  }
+}
+
+#[op2(async)]
+#[serde]
+async fn op_kv_changes_next(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<ToV8KvCommit>, AnyError> {
+  let resource = {
+    let state = state.borrow();
+    state.resource_table.get::<ChangeStreamResource>(rid)?
+  };
+  let mut receiver = RcRef::map(&resource, |r| &r.receiver).borrow_mut().await;
+
+  loop {
+    let Ok(Ok(result)) = receiver
+      .recv()
+      .or_cancel(resource.db_cancel_handle.clone())
+      .or_cancel(resource.cancel_handle.clone())
+      .await
+    else {
+      return Ok(None);
+    };
+    let commit = match result {
+      Ok(commit) => commit,
+      Err(broadcast::error::RecvError::Closed) => return Ok(None),
+      Err(broadcast::error::RecvError::Lagged(missed)) => {
+        bail!("change stream fell behind and missed {missed} commits");
+      }
+    };
+    if commit.database != resource.database {
+      continue;
+    }
+    return Ok(Some(ToV8KvCommit {
+      versionstamp: faster_hex::hex_string(&commit.versionstamp).into(),
+      changes: commit
+        .changes
+        .iter()
+        .map(TryInto::try_into)
+        .collect::<Result<_, AnyError>>()?,
+    }));
+  }
+}
+
+struct KvSnapshotResource(SqliteSnapshot);
+
+impl Resource for KvSnapshotResource {
+  fn name(&self) -> Cow<str> {
+    "databaseSnapshot".into()
+  }
+}
+
+/// The SQLite database backing `db`, if any.
+fn sqlite_db<DB: Database + 'static>(db: &DB) -> Option<&SqliteDb> {
+  let db = db as &dyn Any;
+  match db.downcast_ref::<RcDynamicDb>() {
+    Some(db) => db.downcast_ref(),
+    None => db.downcast_ref(),
+  }
+}
+
+fn snapshot_db<DB: Database + 'static>(db: &DB) -> Result<&SqliteDb, AnyError> {
+  sqlite_db(db).ok_or_else(|| {
+    type_error("Snapshots are only supported for in-memory databases")
+  })
+}
+
+#[op2(async)]
+#[smi]
+async fn op_kv_database_snapshot<DBH>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<ResourceId, AnyError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<DatabaseResource<DBH::DB>>(rid)?;
+  let snapshot = snapshot_db(&resource.db)?.snapshot().await?;
+  let rid = state
+    .borrow_mut()
+    .resource_table
+    .add(KvSnapshotResource(snapshot));
+  Ok(rid)
+}
+
+#[op2(async)]
+async fn op_kv_database_restore<DBH>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[smi] snapshot_rid: ResourceId,
+) -> Result<(), AnyError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let (resource, snapshot) = {
+    let state = state.borrow();
+    (
+      state.resource_table.get::<DatabaseResource<DBH::DB>>(rid)?,
+      state
+        .resource_table
+        .get::<KvSnapshotResource>(snapshot_rid)?,
+    )
+  };
+  snapshot_db(&resource.db)?.restore(&snapshot.0).await
+}
+
 #[op2(async)]
 async fn op_kv_finish_dequeued_message<DBH>(
   state: Rc<RefCell<OpState>>,
@@ -776,21 +1269,26 @@ where
   DBH: DatabaseHandler + 'static,
 {
   let current_timestamp = chrono::Utc::now();
-  let db = {
+  let (db, name, limits, change_feed) = {
     let state = state.borrow();
     let resource =
       state.resource_table.get::<DatabaseResource<DBH::DB>>(rid)?;
-    resource.db.clone()
+    (
+      resource.db.clone(),
+      resource.name.clone(),
+      resource.limits,
+      state.borrow::<KvChangeFeed>().clone(),
+    )
   };
 
   if checks.len() > MAX_CHECKS {
     return Err(type_error(format!("too many checks (max {})", MAX_CHECKS)));
   }
 
-  if mutations.len() + enqueues.len() > MAX_MUTATIONS {
+  if mutations.len() + enqueues.len() > limits.max_mutations {
     return Err(type_error(format!(
       "too many mutations (max {})",
-      MAX_MUTATIONS
+      limits.max_mutations
     )));
   }
 
@@ -830,31 +1328,37 @@ where
     .flat_map(|m| m.kind.value().map(|x| (&m.key, x)))
   {
     let key_size = check_write_key_size(key)?;
-    total_payload_size += check_value_size(value)? + key_size;
+    total_payload_size += check_value_size(value, &limits)? + key_size;
     total_key_size += key_size;
   }
 
   for enqueue in &enqueues {
-    total_payload_size += check_enqueue_payload_size(&enqueue.payload)?;
+    total_payload_size +=
+      check_enqueue_payload_size(&enqueue.payload, &limits)?;
     if let Some(schedule) = enqueue.backoff_schedule.as_ref() {
       total_payload_size += 4 * schedule.len();
     }
   }
 
-  if total_payload_size > MAX_TOTAL_MUTATION_SIZE_BYTES {
+  if total_payload_size > limits.max_total_mutation_size_bytes {
     return Err(type_error(format!(
       "total mutation size too large (max {} bytes)",
-      MAX_TOTAL_MUTATION_SIZE_BYTES
+      limits.max_total_mutation_size_bytes
     )));
   }
 
-  if total_key_size > MAX_TOTAL_KEY_SIZE_BYTES {
+  if total_key_size > limits.max_total_key_size_bytes {
     return Err(type_error(format!(
       "total key size too large (max {} bytes)",
-      MAX_TOTAL_KEY_SIZE_BYTES
+      limits.max_total_key_size_bytes
     )));
   }
 
+  // only pay for copying the mutations if someone is listening
+  let changes = change_feed
+    .has_subscribers()
+    .then(|| mutations.iter().map(KvChange::from).collect::<Vec<_>>());
+
   let atomic_write = AtomicWrite {
     checks,
     mutations,
@@ -863,6 +1367,16 @@ where
 
   let result = db.atomic_write(atomic_write).await?;
 
+  if let (Some(res), Some(changes)) = (&result, changes) {
+    if !changes.is_empty() {
+      change_feed.publish(KvCommit {
+        database: name,
+        versionstamp: res.versionstamp,
+        changes,
+      });
+    }
+  }
+
   Ok(result.map(|res| faster_hex::hex_string(&res.versionstamp)))
 }
 
@@ -903,28 +1417,34 @@ fn check_write_key_size(key: &[u8]) -> Result<usize, AnyError> {
   }
 }
 
-fn check_value_size(value: &KvValue) -> Result<usize, AnyError> {
+fn check_value_size(
+  value: &KvValue,
+  limits: &KvLimits,
+) -> Result<usize, AnyError> {
   let payload = match value {
     KvValue::Bytes(x) => x,
     KvValue::V8(x) => x,
     KvValue::U64(_) => return Ok(8),
   };
 
-  if payload.len() > MAX_VALUE_SIZE_BYTES {
+  if payload.len() > limits.max_value_size_bytes {
     Err(type_error(format!(
       "value too large (max {} bytes)",
-      MAX_VALUE_SIZE_BYTES
+      limits.max_value_size_bytes
     )))
   } else {
     Ok(payload.len())
   }
 }
 
-fn check_enqueue_payload_size(payload: &[u8]) -> Result<usize, AnyError> {
-  if payload.len() > MAX_VALUE_SIZE_BYTES {
+fn check_enqueue_payload_size(
+  payload: &[u8],
+  limits: &KvLimits,
+) -> Result<usize, AnyError> {
+  if payload.len() > limits.max_value_size_bytes {
     Err(type_error(format!(
       "enqueue payload too large (max {} bytes)",
-      MAX_VALUE_SIZE_BYTES
+      limits.max_value_size_bytes
     )))
   } else {
     Ok(payload.len())
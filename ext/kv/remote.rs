@@ -1,11 +1,15 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::any::Any;
 use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::dynamic::KvBackend;
+use crate::dynamic::KvDatabase;
 use crate::DatabaseHandler;
+use crate::OpenOptions;
 use anyhow::Context;
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -19,6 +23,14 @@ use deno_tls::rustls::RootCertStore;
 use deno_tls::Proxy;
 use deno_tls::RootCertStoreProvider;
 use deno_tls::TlsKeys;
+use denokv_proto::AtomicWrite;
+use denokv_proto::CommitResult;
+use denokv_proto::Database;
+use denokv_proto::QueueMessageHandle;
+use denokv_proto::ReadRange;
+use denokv_proto::ReadRangeOutput;
+use denokv_proto::SnapshotReadOptions;
+use denokv_proto::WatchStream;
 use denokv_remote::MetadataEndpoint;
 use denokv_remote::Remote;
 use denokv_remote::RemoteResponse;
@@ -158,9 +170,16 @@ impl<P: RemoteDbHandlerPermissions + 'static> DatabaseHandler
     &self,
     state: Rc<RefCell<OpState>>,
     path: Option<String>,
+    options: OpenOptions,
   ) -> Result<Self::DB, AnyError> {
     const ENV_VAR_NAME: &str = "DENO_KV_ACCESS_TOKEN";
 
+    if options.sqlite.is_some() {
+      return Err(type_error(
+        "SQLite options are not supported for remote databases",
+      ));
+    }
+
     let Some(url) = path else {
       return Err(type_error("Missing database url"));
     };
@@ -206,6 +225,7 @@ impl<P: RemoteDbHandlerPermissions + 'static> DatabaseHandler
         pool_idle_timeout: None,
         http1: false,
         http2: true,
+        retry: None,
       },
     )?;
     let fetch_client = FetchClient(client);
@@ -220,3 +240,59 @@ impl<P: RemoteDbHandlerPermissions + 'static> DatabaseHandler
     Ok(remote)
   }
 }
+
+#[async_trait(?Send)]
+impl<P: RemoteDbHandlerPermissions + 'static> KvBackend for RemoteDbHandler<P> {
+  fn prefixes(&self) -> &'static [&'static str] {
+    &["https://", "http://"]
+  }
+
+  async fn open(
+    &self,
+    state: Rc<RefCell<OpState>>,
+    path: Option<String>,
+    options: OpenOptions,
+  ) -> Result<Box<dyn KvDatabase>, AnyError> {
+    let db = DatabaseHandler::open(self, state, path, options).await?;
+    Ok(Box::new(db))
+  }
+}
+
+#[async_trait(?Send)]
+impl<P: RemoteDbHandlerPermissions + 'static> KvDatabase
+  for Remote<PermissionChecker<P>, FetchClient>
+{
+  async fn snapshot_read(
+    &self,
+    requests: Vec<ReadRange>,
+    options: SnapshotReadOptions,
+  ) -> Result<Vec<ReadRangeOutput>, AnyError> {
+    Database::snapshot_read(self, requests, options).await
+  }
+
+  async fn atomic_write(
+    &self,
+    write: AtomicWrite,
+  ) -> Result<Option<CommitResult>, AnyError> {
+    Database::atomic_write(self, write).await
+  }
+
+  async fn dequeue_next_message(
+    &self,
+  ) -> Result<Option<Box<dyn QueueMessageHandle>>, AnyError> {
+    let handle = Database::dequeue_next_message(self).await?;
+    Ok(handle.map(|handle| Box::new(handle) as Box<dyn QueueMessageHandle>))
+  }
+
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
+    Database::watch(self, keys)
+  }
+
+  fn close(&self) {
+    Database::close(self)
+  }
+
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+}
@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::any::Any;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env::current_dir;
@@ -8,9 +9,14 @@ use std::marker::PhantomData;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use async_trait::async_trait;
 use deno_core::error::type_error;
@@ -18,26 +24,81 @@ use deno_core::error::AnyError;
 use deno_core::unsync::spawn_blocking;
 use deno_core::OpState;
 use deno_node::PathClean;
+use deno_permissions::WriteOperation;
+use denokv_proto::AtomicWrite;
+use denokv_proto::CommitResult;
+use denokv_proto::Database;
+use denokv_proto::QueueMessageHandle;
+use denokv_proto::ReadRange;
+use denokv_proto::ReadRangeOutput;
+use denokv_proto::SnapshotReadOptions;
+use denokv_proto::WatchStream;
 pub use denokv_sqlite::SqliteBackendError;
 use denokv_sqlite::SqliteConfig;
 use denokv_sqlite::SqliteNotifier;
 use rand::SeedableRng;
 use rusqlite::OpenFlags;
 
+use crate::dynamic::KvBackend;
+use crate::dynamic::KvDatabase;
 use crate::DatabaseHandler;
+use crate::OpenOptions;
 
 static SQLITE_NOTIFIERS_MAP: OnceLock<Mutex<HashMap<PathBuf, SqliteNotifier>>> =
   OnceLock::new();
 
+type ConnGen =
+  Arc<dyn Fn() -> rusqlite::Result<rusqlite::Connection> + Send + Sync>;
+
+const DEFAULT_EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+const EXPIRY_SWEEP_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const BACKUP_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const BACKUP_PAGES_PER_STEP: std::os::raw::c_int = 1024;
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
 pub struct SqliteDbHandler<P: SqliteDbHandlerPermissions + 'static> {
   pub default_storage_dir: Option<PathBuf>,
   versionstamp_rng_seed: Option<u64>,
+  expiry_sweep_metrics: Arc<ExpirySweepMetrics>,
   _permissions: PhantomData<P>,
 }
 
+/// Counters for the background sweeps that delete expired keys from the
+/// databases opened by a [`SqliteDbHandler`].
+#[derive(Debug, Default)]
+pub struct ExpirySweepMetrics {
+  sweeps: AtomicU64,
+  failed_sweeps: AtomicU64,
+  expired_entries: AtomicU64,
+}
+
+impl ExpirySweepMetrics {
+  /// The number of sweeps that completed successfully.
+  pub fn sweeps(&self) -> u64 {
+    self.sweeps.load(Ordering::Relaxed)
+  }
+
+  /// The number of sweeps that failed, for example because the database was
+  /// locked for too long.
+  pub fn failed_sweeps(&self) -> u64 {
+    self.failed_sweeps.load(Ordering::Relaxed)
+  }
+
+  /// The total number of expired entries deleted by the sweeps.
+  pub fn expired_entries(&self) -> u64 {
+    self.expired_entries.load(Ordering::Relaxed)
+  }
+}
+
 pub trait SqliteDbHandlerPermissions {
   fn check_read(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError>;
   fn check_write(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError>;
+  fn check_write_operation(
+    &mut self,
+    p: &Path,
+    operation: WriteOperation,
+    api_name: &str,
+  ) -> Result<(), AnyError>;
 }
 
 impl SqliteDbHandlerPermissions for deno_permissions::PermissionsContainer {
@@ -50,6 +111,18 @@ impl SqliteDbHandlerPermissions for deno_permissions::PermissionsContainer {
   fn check_write(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError> {
     deno_permissions::PermissionsContainer::check_write(self, p, api_name)
   }
+
+  #[inline(always)]
+  fn check_write_operation(
+    &mut self,
+    p: &Path,
+    operation: WriteOperation,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_write_operation(
+      self, p, operation, api_name,
+    )
+  }
 }
 
 impl<P: SqliteDbHandlerPermissions> SqliteDbHandler<P> {
@@ -60,20 +133,47 @@ impl<P: SqliteDbHandlerPermissions> SqliteDbHandler<P> {
     Self {
       default_storage_dir,
       versionstamp_rng_seed,
+      expiry_sweep_metrics: Default::default(),
       _permissions: PhantomData,
     }
   }
+
+  /// Metrics for the expiry sweeps of every database opened by this handler.
+  pub fn expiry_sweep_metrics(&self) -> Arc<ExpirySweepMetrics> {
+    self.expiry_sweep_metrics.clone()
+  }
 }
 
 #[async_trait(?Send)]
 impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
-  type DB = denokv_sqlite::Sqlite;
+  type DB = SqliteDb;
 
   async fn open(
     &self,
     state: Rc<RefCell<OpState>>,
     path: Option<String>,
+    options: OpenOptions,
   ) -> Result<Self::DB, AnyError> {
+    let sqlite_options = options.sqlite.unwrap_or_default();
+    let journal_mode = match sqlite_options.journal_mode.as_deref() {
+      None => "wal",
+      Some(
+        mode @ ("delete" | "truncate" | "persist" | "memory" | "wal" | "off"),
+      ) => mode,
+      Some(mode) => {
+        return Err(type_error(format!("Invalid journal mode: {mode}")))
+      }
+    }
+    .to_string();
+    if matches!(sqlite_options.mmap_size, Some(size) if size < 0) {
+      return Err(type_error("mmapSize cannot be negative"));
+    }
+    let mmap_size = sqlite_options.mmap_size;
+    let busy_timeout = sqlite_options.busy_timeout.map(Duration::from_millis);
+    let expiry_sweep_interval = sqlite_options
+      .expiry_sweep_interval
+      .map_or(DEFAULT_EXPIRY_SWEEP_INTERVAL, Duration::from_millis);
+
     // Validate path
     if let Some(path) = &path {
       if path != ":memory:" {
@@ -91,22 +191,33 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
           let permissions = state.borrow_mut::<P>();
           permissions.check_read(path, "Deno.openKv")?;
           permissions.check_write(path, "Deno.openKv")?;
+          // the database is created if it doesn't exist, and written to
+          for operation in [WriteOperation::Create, WriteOperation::Modify] {
+            permissions.check_write_operation(
+              path,
+              operation,
+              "Deno.openKv",
+            )?;
+          }
         }
       }
     }
 
     let path = path.clone();
     let default_storage_dir = self.default_storage_dir.clone();
-    type ConnGen =
-      Arc<dyn Fn() -> rusqlite::Result<rusqlite::Connection> + Send + Sync>;
+    let memory = match (path.as_deref(), &default_storage_dir) {
+      (Some(":memory:"), _) | (None, None) => Some(Arc::new(MemoryDb::new())),
+      _ => None,
+    };
+    let memory_uri = memory.as_ref().map(|memory| memory.uri.clone());
     let (conn_gen, notifier_key): (ConnGen, _) = spawn_blocking(move || {
       denokv_sqlite::sqlite_retry_loop(|| {
         let (conn, notifier_key) = match (path.as_deref(), &default_storage_dir)
         {
-          (Some(":memory:"), _) | (None, None) => (
-            Arc::new(rusqlite::Connection::open_in_memory) as ConnGen,
-            None,
-          ),
+          (Some(":memory:"), _) | (None, None) => {
+            let uri = memory_uri.clone().unwrap();
+            (Arc::new(move || open_memory_db(&uri)) as ConnGen, None)
+          }
           (Some(path), _) => {
             let flags =
               OpenFlags::default().difference(OpenFlags::SQLITE_OPEN_URI);
@@ -156,10 +267,30 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
       num_workers: 1,
     };
 
-    denokv_sqlite::Sqlite::new(
+    // Expired keys are only hidden from reads by denokv, so sweep them from
+    // the file in the background. A separate connection can't see an
+    // in-memory database, so those aren't swept.
+    let sweeper = match &notifier_key {
+      Some(_) if !expiry_sweep_interval.is_zero() => {
+        Some(ExpirySweeper::spawn(
+          conn_gen.clone(),
+          expiry_sweep_interval,
+          self.expiry_sweep_metrics.clone(),
+        ))
+      }
+      _ => None,
+    };
+
+    let db = denokv_sqlite::Sqlite::new(
       move || {
         let conn = conn_gen()?;
-        conn.pragma_update(None, "journal_mode", "wal")?;
+        conn.pragma_update(None, "journal_mode", &journal_mode)?;
+        if let Some(mmap_size) = mmap_size {
+          conn.pragma_update(None, "mmap_size", mmap_size)?;
+        }
+        if let Some(busy_timeout) = busy_timeout {
+          conn.busy_timeout(busy_timeout)?;
+        }
         Ok((
           conn,
           match versionstamp_rng_seed {
@@ -170,10 +301,386 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
       },
       notifier,
       config,
-    )
+    )?;
+
+    Ok(SqliteDb {
+      db,
+      sweeper: sweeper.map(Arc::new),
+      memory,
+    })
+  }
+}
+
+#[async_trait(?Send)]
+impl<P: SqliteDbHandlerPermissions> KvBackend for SqliteDbHandler<P> {
+  fn prefixes(&self) -> &'static [&'static str] {
+    &[""]
+  }
+
+  async fn open(
+    &self,
+    state: Rc<RefCell<OpState>>,
+    path: Option<String>,
+    options: OpenOptions,
+  ) -> Result<Box<dyn KvDatabase>, AnyError> {
+    let db = DatabaseHandler::open(self, state, path, options).await?;
+    Ok(Box::new(db))
+  }
+}
+
+/// A SQLite backed database, along with the sweeper deleting its expired keys.
+#[derive(Clone)]
+pub struct SqliteDb {
+  db: denokv_sqlite::Sqlite,
+  sweeper: Option<Arc<ExpirySweeper>>,
+  memory: Option<Arc<MemoryDb>>,
+}
+
+impl SqliteDb {
+  /// Copies the current state of an in-memory database, including its queue,
+  /// so that it can later be restored with [`SqliteDb::restore`].
+  pub async fn snapshot(&self) -> Result<SqliteSnapshot, AnyError> {
+    let memory = self.memory()?;
+    spawn_blocking(move || memory.snapshot()).await?
+  }
+
+  /// Replaces the contents of an in-memory database with a snapshot taken
+  /// from it, or from another in-memory database.
+  pub async fn restore(
+    &self,
+    snapshot: &SqliteSnapshot,
+  ) -> Result<(), AnyError> {
+    let memory = self.memory()?;
+    let snapshot_uri = snapshot.uri.clone();
+    spawn_blocking(move || memory.restore(&snapshot_uri)).await?
+  }
+
+  fn memory(&self) -> Result<Arc<MemoryDb>, AnyError> {
+    self.memory.clone().ok_or_else(|| {
+      type_error("Snapshots are only supported for in-memory databases")
+    })
   }
 }
 
+/// An in-memory database that is shared between the connections opened with
+/// its URI, so that it can be copied while denokv keeps its own connection.
+struct MemoryDb {
+  uri: String,
+  conn: Mutex<Option<rusqlite::Connection>>,
+}
+
+/// A copy of an in-memory database, held in another in-memory database that
+/// lives as long as this value.
+pub struct SqliteSnapshot {
+  uri: String,
+  _conn: Mutex<rusqlite::Connection>,
+}
+
+fn next_memory_db_uri(kind: &str) -> String {
+  static NEXT_MEMORY_DB: AtomicU64 = AtomicU64::new(0);
+  format!(
+    "file:deno-kv-{kind}-{}-{}?mode=memory&cache=shared",
+    std::process::id(),
+    NEXT_MEMORY_DB.fetch_add(1, Ordering::Relaxed)
+  )
+}
+
+fn open_memory_db(uri: &str) -> rusqlite::Result<rusqlite::Connection> {
+  rusqlite::Connection::open_with_flags(
+    uri,
+    OpenFlags::default() | OpenFlags::SQLITE_OPEN_URI,
+  )
+}
+
+/// The tables of the attached database `schema`.
+fn memory_db_tables(
+  conn: &rusqlite::Connection,
+  schema: &str,
+) -> rusqlite::Result<Vec<String>> {
+  let mut stmt = conn.prepare(&format!(
+    "select name from {schema}.sqlite_master
+       where type = 'table' and name not like 'sqlite_%'"
+  ))?;
+  let tables = stmt.query_map([], |row| row.get(0))?;
+  tables.collect()
+}
+
+impl MemoryDb {
+  fn new() -> Self {
+    Self {
+      uri: next_memory_db_uri("memory"),
+      conn: Mutex::new(None),
+    }
+  }
+
+  /// Runs `f` on a connection to this database with `snapshot_uri` attached
+  /// as the `snapshot` schema.
+  fn with_snapshot_attached<T>(
+    &self,
+    snapshot_uri: &str,
+    f: impl FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<T>,
+  ) -> Result<T, AnyError> {
+    let mut conn = self.conn.lock().unwrap();
+    let conn = match &mut *conn {
+      Some(conn) => conn,
+      conn => conn.insert(open_memory_db(&self.uri)?),
+    };
+    conn.execute("attach database ? as snapshot", [snapshot_uri])?;
+    let result = f(conn);
+    conn.execute("detach database snapshot", [])?;
+    Ok(result?)
+  }
+
+  fn snapshot(&self) -> Result<SqliteSnapshot, AnyError> {
+    let uri = next_memory_db_uri("snapshot");
+    // keeps the snapshot alive after it is detached
+    let snapshot_conn = open_memory_db(&uri)?;
+    self.with_snapshot_attached(&uri, |conn| {
+      let tx = conn.transaction()?;
+      for table in memory_db_tables(&tx, "main")? {
+        tx.execute_batch(&format!(
+          "create table snapshot.\"{table}\" as select * from main.\"{table}\""
+        ))?;
+      }
+      tx.commit()
+    })?;
+    Ok(SqliteSnapshot {
+      uri,
+      _conn: Mutex::new(snapshot_conn),
+    })
+  }
+
+  fn restore(&self, snapshot_uri: &str) -> Result<(), AnyError> {
+    self.with_snapshot_attached(snapshot_uri, |conn| {
+      let tx = conn
+        .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+      for table in memory_db_tables(&tx, "main")? {
+        tx.execute_batch(&format!("delete from main.\"{table}\""))?;
+      }
+      for table in memory_db_tables(&tx, "snapshot")? {
+        tx.execute_batch(&format!(
+          "insert into main.\"{table}\" select * from snapshot.\"{table}\""
+        ))?;
+      }
+      tx.commit()
+    })
+  }
+}
+
+#[async_trait(?Send)]
+impl Database for SqliteDb {
+  type QMH = <denokv_sqlite::Sqlite as Database>::QMH;
+
+  async fn snapshot_read(
+    &self,
+    requests: Vec<ReadRange>,
+    options: SnapshotReadOptions,
+  ) -> Result<Vec<ReadRangeOutput>, AnyError> {
+    self.db.snapshot_read(requests, options).await
+  }
+
+  async fn atomic_write(
+    &self,
+    write: AtomicWrite,
+  ) -> Result<Option<CommitResult>, AnyError> {
+    self.db.atomic_write(write).await
+  }
+
+  async fn dequeue_next_message(&self) -> Result<Option<Self::QMH>, AnyError> {
+    self.db.dequeue_next_message().await
+  }
+
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
+    self.db.watch(keys)
+  }
+
+  fn close(&self) {
+    if let Some(sweeper) = &self.sweeper {
+      sweeper.stop();
+    }
+    self.db.close()
+  }
+}
+
+#[async_trait(?Send)]
+impl KvDatabase for SqliteDb {
+  async fn snapshot_read(
+    &self,
+    requests: Vec<ReadRange>,
+    options: SnapshotReadOptions,
+  ) -> Result<Vec<ReadRangeOutput>, AnyError> {
+    Database::snapshot_read(self, requests, options).await
+  }
+
+  async fn atomic_write(
+    &self,
+    write: AtomicWrite,
+  ) -> Result<Option<CommitResult>, AnyError> {
+    Database::atomic_write(self, write).await
+  }
+
+  async fn dequeue_next_message(
+    &self,
+  ) -> Result<Option<Box<dyn QueueMessageHandle>>, AnyError> {
+    let handle = Database::dequeue_next_message(self).await?;
+    Ok(handle.map(|handle| Box::new(handle) as Box<dyn QueueMessageHandle>))
+  }
+
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
+    Database::watch(self, keys)
+  }
+
+  fn close(&self) {
+    Database::close(self)
+  }
+
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+}
+
+/// A thread that periodically deletes expired keys from a database file, so
+/// that the space they take up can be reused. It stops once the database is
+/// closed or dropped.
+struct ExpirySweeper {
+  stop_tx: Mutex<Option<mpsc::Sender<()>>>,
+}
+
+impl ExpirySweeper {
+  fn spawn(
+    conn_gen: ConnGen,
+    interval: Duration,
+    metrics: Arc<ExpirySweepMetrics>,
+  ) -> Self {
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    std::thread::spawn(move || {
+      let mut conn = None;
+      // a message or a dropped sender both stop the sweeper
+      while let Err(mpsc::RecvTimeoutError::Timeout) =
+        stop_rx.recv_timeout(interval)
+      {
+        if conn.is_none() {
+          conn = conn_gen()
+            .and_then(|conn| {
+              conn.busy_timeout(EXPIRY_SWEEP_BUSY_TIMEOUT)?;
+              Ok(conn)
+            })
+            .map_err(|err| {
+              metrics.failed_sweeps.fetch_add(1, Ordering::Relaxed);
+              log::debug!("Failed to open database for expiry sweep: {err}");
+            })
+            .ok();
+        }
+        let Some(conn) = &conn else {
+          continue;
+        };
+        match sweep_expired(conn) {
+          Ok(deleted) => {
+            metrics.sweeps.fetch_add(1, Ordering::Relaxed);
+            metrics
+              .expired_entries
+              .fetch_add(deleted as u64, Ordering::Relaxed);
+          }
+          Err(err) => {
+            metrics.failed_sweeps.fetch_add(1, Ordering::Relaxed);
+            log::debug!("Failed to sweep expired keys: {err}");
+          }
+        }
+      }
+    });
+    Self {
+      stop_tx: Mutex::new(Some(stop_tx)),
+    }
+  }
+
+  fn stop(&self) {
+    self.stop_tx.lock().unwrap().take();
+  }
+}
+
+fn sweep_expired(conn: &rusqlite::Connection) -> rusqlite::Result<usize> {
+  let now = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_millis() as i64;
+  let deleted = conn.execute(
+    "delete from kv where expiration_ms >= 0 and expiration_ms <= ?",
+    [now],
+  )?;
+  if deleted > 0 {
+    // only shrinks the file with `auto_vacuum = incremental`, otherwise the
+    // freed pages are reused by later writes
+    conn.execute_batch("pragma incremental_vacuum")?;
+  }
+  Ok(deleted)
+}
+
+/// Copies the database at `db_path` to `backup_path` with the SQLite online
+/// backup API. The copy is a consistent snapshot even while other processes
+/// write to the database, and keeps the versionstamps of all entries and of
+/// the database itself. An existing file at `backup_path` is overwritten.
+pub fn backup_database(
+  db_path: &Path,
+  backup_path: &Path,
+) -> Result<(), AnyError> {
+  let src = rusqlite::Connection::open_with_flags(
+    db_path,
+    // fails if the database doesn't exist, unlike the default flags
+    OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+  )?;
+  src.busy_timeout(BACKUP_BUSY_TIMEOUT)?;
+  ensure_kv_database(&src, db_path)?;
+  let mut dst = rusqlite::Connection::open(backup_path)?;
+  copy_database(&src, &mut dst)
+}
+
+/// Replaces the database at `db_path`, creating it if needed, with a backup
+/// made by [`backup_database`]. The database should not be open in other
+/// processes while it is restored, as they would not see the restored
+/// entries consistently.
+pub fn restore_database(
+  backup_path: &Path,
+  db_path: &Path,
+) -> Result<(), AnyError> {
+  let src = rusqlite::Connection::open_with_flags(
+    backup_path,
+    OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+  )?;
+  ensure_kv_database(&src, backup_path)?;
+  let mut dst = rusqlite::Connection::open(db_path)?;
+  dst.busy_timeout(BACKUP_BUSY_TIMEOUT)?;
+  copy_database(&src, &mut dst)
+}
+
+fn ensure_kv_database(
+  conn: &rusqlite::Connection,
+  path: &Path,
+) -> Result<(), AnyError> {
+  let is_kv_database = conn.query_row(
+    "select count(*) from sqlite_master where type = 'table' and name = 'kv'",
+    [],
+    |row| row.get::<_, i64>(0),
+  )? > 0;
+  if !is_kv_database {
+    return Err(type_error(format!(
+      "{} is not a Deno KV database",
+      path.display()
+    )));
+  }
+  Ok(())
+}
+
+fn copy_database(
+  src: &rusqlite::Connection,
+  dst: &mut rusqlite::Connection,
+) -> Result<(), AnyError> {
+  let backup = rusqlite::backup::Backup::new(src, dst)?;
+  // copying in steps lets writers make progress, in which case the copy is
+  // restarted so that it stays consistent
+  backup.run_to_completion(BACKUP_PAGES_PER_STEP, BACKUP_STEP_PAUSE, None)?;
+  Ok(())
+}
+
 /// Same as Path::canonicalize, but also handles non-existing paths.
 fn canonicalize_path(path: &Path) -> Result<PathBuf, AnyError> {
   let path = path.to_path_buf().clean();
@@ -11,24 +11,33 @@ import {
   op_dns_resolve,
   op_net_accept_tcp,
   op_net_accept_unix,
+  op_net_accept_vsock,
   op_net_connect_tcp,
   op_net_connect_unix,
+  op_net_connect_vsock,
+  op_net_icmp_socket,
   op_net_join_multi_v4_udp,
   op_net_join_multi_v6_udp,
   op_net_leave_multi_v4_udp,
   op_net_leave_multi_v6_udp,
   op_net_listen_tcp,
   op_net_listen_unix,
+  op_net_listen_vsock,
+  op_net_recv_icmp,
   op_net_recv_udp,
   op_net_recv_unixpacket,
+  op_net_send_icmp,
   op_net_send_udp,
   op_net_send_unixpacket,
+  op_net_set_icmp_ttl,
   op_net_set_multi_loopback_udp,
   op_net_set_multi_ttl_udp,
+  op_net_unix_peer_credentials,
   op_set_keepalive,
   op_set_nodelay,
 } from "ext:core/ops";
 const UDP_DGRAM_MAXSIZE = 65507;
+const ICMP_PACKET_MAXSIZE = 65535;
 
 const {
   Error,
@@ -100,6 +109,11 @@ class Conn {
 
   #readable;
   #writable;
+  // When both streams are in use, the resource is only closed once the
+  // readable has reached EOF and the writable has been closed, so that either
+  // side can be half-closed while the other one is still in use.
+  #readableEnded = false;
+  #writableClosed = false;
 
   constructor(rid, remoteAddr, localAddr) {
     if (internals.future) {
@@ -166,7 +180,12 @@ class Conn {
 
   get readable() {
     if (this.#readable === undefined) {
-      this.#readable = readableStreamForRidUnrefable(this.#rid);
+      this.#readable = readableStreamForRidUnrefable(this.#rid, () => {
+        this.#readableEnded = true;
+        if (this.#writable === undefined || this.#writableClosed) {
+          core.tryClose(this.#rid);
+        }
+      });
       if (this.#unref) {
         readableStreamForRidUnrefableUnref(this.#readable);
       }
@@ -176,7 +195,16 @@ class Conn {
 
   get writable() {
     if (this.#writable === undefined) {
-      this.#writable = writableStreamForRid(this.#rid);
+      this.#writable = writableStreamForRid(this.#rid, true, async () => {
+        this.#writableClosed = true;
+        try {
+          await this.closeWrite();
+        } finally {
+          if (this.#readable === undefined || this.#readableEnded) {
+            core.tryClose(this.#rid);
+          }
+        }
+      });
     }
     return this.#writable;
   }
@@ -259,6 +287,17 @@ class UnixConn extends Conn {
     );
     return this.#rid;
   }
+
+  peerCredentials() {
+    return op_net_unix_peer_credentials(this.#rid);
+  }
+}
+
+class VsockConn extends Conn {}
+
+/** Maps the `-1` shorthand for `VMADDR_CID_ANY` to its unsigned value. */
+function vsockCid(cid) {
+  return cid === -1 ? 0xffffffff : cid;
 }
 
 class Listener {
@@ -304,6 +343,9 @@ class Listener {
       case "unix":
         promise = op_net_accept_unix(this.#rid);
         break;
+      case "vsock":
+        promise = op_net_accept_vsock(this.#rid);
+        break;
       default:
         throw new Error(`Unsupported transport: ${this.addr.transport}`);
     }
@@ -321,6 +363,10 @@ class Listener {
         { transport: "unix", path: remoteAddr },
         { transport: "unix", path: localAddr },
       );
+    } else if (this.addr.transport == "vsock") {
+      localAddr.transport = "vsock";
+      remoteAddr.transport = "vsock";
+      return new VsockConn(rid, remoteAddr, localAddr);
     } else {
       throw new Error("unreachable");
     }
@@ -381,6 +427,10 @@ class DatagramConn {
   #promise = null;
 
   constructor(rid, addr, bufSize = UDP_DGRAM_MAXSIZE) {
+    ObjectDefineProperty(this, internalRidSymbol, {
+      enumerable: false,
+      value: rid,
+    });
     this.#rid = rid;
     this.#addr = addr;
     this.bufSize = bufSize;
@@ -526,6 +576,83 @@ class DatagramConn {
   }
 }
 
+class IcmpSocket {
+  #rid = 0;
+  #family;
+  #unref = false;
+  #promise = null;
+
+  constructor(rid, family) {
+    this.#rid = rid;
+    this.#family = family;
+  }
+
+  get family() {
+    return this.#family;
+  }
+
+  async receive(p) {
+    const buf = p || new Uint8Array(ICMP_PACKET_MAXSIZE);
+    this.#promise = op_net_recv_icmp(this.#rid, buf);
+    if (this.#unref) core.unrefOpPromise(this.#promise);
+    const { 0: nread, 1: hostname } = await this.#promise;
+    const sub = TypedArrayPrototypeSubarray(buf, 0, nread);
+    return [sub, { transport: "icmp", hostname }];
+  }
+
+  async send(p, hostname) {
+    return await op_net_send_icmp(this.#rid, hostname, p);
+  }
+
+  setTtl(ttl) {
+    op_net_set_icmp_ttl(this.#rid, ttl);
+  }
+
+  close() {
+    core.close(this.#rid);
+  }
+
+  [SymbolDispose]() {
+    core.tryClose(this.#rid);
+  }
+
+  ref() {
+    this.#unref = false;
+    if (this.#promise !== null) {
+      core.refOpPromise(this.#promise);
+    }
+  }
+
+  unref() {
+    this.#unref = true;
+    if (this.#promise !== null) {
+      core.unrefOpPromise(this.#promise);
+    }
+  }
+
+  async *[SymbolAsyncIterator]() {
+    while (true) {
+      try {
+        yield await this.receive();
+      } catch (err) {
+        if (
+          ObjectPrototypeIsPrototypeOf(BadResourcePrototype, err) ||
+          ObjectPrototypeIsPrototypeOf(InterruptedPrototype, err)
+        ) {
+          break;
+        }
+        throw err;
+      }
+    }
+  }
+}
+
+function icmpSocket(options = { __proto__: null }) {
+  const family = options.family ?? "ipv4";
+  const rid = op_net_icmp_socket(family, options.ttl);
+  return new IcmpSocket(rid, family);
+}
+
 const listenOptionApiName = Symbol("listenOptionApiName");
 
 function listen(args) {
@@ -549,6 +676,14 @@ function listen(args) {
       };
       return new Listener(rid, addr);
     }
+    case "vsock": {
+      const { 0: rid, 1: addr } = op_net_listen_vsock({
+        cid: vsockCid(args.cid),
+        port: args.port,
+      });
+      addr.transport = "vsock";
+      return new Listener(rid, addr);
+    }
     default:
       throw new TypeError(`Unsupported transport: '${transport}'`);
   }
@@ -606,6 +741,16 @@ async function connect(args) {
         { transport: "unix", path: localAddr },
       );
     }
+    case "vsock": {
+      const { 0: rid, 1: localAddr, 2: remoteAddr } =
+        await op_net_connect_vsock({
+          cid: vsockCid(args.cid),
+          port: args.port,
+        });
+      localAddr.transport = "vsock";
+      remoteAddr.transport = "vsock";
+      return new VsockConn(rid, remoteAddr, localAddr);
+    }
     default:
       throw new TypeError(`Unsupported transport: '${transport}'`);
   }
@@ -615,6 +760,7 @@ export {
   Conn,
   connect,
   createListenDatagram,
+  icmpSocket,
   listen,
   Listener,
   listenOptionApiName,
@@ -622,4 +768,5 @@ export {
   shutdown,
   TcpConn,
   UnixConn,
+  VsockConn,
 };
@@ -141,6 +141,20 @@ impl TcpStreamResource {
 pub type UnixStreamResource =
   FullDuplexResource<unix::OwnedReadHalf, unix::OwnedWriteHalf>;
 
+#[cfg(unix)]
+impl UnixStreamResource {
+  /// Returns the credentials of the process on the other end of the socket,
+  /// as captured by the kernel when the connection was established.
+  pub fn peer_cred(self: &Rc<Self>) -> Result<unix::UCred, AnyError> {
+    if let Some(wr) = RcRef::map(self, |r| &r.wr).try_borrow() {
+      let stream: &tokio::net::UnixStream = (*wr).as_ref();
+      return Ok(stream.peer_cred()?);
+    }
+
+    Err(generic_error("Unable to get resources"))
+  }
+}
+
 #[cfg(not(unix))]
 pub struct UnixStreamResource;
 
@@ -177,3 +191,27 @@ impl Resource for UnixStreamResource {
     self.cancel_read_ops();
   }
 }
+
+#[cfg(target_os = "linux")]
+pub type VsockStreamResource = FullDuplexResource<
+  tokio::io::ReadHalf<tokio_vsock::VsockStream>,
+  tokio::io::WriteHalf<tokio_vsock::VsockStream>,
+>;
+
+#[cfg(target_os = "linux")]
+impl Resource for VsockStreamResource {
+  deno_core::impl_readable_byob!();
+  deno_core::impl_writable!();
+
+  fn name(&self) -> Cow<str> {
+    "vsockStream".into()
+  }
+
+  fn shutdown(self: Rc<Self>) -> AsyncResult<()> {
+    Box::pin(self.shutdown())
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel_read_ops();
+  }
+}
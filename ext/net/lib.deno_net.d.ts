@@ -18,8 +18,18 @@ declare namespace Deno {
     path: string;
   }
 
+  /** The address of a vsock socket, identified by a context ID and a port.
+   *
+   * @category Network
+   */
+  export interface VsockAddr {
+    transport: "vsock";
+    cid: number;
+    port: number;
+  }
+
   /** @category Network */
-  export type Addr = NetAddr | UnixAddr;
+  export type Addr = NetAddr | UnixAddr | VsockAddr;
 
   /** A generic network listener for stream-oriented protocols.
    *
@@ -92,7 +102,12 @@ declare namespace Deno {
      */
     readonly rid: number;
     /** Shuts down (`shutdown(2)`) the write side of the connection. Most
-     * callers should just use `close()`. */
+     * callers should just use `close()`.
+     *
+     * The peer receives an EOF (a TCP FIN, or a `close_notify` alert for TLS
+     * connections), while data can still be read from the connection.
+     * Closing {@linkcode Conn.writable} has the same effect; the connection is
+     * then closed once {@linkcode Conn.readable} has been read to the end. */
     closeWrite(): Promise<void>;
 
     /** Make the connection block the event loop from finishing.
@@ -189,9 +204,10 @@ declare namespace Deno {
    * const listener = Deno.listen({ path: "/foo/bar.sock", transport: "unix" })
    * ```
    *
-   * Requires `allow-read` and `allow-write` permission.
+   * Requires `allow-read` and `allow-write` permission, or `allow-net`
+   * permission for the socket path, ex. `--allow-net=unix:/foo/bar.sock`.
    *
-   * @tags allow-read, allow-write
+   * @tags allow-read, allow-write, allow-net
    * @category Network
    */
   // deno-lint-ignore adjacent-overload-signatures
@@ -398,9 +414,10 @@ declare namespace Deno {
    * const conn5 = await Deno.connect({ path: "/foo/bar.sock", transport: "unix" });
    * ```
    *
-   * Requires `allow-net` permission for "tcp" and `allow-read` for "unix".
+   * Requires `allow-net` permission for "tcp" and `allow-read` and
+   * `allow-write` for "unix", or `allow-net` given as `unix:<path>`.
    *
-   * @tags allow-net, allow-read
+   * @tags allow-net, allow-read, allow-write
    * @category Network
    */
   // deno-lint-ignore adjacent-overload-signatures
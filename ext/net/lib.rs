@@ -2,12 +2,15 @@
 
 pub mod io;
 pub mod ops;
+pub mod ops_icmp;
 pub mod ops_tls;
 #[cfg(unix)]
 pub mod ops_unix;
+#[cfg(target_os = "linux")]
+pub mod ops_vsock;
 pub mod raw;
 pub mod resolve_addr;
-mod tcp;
+pub mod tcp;
 
 use deno_core::error::AnyError;
 use deno_core::OpState;
@@ -28,6 +31,19 @@ pub trait NetPermissions {
   fn check_read(&mut self, _p: &Path, _api_name: &str) -> Result<(), AnyError>;
   fn check_write(&mut self, _p: &Path, _api_name: &str)
     -> Result<(), AnyError>;
+  fn check_vsock(
+    &mut self,
+    _cid: u32,
+    _port: u32,
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
+  /// Returns whether a `unix:<path>` net permission grants the socket, see
+  /// [`deno_permissions::PermissionsContainer::check_net_unix`].
+  fn check_unix(
+    &mut self,
+    _p: &Path,
+    _api_name: &str,
+  ) -> Result<bool, AnyError>;
 }
 
 impl NetPermissions for deno_permissions::PermissionsContainer {
@@ -57,6 +73,27 @@ impl NetPermissions for deno_permissions::PermissionsContainer {
   ) -> Result<(), AnyError> {
     deno_permissions::PermissionsContainer::check_write(self, path, api_name)
   }
+
+  #[inline(always)]
+  fn check_vsock(
+    &mut self,
+    cid: u32,
+    port: u32,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_net_vsock(
+      self, cid, port, api_name,
+    )
+  }
+
+  #[inline(always)]
+  fn check_unix(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<bool, AnyError> {
+    deno_permissions::PermissionsContainer::check_net_unix(self, path, api_name)
+  }
 }
 
 /// Helper for checking unstable features. Used for sync ops.
@@ -109,10 +146,21 @@ deno_core::extension!(deno_net,
     ops::op_net_leave_multi_v6_udp,
     ops::op_net_set_multi_loopback_udp,
     ops::op_net_set_multi_ttl_udp,
+    ops::op_net_set_broadcast_udp,
+    ops::op_net_set_ttl_udp,
+    ops::op_net_set_multicast_loop_udp,
+    ops::op_net_set_multicast_ttl_udp,
+    ops::op_net_set_multicast_if_udp,
+    ops::op_net_set_membership_udp,
     ops::op_dns_resolve<P>,
     ops::op_set_nodelay,
     ops::op_set_keepalive,
 
+    ops_icmp::op_net_icmp_socket<P>,
+    ops_icmp::op_net_recv_icmp,
+    ops_icmp::op_net_send_icmp<P>,
+    ops_icmp::op_net_set_icmp_ttl,
+
     ops_tls::op_tls_key_null,
     ops_tls::op_tls_key_static,
     ops_tls::op_tls_key_static_from_file<P>,
@@ -125,6 +173,7 @@ deno_core::extension!(deno_net,
     ops_tls::op_net_listen_tls<P>,
     ops_tls::op_net_accept_tls,
     ops_tls::op_tls_handshake,
+    ops_tls::op_tls_peer_certificates,
 
     ops_unix::op_net_accept_unix,
     ops_unix::op_net_connect_unix<P>,
@@ -133,6 +182,11 @@ deno_core::extension!(deno_net,
     ops_unix::op_node_unstable_net_listen_unixpacket<P>,
     ops_unix::op_net_recv_unixpacket,
     ops_unix::op_net_send_unixpacket<P>,
+    ops_unix::op_net_unix_peer_credentials,
+
+    ops_vsock::op_net_accept_vsock,
+    ops_vsock::op_net_connect_vsock<P>,
+    ops_vsock::op_net_listen_vsock<P>,
   ],
   esm = [ "01_net.js", "02_tls.js" ],
   options = {
@@ -191,4 +245,45 @@ mod ops_unix {
   stub_op!(op_node_unstable_net_listen_unixpacket<P>);
   stub_op!(op_net_recv_unixpacket);
   stub_op!(op_net_send_unixpacket<P>);
+  stub_op!(op_net_unix_peer_credentials);
+}
+
+/// Stub ops for platforms without vsock support.
+#[cfg(not(target_os = "linux"))]
+mod ops_vsock {
+  use crate::NetPermissions;
+  use deno_core::op2;
+
+  macro_rules! stub_op {
+    ($name:ident) => {
+      #[op2(fast)]
+      pub fn $name() -> Result<(), std::io::Error> {
+        let error_msg = format!(
+          "Operation `{:?}` not supported on non-linux platforms.",
+          stringify!($name)
+        );
+        Err(std::io::Error::new(
+          std::io::ErrorKind::Unsupported,
+          error_msg,
+        ))
+      }
+    };
+    ($name:ident<P>) => {
+      #[op2(fast)]
+      pub fn $name<P: NetPermissions>() -> Result<(), std::io::Error> {
+        let error_msg = format!(
+          "Operation `{:?}` not supported on non-linux platforms.",
+          stringify!($name)
+        );
+        Err(std::io::Error::new(
+          std::io::ErrorKind::Unsupported,
+          error_msg,
+        ))
+      }
+    };
+  }
+
+  stub_op!(op_net_accept_vsock);
+  stub_op!(op_net_connect_vsock<P>);
+  stub_op!(op_net_listen_vsock<P>);
 }
@@ -289,6 +289,140 @@ pub async fn op_net_set_multi_ttl_udp(
   Ok(())
 }
 
+/// Runs `f` with the socket of the UDP resource `rid`, for the synchronous
+/// socket options that `node:dgram` sets.
+fn with_udp_socket<R>(
+  state: &mut OpState,
+  rid: ResourceId,
+  f: impl FnOnce(&UdpSocket) -> Result<R, AnyError>,
+) -> Result<R, AnyError> {
+  let resource = state
+    .resource_table
+    .get::<UdpSocketResource>(rid)
+    .map_err(|_| bad_resource("Socket has been closed"))?;
+  let socket = RcRef::map(&resource, |r| &r.socket)
+    .try_borrow()
+    .ok_or_else(|| generic_error("Socket is busy"))?;
+  f(&socket)
+}
+
+/// Parses the index of the interface that an IPv6 multicast option applies
+/// to, where the empty string stands for the default interface.
+fn parse_multicast_interface_v6(interface: &str) -> Result<u32, AnyError> {
+  if interface.is_empty() {
+    return Ok(0);
+  }
+  Ok(interface.parse::<u32>()?)
+}
+
+#[op2(fast)]
+pub fn op_net_set_broadcast_udp(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  broadcast: bool,
+) -> Result<(), AnyError> {
+  with_udp_socket(state, rid, |socket| Ok(socket.set_broadcast(broadcast)?))
+}
+
+#[op2(fast)]
+pub fn op_net_set_ttl_udp(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  ttl: u32,
+) -> Result<(), AnyError> {
+  with_udp_socket(state, rid, |socket| Ok(socket.set_ttl(ttl)?))
+}
+
+#[op2(fast)]
+pub fn op_net_set_multicast_loop_udp(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  loopback: bool,
+) -> Result<(), AnyError> {
+  with_udp_socket(state, rid, |socket| {
+    if socket.local_addr()?.is_ipv4() {
+      socket.set_multicast_loop_v4(loopback)?;
+    } else {
+      socket.set_multicast_loop_v6(loopback)?;
+    }
+    Ok(())
+  })
+}
+
+#[op2(fast)]
+pub fn op_net_set_multicast_ttl_udp(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  ttl: u32,
+) -> Result<(), AnyError> {
+  with_udp_socket(state, rid, |socket| {
+    if socket.local_addr()?.is_ipv4() {
+      socket.set_multicast_ttl_v4(ttl)?;
+    } else {
+      socket2::SockRef::from(socket).set_multicast_hops_v6(ttl)?;
+    }
+    Ok(())
+  })
+}
+
+/// Sets the interface that multicast packets are sent from. It is given by
+/// its address for IPv4 sockets, and by its index for IPv6 sockets.
+#[op2(fast)]
+pub fn op_net_set_multicast_if_udp(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] interface: &str,
+) -> Result<(), AnyError> {
+  with_udp_socket(state, rid, |socket| {
+    let socket_ref = socket2::SockRef::from(socket);
+    if socket.local_addr()?.is_ipv4() {
+      socket_ref.set_multicast_if_v4(&Ipv4Addr::from_str(interface)?)?;
+    } else {
+      socket_ref
+        .set_multicast_if_v6(parse_multicast_interface_v6(interface)?)?;
+    }
+    Ok(())
+  })
+}
+
+/// Joins or leaves the multicast group `group` on `interface`, which is an
+/// address or an index like for `op_net_set_multicast_if_udp`, or empty for
+/// the default interface.
+#[op2(fast)]
+pub fn op_net_set_membership_udp(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  #[string] group: &str,
+  #[string] interface: &str,
+  join: bool,
+) -> Result<(), AnyError> {
+  with_udp_socket(state, rid, |socket| {
+    match std::net::IpAddr::from_str(group)? {
+      std::net::IpAddr::V4(group) => {
+        let interface = if interface.is_empty() {
+          Ipv4Addr::UNSPECIFIED
+        } else {
+          Ipv4Addr::from_str(interface)?
+        };
+        if join {
+          socket.join_multicast_v4(group, interface)?;
+        } else {
+          socket.leave_multicast_v4(group, interface)?;
+        }
+      }
+      std::net::IpAddr::V6(group) => {
+        let interface = parse_multicast_interface_v6(interface)?;
+        if join {
+          socket.join_multicast_v6(&group, interface)?;
+        } else {
+          socket.leave_multicast_v6(&group, interface)?;
+        }
+      }
+    }
+    Ok(())
+  })
+}
+
 #[op2(async)]
 #[serde]
 pub async fn op_net_connect_tcp<NP>(
@@ -999,6 +1133,23 @@ mod tests {
     ) -> Result<(), AnyError> {
       Ok(())
     }
+
+    fn check_vsock(
+      &mut self,
+      _cid: u32,
+      _port: u32,
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
+
+    fn check_unix(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<bool, AnyError> {
+      Ok(false)
+    }
   }
 
   #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
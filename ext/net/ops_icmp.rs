@@ -0,0 +1,183 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use crate::resolve_addr::resolve_addr;
+use crate::NetPermissions;
+use deno_core::error::bad_resource;
+use deno_core::error::custom_error;
+use deno_core::error::generic_error;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::AsyncRefCell;
+use deno_core::CancelHandle;
+use deno_core::CancelTryFuture;
+use deno_core::JsBuffer;
+use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use socket2::Domain;
+use socket2::Protocol;
+use socket2::SockRef;
+use socket2::Socket;
+use socket2::Type;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tokio::net::UdpSocket;
+
+pub const UNSTABLE_FEATURE_NAME: &str = "raw-sockets";
+
+struct IcmpSocketResource {
+  // Raw sockets use the same `sendto`/`recvfrom` calls as datagram sockets,
+  // so tokio's UDP socket works as an async wrapper for them.
+  socket: AsyncRefCell<UdpSocket>,
+  domain: Domain,
+  cancel: CancelHandle,
+}
+
+impl Resource for IcmpSocketResource {
+  fn name(&self) -> Cow<str> {
+    "icmpSocket".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel()
+  }
+}
+
+fn set_ttl(
+  socket: &UdpSocket,
+  domain: Domain,
+  ttl: u32,
+) -> std::io::Result<()> {
+  let socket = SockRef::from(socket);
+  if domain == Domain::IPV4 {
+    socket.set_ttl(ttl)
+  } else {
+    socket.set_unicast_hops_v6(ttl)
+  }
+}
+
+fn get_socket(
+  state: &Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Rc<IcmpSocketResource>, AnyError> {
+  state
+    .borrow()
+    .resource_table
+    .get::<IcmpSocketResource>(rid)
+    .map_err(|_| bad_resource("Socket has been closed"))
+}
+
+#[op2]
+#[smi]
+pub fn op_net_icmp_socket<NP>(
+  state: &mut OpState,
+  #[string] family: &str,
+  #[serde] ttl: Option<u32>,
+) -> Result<ResourceId, AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  state.feature_checker.check_or_exit_with_legacy_fallback(
+    UNSTABLE_FEATURE_NAME,
+    "Deno.icmpSocket",
+  );
+  let (domain, protocol, hostname) = match family {
+    "ipv4" => (Domain::IPV4, Protocol::ICMPV4, "0.0.0.0"),
+    "ipv6" => (Domain::IPV6, Protocol::ICMPV6, "::"),
+    _ => {
+      return Err(type_error(format!("Unsupported ICMP family: '{family}'")))
+    }
+  };
+  // Receiving raw ICMP traffic is equivalent to listening on every address.
+  state
+    .borrow_mut::<NP>()
+    .check_net(&(hostname, None), "Deno.icmpSocket()")?;
+
+  let socket = Socket::new(domain, Type::RAW, Some(protocol)).map_err(|e| {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+      custom_error(
+        "PermissionDenied",
+        format!("Creating a raw ICMP socket requires elevated privileges: {e}"),
+      )
+    } else {
+      e.into()
+    }
+  })?;
+  socket.set_nonblocking(true)?;
+  let std_socket: std::net::UdpSocket = socket.into();
+  let socket = UdpSocket::from_std(std_socket)?;
+  if let Some(ttl) = ttl {
+    set_ttl(&socket, domain, ttl)?;
+  }
+
+  let rid = state.resource_table.add(IcmpSocketResource {
+    socket: AsyncRefCell::new(socket),
+    domain,
+    cancel: Default::default(),
+  });
+  Ok(rid)
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_net_recv_icmp(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[buffer] mut buf: JsBuffer,
+) -> Result<(usize, String), AnyError> {
+  let resource = get_socket(&state, rid)?;
+  let socket = RcRef::map(&resource, |r| &r.socket).borrow().await;
+  let cancel_handle = RcRef::map(&resource, |r| &r.cancel);
+  let (nread, remote_addr) = socket
+    .recv_from(&mut buf)
+    .try_or_cancel(cancel_handle)
+    .await?;
+  Ok((nread, remote_addr.ip().to_string()))
+}
+
+#[op2(async)]
+#[number]
+pub async fn op_net_send_icmp<NP>(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] hostname: String,
+  #[buffer] zero_copy: JsBuffer,
+) -> Result<usize, AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  state
+    .borrow_mut()
+    .borrow_mut::<NP>()
+    .check_net(&(&hostname, None), "Deno.IcmpSocket.send()")?;
+  let resource = get_socket(&state, rid)?;
+  let is_ipv4 = resource.domain == Domain::IPV4;
+  let addr = resolve_addr(&hostname, 0)
+    .await?
+    .find(|addr| addr.is_ipv4() == is_ipv4)
+    .ok_or_else(|| generic_error("No resolved address found"))?;
+
+  let socket = RcRef::map(&resource, |r| &r.socket).borrow().await;
+  let nwritten = socket.send_to(&zero_copy, &addr).await?;
+  Ok(nwritten)
+}
+
+#[op2(fast)]
+pub fn op_net_set_icmp_ttl(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+  ttl: u32,
+) -> Result<(), AnyError> {
+  let resource = state
+    .resource_table
+    .get::<IcmpSocketResource>(rid)
+    .map_err(|_| bad_resource("Socket has been closed"))?;
+  let socket = RcRef::map(&resource, |r| &r.socket)
+    .try_borrow()
+    .ok_or_else(|| generic_error("Socket is busy"))?;
+  set_ttl(&socket, resource.domain, ttl)?;
+  Ok(())
+}
@@ -27,7 +27,9 @@ use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
-use deno_tls::create_client_config;
+use deno_core::ToJsBuffer;
+use deno_tls::create_client_config_with_verifier;
+use deno_tls::create_server_cert_verifier;
 use deno_tls::load_certs;
 use deno_tls::load_private_keys;
 use deno_tls::new_resolver;
@@ -36,6 +38,8 @@ use deno_tls::rustls::ClientConnection;
 use deno_tls::rustls::ServerConfig;
 use deno_tls::webpki::types::CertificateDer;
 use deno_tls::webpki::types::PrivateKeyDer;
+use deno_tls::PeerCertificateRecorder;
+use deno_tls::PeerCertificates;
 use deno_tls::ServerConfigProvider;
 use deno_tls::SocketUse;
 use deno_tls::TlsKey;
@@ -101,6 +105,7 @@ pub struct TlsStreamResource {
   wr: AsyncRefCell<TlsStreamWrite>,
   // `None` when a TLS handshake hasn't been done.
   handshake_info: RefCell<Option<TlsHandshakeInfo>>,
+  peer_certificates: PeerCertificates,
   cancel_handle: CancelHandle, // Only read and handshake ops get canceled.
 }
 
@@ -110,10 +115,21 @@ impl TlsStreamResource {
       rd: rd.into(),
       wr: wr.into(),
       handshake_info: RefCell::new(None),
+      peer_certificates: Default::default(),
       cancel_handle: Default::default(),
     }
   }
 
+  /// Sets where the certificate chain of the peer is recorded in the
+  /// handshake, see `PeerCertificateRecorder`.
+  pub fn with_peer_certificates(
+    mut self,
+    peer_certificates: PeerCertificates,
+  ) -> Self {
+    self.peer_certificates = peer_certificates;
+    self
+  }
+
   pub fn into_inner(self) -> (TlsStreamRead, TlsStreamWrite) {
     (self.rd.into_inner(), self.wr.into_inner())
   }
@@ -337,13 +353,17 @@ where
   let local_addr = tcp_stream.local_addr()?;
   let remote_addr = tcp_stream.peer_addr()?;
 
-  let mut tls_config = create_client_config(
+  let verifier = PeerCertificateRecorder::new(create_server_cert_verifier(
     root_cert_store,
     ca_certs,
     unsafely_ignore_certificate_errors,
+  )?);
+  let peer_certificates = verifier.peer_certificates();
+  let mut tls_config = create_client_config_with_verifier(
+    Arc::new(verifier),
     TlsKeys::Null,
     SocketUse::GeneralSsl,
-  )?;
+  );
 
   if let Some(alpn_protocols) = args.alpn_protocols {
     tls_config.alpn_protocols =
@@ -359,9 +379,10 @@ where
 
   let rid = {
     let mut state_ = state.borrow_mut();
-    state_
-      .resource_table
-      .add(TlsStreamResource::new(tls_stream.into_split()))
+    state_.resource_table.add(
+      TlsStreamResource::new(tls_stream.into_split())
+        .with_peer_certificates(peer_certificates),
+    )
   };
 
   Ok((rid, IpAddr::from(local_addr), IpAddr::from(remote_addr)))
@@ -424,13 +445,17 @@ where
   let local_addr = tcp_stream.local_addr()?;
   let remote_addr = tcp_stream.peer_addr()?;
 
-  let mut tls_config = create_client_config(
+  let verifier = PeerCertificateRecorder::new(create_server_cert_verifier(
     root_cert_store,
     ca_certs,
     unsafely_ignore_certificate_errors,
+  )?);
+  let peer_certificates = verifier.peer_certificates();
+  let mut tls_config = create_client_config_with_verifier(
+    Arc::new(verifier),
     key_pair.take(),
     SocketUse::GeneralSsl,
-  )?;
+  );
 
   if let Some(alpn_protocols) = args.alpn_protocols {
     tls_config.alpn_protocols =
@@ -447,9 +472,10 @@ where
 
   let rid = {
     let mut state_ = state.borrow_mut();
-    state_
-      .resource_table
-      .add(TlsStreamResource::new(tls_stream.into_split()))
+    state_.resource_table.add(
+      TlsStreamResource::new(tls_stream.into_split())
+        .with_peer_certificates(peer_certificates),
+    )
   };
 
   Ok((rid, IpAddr::from(local_addr), IpAddr::from(remote_addr)))
@@ -592,3 +618,22 @@ pub async fn op_tls_handshake(
     .map_err(|_| bad_resource("Listener has been closed"))?;
   resource.handshake().await
 }
+
+/// Returns the certificate chain that the peer presented in the handshake,
+/// in DER, which is empty if it presented none or it is not known.
+#[op2]
+#[serde]
+pub fn op_tls_peer_certificates(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<Vec<ToJsBuffer>, AnyError> {
+  let resource = state.resource_table.get::<TlsStreamResource>(rid)?;
+  let peer_certificates = resource.peer_certificates.lock().unwrap();
+  Ok(
+    peer_certificates
+      .iter()
+      .flatten()
+      .map(|cert| cert.to_vec().into())
+      .collect(),
+  )
+}
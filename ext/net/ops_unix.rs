@@ -58,6 +58,14 @@ pub struct UnixListenArgs {
   pub path: String,
 }
 
+#[derive(Serialize)]
+pub struct UnixPeerCredentials {
+  pub uid: u32,
+  pub gid: u32,
+  /// Not every platform reports the process id of the peer.
+  pub pid: Option<i32>,
+}
+
 #[op2(async)]
 #[serde]
 pub async fn op_net_accept_unix(
@@ -102,12 +110,11 @@ where
   let address_path = Path::new(&path);
   {
     let mut state_ = state.borrow_mut();
-    state_
-      .borrow_mut::<NP>()
-      .check_read(address_path, "Deno.connect()")?;
-    state_
-      .borrow_mut::<NP>()
-      .check_write(address_path, "Deno.connect()")?;
+    let permissions = state_.borrow_mut::<NP>();
+    if !permissions.check_unix(address_path, "Deno.connect()")? {
+      permissions.check_read(address_path, "Deno.connect()")?;
+      permissions.check_write(address_path, "Deno.connect()")?;
+    }
   }
   let unix_stream = UnixStream::connect(Path::new(&path)).await?;
   let local_addr = unix_stream.local_addr()?;
@@ -121,6 +128,25 @@ where
   Ok((rid, local_addr_path, remote_addr_path))
 }
 
+#[op2]
+#[serde]
+pub fn op_net_unix_peer_credentials(
+  state: &mut OpState,
+  #[smi] rid: ResourceId,
+) -> Result<UnixPeerCredentials, AnyError> {
+  super::check_unstable(state, "Deno.UnixConn.peerCredentials");
+  let resource = state
+    .resource_table
+    .get::<UnixStreamResource>(rid)
+    .map_err(|_| bad_resource("Connection has been closed"))?;
+  let cred = resource.peer_cred()?;
+  Ok(UnixPeerCredentials {
+    uid: cred.uid(),
+    gid: cred.gid(),
+    pid: cred.pid(),
+  })
+}
+
 #[op2(async)]
 #[serde]
 pub async fn op_net_recv_unixpacket(
@@ -157,8 +183,10 @@ where
   let address_path = Path::new(&path);
   {
     let mut s = state.borrow_mut();
-    s.borrow_mut::<NP>()
-      .check_write(address_path, "Deno.DatagramConn.send()")?;
+    let permissions = s.borrow_mut::<NP>();
+    if !permissions.check_unix(address_path, "Deno.DatagramConn.send()")? {
+      permissions.check_write(address_path, "Deno.DatagramConn.send()")?;
+    }
   }
 
   let resource = state
@@ -187,8 +215,10 @@ where
   let address_path = Path::new(&path);
   let permissions = state.borrow_mut::<NP>();
   let api_call_expr = format!("{}()", api_name);
-  permissions.check_read(address_path, &api_call_expr)?;
-  permissions.check_write(address_path, &api_call_expr)?;
+  if !permissions.check_unix(address_path, &api_call_expr)? {
+    permissions.check_read(address_path, &api_call_expr)?;
+    permissions.check_write(address_path, &api_call_expr)?;
+  }
   let listener = UnixListener::bind(address_path)?;
   let local_addr = listener.local_addr()?;
   let pathname = local_addr.as_pathname().map(pathstring).transpose()?;
@@ -206,8 +236,10 @@ where
 {
   let address_path = Path::new(&path);
   let permissions = state.borrow_mut::<NP>();
-  permissions.check_read(address_path, "Deno.listenDatagram()")?;
-  permissions.check_write(address_path, "Deno.listenDatagram()")?;
+  if !permissions.check_unix(address_path, "Deno.listenDatagram()")? {
+    permissions.check_read(address_path, "Deno.listenDatagram()")?;
+    permissions.check_write(address_path, "Deno.listenDatagram()")?;
+  }
   let socket = UnixDatagram::bind(address_path)?;
   let local_addr = socket.local_addr()?;
   let pathname = local_addr.as_pathname().map(pathstring).transpose()?;
@@ -0,0 +1,135 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use crate::io::VsockStreamResource;
+use crate::NetPermissions;
+use deno_core::error::bad_resource;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::AsyncRefCell;
+use deno_core::CancelHandle;
+use deno_core::CancelTryFuture;
+use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use serde::Deserialize;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tokio_vsock::VsockAddr;
+use tokio_vsock::VsockListener;
+use tokio_vsock::VsockStream;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VsockAddrArgs {
+  pub cid: u32,
+  pub port: u32,
+}
+
+impl From<VsockAddr> for VsockAddrArgs {
+  fn from(addr: VsockAddr) -> Self {
+    Self {
+      cid: addr.cid(),
+      port: addr.port(),
+    }
+  }
+}
+
+struct VsockListenerResource {
+  listener: AsyncRefCell<VsockListener>,
+  cancel: CancelHandle,
+}
+
+impl Resource for VsockListenerResource {
+  fn name(&self) -> Cow<str> {
+    "vsockListener".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_net_accept_vsock(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<(ResourceId, VsockAddrArgs, VsockAddrArgs), AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<VsockListenerResource>(rid)
+    .map_err(|_| bad_resource("Listener has been closed"))?;
+  let listener = RcRef::map(&resource, |r| &r.listener)
+    .try_borrow_mut()
+    .ok_or_else(|| custom_error("Busy", "Listener already in use"))?;
+  let cancel = RcRef::map(resource, |r| &r.cancel);
+  let (vsock_stream, _addr) = listener
+    .accept()
+    .try_or_cancel(cancel)
+    .await
+    .map_err(crate::ops::accept_err)?;
+  let local_addr = vsock_stream.local_addr()?;
+  let remote_addr = vsock_stream.peer_addr()?;
+
+  let mut state = state.borrow_mut();
+  let rid = state
+    .resource_table
+    .add(VsockStreamResource::new(tokio::io::split(vsock_stream)));
+  Ok((rid, local_addr.into(), remote_addr.into()))
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_net_connect_vsock<NP>(
+  state: Rc<RefCell<OpState>>,
+  #[serde] addr: VsockAddrArgs,
+) -> Result<(ResourceId, VsockAddrArgs, VsockAddrArgs), AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  {
+    let mut state_ = state.borrow_mut();
+    super::check_unstable(&state_, "Deno.connect({ transport: \"vsock\" })");
+    state_.borrow_mut::<NP>().check_vsock(
+      addr.cid,
+      addr.port,
+      "Deno.connect()",
+    )?;
+  }
+  let vsock_stream =
+    VsockStream::connect(VsockAddr::new(addr.cid, addr.port)).await?;
+  let local_addr = vsock_stream.local_addr()?;
+  let remote_addr = vsock_stream.peer_addr()?;
+
+  let mut state_ = state.borrow_mut();
+  let rid = state_
+    .resource_table
+    .add(VsockStreamResource::new(tokio::io::split(vsock_stream)));
+  Ok((rid, local_addr.into(), remote_addr.into()))
+}
+
+#[op2]
+#[serde]
+pub fn op_net_listen_vsock<NP>(
+  state: &mut OpState,
+  #[serde] addr: VsockAddrArgs,
+) -> Result<(ResourceId, VsockAddrArgs), AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  super::check_unstable(state, "Deno.listen({ transport: \"vsock\" })");
+  state
+    .borrow_mut::<NP>()
+    .check_vsock(addr.cid, addr.port, "Deno.listen()")?;
+  let listener = VsockListener::bind(VsockAddr::new(addr.cid, addr.port))?;
+  let local_addr = listener.local_addr()?;
+  let rid = state.resource_table.add(VsockListenerResource {
+    listener: AsyncRefCell::new(listener),
+    cancel: Default::default(),
+  });
+  Ok((rid, local_addr.into()))
+}
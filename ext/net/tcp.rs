@@ -119,6 +119,16 @@ impl TcpListener {
     })
   }
 
+  /// Wrap a socket that is already listening, e.g. one received from another
+  /// process.
+  pub fn from_std(listener: std::net::TcpListener) -> std::io::Result<Self> {
+    listener.set_nonblocking(true)?;
+    Ok(Self {
+      listener: Some(tokio::net::TcpListener::from_std(listener)?),
+      conn: None,
+    })
+  }
+
   pub async fn accept(
     &self,
   ) -> std::io::Result<(tokio::net::TcpStream, SocketAddr)> {
@@ -131,6 +141,13 @@ impl TcpListener {
   }
 }
 
+#[cfg(unix)]
+impl std::os::fd::AsFd for TcpListener {
+  fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+    std::os::fd::AsFd::as_fd(self.listener.as_ref().unwrap())
+  }
+}
+
 impl Drop for TcpListener {
   fn drop(&mut self) {
     // If we're in load-balancing mode
@@ -19,6 +19,7 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_fs::sync::MaybeSend;
 use deno_fs::sync::MaybeSync;
+use deno_fs::WriteOperation;
 use once_cell::sync::Lazy;
 
 extern crate libz_sys as zlib;
@@ -76,6 +77,14 @@ pub trait NodePermissions {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError>;
+  /// Checks that `operation` is allowed on `path`, after write access to it
+  /// was checked.
+  fn check_write_operation(
+    &mut self,
+    path: &Path,
+    operation: WriteOperation,
+    api_name: &str,
+  ) -> Result<(), AnyError>;
 }
 
 pub struct AllowAllNodePermissions;
@@ -102,6 +111,14 @@ impl NodePermissions for AllowAllNodePermissions {
   ) -> Result<(), AnyError> {
     Ok(())
   }
+  fn check_write_operation(
+    &mut self,
+    _path: &Path,
+    _operation: WriteOperation,
+    _api_name: &str,
+  ) -> Result<(), AnyError> {
+    Ok(())
+  }
   fn check_sys(
     &mut self,
     _kind: &str,
@@ -143,6 +160,18 @@ impl NodePermissions for deno_permissions::PermissionsContainer {
     )
   }
 
+  #[inline(always)]
+  fn check_write_operation(
+    &mut self,
+    path: &Path,
+    operation: WriteOperation,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    deno_permissions::PermissionsContainer::check_write_operation(
+      self, path, operation, api_name,
+    )
+  }
+
   fn check_sys(&mut self, kind: &str, api_name: &str) -> Result<(), AnyError> {
     deno_permissions::PermissionsContainer::check_sys(self, kind, api_name)
   }
@@ -293,6 +322,8 @@ deno_core::extension!(deno_node,
     ops::crypto::op_node_random_int,
     ops::crypto::op_node_scrypt_sync,
     ops::crypto::op_node_scrypt_async,
+    ops::crypto::op_node_argon2_sync,
+    ops::crypto::op_node_argon2_async,
     ops::crypto::op_node_ecdh_generate_keys,
     ops::crypto::op_node_ecdh_compute_secret,
     ops::crypto::op_node_ecdh_compute_public_key,
@@ -307,6 +338,7 @@ deno_core::extension!(deno_node,
     ops::crypto::x509::op_node_x509_fingerprint512,
     ops::crypto::x509::op_node_x509_get_issuer,
     ops::crypto::x509::op_node_x509_get_subject,
+    ops::crypto::x509::op_node_x509_get_subject_alt_name,
     ops::crypto::x509::op_node_x509_get_valid_from,
     ops::crypto::x509::op_node_x509_get_valid_to,
     ops::crypto::x509::op_node_x509_get_serial_number,
@@ -328,6 +360,19 @@ deno_core::extension!(deno_node,
     ops::vm::op_vm_script_run_in_context,
     ops::vm::op_vm_script_run_in_this_context,
     ops::vm::op_vm_is_context,
+    ops::vm::op_vm_compile_function,
+    ops::vm::op_vm_create_module,
+    ops::vm::op_vm_module_requests,
+    ops::vm::op_vm_module_link,
+    ops::vm::op_vm_module_instantiate,
+    ops::vm::op_vm_module_evaluate,
+    ops::vm::op_vm_module_status,
+    ops::vm::op_vm_module_namespace,
+    ops::vm::op_vm_module_error,
+    ops::inspector::op_inspector_connect,
+    ops::inspector::op_inspector_enabled,
+    ops::inspector::op_inspector_post,
+    ops::inspector::op_inspector_receive,
     ops::idna::op_node_idna_domain_to_ascii,
     ops::idna::op_node_idna_domain_to_unicode,
     ops::idna::op_node_idna_punycode_to_ascii,
@@ -402,6 +447,9 @@ deno_core::extension!(deno_node,
     ops::ipc::op_node_child_ipc_pipe,
     ops::ipc::op_node_ipc_write,
     ops::ipc::op_node_ipc_read,
+    ops::ipc::op_node_ipc_write_handle,
+    ops::ipc::op_node_ipc_take_tcp_stream,
+    ops::ipc::op_node_ipc_take_tcp_listener,
     ops::process::op_node_process_kill,
     ops::process::op_process_abort,
   ],
@@ -510,12 +558,19 @@ deno_core::extension!(deno_node,
     "internal/buffer.mjs",
     "internal/child_process.ts",
     "internal/cli_table.ts",
+    "internal/cluster/child.ts",
+    "internal/cluster/primary.ts",
+    "internal/cluster/round_robin_handle.ts",
+    "internal/cluster/shared_handle.ts",
+    "internal/cluster/utils.ts",
+    "internal/cluster/worker.ts",
     "internal/console/constructor.mjs",
     "internal/constants.ts",
     "internal/crypto/_keys.ts",
     "internal/crypto/_randomBytes.ts",
     "internal/crypto/_randomFill.mjs",
     "internal/crypto/_randomInt.ts",
+    "internal/crypto/argon2.ts",
     "internal/crypto/certificate.ts",
     "internal/crypto/cipher.ts",
     "internal/crypto/constants.ts",
@@ -41,6 +41,7 @@ use rsa::Oaep;
 use rsa::Pkcs1v15Encrypt;
 use rsa::RsaPrivateKey;
 use rsa::RsaPublicKey;
+use serde::Deserialize;
 use spki::EncodePublicKey;
 
 mod cipher;
@@ -961,7 +962,7 @@ fn scrypt(
     parallelization,
     keylen as usize,
   )
-  .unwrap();
+  .map_err(|_| type_error("Invalid scrypt params"))?;
 
   // Call into scrypt
   let res = scrypt::scrypt(&password, &salt, &params, output_buffer);
@@ -1031,6 +1032,85 @@ pub async fn op_node_scrypt_async(
   .await?
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Argon2Params {
+  algorithm: String,
+  message: StringOrBuffer,
+  nonce: StringOrBuffer,
+  parallelism: u32,
+  tag_length: u32,
+  memory: u32,
+  passes: u32,
+  secret: Option<StringOrBuffer>,
+  associated_data: Option<StringOrBuffer>,
+}
+
+fn argon2(
+  params: Argon2Params,
+  output_buffer: &mut [u8],
+) -> Result<(), AnyError> {
+  let algorithm = match params.algorithm.as_str() {
+    "argon2d" => argon2::Algorithm::Argon2d,
+    "argon2i" => argon2::Algorithm::Argon2i,
+    "argon2id" => argon2::Algorithm::Argon2id,
+    _ => {
+      return Err(type_error(format!(
+        "Invalid argon2 algorithm: {}",
+        params.algorithm
+      )))
+    }
+  };
+
+  let mut builder = argon2::ParamsBuilder::new();
+  builder
+    .m_cost(params.memory)
+    .t_cost(params.passes)
+    .p_cost(params.parallelism)
+    .output_len(params.tag_length as usize);
+  if let Some(associated_data) = &params.associated_data {
+    builder.data(
+      argon2::AssociatedData::new(associated_data)
+        .map_err(|e| type_error(e.to_string()))?,
+    );
+  }
+  let argon2_params = builder.build().map_err(|e| type_error(e.to_string()))?;
+
+  let secret = params.secret.as_deref().unwrap_or_default();
+  let hasher = argon2::Argon2::new_with_secret(
+    secret,
+    algorithm,
+    argon2::Version::V0x13,
+    argon2_params,
+  )
+  .map_err(|e| type_error(e.to_string()))?;
+
+  hasher
+    .hash_password_into(&params.message, &params.nonce, output_buffer)
+    .map_err(|e| generic_error(format!("argon2 failure: {e}")))
+}
+
+#[op2]
+pub fn op_node_argon2_sync(
+  #[serde] params: Argon2Params,
+  #[anybuffer] output_buffer: &mut [u8],
+) -> Result<(), AnyError> {
+  argon2(params, output_buffer)
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_node_argon2_async(
+  #[serde] params: Argon2Params,
+) -> Result<ToJsBuffer, AnyError> {
+  spawn_blocking(move || {
+    let mut output_buffer = vec![0u8; params.tag_length as usize];
+    argon2(params, &mut output_buffer)?;
+    Ok(output_buffer.into())
+  })
+  .await?
+}
+
 #[op2]
 #[buffer]
 pub fn op_node_ecdh_encode_pubkey(
@@ -14,7 +14,7 @@ use x509_parser::prelude::*;
 use digest::Digest;
 
 pub(crate) struct Certificate {
-  _buf: Vec<u8>,
+  buf: Vec<u8>,
   pem: Option<pem::Pem>,
   cert: X509Certificate<'static>,
 }
@@ -22,19 +22,21 @@ pub(crate) struct Certificate {
 impl deno_core::GarbageCollected for Certificate {}
 
 impl Certificate {
-  fn fingerprint<D: Digest>(&self) -> Option<String> {
-    self.pem.as_ref().map(|pem| {
-      let mut hasher = D::new();
-      hasher.update(&pem.contents);
-      let bytes = hasher.finalize();
-      // OpenSSL returns colon separated upper case hex values.
-      let mut hex = String::with_capacity(bytes.len() * 2);
-      for byte in bytes {
-        hex.push_str(&format!("{:02X}:", byte));
-      }
-      hex.pop();
-      hex
-    })
+  fn fingerprint<D: Digest>(&self) -> String {
+    let der = match &self.pem {
+      Some(pem) => &pem.contents,
+      None => &self.buf,
+    };
+    let mut hasher = D::new();
+    hasher.update(der);
+    let bytes = hasher.finalize();
+    // OpenSSL returns colon separated upper case hex values.
+    let mut hex = String::with_capacity(bytes.len() * 3);
+    for byte in bytes {
+      hex.push_str(&format!("{:02X}:", byte));
+    }
+    hex.pop();
+    hex
   }
 }
 
@@ -62,7 +64,7 @@ pub fn op_node_x509_parse<'s>(
     .unwrap_or_else(|| X509Certificate::from_der(buf).map(|(_, cert)| cert))?;
 
   let cert = Certificate {
-    _buf: buf.to_vec(),
+    buf: buf.to_vec(),
     // SAFETY: Extending the lifetime of the certificate. Backing buffer is
     // owned by the resource.
     cert: unsafe {
@@ -119,7 +121,7 @@ pub fn op_node_x509_check_email(
 #[string]
 pub fn op_node_x509_fingerprint(
   #[cppgc] cert: &Certificate,
-) -> Result<Option<String>, AnyError> {
+) -> Result<String, AnyError> {
   Ok(cert.fingerprint::<sha1::Sha1>())
 }
 
@@ -127,7 +129,7 @@ pub fn op_node_x509_fingerprint(
 #[string]
 pub fn op_node_x509_fingerprint256(
   #[cppgc] cert: &Certificate,
-) -> Result<Option<String>, AnyError> {
+) -> Result<String, AnyError> {
   Ok(cert.fingerprint::<sha2::Sha256>())
 }
 
@@ -135,7 +137,7 @@ pub fn op_node_x509_fingerprint256(
 #[string]
 pub fn op_node_x509_fingerprint512(
   #[cppgc] cert: &Certificate,
-) -> Result<Option<String>, AnyError> {
+) -> Result<String, AnyError> {
   Ok(cert.fingerprint::<sha2::Sha512>())
 }
 
@@ -155,6 +157,48 @@ pub fn op_node_x509_get_subject(
   Ok(x509name_to_string(cert.subject(), oid_registry())?)
 }
 
+/// Formats the subject alternative names like OpenSSL, for example
+/// "DNS:localhost, IP Address:127.0.0.1".
+#[op2]
+#[string]
+pub fn op_node_x509_get_subject_alt_name(
+  #[cppgc] cert: &Certificate,
+) -> Result<Option<String>, AnyError> {
+  let subject_alt = cert
+    .extensions()
+    .iter()
+    .find(|e| e.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME)
+    .and_then(|e| match e.parsed_extension() {
+      extensions::ParsedExtension::SubjectAlternativeName(s) => Some(s),
+      _ => None,
+    });
+
+  let Some(subject_alt) = subject_alt else {
+    return Ok(None);
+  };
+
+  let names = subject_alt
+    .general_names
+    .iter()
+    .filter_map(|name| match name {
+      extensions::GeneralName::DNSName(n) => Some(format!("DNS:{n}")),
+      extensions::GeneralName::RFC822Name(n) => Some(format!("email:{n}")),
+      extensions::GeneralName::URI(n) => Some(format!("URI:{n}")),
+      extensions::GeneralName::IPAddress(ip) => {
+        let ip = match ip.len() {
+          4 => std::net::IpAddr::from(<[u8; 4]>::try_from(*ip).ok()?),
+          16 => std::net::IpAddr::from(<[u8; 16]>::try_from(*ip).ok()?),
+          _ => return None,
+        };
+        Some(format!("IP Address:{ip}"))
+      }
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+
+  Ok(Some(names.join(", ")))
+}
+
 // Attempt to convert attribute to string. If type is not a string, return value is the hex
 // encoding of the attribute value
 fn attribute_value_to_string(
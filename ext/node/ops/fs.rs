@@ -9,10 +9,16 @@ use deno_core::error::AnyError;
 use deno_core::op2;
 use deno_core::OpState;
 use deno_fs::FileSystemRc;
+use deno_fs::WriteOperation;
 use serde::Serialize;
 
 use crate::NodePermissions;
 
+/// `cp` may both create files below the destination and overwrite existing
+/// ones.
+const CP_WRITE_OPERATIONS: [WriteOperation; 2] =
+  [WriteOperation::Create, WriteOperation::Modify];
+
 #[op2(fast)]
 pub fn op_node_fs_exists_sync<P>(
   state: &mut OpState,
@@ -62,12 +68,12 @@ where
   let path = Path::new(path);
   let new_path = Path::new(new_path);
 
-  state
-    .borrow_mut::<P>()
-    .check_read_with_api_name(path, Some("node:fs.cpSync"))?;
-  state
-    .borrow_mut::<P>()
-    .check_write_with_api_name(new_path, Some("node:fs.cpSync"))?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_read_with_api_name(path, Some("node:fs.cpSync"))?;
+  permissions.check_write_with_api_name(new_path, Some("node:fs.cpSync"))?;
+  for operation in CP_WRITE_OPERATIONS {
+    permissions.check_write_operation(new_path, operation, "node:fs.cpSync")?;
+  }
 
   let fs = state.borrow::<FileSystemRc>();
   fs.cp_sync(path, new_path)?;
@@ -88,12 +94,16 @@ where
 
   let fs = {
     let mut state = state.borrow_mut();
-    state
-      .borrow_mut::<P>()
-      .check_read_with_api_name(&path, Some("node:fs.cpSync"))?;
-    state
-      .borrow_mut::<P>()
-      .check_write_with_api_name(&new_path, Some("node:fs.cpSync"))?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_read_with_api_name(&path, Some("node:fs.cpSync"))?;
+    permissions.check_write_with_api_name(&new_path, Some("node:fs.cpSync"))?;
+    for operation in CP_WRITE_OPERATIONS {
+      permissions.check_write_operation(
+        &new_path,
+        operation,
+        "node:fs.cpSync",
+      )?;
+    }
     state.borrow::<FileSystemRc>().clone()
   };
 
@@ -258,9 +268,13 @@ where
 {
   let path = Path::new(path);
 
-  state
-    .borrow_mut::<P>()
-    .check_write_with_api_name(path, Some("node:fs.lutimes"))?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write_with_api_name(path, Some("node:fs.lutimes"))?;
+  permissions.check_write_operation(
+    path,
+    WriteOperation::Modify,
+    "node:fs.lutimes",
+  )?;
 
   let fs = state.borrow::<FileSystemRc>();
   fs.lutime_sync(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)?;
@@ -283,9 +297,14 @@ where
 
   let fs = {
     let mut state = state.borrow_mut();
-    state
-      .borrow_mut::<P>()
+    let permissions = state.borrow_mut::<P>();
+    permissions
       .check_write_with_api_name(&path, Some("node:fs.lutimesSync"))?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Modify,
+      "node:fs.lutimesSync",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
 
@@ -306,9 +325,13 @@ where
   P: NodePermissions + 'static,
 {
   let path = PathBuf::from(path);
-  state
-    .borrow_mut::<P>()
-    .check_write_with_api_name(&path, Some("node:fs.lchownSync"))?;
+  let permissions = state.borrow_mut::<P>();
+  permissions.check_write_with_api_name(&path, Some("node:fs.lchownSync"))?;
+  permissions.check_write_operation(
+    &path,
+    WriteOperation::Chmod,
+    "node:fs.lchownSync",
+  )?;
   let fs = state.borrow::<FileSystemRc>();
   fs.lchown_sync(&path, uid, gid)?;
   Ok(())
@@ -327,9 +350,13 @@ where
   let path = PathBuf::from(path);
   let fs = {
     let mut state = state.borrow_mut();
-    state
-      .borrow_mut::<P>()
-      .check_write_with_api_name(&path, Some("node:fs.lchown"))?;
+    let permissions = state.borrow_mut::<P>();
+    permissions.check_write_with_api_name(&path, Some("node:fs.lchown"))?;
+    permissions.check_write_operation(
+      &path,
+      WriteOperation::Chmod,
+      "node:fs.lchown",
+    )?;
     state.borrow::<FileSystemRc>().clone()
   };
   fs.lchown_async(path, uid, gid).await?;
@@ -123,5 +123,6 @@ where
   Ok(FetchReturn {
     request_rid,
     cancel_handle_rid: Some(cancel_handle_rid),
+    upload_progress_rid: None,
   })
 }
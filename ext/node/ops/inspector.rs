@@ -0,0 +1,116 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use deno_core::error::bad_resource_id;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc::UnboundedReceiver;
+use deno_core::futures::StreamExt;
+use deno_core::op2;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::AsyncRefCell;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::JsRuntimeInspector;
+use deno_core::LocalInspectorSession;
+use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
+use deno_core::ResourceId;
+
+/// A session with the inspector of the current thread, created by
+/// `inspector.Session.prototype.connect()`.
+struct InspectorSessionResource {
+  session: AsyncRefCell<LocalInspectorSession>,
+  notification_rx: AsyncRefCell<UnboundedReceiver<Value>>,
+  cancel: CancelHandle,
+}
+
+impl Resource for InspectorSessionResource {
+  fn name(&self) -> std::borrow::Cow<str> {
+    "inspectorSession".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+#[op2(fast)]
+pub fn op_inspector_enabled(state: &mut OpState) -> bool {
+  state.has::<Rc<RefCell<JsRuntimeInspector>>>()
+}
+
+#[op2(fast)]
+#[smi]
+pub fn op_inspector_connect(
+  state: &mut OpState,
+) -> Result<ResourceId, AnyError> {
+  let Some(inspector) = state.try_borrow::<Rc<RefCell<JsRuntimeInspector>>>()
+  else {
+    return Err(custom_error("NotSupported", "Inspector is not available"));
+  };
+  let mut session = inspector.borrow().create_local_session();
+  let notification_rx = session.take_notification_rx();
+  Ok(state.resource_table.add(InspectorSessionResource {
+    session: AsyncRefCell::new(session),
+    notification_rx: AsyncRefCell::new(notification_rx),
+    cancel: Default::default(),
+  }))
+}
+
+/// Posts a message to the inspector, resolving to either `{ result }` or the
+/// protocol error as `{ error }`.
+#[op2(async)]
+#[serde]
+pub async fn op_inspector_post(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+  #[string] method: String,
+  #[serde] params: Option<Value>,
+) -> Result<Value, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<InspectorSessionResource>(rid)
+    .map_err(|_| bad_resource_id())?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let mut session = RcRef::map(&resource, |r| &r.session).borrow_mut().await;
+  let result = session
+    .post_message(&method, params)
+    .or_cancel(cancel)
+    .await?;
+  match result {
+    Ok(result) => Ok(json!({ "result": result })),
+    // protocol errors are passed along as the serialized error object
+    Err(err) => match serde_json::from_str::<Value>(&err.to_string()) {
+      Ok(error) if error.is_object() => Ok(json!({ "error": error })),
+      _ => Err(err),
+    },
+  }
+}
+
+/// Resolves to the next notification from the inspector, or `null` once the
+/// session is disconnected.
+#[op2(async)]
+#[serde]
+pub async fn op_inspector_receive(
+  state: Rc<RefCell<OpState>>,
+  #[smi] rid: ResourceId,
+) -> Result<Option<Value>, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<InspectorSessionResource>(rid)
+    .map_err(|_| bad_resource_id())?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let mut notification_rx = RcRef::map(&resource, |r| &r.notification_rx)
+    .borrow_mut()
+    .await;
+  let notification = notification_rx.next().or_cancel(cancel).await;
+  Ok(notification.ok().flatten())
+}
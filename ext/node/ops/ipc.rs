@@ -6,12 +6,18 @@ pub struct ChildPipeFd(pub i64);
 
 mod impl_ {
   use std::cell::RefCell;
+  #[cfg(unix)]
+  use std::collections::VecDeque;
   use std::future::Future;
   use std::io;
   use std::mem;
   #[cfg(unix)]
+  use std::os::fd::AsRawFd;
+  #[cfg(unix)]
   use std::os::fd::FromRawFd;
   #[cfg(unix)]
+  use std::os::fd::OwnedFd;
+  #[cfg(unix)]
   use std::os::fd::RawFd;
   use std::pin::Pin;
   use std::rc::Rc;
@@ -19,6 +25,12 @@ mod impl_ {
   use std::task::Poll;
 
   use deno_core::error::bad_resource_id;
+  #[cfg(unix)]
+  use deno_core::error::custom_error;
+  #[cfg(not(unix))]
+  use deno_core::error::not_supported;
+  #[cfg(unix)]
+  use deno_core::error::type_error;
   use deno_core::error::AnyError;
   use deno_core::op2;
   use deno_core::serde_json;
@@ -28,10 +40,17 @@ mod impl_ {
   use deno_core::OpState;
   use deno_core::RcRef;
   use deno_core::ResourceId;
+  use deno_net::ops::IpAddr;
   use pin_project_lite::pin_project;
   use tokio::io::AsyncBufRead;
+  #[cfg(unix)]
+  use tokio::io::AsyncRead;
   use tokio::io::AsyncWriteExt;
   use tokio::io::BufReader;
+  #[cfg(unix)]
+  use tokio::io::Interest;
+  #[cfg(unix)]
+  use tokio::io::ReadBuf;
 
   #[cfg(unix)]
   use tokio::net::unix::OwnedReadHalf;
@@ -92,12 +111,145 @@ mod impl_ {
     Ok(msgs)
   }
 
+  /// Writes a message together with a TCP listener or connection, which the
+  /// other process receives as a handle of its own. The handle stays open in
+  /// this process.
+  #[op2(async)]
+  pub async fn op_node_ipc_write_handle(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+    #[serde] value: serde_json::Value,
+    #[smi] handle_rid: ResourceId,
+  ) -> Result<(), AnyError> {
+    #[cfg(unix)]
+    {
+      let stream = state
+        .borrow()
+        .resource_table
+        .get::<IpcJsonStreamResource>(rid)
+        .map_err(|_| bad_resource_id())?;
+      let handle = dup_handle_fd(&state, handle_rid).await?;
+      stream.write_msg_with_handle(value, handle).await?;
+      Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+      let _ = (state, rid, value, handle_rid);
+      Err(not_supported())
+    }
+  }
+
+  /// Turns the oldest handle received on the IPC channel into a TCP
+  /// connection resource.
+  #[op2]
+  #[serde]
+  pub fn op_node_ipc_take_tcp_stream(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+  ) -> Result<(ResourceId, IpAddr, IpAddr), AnyError> {
+    #[cfg(unix)]
+    {
+      let handle = take_received_handle(state, rid)?;
+      let stream = std::net::TcpStream::from(handle);
+      stream.set_nonblocking(true)?;
+      let stream = tokio::net::TcpStream::from_std(stream)?;
+      let local_addr = stream.local_addr()?;
+      let remote_addr = stream.peer_addr()?;
+      let rid = state
+        .resource_table
+        .add(deno_net::io::TcpStreamResource::new(stream.into_split()));
+      Ok((rid, IpAddr::from(local_addr), IpAddr::from(remote_addr)))
+    }
+    #[cfg(not(unix))]
+    {
+      let _ = (state, rid);
+      Err(not_supported())
+    }
+  }
+
+  /// Turns the oldest handle received on the IPC channel into a TCP listener
+  /// resource.
+  #[op2]
+  #[serde]
+  pub fn op_node_ipc_take_tcp_listener(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+  ) -> Result<(ResourceId, IpAddr), AnyError> {
+    #[cfg(unix)]
+    {
+      let handle = take_received_handle(state, rid)?;
+      let listener = deno_net::tcp::TcpListener::from_std(
+        std::net::TcpListener::from(handle),
+      )?;
+      let local_addr = listener.local_addr()?;
+      let rid = state
+        .resource_table
+        .add(deno_net::raw::NetworkListenerResource::new(listener));
+      Ok((rid, IpAddr::from(local_addr)))
+    }
+    #[cfg(not(unix))]
+    {
+      let _ = (state, rid);
+      Err(not_supported())
+    }
+  }
+
+  #[cfg(unix)]
+  fn take_received_handle(
+    state: &mut OpState,
+    rid: ResourceId,
+  ) -> Result<OwnedFd, AnyError> {
+    let stream = state
+      .resource_table
+      .get::<IpcJsonStreamResource>(rid)
+      .map_err(|_| bad_resource_id())?;
+    let handle = stream.handles.borrow_mut().pop_front();
+    handle.ok_or_else(|| {
+      custom_error("NotFound", "No handle was received with the message")
+    })
+  }
+
+  /// Duplicates the socket of the TCP listener or connection `rid`, to be
+  /// sent to another process.
+  #[cfg(unix)]
+  async fn dup_handle_fd(
+    state: &Rc<RefCell<OpState>>,
+    rid: ResourceId,
+  ) -> Result<OwnedFd, AnyError> {
+    use deno_net::io::TcpStreamResource;
+    use deno_net::raw::NetworkListenerResource;
+    use deno_net::tcp::TcpListener;
+    use std::os::fd::AsFd;
+
+    let listener = state
+      .borrow()
+      .resource_table
+      .get::<NetworkListenerResource<TcpListener>>(rid);
+    if let Ok(resource) = listener {
+      let listener = RcRef::map(&resource, |r| &r.listener)
+        .try_borrow()
+        .ok_or_else(|| custom_error("Busy", "Listener is currently in use"))?;
+      return Ok(listener.as_fd().try_clone_to_owned()?);
+    }
+    let stream = state
+      .borrow()
+      .resource_table
+      .get::<TcpStreamResource>(rid)
+      .map_err(|_| type_error("Only TCP handles can be sent"))?;
+    let write_half = stream.wr_borrow_mut().await;
+    let stream: &tokio::net::TcpStream = (*write_half).as_ref();
+    Ok(stream.as_fd().try_clone_to_owned()?)
+  }
+
   pub struct IpcJsonStreamResource {
     read_half: AsyncRefCell<IpcJsonStream>,
     #[cfg(unix)]
     write_half: AsyncRefCell<OwnedWriteHalf>,
     #[cfg(windows)]
     write_half: AsyncRefCell<tokio::io::WriteHalf<NamedPipeClient>>,
+    /// Handles received along with messages, in the order they arrived.
+    #[cfg(unix)]
+    handles: Rc<RefCell<VecDeque<OwnedFd>>>,
     cancel: Rc<CancelHandle>,
   }
 
@@ -134,6 +286,13 @@ mod impl_ {
   }
 
   impl IpcJsonStreamResource {
+    #[cfg(unix)]
+    pub fn new(stream: i64) -> Result<Self, std::io::Error> {
+      let (read_half, write_half) = pipe(stream as _)?;
+      Ok(Self::from_halves(read_half, write_half))
+    }
+
+    #[cfg(windows)]
     pub fn new(stream: i64) -> Result<Self, std::io::Error> {
       let (read_half, write_half) = pipe(stream as _)?;
       Ok(Self {
@@ -144,16 +303,29 @@ mod impl_ {
     }
 
     #[cfg(unix)]
-    #[cfg(test)]
-    fn from_stream(stream: UnixStream) -> Self {
-      let (read_half, write_half) = stream.into_split();
+    fn from_halves(
+      read_half: OwnedReadHalf,
+      write_half: OwnedWriteHalf,
+    ) -> Self {
+      let handles = Rc::new(RefCell::new(VecDeque::new()));
       Self {
-        read_half: AsyncRefCell::new(IpcJsonStream::new(read_half)),
+        read_half: AsyncRefCell::new(IpcJsonStream::new(IpcReadHalf {
+          stream: read_half,
+          handles: handles.clone(),
+        })),
         write_half: AsyncRefCell::new(write_half),
+        handles,
         cancel: Default::default(),
       }
     }
 
+    #[cfg(unix)]
+    #[cfg(test)]
+    fn from_stream(stream: UnixStream) -> Self {
+      let (read_half, write_half) = stream.into_split();
+      Self::from_halves(read_half, write_half)
+    }
+
     #[cfg(windows)]
     #[cfg(test)]
     fn from_stream(pipe: NamedPipeClient) -> Self {
@@ -179,6 +351,160 @@ mod impl_ {
       write_half.write_all(&buf).await?;
       Ok(())
     }
+
+    #[cfg(unix)]
+    async fn write_msg_with_handle(
+      self: Rc<Self>,
+      msg: serde_json::Value,
+      handle: OwnedFd,
+    ) -> Result<(), AnyError> {
+      let mut write_half =
+        RcRef::map(self, |r| &r.write_half).borrow_mut().await;
+      let mut buf = Vec::new();
+      serde_json::to_writer(&mut buf, &msg)?;
+      buf.push(b'\n');
+      // The handle is attached to the first chunk of the message, so it is
+      // received no later than the message itself.
+      let stream: &UnixStream = (*write_half).as_ref();
+      let n = stream
+        .async_io(Interest::WRITABLE, || {
+          send_with_fd(stream.as_raw_fd(), &buf, handle.as_raw_fd())
+        })
+        .await?;
+      write_half.write_all(&buf[n..]).await?;
+      Ok(())
+    }
+  }
+
+  /// Room for the control messages of a single `recvmsg` call. Handles are
+  /// sent one per message, but one read can span several messages.
+  #[cfg(unix)]
+  const CMSG_BUFFER_LEN: usize = 16;
+
+  /// The read half of a unix IPC socket, which collects the file descriptors
+  /// sent along with the data.
+  #[cfg(unix)]
+  struct IpcReadHalf {
+    stream: OwnedReadHalf,
+    handles: Rc<RefCell<VecDeque<OwnedFd>>>,
+  }
+
+  #[cfg(unix)]
+  impl AsyncRead for IpcReadHalf {
+    fn poll_read(
+      self: Pin<&mut Self>,
+      cx: &mut Context<'_>,
+      buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+      let this = self.get_mut();
+      let stream: &UnixStream = this.stream.as_ref();
+      loop {
+        std::task::ready!(stream.poll_read_ready(cx))?;
+        let res = stream.try_io(Interest::READABLE, || {
+          recv_with_fds(
+            stream.as_raw_fd(),
+            buf.initialize_unfilled(),
+            &mut this.handles.borrow_mut(),
+          )
+        });
+        match res {
+          Ok(n) => {
+            buf.advance(n);
+            return Poll::Ready(Ok(()));
+          }
+          Err(err) if err.kind() == io::ErrorKind::WouldBlock => continue,
+          Err(err) => return Poll::Ready(Err(err)),
+        }
+      }
+    }
+  }
+
+  #[cfg(unix)]
+  fn recv_with_fds(
+    fd: RawFd,
+    buf: &mut [u8],
+    handles: &mut VecDeque<OwnedFd>,
+  ) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+      iov_base: buf.as_mut_ptr().cast(),
+      iov_len: buf.len(),
+    };
+    // `u64` for the alignment of `cmsghdr`.
+    let mut cmsg_buf = [0u64; CMSG_BUFFER_LEN];
+    // SAFETY: all-zero is a valid `msghdr`.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg_buf.as_mut_ptr().cast();
+    msg.msg_controllen = mem::size_of_val(&cmsg_buf) as _;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let flags = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let flags = 0;
+
+    // SAFETY: `msg` points to buffers that are valid for writes of the given
+    // lengths.
+    let n = unsafe { libc::recvmsg(fd, &mut msg, flags) };
+    if n < 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: `msg` was filled in by `recvmsg`, so the control messages it
+    // points to are well formed, and the fds in them are owned by us.
+    unsafe {
+      let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+      while !cmsg.is_null() {
+        if (*cmsg).cmsg_level == libc::SOL_SOCKET
+          && (*cmsg).cmsg_type == libc::SCM_RIGHTS
+        {
+          let data = libc::CMSG_DATA(cmsg) as *const libc::c_int;
+          let len = ((*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize)
+            / mem::size_of::<libc::c_int>();
+          for i in 0..len {
+            let fd = OwnedFd::from_raw_fd(data.add(i).read_unaligned());
+            #[cfg(not(any(target_os = "linux", target_os = "android")))]
+            libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+            handles.push_back(fd);
+          }
+        }
+        cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+      }
+    }
+    Ok(n as usize)
+  }
+
+  #[cfg(unix)]
+  fn send_with_fd(fd: RawFd, buf: &[u8], handle: RawFd) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+      iov_base: buf.as_ptr() as *mut libc::c_void,
+      iov_len: buf.len(),
+    };
+    let mut cmsg_buf = [0u64; CMSG_BUFFER_LEN];
+    // SAFETY: all-zero is a valid `msghdr`.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg_buf.as_mut_ptr().cast();
+    // SAFETY: `CMSG_SPACE` only does arithmetic.
+    msg.msg_controllen =
+      unsafe { libc::CMSG_SPACE(mem::size_of::<libc::c_int>() as _) } as _;
+
+    // SAFETY: the control buffer has room for a control message with a
+    // single fd, and `msg` points to buffers valid for the given lengths.
+    let n = unsafe {
+      let cmsg = libc::CMSG_FIRSTHDR(&msg);
+      (*cmsg).cmsg_level = libc::SOL_SOCKET;
+      (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+      (*cmsg).cmsg_len =
+        libc::CMSG_LEN(mem::size_of::<libc::c_int>() as _) as _;
+      (libc::CMSG_DATA(cmsg) as *mut libc::c_int).write_unaligned(handle);
+      libc::sendmsg(fd, &msg, 0)
+    };
+    if n < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(n as usize)
   }
 
   #[inline]
@@ -204,7 +530,7 @@ mod impl_ {
   // `\n` is used as a delimiter between messages.
   struct IpcJsonStream {
     #[cfg(unix)]
-    pipe: BufReader<OwnedReadHalf>,
+    pipe: BufReader<IpcReadHalf>,
     #[cfg(windows)]
     pipe: BufReader<tokio::io::ReadHalf<NamedPipeClient>>,
     buffer: Vec<u8>,
@@ -212,7 +538,7 @@ mod impl_ {
 
   impl IpcJsonStream {
     #[cfg(unix)]
-    fn new(pipe: OwnedReadHalf) -> Self {
+    fn new(pipe: IpcReadHalf) -> Self {
       Self {
         pipe: BufReader::with_capacity(INITIAL_CAPACITY, pipe),
         buffer: Vec::with_capacity(INITIAL_CAPACITY),
@@ -555,6 +881,37 @@ mod impl_ {
       Ok(())
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_ipc_handle() -> Result<(), Box<dyn std::error::Error>> {
+      use std::io::Read;
+      use std::io::Write;
+
+      let (a, b) = tokio::net::UnixStream::pair()?;
+      let a = Rc::new(IpcJsonStreamResource::from_stream(a));
+      let b = Rc::new(IpcJsonStreamResource::from_stream(b));
+
+      let (mut sent, handle) = std::os::unix::net::UnixStream::pair()?;
+      a.clone()
+        .write_msg_with_handle(json!("hello"), handle.into())
+        .await?;
+      a.clone().write_msg(json!("world")).await?;
+
+      let mut read_half = RcRef::map(&b, |r| &r.read_half).borrow_mut().await;
+      assert_eq!(read_half.read_msg().await?, json!("hello"));
+      assert_eq!(read_half.read_msg().await?, json!("world"));
+
+      let received = b.handles.borrow_mut().pop_front().unwrap();
+      assert!(b.handles.borrow().is_empty());
+      let mut received = std::os::unix::net::UnixStream::from(received);
+      sent.write_all(b"x")?;
+      let mut buf = [0u8; 1];
+      received.read_exact(&mut buf)?;
+      assert_eq!(&buf, b"x");
+
+      Ok(())
+    }
+
     #[test]
     fn memchr() {
       let str = b"hello world";
@@ -291,9 +291,11 @@ where
 {
   let path = PathBuf::from(request);
   ensure_read_permission::<P>(state, &path)?;
-  let fs = state.borrow::<FileSystemRc>();
-  let canonicalized_path =
-    deno_core::strip_unc_prefix(fs.realpath_sync(&path)?);
+  let canonicalized_path = match state.try_borrow::<NodeResolverRc>() {
+    Some(node_resolver) => node_resolver.realpath(&path)?,
+    None => state.borrow::<FileSystemRc>().realpath_sync(&path)?,
+  };
+  let canonicalized_path = deno_core::strip_unc_prefix(canonicalized_path);
   Ok(canonicalized_path.to_string_lossy().to_string())
 }
 
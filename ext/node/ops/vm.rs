@@ -61,13 +61,7 @@ impl Script {
     scope: &mut v8::HandleScope<'s>,
     sandbox: v8::Local<'s, v8::Value>,
   ) -> Result<v8::Local<'s, v8::Value>, AnyError> {
-    let context = if let Ok(sandbox_obj) = sandbox.try_into() {
-      let context = i::ContextifyContext::from_sandbox_obj(scope, sandbox_obj)
-        .ok_or_else(|| type_error("Invalid sandbox object"))?;
-      context.context(scope)
-    } else {
-      scope.get_current_context()
-    };
+    let context = context_from_sandbox(scope, sandbox)?;
 
     let context_scope = &mut v8::ContextScope::new(scope, context);
     let mut scope = v8::EscapableHandleScope::new(context_scope);
@@ -79,6 +73,21 @@ impl Script {
   }
 }
 
+/// Returns the context of the contextified object `sandbox`, or the current
+/// context if `sandbox` is not an object.
+fn context_from_sandbox<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  sandbox: v8::Local<'s, v8::Value>,
+) -> Result<v8::Local<'s, v8::Context>, AnyError> {
+  if let Ok(sandbox_obj) = sandbox.try_into() {
+    let context = i::ContextifyContext::from_sandbox_obj(scope, sandbox_obj)
+      .ok_or_else(|| type_error("Invalid sandbox object"))?;
+    Ok(context.context(scope))
+  } else {
+    Ok(scope.get_current_context())
+  }
+}
+
 #[op2]
 pub fn op_vm_create_script<'a>(
   scope: &mut v8::HandleScope<'a>,
@@ -132,6 +141,124 @@ pub fn op_vm_is_context(
     .unwrap_or(false)
 }
 
+#[op2]
+pub fn op_vm_compile_function<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  source: v8::Local<'a, v8::String>,
+  filename: v8::Local<'a, v8::String>,
+  #[serde] params: Vec<String>,
+  sandbox: v8::Local<'a, v8::Value>,
+) -> Result<v8::Local<'a, v8::Value>, AnyError> {
+  let context = context_from_sandbox(scope, sandbox)?;
+  let params = params
+    .iter()
+    .map(|param| {
+      v8::String::new(scope, param)
+        .ok_or_else(|| type_error("Invalid parameter name"))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let context_scope = &mut v8::ContextScope::new(scope, context);
+  let mut scope = v8::EscapableHandleScope::new(context_scope);
+  let result = i::compile_function(&mut scope, source, filename, &params)
+    .map(|function| function.into())
+    .unwrap_or_else(|| v8::undefined(&mut scope).into());
+  Ok(scope.escape(result))
+}
+
+#[op2]
+pub fn op_vm_create_module<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  source: v8::Local<'a, v8::String>,
+  identifier: v8::Local<'a, v8::String>,
+  sandbox: v8::Local<'a, v8::Value>,
+) -> Result<v8::Local<'a, v8::Value>, AnyError> {
+  let context = context_from_sandbox(scope, sandbox)?;
+  let module = {
+    let context_scope = &mut v8::ContextScope::new(scope, context);
+    i::ContextifyModule::new(context_scope, context, source, identifier)
+  };
+
+  Ok(match module {
+    Some(module) => deno_core::cppgc::make_cppgc_object(scope, module).into(),
+    None => v8::undefined(scope).into(),
+  })
+}
+
+#[op2]
+#[serde]
+pub fn op_vm_module_requests(
+  scope: &mut v8::HandleScope,
+  #[cppgc] module: &i::ContextifyModule,
+) -> Vec<String> {
+  module.requests(scope)
+}
+
+#[op2]
+pub fn op_vm_module_link(
+  scope: &mut v8::HandleScope,
+  #[cppgc] module: &i::ContextifyModule,
+  #[string] specifier: String,
+  dependency: v8::Local<v8::Object>,
+) -> Result<(), AnyError> {
+  if i::ContextifyModule::from_wrapper(scope, dependency.into()).is_none() {
+    return Err(type_error("Invalid module"));
+  }
+  module.link(scope, specifier, dependency);
+  Ok(())
+}
+
+#[op2]
+pub fn op_vm_module_instantiate(
+  scope: &mut v8::HandleScope,
+  #[cppgc] module: &i::ContextifyModule,
+) {
+  let context = module.context(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  module.instantiate(scope);
+}
+
+#[op2(reentrant)]
+pub fn op_vm_module_evaluate<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  #[cppgc] module: &i::ContextifyModule,
+) -> v8::Local<'a, v8::Value> {
+  let context = module.context(scope);
+  let context_scope = &mut v8::ContextScope::new(scope, context);
+  let mut scope = v8::EscapableHandleScope::new(context_scope);
+  let result = module
+    .evaluate(&mut scope)
+    .unwrap_or_else(|| v8::undefined(&mut scope).into());
+  scope.escape(result)
+}
+
+/// Returns the `v8::ModuleStatus` of the module as a number.
+#[op2]
+pub fn op_vm_module_status(
+  scope: &mut v8::HandleScope,
+  #[cppgc] module: &i::ContextifyModule,
+) -> u32 {
+  module.module(scope).get_status() as u32
+}
+
+#[op2]
+pub fn op_vm_module_namespace<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  #[cppgc] module: &i::ContextifyModule,
+) -> v8::Local<'a, v8::Value> {
+  let module = module.module(scope);
+  v8::Local::new(scope, module.get_module_namespace())
+}
+
+#[op2]
+pub fn op_vm_module_error<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  #[cppgc] module: &i::ContextifyModule,
+) -> v8::Local<'a, v8::Value> {
+  let module = module.module(scope);
+  v8::Local::new(scope, module.get_exception())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
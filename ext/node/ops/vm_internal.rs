@@ -1,5 +1,8 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::v8;
@@ -194,6 +197,276 @@ impl ContextifyContext {
   }
 }
 
+fn script_origin<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  resource_name: v8::Local<'s, v8::String>,
+  is_module: bool,
+) -> v8::ScriptOrigin<'s> {
+  let source_map_url = v8::String::empty(scope);
+  v8::ScriptOrigin::new(
+    scope,
+    resource_name.into(),
+    0,
+    0,
+    false,
+    0,
+    source_map_url.into(),
+    false,
+    false,
+    is_module,
+  )
+}
+
+/// Compiles `source_str` as the body of a function with the parameters
+/// `params` in the current context.
+pub fn compile_function<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  source_str: v8::Local<v8::String>,
+  filename: v8::Local<v8::String>,
+  params: &[v8::Local<v8::String>],
+) -> Option<v8::Local<'s, v8::Function>> {
+  let tc_scope = &mut v8::TryCatch::new(scope);
+
+  let origin = script_origin(tc_scope, filename, false);
+  let source = v8::script_compiler::Source::new(source_str, Some(&origin));
+  let function = v8::script_compiler::compile_function(
+    tc_scope,
+    source,
+    params,
+    &[],
+    v8::script_compiler::CompileOptions::NoCompileOptions,
+    v8::script_compiler::NoCacheReason::NoReason,
+  );
+
+  if tc_scope.has_caught() {
+    if !tc_scope.has_terminated() {
+      tc_scope.rethrow();
+    }
+
+    return None;
+  }
+
+  function
+}
+
+type ResolvedModules = Vec<(
+  v8::Global<v8::Module>,
+  HashMap<String, v8::Global<v8::Module>>,
+)>;
+
+thread_local! {
+  /// The modules of the graph that is being instantiated, with the modules
+  /// their imports resolve to, for `resolve_module` to look up.
+  static RESOLVING: RefCell<ResolvedModules> =
+    const { RefCell::new(Vec::new()) };
+}
+
+/// A module compiled in a context. The modules its imports resolve to are
+/// linked from JavaScript before it is instantiated.
+pub struct ContextifyModule {
+  module: v8::TracedReference<v8::Module>,
+  context: v8::TracedReference<v8::Context>,
+  /// The wrappers of the linked modules, by specifier.
+  linked: RefCell<HashMap<String, v8::TracedReference<v8::Object>>>,
+}
+
+impl deno_core::GarbageCollected for ContextifyModule {
+  fn trace(&self, visitor: &v8::cppgc::Visitor) {
+    visitor.trace(&self.module);
+    visitor.trace(&self.context);
+    for wrapper in self.linked.borrow().values() {
+      visitor.trace(wrapper);
+    }
+  }
+}
+
+impl ContextifyModule {
+  /// Compiles `source_str` as a module in `context`, which must be the
+  /// current context.
+  pub fn new(
+    scope: &mut v8::HandleScope,
+    context: v8::Local<v8::Context>,
+    source_str: v8::Local<v8::String>,
+    identifier: v8::Local<v8::String>,
+  ) -> Option<Self> {
+    let tc_scope = &mut v8::TryCatch::new(scope);
+
+    let origin = script_origin(tc_scope, identifier, true);
+    let source = v8::script_compiler::Source::new(source_str, Some(&origin));
+    let Some(module) = v8::script_compiler::compile_module(tc_scope, source)
+    else {
+      if !tc_scope.has_terminated() {
+        tc_scope.rethrow();
+      }
+      return None;
+    };
+
+    Some(Self {
+      module: v8::TracedReference::new(tc_scope, module),
+      context: v8::TracedReference::new(tc_scope, context),
+      linked: RefCell::new(HashMap::new()),
+    })
+  }
+
+  pub fn from_wrapper<'a>(
+    scope: &mut v8::HandleScope<'a>,
+    wrapper: v8::Local<v8::Value>,
+  ) -> Option<&'a Self> {
+    deno_core::cppgc::try_unwrap_cppgc_object::<Self>(scope, wrapper)
+      // SAFETY: the lifetime of the scope does not actually bind to the
+      // lifetime of this reference at all, but the wrapper we read it from
+      // does, so it will be alive at least that long.
+      .map(|r| unsafe { &*(&*r as *const _) })
+  }
+
+  pub fn module<'a>(
+    &self,
+    scope: &mut v8::HandleScope<'a>,
+  ) -> v8::Local<'a, v8::Module> {
+    self.module.get(scope).unwrap()
+  }
+
+  pub fn context<'a>(
+    &self,
+    scope: &mut v8::HandleScope<'a>,
+  ) -> v8::Local<'a, v8::Context> {
+    self.context.get(scope).unwrap()
+  }
+
+  /// The specifiers of the imports of the module, in source order.
+  pub fn requests(&self, scope: &mut v8::HandleScope) -> Vec<String> {
+    let module = self.module(scope);
+    let requests = module.get_module_requests();
+    (0..requests.length())
+      .map(|i| {
+        let request = v8::Local::<v8::ModuleRequest>::try_from(
+          requests.get(scope, i).unwrap(),
+        )
+        .unwrap();
+        request.get_specifier().to_rust_string_lossy(scope)
+      })
+      .collect()
+  }
+
+  /// Resolves imports of `specifier` to the module of `wrapper`.
+  pub fn link(
+    &self,
+    scope: &mut v8::HandleScope,
+    specifier: String,
+    wrapper: v8::Local<v8::Object>,
+  ) {
+    let wrapper = v8::TracedReference::new(scope, wrapper);
+    self.linked.borrow_mut().insert(specifier, wrapper);
+  }
+
+  /// Instantiates the module and the modules linked to it, which must be
+  /// done in the context of the module.
+  pub fn instantiate(&self, scope: &mut v8::HandleScope) -> Option<bool> {
+    let mut graph = Vec::new();
+    self.collect_graph(scope, &mut graph);
+    RESOLVING.with(|resolving| *resolving.borrow_mut() = graph);
+
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let module = self.module(tc_scope);
+    let result = module.instantiate_module(tc_scope, resolve_module);
+    RESOLVING.with(|resolving| resolving.borrow_mut().clear());
+
+    if tc_scope.has_caught() {
+      if !tc_scope.has_terminated() {
+        tc_scope.rethrow();
+      }
+
+      return None;
+    }
+
+    result
+  }
+
+  fn collect_graph(
+    &self,
+    scope: &mut v8::HandleScope,
+    graph: &mut ResolvedModules,
+  ) {
+    let module = self.module(scope);
+    if graph
+      .iter()
+      .any(|(other, _)| v8::Local::new(scope, other) == module)
+    {
+      return;
+    }
+
+    let mut resolved = HashMap::new();
+    let mut dependencies = Vec::new();
+    for (specifier, wrapper) in self.linked.borrow().iter() {
+      let wrapper = wrapper.get(scope).unwrap();
+      let dependency = Self::from_wrapper(scope, wrapper.into()).unwrap();
+      let dependency_module = dependency.module(scope);
+      resolved
+        .insert(specifier.clone(), v8::Global::new(scope, dependency_module));
+      dependencies.push(dependency);
+    }
+    graph.push((v8::Global::new(scope, module), resolved));
+
+    for dependency in dependencies {
+      dependency.collect_graph(scope, graph);
+    }
+  }
+
+  /// Evaluates the module, which must be done in the context of the module.
+  /// Returns a promise for the completion of the evaluation.
+  pub fn evaluate<'s>(
+    &self,
+    scope: &mut v8::HandleScope<'s>,
+  ) -> Option<v8::Local<'s, v8::Value>> {
+    let tc_scope = &mut v8::TryCatch::new(scope);
+
+    let module = self.module(tc_scope);
+    let result = module.evaluate(tc_scope);
+
+    if tc_scope.has_caught() {
+      if !tc_scope.has_terminated() {
+        tc_scope.rethrow();
+      }
+
+      return None;
+    }
+
+    result
+  }
+}
+
+fn resolve_module<'s>(
+  context: v8::Local<'s, v8::Context>,
+  specifier: v8::Local<'s, v8::String>,
+  _import_attributes: v8::Local<'s, v8::FixedArray>,
+  referrer: v8::Local<'s, v8::Module>,
+) -> Option<v8::Local<'s, v8::Module>> {
+  // SAFETY: `CallbackScope` can be safely constructed from `Local<Context>`
+  let scope = &mut unsafe { v8::CallbackScope::new(context) };
+
+  let specifier = specifier.to_rust_string_lossy(scope);
+  let resolved = RESOLVING.with(|resolving| {
+    let resolving = resolving.borrow();
+    let (_, resolved) = resolving
+      .iter()
+      .find(|(module, _)| v8::Local::new(scope, module) == referrer)?;
+    let module = resolved.get(&specifier)?;
+    Some(v8::Local::new(scope, module))
+  });
+
+  if resolved.is_none() {
+    let message = v8::String::new(
+      scope,
+      &format!("Cannot resolve module \"{specifier}\", it was not linked"),
+    )
+    .unwrap();
+    let exception = v8::Exception::error(scope, message);
+    scope.throw_exception(exception);
+  }
+
+  resolved
+}
+
 pub const VM_CONTEXT_INDEX: usize = 0;
 
 #[derive(PartialEq)]
@@ -144,63 +144,80 @@ fn encoder_param(param: u8) -> BrotliEncoderParameter {
   unsafe { std::mem::transmute(param as u32) }
 }
 
-#[op2(fast)]
-#[number]
+// Size of the intermediate buffer the streaming ops write into before the
+// output is collected and handed back to JS.
+const STREAM_CHUNK_SIZE: usize = 16 * 1024;
+
+fn compress_stream(
+  inst: &mut BrotliEncoderStateStruct<StandardAlloc>,
+  op: BrotliEncoderOperation,
+  input: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  let mut output = Vec::new();
+  let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+  let mut available_in = input.len();
+  let mut next_in = 0;
+
+  loop {
+    let mut available_out = chunk.len();
+    let mut next_out = 0;
+    let result = inst.compress_stream(
+      op,
+      &mut available_in,
+      input,
+      &mut next_in,
+      &mut available_out,
+      &mut chunk,
+      &mut next_out,
+      &mut None,
+      &mut |_, _, _, _| (),
+    );
+    if !result {
+      return Err(type_error("Failed to compress"));
+    }
+    output.extend_from_slice(&chunk[..next_out]);
+
+    let done = match op {
+      BrotliEncoderOperation::BROTLI_OPERATION_FINISH => inst.is_finished(),
+      _ => available_in == 0 && !inst.has_more_output(),
+    };
+    if done {
+      break;
+    }
+  }
+
+  Ok(output.into())
+}
+
+#[op2]
+#[serde]
 pub fn op_brotli_compress_stream(
   state: &mut OpState,
   #[smi] rid: u32,
   #[buffer] input: &[u8],
-  #[buffer] output: &mut [u8],
-) -> Result<usize, AnyError> {
+) -> Result<ToJsBuffer, AnyError> {
   let ctx = state.resource_table.get::<BrotliCompressCtx>(rid)?;
   let mut inst = ctx.inst.borrow_mut();
-  let mut output_offset = 0;
-
-  let result = inst.compress_stream(
+  compress_stream(
+    &mut inst,
     BrotliEncoderOperation::BROTLI_OPERATION_PROCESS,
-    &mut input.len(),
     input,
-    &mut 0,
-    &mut output.len(),
-    output,
-    &mut output_offset,
-    &mut None,
-    &mut |_, _, _, _| (),
-  );
-  if !result {
-    return Err(type_error("Failed to compress"));
-  }
-
-  Ok(output_offset)
+  )
 }
 
-#[op2(fast)]
-#[number]
+#[op2]
+#[serde]
 pub fn op_brotli_compress_stream_end(
   state: &mut OpState,
   #[smi] rid: u32,
-  #[buffer] output: &mut [u8],
-) -> Result<usize, AnyError> {
+) -> Result<ToJsBuffer, AnyError> {
   let ctx = state.resource_table.get::<BrotliCompressCtx>(rid)?;
   let mut inst = ctx.inst.borrow_mut();
-  let mut output_offset = 0;
-
-  let result = inst.compress_stream(
+  compress_stream(
+    &mut inst,
     BrotliEncoderOperation::BROTLI_OPERATION_FINISH,
-    &mut 0,
     &[],
-    &mut 0,
-    &mut output.len(),
-    output,
-    &mut output_offset,
-    &mut None,
-    &mut |_, _, _, _| (),
-  );
-  if !result {
-    return Err(type_error("Failed to compress"));
-  }
-
-  Ok(output_offset)
+  )
 }
 
 fn brotli_decompress(buffer: &[u8]) -> Result<ToJsBuffer, AnyError> {
@@ -245,59 +262,61 @@ pub fn op_create_brotli_decompress(state: &mut OpState) -> u32 {
   })
 }
 
-#[op2(fast)]
-#[number]
+fn decompress_stream(
+  inst: &mut BrotliState<StandardAlloc, StandardAlloc, StandardAlloc>,
+  input: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  let mut output = Vec::new();
+  let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+  let mut available_in = input.len();
+  let mut next_in = 0;
+
+  loop {
+    let mut available_out = chunk.len();
+    let mut next_out = 0;
+    let result = BrotliDecompressStream(
+      &mut available_in,
+      &mut next_in,
+      input,
+      &mut available_out,
+      &mut next_out,
+      &mut chunk,
+      &mut 0,
+      inst,
+    );
+    output.extend_from_slice(&chunk[..next_out]);
+
+    match result {
+      BrotliResult::ResultFailure => {
+        return Err(type_error("Failed to decompress"))
+      }
+      BrotliResult::NeedsMoreOutput => continue,
+      BrotliResult::NeedsMoreInput | BrotliResult::ResultSuccess => break,
+    }
+  }
+
+  Ok(output.into())
+}
+
+#[op2]
+#[serde]
 pub fn op_brotli_decompress_stream(
   state: &mut OpState,
   #[smi] rid: u32,
   #[buffer] input: &[u8],
-  #[buffer] output: &mut [u8],
-) -> Result<usize, AnyError> {
+) -> Result<ToJsBuffer, AnyError> {
   let ctx = state.resource_table.get::<BrotliDecompressCtx>(rid)?;
   let mut inst = ctx.inst.borrow_mut();
-  let mut output_offset = 0;
-
-  let result = BrotliDecompressStream(
-    &mut input.len(),
-    &mut 0,
-    input,
-    &mut output.len(),
-    &mut output_offset,
-    output,
-    &mut 0,
-    &mut inst,
-  );
-  if matches!(result, BrotliResult::ResultFailure) {
-    return Err(type_error("Failed to decompress"));
-  }
-
-  Ok(output_offset)
+  decompress_stream(&mut inst, input)
 }
 
-#[op2(fast)]
-#[number]
+#[op2]
+#[serde]
 pub fn op_brotli_decompress_stream_end(
   state: &mut OpState,
   #[smi] rid: u32,
-  #[buffer] output: &mut [u8],
-) -> Result<usize, AnyError> {
+) -> Result<ToJsBuffer, AnyError> {
   let ctx = state.resource_table.get::<BrotliDecompressCtx>(rid)?;
   let mut inst = ctx.inst.borrow_mut();
-  let mut output_offset = 0;
-
-  let result = BrotliDecompressStream(
-    &mut 0,
-    &mut 0,
-    &[],
-    &mut output.len(),
-    &mut output_offset,
-    output,
-    &mut 0,
-    &mut inst,
-  );
-  if matches!(result, BrotliResult::ResultFailure) {
-    return Err(type_error("Failed to decompress"));
-  }
-
-  Ok(output_offset)
+  decompress_stream(&mut inst, &[])
 }
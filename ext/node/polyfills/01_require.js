@@ -39,6 +39,7 @@ const {
   Error,
   JSONParse,
   ObjectCreate,
+  ObjectDefineProperty,
   ObjectEntries,
   ObjectGetOwnPropertyDescriptor,
   ObjectGetPrototypeOf,
@@ -47,6 +48,8 @@ const {
   ObjectPrototype,
   ObjectSetPrototypeOf,
   Proxy,
+  ReflectDefineProperty,
+  ReflectSet,
   RegExpPrototypeTest,
   SafeArrayIterator,
   SafeMap,
@@ -57,6 +60,7 @@ const {
   StringPrototypeIncludes,
   StringPrototypeIndexOf,
   StringPrototypeMatch,
+  StringPrototypeReplace,
   StringPrototypeSlice,
   StringPrototypeSplit,
   StringPrototypeStartsWith,
@@ -944,15 +948,69 @@ Module.prototype.require = function (id) {
 // is the actual wrapper function we run the users code in.
 // The only observable difference is that in Deno `arguments.callee` is not
 // null.
-Module.wrapper = [
-  "(function (exports, require, module, __filename, __dirname, Buffer, clearImmediate, clearInterval, clearTimeout, console, global, process, setImmediate, setInterval, setTimeout, performance) { (function (exports, require, module, __filename, __dirname) {",
+const nodeGlobalsWrapperStart =
+  "(function (exports, require, module, __filename, __dirname, Buffer, clearImmediate, clearInterval, clearTimeout, console, global, process, setImmediate, setInterval, setTimeout, performance) {";
+const denoWrapper = [
+  `${nodeGlobalsWrapperStart} (function (exports, require, module, __filename, __dirname) {`,
   "\n}).call(this, exports, require, module, __filename, __dirname); })",
 ];
-Module.wrap = function (script) {
-  script = script.replace(/^#!.*?\n/, "");
-  return `${Module.wrapper[0]}${script}${Module.wrapper[1]}`;
+
+// `Module.wrap` and `Module.wrapper` are the same as in Node, so that code
+// instrumenting modules by patching them keeps working. Once either of them
+// is patched, the user provided wrapper is used instead of `denoWrapper`.
+let patched = false;
+
+let wrap = function (script) {
+  return Module.wrapper[0] + script + Module.wrapper[1];
 };
 
+const wrapper = [
+  "(function (exports, require, module, __filename, __dirname) { ",
+  "\n});",
+];
+
+let wrapperProxy = new Proxy(wrapper, {
+  __proto__: null,
+
+  set(target, property, value, receiver) {
+    patched = true;
+    return ReflectSet(target, property, value, receiver);
+  },
+
+  defineProperty(target, property, descriptor) {
+    patched = true;
+    return ReflectDefineProperty(target, property, descriptor);
+  },
+});
+
+ObjectDefineProperty(Module, "wrap", {
+  __proto__: null,
+  get() {
+    return wrap;
+  },
+
+  set(value) {
+    patched = true;
+    wrap = value;
+  },
+});
+
+ObjectDefineProperty(Module, "wrapper", {
+  __proto__: null,
+  get() {
+    return wrapperProxy;
+  },
+
+  set(value) {
+    patched = true;
+    wrapperProxy = value;
+  },
+});
+
+function stripShebang(content) {
+  return StringPrototypeReplace(content, /^#!.*?\n/, "");
+}
+
 function isEsmSyntaxError(error) {
   return error instanceof SyntaxError && (
     StringPrototypeIncludes(
@@ -977,7 +1035,17 @@ function wrapSafe(
   content,
   cjsModuleInstance,
 ) {
-  const wrapper = Module.wrap(content);
+  content = stripShebang(content);
+  let wrapper;
+  if (patched) {
+    // The patched wrapper only receives the arguments Node passes to it, so
+    // the magic node globals are provided by an outer function.
+    wrapper = `${nodeGlobalsWrapperStart} const compiledWrapper = ${
+      Module.wrap(content)
+    }\n;return compiledWrapper.call(this, exports, require, module, __filename, __dirname); })`;
+  } else {
+    wrapper = `${denoWrapper[0]}${content}${denoWrapper[1]}`;
+  }
   const [f, err] = core.evalContext(wrapper, `file://${filename}`);
   if (err) {
     if (process.mainModule === cjsModuleInstance) {
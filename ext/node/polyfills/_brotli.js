@@ -2,14 +2,18 @@
 
 import { core, primordials } from "ext:core/mod.js";
 const {
+  ArrayPrototypeMap,
+  Number,
   Uint8Array,
   PromisePrototypeThen,
   PromisePrototypeCatch,
-  ObjectValues,
-  TypedArrayPrototypeSlice,
+  ObjectEntries,
   TypedArrayPrototypeSubarray,
   TypedArrayPrototypeGetByteLength,
+  TypedArrayPrototypeGetByteOffset,
   DataViewPrototypeGetBuffer,
+  DataViewPrototypeGetByteLength,
+  DataViewPrototypeGetByteOffset,
   TypedArrayPrototypeGetBuffer,
 } = primordials;
 const { isTypedArray, isDataView, close } = core;
@@ -38,9 +42,17 @@ const toU8 = (input) => {
   }
 
   if (isTypedArray(input)) {
-    return new Uint8Array(TypedArrayPrototypeGetBuffer(input));
+    return new Uint8Array(
+      TypedArrayPrototypeGetBuffer(input),
+      TypedArrayPrototypeGetByteOffset(input),
+      TypedArrayPrototypeGetByteLength(input),
+    );
   } else if (isDataView(input)) {
-    return new Uint8Array(DataViewPrototypeGetBuffer(input));
+    return new Uint8Array(
+      DataViewPrototypeGetBuffer(input),
+      DataViewPrototypeGetByteOffset(input),
+      DataViewPrototypeGetByteLength(input),
+    );
   }
 
   return input;
@@ -62,21 +74,31 @@ export class BrotliDecompress extends Transform {
     super({
       // TODO(littledivy): use `encoding` argument
       transform(chunk, _encoding, callback) {
-        const input = toU8(chunk);
-        const output = new Uint8Array(TypedArrayPrototypeGetByteLength(chunk));
-        const avail = op_brotli_decompress_stream(context, input, output);
-        // deno-lint-ignore prefer-primordials
-        this.push(TypedArrayPrototypeSlice(output, 0, avail));
+        let output;
+        try {
+          output = op_brotli_decompress_stream(context, toU8(chunk));
+        } catch (err) {
+          return callback(err);
+        }
+        if (TypedArrayPrototypeGetByteLength(output) > 0) {
+          // deno-lint-ignore prefer-primordials
+          this.push(Buffer.from(output));
+        }
         callback();
       },
       flush(callback) {
-        const output = new Uint8Array(1024);
-        let avail;
-        while ((avail = op_brotli_decompress_stream_end(context, output)) > 0) {
+        let output;
+        try {
+          output = op_brotli_decompress_stream_end(context);
+        } catch (err) {
+          return callback(err);
+        } finally {
+          close(context);
+        }
+        if (TypedArrayPrototypeGetByteLength(output) > 0) {
           // deno-lint-ignore prefer-primordials
-          this.push(TypedArrayPrototypeSlice(output, 0, avail));
+          this.push(Buffer.from(output));
         }
-        close(context);
         callback();
       },
     });
@@ -93,28 +115,40 @@ export class BrotliCompress extends Transform {
     super({
       // TODO(littledivy): use `encoding` argument
       transform(chunk, _encoding, callback) {
-        const input = toU8(chunk);
-        const output = new Uint8Array(brotliMaxCompressedSize(input.length));
-        const written = op_brotli_compress_stream(context, input, output);
-        if (written > 0) {
+        let output;
+        try {
+          output = op_brotli_compress_stream(context, toU8(chunk));
+        } catch (err) {
+          return callback(err);
+        }
+        if (TypedArrayPrototypeGetByteLength(output) > 0) {
           // deno-lint-ignore prefer-primordials
-          this.push(TypedArrayPrototypeSlice(output, 0, written));
+          this.push(Buffer.from(output));
         }
         callback();
       },
       flush(callback) {
-        const output = new Uint8Array(1024);
-        let avail;
-        while ((avail = op_brotli_compress_stream_end(context, output)) > 0) {
+        let output;
+        try {
+          output = op_brotli_compress_stream_end(context);
+        } catch (err) {
+          return callback(err);
+        } finally {
+          close(context);
+        }
+        if (TypedArrayPrototypeGetByteLength(output) > 0) {
           // deno-lint-ignore prefer-primordials
-          this.push(TypedArrayPrototypeSlice(output, 0, avail));
+          this.push(Buffer.from(output));
         }
-        close(context);
         callback();
       },
     });
 
-    const params = ObjectValues(options?.params ?? {});
+    // `params` is keyed by the numeric `BROTLI_PARAM_*` constants.
+    const params = ArrayPrototypeMap(
+      ObjectEntries(options?.params ?? {}),
+      ({ 0: key, 1: value }) => [Number(key), value],
+    );
     this.#context = op_create_brotli_compress(params);
     const context = this.#context;
   }
@@ -139,7 +173,7 @@ function brotliMaxCompressedSize(input) {
   if (input == 0) return 2;
 
   // [window bits / empty metadata] + N * [uncompressed] + [last empty]
-  const numLargeBlocks = input >> 24;
+  const numLargeBlocks = input >> 14;
   const overhead = 2 + (4 * numLargeBlocks) + 3 + 1;
   const result = input + overhead;
 
@@ -180,9 +214,15 @@ export function brotliCompressSync(
   return Buffer.from(TypedArrayPrototypeSubarray(output, 0, len));
 }
 
-export function brotliDecompress(input) {
+export function brotliDecompress(input, options, callback) {
   const buf = toU8(input);
-  return PromisePrototypeCatch(
+
+  if (typeof options === "function") {
+    callback = options;
+    options = {};
+  }
+
+  PromisePrototypeCatch(
     PromisePrototypeThen(
       op_brotli_decompress_async(buf),
       (result) => callback(null, Buffer.from(result)),
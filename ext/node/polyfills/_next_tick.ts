@@ -9,6 +9,19 @@ import { core } from "ext:core/mod.js";
 import { validateFunction } from "ext:deno_node/internal/validators.mjs";
 import { _exiting } from "ext:deno_node/_process/exiting.ts";
 import { FixedQueue } from "ext:deno_node/internal/fixed_queue.ts";
+import {
+  destroyHooksExist,
+  emitAfter,
+  emitBefore,
+  emitDestroy,
+  initAsyncResource,
+  symbols,
+} from "ext:deno_node/internal/async_hooks.ts";
+
+const {
+  async_id_symbol: asyncIdSymbol,
+  trigger_async_id_symbol: triggerAsyncIdSymbol,
+} = symbols;
 
 interface Tock {
   callback: (...args: Array<unknown>) => void;
@@ -27,9 +40,8 @@ export function processTicksAndRejections() {
   do {
     // deno-lint-ignore no-cond-assign
     while (tock = queue.shift()) {
-      // FIXME(bartlomieju): Deno currently doesn't support async hooks
-      // const asyncId = tock[async_id_symbol];
-      // emitBefore(asyncId, tock[trigger_async_id_symbol], tock);
+      const asyncId = tock[asyncIdSymbol];
+      emitBefore(asyncId, tock[triggerAsyncIdSymbol], tock);
 
       try {
         const callback = (tock as Tock).callback;
@@ -55,13 +67,13 @@ export function processTicksAndRejections() {
           }
         }
       } finally {
-        // FIXME(bartlomieju): Deno currently doesn't support async hooks
-        // if (destroyHooksExist())
-        // emitDestroy(asyncId);
+        if (destroyHooksExist()) {
+          emitDestroy(asyncId);
+        }
+        // Unlike in Node, an exception thrown by the callback doesn't reset
+        // the async id stack, so this must run either way.
+        emitAfter(asyncId);
       }
-
-      // FIXME(bartlomieju): Deno currently doesn't support async hooks
-      // emitAfter(asyncId);
     }
     core.runMicrotasks();
     // FIXME(bartlomieju): Deno currently doesn't unhandled rejections
@@ -136,18 +148,10 @@ export function nextTick<T extends Array<unknown>>(
   if (queue.isEmpty()) {
     core.setHasTickScheduled(true);
   }
-  // FIXME(bartlomieju): Deno currently doesn't support async hooks
-  // const asyncId = newAsyncId();
-  // const triggerAsyncId = getDefaultTriggerAsyncId();
   const tickObject = {
-    // FIXME(bartlomieju): Deno currently doesn't support async hooks
-    // [async_id_symbol]: asyncId,
-    // [trigger_async_id_symbol]: triggerAsyncId,
     callback,
     args: args_,
   };
-  // FIXME(bartlomieju): Deno currently doesn't support async hooks
-  // if (initHooksExist())
-  //   emitInit(asyncId, 'TickObject', triggerAsyncId, tickObject);
+  initAsyncResource(tickObject, "TickObject");
   queue.push(tickObject);
 }
@@ -2,14 +2,29 @@
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 // deno-lint-ignore-file no-explicit-any
 
+/**
+ * The key material of a TLS endpoint. Unlike in Node, it is not backed by an
+ * OpenSSL context, so `context` is the object itself.
+ */
+export class SecureContext {
+  ca: any;
+  cert: any;
+  key: any;
+  context: SecureContext;
+
+  constructor(options: any) {
+    this.ca = options?.ca;
+    this.cert = options?.cert;
+    this.key = options?.key;
+    this.context = this;
+  }
+}
+
 export function createSecureContext(options: any) {
-  return {
-    ca: options?.ca,
-    cert: options?.cert,
-    key: options?.key,
-  };
+  return new SecureContext(options);
 }
 
 export default {
   createSecureContext,
+  SecureContext,
 };
@@ -4,15 +4,25 @@
 // TODO(petamoriken): enable prefer-primordials for node polyfills
 // deno-lint-ignore-file no-explicit-any prefer-primordials
 
+import { core, internals } from "ext:core/mod.js";
+import { op_tls_peer_certificates } from "ext:core/ops";
 import {
   ObjectAssign,
   StringPrototypeReplace,
 } from "ext:deno_node/internal/primordials.mjs";
 import assert from "ext:deno_node/internal/assert.mjs";
 import * as net from "node:net";
-import { createSecureContext } from "ext:deno_node/_tls_common.ts";
+import {
+  createSecureContext,
+  SecureContext,
+} from "ext:deno_node/_tls_common.ts";
 import { kStreamBaseField } from "ext:deno_node/internal_binding/stream_wrap.ts";
-import { connResetException } from "ext:deno_node/internal/errors.ts";
+import {
+  connResetException,
+  ERR_INVALID_ARG_TYPE,
+} from "ext:deno_node/internal/errors.ts";
+import { X509Certificate } from "ext:deno_node/internal/crypto/x509.ts";
+import { Buffer } from "node:buffer";
 import { emitWarning } from "node:process";
 import { debuglog } from "ext:deno_node/internal/util/debuglog.ts";
 import {
@@ -32,6 +42,8 @@ import {
   isArrayBufferView,
 } from "ext:deno_node/internal/util/types.ts";
 
+const { internalRidSymbol } = core;
+
 const kConnectOptions = Symbol("connect-options");
 const kIsVerified = Symbol("verified");
 const kPendingSession = Symbol("pendingSession");
@@ -71,6 +83,7 @@ export class TLSSocket extends net.Socket {
   alpnProtocols: string[] | null;
   authorized: boolean;
   authorizationError: any;
+  encrypted: boolean;
   [kRes]: any;
   [kIsVerified]: boolean;
   [kPendingSession]: any;
@@ -84,7 +97,7 @@ export class TLSSocket extends net.Socket {
   constructor(socket: any, opts: any = kEmptyObject) {
     const tlsOptions = { ...opts };
 
-    const hostname = opts.servername ?? opts.host ?? socket._host;
+    const hostname = opts.servername ?? opts.host ?? socket?._host;
     tlsOptions.hostname = hostname;
 
     const _cert = tlsOptions?.secureContext?.cert;
@@ -98,10 +111,14 @@ export class TLSSocket extends net.Socket {
     tlsOptions.caCerts = caCerts;
     tlsOptions.alpnProtocols = opts.ALPNProtocols;
 
+    // The handle of a server side socket already has a TLS connection.
     super({
-      handle: _wrapHandle(tlsOptions, socket),
+      handle: tlsOptions.isServer
+        ? opts.handle
+        : _wrapHandle(tlsOptions, socket),
       ...opts,
-      manualStart: true, // This prevents premature reading from TLS handle
+      // This prevents premature reading from TLS handle
+      manualStart: !tlsOptions.isServer,
     });
     if (socket) {
       this._parent = socket;
@@ -111,12 +128,13 @@ export class TLSSocket extends net.Socket {
     this._securePending = false;
     this._newSessionPending = false;
     this._controlReleased = false;
-    this.secureConnecting = true;
+    this.secureConnecting = !tlsOptions.isServer;
     this._SNICallback = null;
     this.servername = null;
     this.alpnProtocols = tlsOptions.ALPNProtocols;
     this.authorized = false;
     this.authorizationError = null;
+    this.encrypted = true;
     this[kRes] = null;
     this[kIsVerified] = false;
     this[kPendingSession] = null;
@@ -206,12 +224,29 @@ export class TLSSocket extends net.Socket {
     // TODO(kt3k): implement this
   }
 
-  getPeerCertificate(_detailed: boolean) {
-    // TODO(kt3k): implement this
-    return {
-      subject: "localhost",
-      subjectaltname: "IP Address:127.0.0.1, IP Address:::1",
-    };
+  getPeerCertificate(detailed = false) {
+    const rid = this._handle?.[kStreamBaseField]?.[internalRidSymbol];
+
+    if (rid === undefined) {
+      return null;
+    }
+
+    const certificates = op_tls_peer_certificates(rid).map(
+      (der: Uint8Array) => {
+        const certificate = new X509Certificate(der).toLegacyObject();
+        certificate.raw = Buffer.from(der);
+        return certificate;
+      },
+    );
+
+    if (detailed) {
+      for (let i = 0; i < certificates.length - 1; i++) {
+        certificates[i].issuerCertificate = certificates[i + 1];
+      }
+    }
+
+    // Servers don't request certificates of clients.
+    return certificates[0] ?? {};
   }
 }
 
@@ -240,23 +275,120 @@ export function Server(options: any, listener: any) {
   return new ServerImpl(options, listener);
 }
 
+function keyPairOf(context: SecureContext) {
+  return {
+    cert: context.cert?.toString(),
+    key: context.key?.toString(),
+  };
+}
+
+// The default `SNICallback`, which looks up the contexts of `addContext()`.
+function SNICallback(
+  this: ServerImpl,
+  servername: string,
+  callback: (err: Error | null, context?: SecureContext) => void,
+) {
+  let context: SecureContext | undefined;
+
+  // The last matching context wins, like in Node.
+  for (const [pattern, patternContext] of this._contexts) {
+    if (pattern.test(servername)) {
+      context = patternContext;
+    }
+  }
+
+  callback(null, context);
+}
+
 export class ServerImpl extends EventEmitter {
   listener?: Deno.TlsListener;
+  _contexts: [RegExp, SecureContext][] = [];
+  _sharedCreds: SecureContext;
+  _SNICallback: typeof SNICallback;
   #closed = false;
   constructor(public options: any, listener: any) {
     super();
+    options ??= {};
+    this.options = options;
+
+    if (
+      options.SNICallback != null && typeof options.SNICallback !== "function"
+    ) {
+      throw new ERR_INVALID_ARG_TYPE(
+        "options.SNICallback",
+        "function",
+        options.SNICallback,
+      );
+    }
+
+    this._sharedCreds = options.secureContext ?? createSecureContext(options);
+    this._SNICallback = options.SNICallback ?? SNICallback;
     if (listener) {
       this.on("secureConnection", listener);
     }
   }
 
+  addContext(servername: string, context: any) {
+    if (!servername) {
+      throw new ERR_INVALID_ARG_TYPE("servername", "string", servername);
+    }
+
+    const pattern = new RegExp(
+      "^" +
+        servername
+          .replace(/([.^$+?\-\\[\]{}])/g, "\\$1")
+          .replaceAll("*", "[^.]*") +
+        "$",
+    );
+    this._contexts.push([
+      pattern,
+      context instanceof SecureContext ? context : createSecureContext(context),
+    ]);
+  }
+
+  setSecureContext(options: any) {
+    this._sharedCreds = createSecureContext(options);
+  }
+
+  /**
+   * Returns the key pair for a connection to `servername`, which is empty
+   * if the client didn't ask for a server name.
+   */
+  #resolveKeyPair(servername: string) {
+    if (!servername) {
+      return keyPairOf(this._sharedCreds);
+    }
+
+    return new Promise((resolve, reject) => {
+      this._SNICallback(servername, (err, context) => {
+        if (err) {
+          reject(err);
+        } else {
+          resolve(keyPairOf(context ?? this._sharedCreds));
+        }
+      });
+    });
+  }
+
   listen(port: any, callback: any): this {
-    const key = this.options.key?.toString();
-    const cert = this.options.cert?.toString();
     // TODO(kt3k): The default host should be "localhost"
     const hostname = this.options.host ?? "0.0.0.0";
+    // Only arrays of protocol names are supported, not the wire format.
+    const alpnProtocols = Array.isArray(this.options.ALPNProtocols)
+      ? this.options.ALPNProtocols
+      : undefined;
+    const options: any = { port, hostname, alpnProtocols };
+
+    // Certificates can only be picked by server name through a resolver,
+    // which caches the key pair of each name.
+    if (this.options.SNICallback || this._contexts.length > 0) {
+      options[internals.resolverSymbol] = (servername: string) =>
+        this.#resolveKeyPair(servername);
+    } else {
+      ObjectAssign(options, keyPairOf(this._sharedCreds));
+    }
 
-    this.listener = Deno.listenTls({ port, hostname, cert, key });
+    this.listener = Deno.listenTls(options);
 
     callback?.call(this);
     this.#listen(this.listener);
@@ -270,7 +402,7 @@ export class ServerImpl extends EventEmitter {
         // This works as TLS socket. We don't use TLSSocket class for doing
         // this because Deno.startTls only supports client side tcp connection.
         const handle = new TCP(TCPConstants.SOCKET, await listener.accept());
-        const socket = new net.Socket({ handle });
+        const socket = this._createSocket(handle);
         this.emit("secureConnection", socket);
       } catch (e) {
         if (e instanceof Deno.errors.BadResource) {
@@ -281,6 +413,15 @@ export class ServerImpl extends EventEmitter {
     }
   }
 
+  _createSocket(handle: TCP) {
+    return new TLSSocket(undefined, {
+      handle,
+      isServer: true,
+      server: this,
+      secureContext: this._sharedCreds,
+    });
+  }
+
   close(cb?: (err?: Error) => void): this {
     if (this.listener) {
       this.listener.close();
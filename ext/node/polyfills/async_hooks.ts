@@ -7,73 +7,31 @@
 // TODO(petamoriken): enable prefer-primordials for node polyfills
 // deno-lint-ignore-file prefer-primordials
 
-import { core } from "ext:core/mod.js";
-import { op_node_is_promise_rejected } from "ext:core/ops";
 import { validateFunction } from "ext:deno_node/internal/validators.mjs";
-import { newAsyncId } from "ext:deno_node/internal/async_hooks.ts";
+import {
+  AsyncHook,
+  emitAfter,
+  emitBefore,
+  emitDestroy,
+  emitInit,
+  enablePromiseHooks,
+  executionAsyncId,
+  executionAsyncResource,
+  getAsyncContext,
+  getDefaultTriggerAsyncId,
+  newAsyncId,
+  setAsyncContext,
+  symbols,
+  triggerAsyncId,
+} from "ext:deno_node/internal/async_hooks.ts";
+
+const { async_context_symbol: asyncContext } = symbols;
 
 function assert(cond: boolean) {
   if (!cond) throw new Error("Assertion failed");
 }
-const asyncContextStack: AsyncContextFrame[] = [];
-
-function pushAsyncFrame(frame: AsyncContextFrame) {
-  asyncContextStack.push(frame);
-}
-
-function popAsyncFrame() {
-  if (asyncContextStack.length > 0) {
-    asyncContextStack.pop();
-  }
-}
 
 let rootAsyncFrame: AsyncContextFrame | undefined = undefined;
-let promiseHooksSet = false;
-
-const asyncContext = Symbol("asyncContext");
-
-function setPromiseHooks() {
-  if (promiseHooksSet) {
-    return;
-  }
-  promiseHooksSet = true;
-
-  const init = (promise: Promise<unknown>) => {
-    const currentFrame = AsyncContextFrame.current();
-    if (!currentFrame.isRoot()) {
-      if (typeof promise[asyncContext] !== "undefined") {
-        throw new Error("Promise already has async context");
-      }
-      AsyncContextFrame.attachContext(promise);
-    }
-  };
-  const before = (promise: Promise<unknown>) => {
-    const maybeFrame = promise[asyncContext];
-    if (maybeFrame) {
-      pushAsyncFrame(maybeFrame);
-    } else {
-      pushAsyncFrame(AsyncContextFrame.getRootAsyncContext());
-    }
-  };
-  const after = (promise: Promise<unknown>) => {
-    popAsyncFrame();
-    if (!op_node_is_promise_rejected(promise)) {
-      // @ts-ignore promise async context
-      promise[asyncContext] = undefined;
-    }
-  };
-  const resolve = (promise: Promise<unknown>) => {
-    const currentFrame = AsyncContextFrame.current();
-    if (
-      !currentFrame.isRoot() && op_node_is_promise_rejected(promise) &&
-      typeof promise[asyncContext] === "undefined"
-    ) {
-      AsyncContextFrame.attachContext(promise);
-    }
-  };
-
-  core.setPromiseHooks(init, before, after, resolve);
-}
 
 class AsyncContextFrame {
   storage: StorageEntry[];
@@ -84,7 +42,7 @@ class AsyncContextFrame {
   ) {
     this.storage = [];
 
-    setPromiseHooks();
+    enablePromiseHooks();
 
     const propagate = (parent: AsyncContextFrame) => {
       parent.storage = parent.storage.filter((entry) => !entry.key.isDead());
@@ -111,16 +69,6 @@ class AsyncContextFrame {
     }
   }
 
-  static tryGetContext(promise: Promise<unknown>) {
-    // @ts-ignore promise async context
-    return promise[asyncContext];
-  }
-
-  static attachContext(promise: Promise<unknown>) {
-    // @ts-ignore promise async context
-    promise[asyncContext] = AsyncContextFrame.current();
-  }
-
   static getRootAsyncContext() {
     if (typeof rootAsyncFrame !== "undefined") {
       return rootAsyncFrame;
@@ -130,12 +78,9 @@ class AsyncContextFrame {
     return rootAsyncFrame;
   }
 
-  static current() {
-    if (asyncContextStack.length === 0) {
-      return AsyncContextFrame.getRootAsyncContext();
-    }
-
-    return asyncContextStack[asyncContextStack.length - 1];
+  static current(): AsyncContextFrame {
+    return (getAsyncContext() as AsyncContextFrame | undefined) ??
+      AsyncContextFrame.getRootAsyncContext();
   }
 
   static create(
@@ -145,24 +90,6 @@ class AsyncContextFrame {
     return new AsyncContextFrame(maybeParent, maybeStorageEntry);
   }
 
-  static wrap(
-    fn: () => unknown,
-    maybeFrame: AsyncContextFrame | undefined,
-    // deno-lint-ignore no-explicit-any
-    thisArg: any,
-  ) {
-    // deno-lint-ignore no-explicit-any
-    return (...args: any) => {
-      const frame = maybeFrame || AsyncContextFrame.current();
-      Scope.enter(frame);
-      try {
-        return fn.apply(thisArg, args);
-      } finally {
-        Scope.exit();
-      }
-    };
-  }
-
   get(key: StorageKey) {
     assert(!key.isDead());
     this.storage = this.storage.filter((entry) => !entry.key.isDead());
@@ -178,41 +105,76 @@ class AsyncContextFrame {
   }
 }
 
+/** Runs `fn` with the stores of `frame`, and restores the current ones
+ * afterwards. */
+function runInFrame<T>(frame: AsyncContextFrame, fn: () => T): T {
+  const previous = getAsyncContext();
+  setAsyncContext(frame.isRoot() ? undefined : frame);
+  try {
+    return fn();
+  } finally {
+    setAsyncContext(previous);
+  }
+}
+
 export class AsyncResource {
   frame: AsyncContextFrame;
   type: string;
   #asyncId: number;
+  #triggerAsyncId: number;
 
-  constructor(type: string) {
+  constructor(
+    type: string,
+    opts: { triggerAsyncId?: number; requireManualDestroy?: boolean } = {},
+  ) {
     this.type = type;
     this.frame = AsyncContextFrame.current();
     this.#asyncId = newAsyncId();
+    this.#triggerAsyncId = opts.triggerAsyncId ?? getDefaultTriggerAsyncId();
+    // @ts-ignore async context of the resource
+    this[asyncContext] = this.frame.isRoot() ? undefined : this.frame;
+    emitInit(this.#asyncId, type, this.#triggerAsyncId, this);
   }
 
   asyncId() {
     return this.#asyncId;
   }
 
+  triggerAsyncId() {
+    return this.#triggerAsyncId;
+  }
+
   runInAsyncScope(
     fn: (...args: unknown[]) => unknown,
     thisArg: unknown,
     ...args: unknown[]
   ) {
-    Scope.enter(this.frame);
+    emitBefore(this.#asyncId, this.#triggerAsyncId, this);
 
     try {
       return fn.apply(thisArg, args);
     } finally {
-      Scope.exit();
+      emitAfter(this.#asyncId);
     }
   }
 
-  emitDestroy() {}
+  emitDestroy() {
+    emitDestroy(this.#asyncId);
+    return this;
+  }
 
-  bind(fn: (...args: unknown[]) => unknown, thisArg = this) {
+  bind(fn: (...args: unknown[]) => unknown, thisArg?: unknown) {
     validateFunction(fn, "fn");
-    const frame = AsyncContextFrame.current();
-    const bound = AsyncContextFrame.wrap(fn, frame, thisArg);
+    let bound;
+    if (thisArg === undefined) {
+      // deno-lint-ignore no-this-alias
+      const resource = this;
+      bound = function (this: unknown, ...args: unknown[]) {
+        return resource.runInAsyncScope(fn, this, ...args);
+      };
+    } else {
+      bound = this.runInAsyncScope.bind(this, fn, thisArg);
+    }
 
     Object.defineProperties(bound, {
       "length": {
@@ -241,20 +203,6 @@ export class AsyncResource {
   }
 }
 
-class Scope {
-  static enter(maybeFrame?: AsyncContextFrame) {
-    if (maybeFrame) {
-      pushAsyncFrame(maybeFrame);
-    } else {
-      pushAsyncFrame(AsyncContextFrame.getRootAsyncContext());
-    }
-  }
-
-  static exit() {
-    popAsyncFrame();
-  }
-}
-
 class StorageEntry {
   key: StorageKey;
   value: unknown;
@@ -298,19 +246,12 @@ export class AsyncLocalStorage {
       null,
       new StorageEntry(this.#key, store),
     );
-    Scope.enter(frame);
-    let res;
-    try {
-      res = callback(...args);
-    } finally {
-      Scope.exit();
-    }
-    return res;
+    return runInFrame(frame, () => callback(...args));
   }
 
   // deno-lint-ignore no-explicit-any
   exit(callback: (...args: unknown[]) => any, ...args: any[]): any {
-    return this.run(undefined, callback, args);
+    return this.run(undefined, callback, ...args);
   }
 
   // deno-lint-ignore no-explicit-any
@@ -324,7 +265,7 @@ export class AsyncLocalStorage {
       null,
       new StorageEntry(this.#key, store),
     );
-    Scope.enter(frame);
+    setAsyncContext(frame);
   }
 
   static bind(fn: (...args: unknown[]) => unknown) {
@@ -339,28 +280,23 @@ export class AsyncLocalStorage {
   }
 }
 
-export function executionAsyncId() {
-  return 1;
-}
-
-class AsyncHook {
-  enable() {
-  }
-
-  disable() {
-  }
-}
+export { executionAsyncId, executionAsyncResource, triggerAsyncId };
 
-export function createHook() {
-  return new AsyncHook();
+export function createHook(
+  fns: ConstructorParameters<typeof AsyncHook>[0],
+) {
+  return new AsyncHook(fns);
 }
 
 // Placing all exports down here because the exported classes won't export
 // otherwise.
 export default {
+  // Public API
+  AsyncLocalStorage,
+  createHook,
+  executionAsyncId,
+  triggerAsyncId,
+  executionAsyncResource,
   // Embedder API
   AsyncResource,
-  executionAsyncId,
-  createHook,
-  AsyncLocalStorage,
 };
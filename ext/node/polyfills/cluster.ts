@@ -1,69 +1,102 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
-import { notImplemented } from "ext:deno_node/_utils.ts";
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
 
-/** A Worker object contains all public information and method about a worker.
- * In the primary it can be obtained using cluster.workers. In a worker it can
- * be obtained using cluster.worker.
- */
-export class Worker {
-  constructor() {
-    notImplemented("cluster.Worker.prototype.constructor");
-  }
-}
+import { internals } from "ext:core/mod.js";
+import { EventEmitter } from "node:events";
+import process from "node:process";
+import { initWorker } from "ext:deno_node/internal/cluster/child.ts";
+import { initPrimary } from "ext:deno_node/internal/cluster/primary.ts";
+import { SCHED_NONE, SCHED_RR } from "ext:deno_node/internal/cluster/utils.ts";
+import { Worker } from "ext:deno_node/internal/cluster/worker.ts";
+
+// The module is shared by the primary and its workers, and which one this
+// process is is only known once it starts, see `__initCluster` below.
+const cluster: any = new EventEmitter();
+cluster.Worker = Worker;
+cluster.SCHED_NONE = SCHED_NONE;
+cluster.SCHED_RR = SCHED_RR;
+
+export { SCHED_NONE, SCHED_RR, Worker };
 /** Calls .disconnect() on each worker in cluster.workers. */
-export function disconnected() {
-  notImplemented("cluster.disconnected");
-}
+export let disconnect: ((cb?: () => void) => void) | undefined;
 /** Spawn a new worker process. */
-export function fork() {
-  notImplemented("cluster.fork");
-}
+export let fork: ((env?: Record<string, string>) => Worker) | undefined;
 /** True if the process is a primary. This is determined by
  * the process.env.NODE_UNIQUE_ID. If process.env.NODE_UNIQUE_ID is undefined,
  * then isPrimary is true. */
-export const isPrimary = undefined;
+export let isPrimary = true;
 /** True if the process is not a primary (it is the negation of
  * cluster.isPrimary). */
-export const isWorker = undefined;
-/** Deprecated alias for cluster.isPrimary. details. */
-export const isMaster = isPrimary;
+export let isWorker = false;
+/** Deprecated alias for cluster.isPrimary. */
+export let isMaster = true;
 /** The scheduling policy, either cluster.SCHED_RR for round-robin or
  * cluster.SCHED_NONE to leave it to the operating system. This is a global
  * setting and effectively frozen once either the first worker is spawned, or
  * .setupPrimary() is called, whichever comes first. */
-export const schedulingPolicy = undefined;
+export let schedulingPolicy: number | undefined;
 /** The settings object */
-export const settings = undefined;
+export let settings: Record<string, unknown> | undefined;
 /** Deprecated alias for .setupPrimary(). */
-export function setupMaster() {
-  notImplemented("cluster.setupMaster");
-}
+export let setupMaster:
+  | ((settings?: Record<string, unknown>) => void)
+  | undefined;
 /** setupPrimary is used to change the default 'fork' behavior. Once called,
  * the settings will be present in cluster.settings. */
-export function setupPrimary() {
-  notImplemented("cluster.setupPrimary");
-}
+export let setupPrimary:
+  | ((settings?: Record<string, unknown>) => void)
+  | undefined;
 /** A reference to the current worker object. Not available in the primary
  * process. */
-export const worker = undefined;
+export let worker: Worker | undefined;
 /** A hash that stores the active worker objects, keyed by id field. Makes it
  * easy to loop through all the workers. It is only available in the primary
  * process. */
-export const workers = undefined;
+export let workers: Record<number, Worker> | undefined;
+
+function syncExports() {
+  disconnect = cluster.disconnect;
+  fork = cluster.fork;
+  isPrimary = cluster.isPrimary;
+  isWorker = cluster.isWorker;
+  isMaster = cluster.isMaster;
+  schedulingPolicy = cluster.schedulingPolicy;
+  settings = cluster.settings;
+  setupMaster = cluster.setupMaster;
+  setupPrimary = cluster.setupPrimary;
+  worker = cluster.worker;
+  workers = cluster.workers;
+}
 
-export default {
-  Worker,
-  disconnected,
-  fork,
-  isPrimary,
-  isWorker,
-  isMaster,
-  schedulingPolicy,
-  settings,
-  setupMaster,
-  setupPrimary,
-  worker,
-  workers,
+// `cluster.settings`, `cluster.worker` and `cluster.schedulingPolicy` are
+// replaced on the module object, so keep the named exports in sync.
+for (const name of ["settings", "worker", "schedulingPolicy"]) {
+  let value: unknown;
+  Object.defineProperty(cluster, name, {
+    get: () => value,
+    set: (v) => {
+      value = v;
+      syncExports();
+    },
+    enumerable: true,
+    configurable: true,
+  });
+}
+
+internals.__initCluster = (runningOnMainThread: boolean) => {
+  // Worker threads of a worker act as primaries, like in Node.
+  if (runningOnMainThread && "NODE_UNIQUE_ID" in process.env) {
+    initWorker(cluster);
+    cluster._setupWorker();
+    // Make sure it's not accidentally inherited by child processes.
+    delete process.env.NODE_UNIQUE_ID;
+  } else {
+    initPrimary(cluster);
+  }
+  syncExports();
 };
+
+export default cluster;
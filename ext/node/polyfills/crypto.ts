@@ -37,6 +37,7 @@ import type {
 } from "ext:deno_node/internal/crypto/pbkdf2.ts";
 import { scrypt, scryptSync } from "ext:deno_node/internal/crypto/scrypt.ts";
 import { hkdf, hkdfSync } from "ext:deno_node/internal/crypto/hkdf.ts";
+import { argon2, argon2Sync } from "ext:deno_node/internal/crypto/argon2.ts";
 import {
   generateKey,
   generateKeyPair,
@@ -315,6 +316,8 @@ const verify = verifyOneShot;
 const pseudoRandomBytes = randomBytes;
 
 export default {
+  argon2,
+  argon2Sync,
   Certificate,
   checkPrime,
   checkPrimeSync,
@@ -453,6 +456,8 @@ export type {
 };
 
 export {
+  argon2,
+  argon2Sync,
   Certificate,
   checkPrime,
   checkPrimeSync,
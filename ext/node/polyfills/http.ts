@@ -48,6 +48,7 @@ import { Agent, globalAgent } from "ext:deno_node/_http_agent.mjs";
 import { urlToHttpOptions } from "ext:deno_node/internal/url.ts";
 import { kEmptyObject } from "ext:deno_node/internal/util.mjs";
 import { constants, TCP } from "ext:deno_node/internal_binding/tcp_wrap.ts";
+import { kStreamBaseField } from "ext:deno_node/internal_binding/stream_wrap.ts";
 import { notImplemented, warnNotImplemented } from "ext:deno_node/_utils.ts";
 import {
   connResetException,
@@ -57,15 +58,21 @@ import {
   ERR_INVALID_HTTP_TOKEN,
   ERR_INVALID_PROTOCOL,
   ERR_UNESCAPED_CHARACTERS,
+  exceptionWithHostPort,
 } from "ext:deno_node/internal/errors.ts";
 import { getTimerDuration } from "ext:deno_node/internal/timers.mjs";
-import { serve, upgradeHttpRaw } from "ext:deno_http/00_serve.ts";
+import {
+  serve,
+  serveHttpOnConnection,
+  upgradeHttpRaw,
+} from "ext:deno_http/00_serve.ts";
 import { createHttpClient } from "ext:deno_fetch/22_http_client.js";
 import { headersEntries } from "ext:deno_fetch/20_headers.js";
 import { timerId } from "ext:deno_web/03_abort_signal.js";
 import { clearTimeout as webClearTimeout } from "ext:deno_web/02_timers.js";
 import { resourceForReadableStream } from "ext:deno_web/06_streams.js";
 import { TcpConn } from "ext:deno_net/01_net.js";
+import cluster from "node:cluster";
 
 const { internalRidSymbol } = core;
 const { ArrayIsArray } = primordials;
@@ -1796,6 +1803,12 @@ export class ServerImpl extends EventEmitter {
       return;
     }
     this.#ac = ac;
+    // Encrypted servers can't terminate TLS on connections passed to them by
+    // the primary, so they listen on their own.
+    if (cluster.isWorker && !this._encrypted) {
+      this._serveInCluster(handler as Deno.ServeHandler, ac.signal);
+      return;
+    }
     try {
       this.#server = serve(
         {
@@ -1821,6 +1834,39 @@ export class ServerImpl extends EventEmitter {
     this.#server.finished.then(() => this.#serveDeferred!.resolve());
   }
 
+  /** Serves on the primary's server handle, like `net.Server` does in a
+   * cluster worker. */
+  _serveInCluster(handler: Deno.ServeHandler, signal: AbortSignal) {
+    const { hostname, port } = this.#addr!;
+    const serverQuery = {
+      address: hostname,
+      port,
+      addressType: 4,
+      fd: null,
+      flags: 0,
+      backlog: 511,
+    };
+    cluster._getServer(this, serverQuery, (err: number, handle: TCP) => {
+      if (err) {
+        this.emit("error", exceptionWithHostPort(err, "bind", hostname, port));
+        return;
+      }
+      if (this.#hasClosed) {
+        handle.close();
+        return;
+      }
+
+      this.#server = serveOnHandle(handle, handler, signal);
+      if (this.#unref) {
+        this.#server.unref();
+      }
+      this.#server.finished.then(() => this.#serveDeferred!.resolve());
+
+      this.#addr!.port = (this.#server.addr as Deno.NetAddr).port || port;
+      this.emit("listening");
+    });
+  }
+
   setTimeout() {
     console.error("Not implemented: Server.setTimeout()");
   }
@@ -1900,6 +1946,77 @@ export class ServerImpl extends EventEmitter {
 
 Server.prototype = ServerImpl.prototype;
 
+/** Serves HTTP on the connections accepted by `handle`, a listening handle
+ * from the cluster primary. */
+function serveOnHandle(
+  handle: TCP,
+  handler: Deno.ServeHandler,
+  signal: AbortSignal,
+): Deno.HttpServer {
+  const connections = new Set<Deno.HttpServer>();
+  const onError = (error: unknown) => {
+    console.error(error);
+    return new Response("Internal Server Error", { status: 500 });
+  };
+  let ref = true;
+
+  handle.onconnection = (err: number, clientHandle?: TCP) => {
+    if (err) {
+      return;
+    }
+    const server = serveHttpOnConnection(
+      clientHandle![kStreamBaseField],
+      signal,
+      handler,
+      onError,
+      () => {},
+    );
+    if (!ref) {
+      server.unref();
+    }
+    connections.add(server);
+    server.finished.then(() => connections.delete(server));
+  };
+  handle.listen(511);
+
+  const sockname: { address?: string; port?: number } = {};
+  handle.getsockname(sockname);
+  const { promise: finished, resolve } = Promise.withResolvers<void>();
+  let closing: Promise<void> | undefined;
+  const shutdown = () => {
+    closing ??= (async () => {
+      handle.close();
+      await Promise.allSettled(
+        [...connections].map((server) => server.shutdown()),
+      );
+      resolve();
+    })();
+    return closing;
+  };
+  signal.addEventListener("abort", shutdown, { once: true });
+
+  return {
+    addr: {
+      transport: "tcp",
+      hostname: sockname.address ?? "",
+      port: sockname.port ?? 0,
+    },
+    finished,
+    shutdown,
+    ref() {
+      ref = true;
+      handle.ref();
+      connections.forEach((server) => server.ref());
+    },
+    unref() {
+      ref = false;
+      handle.unref();
+      connections.forEach((server) => server.unref());
+    },
+    [Symbol.asyncDispose]: shutdown,
+  };
+}
+
 export function createServer(opts, requestListener?: ServerHandler) {
   return Server(opts, requestListener);
 }
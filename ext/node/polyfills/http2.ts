@@ -28,7 +28,11 @@ import { emitWarning } from "node:process";
 import Stream from "node:stream";
 import { connect as netConnect, Server, Socket, TCP } from "node:net";
 import { connect as tlsConnect } from "node:tls";
-import { TypedArray } from "ext:deno_node/internal/util/types.ts";
+import { ServerImpl as TlsServer } from "ext:deno_node/_tls_wrap.ts";
+import {
+  isArrayBufferView,
+  TypedArray,
+} from "ext:deno_node/internal/util/types.ts";
 import {
   kHandle,
   kMaybeDestroy,
@@ -49,10 +53,13 @@ import {
   ERR_HTTP2_GOAWAY_SESSION,
   ERR_HTTP2_HEADERS_SENT,
   ERR_HTTP2_INFO_STATUS_NOT_ALLOWED,
+  ERR_HTTP2_INVALID_PACKED_SETTINGS_LENGTH,
   ERR_HTTP2_INVALID_PSEUDOHEADER,
   ERR_HTTP2_INVALID_SESSION,
+  ERR_HTTP2_INVALID_SETTING_VALUE,
   ERR_HTTP2_INVALID_STREAM,
   ERR_HTTP2_NO_SOCKET_MANIPULATION,
+  ERR_HTTP2_PUSH_DISABLED,
   ERR_HTTP2_SESSION_ERROR,
   ERR_HTTP2_STATUS_INVALID,
   ERR_HTTP2_STREAM_CANCEL,
@@ -61,12 +68,17 @@ import {
   ERR_HTTP2_TRAILERS_ALREADY_SENT,
   ERR_HTTP2_TRAILERS_NOT_READY,
   ERR_HTTP2_UNSUPPORTED_PROTOCOL,
+  ERR_INVALID_ARG_TYPE,
   ERR_INVALID_ARG_VALUE,
   ERR_INVALID_HTTP_TOKEN,
   ERR_SOCKET_CLOSED,
   ERR_STREAM_WRITE_AFTER_END,
 } from "ext:deno_node/internal/errors.ts";
 import { _checkIsHttpToken } from "ext:deno_node/_http_common.ts";
+import {
+  validateFunction,
+  validateObject,
+} from "ext:deno_node/internal/validators.mjs";
 const {
   StringPrototypeTrim,
   FunctionPrototypeBind,
@@ -87,9 +99,11 @@ const kEncrypted = Symbol("encrypted");
 const kID = Symbol("id");
 const kInit = Symbol("init");
 const kInfoHeaders = Symbol("sent-info-headers");
+const kLocalSettings = Symbol("local-settings");
 const kOrigin = Symbol("origin");
 const kPendingRequestCalls = Symbol("kPendingRequestCalls");
 const kProtocol = Symbol("protocol");
+const kRemoteSettings = Symbol("remote-settings");
 const kSentHeaders = Symbol("sent-headers");
 const kSentTrailers = Symbol("sent-trailers");
 const kState = Symbol("state");
@@ -129,7 +143,7 @@ function debugHttp2(...args) {
 }
 
 export class Http2Session extends EventEmitter {
-  constructor(type, _options /* socket */) {
+  constructor(type, options /* socket */) {
     super();
 
     // TODO(bartlomieju): Handle sockets here
@@ -150,6 +164,10 @@ export class Http2Session extends EventEmitter {
     this[kAlpnProtocol] = undefined;
     this[kType] = type;
     this[kTimeout] = null;
+    this[kLocalSettings] = { ...getDefaultSettings(), ...options?.settings };
+    // The connection is set up by hyper, which doesn't report the settings
+    // of the peer, so they are assumed to be the defaults.
+    this[kRemoteSettings] = getDefaultSettings();
     // this[kProxySocket] = null;
     // this[kSocket] = socket;
     // this[kHandle] = undefined;
@@ -224,17 +242,38 @@ export class Http2Session extends EventEmitter {
   }
 
   get localSettings(): Record<string, unknown> {
-    notImplemented("Http2Session.localSettings");
-    return {};
+    return this[kLocalSettings];
   }
 
   get remoteSettings(): Record<string, unknown> {
-    notImplemented("Http2Session.remoteSettings");
-    return {};
+    return this[kRemoteSettings];
   }
 
-  settings(_settings: Record<string, unknown>, _callback: () => void) {
-    notImplemented("Http2Session.settings");
+  settings(
+    settings: Record<string, unknown>,
+    callback?: (
+      err: Error | null,
+      settings: Record<string, unknown>,
+      duration: number,
+    ) => void,
+  ) {
+    if (this.destroyed) {
+      throw new ERR_HTTP2_INVALID_SESSION();
+    }
+    validateSettings(settings);
+    if (callback !== undefined) {
+      validateFunction(callback, "callback");
+    }
+
+    // hyper manages the SETTINGS frames of the connection, so the new
+    // settings are only recorded and acknowledged here.
+    this[kState].pendingAck++;
+    nextTick(() => {
+      this[kState].pendingAck--;
+      this[kLocalSettings] = { ...this[kLocalSettings], ...settings };
+      this.emit("localSettings", this[kLocalSettings]);
+      callback?.(null, this[kLocalSettings], 0);
+    });
   }
 
   goaway(
@@ -356,8 +395,8 @@ function closeSession(session: Http2Session, code?: number, error?: Error) {
 }
 
 export class ServerHttp2Session extends Http2Session {
-  constructor() {
-    super(constants.NGHTTP2_SESSION_SERVER, {});
+  constructor(options: Record<string, unknown> = {}) {
+    super(constants.NGHTTP2_SESSION_SERVER, options);
   }
 
   altsvc(
@@ -624,10 +663,20 @@ export class Http2Stream extends EventEmitter {
   }
 
   // TODO(mmastrac): Implement duplex
-  end() {
+  end(chunk?, callback?: () => void) {
+    if (typeof chunk === "function") {
+      callback = chunk;
+      chunk = undefined;
+    }
     (async () => {
       const controller = await this.#controllerPromise;
+      if (typeof chunk === "string") {
+        controller.enqueue(ENCODER.encode(chunk));
+      } else if (chunk != null) {
+        controller.enqueue(chunk);
+      }
       controller.close();
+      callback?.();
     })();
   }
 
@@ -698,19 +747,16 @@ export class Http2Stream extends EventEmitter {
     return 0;
   }
 
-  get sentHeaders(): boolean {
-    notImplemented("Http2Stream.sentHeaders");
-    return false;
+  get sentHeaders(): Record<string, unknown> | undefined {
+    return this[kSentHeaders];
   }
 
-  get sentInfoHeaders(): Record<string, unknown> {
-    notImplemented("Http2Stream.sentInfoHeaders");
-    return {};
+  get sentInfoHeaders(): Record<string, unknown>[] | undefined {
+    return this[kInfoHeaders];
   }
 
-  get sentTrailers(): Record<string, unknown> {
-    notImplemented("Http2Stream.sentTrailers");
-    return {};
+  get sentTrailers(): Record<string, unknown> | undefined {
+    return this[kSentTrailers];
   }
 
   get session(): Http2Session {
@@ -727,6 +773,7 @@ export class Http2Stream extends EventEmitter {
   }
 
   sendTrailers(headers: Record<string, unknown>) {
+    this[kSentTrailers] = headers;
     const request = toInnerRequest(this._request);
     op_http_set_response_trailers(request.external, Object.entries(headers));
   }
@@ -1344,8 +1391,8 @@ export class ServerHttp2Stream extends Http2Stream {
     notImplemented("ServerHttp2Stream.additionalHeaders");
   }
 
-  end(): void {
-    super.end();
+  end(chunk?, callback?: () => void): void {
+    super.end(chunk, callback);
     if (this.#waitForTrailers) {
       this.emit("wantTrailers");
     }
@@ -1355,17 +1402,22 @@ export class ServerHttp2Stream extends Http2Stream {
     return this.#headersSent;
   }
 
+  // hyper doesn't support server push, so it is always disabled, like when
+  // the client sent SETTINGS_ENABLE_PUSH as 0.
   get pushAllowed(): boolean {
-    notImplemented("ServerHttp2Stream.pushAllowed");
     return false;
   }
 
   pushStream(
     _headers: Record<string, unknown>,
-    _options: Record<string, unknown>,
-    _callback: () => unknown,
+    options: Record<string, unknown> | ((err: Error) => unknown),
+    callback?: (err: Error) => unknown,
   ) {
-    notImplemented("ServerHttp2Stream.pushStream");
+    if (typeof options === "function") {
+      callback = options;
+    }
+    validateFunction(callback, "callback");
+    nextTick(callback, new ERR_HTTP2_PUSH_DISABLED());
   }
 
   respond(
@@ -1373,13 +1425,21 @@ export class ServerHttp2Stream extends Http2Stream {
     options: Record<string, unknown>,
   ) {
     this.#headersSent = true;
+    this[kSentHeaders] = headers;
     const response: ResponseInit = {};
     if (headers) {
+      const responseHeaders: [string, string][] = [];
       for (const [name, value] of Object.entries(headers)) {
         if (name == constants.HTTP2_HEADER_STATUS) {
           response.status = Number(value);
+        } else if (name[0] !== ":" && value !== undefined) {
+          const values = ArrayIsArray(value) ? value : [value];
+          for (const value of values) {
+            responseHeaders.push([name, String(value)]);
+          }
         }
       }
+      response.headers = responseHeaders;
     }
     if (options?.endStream) {
       this._deferred.resolve(this._response = new Response("", response));
@@ -1509,10 +1569,68 @@ function initializeOptions(options) {
   return options;
 }
 
+/** Serves the HTTP/2 connection `conn`, emitting a "session" event on
+ * `server` and a "stream" event for each request. */
+function serveHttp2Connection(
+  server: Http2Server | Http2SecureServer,
+  conn: Deno.Conn,
+  signal: AbortSignal,
+) {
+  try {
+    const session = new ServerHttp2Session(server[kOptions]);
+    server.emit("session", session);
+    const httpServer = serveHttpOnConnection(
+      conn,
+      signal,
+      async (req: Request) => {
+        try {
+          const controllerDeferred = Promise.withResolvers<
+            ReadableStreamDefaultController<Uint8Array>
+          >();
+          const body = new ReadableStream({
+            start(controller) {
+              controllerDeferred.resolve(controller);
+            },
+          });
+          const url = new URL(req.url);
+          const headers: Http2Headers = {
+            [constants.HTTP2_HEADER_METHOD]: req.method,
+            [constants.HTTP2_HEADER_SCHEME]: url.protocol.slice(0, -1),
+            [constants.HTTP2_HEADER_AUTHORITY]: url.host,
+            [constants.HTTP2_HEADER_PATH]: url.pathname + url.search,
+          };
+          for (const [name, value] of req.headers) {
+            headers[name] = value;
+          }
+          const stream = new ServerHttp2Stream(
+            session,
+            Promise.resolve(headers),
+            controllerDeferred.promise,
+            req.body,
+            body,
+            req,
+          );
+          server.emit("stream", stream, headers);
+          return await stream._deferred.promise;
+        } catch (e) {
+          console.log(">>> Error in serveHttpOnConnection", e);
+        }
+        return new Response("");
+      },
+      () => {
+        console.log(">>> error");
+      },
+      () => {},
+    );
+    httpServer.finished.then(() => session.destroy());
+  } catch (e) {
+    console.log(">>> Error in Http2Server", e);
+  }
+}
+
 export class Http2Server extends Server {
   #options: Record<string, unknown> = {};
   #abortController;
-  #server;
   timeout = 0;
 
   constructor(
@@ -1525,56 +1643,9 @@ export class Http2Server extends Server {
     this.#abortController = new AbortController();
     this.on("newListener", setupCompat);
 
-    this.on(
-      "connection",
-      (conn: Deno.Conn) => {
-        try {
-          const session = new ServerHttp2Session();
-          this.emit("session", session);
-          this.#server = serveHttpOnConnection(
-            conn,
-            this.#abortController.signal,
-            async (req: Request) => {
-              try {
-                const controllerDeferred = Promise.withResolvers<
-                  ReadableStreamDefaultController<Uint8Array>
-                >();
-                const body = new ReadableStream({
-                  start(controller) {
-                    controllerDeferred.resolve(controller);
-                  },
-                });
-                const headers: Http2Headers = {};
-                for (const [name, value] of req.headers) {
-                  headers[name] = value;
-                }
-                headers[constants.HTTP2_HEADER_PATH] =
-                  new URL(req.url).pathname;
-                const stream = new ServerHttp2Stream(
-                  session,
-                  Promise.resolve(headers),
-                  controllerDeferred.promise,
-                  req.body,
-                  body,
-                  req,
-                );
-                this.emit("stream", stream, headers);
-                return await stream._deferred.promise;
-              } catch (e) {
-                console.log(">>> Error in serveHttpOnConnection", e);
-              }
-              return new Response("");
-            },
-            () => {
-              console.log(">>> error");
-            },
-            () => {},
-          );
-        } catch (e) {
-          console.log(">>> Error in Http2Server", e);
-        }
-      },
-    );
+    this.on("connection", (conn: Deno.Conn) => {
+      serveHttp2Connection(this, conn, this.#abortController.signal);
+    });
     this.#options = options;
     if (typeof requestListener === "function") {
       this.on("request", requestListener);
@@ -1594,29 +1665,39 @@ export class Http2Server extends Server {
   }
 
   updateSettings(settings: Record<string, unknown>) {
+    validateSettings(settings);
     this.#options.settings = { ...this.#options.settings, ...settings };
   }
 }
 
-export class Http2SecureServer extends Server {
-  #options: Record<string, unknown> = {};
+export class Http2SecureServer extends TlsServer {
+  #abortController;
   timeout = 0;
 
   constructor(
     options: Record<string, unknown>,
     requestListener: () => unknown,
   ) {
-    super(options, function () {
-      notImplemented("connectionListener");
+    options = initializeOptions(options);
+    super({
+      ...options,
+      ALPNProtocols: options.allowHTTP1 ? ["h2", "http/1.1"] : ["h2"],
+    });
+    this[kOptions] = options;
+    this.#abortController = new AbortController();
+    this.on("newListener", setupCompat);
+
+    this.on("secureConnection", (conn: Deno.Conn) => {
+      serveHttp2Connection(this, conn, this.#abortController.signal);
     });
-    this.#options = options;
     if (typeof requestListener === "function") {
       this.on("request", requestListener);
     }
   }
 
-  close(_callback?: () => unknown) {
-    notImplemented("Http2SecureServer.close");
+  // Like `Http2Server`, serve the TLS connection instead of a socket.
+  _createSocket(clientHandle: TCP) {
+    return clientHandle[kStreamBaseField];
   }
 
   setTimeout(msecs: number, callback?: () => unknown) {
@@ -1627,7 +1708,8 @@ export class Http2SecureServer extends Server {
   }
 
   updateSettings(settings: Record<string, unknown>) {
-    this.#options.settings = { ...this.#options.settings, ...settings };
+    validateSettings(settings);
+    this[kOptions].settings = { ...this[kOptions].settings, ...settings };
   }
 }
 
@@ -1643,11 +1725,14 @@ export function createServer(
 }
 
 export function createSecureServer(
-  _options: Record<string, unknown>,
-  _onRequestHandler: () => unknown,
+  options: Record<string, unknown>,
+  onRequestHandler: () => unknown,
 ): Http2SecureServer {
-  notImplemented("http2.createSecureServer");
-  return new Http2SecureServer();
+  if (typeof options === "function") {
+    onRequestHandler = options;
+    options = {};
+  }
+  return new Http2SecureServer(options, onRequestHandler);
 }
 
 export function connect(
@@ -2048,21 +2133,148 @@ export const constants = {
 //   constants.HTTP2_HEADER_X_CONTENT_TYPE_OPTIONS,
 // ]);
 
+const kMaxInt = 2 ** 32 - 1;
+
+// The settings of a SETTINGS frame with their identifiers and the range of
+// their values, which is undefined for boolean settings.
+const kSettings: [string, number, [number, number] | undefined][] = [
+  [
+    "headerTableSize",
+    constants.NGHTTP2_SETTINGS_HEADER_TABLE_SIZE,
+    [0, kMaxInt],
+  ],
+  ["enablePush", constants.NGHTTP2_SETTINGS_ENABLE_PUSH, undefined],
+  [
+    "maxConcurrentStreams",
+    constants.NGHTTP2_SETTINGS_MAX_CONCURRENT_STREAMS,
+    [0, kMaxInt],
+  ],
+  [
+    "initialWindowSize",
+    constants.NGHTTP2_SETTINGS_INITIAL_WINDOW_SIZE,
+    [0, 2 ** 31 - 1],
+  ],
+  [
+    "maxFrameSize",
+    constants.NGHTTP2_SETTINGS_MAX_FRAME_SIZE,
+    [16384, 2 ** 24 - 1],
+  ],
+  [
+    "maxHeaderListSize",
+    constants.NGHTTP2_SETTINGS_MAX_HEADER_LIST_SIZE,
+    [0, kMaxInt],
+  ],
+  [
+    "enableConnectProtocol",
+    constants.NGHTTP2_SETTINGS_ENABLE_CONNECT_PROTOCOL,
+    undefined,
+  ],
+];
+
+function validateSettings(settings: Record<string, unknown> | undefined) {
+  if (settings === undefined) {
+    return;
+  }
+  validateObject(settings, "settings");
+  for (const [name, , range] of kSettings) {
+    const value = settings[name];
+    if (value === undefined) {
+      continue;
+    }
+    if (range === undefined) {
+      if (typeof value !== "boolean") {
+        throw new ERR_HTTP2_INVALID_SETTING_VALUE(name, value);
+      }
+    } else if (
+      typeof value !== "number" || !Number.isInteger(value) ||
+      value < range[0] || value > range[1]
+    ) {
+      throw new ERR_HTTP2_INVALID_SETTING_VALUE(
+        name,
+        value,
+        range[0],
+        range[1],
+      );
+    }
+  }
+}
+
 export function getDefaultSettings(): Record<string, unknown> {
-  notImplemented("http2.getDefaultSettings");
-  return {};
+  return {
+    headerTableSize: constants.DEFAULT_SETTINGS_HEADER_TABLE_SIZE,
+    enablePush: !!constants.DEFAULT_SETTINGS_ENABLE_PUSH,
+    initialWindowSize: constants.DEFAULT_SETTINGS_INITIAL_WINDOW_SIZE,
+    maxFrameSize: constants.DEFAULT_SETTINGS_MAX_FRAME_SIZE,
+    maxConcurrentStreams: constants.DEFAULT_SETTINGS_MAX_CONCURRENT_STREAMS,
+    maxHeaderListSize: constants.DEFAULT_SETTINGS_MAX_HEADER_LIST_SIZE,
+    maxHeaderSize: constants.DEFAULT_SETTINGS_MAX_HEADER_LIST_SIZE,
+    enableConnectProtocol: !!constants.DEFAULT_SETTINGS_ENABLE_CONNECT_PROTOCOL,
+  };
 }
 
-export function getPackedSettings(_settings: Record<string, unknown>): Buffer {
-  notImplemented("http2.getPackedSettings");
-  return {};
+export function getPackedSettings(
+  settings: Record<string, unknown> = {},
+): Buffer {
+  validateSettings(settings);
+  settings = {
+    ...settings,
+    maxHeaderListSize: settings.maxHeaderListSize ?? settings.maxHeaderSize,
+  };
+
+  const entries: [number, number][] = [];
+  for (const [name, id] of kSettings) {
+    if (settings[name] !== undefined) {
+      entries.push([id, Number(settings[name])]);
+    }
+  }
+
+  const buffer = Buffer.alloc(entries.length * 6);
+  for (let i = 0; i < entries.length; i++) {
+    buffer.writeUInt16BE(entries[i][0], i * 6);
+    buffer.writeUInt32BE(entries[i][1], i * 6 + 2);
+  }
+  return buffer;
 }
 
 export function getUnpackedSettings(
-  _buffer: Buffer | TypedArray,
+  buffer: Buffer | TypedArray,
+  options: { validate?: boolean } = {},
 ): Record<string, unknown> {
-  notImplemented("http2.getUnpackedSettings");
-  return {};
+  if (!isArrayBufferView(buffer)) {
+    throw new ERR_INVALID_ARG_TYPE(
+      "buf",
+      ["Buffer", "TypedArray"],
+      buffer,
+    );
+  }
+  if (buffer.byteLength % 6 !== 0) {
+    throw new ERR_HTTP2_INVALID_PACKED_SETTINGS_LENGTH();
+  }
+
+  const view = new DataView(
+    buffer.buffer,
+    buffer.byteOffset,
+    buffer.byteLength,
+  );
+  const settings: Record<string, unknown> = {};
+  for (let offset = 0; offset < buffer.byteLength; offset += 6) {
+    const id = view.getUint16(offset);
+    const value = view.getUint32(offset + 2);
+    const setting = kSettings.find(([, settingId]) => settingId === id);
+    if (setting === undefined) {
+      continue;
+    }
+    const [name, , range] = setting;
+    settings[name] = range === undefined ? value !== 0 : value;
+    if (name === "maxHeaderListSize") {
+      settings.maxHeaderSize = value;
+    }
+  }
+
+  if (options?.validate) {
+    validateSettings(settings);
+  }
+  return settings;
 }
 
 export const sensitiveHeaders = Symbol("nodejs.http2.sensitiveHeaders");
@@ -4,46 +4,148 @@
 // TODO(petamoriken): enable prefer-primordials for node polyfills
 // deno-lint-ignore-file prefer-primordials
 
+import { core } from "ext:core/mod.js";
+import {
+  op_inspector_connect,
+  op_inspector_enabled,
+  op_inspector_post,
+  op_inspector_receive,
+} from "ext:core/ops";
 import { EventEmitter } from "node:events";
+import process from "node:process";
+import { isMainThread } from "node:worker_threads";
 import { notImplemented } from "ext:deno_node/_utils.ts";
+import {
+  ERR_INSPECTOR_ALREADY_CONNECTED,
+  ERR_INSPECTOR_CLOSED,
+  ERR_INSPECTOR_COMMAND,
+  ERR_INSPECTOR_NOT_AVAILABLE,
+  ERR_INSPECTOR_NOT_CONNECTED,
+  ERR_INSPECTOR_NOT_WORKER,
+} from "ext:deno_node/internal/errors.ts";
+import {
+  validateFunction,
+  validateObject,
+  validateString,
+} from "ext:deno_node/internal/validators.mjs";
 
 const connectionSymbol = Symbol("connectionProperty");
 const messageCallbacksSymbol = Symbol("messageCallbacks");
 const nextIdSymbol = Symbol("nextId");
 const onMessageSymbol = Symbol("onMessage");
 
+type MessageCallback = (err: Error | null, result?: unknown) => void;
+
+interface InspectorResponse {
+  result?: Record<string, unknown>;
+  error?: { code: number; message: string };
+}
+
 class Session extends EventEmitter {
-  [connectionSymbol]: null;
-  [nextIdSymbol]: number;
-  [messageCallbacksSymbol]: Map<string, (e: Error) => void>;
+  /** The resource id of the session with the inspector. */
+  [connectionSymbol]: number | null = null;
+  [nextIdSymbol] = 1;
+  [messageCallbacksSymbol] = new Map<number, MessageCallback>();
 
   constructor() {
     super();
-    notImplemented("inspector.Session.prototype.constructor");
   }
 
   /** Connects the session to the inspector back-end. */
   connect() {
-    notImplemented("inspector.Session.prototype.connect");
+    if (this[connectionSymbol] !== null) {
+      throw new ERR_INSPECTOR_ALREADY_CONNECTED("The inspector session");
+    }
+    if (!op_inspector_enabled()) {
+      throw new ERR_INSPECTOR_NOT_AVAILABLE();
+    }
+    const rid = op_inspector_connect();
+    this[connectionSymbol] = rid;
+    this.#receiveNotifications(rid);
   }
 
   /** Connects the session to the main thread
    * inspector back-end. */
   connectToMainThread() {
-    notImplemented("inspector.Session.prototype.connectToMainThread");
+    if (isMainThread) {
+      throw new ERR_INSPECTOR_NOT_WORKER();
+    }
+    // Workers don't have access to the inspector of the main thread.
+    throw new ERR_INSPECTOR_NOT_AVAILABLE();
+  }
+
+  async #receiveNotifications(rid: number) {
+    while (true) {
+      const promise = op_inspector_receive(rid);
+      // Waiting for notifications shouldn't keep the process alive.
+      core.unrefOpPromise(promise);
+      let notification;
+      try {
+        notification = await promise;
+      } catch {
+        return;
+      }
+      if (notification === null) {
+        return;
+      }
+      this[onMessageSymbol](notification);
+    }
   }
 
-  [onMessageSymbol](_message: string) {
-    notImplemented("inspector.Session.prototype[Symbol('onMessage')]");
+  [onMessageSymbol](message: { method: string; params?: unknown }) {
+    this.emit("inspectorNotification", message);
+    this.emit(message.method, message);
   }
 
   /** Posts a message to the inspector back-end. */
   post(
-    _method: string,
-    _params?: Record<string, unknown>,
-    _callback?: (...args: unknown[]) => void,
+    method: string,
+    params?: Record<string, unknown> | MessageCallback | null,
+    callback?: MessageCallback,
   ) {
-    notImplemented("inspector.Session.prototype.post");
+    validateString(method, "method");
+    if (!callback && typeof params === "function") {
+      callback = params;
+      params = null;
+    }
+    if (params) {
+      validateObject(params, "params");
+    }
+    if (callback) {
+      validateFunction(callback, "callback");
+    }
+
+    const rid = this[connectionSymbol];
+    if (rid === null) {
+      throw new ERR_INSPECTOR_NOT_CONNECTED();
+    }
+    const id = this[nextIdSymbol]++;
+    if (callback) {
+      this[messageCallbacksSymbol].set(id, callback);
+    }
+    op_inspector_post(rid, method, params ?? null).then(
+      (response: InspectorResponse) => {
+        const callback = this[messageCallbacksSymbol].get(id);
+        this[messageCallbacksSymbol].delete(id);
+        if (!callback) {
+          return;
+        }
+        if (response.error) {
+          const { code, message } = response.error;
+          callback(new ERR_INSPECTOR_COMMAND(code, message));
+        } else {
+          callback(null, response.result);
+        }
+      },
+      (err: Error) => {
+        const callback = this[messageCallbacksSymbol].get(id);
+        this[messageCallbacksSymbol].delete(id);
+        // Posts cancelled by `disconnect()` were already called back.
+        if (callback) {
+          callback(err);
+        }
+      },
+    );
   }
 
   /** Immediately closes the session, all pending
@@ -51,7 +153,17 @@ class Session extends EventEmitter {
    * error.
    */
   disconnect() {
-    notImplemented("inspector.Session.prototype.disconnect");
+    const rid = this[connectionSymbol];
+    if (rid === null) {
+      return;
+    }
+    this[connectionSymbol] = null;
+    core.tryClose(rid);
+    const remainingCallbacks = this[messageCallbacksSymbol].values();
+    this[messageCallbacksSymbol] = new Map();
+    for (const callback of remainingCallbacks) {
+      process.nextTick(callback, new ERR_INSPECTOR_CLOSED());
+    }
   }
 }
 
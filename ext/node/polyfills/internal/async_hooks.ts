@@ -4,6 +4,8 @@
 // TODO(petamoriken): enable prefer-primordials for node polyfills
 // deno-lint-ignore-file prefer-primordials
 
+import { core } from "ext:core/mod.js";
+import { op_node_is_promise_rejected } from "ext:core/ops";
 // deno-lint-ignore camelcase
 import * as async_wrap from "ext:deno_node/internal_binding/async_wrap.ts";
 import { ERR_ASYNC_CALLBACK } from "ext:deno_node/internal/errors.ts";
@@ -69,7 +71,6 @@ const {
   kPromiseResolve,
   kTotals,
   kCheck,
-  kDefaultTriggerAsyncId,
   kStackLength,
 } = constants;
 
@@ -89,6 +90,10 @@ export const after_symbol = Symbol("after");
 export const destroy_symbol = Symbol("destroy");
 // deno-lint-ignore camelcase
 export const promise_resolve_symbol = Symbol("promiseResolve");
+// The stores of AsyncLocalStorage that the callbacks of an async resource run
+// with, see `AsyncContextFrame` in node:async_hooks.
+// deno-lint-ignore camelcase
+export const async_context_symbol = Symbol("asyncContext");
 
 export const symbols = {
   // deno-lint-ignore camelcase
@@ -105,7 +110,30 @@ export const symbols = {
   destroy_symbol,
   // deno-lint-ignore camelcase
   promise_resolve_symbol,
+  // deno-lint-ignore camelcase
+  async_context_symbol,
+};
+
+interface ExecutionContext {
+  asyncId: number;
+  triggerAsyncId: number;
+  // deno-lint-ignore no-explicit-any
+  resource: any;
+  context: unknown;
+}
+
+// The async resource whose callback is running. The ones whose callbacks it
+// was called from are on `executionStack`.
+const topLevelContext: ExecutionContext = {
+  asyncId: 1,
+  triggerAsyncId: 0,
+  resource: {},
+  context: undefined,
 };
+let executionContext = topLevelContext;
+const executionStack: ExecutionContext[] = [];
+async_id_fields[async_wrap.UidFields.kExecutionAsyncId] =
+  topLevelContext.asyncId;
 
 // deno-lint-ignore no-explicit-any
 function lookupPublicResource(resource: any) {
@@ -202,8 +230,65 @@ function restoreActiveHooks() {
   active_hooks.tmp_fields = null;
 }
 
-// deno-lint-ignore no-unused-vars
-let wantPromiseHook = false;
+let promiseHooksSet = false;
+
+/** Tracks promises as async resources, so that their reactions run in the
+ * async context the promise was created in. Hooks with callbacks are told
+ * about them from then on. */
+export function enablePromiseHooks() {
+  if (promiseHooksSet) {
+    return;
+  }
+  promiseHooksSet = true;
+
+  // deno-lint-ignore no-explicit-any
+  type AsyncPromise = any;
+  const init = (promise: AsyncPromise, parent?: AsyncPromise) => {
+    const context = executionContext.context;
+    if (context !== undefined) {
+      promise[async_context_symbol] = context;
+    }
+    if (enabledHooksExist()) {
+      const asyncId = newAsyncId();
+      const triggerAsyncId = parent?.[async_id_symbol] ??
+        getDefaultTriggerAsyncId();
+      promise[async_id_symbol] = asyncId;
+      promise[trigger_async_id_symbol] = triggerAsyncId;
+      emitInitScript(asyncId, "PROMISE", triggerAsyncId, promise);
+    }
+  };
+  // Promises created while no hooks were enabled have no async ids, so
+  // `executionAsyncId()` is 0 in their reactions, like in Node.
+  const before = (promise: AsyncPromise) => {
+    emitBeforeScript(
+      promise[async_id_symbol] ?? 0,
+      promise[trigger_async_id_symbol] ?? 0,
+      promise,
+    );
+  };
+  const after = (promise: AsyncPromise) => {
+    emitAfterScript(promise[async_id_symbol] ?? 0);
+    if (!op_node_is_promise_rejected(promise)) {
+      promise[async_context_symbol] = undefined;
+    }
+  };
+  const resolve = (promise: AsyncPromise) => {
+    const context = executionContext.context;
+    if (
+      context !== undefined && op_node_is_promise_rejected(promise) &&
+      promise[async_context_symbol] === undefined
+    ) {
+      promise[async_context_symbol] = context;
+    }
+    const asyncId = promise[async_id_symbol];
+    if (asyncId !== undefined && hasHooks(kPromiseResolve)) {
+      emitHook(promise_resolve_symbol, asyncId);
+    }
+  };
+
+  core.setPromiseHooks(init, before, after, resolve);
+}
+
 function enableHooks() {
   async_hook_fields[kCheck] += 1;
 
@@ -214,15 +299,8 @@ function enableHooks() {
 function disableHooks() {
   async_hook_fields[kCheck] -= 1;
 
-  wantPromiseHook = false;
-
   // TODO(kt3k): Uncomment the below
   // setCallbackTrampoline();
-
-  // Delay the call to `disablePromiseHook()` because we might currently be
-  // between the `before` and `after` calls of a Promise.
-  // TODO(kt3k): Uncomment the below
-  // enqueueMicrotask(disablePromiseHookIfNecessary);
 }
 
 // Return the triggerAsyncId meant for the constructor calling it. It's up to
@@ -249,13 +327,16 @@ export function defaultTriggerAsyncIdScope(
   }
   // CHECK(NumberIsSafeInteger(triggerAsyncId))
   // CHECK(triggerAsyncId > 0)
-  const oldDefaultTriggerAsyncId = async_id_fields[kDefaultTriggerAsyncId];
-  async_id_fields[kDefaultTriggerAsyncId] = triggerAsyncId;
+  const oldDefaultTriggerAsyncId =
+    async_id_fields[async_wrap.UidFields.kDefaultTriggerAsyncId];
+  async_id_fields[async_wrap.UidFields.kDefaultTriggerAsyncId] =
+    triggerAsyncId;
 
   try {
     return block.apply(null, args);
   } finally {
-    async_id_fields[kDefaultTriggerAsyncId] = oldDefaultTriggerAsyncId;
+    async_id_fields[async_wrap.UidFields.kDefaultTriggerAsyncId] =
+      oldDefaultTriggerAsyncId;
   }
 }
 
@@ -305,6 +386,125 @@ function emitInitScript(
 }
 export { emitInitScript as emitInit };
 
+// Calls the `before`, `after`, `destroy` or `promiseResolve` callbacks of
+// all hooks.
+function emitHook(symbol: symbol, asyncId: number) {
+  active_hooks.call_depth += 1;
+  try {
+    for (let i = 0; i < active_hooks.array.length; i++) {
+      // deno-lint-ignore no-explicit-any
+      const hook = active_hooks.array[i] as any;
+      if (typeof hook[symbol] === "function") {
+        hook[symbol](asyncId);
+      }
+    }
+  } finally {
+    active_hooks.call_depth -= 1;
+  }
+
+  if (active_hooks.call_depth === 0 && active_hooks.tmp_array !== null) {
+    restoreActiveHooks();
+  }
+}
+
+/** Makes `resource` the one whose callback is running, in the async context
+ * it was created in. */
+function emitBeforeScript(
+  asyncId: number,
+  triggerAsyncId: number,
+  // deno-lint-ignore no-explicit-any
+  resource: any,
+) {
+  pushAsyncContext(asyncId, triggerAsyncId, resource);
+
+  if (hasHooks(kBefore) && asyncId !== 0) {
+    emitHook(before_symbol, asyncId);
+  }
+}
+export { emitBeforeScript as emitBefore };
+
+function emitAfterScript(asyncId: number) {
+  if (hasHooks(kAfter) && asyncId !== 0) {
+    emitHook(after_symbol, asyncId);
+  }
+
+  popAsyncContext();
+}
+export { emitAfterScript as emitAfter };
+
+function emitDestroyScript(asyncId: number) {
+  // Return early if there are no destroy callbacks, or invalid asyncId.
+  if (!hasHooks(kDestroy) || asyncId <= 0) {
+    return;
+  }
+  emitHook(destroy_symbol, asyncId);
+}
+export { emitDestroyScript as emitDestroy };
+
+/** Sets up `resource`, a timer or tick object for example, as an async
+ * resource of type `type`. Its callbacks are run with `emitBefore()` and
+ * `emitAfter()`. */
+export function initAsyncResource(
+  // deno-lint-ignore no-explicit-any
+  resource: any,
+  type: string,
+  triggerAsyncId = getDefaultTriggerAsyncId(),
+) {
+  const asyncId = newAsyncId();
+  resource[async_id_symbol] = asyncId;
+  resource[trigger_async_id_symbol] = triggerAsyncId;
+  resource[async_context_symbol] = executionContext.context;
+  emitInitScript(asyncId, type, triggerAsyncId, resource);
+}
+
+function pushAsyncContext(
+  asyncId: number,
+  triggerAsyncId: number,
+  // deno-lint-ignore no-explicit-any
+  resource: any,
+) {
+  executionStack.push(executionContext);
+  executionContext = {
+    asyncId,
+    triggerAsyncId,
+    resource,
+    context: resource[async_context_symbol],
+  };
+  async_id_fields[async_wrap.UidFields.kExecutionAsyncId] = asyncId;
+  async_id_fields[async_wrap.UidFields.kTriggerAsyncId] = triggerAsyncId;
+}
+
+function popAsyncContext() {
+  executionContext = executionStack.pop() ?? topLevelContext;
+  async_id_fields[async_wrap.UidFields.kExecutionAsyncId] =
+    executionContext.asyncId;
+  async_id_fields[async_wrap.UidFields.kTriggerAsyncId] =
+    executionContext.triggerAsyncId;
+}
+
+export function executionAsyncId() {
+  return executionContext.asyncId;
+}
+
+export function triggerAsyncId() {
+  return executionContext.triggerAsyncId;
+}
+
+export function executionAsyncResource() {
+  return lookupPublicResource(executionContext.resource);
+}
+
+/** Returns the stores of AsyncLocalStorage that the running callback sees. */
+export function getAsyncContext(): unknown {
+  return executionContext.context;
+}
+
+/** Replaces the stores of AsyncLocalStorage for the rest of the running
+ * callback, and for the async resources it creates. */
+export function setAsyncContext(context: unknown) {
+  executionContext.context = context;
+}
+
 export function hasAsyncIdStack() {
   return hasHooks(kStackLength);
 }
@@ -387,8 +587,12 @@ export class AsyncHook {
       enableHooks();
     }
 
-    // TODO(kt3k): Uncomment the below
-    // updatePromiseHookMode();
+    if (
+      this[init_symbol] || this[before_symbol] || this[after_symbol] ||
+      this[promise_resolve_symbol]
+    ) {
+      enablePromiseHooks();
+    }
 
     return this;
   }
@@ -7,7 +7,13 @@
 // deno-lint-ignore-file prefer-primordials
 
 import { core, internals } from "ext:core/mod.js";
-import { op_node_ipc_read, op_node_ipc_write } from "ext:core/ops";
+import {
+  op_node_ipc_read,
+  op_node_ipc_take_tcp_listener,
+  op_node_ipc_take_tcp_stream,
+  op_node_ipc_write,
+  op_node_ipc_write_handle,
+} from "ext:core/ops";
 import {
   ArrayIsArray,
   ArrayPrototypeFilter,
@@ -46,6 +52,13 @@ import {
 import { kEmptyObject } from "ext:deno_node/internal/util.mjs";
 import { getValidatedPath } from "ext:deno_node/internal/fs/utils.mjs";
 import process from "node:process";
+import { Listener, TcpConn } from "ext:deno_net/01_net.js";
+import {
+  constants as TCPConstants,
+  TCP,
+} from "ext:deno_node/internal_binding/tcp_wrap.ts";
+
+const { internalRidSymbol } = core;
 
 export function mapValues<T, O>(
   record: Readonly<Record<string, T>>,
@@ -1099,6 +1112,46 @@ function toDenoArgs(args: string[]): string[] {
   return denoArgs;
 }
 
+/**
+ * Messages with a `cmd` starting with `NODE_` are used by Node itself, e.g.
+ * by the cluster module, and are emitted as "internalMessage" events.
+ */
+function isInternal(message) {
+  return message !== null &&
+    typeof message === "object" &&
+    typeof message.cmd === "string" &&
+    message.cmd.length > 5 &&
+    message.cmd.startsWith("NODE_");
+}
+
+/**
+ * Takes the handle sent along with a `NODE_HANDLE` message out of the IPC
+ * channel, and wraps it like the handles created by `node:net`.
+ */
+function takeHandle(ipc, type) {
+  switch (type) {
+    case "net.Socket": {
+      const { 0: rid, 1: localAddr, 2: remoteAddr } =
+        op_node_ipc_take_tcp_stream(ipc);
+      localAddr.transport = "tcp";
+      remoteAddr.transport = "tcp";
+      return new TCP(
+        TCPConstants.SOCKET,
+        new TcpConn(rid, remoteAddr, localAddr),
+      );
+    }
+    case "net.Server": {
+      const { 0: rid, 1: addr } = op_node_ipc_take_tcp_listener(ipc);
+      addr.transport = "tcp";
+      const handle = new TCP(TCPConstants.SERVER);
+      handle.adoptListener(new Listener(rid, addr));
+      return handle;
+    }
+    default:
+      return undefined;
+  }
+}
+
 export function setupChannel(target, ipc) {
   async function readLoop() {
     try {
@@ -1113,7 +1166,13 @@ export function setupChannel(target, ipc) {
           return;
         }
 
-        process.nextTick(handleMessage, msg);
+        if (msg.cmd === "NODE_HANDLE") {
+          // The handle has to be taken right away, as handles are queued in
+          // the order they are received.
+          process.nextTick(handleMessage, msg.msg, takeHandle(ipc, msg.type));
+        } else {
+          process.nextTick(handleMessage, msg);
+        }
       }
     } catch (err) {
       if (
@@ -1125,8 +1184,12 @@ export function setupChannel(target, ipc) {
     }
   }
 
-  function handleMessage(msg) {
-    target.emit("message", msg);
+  function handleMessage(msg, handle) {
+    if (isInternal(msg)) {
+      target.emit("internalMessage", msg, handle);
+    } else {
+      target.emit("message", msg, handle);
+    }
   }
 
   target.send = function (message, handle, options, callback) {
@@ -1147,11 +1210,23 @@ export function setupChannel(target, ipc) {
       throw new TypeError("ERR_MISSING_ARGS", "message");
     }
 
-    if (handle !== undefined) {
+    let write;
+    if (handle === undefined || handle === null) {
+      write = op_node_ipc_write(ipc, message);
+    } else if (handle instanceof TcpConn || handle instanceof Listener) {
+      // Only the connections and listeners used by the cluster module can be
+      // sent for now. The handle stays open in this process.
+      const type = handle instanceof TcpConn ? "net.Socket" : "net.Server";
+      write = op_node_ipc_write_handle(
+        ipc,
+        { cmd: "NODE_HANDLE", type, msg: message },
+        handle[internalRidSymbol],
+      );
+    } else {
       notImplemented("ChildProcess.send with handle");
     }
 
-    op_node_ipc_write(ipc, message)
+    write
       .then(() => {
         if (callback) {
           process.nextTick(callback, null);
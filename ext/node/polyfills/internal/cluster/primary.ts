@@ -0,0 +1,344 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
+
+import { fork } from "node:child_process";
+import { EventEmitter } from "node:events";
+import process from "node:process";
+import { nextTick } from "ext:deno_node/_next_tick.ts";
+import { isWindows } from "ext:deno_node/_util/os.ts";
+import { codeMap } from "ext:deno_node/internal_binding/uv.ts";
+import { RoundRobinHandle } from "ext:deno_node/internal/cluster/round_robin_handle.ts";
+import { SharedHandle } from "ext:deno_node/internal/cluster/shared_handle.ts";
+import {
+  internal,
+  SCHED_NONE,
+  SCHED_RR,
+  sendHelper,
+} from "ext:deno_node/internal/cluster/utils.ts";
+import { Worker } from "ext:deno_node/internal/cluster/worker.ts";
+
+const intercom = new EventEmitter();
+const handles = new Map<string, RoundRobinHandle | SharedHandle>();
+let ids = 0;
+let initialized = false;
+let schedulingPolicy: number;
+let cluster: any;
+
+/** Turns `target`, an EventEmitter, into the primary's cluster module. */
+export function initPrimary(target: any) {
+  cluster = target;
+  cluster.isWorker = false;
+  cluster.isMaster = true;
+  cluster.isPrimary = true;
+  cluster.worker = undefined;
+  cluster.workers = {};
+  cluster.settings = {};
+
+  const schedulingPolicyEnv = ({
+    none: SCHED_NONE,
+    rr: SCHED_RR,
+  } as Record<string, number>)[process.env.NODE_CLUSTER_SCHED_POLICY!];
+  // Handles can't be sent to processes on Windows yet, so connections
+  // can't be distributed by the primary there.
+  cluster.schedulingPolicy = schedulingPolicyEnv ??
+    (isWindows ? SCHED_NONE : SCHED_RR);
+
+  cluster.setupPrimary = setupPrimary;
+  // Deprecated alias must be same as setupPrimary
+  cluster.setupMaster = setupPrimary;
+  cluster.fork = clusterFork;
+  cluster.disconnect = disconnect;
+
+  // Extend generic Worker with methods specific to the primary process.
+  Worker.prototype.disconnect = workerDisconnect;
+  Worker.prototype.destroy = workerDestroy;
+}
+
+function setupPrimary(options?: Record<string, unknown>) {
+  const settings = {
+    args: process.argv.slice(2),
+    exec: process.argv[1],
+    execArgv: process.execArgv,
+    silent: false,
+    ...cluster.settings,
+    ...options,
+  };
+
+  cluster.settings = settings;
+
+  if (initialized === true) {
+    nextTick(setupSettingsNT, settings);
+    return;
+  }
+
+  initialized = true;
+  // Freeze policy.
+  schedulingPolicy = cluster.schedulingPolicy;
+
+  if (schedulingPolicy !== SCHED_NONE && schedulingPolicy !== SCHED_RR) {
+    throw new Error(`Bad cluster.schedulingPolicy: ${schedulingPolicy}`);
+  }
+
+  nextTick(setupSettingsNT, settings);
+}
+
+function setupSettingsNT(settings: unknown) {
+  cluster.emit("setup", settings);
+}
+
+function createWorkerProcess(id: number, env?: Record<string, string>) {
+  const workerEnv = {
+    ...process.env,
+    ...env,
+    NODE_UNIQUE_ID: `${id}`,
+  };
+  const settings = cluster.settings;
+
+  return fork(settings.exec, settings.args, {
+    cwd: settings.cwd,
+    env: workerEnv,
+    serialization: settings.serialization,
+    silent: settings.silent,
+    windowsHide: settings.windowsHide,
+    execArgv: [...settings.execArgv],
+    stdio: settings.stdio,
+    gid: settings.gid,
+    uid: settings.uid,
+  });
+}
+
+function removeWorker(worker: Worker) {
+  delete cluster.workers[worker.id];
+
+  if (Object.keys(cluster.workers).length === 0) {
+    intercom.emit("disconnect");
+  }
+}
+
+function removeHandlesForWorker(worker: Worker) {
+  handles.forEach((handle, key) => {
+    if (handle.remove(worker)) {
+      handles.delete(key);
+    }
+  });
+}
+
+function clusterFork(env?: Record<string, string>) {
+  setupPrimary();
+  const id = ++ids;
+  const workerProcess = createWorkerProcess(id, env);
+  const worker = new Worker({
+    id: id,
+    process: workerProcess,
+  });
+
+  worker.on("message", function (this: Worker, message, handle) {
+    cluster.emit("message", this, message, handle);
+  });
+
+  worker.process.once("exit", (exitCode: number, signalCode: string) => {
+    // Remove the worker from the workers list only if it has disconnected,
+    // otherwise we might still want to access it.
+    if (!worker.isConnected()) {
+      removeHandlesForWorker(worker);
+      removeWorker(worker);
+    }
+
+    worker.exitedAfterDisconnect = !!worker.exitedAfterDisconnect;
+    worker.state = "dead";
+    worker.emit("exit", exitCode, signalCode);
+    cluster.emit("exit", worker, exitCode, signalCode);
+  });
+
+  worker.process.once("disconnect", () => {
+    // Now is a good time to remove the handles associated with this worker
+    // because it is not connected to the primary anymore.
+    removeHandlesForWorker(worker);
+
+    // Remove the worker from the workers list only if its process has
+    // exited. Otherwise, we might still want to access it.
+    if (worker.isDead()) {
+      removeWorker(worker);
+    }
+
+    worker.exitedAfterDisconnect = !!worker.exitedAfterDisconnect;
+    worker.state = "disconnected";
+    worker.emit("disconnect");
+    cluster.emit("disconnect", worker);
+  });
+
+  worker.process.on("internalMessage", internal(worker, onmessage));
+  nextTick(emitForkNT, worker);
+  cluster.workers[worker.id] = worker;
+  return worker;
+}
+
+function emitForkNT(worker: Worker) {
+  cluster.emit("fork", worker);
+}
+
+function disconnect(cb?: () => void) {
+  const workers = Object.values(cluster.workers) as Worker[];
+
+  if (workers.length === 0) {
+    nextTick(() => intercom.emit("disconnect"));
+  } else {
+    for (const worker of workers) {
+      if (worker.isConnected()) {
+        worker.disconnect();
+      }
+    }
+  }
+
+  if (typeof cb === "function") {
+    intercom.once("disconnect", cb);
+  }
+}
+
+const methodMessageMapping: Record<
+  string,
+  (worker: Worker, message: any) => void
+> = {
+  close,
+  exitedAfterDisconnect,
+  listening,
+  online,
+  queryServer,
+};
+
+function onmessage(this: Worker, message: any, _handle: unknown) {
+  const fn = methodMessageMapping[message.act];
+
+  if (typeof fn === "function") {
+    fn(this, message);
+  }
+}
+
+function online(worker: Worker) {
+  worker.state = "online";
+  worker.emit("online");
+  cluster.emit("online", worker);
+}
+
+function exitedAfterDisconnect(worker: Worker, message: any) {
+  worker.exitedAfterDisconnect = true;
+  send(worker, { ack: message.seq });
+}
+
+function queryServer(worker: Worker, message: any) {
+  // Stop processing if worker already disconnecting
+  if (worker.exitedAfterDisconnect) {
+    return;
+  }
+
+  const key = `${message.address}:${message.port}:${message.addressType}:` +
+    `${message.fd}:${message.index}`;
+  let handle = handles.get(key);
+
+  if (handle === undefined) {
+    if (
+      typeof message.port !== "number" || message.port < 0 ||
+      typeof message.fd === "number"
+    ) {
+      // Only TCP servers are shared with workers.
+      send(worker, {
+        errno: codeMap.get("ENOTSUP"),
+        key,
+        ack: message.seq,
+        data: null,
+      });
+      return;
+    }
+
+    if (schedulingPolicy !== SCHED_RR) {
+      handle = new SharedHandle(key, message.address, message);
+    } else {
+      handle = new RoundRobinHandle(key, message.address, message);
+    }
+
+    handles.set(key, handle);
+  }
+
+  if (!handle.data) {
+    handle.data = message.data;
+  }
+
+  // Set custom server data
+  handle.add(worker, (errno, reply, handle) => {
+    const { data } = handles.get(key)!;
+
+    if (errno) {
+      // Gives other workers a chance to retry.
+      handles.delete(key);
+    }
+
+    send(
+      worker,
+      {
+        errno,
+        key,
+        ack: message.seq,
+        data,
+        ...(reply as Record<string, unknown>),
+      },
+      handle,
+    );
+  });
+}
+
+function listening(worker: Worker, message: any) {
+  const info = {
+    addressType: message.addressType,
+    address: message.address,
+    port: message.port,
+    fd: message.fd,
+  };
+
+  worker.state = "listening";
+  worker.emit("listening", info);
+  cluster.emit("listening", worker, info);
+}
+
+// Server in worker is closing, remove from list. The handle may have been
+// removed by a prior call to removeHandlesForWorker() so guard against that.
+function close(worker: Worker, message: any) {
+  const key = message.key;
+  const handle = handles.get(key);
+
+  if (handle && handle.remove(worker)) {
+    handles.delete(key);
+  }
+}
+
+function send(
+  worker: Worker,
+  message: Record<string, unknown>,
+  handle?: unknown,
+  cb?: (...args: any[]) => void,
+) {
+  return sendHelper(worker.process, message, handle, cb);
+}
+
+function workerDisconnect(this: Worker) {
+  this.exitedAfterDisconnect = true;
+  send(this, { act: "disconnect" });
+  removeHandlesForWorker(this);
+  removeWorker(this);
+  return this;
+}
+
+function workerDestroy(this: Worker, signo?: string) {
+  const proc = this.process;
+  const signal = signo || "SIGTERM";
+
+  if (this.isConnected()) {
+    this.once("disconnect", () => proc.kill(signal));
+    this.disconnect();
+    return;
+  }
+
+  proc.kill(signal);
+}
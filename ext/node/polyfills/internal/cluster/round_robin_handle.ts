@@ -0,0 +1,154 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
+
+import { createServer } from "node:net";
+import { kStreamBaseField } from "ext:deno_node/internal_binding/stream_wrap.ts";
+import { constants } from "ext:deno_node/internal_binding/tcp_wrap.ts";
+import { sendHelper } from "ext:deno_node/internal/cluster/utils.ts";
+import type { Worker } from "ext:deno_node/internal/cluster/worker.ts";
+
+/**
+ * A server in the primary that accepts connections and hands them to its
+ * workers in turn, for `cluster.SCHED_RR`.
+ */
+export class RoundRobinHandle {
+  key: string;
+  all = new Map<number, Worker>();
+  free = new Map<number, Worker>();
+  handles: any[] = [];
+  handle: any = null;
+  server: any;
+  data: unknown;
+
+  constructor(
+    key: string,
+    address: string,
+    { port, flags, backlog }: Record<string, any>,
+  ) {
+    this.key = key;
+    this.server = createServer(() => {
+      throw new Error("unreachable");
+    });
+
+    this.server.listen({
+      port,
+      host: address,
+      ipv6Only: Boolean(flags & constants.UV_TCP_IPV6ONLY),
+      backlog,
+    });
+
+    this.server.once("listening", () => {
+      this.handle = this.server._handle;
+      this.handle.onconnection = (err: number, handle: any) =>
+        this.distribute(err, handle);
+      this.server._handle = null;
+      this.server = null;
+    });
+  }
+
+  add(
+    worker: Worker,
+    send: (errno: number | null, reply: unknown, handle?: unknown) => void,
+  ) {
+    this.all.set(worker.id, worker);
+
+    const done = () => {
+      const out = {};
+      this.handle.getsockname(out);
+      send(null, { sockname: out }, null);
+      // In case there are connections pending.
+      this.handoff(worker);
+    };
+
+    if (this.server === null) {
+      return done();
+    }
+
+    // Still busy binding.
+    this.server.once("listening", done);
+    this.server.once("error", (err: any) => {
+      send(err.errno, null);
+    });
+  }
+
+  remove(worker: Worker) {
+    const existed = this.all.delete(worker.id);
+
+    if (!existed) {
+      return false;
+    }
+
+    this.free.delete(worker.id);
+
+    if (this.all.size !== 0) {
+      return false;
+    }
+
+    for (const handle of this.handles) {
+      handle.close();
+    }
+    this.handles = [];
+
+    if (this.handle) {
+      this.handle.close();
+      this.handle = null;
+    } else if (this.server) {
+      this.server.close();
+      this.server = null;
+    }
+    return true;
+  }
+
+  distribute(err: number, handle: any) {
+    // If `accept` fails just skip it (handle is undefined).
+    if (err) {
+      return;
+    }
+
+    this.handles.push(handle);
+    const { value: worker } = this.free.values().next();
+
+    if (worker !== undefined) {
+      this.free.delete(worker.id);
+      this.handoff(worker);
+    }
+  }
+
+  handoff(worker: Worker) {
+    if (!this.all.has(worker.id)) {
+      // Worker is closing (or has closed) the server.
+      return;
+    }
+
+    const handle = this.handles.shift();
+
+    if (handle === undefined) {
+      // Add to ready queue again.
+      this.free.set(worker.id, worker);
+      return;
+    }
+
+    const message = { act: "newconn", key: this.key };
+
+    // The worker gets a copy of the connection, so the primary closes its
+    // own once the worker has accepted it.
+    sendHelper(
+      worker.process,
+      message,
+      handle[kStreamBaseField],
+      (reply: any) => {
+        if (reply.accepted) {
+          handle.close();
+        } else {
+          // Worker is shutting down. Send to another.
+          this.distribute(0, handle);
+        }
+
+        this.handoff(worker);
+      },
+    );
+  }
+}
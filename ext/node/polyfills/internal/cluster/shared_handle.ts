@@ -0,0 +1,63 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
+
+import { listen } from "ext:deno_net/01_net.js";
+import { codeMap } from "ext:deno_node/internal_binding/uv.ts";
+import type { Worker } from "ext:deno_node/internal/cluster/worker.ts";
+
+/**
+ * A listener in the primary that is shared with all of its workers, which
+ * accept connections from it themselves, for `cluster.SCHED_NONE`.
+ */
+export class SharedHandle {
+  key: string;
+  workers = new Map<number, Worker>();
+  handle: Deno.Listener | null = null;
+  errno = 0;
+  data: unknown;
+
+  constructor(key: string, address: string, { port }: Record<string, any>) {
+    this.key = key;
+
+    try {
+      this.handle = listen({ hostname: address, port, transport: "tcp" });
+    } catch (e) {
+      if (e instanceof Deno.errors.AddrInUse) {
+        this.errno = codeMap.get("EADDRINUSE")!;
+      } else if (e instanceof Deno.errors.AddrNotAvailable) {
+        this.errno = codeMap.get("EADDRNOTAVAIL")!;
+      } else if (e instanceof Deno.errors.PermissionDenied) {
+        this.errno = codeMap.get("EACCES")!;
+      } else {
+        this.errno = codeMap.get("UNKNOWN")!;
+      }
+    }
+  }
+
+  add(
+    worker: Worker,
+    send: (errno: number, reply: unknown, handle?: unknown) => void,
+  ) {
+    this.workers.set(worker.id, worker);
+    send(this.errno, null, this.handle);
+  }
+
+  remove(worker: Worker) {
+    if (!this.workers.has(worker.id)) {
+      return false;
+    }
+
+    this.workers.delete(worker.id);
+
+    if (this.workers.size !== 0) {
+      return false;
+    }
+
+    this.handle?.close();
+    this.handle = null;
+    return true;
+  }
+}
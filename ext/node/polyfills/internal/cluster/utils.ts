@@ -0,0 +1,64 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
+
+/** Let the operating system distribute connections between workers. */
+export const SCHED_NONE = 1;
+/** Let the primary accept connections and hand them to workers in turn. */
+export const SCHED_RR = 2;
+
+const callbacks = new Map<number, (...args: any[]) => void>();
+let seq = 0;
+
+/**
+ * Sends an internal cluster message over the IPC channel of `proc`. `cb` is
+ * called with the reply, which is the message that acknowledges `seq`.
+ */
+export function sendHelper(
+  proc: any,
+  message: Record<string, unknown>,
+  handle?: unknown,
+  cb?: (...args: any[]) => void,
+) {
+  if (!proc.connected) {
+    return false;
+  }
+
+  // Mark the message as internal, see `isInternal()` in
+  // ext:deno_node/internal/child_process.ts.
+  message = { cmd: "NODE_CLUSTER", ...message, seq };
+
+  if (typeof cb === "function") {
+    callbacks.set(seq, cb);
+  }
+
+  seq += 1;
+  return proc.send(message, handle);
+}
+
+/**
+ * Returns a handler for the "internalMessage" event that calls `cb` for
+ * cluster messages, or the callback of the message they reply to.
+ */
+export function internal(worker: unknown, cb: (...args: any[]) => void) {
+  return function onInternalMessage(message: any, handle: unknown) {
+    if (message.cmd !== "NODE_CLUSTER") {
+      return;
+    }
+
+    let fn = cb;
+
+    if (message.ack !== undefined) {
+      const callback = callbacks.get(message.ack);
+
+      if (callback !== undefined) {
+        fn = callback;
+        callbacks.delete(message.ack);
+      }
+    }
+
+    fn.call(worker, message, handle);
+  };
+}
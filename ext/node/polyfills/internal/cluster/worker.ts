@@ -0,0 +1,74 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
+
+import { EventEmitter } from "node:events";
+
+export interface WorkerOptions {
+  id?: number;
+  process?: any;
+  state?: string;
+}
+
+/**
+ * A Worker object contains all public information and method about a worker.
+ * In the primary it can be obtained using cluster.workers. In a worker it can
+ * be obtained using cluster.worker.
+ *
+ * `disconnect()` and `destroy()` are added by the primary and the worker
+ * implementations of the cluster module, as they differ between the two.
+ */
+export class Worker extends EventEmitter {
+  exitedAfterDisconnect: boolean | undefined = undefined;
+  state: string;
+  id: number;
+  process: any;
+  declare disconnect: () => this;
+  declare destroy: (signal?: string) => void;
+
+  constructor(options?: WorkerOptions) {
+    super();
+
+    if (options === null || typeof options !== "object") {
+      options = {};
+    }
+
+    this.state = options.state || "none";
+    this.id = options.id! | 0;
+
+    if (options.process) {
+      this.process = options.process;
+      this.process.on(
+        "error",
+        (code: unknown, signal: unknown) => this.emit("error", code, signal),
+      );
+      this.process.on(
+        "message",
+        (message: unknown, handle: unknown) =>
+          this.emit("message", message, handle),
+      );
+    }
+  }
+
+  /** Kills the worker, see `destroy()`. */
+  kill(signal?: string) {
+    this.destroy(signal);
+  }
+
+  /** Sends a message to the primary, or to the worker from the primary. */
+  send(...args: unknown[]) {
+    return this.process.send(...args);
+  }
+
+  /** Whether the worker's process has exited. */
+  isDead() {
+    return this.process.exitCode != null || this.process.signalCode != null;
+  }
+
+  /** Whether the worker is connected to its primary. */
+  isConnected() {
+    return this.process.connected;
+  }
+}
@@ -0,0 +1,138 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials
+
+import { op_node_argon2_async, op_node_argon2_sync } from "ext:core/ops";
+
+import { Buffer } from "node:buffer";
+import {
+  validateFunction,
+  validateInteger,
+  validateObject,
+  validateOneOf,
+} from "ext:deno_node/internal/validators.mjs";
+import {
+  ERR_OUT_OF_RANGE,
+  hideStackFrames,
+} from "ext:deno_node/internal/errors.ts";
+import {
+  toBuf,
+  validateByteSource,
+} from "ext:deno_node/internal/crypto/util.ts";
+import type { BinaryLike } from "ext:deno_node/internal/crypto/types.ts";
+import { isAnyArrayBuffer } from "ext:deno_node/internal/util/types.ts";
+
+export type Argon2Algorithm = "argon2d" | "argon2i" | "argon2id";
+
+export interface Argon2Parameters {
+  message: BinaryLike;
+  nonce: BinaryLike;
+  parallelism: number;
+  tagLength: number;
+  memory: number;
+  passes: number;
+  secret?: BinaryLike;
+  associatedData?: BinaryLike;
+}
+
+type Callback = (err: Error | null, result?: Buffer) => void;
+
+function toBytes(value: BinaryLike): string | ArrayBufferView {
+  if (isAnyArrayBuffer(value)) {
+    return new Uint8Array(value as unknown as ArrayBuffer);
+  }
+  return toBuf(value as string | Buffer);
+}
+
+const validateParameters = hideStackFrames(
+  (algorithm: string, parameters: Argon2Parameters) => {
+    validateOneOf(algorithm, "algorithm", ["argon2d", "argon2i", "argon2id"]);
+    validateObject(parameters, "parameters");
+
+    const {
+      message,
+      nonce,
+      parallelism,
+      tagLength,
+      memory,
+      passes,
+      secret,
+      associatedData,
+    } = parameters;
+
+    validateByteSource(message, "parameters.message");
+    validateByteSource(nonce, "parameters.nonce");
+    if (secret !== undefined) {
+      validateByteSource(secret, "parameters.secret");
+    }
+    if (associatedData !== undefined) {
+      validateByteSource(associatedData, "parameters.associatedData");
+    }
+
+    validateInteger(parallelism, "parameters.parallelism", 1, 2 ** 24 - 1);
+    validateInteger(tagLength, "parameters.tagLength", 4, 2 ** 32 - 1);
+    validateInteger(passes, "parameters.passes", 1, 2 ** 32 - 1);
+    validateInteger(memory, "parameters.memory", 1, 2 ** 32 - 1);
+    if (memory < 8 * parallelism) {
+      throw new ERR_OUT_OF_RANGE(
+        "parameters.memory",
+        `>= ${8 * parallelism}`,
+        memory,
+      );
+    }
+
+    const nonceBytes = toBytes(nonce);
+    if (Buffer.byteLength(nonceBytes) < 8) {
+      throw new ERR_OUT_OF_RANGE(
+        "parameters.nonce.byteLength",
+        ">= 8",
+        Buffer.byteLength(nonceBytes),
+      );
+    }
+
+    return {
+      algorithm,
+      message: toBytes(message),
+      nonce: nonceBytes,
+      parallelism,
+      tagLength,
+      memory,
+      passes,
+      secret: secret === undefined ? undefined : toBytes(secret),
+      associatedData: associatedData === undefined
+        ? undefined
+        : toBytes(associatedData),
+    };
+  },
+);
+
+export function argon2Sync(
+  algorithm: Argon2Algorithm,
+  parameters: Argon2Parameters,
+): Buffer {
+  const params = validateParameters(algorithm, parameters);
+
+  const buf = Buffer.alloc(params.tagLength);
+  op_node_argon2_sync(params, buf);
+  return buf;
+}
+
+export function argon2(
+  algorithm: Argon2Algorithm,
+  parameters: Argon2Parameters,
+  callback: Callback,
+) {
+  const params = validateParameters(algorithm, parameters);
+  validateFunction(callback, "callback");
+
+  op_node_argon2_async(params).then(
+    (buf: Uint8Array) => callback(null, Buffer.from(buf.buffer)),
+    (err: Error) => callback(err),
+  );
+}
+
+export default {
+  argon2,
+  argon2Sync,
+};
@@ -120,6 +120,7 @@ export function scrypt(
       (buf: Uint8Array) => {
         cb(null, Buffer.from(buf.buffer));
       },
+      (err: unknown) => cb(err),
     );
   } catch (err: unknown) {
     return cb(err);
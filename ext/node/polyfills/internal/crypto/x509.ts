@@ -13,6 +13,7 @@ import {
   op_node_x509_get_issuer,
   op_node_x509_get_serial_number,
   op_node_x509_get_subject,
+  op_node_x509_get_subject_alt_name,
   op_node_x509_get_valid_from,
   op_node_x509_get_valid_to,
   op_node_x509_key_usage,
@@ -53,6 +54,37 @@ export interface X509CheckOptions {
   singleLabelSubdomains: boolean;
 }
 
+/**
+ * Turns a distinguished name as formatted by `X509Certificate`, one
+ * attribute per line, into an object like in a legacy certificate object.
+ * Repeated attributes are collected in arrays.
+ */
+function nameToObject(name: string): Record<string, string | string[]> {
+  const object: Record<string, string | string[]> = Object.create(null);
+
+  for (const line of name.split("\n")) {
+    const index = line.indexOf("=");
+
+    if (index === -1) {
+      continue;
+    }
+
+    const key = line.slice(0, index);
+    const value = line.slice(index + 1);
+    const existing = object[key];
+
+    if (existing === undefined) {
+      object[key] = value;
+    } else if (Array.isArray(existing)) {
+      existing.push(value);
+    } else {
+      object[key] = [existing, value];
+    }
+  }
+
+  return object;
+}
+
 export class X509Certificate {
   #handle: number;
 
@@ -165,7 +197,7 @@ export class X509Certificate {
   }
 
   get subjectAltName(): string | undefined {
-    return undefined;
+    return op_node_x509_get_subject_alt_name(this.#handle) ?? undefined;
   }
 
   toJSON(): string {
@@ -173,7 +205,18 @@ export class X509Certificate {
   }
 
   toLegacyObject(): PeerCertificate {
-    notImplemented("crypto.X509Certificate.prototype.toLegacyObject");
+    return {
+      subject: nameToObject(this.subject),
+      issuer: nameToObject(this.issuer),
+      subjectaltname: this.subjectAltName,
+      ca: this.ca,
+      valid_from: this.validFrom,
+      valid_to: this.validTo,
+      fingerprint: this.fingerprint,
+      fingerprint256: this.fingerprint256,
+      fingerprint512: this.fingerprint512,
+      serialNumber: this.serialNumber,
+    };
   }
 
   toString(): string {
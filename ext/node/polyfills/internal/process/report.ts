@@ -12,6 +12,7 @@ const {
 
 import { arch, versions } from "ext:deno_node/_process/process.ts";
 import { cpus, hostname, networkInterfaces } from "node:os";
+import { getRequestedProcessBindings } from "ext:deno_node/internal_binding/mod.ts";
 
 function writeReport(_filename: string, _err: typeof Error) {
   return "";
@@ -67,6 +68,8 @@ function getReport(_err: typeof Error) {
     environmentVariables: todoUndefined,
     userLimits: todoUndefined,
     sharedObjects: todoUndefined,
+    // Deno specific: which legacy `process.binding()` modules were used.
+    processBindings: getRequestedProcessBindings(),
   };
 }
 
@@ -18,6 +18,13 @@ import {
 } from "ext:deno_node/internal/validators.mjs";
 import { ERR_OUT_OF_RANGE } from "ext:deno_node/internal/errors.ts";
 import { emitWarning } from "node:process";
+import {
+  emitAfter,
+  emitBefore,
+  emitDestroy,
+  initAsyncResource,
+  symbols,
+} from "ext:deno_node/internal/async_hooks.ts";
 import {
   clearTimeout as clearTimeout_,
   setImmediate as setImmediate_,
@@ -32,6 +39,10 @@ export const kTimerId = Symbol("timerId");
 export const kTimeout = Symbol("timeout");
 const kRefed = Symbol("refed");
 const createTimer = Symbol("createTimer");
+const {
+  async_id_symbol: asyncIdSymbol,
+  trigger_async_id_symbol: triggerAsyncIdSymbol,
+} = symbols;
 
 /**
  * The keys in this map correspond to the key ID's in the spec's map of active
@@ -52,6 +63,7 @@ export function Timeout(callback, after, args, isRepeat, isRefed) {
   this._isRepeat = isRepeat;
   this._destroyed = false;
   this[kRefed] = isRefed;
+  initAsyncResource(this, "Timeout");
   this[kTimerId] = this[createTimer]();
 }
 
@@ -61,7 +73,16 @@ Timeout.prototype[createTimer] = function () {
     if (!this._isRepeat) {
       MapPrototypeDelete(activeTimers, this[kTimerId]);
     }
-    return callback.bind(this)(...args);
+    const asyncId = this[asyncIdSymbol];
+    emitBefore(asyncId, this[triggerAsyncIdSymbol], this);
+    try {
+      return callback.bind(this)(...args);
+    } finally {
+      emitAfter(asyncId);
+      if (!this._isRepeat) {
+        emitDestroy(asyncId);
+      }
+    }
   };
   const id = this._isRepeat
     ? setInterval_(cb, this._idleTimeout, ...this._timerArgs)
@@ -118,7 +139,17 @@ Timeout.prototype[Symbol.toPrimitive] = function () {
 
 // Immediate constructor function.
 export function Immediate(callback, ...args) {
-  this._immediateId = setImmediate_(callback, ...args);
+  initAsyncResource(this, "Immediate");
+  const asyncId = this[asyncIdSymbol];
+  this._immediateId = setImmediate_((...args) => {
+    emitBefore(asyncId, this[triggerAsyncIdSymbol], this);
+    try {
+      return callback(...args);
+    } finally {
+      emitAfter(asyncId);
+      emitDestroy(asyncId);
+    }
+  }, ...args);
 }
 
 // Make sure the linked list only shows the minimal necessary information.
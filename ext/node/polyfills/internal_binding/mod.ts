@@ -8,6 +8,7 @@ import * as buffer from "ext:deno_node/internal_binding/buffer.ts";
 import * as caresWrap from "ext:deno_node/internal_binding/cares_wrap.ts";
 import * as constants from "ext:deno_node/internal_binding/constants.ts";
 import * as crypto from "ext:deno_node/internal_binding/crypto.ts";
+import * as nodeFile from "ext:deno_node/internal_binding/node_file.ts";
 import * as pipeWrap from "ext:deno_node/internal_binding/pipe_wrap.ts";
 import * as streamWrap from "ext:deno_node/internal_binding/stream_wrap.ts";
 import * as stringDecoder from "ext:deno_node/internal_binding/string_decoder.ts";
@@ -28,7 +29,7 @@ const modules = {
   credentials: {},
   crypto,
   errors: {},
-  fs: {},
+  fs: nodeFile,
   "fs_dir": {},
   "fs_event_wrap": {},
   "heap_utils": {},
@@ -77,3 +78,52 @@ export function getBinding(name: BindingName) {
   }
   return mod;
 }
+
+// Bindings that are reachable through `process.binding()`. Everything else
+// is internal to the polyfills. This is Node's own allow list, minus
+// `process_wrap` which has no binding here.
+const processBindingAllowList = new Set<string>([
+  "async_wrap",
+  "buffer",
+  "cares_wrap",
+  "config",
+  "constants",
+  "contextify",
+  "crypto",
+  "fs",
+  "fs_event_wrap",
+  "http_parser",
+  "icu",
+  "inspector",
+  "js_stream",
+  "natives",
+  "os",
+  "pipe_wrap",
+  "signal_wrap",
+  "spawn_sync",
+  "stream_wrap",
+  "tcp_wrap",
+  "tls_wrap",
+  "tty_wrap",
+  "udp_wrap",
+  "url",
+  "util",
+  "uv",
+  "v8",
+  "zlib",
+]);
+
+const requestedProcessBindings = new Set<string>();
+
+export function getProcessBinding(name: string) {
+  if (!processBindingAllowList.has(name)) {
+    throw new Error(`No such module: ${name}`);
+  }
+  requestedProcessBindings.add(name);
+  return getBinding(name as BindingName);
+}
+
+/** Names of the bindings requested through `process.binding()` so far. */
+export function getRequestedProcessBindings(): string[] {
+  return [...requestedProcessBindings];
+}
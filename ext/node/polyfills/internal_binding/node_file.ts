@@ -31,6 +31,7 @@
 import { assert } from "ext:deno_node/_util/asserts.ts";
 import * as io from "ext:deno_io/12_io.js";
 import * as fs from "ext:deno_fs/30_fs.js";
+import { UV_ENOENT } from "ext:deno_node/internal_binding/uv.ts";
 
 /**
  * Write to the given file from the given buffer synchronously.
@@ -76,6 +77,35 @@ export function writeBuffer(
   }
 }
 
+/**
+ * Stat the given path, returning 0 for files, 1 for directories and a
+ * negative errno if the path can't be stat'ed.
+ *
+ * Implements InternalModuleStat in src/node_file.cc, which is still used by
+ * legacy packages through `process.binding("fs")`.
+ * See: https://github.com/nodejs/node/blob/e9ed113/src/node_file.cc#L1027
+ *
+ * @param path the path to stat
+ */
+export function internalModuleStat(path: string): number {
+  try {
+    return fs.statSync(path).isDirectory ? 1 : 0;
+  } catch {
+    return UV_ENOENT!;
+  }
+}
+
+/**
+ * Request object for asynchronous fs binding calls. Only exposed so that
+ * packages patching `process.binding("fs").FSReqCallback` don't crash.
+ */
+export class FSReqCallback {
+  oncomplete: ((...args: unknown[]) => void) | undefined;
+  context: unknown;
+
+  constructor(_bigint?: boolean) {}
+}
+
 function extractOsErrorNumberFromErrorMessage(e: unknown): number {
   const match = e instanceof Error
     ? e.message.match(/\(os error (\d+)\)/)
@@ -200,6 +200,13 @@ export class TCP extends ConnectionWrap {
   listen(backlog: number): number {
     this.#backlog = ceilPowOf2(backlog + 1);
 
+    if (this.#listener) {
+      // Adopted from another process, so already listening.
+      this.#accept();
+
+      return 0;
+    }
+
     const listenOptions = {
       hostname: this.#address!,
       port: this.#port!,
@@ -233,6 +240,20 @@ export class TCP extends ConnectionWrap {
     return 0;
   }
 
+  /**
+   * Wraps a listener that was bound elsewhere, e.g. one received from the
+   * cluster primary. Connections are accepted from it once `listen()` is
+   * called.
+   * @param listener The listener to wrap.
+   */
+  adoptListener(listener: Deno.Listener) {
+    const address = listener.addr as Deno.NetAddr;
+    this.#address = address.hostname;
+    this.#port = address.port;
+
+    this.#listener = listener;
+  }
+
   override ref() {
     if (this.#listener) {
       this.#listener.ref();
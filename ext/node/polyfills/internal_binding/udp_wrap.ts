@@ -23,7 +23,14 @@
 // TODO(petamoriken): enable prefer-primordials for node polyfills
 // deno-lint-ignore-file prefer-primordials
 
+import { core } from "ext:core/mod.js";
 import {
+  op_net_set_broadcast_udp,
+  op_net_set_membership_udp,
+  op_net_set_multicast_if_udp,
+  op_net_set_multicast_loop_udp,
+  op_net_set_multicast_ttl_udp,
+  op_net_set_ttl_udp,
   op_node_unstable_net_listen_udp,
   op_node_unstable_net_listen_unixpacket,
 } from "ext:core/ops";
@@ -42,6 +49,9 @@ import type { ErrnoException } from "ext:deno_node/internal/errors.ts";
 import { isIP } from "ext:deno_node/internal/net.ts";
 import * as net from "ext:deno_net/01_net.js";
 import { isLinux, isWindows } from "ext:deno_node/_util/os.ts";
+import { networkInterfaces } from "node:os";
+
+const { internalRidSymbol } = core;
 
 const DenoListenDatagram = net.createListenDatagram(
   op_node_unstable_net_listen_udp,
@@ -55,6 +65,39 @@ const AF_INET6 = 10;
 
 const UDP_DGRAM_MAXSIZE = 64 * 1024;
 
+function errorCode(e: unknown): number {
+  if (e instanceof Deno.errors.BadResource) {
+    return codeMap.get("EBADF")!;
+  }
+
+  return codeMap.get((e as { code?: string })?.code ?? "") ??
+    codeMap.get("EINVAL")!;
+}
+
+/**
+ * Returns the index of the interface that an IPv6 multicast option applies
+ * to. It is given by the scope of an address, which is an interface name or
+ * index, like in "::%eth1" or "::%2". Returns the empty string for the
+ * default interface and undefined for an unknown interface.
+ */
+function multicastInterfaceV6(interfaceAddress?: string): string | undefined {
+  const scope = interfaceAddress?.split("%")[1];
+
+  if (!scope) {
+    return "";
+  }
+
+  if (/^\d+$/.test(scope)) {
+    return scope;
+  }
+
+  const scopeid = networkInterfaces()[scope]?.find((address) =>
+    address.family === "IPv6" && address.scopeid
+  )?.scopeid;
+
+  return scopeid === undefined ? undefined : `${scopeid}`;
+}
+
 export class SendWrap extends AsyncWrap {
   list!: MessageType[];
   address!: string;
@@ -111,8 +154,8 @@ export class UDP extends HandleWrap {
     super(providerType.UDPWRAP);
   }
 
-  addMembership(_multicastAddress: string, _interfaceAddress?: string): number {
-    notImplemented("udp.UDP.prototype.addMembership");
+  addMembership(multicastAddress: string, interfaceAddress?: string): number {
+    return this.#setMembership(multicastAddress, interfaceAddress, true);
   }
 
   addSourceSpecificMembership(
@@ -195,10 +238,10 @@ export class UDP extends HandleWrap {
   }
 
   dropMembership(
-    _multicastAddress: string,
-    _interfaceAddress?: string,
+    multicastAddress: string,
+    interfaceAddress?: string,
   ): number {
-    notImplemented("udp.UDP.prototype.dropMembership");
+    return this.#setMembership(multicastAddress, interfaceAddress, false);
   }
 
   dropSourceSpecificMembership(
@@ -299,24 +342,44 @@ export class UDP extends HandleWrap {
     return this.#doSend(req, bufs, count, args, AF_INET6);
   }
 
-  setBroadcast(_bool: 0 | 1): number {
-    notImplemented("udp.UDP.prototype.setBroadcast");
+  setBroadcast(bool: 0 | 1): number {
+    return this.#setOption((rid) => op_net_set_broadcast_udp(rid, !!bool));
   }
 
-  setMulticastInterface(_interfaceAddress: string): number {
-    notImplemented("udp.UDP.prototype.setMulticastInterface");
+  setMulticastInterface(interfaceAddress: string): number {
+    const multicastInterface = this.#family === "IPv6"
+      ? multicastInterfaceV6(interfaceAddress)
+      : interfaceAddress;
+
+    if (multicastInterface === undefined) {
+      return codeMap.get("EINVAL")!;
+    }
+
+    return this.#setOption((rid) =>
+      op_net_set_multicast_if_udp(rid, multicastInterface)
+    );
   }
 
-  setMulticastLoopback(_bool: 0 | 1): number {
-    notImplemented("udp.UDP.prototype.setMulticastLoopback");
+  setMulticastLoopback(bool: 0 | 1): number {
+    return this.#setOption((rid) =>
+      op_net_set_multicast_loop_udp(rid, !!bool)
+    );
   }
 
-  setMulticastTTL(_ttl: number): number {
-    notImplemented("udp.UDP.prototype.setMulticastTTL");
+  setMulticastTTL(ttl: number): number {
+    if (ttl < 0 || ttl > 255) {
+      return codeMap.get("EINVAL")!;
+    }
+
+    return this.#setOption((rid) => op_net_set_multicast_ttl_udp(rid, ttl));
   }
 
-  setTTL(_ttl: number): number {
-    notImplemented("udp.UDP.prototype.setTTL");
+  setTTL(ttl: number): number {
+    if (ttl < 1 || ttl > 255) {
+      return codeMap.get("EINVAL")!;
+    }
+
+    return this.#setOption((rid) => op_net_set_ttl_udp(rid, ttl));
   }
 
   override unref() {
@@ -358,6 +421,44 @@ export class UDP extends HandleWrap {
     return 0;
   }
 
+  #setMembership(
+    multicastAddress: string,
+    interfaceAddress: string | undefined,
+    join: boolean,
+  ): number {
+    const multicastInterface = isIP(multicastAddress) === 6
+      ? multicastInterfaceV6(interfaceAddress)
+      : interfaceAddress ?? "";
+
+    if (multicastInterface === undefined) {
+      return codeMap.get("EINVAL")!;
+    }
+
+    return this.#setOption((rid) =>
+      op_net_set_membership_udp(
+        rid,
+        multicastAddress,
+        multicastInterface,
+        join,
+      )
+    );
+  }
+
+  /** Sets a socket option with `set`, which is given the resource id. */
+  #setOption(set: (rid: number) => void): number {
+    if (!this.#listener) {
+      return codeMap.get("EBADF")!;
+    }
+
+    try {
+      set(this.#listener[internalRidSymbol]);
+    } catch (e) {
+      return errorCode(e);
+    }
+
+    return 0;
+  }
+
   #doConnect(ip: string, port: number, family: number): number {
     this.#remoteAddress = ip;
     this.#remotePort = port;
@@ -376,18 +477,21 @@ export class UDP extends HandleWrap {
     _family: number,
   ): number {
     let hasCallback: boolean;
+    let hostname = this.#remoteAddress!;
+    let port = this.#remotePort!;
 
+    // Connected sockets are sent to without an address.
     if (args.length === 3) {
-      this.#remotePort = args[0] as number;
-      this.#remoteAddress = args[1] as string;
+      port = args[0] as number;
+      hostname = args[1] as string;
       hasCallback = args[2] as boolean;
     } else {
       hasCallback = args[0] as boolean;
     }
 
     const addr: Deno.NetAddr = {
-      hostname: this.#remoteAddress!,
-      port: this.#remotePort!,
+      hostname,
+      port,
       transport: "udp",
     };
 
@@ -477,6 +581,17 @@ export class UDP extends HandleWrap {
 
     nread ??= 0;
 
+    // Like the kernel does for connected sockets, drop the messages that are
+    // not from the peer.
+    if (
+      remoteAddr && this.#remoteAddress !== undefined &&
+      (remoteAddr.hostname !== this.#remoteAddress ||
+        remoteAddr.port !== this.#remotePort)
+    ) {
+      this.#receive();
+      return;
+    }
+
     const rinfo = remoteAddr
       ? {
         address: remoteAddr.hostname,
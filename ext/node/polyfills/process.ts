@@ -64,10 +64,9 @@ let stdin, stdout, stderr;
 
 export { stderr, stdin, stdout };
 
-import { getBinding } from "ext:deno_node/internal_binding/mod.ts";
+import { getProcessBinding } from "ext:deno_node/internal_binding/mod.ts";
 import * as constants from "ext:deno_node/internal_binding/constants.ts";
 import * as uv from "ext:deno_node/internal_binding/uv.ts";
-import type { BindingName } from "ext:deno_node/internal_binding/mod.ts";
 import { buildAllowedFlags } from "ext:deno_node/internal/process/per_thread.mjs";
 
 const notImplementedEvents = [
@@ -671,8 +670,9 @@ Process.prototype.versions = versions;
 /** https://nodejs.org/api/process.html#process_process_emitwarning_warning_options */
 Process.prototype.emitWarning = emitWarning;
 
-Process.prototype.binding = (name: BindingName) => {
-  return getBinding(name);
+/** Only the bindings legacy packages still rely on are exposed. */
+Process.prototype.binding = (name: string) => {
+  return getProcessBinding(name);
 };
 
 /** https://nodejs.org/api/process.html#processumaskmask */
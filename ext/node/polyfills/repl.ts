@@ -1,13 +1,708 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
-import { notImplemented } from "ext:deno_node/_utils.ts";
+// TODO(petamoriken): enable prefer-primordials for node polyfills
+// deno-lint-ignore-file prefer-primordials no-explicit-any
 
-export class REPLServer {
-  constructor() {
-    notImplemented("REPLServer.prototype.constructor");
+import { Console } from "node:console";
+import fs from "node:fs";
+import { createRequire } from "node:module";
+import path from "node:path";
+import process from "node:process";
+import { Interface } from "node:readline";
+import { inspect } from "node:util";
+import vm from "node:vm";
+import { validateFunction } from "ext:deno_node/internal/validators.mjs";
+
+export const REPL_MODE_SLOPPY = Symbol("repl-sloppy");
+export const REPL_MODE_STRICT = Symbol("repl-strict");
+
+/** Wraps the syntax error of input that is incomplete so far, which makes
+ * the REPL wait for more lines instead of reporting it. */
+export class Recoverable extends SyntaxError {
+  err: Error;
+
+  constructor(err: Error) {
+    super();
+    this.err = err;
+  }
+}
+
+export const writer: any = (obj: unknown) => inspect(obj, writer.options);
+writer.options = { ...inspect.defaultOptions, showProxy: true };
+
+const kBufferedCommand = Symbol("bufferedCommand");
+
+const identifierRegExp = /^[A-Za-z_$][\w$]*$/;
+
+const closingBrackets: Record<string, string> = {
+  "(": ")",
+  "[": "]",
+  "{": "}",
+  "${": "}",
+};
+
+// the characters after which a `/` starts a regular expression literal
+const regExpPrecedingRegExp = /^$|[(,=:[!&|?{};+\-*%<>~^]/;
+
+/** Whether `code` ends inside brackets, a template literal or a block
+ * comment, so that a syntax error in it is recoverable. Like the validator
+ * of `deno repl`, this looks at the tokens of the input instead of at the
+ * messages of syntax errors, and leaves mismatched brackets to V8. */
+function isIncomplete(code: string) {
+  // open brackets, and "`" for the template literals they are in
+  const stack: string[] = [];
+  let lastToken = "";
+  let i = 0;
+  while (i < code.length) {
+    const char = code[i];
+    if (stack[stack.length - 1] === "`") {
+      if (char === "\\") {
+        i += 2;
+      } else if (char === "`") {
+        stack.pop();
+        lastToken = char;
+        i++;
+      } else if (char === "$" && code[i + 1] === "{") {
+        stack.push("${");
+        lastToken = "{";
+        i += 2;
+      } else {
+        i++;
+      }
+      continue;
+    }
+    if (char === "/" && code[i + 1] === "/") {
+      const end = code.indexOf("\n", i);
+      if (end === -1) {
+        break;
+      }
+      i = end;
+      continue;
+    }
+    if (char === "/" && code[i + 1] === "*") {
+      const end = code.indexOf("*/", i + 2);
+      if (end === -1) {
+        return true;
+      }
+      i = end + 2;
+      continue;
+    }
+    if (
+      char === "'" || char === '"' ||
+      (char === "/" && regExpPrecedingRegExp.test(lastToken))
+    ) {
+      // strings and regular expressions end with the line
+      let inClass = false;
+      i++;
+      while (i < code.length && code[i] !== "\n") {
+        if (code[i] === "\\") {
+          i++;
+        } else if (char === "/" && code[i] === "[") {
+          inClass = true;
+        } else if (char === "/" && code[i] === "]") {
+          inClass = false;
+        } else if (code[i] === char && !inClass) {
+          break;
+        }
+        i++;
+      }
+      lastToken = char;
+      i++;
+      continue;
+    }
+    if (char === "(" || char === "[" || char === "{" || char === "`") {
+      stack.push(char);
+    } else if (char === ")" || char === "]" || char === "}") {
+      const open = stack.pop();
+      if (open === undefined || closingBrackets[open] !== char) {
+        return false;
+      }
+    }
+    if (!/\s/.test(char)) {
+      lastToken = char;
+    }
+    i++;
+  }
+  return stack.length > 0;
+}
+
+/** Compiles input that uses `await` as the body of an async function, as
+ * an expression first so that its value is the result. */
+function compileTopLevelAwait(code: string) {
+  const expression = code.trim().replace(/;+$/, "");
+  try {
+    return new vm.Script(`(async () => (${expression}\n))()`);
+  } catch {
+    return new vm.Script(`(async () => {${code}})()`);
+  }
+}
+
+function defaultEval(
+  this: any,
+  code: string,
+  context: any,
+  _file: string,
+  cb: (err: unknown, result?: unknown) => void,
+) {
+  let script;
+  let isAsync = false;
+  // Input like `{ a: 1 }` is more likely an object literal than a block.
+  if (/^\s*\{/.test(code) && !/;\s*$/.test(code)) {
+    try {
+      script = new vm.Script(`(${code.trim()}\n)`);
+    } catch {
+      // not an object literal
+    }
+  }
+  if (!script) {
+    try {
+      script = new vm.Script(
+        this.replMode === REPL_MODE_STRICT ? `"use strict"; ${code}` : code,
+      );
+    } catch (e) {
+      if (e instanceof SyntaxError && isIncomplete(code)) {
+        return cb(new Recoverable(e as Error));
+      }
+      if (!/\bawait\b/.test(code)) {
+        return cb(e);
+      }
+      try {
+        script = compileTopLevelAwait(code);
+        isAsync = true;
+      } catch {
+        return cb(e);
+      }
+    }
+  }
+
+  let result;
+  try {
+    result = this.useGlobal
+      ? script.runInThisContext()
+      : script.runInContext(context);
+  } catch (e) {
+    return cb(e);
+  }
+  if (isAsync) {
+    result.then((value: unknown) => cb(null, value), (e: unknown) => cb(e));
+  } else {
+    cb(null, result);
+  }
+}
+
+/** Collects the names of the properties of `obj` and its prototypes that
+ * can be completed after a dot. */
+function getPropertyNames(obj: unknown) {
+  const names = new Set<string>();
+  if (obj === null || obj === undefined) {
+    return names;
+  }
+  let current: any = Object(obj);
+  while (current !== null) {
+    for (const name of Object.getOwnPropertyNames(current)) {
+      if (identifierRegExp.test(name)) {
+        names.add(name);
+      }
+    }
+    current = Object.getPrototypeOf(current);
+  }
+  return names;
+}
+
+const keywords = [
+  "async",
+  "await",
+  "break",
+  "case",
+  "catch",
+  "class",
+  "const",
+  "continue",
+  "debugger",
+  "default",
+  "delete",
+  "do",
+  "else",
+  "export",
+  "false",
+  "finally",
+  "for",
+  "function",
+  "if",
+  "import",
+  "in",
+  "instanceof",
+  "let",
+  "new",
+  "null",
+  "return",
+  "switch",
+  "this",
+  "throw",
+  "true",
+  "try",
+  "typeof",
+  "undefined",
+  "var",
+  "void",
+  "while",
+  "yield",
+];
+
+/** A REPL on the streams of the options, for programs that embed one.
+ *
+ * Unlike `deno repl`, which runs in the CLI and evaluates through the
+ * inspector, this evaluates JavaScript with `node:vm`, like Node does. Input
+ * isn't transpiled, so TypeScript and JSX aren't supported, imports have to
+ * use `await import()` or `require()`, and the history is only the one of
+ * `setupHistory()`. Whether input is incomplete is decided like `deno repl`
+ * does, by its open brackets, template literals and comments. */
+export function REPLServer(
+  this: any,
+  prompt?: any,
+  stream?: any,
+  eval_?: any,
+  useGlobal?: boolean,
+  ignoreUndefined?: boolean,
+  replMode?: symbol,
+) {
+  if (!(this instanceof REPLServer)) {
+    return new (REPLServer as any)(
+      prompt,
+      stream,
+      eval_,
+      useGlobal,
+      ignoreUndefined,
+      replMode,
+    );
+  }
+
+  let options: any;
+  if (prompt !== null && typeof prompt === "object") {
+    options = { ...prompt };
+    stream = options.stream || options.socket;
+    eval_ = options.eval;
+    useGlobal = options.useGlobal;
+    ignoreUndefined = options.ignoreUndefined;
+    prompt = options.prompt;
+    replMode = options.replMode;
+  } else {
+    options = {};
+  }
+
+  if (!options.input && !options.output) {
+    stream ??= process;
+    options.input = stream.stdin ?? stream;
+    options.output = stream.stdout ?? stream;
+  }
+  if (options.terminal === undefined) {
+    options.terminal = options.output.isTTY;
+  }
+  options.terminal = !!options.terminal;
+  if (options.useColors === undefined) {
+    options.useColors = options.terminal;
+  }
+
+  this.useColors = !!options.useColors;
+  this.useGlobal = !!useGlobal;
+  this.ignoreUndefined = !!ignoreUndefined;
+  this.replMode = replMode || REPL_MODE_SLOPPY;
+  this.eval = eval_ || defaultEval;
+  this.writer = options.writer || writer;
+  if (this.writer === writer) {
+    writer.options.colors = this.useColors;
+  }
+  this.commands = Object.create(null);
+  defineDefaultCommands(this);
+  this.lines = [];
+  this[kBufferedCommand] = "";
+  this._initialPrompt = prompt === undefined ? "> " : prompt;
+
+  Interface.call(this, {
+    input: options.input,
+    output: options.output,
+    completer: options.completer || this.complete.bind(this),
+    terminal: options.terminal,
+    historySize: options.historySize,
+    prompt: this._initialPrompt,
+  });
+
+  this.resetContext();
+
+  this.on("close", () => this.emit("exit"));
+
+  let sawSIGINT = false;
+  this.on("SIGINT", () => {
+    const empty = this.line.length === 0;
+    this.clearLine();
+    const wasBuffering = this[kBufferedCommand].length > 0;
+    this.clearBufferedCommand();
+    if (!wasBuffering && empty) {
+      if (sawSIGINT) {
+        this.close();
+        sawSIGINT = false;
+        return;
+      }
+      this.output.write(
+        "(To exit, press Ctrl+C again or Ctrl+D or type .exit)\n",
+      );
+      sawSIGINT = true;
+    } else {
+      sawSIGINT = false;
+    }
+    this.displayPrompt();
+  });
+
+  this.on("line", (cmd: string) => {
+    cmd ||= "";
+    sawSIGINT = false;
+
+    const trimmedCmd = cmd.trim();
+    if (
+      trimmedCmd[0] === "." && trimmedCmd[1] !== "." &&
+      Number.isNaN(Number.parseFloat(trimmedCmd))
+    ) {
+      const matches = /^\.([^\s]+)\s*(.*)$/.exec(trimmedCmd);
+      if (matches && this.parseREPLKeyword(matches[1], matches[2])) {
+        return;
+      }
+      if (!this[kBufferedCommand]) {
+        this.output.write("Invalid REPL keyword\n");
+        this.displayPrompt();
+        return;
+      }
+    }
+
+    const evalCmd = this[kBufferedCommand] + cmd + "\n";
+    if (evalCmd.trim() === "") {
+      this.displayPrompt();
+      return;
+    }
+    const finish = (e: unknown, ret?: unknown) => {
+      if (e instanceof Recoverable) {
+        this[kBufferedCommand] = evalCmd;
+        this.displayPrompt();
+        return;
+      }
+      this.clearBufferedCommand();
+      if (e) {
+        this._handleError(e);
+      } else {
+        this.lines.push(...evalCmd.trimEnd().split("\n"));
+        if (!this.ignoreUndefined || ret !== undefined) {
+          if (!this.underscoreAssigned) {
+            this.last = ret;
+          }
+          this.output.write(this.writer(ret) + "\n");
+        }
+      }
+      if (!this.closed) {
+        this.displayPrompt();
+      }
+    };
+    this.eval(evalCmd, this.context, "REPL", finish);
+  });
+
+  this.displayPrompt();
+}
+
+Object.setPrototypeOf(REPLServer.prototype, Interface.prototype);
+Object.setPrototypeOf(REPLServer, Interface);
+
+REPLServer.prototype.createContext = function createContext() {
+  let context;
+  if (this.useGlobal) {
+    context = globalThis;
+  } else {
+    context = vm.createContext();
+    for (const name of Object.getOwnPropertyNames(globalThis)) {
+      if (name === "console" || name === "global") {
+        continue;
+      }
+      if (context[name] === undefined) {
+        Object.defineProperty(
+          context,
+          name,
+          Object.getOwnPropertyDescriptor(globalThis, name)!,
+        );
+      }
+    }
+    context.global = context;
+    Object.defineProperty(context, "console", {
+      configurable: true,
+      writable: true,
+      value: new Console(this.output),
+    });
+  }
+
+  Object.defineProperty(context, "require", {
+    configurable: true,
+    writable: true,
+    value: createRequire(path.join(process.cwd(), "repl")),
+  });
+  return context;
+};
+
+REPLServer.prototype.resetContext = function resetContext() {
+  this.context = this.createContext();
+  this.underscoreAssigned = false;
+  this.last = undefined;
+  this.underscoreErrAssigned = false;
+  this.lastError = undefined;
+  this.lines = [];
+
+  // `_` and `_error` hold the last result and error until assigned to.
+  Object.defineProperty(this.context, "_", {
+    configurable: true,
+    get: () => this.last,
+    set: (value) => {
+      this.last = value;
+      if (!this.underscoreAssigned) {
+        this.underscoreAssigned = true;
+        this.output.write("Expression assignment to _ now disabled.\n");
+      }
+    },
+  });
+  Object.defineProperty(this.context, "_error", {
+    configurable: true,
+    get: () => this.lastError,
+    set: (value) => {
+      this.lastError = value;
+      if (!this.underscoreErrAssigned) {
+        this.underscoreErrAssigned = true;
+        this.output.write(
+          "Expression assignment to _error now disabled.\n",
+        );
+      }
+    },
+  });
+
+  this.emit("reset", this.context);
+};
+
+REPLServer.prototype._handleError = function _handleError(e: unknown) {
+  if (!this.underscoreErrAssigned) {
+    this.lastError = e;
+  }
+  const message = e instanceof Error && e.stack
+    ? e.stack
+    : inspect(e, { colors: this.useColors });
+  this.output.write(`Uncaught ${message}\n`);
+};
+
+REPLServer.prototype.setPrompt = function setPrompt(prompt: string) {
+  this._initialPrompt = prompt;
+  Interface.prototype.setPrompt.call(this, prompt);
+};
+
+REPLServer.prototype.displayPrompt = function displayPrompt(
+  preserveCursor?: boolean,
+) {
+  const prompt = this[kBufferedCommand].length > 0
+    ? "... "
+    : this._initialPrompt;
+  Interface.prototype.setPrompt.call(this, prompt);
+  this.prompt(preserveCursor);
+};
+
+REPLServer.prototype.clearBufferedCommand = function clearBufferedCommand() {
+  this[kBufferedCommand] = "";
+};
+
+REPLServer.prototype.defineCommand = function defineCommand(
+  keyword: string,
+  cmd: any,
+) {
+  if (typeof cmd === "function") {
+    cmd = { action: cmd };
+  } else {
+    validateFunction(cmd.action, "cmd.action");
+  }
+  this.commands[keyword] = cmd;
+};
+
+REPLServer.prototype.parseREPLKeyword = function parseREPLKeyword(
+  keyword: string,
+  rest: string,
+) {
+  const cmd = this.commands[keyword];
+  if (cmd) {
+    cmd.action.call(this, rest);
+    return true;
+  }
+  return false;
+};
+
+/** Completes REPL commands, global names and the properties of identifier
+ * chains like `process.env.`. */
+REPLServer.prototype.complete = function complete(
+  line: string,
+  callback: (err: Error | null, result: [string[], string]) => void,
+) {
+  const commandMatch = /^\s*\.([\w$-]*)$/.exec(line);
+  if (commandMatch) {
+    const completions = Object.keys(this.commands)
+      .filter((name) => name.startsWith(commandMatch[1]))
+      .sort()
+      .map((name) => `.${name}`);
+    return callback(null, [completions, `.${commandMatch[1]}`]);
+  }
+
+  const match = /(?:^|[^\w$.])((?:[\w$]+\.)*)([\w$]*)$/.exec(line);
+  const expr = match?.[1].slice(0, -1) ?? "";
+  const filter = match?.[2] ?? "";
+  let names;
+  try {
+    if (expr) {
+      names = getPropertyNames(this._evaluateSilently(expr));
+    } else {
+      names = getPropertyNames(this._evaluateSilently("globalThis"));
+      for (const keyword of keywords) {
+        names.add(keyword);
+      }
+    }
+  } catch {
+    names = new Set<string>();
+  }
+
+  const prefix = expr ? `${expr}.` : "";
+  const completions = [...names]
+    .filter((name) => name.startsWith(filter))
+    .sort()
+    .map((name) => prefix + name);
+  callback(null, [completions, prefix + filter]);
+};
+
+REPLServer.prototype._evaluateSilently = function _evaluateSilently(
+  code: string,
+) {
+  const script = new vm.Script(code);
+  return this.useGlobal
+    ? script.runInThisContext()
+    : script.runInContext(this.context);
+};
+
+/** Loads the history from `historyPath`, and saves it there after every
+ * line. */
+REPLServer.prototype.setupHistory = function setupHistory(
+  historyPath: string,
+  cb: (err: Error | null, repl?: any) => void,
+) {
+  try {
+    const data = fs.readFileSync(historyPath, "utf8");
+    this.history = data.split(/[\n\r]+/)
+      .filter((line: string) => line.trim() !== "")
+      .slice(0, this.historySize);
+  } catch (err: any) {
+    if (err.code !== "ENOENT") {
+      process.nextTick(cb, err);
+      return;
+    }
   }
+  this.on("line", () => {
+    try {
+      fs.writeFileSync(historyPath, this.history.join("\n") + "\n");
+    } catch {
+      // not being able to save the history shouldn't end the session
+    }
+  });
+  process.nextTick(cb, null, this);
+};
+
+function defineDefaultCommands(repl: any) {
+  repl.defineCommand("break", {
+    help: "Sometimes you get stuck, this gets you out",
+    action(this: any) {
+      this.clearBufferedCommand();
+      this.displayPrompt();
+    },
+  });
+  repl.defineCommand("clear", {
+    help: repl.useGlobal
+      ? "Alias for .break"
+      : "Break, and also clear the local context",
+    action(this: any) {
+      this.clearBufferedCommand();
+      if (!this.useGlobal) {
+        this.output.write("Clearing context...\n");
+        this.resetContext();
+      }
+      this.displayPrompt();
+    },
+  });
+  repl.defineCommand("exit", {
+    help: "Exit the REPL",
+    action(this: any) {
+      this.close();
+    },
+  });
+  repl.defineCommand("help", {
+    help: "Print this help message",
+    action(this: any) {
+      const names = Object.keys(this.commands).sort();
+      const longestNameLength = Math.max(
+        ...names.map((name) => name.length),
+      );
+      for (const name of names) {
+        const cmd = this.commands[name];
+        const spaces = " ".repeat(longestNameLength - name.length + 3);
+        this.output.write(`.${name}${cmd.help ? spaces + cmd.help : ""}\n`);
+      }
+      this.output.write(
+        "\nPress Ctrl+C to abort current expression, Ctrl+D to exit the REPL\n",
+      );
+      this.displayPrompt();
+    },
+  });
+  repl.defineCommand("save", {
+    help: "Save all evaluated commands in this REPL session to a file",
+    action(this: any, file: string) {
+      try {
+        fs.writeFileSync(file, this.lines.join("\n"));
+        this.output.write(`Session saved to: ${file}\n`);
+      } catch {
+        this.output.write(`Failed to save: ${file}\n`);
+      }
+      this.displayPrompt();
+    },
+  });
+  repl.defineCommand("load", {
+    help: "Load JS from a file into the REPL session",
+    action(this: any, file: string) {
+      let data;
+      try {
+        data = fs.readFileSync(file, "utf8");
+      } catch {
+        this.output.write(`Failed to load: ${file}\n`);
+        this.displayPrompt();
+        return;
+      }
+      // evaluated line by line like typed input
+      this.write(`${data}\n`);
+    },
+  });
+}
+
+/** Starts a REPL, see `REPLServer` for the arguments. */
+export function start(
+  prompt?: any,
+  source?: any,
+  eval_?: any,
+  useGlobal?: boolean,
+  ignoreUndefined?: boolean,
+  replMode?: symbol,
+) {
+  return new (REPLServer as any)(
+    prompt,
+    source,
+    eval_,
+    useGlobal,
+    ignoreUndefined,
+    replMode,
+  );
 }
+
 export const builtinModules = [
   "assert",
   "async_hooks",
@@ -53,12 +748,13 @@ export const builtinModules = [
   "zlib",
 ];
 export const _builtinLibs = builtinModules;
-export function start() {
-  notImplemented("repl.start");
-}
 export default {
   REPLServer,
+  REPL_MODE_SLOPPY,
+  REPL_MODE_STRICT,
+  Recoverable,
   builtinModules,
   _builtinLibs,
   start,
+  writer,
 };
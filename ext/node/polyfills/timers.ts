@@ -18,6 +18,10 @@ import {
 import { validateFunction } from "ext:deno_node/internal/validators.mjs";
 import { promisify } from "ext:deno_node/internal/util.mjs";
 export { setUnrefTimeout } from "ext:deno_node/internal/timers.mjs";
+import {
+  emitDestroy,
+  symbols,
+} from "ext:deno_node/internal/async_hooks.ts";
 import * as timers from "ext:deno_web/02_timers.js";
 
 const clearTimeout_ = timers.clearTimeout;
@@ -49,6 +53,7 @@ export function clearTimeout(timeout?: Timeout | number) {
   if (timer) {
     timeout._destroyed = true;
     MapPrototypeDelete(activeTimers, id);
+    emitDestroy(timer[symbols.async_id_symbol]);
   }
   clearTimeout_(id);
 }
@@ -69,6 +74,7 @@ export function clearInterval(timeout?: Timeout | number | string) {
   if (timer) {
     timeout._destroyed = true;
     MapPrototypeDelete(activeTimers, id);
+    emitDestroy(timer[symbols.async_id_symbol]);
   }
   clearInterval_(id);
 }
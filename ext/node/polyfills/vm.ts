@@ -4,12 +4,35 @@
 
 import { notImplemented } from "ext:deno_node/_utils.ts";
 import {
+  op_vm_compile_function,
   op_vm_create_context,
+  op_vm_create_module,
   op_vm_create_script,
   op_vm_is_context,
+  op_vm_module_error,
+  op_vm_module_evaluate,
+  op_vm_module_instantiate,
+  op_vm_module_link,
+  op_vm_module_namespace,
+  op_vm_module_requests,
+  op_vm_module_status,
   op_vm_script_run_in_context,
   op_vm_script_run_in_this_context,
 } from "ext:core/ops";
+import {
+  ERR_INVALID_ARG_TYPE,
+  ERR_VM_MODULE_ALREADY_LINKED,
+  ERR_VM_MODULE_DIFFERENT_CONTEXT,
+  ERR_VM_MODULE_LINKING_ERRORED,
+  ERR_VM_MODULE_NOT_MODULE,
+  ERR_VM_MODULE_STATUS,
+} from "ext:deno_node/internal/errors.ts";
+import {
+  validateArray,
+  validateFunction,
+  validateObject,
+  validateString,
+} from "ext:deno_node/internal/validators.mjs";
 
 export class Script {
   #inner;
@@ -79,8 +102,169 @@ export function isContext(maybeContext: any) {
   return op_vm_is_context(maybeContext);
 }
 
-export function compileFunction(_code: string, _params: any, _options: any) {
-  notImplemented("compileFunction");
+export function compileFunction(
+  code: string,
+  params: string[] = [],
+  options: any = {},
+) {
+  validateString(code, "code");
+  validateArray(params, "params");
+  validateObject(options, "options");
+  const { filename = "", parsingContext } = options;
+  validateString(filename, "options.filename");
+  if (parsingContext !== undefined && !isContext(parsingContext)) {
+    throw new ERR_INVALID_ARG_TYPE(
+      "options.parsingContext",
+      "Context",
+      parsingContext,
+    );
+  }
+  for (const param of params) {
+    validateString(param, "params");
+  }
+
+  return op_vm_compile_function(code, filename, params, parsingContext);
+}
+
+// The statuses of `v8::Module`, in the order of `v8::ModuleStatus`.
+const kModuleStatuses = [
+  "unlinked",
+  "linking",
+  "linked",
+  "evaluating",
+  "evaluated",
+  "errored",
+];
+let globalModuleId = 0;
+
+export class Module {
+  #inner;
+  #identifier: string;
+  #context: any;
+  #linking = false;
+  #linkingError: unknown;
+  #dependencySpecifiers: string[];
+
+  constructor(sourceText: string, options: any = {}) {
+    if (new.target === Module) {
+      throw new TypeError("Module is not a constructor");
+    }
+    validateString(sourceText, "sourceText");
+    validateObject(options, "options");
+    const {
+      context,
+      identifier = `vm:module(${globalModuleId++})`,
+    } = options;
+    validateString(identifier, "options.identifier");
+    if (context !== undefined && !isContext(context)) {
+      throw new ERR_INVALID_ARG_TYPE("options.context", "vm.Context", context);
+    }
+
+    this.#inner = op_vm_create_module(sourceText, identifier, context);
+    this.#identifier = identifier;
+    this.#context = context;
+    this.#dependencySpecifiers = Object.freeze(
+      op_vm_module_requests(this.#inner),
+    );
+  }
+
+  get identifier() {
+    return this.#identifier;
+  }
+
+  get context() {
+    return this.#context;
+  }
+
+  get dependencySpecifiers() {
+    return this.#dependencySpecifiers;
+  }
+
+  get status() {
+    if (this.#linking) {
+      return "linking";
+    }
+    if (this.#linkingError !== undefined) {
+      return "errored";
+    }
+    return kModuleStatuses[op_vm_module_status(this.#inner)];
+  }
+
+  get namespace() {
+    const status = this.status;
+    if (status === "unlinked" || status === "linking") {
+      throw new ERR_VM_MODULE_STATUS("must not be unlinked or linking");
+    }
+    return op_vm_module_namespace(this.#inner);
+  }
+
+  get error() {
+    if (this.status !== "errored") {
+      throw new ERR_VM_MODULE_STATUS("must be errored");
+    }
+    return this.#linkingError ?? op_vm_module_error(this.#inner);
+  }
+
+  async link(linker: any) {
+    validateFunction(linker, "linker");
+    if (this.status === "linked") {
+      throw new ERR_VM_MODULE_ALREADY_LINKED();
+    }
+    if (this.status !== "unlinked") {
+      throw new ERR_VM_MODULE_STATUS("must be unlinked");
+    }
+    await this.#link(linker);
+    op_vm_module_instantiate(this.#inner);
+  }
+
+  // Links the imports of this module and, recursively, of the modules they
+  // resolve to. The whole graph is instantiated with the root module.
+  async #link(linker: any) {
+    this.#linking = true;
+    try {
+      for (const specifier of this.#dependencySpecifiers) {
+        const module = await linker(specifier, this, { attributes: {} });
+        if (!(module instanceof Module)) {
+          throw new ERR_VM_MODULE_NOT_MODULE();
+        }
+        if (module.context !== this.context) {
+          throw new ERR_VM_MODULE_DIFFERENT_CONTEXT();
+        }
+        if (module.#linkingError !== undefined) {
+          throw new ERR_VM_MODULE_LINKING_ERRORED();
+        }
+        if (module.status === "unlinked") {
+          await module.#link(linker);
+        }
+        op_vm_module_link(this.#inner, specifier, module.#inner);
+      }
+    } catch (error) {
+      this.#linkingError = error;
+      throw error;
+    } finally {
+      this.#linking = false;
+    }
+  }
+
+  async evaluate(options: any = {}) {
+    validateObject(options, "options");
+    const status = this.status;
+    if (
+      status !== "linked" && status !== "evaluating" &&
+      status !== "evaluated" && status !== "errored"
+    ) {
+      throw new ERR_VM_MODULE_STATUS(
+        "must be one of linked, evaluating, evaluated, or errored",
+      );
+    }
+    await op_vm_module_evaluate(this.#inner);
+  }
+}
+
+export class SourceTextModule extends Module {
+  constructor(sourceText: string, options: any = {}) {
+    super(sourceText, options);
+  }
 }
 
 export function measureMemory(_options: any) {
@@ -97,4 +281,6 @@ export default {
   isContext,
   compileFunction,
   measureMemory,
+  Module,
+  SourceTextModule,
 };
@@ -13,6 +13,8 @@ import {
 } from "ext:core/ops";
 import {
   deserializeJsMessageData,
+  isMarkedAsUntransferable,
+  markAsUntransferable,
   MessageChannel,
   MessagePort,
   MessagePortIdSymbol,
@@ -28,11 +30,15 @@ import { notImplemented } from "ext:deno_node/_utils.ts";
 import { EventEmitter } from "node:events";
 import { BroadcastChannel } from "ext:deno_broadcast_channel/01_broadcast_channel.js";
 import process from "node:process";
+import { Readable, Writable } from "node:stream";
+import { Console } from "node:console";
 
 const { JSONParse, JSONStringify, ObjectPrototypeIsPrototypeOf } = primordials;
 const {
+  ArrayPrototypeConcat,
   Error,
   ObjectHasOwn,
+  PromisePrototypeThen,
   PromiseResolve,
   SafeSet,
   Symbol,
@@ -81,6 +87,9 @@ class NodeWorker extends EventEmitter {
   #refCount = 1;
   #messagePromise = undefined;
   #controlPromise = undefined;
+  #messagesDone: Promise<void>;
+  // Whether the output of the worker is sent to `stdout` and `stderr`.
+  #interceptsStdio = false;
   // "RUNNING" | "CLOSED" | "TERMINATED"
   // "TERMINATED" means that any controls or messages received will be
   // discarded. "CLOSED" means that we have received a control
@@ -99,6 +108,10 @@ class NodeWorker extends EventEmitter {
     codeRangeSizeMb: -1,
     stackSizeMb: 4,
   };
+  // https://nodejs.org/api/worker_threads.html#workerstdout
+  readonly stdout = new Readable({ read() {} });
+  // https://nodejs.org/api/worker_threads.html#workerstderr
+  readonly stderr = new Readable({ read() {} });
 
   constructor(specifier: URL | string, options?: WorkerOptions) {
     super();
@@ -137,11 +150,26 @@ class NodeWorker extends EventEmitter {
     if (options?.env) {
       env_ = JSONParse(JSONStringify(options?.env));
     }
+    // The output of the worker is only sent to the parent if it asked for
+    // it, otherwise the worker writes to the process' stdout and stderr.
+    let stdio = undefined;
+    let transferList = options?.transferList ?? [];
+    if (options?.stdout || options?.stderr) {
+      const { port1, port2 } = new MessageChannel();
+      stdio = {
+        port: port2,
+        stdout: !!options.stdout,
+        stderr: !!options.stderr,
+      };
+      transferList = ArrayPrototypeConcat(transferList, [port2]);
+      this.#pipeStdio(port1);
+    }
     const serializedWorkerMetadata = serializeJsMessageData({
       workerData: options?.workerData,
       environmentData: environmentData,
       env: env_,
-    }, options?.transferList ?? []);
+      stdio,
+    }, transferList);
     const id = op_create_worker(
       {
         // deno-lint-ignore prefer-primordials
@@ -158,9 +186,22 @@ class NodeWorker extends EventEmitter {
     this.#id = id;
     this.threadId = id;
     this.#pollControl();
-    this.#pollMessages();
+    this.#messagesDone = this.#pollMessages();
     // https://nodejs.org/api/worker_threads.html#event-online
-    this.emit("online");
+    process.nextTick(() => this.emit("online"));
+  }
+
+  #pipeStdio(port: MessagePort) {
+    this.#interceptsStdio = true;
+    webMessagePortToNodeMessagePort(port);
+    port.on("message", ({ name, chunk }) => {
+      this[name as "stdout" | "stderr"].push(chunk);
+    });
+    // The worker's end of the port is closed when it exits.
+    port.on("close", () => {
+      this.stdout.push(null);
+      this.stderr.push(null);
+    });
   }
 
   [privateWorkerRef](ref) {
@@ -207,7 +248,10 @@ class NodeWorker extends EventEmitter {
       switch (type) {
         case 1: { // TerminalError
           this.#status = "CLOSED";
-        } /* falls through */
+          this.#handleError(data);
+          this.#exit(1);
+          return;
+        }
         case 2: { // Error
           this.#handleError(data);
           break;
@@ -215,6 +259,7 @@ class NodeWorker extends EventEmitter {
         case 3: { // Close
           debugWT(`Host got "close" message from worker: ${this.#name}`);
           this.#status = "CLOSED";
+          this.#exit(0);
           return;
         }
         default: {
@@ -243,10 +288,26 @@ class NodeWorker extends EventEmitter {
         this.emit("messageerror", err);
         return;
       }
+      patchMessagePortIfFound(message);
       this.emit("message", message);
     }
   };
 
+  // https://nodejs.org/api/worker_threads.html#event-exit
+  #exit(code: number) {
+    // Messages the worker sent before it closed are emitted first.
+    PromisePrototypeThen(this.#messagesDone, () => {
+      if (this.#status === "TERMINATED") {
+        return;
+      }
+      if (!this.#interceptsStdio) {
+        this.stdout.push(null);
+        this.stderr.push(null);
+      }
+      this.emit("exit", code);
+    });
+  }
+
   postMessage(message, transferOrOptions = {}) {
     const prefix = "Failed to execute 'postMessage' on 'MessagePort'";
     webidl.requiredArguments(arguments.length, 1, prefix);
@@ -366,6 +427,9 @@ internals.__initWorkerThreads = (
       if (env) {
         process.env = env;
       }
+      if (metadata.stdio) {
+        interceptStdio(metadata.stdio);
+      }
     }
     defaultExport.workerData = workerData;
     defaultExport.parentPort = parentPort;
@@ -400,9 +464,13 @@ internals.__initWorkerThreads = (
 
     parentPort.once = function (this: ParentPort, name, listener) {
       // deno-lint-ignore no-explicit-any
-      const _listener = (ev: any) => listener(ev.data);
+      const _listener = (ev: any) => {
+        const message = ev.data;
+        patchMessagePortIfFound(message);
+        return listener(message);
+      };
       listeners.set(listener, _listener);
-      this.addEventListener(name, _listener);
+      this.addEventListener(name, _listener, { once: true });
       return this;
     };
 
@@ -428,6 +496,33 @@ internals.__initWorkerThreads = (
   }
 };
 
+/** Sends what the worker writes to stdout and stderr to the parent, where it
+ * is read from `worker.stdout` and `worker.stderr`. */
+function interceptStdio(
+  { port, stdout, stderr }: {
+    port: MessagePort;
+    stdout: boolean;
+    stderr: boolean;
+  },
+) {
+  const createStdioStream = (name: string) =>
+    new Writable({
+      write(chunk, _encoding, cb) {
+        port.postMessage({ name, chunk });
+        cb();
+      },
+    });
+
+  if (stdout) {
+    process.stdout = createStdioStream("stdout");
+  }
+  if (stderr) {
+    process.stderr = createStdioStream("stderr");
+  }
+  // `console` writes to the file descriptors directly otherwise.
+  globalThis.console = new Console(process.stdout, process.stderr);
+}
+
 export function getEnvironmentData(key: unknown) {
   return environmentData.get(key);
 }
@@ -441,9 +536,7 @@ export function setEnvironmentData(key: unknown, value?: unknown) {
 }
 
 export const SHARE_ENV = SymbolFor("nodejs.worker_threads.SHARE_ENV");
-export function markAsUntransferable() {
-  notImplemented("markAsUntransferable");
-}
+export { isMarkedAsUntransferable, markAsUntransferable };
 export function moveMessagePortToContext() {
   notImplemented("moveMessagePortToContext");
 }
@@ -463,7 +556,9 @@ export function receiveMessageOnPort(port: MessagePort): object | undefined {
   port[MessagePortReceiveMessageOnPortSymbol] = true;
   const data = op_message_port_recv_message_sync(port[MessagePortIdSymbol]);
   if (data === null) return undefined;
-  return { message: deserializeJsMessageData(data)[0] };
+  const message = deserializeJsMessageData(data)[0];
+  patchMessagePortIfFound(message);
+  return { message };
 }
 
 class NodeMessageChannel {
@@ -516,7 +611,12 @@ function webMessagePortToNodeMessagePort(port: MessagePort) {
     listener,
   ) {
     if (name == "message") {
-      port.removeEventListener("message", listeners.get(listener)!);
+      const _listener = listeners.get(listener)!;
+      if (port.onmessage === _listener) {
+        port.onmessage = null;
+      } else {
+        port.removeEventListener("message", _listener);
+      }
     } else if (name == "messageerror") {
       port.removeEventListener("messageerror", listeners.get(listener)!);
     } else if (name == "close") {
@@ -530,6 +630,13 @@ function webMessagePortToNodeMessagePort(port: MessagePort) {
   port[nodeWorkerThreadCloseCb] = () => {
     port.dispatchEvent(new Event("close"));
   };
+  port.once = function (this: MessagePort, name, listener) {
+    const _listener = (message: unknown) => {
+      this.off(name, _listener);
+      listener(message);
+    };
+    return this.on(name, _listener);
+  };
   port.unref = () => {
     port[refMessagePort](false);
   };
@@ -572,6 +679,7 @@ export {
 };
 
 const defaultExport = {
+  isMarkedAsUntransferable,
   markAsUntransferable,
   moveMessagePortToContext,
   receiveMessageOnPort,
@@ -33,8 +33,10 @@ import {
   unzipSync,
 } from "ext:deno_node/_zlib.mjs";
 import {
+  BrotliCompress,
   brotliCompress,
   brotliCompressSync,
+  BrotliDecompress,
   brotliDecompress,
   brotliDecompressSync,
   createBrotliCompress,
@@ -60,16 +62,6 @@ export class BrotliOptions {
     notImplemented("BrotliOptions.prototype.constructor");
   }
 }
-export class BrotliCompress {
-  constructor() {
-    notImplemented("BrotliCompress.prototype.constructor");
-  }
-}
-export class BrotliDecompress {
-  constructor() {
-    notImplemented("BrotliDecompress.prototype.constructor");
-  }
-}
 export class ZlibBase {
   constructor() {
     notImplemented("ZlibBase.prototype.constructor");
@@ -122,8 +114,10 @@ export default {
 };
 
 export {
+  BrotliCompress,
   brotliCompress,
   brotliCompressSync,
+  BrotliDecompress,
   brotliDecompress,
   brotliDecompressSync,
   codes,
@@ -12,6 +12,8 @@ use deno_core::serde_json::Value;
 use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_fs::FileSystemRc;
+use deno_fs::RealpathCache;
+use deno_io::fs::FsResult;
 use deno_media_type::MediaType;
 use deno_package_json::PackageJsonRc;
 
@@ -141,6 +143,7 @@ pub struct NodeResolver {
   fs: FileSystemRc,
   npm_resolver: NpmResolverRc,
   in_npm_package_cache: deno_fs::sync::MaybeArcMutex<HashMap<String, bool>>,
+  realpath_cache: RealpathCache,
 }
 
 impl NodeResolver {
@@ -149,9 +152,21 @@ impl NodeResolver {
       fs,
       npm_resolver,
       in_npm_package_cache: deno_fs::sync::MaybeArcMutex::new(HashMap::new()),
+      realpath_cache: RealpathCache::new(),
     }
   }
 
+  /// Canonicalizes `path`, caching the canonical paths of directories.
+  pub fn realpath(&self, path: &Path) -> FsResult<PathBuf> {
+    self.realpath_cache.realpath(&*self.fs, path)
+  }
+
+  /// Forgets cached canonical paths at or below any of the `changed_paths`
+  /// reported by a file watcher.
+  pub fn invalidate_realpath_cache(&self, changed_paths: &[PathBuf]) {
+    self.realpath_cache.invalidate(changed_paths);
+  }
+
   pub fn in_npm_package(&self, specifier: &ModuleSpecifier) -> bool {
     self.npm_resolver.in_npm_package(specifier)
   }
@@ -596,47 +611,24 @@ impl NodeResolver {
           if let Some(resolved) = maybe_resolved {
             return Ok(resolved);
           }
-        } else {
-          let mut best_match = "";
-          let mut best_match_subpath = None;
-          for key in imports.keys() {
-            let pattern_index = key.find('*');
-            if let Some(pattern_index) = pattern_index {
-              let key_sub = &key[0..=pattern_index];
-              if name.starts_with(key_sub) {
-                let pattern_trailer = &key[pattern_index + 1..];
-                if name.len() > key.len()
-                  && name.ends_with(&pattern_trailer)
-                  && pattern_key_compare(best_match, key) == 1
-                  && key.rfind('*') == Some(pattern_index)
-                {
-                  best_match = key;
-                  best_match_subpath = Some(
-                    name[pattern_index..=(name.len() - pattern_trailer.len())]
-                      .to_string(),
-                  );
-                }
-              }
-            }
-          }
-
-          if !best_match.is_empty() {
-            let target = imports.get(best_match).unwrap();
-            let maybe_resolved = self.resolve_package_target(
-              package_json_path.as_ref().unwrap(),
-              target,
-              &best_match_subpath.unwrap(),
-              best_match,
-              maybe_referrer,
-              referrer_kind,
-              true,
-              true,
-              conditions,
-              mode,
-            )?;
-            if let Some(resolved) = maybe_resolved {
-              return Ok(resolved);
-            }
+        } else if let Some((best_match, best_match_subpath)) =
+          best_pattern_match(imports.keys(), name)
+        {
+          let target = imports.get(best_match).unwrap();
+          let maybe_resolved = self.resolve_package_target(
+            package_json_path.as_ref().unwrap(),
+            target,
+            &best_match_subpath,
+            best_match,
+            maybe_referrer,
+            referrer_kind,
+            true,
+            true,
+            conditions,
+            mode,
+          )?;
+          if let Some(resolved) = maybe_resolved {
+            return Ok(resolved);
           }
         }
       }
@@ -695,7 +687,7 @@ impl NodeResolver {
           Err(_) => {
             let export_target = if pattern {
               pattern_re
-                .replace(target, |_caps: &regex::Captures| subpath)
+                .replace_all(target, |_caps: &regex::Captures| subpath)
                 .to_string()
             } else {
               format!("{target}{subpath}")
@@ -811,7 +803,7 @@ impl NodeResolver {
     if pattern {
       let resolved_path_str = resolved_path.to_string_lossy();
       let replaced = pattern_re
-        .replace(&resolved_path_str, |_caps: &regex::Captures| subpath);
+        .replace_all(&resolved_path_str, |_caps: &regex::Captures| subpath);
       return Ok(to_file_specifier(&PathBuf::from(replaced.to_string())));
     }
     Ok(to_file_specifier(&resolved_path.join(subpath).clean()))
@@ -985,6 +977,8 @@ impl NodeResolver {
           }
         }
       }
+      // none of the conditions matched
+      return Ok(None);
     } else if target.is_null() {
       return Ok(None);
     }
@@ -1043,46 +1037,16 @@ impl NodeResolver {
       };
     }
 
-    let mut best_match = "";
-    let mut best_match_subpath = None;
-    for key in package_exports.keys() {
-      let pattern_index = key.find('*');
-      if let Some(pattern_index) = pattern_index {
-        let key_sub = &key[0..pattern_index];
-        if package_subpath.starts_with(key_sub) {
-          // When this reaches EOL, this can throw at the top of the whole function:
-          //
-          // if (StringPrototypeEndsWith(packageSubpath, '/'))
-          //   throwInvalidSubpath(packageSubpath)
-          //
-          // To match "imports" and the spec.
-          if package_subpath.ends_with('/') {
-            // TODO(bartlomieju):
-            // emitTrailingSlashPatternDeprecation();
-          }
-          let pattern_trailer = &key[pattern_index + 1..];
-          if package_subpath.len() >= key.len()
-            && package_subpath.ends_with(&pattern_trailer)
-            && pattern_key_compare(best_match, key) == 1
-            && key.rfind('*') == Some(pattern_index)
-          {
-            best_match = key;
-            best_match_subpath = Some(
-              package_subpath[pattern_index
-                ..(package_subpath.len() - pattern_trailer.len())]
-                .to_string(),
-            );
-          }
-        }
-      }
-    }
-
-    if !best_match.is_empty() {
+    // TODO(bartlomieju): emitTrailingSlashPatternDeprecation() when the
+    // subpath ends with a slash and matches a pattern.
+    if let Some((best_match, best_match_subpath)) =
+      best_pattern_match(package_exports.keys(), package_subpath)
+    {
       let target = package_exports.get(best_match).unwrap();
       let maybe_resolved = self.resolve_package_target(
         package_json_path,
         target,
-        &best_match_subpath.unwrap(),
+        &best_match_subpath,
         best_match,
         maybe_referrer,
         referrer_kind,
@@ -1380,7 +1344,7 @@ impl NodeResolver {
   ) -> Result<Option<PackageJsonRc>, ClosestPkgJsonError> {
     let parent_dir = file_path.parent().unwrap();
     let current_dir =
-      deno_core::strip_unc_prefix(self.fs.realpath_sync(parent_dir).map_err(
+      deno_core::strip_unc_prefix(self.realpath(parent_dir).map_err(
         |source| CanonicalizingPkgJsonDirError {
           dir_path: parent_dir.to_path_buf(),
           source: source.into_io_error(),
@@ -1774,6 +1738,37 @@ pub fn parse_npm_pkg_name(
   Ok((package_name, package_subpath, is_scoped))
 }
 
+/// Finds the pattern key (ex. `"./features/*.js"` or `"#internal/*"`) that
+/// best matches the provided subpath, returning the key along with the text
+/// that should be substituted for its `*`.
+fn best_pattern_match<'a>(
+  keys: impl Iterator<Item = &'a String>,
+  subpath: &str,
+) -> Option<(&'a str, String)> {
+  let mut best_match: Option<(&'a str, String)> = None;
+  for key in keys {
+    let Some(pattern_index) = key.find('*') else {
+      continue;
+    };
+    // a pattern key may only contain a single "*"
+    if key.rfind('*') != Some(pattern_index) {
+      continue;
+    }
+    let pattern_trailer = &key[pattern_index + 1..];
+    let best_key = best_match.as_ref().map(|(key, _)| *key).unwrap_or("");
+    if subpath.len() >= key.len()
+      && subpath.starts_with(&key[..pattern_index])
+      && subpath.ends_with(pattern_trailer)
+      && pattern_key_compare(best_key, key) == 1
+    {
+      let substitution =
+        &subpath[pattern_index..subpath.len() - pattern_trailer.len()];
+      best_match = Some((key, substitution.to_string()));
+    }
+  }
+  best_match
+}
+
 fn pattern_key_compare(a: &str, b: &str) -> i32 {
   let a_pattern_index = a.find('*');
   let b_pattern_index = b.find('*');
@@ -2013,4 +2008,35 @@ mod tests {
       "@types/@scoped__package"
     );
   }
+
+  #[test]
+  fn test_best_pattern_match() {
+    fn run(keys: &[&str], subpath: &str) -> Option<(String, String)> {
+      let keys = keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+      best_pattern_match(keys.iter(), subpath)
+        .map(|(key, substitution)| (key.to_string(), substitution))
+    }
+
+    assert_eq!(
+      run(&["#internal/*"], "#internal/foo/bar"),
+      Some(("#internal/*".to_string(), "foo/bar".to_string()))
+    );
+    assert_eq!(
+      run(&["./features/*.js"], "./features/a/b.js"),
+      Some(("./features/*.js".to_string(), "a/b".to_string()))
+    );
+    // the longest matching prefix wins
+    assert_eq!(
+      run(&["./*", "./features/*"], "./features/x"),
+      Some(("./features/*".to_string(), "x".to_string()))
+    );
+    // then the longest trailer
+    assert_eq!(
+      run(&["./features/*", "./features/*.js"], "./features/x.js"),
+      Some(("./features/*.js".to_string(), "x".to_string()))
+    );
+    assert_eq!(run(&["./features/*.js"], "./features/x.cjs"), None);
+    assert_eq!(run(&["./*/*"], "./a/b"), None);
+    assert_eq!(run(&["./exact"], "./exact"), None);
+  }
 }
@@ -31,6 +31,7 @@ use std::io::BufReader;
 use std::io::Cursor;
 use std::net::IpAddr;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 mod tls_key;
 pub use tls_key::*;
@@ -172,6 +173,77 @@ pub enum SocketUse {
   Http2Only,
 }
 
+/// The certificate chain of a server, end-entity certificate first, once it
+/// has been presented in a handshake.
+pub type PeerCertificates = Arc<Mutex<Option<Vec<CertificateDer<'static>>>>>;
+
+/// Wraps a `ServerCertVerifier` to record the certificate chain of the server
+/// that it verifies, whether or not the verification succeeds.
+#[derive(Debug)]
+pub struct PeerCertificateRecorder {
+  verifier: Arc<dyn ServerCertVerifier>,
+  peer_certificates: PeerCertificates,
+}
+
+impl PeerCertificateRecorder {
+  pub fn new(verifier: Arc<dyn ServerCertVerifier>) -> Self {
+    Self {
+      verifier,
+      peer_certificates: Default::default(),
+    }
+  }
+
+  pub fn peer_certificates(&self) -> PeerCertificates {
+    self.peer_certificates.clone()
+  }
+}
+
+impl ServerCertVerifier for PeerCertificateRecorder {
+  fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+    self.verifier.supported_verify_schemes()
+  }
+
+  fn verify_server_cert(
+    &self,
+    end_entity: &rustls::pki_types::CertificateDer<'_>,
+    intermediates: &[rustls::pki_types::CertificateDer<'_>],
+    server_name: &rustls::pki_types::ServerName<'_>,
+    ocsp_response: &[u8],
+    now: rustls::pki_types::UnixTime,
+  ) -> Result<ServerCertVerified, Error> {
+    let chain = std::iter::once(end_entity)
+      .chain(intermediates)
+      .map(|cert| cert.clone().into_owned())
+      .collect();
+    *self.peer_certificates.lock().unwrap() = Some(chain);
+    self.verifier.verify_server_cert(
+      end_entity,
+      intermediates,
+      server_name,
+      ocsp_response,
+      now,
+    )
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &rustls::pki_types::CertificateDer,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, Error> {
+    self.verifier.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &rustls::pki_types::CertificateDer,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, Error> {
+    self.verifier.verify_tls13_signature(message, cert, dss)
+  }
+}
+
 pub fn create_client_config(
   root_cert_store: Option<RootCertStore>,
   ca_certs: Vec<Vec<u8>>,
@@ -179,27 +251,27 @@ pub fn create_client_config(
   maybe_cert_chain_and_key: TlsKeys,
   socket_use: SocketUse,
 ) -> Result<ClientConfig, AnyError> {
-  if let Some(ic_allowlist) = unsafely_ignore_certificate_errors {
-    let client_config = ClientConfig::builder()
-      .dangerous()
-      .with_custom_certificate_verifier(Arc::new(
-        NoCertificateVerification::new(ic_allowlist),
-      ));
-
-    // NOTE(bartlomieju): this if/else is duplicated at the end of the body of this function.
-    // However it's not really feasible to deduplicate it as the `client_config` instances
-    // are not type-compatible - one wants "client cert", the other wants "transparency policy
-    // or client cert".
-    let mut client = match maybe_cert_chain_and_key {
-      TlsKeys::Static(TlsKey(cert_chain, private_key)) => client_config
-        .with_client_auth_cert(cert_chain, private_key.clone_key())
-        .expect("invalid client key or certificate"),
-      TlsKeys::Null => client_config.with_no_client_auth(),
-      TlsKeys::Resolver(_) => unimplemented!(),
-    };
+  let verifier = create_server_cert_verifier(
+    root_cert_store,
+    ca_certs,
+    unsafely_ignore_certificate_errors,
+  )?;
+  Ok(create_client_config_with_verifier(
+    verifier,
+    maybe_cert_chain_and_key,
+    socket_use,
+  ))
+}
 
-    add_alpn(&mut client, socket_use);
-    return Ok(client);
+/// Creates the verifier of server certificates that `create_client_config`
+/// uses, for callers that wrap it, like in a `PeerCertificateRecorder`.
+pub fn create_server_cert_verifier(
+  root_cert_store: Option<RootCertStore>,
+  ca_certs: Vec<Vec<u8>>,
+  unsafely_ignore_certificate_errors: Option<Vec<String>>,
+) -> Result<Arc<dyn ServerCertVerifier>, AnyError> {
+  if let Some(ic_allowlist) = unsafely_ignore_certificate_errors {
+    return Ok(Arc::new(NoCertificateVerification::new(ic_allowlist)));
   }
 
   let mut root_cert_store =
@@ -223,8 +295,17 @@ pub fn create_client_config(
     }
   }
 
-  let client_config =
-    ClientConfig::builder().with_root_certificates(root_cert_store);
+  Ok(WebPkiServerVerifier::builder(root_cert_store.into()).build()?)
+}
+
+pub fn create_client_config_with_verifier(
+  verifier: Arc<dyn ServerCertVerifier>,
+  maybe_cert_chain_and_key: TlsKeys,
+  socket_use: SocketUse,
+) -> ClientConfig {
+  let client_config = ClientConfig::builder()
+    .dangerous()
+    .with_custom_certificate_verifier(verifier);
 
   let mut client = match maybe_cert_chain_and_key {
     TlsKeys::Static(TlsKey(cert_chain, private_key)) => client_config
@@ -235,7 +316,7 @@ pub fn create_client_config(
   };
 
   add_alpn(&mut client, socket_use);
-  Ok(client)
+  client
 }
 
 fn add_alpn(client: &mut ClientConfig, socket_use: SocketUse) {
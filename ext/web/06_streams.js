@@ -902,11 +902,13 @@ const _original = Symbol("[[original]]");
  *
  * @param {number} rid The resource ID to read from.
  * @param {boolean=} autoClose If the resource should be auto-closed when the stream closes. Defaults to true.
+ * @param {(() => void)=} onEof Called once the end of the stream is reached,
+ * before the resource is closed.
  * @returns {ReadableStream<Uint8Array>}
  */
-function readableStreamForRid(rid, autoClose = true) {
+function readableStreamForRid(rid, autoClose = true, onEof = undefined) {
   const stream = new ReadableStream(_brand);
-  stream[_resourceBacking] = { rid, autoClose };
+  stream[_resourceBacking] = { rid, autoClose, onEof };
 
   const tryClose = () => {
     if (!autoClose) return;
@@ -930,12 +932,14 @@ function readableStreamForRid(rid, autoClose = true) {
             controller.enqueue(chunk);
           }
           controller.close();
+          onEof?.();
           tryClose();
           return;
         }
 
         const bytesRead = await core.read(rid, v);
         if (bytesRead === 0) {
+          onEof?.();
           tryClose();
           controller.close();
           controller.byobRequest.respond(0);
@@ -974,7 +978,13 @@ const _isUnref = Symbol("isUnref");
  * @param {number} rid The resource ID to read from.
  * @returns {ReadableStream<Uint8Array>}
  */
-function readableStreamForRidUnrefable(rid) {
+/**
+ * @param {number} rid
+ * @param {(() => void)=} onEof Called instead of closing the resource once
+ * the end of the stream is reached.
+ * @returns {ReadableStream<Uint8Array>}
+ */
+function readableStreamForRidUnrefable(rid, onEof = undefined) {
   const stream = new ReadableStream(_brand);
   stream[promiseSymbol] = undefined;
   stream[_isUnref] = false;
@@ -990,7 +1000,11 @@ function readableStreamForRidUnrefable(rid) {
         const bytesRead = await promise;
         stream[promiseSymbol] = undefined;
         if (bytesRead === 0) {
-          core.tryClose(rid);
+          if (onEof !== undefined) {
+            onEof();
+          } else {
+            core.tryClose(rid);
+          }
           controller.close();
           controller.byobRequest.respond(0);
         } else {
@@ -1065,6 +1079,8 @@ async function readableStreamCollectIntoUint8Array(stream) {
       const buf = await promise;
       stream[promiseSymbol] = undefined;
       readableStreamThrowIfErrored(stream);
+      // the resource is closed below, without reaching the pull path
+      resourceBacking.onEof?.();
       readableStreamClose(stream);
       return buf;
     } catch (err) {
@@ -1124,9 +1140,10 @@ async function readableStreamCollectIntoUint8Array(stream) {
  *
  * @param {number} rid The resource ID to write to.
  * @param {boolean=} autoClose If the resource should be auto-closed when the stream closes. Defaults to true.
+ * @param {(() => Promise<void>)=} onClose Called instead of closing the resource when the stream is closed, e.g. to only shut down the write side of a connection.
  * @returns {ReadableStream<Uint8Array>}
  */
-function writableStreamForRid(rid, autoClose = true) {
+function writableStreamForRid(rid, autoClose = true, onClose = undefined) {
   const stream = new WritableStream(_brand);
   stream[_resourceBacking] = { rid, autoClose };
 
@@ -1150,6 +1167,9 @@ function writableStreamForRid(rid, autoClose = true) {
       }
     },
     close() {
+      if (onClose !== undefined) {
+        return onClose();
+      }
       tryClose();
     },
     abort() {
@@ -23,6 +23,7 @@ const {
   SymbolFor,
   SymbolIterator,
   SafeArrayIterator,
+  SafeWeakSet,
   TypeError,
 } = primordials;
 const {
@@ -248,7 +249,7 @@ class MessagePort extends EventTarget {
       messageEventListenerCount++;
     } else if (!ref && this[_refed]) {
       this[_refed] = false;
-      messageEventListenerCount = 0;
+      messageEventListenerCount--;
     }
   }
 
@@ -355,12 +356,40 @@ function deserializeJsMessageData(messageData) {
   return [data, transferables];
 }
 
+/** Objects that are copied instead of transferred, see
+ * `markAsUntransferable()` in node:worker_threads. */
+const untransferables = new SafeWeakSet();
+
+/**
+ * @param {unknown} object
+ */
+function markAsUntransferable(object) {
+  if (
+    object !== null &&
+    (typeof object === "object" || typeof object === "function")
+  ) {
+    untransferables.add(object);
+  }
+}
+
+/**
+ * @param {unknown} object
+ * @returns {boolean}
+ */
+function isMarkedAsUntransferable(object) {
+  return untransferables.has(object);
+}
+
 /**
  * @param {any} data
  * @param {object[]} transferables
  * @returns {messagePort.MessageData}
  */
 function serializeJsMessageData(data, transferables) {
+  transferables = ArrayPrototypeFilter(
+    transferables,
+    (t) => !untransferables.has(t),
+  );
   let options;
   const transferredArrayBuffers = [];
   if (transferables.length > 0) {
@@ -459,6 +488,8 @@ function structuredClone(value, options) {
 
 export {
   deserializeJsMessageData,
+  isMarkedAsUntransferable,
+  markAsUntransferable,
   MessageChannel,
   messageEventListenerCount,
   MessagePort,
@@ -116,4 +116,6 @@ declare module "ext:deno_web/13_message_port.js" {
   function deserializeJsMessageData(
     messageData: messagePort.MessageData,
   ): [object, object[]];
+  function markAsUntransferable(object: unknown): void;
+  function isMarkedAsUntransferable(object: unknown): boolean;
 }
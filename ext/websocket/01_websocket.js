@@ -4,6 +4,7 @@
 
 import { core, primordials } from "ext:core/mod.js";
 const {
+  internalRidSymbol,
   isAnyArrayBuffer,
   isArrayBuffer,
 } = core;
@@ -42,6 +43,7 @@ const {
   Symbol,
   SymbolFor,
   SymbolIterator,
+  TypeError,
   TypedArrayPrototypeGetByteLength,
 } = primordials;
 
@@ -63,6 +65,12 @@ import {
 } from "ext:deno_web/02_event.js";
 import { Blob, BlobPrototype } from "ext:deno_web/09_file.js";
 import { getLocationHref } from "ext:deno_web/12_location.js";
+import {
+  fillHeaders,
+  headerListFromHeaders,
+  headersFromHeaderList,
+} from "ext:deno_fetch/20_headers.js";
+import { HttpClientPrototype } from "ext:deno_fetch/22_http_client.js";
 
 webidl.converters["sequence<DOMString> or DOMString"] = (
   V,
@@ -79,6 +87,51 @@ webidl.converters["sequence<DOMString> or DOMString"] = (
   return webidl.converters.DOMString(V, prefix, context, opts);
 };
 
+webidl.converters["WebSocketOptions"] = webidl.createDictionaryConverter(
+  "WebSocketOptions",
+  [
+    {
+      key: "protocols",
+      converter: webidl.converters["sequence<DOMString> or DOMString"],
+      get defaultValue() {
+        return [];
+      },
+    },
+    {
+      key: "headers",
+      converter: webidl.converters.HeadersInit,
+    },
+    {
+      key: "client",
+      converter: webidl.converters.any,
+    },
+  ],
+);
+
+// NOTE: non standard extension. Besides a list of protocols, the second
+// argument of the constructor may be a `WebSocketOptions` dictionary.
+webidl.converters["sequence<DOMString> or DOMString or WebSocketOptions"] = (
+  V,
+  prefix,
+  context,
+  opts,
+) => {
+  if (
+    webidl.type(V) === "Object" && V !== null &&
+    V[SymbolIterator] === undefined
+  ) {
+    return webidl.converters["WebSocketOptions"](V, prefix, context, opts);
+  }
+  return {
+    protocols: webidl.converters["sequence<DOMString> or DOMString"](
+      V,
+      prefix,
+      context,
+      opts,
+    ),
+  };
+};
+
 webidl.converters["WebSocketSend"] = (V, prefix, context, opts) => {
   // Union for (Blob or ArrayBufferView or ArrayBuffer or USVString)
   if (ObjectPrototypeIsPrototypeOf(BlobPrototype, V)) {
@@ -111,6 +164,7 @@ const _rid = Symbol("[[rid]]");
 const _role = Symbol("[[role]]");
 const _extensions = Symbol("[[extensions]]");
 const _protocol = Symbol("[[protocol]]");
+const _headers = Symbol("[[headers]]");
 const _binaryType = Symbol("[[binaryType]]");
 const _eventLoop = Symbol("[[eventLoop]]");
 const _sendQueue = Symbol("[[sendQueue]]");
@@ -122,7 +176,7 @@ const _idleTimeoutTimeout = Symbol("[[idleTimeoutTimeout]]");
 const _serverHandleIdleTimeout = Symbol("[[serverHandleIdleTimeout]]");
 
 class WebSocket extends EventTarget {
-  constructor(url, protocols = []) {
+  constructor(url, protocolsOrOptions = []) {
     super();
     this[webidl.brand] = webidl.brand;
     this[_rid] = undefined;
@@ -130,6 +184,7 @@ class WebSocket extends EventTarget {
     this[_readyState] = CONNECTING;
     this[_extensions] = "";
     this[_protocol] = "";
+    this[_headers] = null;
     this[_url] = "";
     this[_binaryType] = "blob";
     this[_idleTimeoutDuration] = 0;
@@ -139,11 +194,33 @@ class WebSocket extends EventTarget {
     const prefix = "Failed to construct 'WebSocket'";
     webidl.requiredArguments(arguments.length, 1, prefix);
     url = webidl.converters.USVString(url, prefix, "Argument 1");
-    protocols = webidl.converters["sequence<DOMString> or DOMString"](
-      protocols,
+    const options = webidl.converters[
+      "sequence<DOMString> or DOMString or WebSocketOptions"
+    ](
+      protocolsOrOptions,
       prefix,
       "Argument 2",
     );
+    let protocols = options.protocols;
+
+    let headers = null;
+    if (options.headers !== undefined) {
+      headers = headersFromHeaderList([], "request");
+      fillHeaders(headers, options.headers);
+    }
+
+    let clientRid = null;
+    if (options.client !== undefined && options.client !== null) {
+      if (!ObjectPrototypeIsPrototypeOf(HttpClientPrototype, options.client)) {
+        throw webidl.makeException(
+          TypeError,
+          "`client` must be a Deno.HttpClient",
+          prefix,
+          "Argument 2",
+        );
+      }
+      clientRid = options.client[internalRidSymbol];
+    }
 
     let wsURL;
 
@@ -218,11 +295,15 @@ class WebSocket extends EventTarget {
         "new WebSocket()",
         wsURL.href,
         ArrayPrototypeJoin(protocols, ", "),
+        null,
+        headers !== null ? headerListFromHeaders(headers) : null,
+        clientRid,
       ),
       (create) => {
         this[_rid] = create.rid;
         this[_extensions] = create.extensions;
         this[_protocol] = create.protocol;
+        this[_headers] = headersFromHeaderList(create.headers, "immutable");
 
         if (this[_readyState] === CLOSING) {
           PromisePrototypeThen(
@@ -293,6 +374,13 @@ class WebSocket extends EventTarget {
     return this[_protocol];
   }
 
+  // NOTE: non standard extension. The headers of the server's handshake
+  // response, or null until the connection is open.
+  get headers() {
+    webidl.assertBranded(this, WebSocketPrototype);
+    return this[_headers];
+  }
+
   get url() {
     webidl.assertBranded(this, WebSocketPrototype);
     return this[_url];
@@ -655,6 +743,7 @@ function createWebSocketBranded() {
   socket[_readyState] = CONNECTING;
   socket[_extensions] = "";
   socket[_protocol] = "";
+  socket[_headers] = null;
   socket[_url] = "";
   // We use ArrayBuffer for server websockets for backwards compatibility
   // and performance reasons.
@@ -14,6 +14,7 @@ import {
   op_ws_send_binary_async,
   op_ws_send_text_async,
 } from "ext:core/ops";
+const { internalRidSymbol } = core;
 const {
   ArrayPrototypeJoin,
   ArrayPrototypeMap,
@@ -42,6 +43,7 @@ import {
   headerListFromHeaders,
   headersFromHeaderList,
 } from "ext:deno_fetch/20_headers.js";
+import { HttpClientPrototype } from "ext:deno_fetch/22_http_client.js";
 
 webidl.converters.WebSocketStreamOptions = webidl.createDictionaryConverter(
   "WebSocketStreamOptions",
@@ -61,6 +63,10 @@ webidl.converters.WebSocketStreamOptions = webidl.createDictionaryConverter(
       key: "headers",
       converter: webidl.converters.HeadersInit,
     },
+    {
+      key: "client",
+      converter: webidl.converters.any,
+    },
   ],
 );
 webidl.converters.WebSocketCloseInfo = webidl.createDictionaryConverter(
@@ -150,6 +156,19 @@ class WebSocketStream {
       fillHeaders(headers, options.headers);
     }
 
+    let clientRid = null;
+    if (options.client !== undefined && options.client !== null) {
+      if (!ObjectPrototypeIsPrototypeOf(HttpClientPrototype, options.client)) {
+        throw webidl.makeException(
+          TypeError,
+          "`client` must be a Deno.HttpClient",
+          prefix,
+          "Argument 2",
+        );
+      }
+      clientRid = options.client[internalRidSymbol];
+    }
+
     const cancelRid = op_ws_check_permission_and_cancel_handle(
       "WebSocketStream.abort()",
       this[_url],
@@ -173,6 +192,7 @@ class WebSocketStream {
           options.protocols ? ArrayPrototypeJoin(options.protocols, ", ") : "",
           cancelRid,
           headerListFromHeaders(headers),
+          clientRid,
         ),
         (create) => {
           options.signal?.[remove](abort);
@@ -360,6 +380,7 @@ class WebSocketStream {
               writable,
               extensions: create.extensions ?? "",
               protocol: create.protocol ?? "",
+              headers: headersFromHeaderList(create.headers, "immutable"),
             });
           }
         },
@@ -77,6 +77,11 @@ declare interface WebSocket extends EventTarget {
    * Returns the subprotocol selected by the server, if any. It can be used in conjunction with the array form of the constructor's second argument to perform subprotocol negotiation.
    */
   readonly protocol: string;
+  /**
+   * Non-standard. Returns the headers of the server's handshake response, or
+   * `null` until the connection is open.
+   */
+  readonly headers: Headers | null;
   /**
    * Returns the state of the WebSocket object's connection. It can have the values described below.
    */
@@ -119,10 +124,27 @@ declare interface WebSocket extends EventTarget {
   ): void;
 }
 
+/** Non-standard options for the {@linkcode WebSocket} constructor.
+ *
+ * @category WebSockets
+ */
+declare interface WebSocketOptions {
+  /** The sub-protocols to request from the server. */
+  protocols?: string | string[];
+  /** Additional headers to send with the opening handshake. */
+  headers?: HeadersInit;
+  /** An HTTP client used to open the connection, to connect through its
+   * proxy or with its CA certificates and client certificate. Clients are
+   * created with `Deno.createHttpClient()`, which requires `--unstable-http`.
+   */
+  client?: Deno.HttpClient;
+}
+
 /** @category WebSockets */
 declare var WebSocket: {
   readonly prototype: WebSocket;
   new (url: string | URL, protocols?: string | string[]): WebSocket;
+  new (url: string | URL, options?: WebSocketOptions): WebSocket;
   readonly CLOSED: number;
   readonly CLOSING: number;
   readonly CONNECTING: number;
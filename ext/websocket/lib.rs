@@ -20,6 +20,7 @@ use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_core::ToJsBuffer;
+use deno_fetch::HttpClientResource;
 use deno_net::raw::NetworkStream;
 use deno_tls::create_client_config;
 use deno_tls::rustls::ClientConfig;
@@ -28,6 +29,7 @@ use deno_tls::RootCertStoreProvider;
 use deno_tls::SocketUse;
 use deno_tls::TlsKeys;
 use http::header::CONNECTION;
+use http::header::PROXY_AUTHORIZATION;
 use http::header::UPGRADE;
 use http::HeaderName;
 use http::HeaderValue;
@@ -161,6 +163,7 @@ pub struct CreateResponse {
   rid: ResourceId,
   protocol: String,
   extensions: String,
+  headers: Vec<(ByteString, ByteString)>,
 }
 
 async fn handshake_websocket(
@@ -168,6 +171,7 @@ async fn handshake_websocket(
   uri: &Uri,
   protocols: &str,
   headers: Option<Vec<(ByteString, ByteString)>>,
+  client: Option<Rc<HttpClientResource>>,
 ) -> Result<(WebSocket<WebSocketStream>, http::HeaderMap), AnyError> {
   let mut request = Request::builder().method(Method::GET).uri(
     uri
@@ -194,6 +198,10 @@ async fn handshake_websocket(
   request =
     populate_common_request_headers(request, &user_agent, protocols, &headers)?;
 
+  if let Some(client) = client {
+    return handshake_with_client(&client, request, uri).await;
+  }
+
   let request = request.body(http_body_util::Empty::new())?;
   let domain = &uri.host().unwrap().to_string();
   let port = &uri.port_u16().unwrap_or(match uri.scheme_str() {
@@ -256,6 +264,33 @@ async fn handshake_http1_wss(
   handshake_connection(request, tls_connector).await
 }
 
+/// Performs an HTTP/1.1 handshake over a connection opened by a user-provided
+/// `Deno.HttpClient`, honoring its proxy and TLS settings.
+async fn handshake_with_client(
+  client: &HttpClientResource,
+  mut request: http::request::Builder,
+  uri: &Uri,
+) -> Result<(WebSocket<WebSocketStream>, http::HeaderMap), AnyError> {
+  let mut parts = uri.clone().into_parts();
+  parts.scheme = Some(match uri.scheme_str() {
+    Some("wss") => http::uri::Scheme::HTTPS,
+    _ => http::uri::Scheme::HTTP,
+  });
+  let dst = Uri::from_parts(parts)?;
+
+  let connection = client.client.connect_upgrade(dst.clone()).await?;
+  if connection.is_forwarded() {
+    // Forwarding proxies expect the absolute form of the target URI.
+    request = request.uri(dst);
+    if let Some(auth) = connection.proxy_authorization() {
+      request = request.header(PROXY_AUTHORIZATION, auth.clone());
+    }
+  }
+
+  let request = request.body(http_body_util::Empty::new())?;
+  handshake_connection(request, connection).await
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn handshake_http2_wss(
   state: &Rc<RefCell<OpState>>,
@@ -401,6 +436,7 @@ pub async fn op_ws_create<WP>(
   #[string] protocols: String,
   #[smi] cancel_handle: Option<ResourceId>,
   #[serde] headers: Option<Vec<(ByteString, ByteString)>>,
+  #[smi] client_rid: Option<ResourceId>,
 ) -> Result<CreateResponse, AnyError>
 where
   WP: WebSocketPermissions + 'static,
@@ -424,14 +460,26 @@ where
     None
   };
 
+  let client = if let Some(client_rid) = client_rid {
+    let r = state
+      .borrow()
+      .resource_table
+      .get::<HttpClientResource>(client_rid)?;
+    Some(r)
+  } else {
+    None
+  };
+
   let uri: Uri = url.parse()?;
 
-  let handshake = handshake_websocket(&state, &uri, &protocols, headers)
-    .map_err(|err| {
-      AnyError::from(DomExceptionNetworkError::new(&format!(
-        "failed to connect to WebSocket: {err}"
-      )))
-    });
+  let handshake = handshake_websocket(
+    &state, &uri, &protocols, headers, client,
+  )
+  .map_err(|err| {
+    AnyError::from(DomExceptionNetworkError::new(&format!(
+      "failed to connect to WebSocket: {err}"
+    )))
+  });
   let (stream, response) = match cancel_resource {
     Some(rc) => handshake.try_or_cancel(rc).await,
     None => handshake.await,
@@ -455,10 +503,15 @@ where
     .iter()
     .map(|header| header.to_str().unwrap())
     .collect::<String>();
+  let headers = response
+    .iter()
+    .map(|(name, value)| (name.as_str().into(), value.as_bytes().into()))
+    .collect();
   Ok(CreateResponse {
     rid,
     protocol: protocol.to_string(),
     extensions,
+    headers,
   })
 }
 
@@ -830,7 +883,7 @@ pub async fn op_ws_next_event(
 }
 
 deno_core::extension!(deno_websocket,
-  deps = [ deno_url, deno_webidl ],
+  deps = [ deno_url, deno_webidl, deno_fetch ],
   parameters = [P: WebSocketPermissions],
   ops = [
     op_ws_check_permission_and_cancel_handle<P>,
@@ -14,10 +14,22 @@ use rusqlite::OptionalExtension;
 
 pub use rusqlite;
 
-#[derive(Clone)]
-struct OriginStorageDir(PathBuf);
+/// Where the data of `localStorage` is kept.
+#[derive(Clone, Debug)]
+pub enum LocalStorageLocation {
+  /// A `local_storage` database inside the storage directory of the origin.
+  OriginStorageDir(PathBuf),
+  /// A database at the given path, regardless of the origin.
+  Path(PathBuf),
+  /// A database that is discarded when the worker exits, e.g. for tests.
+  InMemory,
+}
+
+const DEFAULT_MAX_STORAGE_BYTES: usize = 10 * 1024 * 1024;
 
-const MAX_STORAGE_BYTES: usize = 10 * 1024 * 1024;
+/// The maximum size in bytes of each storage area.
+#[derive(Clone, Copy)]
+struct MaxStorageBytes(usize);
 
 deno_core::extension!(deno_webstorage,
   deps = [ deno_webidl ],
@@ -32,12 +44,16 @@ deno_core::extension!(deno_webstorage,
   ],
   esm = [ "01_webstorage.js" ],
   options = {
-    origin_storage_dir: Option<PathBuf>
+    location: Option<LocalStorageLocation>,
+    max_storage_bytes: Option<usize>,
   },
   state = |state, options| {
-    if let Some(origin_storage_dir) = options.origin_storage_dir {
-      state.put(OriginStorageDir(origin_storage_dir));
+    if let Some(location) = options.location {
+      state.put(location);
     }
+    state.put(MaxStorageBytes(
+      options.max_storage_bytes.unwrap_or(DEFAULT_MAX_STORAGE_BYTES),
+    ));
   },
 );
 
@@ -54,13 +70,25 @@ fn get_webstorage(
 ) -> Result<&Connection, AnyError> {
   let conn = if persistent {
     if state.try_borrow::<LocalStorage>().is_none() {
-      let path = state.try_borrow::<OriginStorageDir>().ok_or_else(|| {
-        DomExceptionNotSupportedError::new(
-          "LocalStorage is not supported in this context.",
-        )
-      })?;
-      std::fs::create_dir_all(&path.0)?;
-      let conn = Connection::open(path.0.join("local_storage"))?;
+      let location =
+        state.try_borrow::<LocalStorageLocation>().ok_or_else(|| {
+          DomExceptionNotSupportedError::new(
+            "LocalStorage is not supported in this context.",
+          )
+        })?;
+      let conn = match location {
+        LocalStorageLocation::OriginStorageDir(dir) => {
+          std::fs::create_dir_all(dir)?;
+          Connection::open(dir.join("local_storage"))?
+        }
+        LocalStorageLocation::Path(path) => {
+          if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+          }
+          Connection::open(path)?
+        }
+        LocalStorageLocation::InMemory => Connection::open_in_memory()?,
+      };
       // Enable write-ahead-logging and tweak some other stuff.
       let initial_pragmas = "
         -- enable write-ahead-logging mode
@@ -135,8 +163,8 @@ pub fn op_webstorage_key(
 }
 
 #[inline]
-fn size_check(input: usize) -> Result<(), AnyError> {
-  if input >= MAX_STORAGE_BYTES {
+fn size_check(input: usize, max_storage_bytes: usize) -> Result<(), AnyError> {
+  if input >= max_storage_bytes {
     return Err(
       deno_web::DomExceptionQuotaExceededError::new(
         "Exceeded maximum storage size",
@@ -155,15 +183,16 @@ pub fn op_webstorage_set(
   #[string] value: &str,
   persistent: bool,
 ) -> Result<(), AnyError> {
+  let MaxStorageBytes(max_storage_bytes) = *state.borrow::<MaxStorageBytes>();
   let conn = get_webstorage(state, persistent)?;
 
-  size_check(key.len() + value.len())?;
+  size_check(key.len() + value.len(), max_storage_bytes)?;
 
   let mut stmt = conn
     .prepare_cached("SELECT SUM(pgsize) FROM dbstat WHERE name = 'data'")?;
-  let size: u32 = stmt.query_row(params![], |row| row.get(0))?;
+  let size: Option<u64> = stmt.query_row(params![], |row| row.get(0))?;
 
-  size_check(size as usize)?;
+  size_check(size.unwrap_or(0) as usize, max_storage_bytes)?;
 
   let mut stmt = conn
     .prepare_cached("INSERT OR REPLACE INTO data (key, value) VALUES (?, ?)")?;
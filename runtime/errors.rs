@@ -58,6 +58,7 @@ fn get_io_error_class(error: &io::Error) -> &'static str {
     UnexpectedEof => "UnexpectedEof",
     Other => "Error",
     WouldBlock => "WouldBlock",
+    Unsupported => "NotSupported",
     // Non-exhaustive enum - might add new variants
     // in the future
     kind => {
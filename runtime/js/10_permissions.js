@@ -2,6 +2,7 @@
 
 import { primordials } from "ext:core/mod.js";
 import {
+  op_create_capability_token,
   op_query_permission,
   op_request_permission,
   op_revoke_permission,
@@ -59,10 +60,11 @@ function opQuery(desc) {
 
 /**
  * @param {Deno.PermissionDescriptor} desc
+ * @param {boolean} propagate
  * @returns {Deno.PermissionState}
  */
-function opRevoke(desc) {
-  return op_revoke_permission(desc);
+function opRevoke(desc, propagate) {
+  return op_revoke_permission(desc, propagate);
 }
 
 /**
@@ -220,15 +222,15 @@ class Permissions {
     return cache(desc, status);
   }
 
-  revoke(desc) {
+  revoke(desc, options = undefined) {
     try {
-      return PromiseResolve(this.revokeSync(desc));
+      return PromiseResolve(this.revokeSync(desc, options));
     } catch (error) {
       return PromiseReject(error);
     }
   }
 
-  revokeSync(desc) {
+  revokeSync(desc, options = undefined) {
     if (!isValidDescriptor(desc)) {
       throw new TypeError(
         `The provided value "${desc?.name}" is not a valid permission name.`,
@@ -237,7 +239,7 @@ class Permissions {
 
     formDescriptor(desc);
 
-    const status = opRevoke(desc);
+    const status = opRevoke(desc, !!options?.propagate);
     return cache(desc, status);
   }
 
@@ -295,4 +297,18 @@ function serializePermissions(permissions) {
   return permissions;
 }
 
-export { Permissions, permissions, PermissionStatus, serializePermissions };
+/**
+ * @param {Deno.PermissionOptions} permissions
+ * @returns {string}
+ */
+function createCapabilityToken(permissions) {
+  return op_create_capability_token(serializePermissions(permissions));
+}
+
+export {
+  createCapabilityToken,
+  Permissions,
+  permissions,
+  PermissionStatus,
+  serializePermissions,
+};
@@ -48,10 +48,14 @@ function createWorker(
   workerType,
   closeOnIdle,
 ) {
+  // keep in sync with `runtime/permissions/capability.rs`
+  const isToken = typeof permissions === "string" &&
+    StringPrototypeStartsWith(permissions, "denocap.");
   return op_create_worker({
     hasSourceCode,
     name,
-    permissions: serializePermissions(permissions),
+    permissions: isToken ? undefined : serializePermissions(permissions),
+    permissionsToken: isToken ? permissions : undefined,
     sourceCode,
     specifier,
     workerType,
@@ -27,8 +27,14 @@ class FsWatcher {
         value: undefined,
       });
     }
-    const { recursive } = options;
-    this.#rid = op_fs_events_open({ recursive, paths });
+    const { recursive = true, exclude, debounce, pairRenames } = options;
+    this.#rid = op_fs_events_open({
+      recursive,
+      paths,
+      exclude,
+      debounce,
+      pairRenames,
+    });
   }
 
   get rid() {
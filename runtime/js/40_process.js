@@ -32,6 +32,7 @@ import {
   SymbolAsyncDispose,
 } from "ext:deno_web/00_infra.js";
 import * as abortSignal from "ext:deno_web/03_abort_signal.js";
+import { serializePermissions } from "ext:runtime/10_permissions.js";
 import {
   readableStreamCollectIntoUint8Array,
   readableStreamForRidUnrefable,
@@ -168,6 +169,19 @@ function run({
 
 const illegalConstructorKey = Symbol("illegalConstructorKey");
 
+function serializeSandbox(sandbox) {
+  if (sandbox === undefined) {
+    return undefined;
+  }
+  return {
+    permissions: serializePermissions(sandbox.permissions),
+    cpu: sandbox.cpu,
+    memory: sandbox.memory,
+    openFiles: sandbox.openFiles,
+    noNewPrivileges: !!sandbox.noNewPrivileges,
+  };
+}
+
 function spawnChildInner(opFn, command, apiName, {
   args = [],
   cwd = undefined,
@@ -181,6 +195,7 @@ function spawnChildInner(opFn, command, apiName, {
   signal = undefined,
   windowsRawArguments = false,
   ipc = -1,
+  sandbox = undefined,
 } = { __proto__: null }) {
   const child = opFn({
     cmd: pathFromURL(command),
@@ -195,6 +210,7 @@ function spawnChildInner(opFn, command, apiName, {
     stderr,
     windowsRawArguments,
     ipc,
+    sandbox: serializeSandbox(sandbox),
   }, apiName);
   return new ChildProcess(illegalConstructorKey, {
     ...child,
@@ -399,6 +415,7 @@ function spawnSync(command, {
   stdout = "piped",
   stderr = "piped",
   windowsRawArguments = false,
+  sandbox = undefined,
 } = { __proto__: null }) {
   if (stdin === "piped") {
     throw new TypeError(
@@ -417,6 +434,7 @@ function spawnSync(command, {
     stdout,
     stderr,
     windowsRawArguments,
+    sandbox: serializeSandbox(sandbox),
   });
   return {
     success: result.status.success,
@@ -79,6 +79,8 @@ const denoNs = {
   lstatSync: fs.lstatSync,
   stat: fs.stat,
   lstat: fs.lstat,
+  statfs: fs.statfs,
+  statfsSync: fs.statfsSync,
   truncateSync: fs.truncateSync,
   truncate: fs.truncate,
   ftruncateSync(rid, len) {
@@ -163,6 +165,9 @@ const denoNs = {
   FsFile: fs.FsFile,
   open: fs.open,
   openSync: fs.openSync,
+  DirHandle: fs.DirHandle,
+  openDir: fs.openDir,
+  openDirSync: fs.openDirSync,
   create: fs.create,
   createSync: fs.createSync,
   stdin: io.stdin,
@@ -231,6 +236,14 @@ const denoNs = {
   upgradeWebSocket: websocket.upgradeWebSocket,
   utime: fs.utime,
   utimeSync: fs.utimeSync,
+  getXattr: fs.getXattr,
+  getXattrSync: fs.getXattrSync,
+  setXattr: fs.setXattr,
+  setXattrSync: fs.setXattrSync,
+  listXattrs: fs.listXattrs,
+  listXattrsSync: fs.listXattrsSync,
+  removeXattr: fs.removeXattr,
+  removeXattrSync: fs.removeXattrSync,
   kill: process.kill,
   addSignalListener: signals.addSignalListener,
   removeSignalListener: signals.removeSignalListener,
@@ -262,6 +275,7 @@ const unstableIds = {
   unsafeProto: 10,
   webgpu: 11,
   workerOptions: 12,
+  rawSockets: 13,
 };
 
 const denoNsUnstableById = { __proto__: null };
@@ -308,13 +322,19 @@ denoNsUnstableById[unstableIds.net] = {
   ),
 };
 
+denoNsUnstableById[unstableIds.rawSockets] = {
+  icmpSocket: net.icmpSocket,
+};
+
 // denoNsUnstableById[unstableIds.unsafeProto] = { __proto__: null }
 
 denoNsUnstableById[unstableIds.webgpu] = {
   UnsafeWindowSurface: webgpuSurface.UnsafeWindowSurface,
 };
 
-// denoNsUnstableById[unstableIds.workerOptions] = { __proto__: null }
+denoNsUnstableById[unstableIds.workerOptions] = {
+  createCapabilityToken: permissions.createCapabilityToken,
+};
 
 // when editing this list, also update unstableDenoProps in cli/tsc/99_main_compiler.js
 const denoNsUnstable = {
@@ -341,6 +361,8 @@ const denoNsUnstable = {
   KvU64: kv.KvU64,
   KvListIterator: kv.KvListIterator,
   cron: cron.cron,
+  icmpSocket: net.icmpSocket,
+  createCapabilityToken: permissions.createCapabilityToken,
 };
 
 export { denoNs, denoNsUnstable, denoNsUnstableById, unstableIds };
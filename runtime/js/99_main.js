@@ -581,6 +581,8 @@ const NOT_IMPORTED_OPS = [
   "op_jupyter_input",
 
   // Related to `Deno.test()` API
+  "op_test_assert_inline_snapshot",
+  "op_test_assert_snapshot",
   "op_test_event_step_result_failed",
   "op_test_event_step_result_ignored",
   "op_test_event_step_result_ok",
@@ -33,6 +33,7 @@ pub mod fs_util;
 pub mod inspector_server;
 pub mod js;
 pub mod ops;
+pub mod sandbox;
 pub mod snapshot;
 pub mod tokio_util;
 pub mod web_worker;
@@ -95,6 +96,11 @@ pub static UNSTABLE_GRANULAR_FLAGS: &[(
     "Enable unstable process APIs",
     8,
   ),
+  (
+    deno_net::ops_icmp::UNSTABLE_FEATURE_NAME,
+    "Enable unstable raw socket APIs",
+    13,
+  ),
   ("temporal", "Enable unstable Temporal API", 9),
   (
     "unsafe-proto",
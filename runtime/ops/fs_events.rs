@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
 use deno_core::AsyncRefCell;
@@ -13,7 +14,10 @@ use deno_core::ResourceId;
 use deno_core::op2;
 
 use deno_permissions::PermissionsContainer;
+use glob::Pattern;
 use notify::event::Event as NotifyEvent;
+use notify::event::ModifyKind;
+use notify::event::RenameMode;
 use notify::Error as NotifyError;
 use notify::EventKind;
 use notify::RecommendedWatcher;
@@ -23,10 +27,18 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::convert::From;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// How long the first half of a rename is held back waiting for the second
+/// half when events are not debounced.
+const RENAME_PAIR_TIMEOUT: Duration = Duration::from_millis(50);
 
 deno_core::extension!(
   deno_fs_events,
@@ -36,7 +48,7 @@ deno_core::extension!(
 struct FsEventsResource {
   #[allow(unused)]
   watcher: RecommendedWatcher,
-  receiver: AsyncRefCell<mpsc::Receiver<Result<FsEvent, AnyError>>>,
+  receiver: AsyncRefCell<EventQueue>,
   cancel: CancelHandle,
 }
 
@@ -58,7 +70,7 @@ impl Resource for FsEventsResource {
 ///
 /// Feel free to expand this struct as long as you can add tests to demonstrate
 /// the complexity.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, PartialEq)]
 struct FsEvent {
   kind: &'static str,
   paths: Vec<PathBuf>,
@@ -86,10 +98,207 @@ impl From<NotifyEvent> for FsEvent {
   }
 }
 
+impl FsEvent {
+  fn rename(paths: Vec<PathBuf>) -> Self {
+    FsEvent {
+      kind: "rename",
+      paths,
+      flag: None,
+    }
+  }
+}
+
+/// Drops the paths matching any of the `exclude` patterns from events. A
+/// pattern is matched against both the reported path and the path relative to
+/// the watched path it was reported for.
+struct EventFilter {
+  exclude: Vec<Pattern>,
+  roots: Vec<PathBuf>,
+}
+
+impl EventFilter {
+  fn is_excluded(&self, path: &Path) -> bool {
+    self.exclude.iter().any(|pattern| {
+      pattern.matches_path(path)
+        || self.roots.iter().any(|root| {
+          path
+            .strip_prefix(root)
+            .is_ok_and(|relative| pattern.matches_path(relative))
+        })
+    })
+  }
+
+  /// Returns `None` if every path of the event is excluded.
+  fn apply(&self, mut event: NotifyEvent) -> Option<NotifyEvent> {
+    if self.exclude.is_empty() || event.paths.is_empty() {
+      return Some(event);
+    }
+    event.paths.retain(|path| !self.is_excluded(path));
+    if event.paths.is_empty() {
+      None
+    } else {
+      Some(event)
+    }
+  }
+}
+
+/// Turns the raw events of the watcher into the events yielded to JS,
+/// batching them if `debounce` is set and pairing the two halves of renames
+/// if `pair_renames` is set.
+struct EventQueue {
+  receiver: mpsc::Receiver<Result<NotifyEvent, AnyError>>,
+  debounce: Option<Duration>,
+  pair_renames: bool,
+  /// Events that can be yielded right away.
+  ready: VecDeque<FsEvent>,
+  /// Events collected during the current debounce interval, without
+  /// duplicates.
+  batch: Vec<FsEvent>,
+  /// When the current batch or the held back rename half is due.
+  deadline: Option<Instant>,
+  /// The "from" half of a rename, waiting for its "to" half.
+  rename_from: Option<NotifyEvent>,
+  /// The paths of the last paired rename. Some backends report a rename as
+  /// both halves followed by a single event with both paths.
+  last_rename: Option<Vec<PathBuf>>,
+}
+
+impl EventQueue {
+  fn new(
+    receiver: mpsc::Receiver<Result<NotifyEvent, AnyError>>,
+    debounce: Option<Duration>,
+    pair_renames: bool,
+  ) -> Self {
+    Self {
+      receiver,
+      debounce,
+      pair_renames,
+      ready: VecDeque::new(),
+      batch: Vec::new(),
+      deadline: None,
+      rename_from: None,
+      last_rename: None,
+    }
+  }
+
+  async fn next(&mut self) -> Result<Option<FsEvent>, AnyError> {
+    loop {
+      if let Some(event) = self.ready.pop_front() {
+        return Ok(Some(event));
+      }
+      let received = match self.deadline {
+        Some(deadline) => {
+          match tokio::time::timeout_at(deadline, self.receiver.recv()).await {
+            Ok(received) => received,
+            Err(_) => {
+              self.flush();
+              continue;
+            }
+          }
+        }
+        None => self.receiver.recv().await,
+      };
+      match received {
+        Some(Ok(event)) => self.push(event),
+        Some(Err(err)) => return Err(err),
+        None => {
+          self.flush();
+          if self.ready.is_empty() {
+            return Ok(None);
+          }
+        }
+      }
+    }
+  }
+
+  fn push(&mut self, event: NotifyEvent) {
+    if self.pair_renames {
+      if let EventKind::Modify(ModifyKind::Name(mode)) = event.kind {
+        match mode {
+          RenameMode::From => {
+            self.release_rename_from();
+            self.rename_from = Some(event);
+            if self.deadline.is_none() {
+              let timeout = self.debounce.unwrap_or(RENAME_PAIR_TIMEOUT);
+              self.deadline = Some(Instant::now() + timeout);
+            }
+            return;
+          }
+          RenameMode::To => {
+            if let Some(from) = self.rename_from.take() {
+              let tracker = event.attrs.tracker();
+              let from_tracker = from.attrs.tracker();
+              if tracker.is_none()
+                || from_tracker.is_none()
+                || tracker == from_tracker
+              {
+                let mut paths = from.paths;
+                paths.extend(event.paths);
+                self.last_rename = Some(paths.clone());
+                self.emit(FsEvent::rename(paths));
+                return;
+              }
+              self.emit(FsEvent::from(from));
+            }
+          }
+          RenameMode::Both => {
+            self.release_rename_from();
+            if self.last_rename.take().as_ref() == Some(&event.paths) {
+              return;
+            }
+            self.emit(FsEvent::rename(event.paths));
+            return;
+          }
+          _ => {}
+        }
+      }
+      self.release_rename_from();
+    }
+    self.emit(FsEvent::from(event));
+  }
+
+  fn release_rename_from(&mut self) {
+    if let Some(from) = self.rename_from.take() {
+      self.emit(FsEvent::from(from));
+    }
+  }
+
+  fn emit(&mut self, event: FsEvent) {
+    match self.debounce {
+      Some(debounce) => {
+        if !self.batch.contains(&event) {
+          self.batch.push(event);
+        }
+        if self.deadline.is_none() {
+          self.deadline = Some(Instant::now() + debounce);
+        }
+      }
+      None => {
+        self.ready.push_back(event);
+        self.deadline = None;
+      }
+    }
+  }
+
+  /// Yields the current batch, including a rename half that is still waiting
+  /// for its counterpart.
+  fn flush(&mut self) {
+    self.release_rename_from();
+    self.ready.extend(self.batch.drain(..));
+    self.deadline = None;
+  }
+}
+
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OpenArgs {
   recursive: bool,
   paths: Vec<String>,
+  #[serde(default)]
+  exclude: Vec<String>,
+  debounce: Option<u64>,
+  #[serde(default)]
+  pair_renames: bool,
 }
 
 #[op2]
@@ -98,11 +307,30 @@ fn op_fs_events_open(
   state: &mut OpState,
   #[serde] args: OpenArgs,
 ) -> Result<ResourceId, AnyError> {
-  let (sender, receiver) = mpsc::channel::<Result<FsEvent, AnyError>>(16);
+  let exclude = args
+    .exclude
+    .iter()
+    .map(|pattern| {
+      Pattern::new(pattern).map_err(|err| {
+        type_error(format!("Invalid exclude pattern '{pattern}': {err}"))
+      })
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  let filter = EventFilter {
+    exclude,
+    roots: args.paths.iter().map(PathBuf::from).collect(),
+  };
+  let (sender, receiver) = mpsc::channel::<Result<NotifyEvent, AnyError>>(16);
   let sender = Mutex::new(sender);
   let mut watcher: RecommendedWatcher = Watcher::new(
     move |res: Result<NotifyEvent, NotifyError>| {
-      let res2 = res.map(FsEvent::from).map_err(AnyError::from);
+      let res2 = match res {
+        Ok(event) => match filter.apply(event) {
+          Some(event) => Ok(event),
+          None => return,
+        },
+        Err(err) => Err(AnyError::from(err)),
+      };
       let sender = sender.lock();
       // Ignore result, if send failed it means that watcher was already closed,
       // but not all messages have been flushed.
@@ -124,7 +352,11 @@ fn op_fs_events_open(
   }
   let resource = FsEventsResource {
     watcher,
-    receiver: AsyncRefCell::new(receiver),
+    receiver: AsyncRefCell::new(EventQueue::new(
+      receiver,
+      args.debounce.map(Duration::from_millis),
+      args.pair_renames,
+    )),
     cancel: Default::default(),
   };
   let rid = state.resource_table.add(resource);
@@ -140,10 +372,5 @@ async fn op_fs_events_poll(
   let resource = state.borrow().resource_table.get::<FsEventsResource>(rid)?;
   let mut receiver = RcRef::map(&resource, |r| &r.receiver).borrow_mut().await;
   let cancel = RcRef::map(resource, |r| &r.cancel);
-  let maybe_result = receiver.recv().or_cancel(cancel).await?;
-  match maybe_result {
-    Some(Ok(value)) => Ok(Some(value)),
-    Some(Err(err)) => Err(err),
-    None => Ok(None),
-  }
+  receiver.next().or_cancel(cancel).await?
 }
@@ -1,7 +1,9 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use ::deno_permissions::parse_sys_kind;
+use ::deno_permissions::ChildPermissionsArg;
 use ::deno_permissions::PermissionState;
+use ::deno_permissions::Permissions;
 use ::deno_permissions::PermissionsContainer;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
@@ -17,6 +19,7 @@ deno_core::extension!(
     op_query_permission,
     op_revoke_permission,
     op_request_permission,
+    op_create_capability_token,
   ],
 );
 
@@ -89,8 +92,20 @@ pub fn op_query_permission(
 pub fn op_revoke_permission(
   state: &mut OpState,
   #[serde] args: PermissionArgs,
+  propagate: bool,
 ) -> Result<PermissionStatus, AnyError> {
   let mut permissions = state.borrow_mut::<PermissionsContainer>().0.lock();
+  let perm = revoke_permission(&mut permissions, &args, propagate)?;
+  Ok(PermissionStatus::from(perm))
+}
+
+/// Revokes a permission and, if `propagate` is set, also revokes it in the
+/// workers started with their own permissions and in their workers.
+fn revoke_permission(
+  permissions: &mut Permissions,
+  args: &PermissionArgs,
+  propagate: bool,
+) -> Result<PermissionState, AnyError> {
   let path = args.path.as_deref();
   let perm = match args.name.as_ref() {
     "read" => permissions.read.revoke(path.map(Path::new)),
@@ -116,7 +131,12 @@ pub fn op_revoke_permission(
       ))
     }
   };
-  Ok(PermissionStatus::from(perm))
+  if propagate {
+    for worker in permissions.workers.running() {
+      revoke_permission(&mut worker.lock(), args, propagate)?;
+    }
+  }
+  Ok(perm)
 }
 
 #[op2]
@@ -153,3 +173,19 @@ pub fn op_request_permission(
   };
   Ok(PermissionStatus::from(perm))
 }
+
+#[op2]
+#[string]
+pub fn op_create_capability_token(
+  state: &mut OpState,
+  #[serde] permissions: ChildPermissionsArg,
+) -> Result<String, AnyError> {
+  super::check_unstable(
+    state,
+    super::worker_host::UNSTABLE_FEATURE_NAME,
+    "Deno.createCapabilityToken",
+  );
+  state
+    .borrow::<PermissionsContainer>()
+    .create_capability_token(permissions, "Deno.createCapabilityToken")
+}
@@ -1,6 +1,8 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use super::check_unstable;
+#[cfg(unix)]
+use crate::sandbox::KernelSandbox;
 use deno_core::anyhow::Context;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
@@ -17,6 +19,9 @@ use deno_io::fs::FileResource;
 use deno_io::ChildStderrResource;
 use deno_io::ChildStdinResource;
 use deno_io::ChildStdoutResource;
+#[cfg(unix)]
+use deno_permissions::create_child_permissions;
+use deno_permissions::ChildPermissionsArg;
 use deno_permissions::PermissionsContainer;
 use serde::Deserialize;
 use serde::Serialize;
@@ -151,11 +156,28 @@ pub struct SpawnArgs {
   #[cfg(windows)]
   windows_raw_arguments: bool,
   ipc: Option<i32>,
+  sandbox: Option<SpawnSandbox>,
 
   #[serde(flatten)]
   stdio: ChildStdio,
 }
 
+/// Restrictions applied to a subprocess before its program is executed.
+#[derive(Deserialize)]
+#[cfg_attr(not(unix), allow(dead_code))]
+#[serde(rename_all = "camelCase")]
+pub struct SpawnSandbox {
+  /// Enforced with the kernel sandbox, must be a subset of the parent's.
+  permissions: Option<ChildPermissionsArg>,
+  /// CPU time in seconds.
+  cpu: Option<u64>,
+  /// Address space in bytes.
+  memory: Option<u64>,
+  open_files: Option<u64>,
+  #[serde(default)]
+  no_new_privileges: bool,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChildStdio {
@@ -222,9 +244,15 @@ fn create_command(
   mut args: SpawnArgs,
   api_name: &str,
 ) -> Result<CreateCommand, AnyError> {
-  state
-    .borrow_mut::<PermissionsContainer>()
-    .check_run(&args.cmd, api_name)?;
+  let permissions = state.borrow_mut::<PermissionsContainer>();
+  permissions.check_run(&args.cmd, api_name)?;
+  permissions.check_run_arguments(
+    &args.cmd,
+    &args.args,
+    &args.env,
+    args.cwd.as_deref(),
+    api_name,
+  )?;
 
   let mut command = std::process::Command::new(args.cmd);
 
@@ -249,6 +277,11 @@ fn create_command(
   }
   command.envs(args.env);
 
+  if let Some(sandbox) = args.sandbox {
+    check_unstable(state, UNSTABLE_FEATURE_NAME, "Deno.Command.sandbox");
+    sandbox_command(state, &mut command, sandbox)?;
+  }
+
   #[cfg(unix)]
   if let Some(gid) = args.gid {
     command.gid(gid);
@@ -487,6 +520,113 @@ fn create_command(
   return Ok((command, None));
 }
 
+#[cfg(unix)]
+fn sandbox_command(
+  state: &mut OpState,
+  command: &mut std::process::Command,
+  sandbox: SpawnSandbox,
+) -> Result<(), AnyError> {
+  let kernel_sandbox = match sandbox.permissions {
+    Some(child_permissions_arg) => {
+      if child_permissions_arg.sets_sys_or_ffi() {
+        return Err(type_error(
+          "The sys and ffi permissions can't be restricted for a subprocess",
+        ));
+      }
+      let permissions = create_child_permissions(
+        &mut state.borrow::<PermissionsContainer>().0.lock(),
+        child_permissions_arg,
+      )?;
+      // only the granted environment variables are passed on, whether they
+      // are inherited or set for the subprocess
+      let removed_env = std::env::vars_os()
+        .map(|(key, _)| key)
+        .chain(command.get_envs().map(|(key, _)| key.to_owned()))
+        .filter(|key| {
+          key.to_str().map_or(true, |key| {
+            permissions.env.query(Some(key))
+              != deno_permissions::PermissionState::Granted
+          })
+        })
+        .collect::<Vec<_>>();
+      for key in removed_env {
+        command.env_remove(key);
+      }
+      let program = resolve_program(command)?;
+      Some(KernelSandbox::for_subprocess(&permissions, &program)?.prepare()?)
+    }
+    None => None,
+  };
+  if sandbox.no_new_privileges && !cfg!(target_os = "linux") {
+    return Err(type_error(
+      "The noNewPrivileges sandbox option is only supported on Linux",
+    ));
+  }
+  let no_new_privileges = sandbox.no_new_privileges;
+  let limits = [
+    (libc::RLIMIT_CPU, sandbox.cpu),
+    (libc::RLIMIT_AS, sandbox.memory),
+    (libc::RLIMIT_NOFILE, sandbox.open_files),
+  ];
+
+  // SAFETY: the closure only makes syscalls and doesn't allocate, which is
+  // required between `fork` and `exec`.
+  unsafe {
+    command.pre_exec(move || {
+      for (resource, limit) in limits {
+        let Some(limit) = limit else {
+          continue;
+        };
+        let rlimit = libc::rlimit {
+          rlim_cur: limit as libc::rlim_t,
+          rlim_max: limit as libc::rlim_t,
+        };
+        if libc::setrlimit(resource, &rlimit) != 0 {
+          return Err(std::io::Error::last_os_error());
+        }
+      }
+      #[cfg(target_os = "linux")]
+      if no_new_privileges
+        && libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0
+      {
+        return Err(std::io::Error::last_os_error());
+      }
+      if let Some(kernel_sandbox) = &kernel_sandbox {
+        kernel_sandbox.restrict()?;
+      }
+      Ok(())
+    });
+  }
+  Ok(())
+}
+
+#[cfg(not(unix))]
+fn sandbox_command(
+  _state: &mut OpState,
+  _command: &mut std::process::Command,
+  _sandbox: SpawnSandbox,
+) -> Result<(), AnyError> {
+  Err(type_error(
+    "Sandboxing subprocesses is not supported on Windows",
+  ))
+}
+
+/// Finds the file that will be executed for `command`, like `execvp` does.
+#[cfg(unix)]
+fn resolve_program(
+  command: &std::process::Command,
+) -> Result<std::path::PathBuf, AnyError> {
+  let program = std::path::Path::new(command.get_program());
+  if program.components().count() > 1 {
+    return Ok(match command.get_current_dir() {
+      Some(cwd) => cwd.join(program),
+      None => program.to_path_buf(),
+    });
+  }
+  which::which(program)
+    .with_context(|| format!("Failed to find '{}'", program.display()))
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Child {
@@ -714,11 +854,17 @@ mod deprecated {
     #[serde] run_args: RunArgs,
   ) -> Result<RunInfo, AnyError> {
     let args = run_args.cmd;
-    state
-      .borrow_mut::<PermissionsContainer>()
-      .check_run(&args[0], "Deno.run()")?;
+    let permissions = state.borrow_mut::<PermissionsContainer>();
+    permissions.check_run(&args[0], "Deno.run()")?;
     let env = run_args.env;
     let cwd = run_args.cwd;
+    permissions.check_run_arguments(
+      &args[0],
+      &args[1..],
+      &env,
+      cwd.as_deref(),
+      "Deno.run()",
+    )?;
 
     let mut c = Command::new(args.first().unwrap());
     (1..args.len()).for_each(|i| {
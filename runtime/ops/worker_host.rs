@@ -113,6 +113,9 @@ pub struct CreateWorkerArgs {
   has_source_code: bool,
   name: Option<String>,
   permissions: Option<ChildPermissionsArg>,
+  /// A token from `Deno.createCapabilityToken()`, passed instead of
+  /// `permissions`.
+  permissions_token: Option<String>,
   source_code: String,
   specifier: String,
   worker_type: WebWorkerType,
@@ -146,7 +149,7 @@ fn op_create_worker(
     }
   }
 
-  if args.permissions.is_some() {
+  if args.permissions.is_some() || args.permissions_token.is_some() {
     super::check_unstable(
       state,
       UNSTABLE_FEATURE_NAME,
@@ -154,12 +157,20 @@ fn op_create_worker(
     );
   }
   let parent_permissions = state.borrow_mut::<PermissionsContainer>();
-  let worker_permissions = if let Some(child_permissions_arg) = args.permissions
-  {
-    let mut parent_permissions = parent_permissions.0.lock();
-    let perms =
-      create_child_permissions(&mut parent_permissions, child_permissions_arg)?;
-    PermissionsContainer::new(perms)
+  let permissions = match args.permissions_token {
+    Some(token) => {
+      Some(parent_permissions.redeem_capability_token(&token, "new Worker()")?)
+    }
+    None => args.permissions,
+  };
+  let worker_permissions = if let Some(child_permissions_arg) = permissions {
+    let perms = create_child_permissions(
+      &mut parent_permissions.0.lock(),
+      child_permissions_arg,
+    )?;
+    let worker_permissions = PermissionsContainer::new(perms);
+    parent_permissions.add_worker(&worker_permissions);
+    worker_permissions
   } else {
     parent_permissions.clone()
   };
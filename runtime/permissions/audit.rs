@@ -0,0 +1,83 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use once_cell::sync::Lazy;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::LineWriter;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+static PERMISSION_AUDIT_LOG: Lazy<Mutex<Option<LineWriter<File>>>> =
+  Lazy::new(|| Mutex::new(None));
+
+/// Checked before taking the lock so that permission checks stay cheap when
+/// no audit log was requested.
+static PERMISSION_AUDIT_LOG_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Appends a JSON line to the file at `path` for every permission check made
+/// through a `PermissionsContainer` from now on.
+pub fn set_audit_log(path: &Path) -> Result<(), AnyError> {
+  let file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .with_context(|| {
+      format!("Failed to open permission audit log '{}'", path.display())
+    })?;
+  *PERMISSION_AUDIT_LOG.lock() = Some(LineWriter::new(file));
+  PERMISSION_AUDIT_LOG_ENABLED.store(true, Ordering::Relaxed);
+  Ok(())
+}
+
+/// Records the outcome of a permission check and passes it through.
+/// `descriptor` is only evaluated when the audit log is enabled.
+#[inline]
+pub(crate) fn record(
+  permission: &str,
+  api_name: Option<&str>,
+  descriptor: impl FnOnce() -> Option<String>,
+  result: Result<(), AnyError>,
+) -> Result<(), AnyError> {
+  if PERMISSION_AUDIT_LOG_ENABLED.load(Ordering::Relaxed) {
+    write_entry(permission, api_name, descriptor(), result.is_ok());
+  }
+  result
+}
+
+fn write_entry(
+  permission: &str,
+  api_name: Option<&str>,
+  descriptor: Option<String>,
+  granted: bool,
+) {
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default();
+  let thread = std::thread::current();
+  let entry = json!({
+    "timestamp": timestamp,
+    "permission": permission,
+    "descriptor": descriptor,
+    "decision": if granted { "granted" } else { "denied" },
+    "api": api_name,
+    "thread": thread.name(),
+  });
+  let Ok(mut line) = serde_json::to_vec(&entry) else {
+    return;
+  };
+  line.push(b'\n');
+  if let Some(log) = PERMISSION_AUDIT_LOG.lock().as_mut() {
+    // a failing audit log must not change the outcome of the check
+    let _ = log.write_all(&line);
+  }
+}
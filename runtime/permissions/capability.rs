@@ -0,0 +1,107 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Capability tokens, which carry a subset of the permissions of the code
+//! that minted them to a worker. A token is the prefix, the claims as base64
+//! encoded JSON and an HMAC of the claims. The signing key is random for
+//! every process, so tokens can't be forged from JavaScript and are only
+//! valid in the process that minted them.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use once_cell::sync::Lazy;
+use ring::hmac;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+use std::path::Path;
+
+use super::create_child_permissions;
+use super::resolve_from_cwd;
+use super::ChildPermissionsArg;
+use super::ChildUnaryPermissionArg;
+use super::Permissions;
+
+/// Keep in sync with `runtime/js/11_workers.js`.
+const TOKEN_PREFIX: &str = "denocap.";
+
+static SIGNING_KEY: Lazy<hmac::Key> = Lazy::new(|| {
+  hmac::Key::generate(hmac::HMAC_SHA256, &SystemRandom::new())
+    .expect("Failed to generate capability token signing key")
+});
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+  /// Identifies the token in the permission audit log.
+  id: String,
+  permissions: ChildPermissionsArg,
+}
+
+pub(crate) struct CapabilityToken {
+  pub id: String,
+  pub token: String,
+}
+
+/// Mints a token for `arg`, which has to be a subset of `permissions`.
+pub(crate) fn mint(
+  permissions: &mut Permissions,
+  mut arg: ChildPermissionsArg,
+) -> Result<CapabilityToken, AnyError> {
+  // relative paths mean the same when the token is redeemed after a chdir
+  for list in [&mut arg.read, &mut arg.write, &mut arg.ffi] {
+    if let ChildUnaryPermissionArg::GrantedList(paths) = list {
+      for path in paths.iter_mut() {
+        *path = resolve_from_cwd(Path::new(path))?
+          .to_string_lossy()
+          .into_owned();
+      }
+    }
+  }
+  // only minted when the permissions could be granted right now
+  create_child_permissions(permissions, arg.clone())?;
+
+  let mut id = [0u8; 16];
+  SystemRandom::new()
+    .fill(&mut id)
+    .map_err(|_| custom_error("Error", "Failed to generate token id"))?;
+  let claims = Claims {
+    id: id.iter().map(|byte| format!("{byte:02x}")).collect(),
+    permissions: arg,
+  };
+  let claims_json = serde_json::to_vec(&claims)?;
+  let signature = hmac::sign(&SIGNING_KEY, &claims_json);
+  Ok(CapabilityToken {
+    token: format!(
+      "{TOKEN_PREFIX}{}.{}",
+      URL_SAFE_NO_PAD.encode(&claims_json),
+      URL_SAFE_NO_PAD.encode(signature.as_ref())
+    ),
+    id: claims.id,
+  })
+}
+
+/// Verifies `token` and returns its id and the permissions it carries.
+pub(crate) fn redeem(
+  token: &str,
+) -> Result<(String, ChildPermissionsArg), AnyError> {
+  let invalid_token =
+    || custom_error("PermissionDenied", "Invalid capability token");
+  let (claims, signature) = token
+    .strip_prefix(TOKEN_PREFIX)
+    .and_then(|token| token.split_once('.'))
+    .ok_or_else(invalid_token)?;
+  let claims_json = URL_SAFE_NO_PAD
+    .decode(claims)
+    .map_err(|_| invalid_token())?;
+  let signature = URL_SAFE_NO_PAD
+    .decode(signature)
+    .map_err(|_| invalid_token())?;
+  hmac::verify(&SIGNING_KEY, &claims_json, &signature)
+    .map_err(|_| invalid_token())?;
+  let claims: Claims =
+    serde_json::from_slice(&claims_json).map_err(|_| invalid_token())?;
+  Ok((claims.id, claims.permissions))
+}
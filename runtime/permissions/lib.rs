@@ -11,6 +11,7 @@ use deno_core::serde::de;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Deserializer;
 use deno_core::serde::Serialize;
+use deno_core::serde::Serializer;
 use deno_core::serde_json;
 use deno_core::url;
 use deno_core::url::Url;
@@ -25,19 +26,27 @@ use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::net::IpAddr;
+use std::net::Ipv4Addr;
 use std::net::Ipv6Addr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::string::ToString;
 use std::sync::Arc;
+use std::sync::Weak;
+use std::time::Instant;
 use which::which;
 
+mod audit;
+mod capability;
 pub mod prompter;
+mod wx;
 use prompter::permission_prompt;
 use prompter::PromptResponse;
 use prompter::PERMISSION_EMOJI;
+use prompter::TEMPORARY_GRANT_DURATION;
 
+pub use audit::set_audit_log;
 pub use prompter::set_prompt_callbacks;
 pub use prompter::PromptCallback;
 
@@ -142,7 +151,7 @@ impl PermissionState {
     )
   }
 
-  /// Check the permission state. bool is whether a prompt was issued.
+  /// Check the permission state. The response is set if a prompt was issued.
   #[inline]
   fn check(
     self,
@@ -150,7 +159,7 @@ impl PermissionState {
     api_name: Option<&str>,
     info: Option<&str>,
     prompt: bool,
-  ) -> (Result<(), AnyError>, bool, bool) {
+  ) -> (Result<(), AnyError>, Option<PromptResponse>) {
     self.check2(name, api_name, || info.map(|s| s.to_string()), prompt)
   }
 
@@ -161,11 +170,11 @@ impl PermissionState {
     api_name: Option<&str>,
     info: impl Fn() -> Option<String>,
     prompt: bool,
-  ) -> (Result<(), AnyError>, bool, bool) {
+  ) -> (Result<(), AnyError>, Option<PromptResponse>) {
     match self {
       PermissionState::Granted => {
         Self::log_perm_access(name, info);
-        (Ok(()), false, false)
+        (Ok(()), None)
       }
       PermissionState::Prompt if prompt => {
         let msg = format!(
@@ -175,19 +184,15 @@ impl PermissionState {
             .map(|info| { format!(" to {info}") })
             .unwrap_or_default(),
         );
-        match permission_prompt(&msg, name, api_name, true) {
-          PromptResponse::Allow => {
-            Self::log_perm_access(name, info);
-            (Ok(()), true, false)
-          }
-          PromptResponse::AllowAll => {
-            Self::log_perm_access(name, info);
-            (Ok(()), true, true)
-          }
-          PromptResponse::Deny => (Err(Self::error(name, info)), true, false),
+        let response = permission_prompt(&msg, name, api_name, true);
+        if response == PromptResponse::Deny {
+          (Err(Self::error(name, info)), Some(response))
+        } else {
+          Self::log_perm_access(name, info);
+          (Ok(()), Some(response))
         }
       }
-      _ => (Err(Self::error(name, info)), false, false),
+      _ => (Err(Self::error(name, info)), None),
     }
   }
 }
@@ -242,9 +247,9 @@ impl UnitPermission {
   }
 
   pub fn check(&mut self) -> Result<(), AnyError> {
-    let (result, prompted, _is_allow_all) =
+    let (result, response) =
       self.state.check(self.name, None, None, self.prompt);
-    if prompted {
+    if response.is_some() {
       if result.is_ok() {
         self.state = PermissionState::Granted;
       } else {
@@ -339,6 +344,9 @@ pub struct UnaryPermission<T: Descriptor + Hash> {
   flag_denied_list: HashSet<T>,
   prompt_denied_global: bool,
   prompt_denied_list: HashSet<T>,
+  /// Grants made with [`PromptResponse::AllowTemporarily`] and when they
+  /// expire. `None` stands for all descriptors.
+  temporary_grants: Vec<(Option<T>, Instant)>,
   prompt: bool,
 }
 
@@ -347,6 +355,7 @@ impl<T: Descriptor + Hash> Default for UnaryPermission<T> {
     UnaryPermission {
       granted_global: Default::default(),
       granted_list: Default::default(),
+      temporary_grants: Default::default(),
       flag_denied_global: Default::default(),
       flag_denied_list: Default::default(),
       prompt_denied_global: Default::default(),
@@ -370,6 +379,16 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
       && self.prompt_denied_list.is_empty()
   }
 
+  /// The granted descriptors, or `None` if the whole permission is granted.
+  pub fn granted(&self) -> Option<&HashSet<T>> {
+    (!self.granted_global).then_some(&self.granted_list)
+  }
+
+  /// The descriptors denied with a `--deny-*` flag.
+  pub fn flag_denied(&self) -> &HashSet<T> {
+    &self.flag_denied_list
+  }
+
   pub fn check_all_api(
     &mut self,
     api_name: Option<&str>,
@@ -386,7 +405,8 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
     get_display_name: impl Fn() -> Option<String>,
   ) -> Result<(), AnyError> {
     skip_check_if_is_permission_fully_granted!(self);
-    let (result, prompted, is_allow_all) = self
+    self.revoke_expired_grants();
+    let (result, response) = self
       .query_desc(desc, AllowPartial::from(!assert_non_partial))
       .check2(
         T::flag_name(),
@@ -397,16 +417,16 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
         },
         self.prompt,
       );
-    if prompted {
-      if result.is_ok() {
-        if is_allow_all {
-          self.insert_granted(None);
-        } else {
-          self.insert_granted(desc.cloned());
-        }
-      } else {
-        self.insert_prompt_denied(desc.cloned());
-      }
+    match response {
+      Some(PromptResponse::Allow) => self.insert_granted(desc.cloned()),
+      Some(PromptResponse::AllowAll) => self.insert_granted(None),
+      Some(PromptResponse::AllowTemporarily) => self
+        .insert_temporarily_granted(
+          desc.cloned(),
+          Instant::now() + TEMPORARY_GRANT_DURATION,
+        ),
+      Some(PromptResponse::Deny) => self.insert_prompt_denied(desc.cloned()),
+      None => {}
     }
     result
   }
@@ -462,7 +482,10 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
   ) -> PermissionState {
     let state = self.query_desc(desc, AllowPartial::TreatAsPartialGranted);
     if state == PermissionState::Granted {
-      self.insert_granted(desc.cloned());
+      // don't turn a temporary grant into a lasting one
+      if !self.is_temporarily_granted(desc) {
+        self.insert_granted(desc.cloned());
+      }
       return state;
     }
     if state != PermissionState::Prompt {
@@ -498,6 +521,13 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
         self.insert_granted(None);
         PermissionState::Granted
       }
+      PromptResponse::AllowTemporarily => {
+        self.insert_temporarily_granted(
+          desc.cloned(),
+          Instant::now() + TEMPORARY_GRANT_DURATION,
+        );
+        PermissionState::Granted
+      }
     }
   }
 
@@ -508,6 +538,14 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
         for alias in desc.aliases() {
           self.granted_list.retain(|v| !v.stronger_than(&alias));
         }
+        let aliases = desc.aliases();
+        self.temporary_grants.retain(|(v, _)| match v {
+          Some(v) => {
+            !v.stronger_than(desc)
+              && !aliases.iter().any(|alias| v.stronger_than(alias))
+          }
+          None => true,
+        });
       }
       None => {
         self.granted_global = false;
@@ -515,6 +553,7 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
         // cleared. It's inconsistent with the granular case where only
         // descriptors stronger than the revoked one are purged.
         self.granted_list.clear();
+        self.temporary_grants.clear();
       }
     }
     self.query_desc(desc, AllowPartial::TreatAsPartialGranted)
@@ -522,6 +561,20 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
 
   fn is_granted(&self, desc: Option<&T>) -> bool {
     Self::list_contains(desc, self.granted_global, &self.granted_list)
+      || self.is_temporarily_granted(desc)
+  }
+
+  fn is_temporarily_granted(&self, desc: Option<&T>) -> bool {
+    let now = Instant::now();
+    self
+      .temporary_grants
+      .iter()
+      .filter(|(_, expires)| *expires > now)
+      .any(|(granted, _)| match (granted, desc) {
+        (None, _) => true,
+        (Some(granted), Some(desc)) => granted.stronger_than(desc),
+        (Some(_), None) => false,
+      })
   }
 
   fn is_flag_denied(&self, desc: Option<&T>) -> bool {
@@ -560,6 +613,21 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
     Self::list_insert(desc, &mut self.granted_global, &mut self.granted_list);
   }
 
+  fn insert_temporarily_granted(&mut self, desc: Option<T>, expires: Instant) {
+    let aliases = desc.as_ref().map_or(vec![], T::aliases);
+    self.temporary_grants.push((desc, expires));
+    for alias in aliases {
+      self.temporary_grants.push((Some(alias), expires));
+    }
+  }
+
+  /// Drops grants made with [`PromptResponse::AllowTemporarily`] once their
+  /// time is up, so the next check prompts again.
+  fn revoke_expired_grants(&mut self) {
+    let now = Instant::now();
+    self.temporary_grants.retain(|(_, expires)| *expires > now);
+  }
+
   fn insert_prompt_denied(&mut self, desc: Option<T>) {
     Self::list_insert(
       desc,
@@ -691,10 +759,224 @@ impl Descriptor for WriteDescriptor {
   }
 }
 
+/// A kind of write that `--allow-write=<path>:<operation>,...` can allow on
+/// its own, ex. `--allow-write=logs/:create,append` to allow creating and
+/// appending to log files but not deleting or overwriting them.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum WriteOperation {
+  Create,
+  Append,
+  Modify,
+  Delete,
+  Rename,
+  Chmod,
+}
+
+impl WriteOperation {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      WriteOperation::Create => "create",
+      WriteOperation::Append => "append",
+      WriteOperation::Modify => "modify",
+      WriteOperation::Delete => "delete",
+      WriteOperation::Rename => "rename",
+      WriteOperation::Chmod => "chmod",
+    }
+  }
+}
+
+impl FromStr for WriteOperation {
+  type Err = AnyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "create" => Ok(WriteOperation::Create),
+      "append" => Ok(WriteOperation::Append),
+      "modify" => Ok(WriteOperation::Modify),
+      "delete" => Ok(WriteOperation::Delete),
+      "rename" => Ok(WriteOperation::Rename),
+      "chmod" => Ok(WriteOperation::Chmod),
+      _ => Err(type_error(format!("invalid write operation: '{s}'"))),
+    }
+  }
+}
+
+/// Splits a `--allow-write` entry of the form `<path>:<operation>,...` into
+/// the path and its operations. Returns `None` for plain paths, including
+/// Windows paths with a drive letter.
+pub fn split_write_operations(
+  entry: &str,
+) -> Option<(&str, HashSet<WriteOperation>)> {
+  let (path, operations) = entry.rsplit_once(':')?;
+  let operations = operations
+    .split(',')
+    .map(WriteOperation::from_str)
+    .collect::<Result<HashSet<_>, _>>()
+    .ok()?;
+  Some((path, operations))
+}
+
+/// The write operations allowed under path prefixes given as
+/// `--allow-write=<path>:<operation>,...`. Writes outside these prefixes are
+/// only subject to the write permission of the path.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WriteOperations(Vec<(PathBuf, HashSet<WriteOperation>)>);
+
+impl WriteOperations {
+  /// Removes the operations from the entries of `allow_write`, and returns
+  /// the restrictions they describe.
+  fn extract(
+    allow_write: &Option<Vec<PathBuf>>,
+  ) -> Result<(Option<Vec<PathBuf>>, Self), AnyError> {
+    let Some(allow_write) = allow_write else {
+      return Ok((None, Self::default()));
+    };
+    let mut paths = Vec::with_capacity(allow_write.len());
+    let mut restrictions = Vec::new();
+    for entry in allow_write {
+      match entry.to_str().and_then(split_write_operations) {
+        Some((path, operations)) => {
+          let path = PathBuf::from(path);
+          if path.as_os_str().is_empty() {
+            return Err(AnyError::msg("Empty path is not allowed"));
+          }
+          restrictions.push((resolve_from_cwd(&path)?, operations));
+          paths.push(path);
+        }
+        None => paths.push(entry.clone()),
+      }
+    }
+    Ok((Some(paths), Self(restrictions)))
+  }
+
+  pub fn check(
+    &self,
+    path: &Path,
+    operation: WriteOperation,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    if self.0.is_empty() {
+      return Ok(());
+    }
+    let path = resolve_from_cwd(path)?;
+    // the most specific prefix decides
+    let allowed = self
+      .0
+      .iter()
+      .filter(|(prefix, _)| path.starts_with(prefix))
+      .max_by_key(|(prefix, _)| prefix.components().count())
+      .map_or(true, |(_, operations)| operations.contains(&operation));
+    if allowed {
+      return Ok(());
+    }
+    Err(custom_error(
+      "PermissionDenied",
+      format!(
+        "Requires write access to \"{}\" for the {} operation{}, run again with the --allow-write flag",
+        path.display(),
+        operation.as_str(),
+        api_name.map(|name| format!(" in {name}")).unwrap_or_default(),
+      ),
+    ))
+  }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Host {
   Fqdn(FQDN),
   Ip(IpAddr),
+  /// A network given as `<address>/<prefix length>`, ex. `10.0.0.0/8`. The
+  /// address has its host bits cleared.
+  ///
+  /// Only matches hosts given as IP addresses: hostnames aren't resolved, so
+  /// a hostname that resolves to an address in the network isn't matched.
+  Cidr(IpAddr, u8),
+  /// All subdomains of a domain, given as `*.<domain>`.
+  Wildcard(FQDN),
+  /// A Unix domain socket, given as `unix:<path>`. A directory covers every
+  /// socket below it.
+  Unix(PathBuf),
+}
+
+impl Host {
+  /// Whether every host matched by `other` is also matched by `self`.
+  fn contains(&self, other: &Host) -> bool {
+    match (self, other) {
+      (Host::Cidr(network, prefix), Host::Ip(ip)) => {
+        cidr_contains(network, *prefix, ip)
+      }
+      (Host::Cidr(network, prefix), Host::Cidr(other, other_prefix)) => {
+        network.is_ipv4() == other.is_ipv4()
+          && prefix <= other_prefix
+          && cidr_contains(network, *prefix, other)
+      }
+      (Host::Ip(ip), Host::Ip(other)) => {
+        canonical_ip(ip) == canonical_ip(other)
+      }
+      (Host::Wildcard(domain), Host::Fqdn(fqdn) | Host::Wildcard(fqdn)) => {
+        let (fqdn, domain) = (fqdn.to_string(), domain.to_string());
+        fqdn
+          .strip_suffix(&domain)
+          .is_some_and(|subdomain| subdomain.ends_with('.'))
+      }
+      (Host::Unix(dir), Host::Unix(path)) => path.starts_with(dir),
+      _ => self == other,
+    }
+  }
+
+  fn parse_cidr(s: &str) -> Result<Self, AnyError> {
+    let invalid = || uri_error(format!("invalid CIDR range: '{s}'"));
+    let (ip, prefix) = s.split_once('/').ok_or_else(invalid)?;
+    let ip = ip.parse::<IpAddr>().map_err(|_| invalid())?;
+    let prefix = prefix.parse::<u8>().map_err(|_| invalid())?;
+    let bits = if ip.is_ipv4() { 32 } else { 128 };
+    if prefix > bits {
+      return Err(invalid());
+    }
+    Ok(Host::Cidr(cidr_network(&ip, prefix), prefix))
+  }
+}
+
+fn cidr_network(ip: &IpAddr, prefix: u8) -> IpAddr {
+  match ip {
+    IpAddr::V4(ip) => {
+      let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+      IpAddr::V4(Ipv4Addr::from(u32::from(*ip) & mask))
+    }
+    IpAddr::V6(ip) => {
+      let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+      IpAddr::V6(Ipv6Addr::from(u128::from(*ip) & mask))
+    }
+  }
+}
+
+/// The IPv4 address that an IPv4-mapped (`::ffff:a.b.c.d`) or
+/// IPv4-compatible (`::a.b.c.d`) IPv6 address designates, so that they can't
+/// be used to get around the IPv4 addresses in the permissions.
+fn embedded_ipv4(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+  if ip.is_loopback() || ip.is_unspecified() {
+    return None;
+  }
+  ip.to_ipv4()
+}
+
+fn canonical_ip(ip: &IpAddr) -> IpAddr {
+  match ip {
+    IpAddr::V6(v6) => embedded_ipv4(v6).map_or(*ip, IpAddr::V4),
+    IpAddr::V4(_) => *ip,
+  }
+}
+
+fn cidr_contains(network: &IpAddr, prefix: u8, ip: &IpAddr) -> bool {
+  let ip = match (network, ip) {
+    (IpAddr::V4(_), IpAddr::V6(ip)) => match embedded_ipv4(ip) {
+      Some(ip) => IpAddr::V4(ip),
+      None => return false,
+    },
+    (IpAddr::V6(_), IpAddr::V4(ip)) => IpAddr::V6(ip.to_ipv6_mapped()),
+    _ => *ip,
+  };
+  cidr_network(&ip, prefix) == *network
 }
 
 impl FromStr for Host {
@@ -702,11 +984,24 @@ impl FromStr for Host {
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     if s.starts_with('[') && s.ends_with(']') {
-      let ip = s[1..s.len() - 1]
+      let inner = &s[1..s.len() - 1];
+      if inner.contains('/') {
+        return Host::parse_cidr(inner);
+      }
+      let ip = inner
         .parse::<Ipv6Addr>()
         .map_err(|_| uri_error(format!("invalid IPv6 address: '{s}'")))?;
       return Ok(Host::Ip(IpAddr::V6(ip)));
     }
+    if s.contains('/') {
+      return Host::parse_cidr(s);
+    }
+    if let Some(domain) = s.strip_prefix("*.") {
+      return match domain.parse::<Host>()? {
+        Host::Fqdn(fqdn) => Ok(Host::Wildcard(fqdn)),
+        _ => Err(uri_error(format!("invalid host: '{s}'"))),
+      };
+    }
     let (without_trailing_dot, has_trailing_dot) =
       s.strip_suffix('.').map_or((s, false), |s| (s, true));
     if let Ok(ip) = without_trailing_dot.parse::<IpAddr>() {
@@ -732,8 +1027,46 @@ impl FromStr for Host {
   }
 }
 
+/// An inclusive range of ports. A single port is a range of one.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct PortRange {
+  pub start: u16,
+  pub end: u16,
+}
+
+impl PortRange {
+  fn contains(&self, other: &PortRange) -> bool {
+    self.start <= other.start && other.end <= self.end
+  }
+}
+
+impl From<u16> for PortRange {
+  fn from(port: u16) -> Self {
+    Self {
+      start: port,
+      end: port,
+    }
+  }
+}
+
+impl fmt::Display for PortRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.start == self.end {
+      write!(f, "{}", self.start)
+    } else {
+      write!(f, "{}-{}", self.start, self.end)
+    }
+  }
+}
+
+/// Returns the socket path of a `unix:<path>` entry of `--allow-net`.
+pub fn unix_socket_path(entry: &str) -> Option<&Path> {
+  entry.strip_prefix("unix:").map(Path::new)
+}
+
+/// A host and the ports allowed on it, where no ports means any port.
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
-pub struct NetDescriptor(pub Host, pub Option<u16>);
+pub struct NetDescriptor(pub Host, pub Option<PortRange>);
 
 impl Descriptor for NetDescriptor {
   type Arg = String;
@@ -760,31 +1093,68 @@ impl Descriptor for NetDescriptor {
   }
 
   fn stronger_than(&self, other: &Self) -> bool {
-    self.0 == other.0 && (self.1.is_none() || self.1 == other.1)
+    self.0.contains(&other.0)
+      && match (self.1, other.1) {
+        (None, _) => true,
+        (Some(ports), Some(other_ports)) => ports.contains(&other_ports),
+        (Some(_), None) => false,
+      }
+  }
+}
+
+/// Parses the port of a net descriptor, which is a port number, a range like
+/// `8000-8100`, or `any`.
+fn parse_net_port(
+  hostname: &str,
+  port: &str,
+) -> Result<Option<PortRange>, AnyError> {
+  if port == "any" {
+    return Ok(None);
+  }
+  let invalid = || uri_error(format!("invalid port in '{hostname}': '{port}'"));
+  let range = match port.split_once('-') {
+    Some((start, end)) => PortRange {
+      start: start.parse().map_err(|_| invalid())?,
+      end: end.parse().map_err(|_| invalid())?,
+    },
+    None => PortRange::from(port.parse::<u16>().map_err(|_| invalid())?),
+  };
+  if range.start > range.end {
+    return Err(invalid());
   }
+  Ok(Some(range))
 }
 
 impl FromStr for NetDescriptor {
   type Err = AnyError;
 
   fn from_str(hostname: &str) -> Result<Self, Self::Err> {
+    if let Some(path) = unix_socket_path(hostname) {
+      if path.as_os_str().is_empty() {
+        return Err(uri_error(format!("invalid empty path in '{hostname}'")));
+      }
+      return Ok(NetDescriptor(Host::Unix(resolve_from_cwd(path)?), None));
+    }
+
     // If this is a IPv6 address enclosed in square brackets, parse it as such.
     if hostname.starts_with('[') {
       if let Some((ip, after)) = hostname.split_once(']') {
-        let ip = ip[1..].parse::<Ipv6Addr>().map_err(|_| {
-          uri_error(format!("invalid IPv6 address in '{hostname}': '{ip}'"))
-        })?;
-        let port = if let Some(port) = after.strip_prefix(':') {
-          let port = port.parse::<u16>().map_err(|_| {
-            uri_error(format!("invalid port in '{hostname}': '{port}'"))
+        let host = if ip.contains('/') {
+          Host::parse_cidr(&ip[1..])?
+        } else {
+          let ip = ip[1..].parse::<Ipv6Addr>().map_err(|_| {
+            uri_error(format!("invalid IPv6 address in '{hostname}': '{ip}'"))
           })?;
-          Some(port)
+          Host::Ip(IpAddr::V6(ip))
+        };
+        let port = if let Some(port) = after.strip_prefix(':') {
+          parse_net_port(hostname, port)?
         } else if after.is_empty() {
           None
         } else {
           return Err(uri_error(format!("invalid host: '{hostname}'")));
         };
-        return Ok(NetDescriptor(Host::Ip(IpAddr::V6(ip)), port));
+        return Ok(NetDescriptor(host, port));
       } else {
         return Err(uri_error(format!("invalid host: '{hostname}'")));
       }
@@ -803,19 +1173,15 @@ impl FromStr for NetDescriptor {
     let port = if port.is_empty() {
       None
     } else {
-      let port = port.parse::<u16>().map_err(|_| {
-        // If the user forgot to enclose an IPv6 address in square brackets, we
-        // should give them a hint. There are always at least two colons in an
-        // IPv6 address, so this heuristic finds likely a bare IPv6 address.
-        if port.contains(':') {
-          uri_error(format!(
-            "ipv6 addresses must be enclosed in square brackets: '{hostname}'"
-          ))
-        } else {
-          uri_error(format!("invalid port in '{hostname}': '{port}'"))
-        }
-      })?;
-      Some(port)
+      // If the user forgot to enclose an IPv6 address in square brackets, we
+      // should give them a hint. There are always at least two colons in an
+      // IPv6 address, so this heuristic finds likely a bare IPv6 address.
+      if port.contains(':') {
+        return Err(uri_error(format!(
+          "ipv6 addresses must be enclosed in square brackets: '{hostname}'"
+        )));
+      }
+      parse_net_port(hostname, port)?
     };
 
     Ok(NetDescriptor(host, port))
@@ -828,6 +1194,10 @@ impl fmt::Display for NetDescriptor {
       Host::Fqdn(fqdn) => write!(f, "{fqdn}"),
       Host::Ip(IpAddr::V4(ip)) => write!(f, "{ip}"),
       Host::Ip(IpAddr::V6(ip)) => write!(f, "[{ip}]"),
+      Host::Cidr(IpAddr::V4(ip), prefix) => write!(f, "{ip}/{prefix}"),
+      Host::Cidr(IpAddr::V6(ip), prefix) => write!(f, "[{ip}/{prefix}]"),
+      Host::Wildcard(fqdn) => write!(f, "*.{fqdn}"),
+      Host::Unix(path) => write!(f, "unix:{}", path.display()),
     }?;
     if let Some(port) = self.1 {
       write!(f, ":{}", port)?;
@@ -868,6 +1238,12 @@ impl Descriptor for EnvDescriptor {
   fn name(&self) -> Cow<str> {
     Cow::from(self.0.as_ref())
   }
+
+  /// A `*` in a variable name matches any characters, ex. `AWS_*` for all
+  /// variables starting with `AWS_`.
+  fn stronger_than(&self, other: &Self) -> bool {
+    matches_wildcard(self.0.as_ref(), other.0.as_ref())
+  }
 }
 
 impl AsRef<str> for EnvDescriptor {
@@ -966,6 +1342,262 @@ impl AsRef<Path> for RunDescriptor {
   }
 }
 
+/// Splits an `--allow-run` entry of the form `<program>:<argument>,...` into
+/// the program and the first-level arguments it may be run with. Returns
+/// `None` for plain programs, including Windows paths with a drive letter.
+pub fn split_run_arguments(entry: &str) -> Option<(&str, HashSet<&str>)> {
+  let (program, arguments) = entry.rsplit_once(':')?;
+  if program.is_empty() || arguments.contains(|c| c == '/' || c == '\\') {
+    return None;
+  }
+  let arguments = arguments.split(',').collect::<HashSet<_>>();
+  if arguments.contains("") {
+    return None;
+  }
+  Some((program, arguments))
+}
+
+/// The first-level arguments allowed for programs given as
+/// `--allow-run=<program>:<argument>,...`, ex. `--allow-run=git:status,log`
+/// to allow `git status` and `git log` but not `git commit`, which could run
+/// hooks. Other programs are only subject to the run permission.
+///
+/// What a program does with its arguments also depends on its environment
+/// and working directory, ex. `GIT_CONFIG_*` variables or the `.git/config`
+/// of another repository can make `git status` run any command, so these
+/// programs can't be given environment variables that differ from the
+/// inherited ones or another working directory. Variables changed with
+/// `Deno.env.set()` are still inherited, so `--allow-env` shouldn't be
+/// granted along with them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RunArguments(Vec<(HashSet<RunDescriptor>, HashSet<String>)>);
+
+impl RunArguments {
+  /// Removes the arguments from the entries of `allow_run`, and returns the
+  /// restrictions they describe.
+  fn extract(
+    allow_run: &Option<Vec<String>>,
+  ) -> Result<(Option<Vec<String>>, Self), AnyError> {
+    let Some(allow_run) = allow_run else {
+      return Ok((None, Self::default()));
+    };
+    let mut programs = Vec::with_capacity(allow_run.len());
+    let mut restrictions = Vec::new();
+    for entry in allow_run {
+      match split_run_arguments(entry) {
+        Some((program, arguments)) => {
+          restrictions.push((
+            parse_run_list(&Some(vec![program.to_string()]))?,
+            arguments.into_iter().map(String::from).collect(),
+          ));
+          programs.push(program.to_string());
+        }
+        None => programs.push(entry.clone()),
+      }
+    }
+    Ok((Some(programs), Self(restrictions)))
+  }
+
+  /// Checks that `cmd` may be run with `args`, the environment variables
+  /// `env` on top of the inherited ones and the working directory `cwd`.
+  pub fn check(
+    &self,
+    cmd: &str,
+    args: &[String],
+    env: &[(String, String)],
+    cwd: Option<&str>,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    if self.0.is_empty() {
+      return Ok(());
+    }
+    let desc = RunDescriptor::from(cmd.to_string());
+    let mut descs = desc.aliases();
+    descs.push(desc);
+    let mut restrictions = self
+      .0
+      .iter()
+      .filter(|(programs, _)| descs.iter().any(|d| programs.contains(d)))
+      .peekable();
+    if restrictions.peek().is_none() {
+      return Ok(());
+    }
+
+    let api_name = api_name
+      .map(|name| format!(" in {name}"))
+      .unwrap_or_default();
+    let changes_env = env.iter().any(|(key, value)| {
+      std::env::var_os(key).as_deref() != Some(OsStr::new(value))
+    });
+    if changes_env {
+      return Err(custom_error(
+        "PermissionDenied",
+        format!(
+          "Requires run access to \"{cmd}\" with a modified environment{api_name}, which isn't allowed for programs restricted to some arguments",
+        ),
+      ));
+    }
+    let changes_cwd = cwd.is_some_and(|cwd| {
+      let current_dir = std::env::current_dir().and_then(std::fs::canonicalize);
+      match (std::fs::canonicalize(cwd), current_dir) {
+        (Ok(cwd), Ok(current_dir)) => cwd != current_dir,
+        _ => true,
+      }
+    });
+    if changes_cwd {
+      return Err(custom_error(
+        "PermissionDenied",
+        format!(
+          "Requires run access to \"{cmd}\" in another working directory{api_name}, which isn't allowed for programs restricted to some arguments",
+        ),
+      ));
+    }
+
+    let allowed = restrictions.any(|(_, arguments)| {
+      args.first().is_some_and(|arg| arguments.contains(arg))
+    });
+    if allowed {
+      return Ok(());
+    }
+    Err(custom_error(
+      "PermissionDenied",
+      format!(
+        "Requires run access to \"{cmd}\" with the arguments {args:?}{api_name}, run again with the --allow-run flag",
+      ),
+    ))
+  }
+}
+
+/// Splits an `--allow-ffi` entry of the form `<path>:<symbol>,...` into the
+/// library and the patterns of the symbols that may be looked up in it.
+/// Returns `None` for plain paths, including Windows paths with a drive
+/// letter.
+pub fn split_ffi_symbols(entry: &str) -> Option<(&str, Vec<&str>)> {
+  let (path, symbols) = entry.rsplit_once(':')?;
+  if path.is_empty() {
+    return None;
+  }
+  let symbols = symbols.split(',').collect::<Vec<_>>();
+  let is_symbol_pattern = |symbol: &&str| {
+    !symbol.is_empty()
+      && symbol.chars().all(|c| {
+        c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '@' | '.' | '*')
+      })
+  };
+  if !symbols.iter().all(is_symbol_pattern) {
+    return None;
+  }
+  Some((path, symbols))
+}
+
+/// Matches a name against a pattern in which `*` stands for any sequence of
+/// characters.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+  let mut parts = pattern.split('*');
+  let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default())
+  else {
+    return false;
+  };
+  let parts = parts.collect::<Vec<_>>();
+  let Some((last, middle)) = parts.split_last() else {
+    return rest.is_empty();
+  };
+  for part in middle {
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+  rest.ends_with(last)
+}
+
+/// The symbols that may be looked up in libraries given as
+/// `--allow-ffi=<path>:<symbol>,...`, ex. `--allow-ffi=libcrypto.so:EVP_*`.
+/// Symbols of other libraries are only subject to the ffi permission.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FfiSymbols(Vec<(PathBuf, Vec<String>)>);
+
+impl FfiSymbols {
+  /// Removes the symbols from the entries of `allow_ffi`, and returns the
+  /// restrictions they describe.
+  fn extract(
+    allow_ffi: &Option<Vec<PathBuf>>,
+  ) -> Result<(Option<Vec<PathBuf>>, Self), AnyError> {
+    let Some(allow_ffi) = allow_ffi else {
+      return Ok((None, Self::default()));
+    };
+    let mut paths = Vec::with_capacity(allow_ffi.len());
+    let mut restrictions = Vec::new();
+    for entry in allow_ffi {
+      match entry.to_str().and_then(split_ffi_symbols) {
+        Some((path, symbols)) => {
+          let path = PathBuf::from(path);
+          restrictions.push((
+            resolve_from_cwd(&path)?,
+            symbols.into_iter().map(String::from).collect(),
+          ));
+          paths.push(path);
+        }
+        None => paths.push(entry.clone()),
+      }
+    }
+    Ok((Some(paths), Self(restrictions)))
+  }
+
+  pub fn check(
+    &self,
+    path: &Path,
+    symbol: &str,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    if self.0.is_empty() {
+      return Ok(());
+    }
+    let path = resolve_from_cwd(path)?;
+    // the most specific prefix decides
+    let allowed = self
+      .0
+      .iter()
+      .filter(|(prefix, _)| path.starts_with(prefix))
+      .max_by_key(|(prefix, _)| prefix.components().count())
+      .map_or(true, |(_, patterns)| {
+        patterns
+          .iter()
+          .any(|pattern| matches_wildcard(pattern, symbol))
+      });
+    if allowed {
+      return Ok(());
+    }
+    Err(custom_error(
+      "PermissionDenied",
+      format!(
+        "Requires ffi access to the symbol \"{}\" of \"{}\"{}, run again with the --allow-ffi flag",
+        symbol,
+        path.display(),
+        api_name.map(|name| format!(" in {name}")).unwrap_or_default(),
+      ),
+    ))
+  }
+
+  /// Raw pointers can be read from and called regardless of the symbols they
+  /// point into, so they're denied while symbols are restricted.
+  pub fn check_raw_pointers(
+    &self,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    if self.0.is_empty() {
+      return Ok(());
+    }
+    Err(custom_error(
+      "PermissionDenied",
+      format!(
+        "Requires ffi access to raw pointers{}, which isn't granted when --allow-ffi is restricted to symbols",
+        api_name.map(|name| format!(" in {name}")).unwrap_or_default(),
+      ),
+    ))
+  }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct SysDescriptor(pub String);
 
@@ -1219,7 +1851,7 @@ impl UnaryPermission<NetDescriptor> {
       .host_str()
       .ok_or_else(|| type_error(format!("Missing host in url: '{}'", url)))?;
     let host = host.parse::<Host>()?;
-    let port = url.port_or_known_default();
+    let port = url.port_or_known_default().map(PortRange::from);
     let descriptor = NetDescriptor(host, port);
     self.check_desc(Some(&descriptor), false, api_name, || {
       Some(format!("\"{descriptor}\""))
@@ -1403,13 +2035,20 @@ impl UnaryPermission<FfiDescriptor> {
 pub struct Permissions {
   pub read: UnaryPermission<ReadDescriptor>,
   pub write: UnaryPermission<WriteDescriptor>,
+  pub write_operations: WriteOperations,
   pub net: UnaryPermission<NetDescriptor>,
   pub env: UnaryPermission<EnvDescriptor>,
   pub sys: UnaryPermission<SysDescriptor>,
   pub run: UnaryPermission<RunDescriptor>,
+  pub run_arguments: RunArguments,
   pub ffi: UnaryPermission<FfiDescriptor>,
+  pub ffi_symbols: FfiSymbols,
+  /// Whether APIs that need writable and executable memory are denied, see
+  /// `--enforce-wx`.
+  pub enforce_wx: bool,
   pub all: UnitPermission,
   pub hrtime: UnitPermission,
+  pub workers: WorkerPermissions,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
@@ -1431,6 +2070,7 @@ pub struct PermissionsOptions {
   pub deny_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub deny_write: Option<Vec<PathBuf>>,
+  pub enforce_wx: bool,
   pub prompt: bool,
 }
 
@@ -1477,6 +2117,10 @@ impl Permissions {
   }
 
   pub fn from_options(opts: &PermissionsOptions) -> Result<Self, AnyError> {
+    let (allow_write, write_operations) =
+      WriteOperations::extract(&opts.allow_write)?;
+    let (allow_run, run_arguments) = RunArguments::extract(&opts.allow_run)?;
+    let (allow_ffi, ffi_symbols) = FfiSymbols::extract(&opts.allow_ffi)?;
     Ok(Self {
       read: Permissions::new_unary(
         &opts.allow_read,
@@ -1484,10 +2128,11 @@ impl Permissions {
         opts.prompt,
       )?,
       write: Permissions::new_unary(
-        &opts.allow_write,
+        &allow_write,
         &opts.deny_write,
         opts.prompt,
       )?,
+      write_operations,
       net: Permissions::new_unary(
         &opts.allow_net,
         &opts.deny_net,
@@ -1503,18 +2148,14 @@ impl Permissions {
         &opts.deny_sys,
         opts.prompt,
       )?,
-      run: Permissions::new_unary(
-        &opts.allow_run,
-        &opts.deny_run,
-        opts.prompt,
-      )?,
-      ffi: Permissions::new_unary(
-        &opts.allow_ffi,
-        &opts.deny_ffi,
-        opts.prompt,
-      )?,
+      run: Permissions::new_unary(&allow_run, &opts.deny_run, opts.prompt)?,
+      run_arguments,
+      ffi: Permissions::new_unary(&allow_ffi, &opts.deny_ffi, opts.prompt)?,
+      ffi_symbols,
+      enforce_wx: opts.enforce_wx,
       all: Permissions::new_all(opts.allow_all),
       hrtime: Permissions::new_hrtime(opts.allow_hrtime, opts.deny_hrtime),
+      workers: Default::default(),
     })
   }
 
@@ -1523,13 +2164,18 @@ impl Permissions {
     Self {
       read: UnaryPermission::allow_all(),
       write: UnaryPermission::allow_all(),
+      write_operations: Default::default(),
       net: UnaryPermission::allow_all(),
       env: UnaryPermission::allow_all(),
       sys: UnaryPermission::allow_all(),
       run: UnaryPermission::allow_all(),
+      run_arguments: Default::default(),
       ffi: UnaryPermission::allow_all(),
+      ffi_symbols: Default::default(),
+      enforce_wx: false,
       all: Permissions::new_all(true),
       hrtime: Permissions::new_hrtime(true, false),
+      workers: Default::default(),
     }
   }
 
@@ -1547,13 +2193,18 @@ impl Permissions {
     Self {
       read: Permissions::new_unary(&None, &None, prompt).unwrap(),
       write: Permissions::new_unary(&None, &None, prompt).unwrap(),
+      write_operations: Default::default(),
       net: Permissions::new_unary(&None, &None, prompt).unwrap(),
       env: Permissions::new_unary(&None, &None, prompt).unwrap(),
       sys: Permissions::new_unary(&None, &None, prompt).unwrap(),
       run: Permissions::new_unary(&None, &None, prompt).unwrap(),
+      run_arguments: Default::default(),
       ffi: Permissions::new_unary(&None, &None, prompt).unwrap(),
+      ffi_symbols: Default::default(),
+      enforce_wx: false,
       all: Permissions::new_all(false),
       hrtime: Permissions::new_hrtime(false, false),
+      workers: Default::default(),
     }
   }
 
@@ -1577,6 +2228,32 @@ impl Permissions {
   }
 }
 
+fn display_path(path: &Path) -> Option<String> {
+  Some(path.display().to_string())
+}
+
+/// The permissions of the workers that were started with their own set of
+/// permissions, so that revocations can be propagated to them.
+#[derive(Clone, Debug, Default)]
+pub struct WorkerPermissions(Vec<Weak<Mutex<Permissions>>>);
+
+impl WorkerPermissions {
+  /// Returns the permissions of the workers that are still running.
+  pub fn running(&mut self) -> Vec<Arc<Mutex<Permissions>>> {
+    self.0.retain(|worker| worker.strong_count() > 0);
+    self.0.iter().filter_map(Weak::upgrade).collect()
+  }
+}
+
+// The permissions of the workers aren't part of a set of permissions.
+impl PartialEq for WorkerPermissions {
+  fn eq(&self, _other: &Self) -> bool {
+    true
+  }
+}
+
+impl Eq for WorkerPermissions {}
+
 /// Wrapper struct for `Permissions` that can be shared across threads.
 ///
 /// We need a way to have internal mutability for permissions as they might get
@@ -1591,9 +2268,55 @@ impl PermissionsContainer {
     Self(Arc::new(Mutex::new(perms)))
   }
 
+  /// Registers the permissions of a worker started with its own set of
+  /// permissions, see [`WorkerPermissions`].
+  pub fn add_worker(&self, worker: &PermissionsContainer) {
+    self.0.lock().workers.0.push(Arc::downgrade(&worker.0));
+  }
+
+  /// Mints a signed token carrying `arg`, which has to be a subset of these
+  /// permissions, to start a worker with.
+  pub fn create_capability_token(
+    &self,
+    arg: ChildPermissionsArg,
+    api_name: &str,
+  ) -> Result<String, AnyError> {
+    let mut minted = None;
+    let result = capability::mint(&mut self.0.lock(), arg)
+      .map(|token| minted = Some(token));
+    audit::record(
+      "capability",
+      Some(api_name),
+      || minted.as_ref().map(|token| token.id.clone()),
+      result,
+    )?;
+    Ok(minted.unwrap().token)
+  }
+
+  /// Verifies a token from `create_capability_token()` and returns the
+  /// permissions it carries. They are still checked against the permissions
+  /// of the worker's parent when it is created.
+  pub fn redeem_capability_token(
+    &self,
+    token: &str,
+    api_name: &str,
+  ) -> Result<ChildPermissionsArg, AnyError> {
+    let mut redeemed = None;
+    let result =
+      capability::redeem(token).map(|claims| redeemed = Some(claims));
+    audit::record(
+      "capability",
+      Some(api_name),
+      || redeemed.as_ref().map(|(id, _)| id.clone()),
+      result,
+    )?;
+    Ok(redeemed.unwrap().1)
+  }
+
   #[inline(always)]
   pub fn allow_hrtime(&mut self) -> bool {
-    self.0.lock().hrtime.check().is_ok()
+    let result = self.0.lock().hrtime.check();
+    audit::record("hrtime", None, || None, result).is_ok()
   }
 
   pub fn allow_all() -> Self {
@@ -1605,7 +2328,18 @@ impl PermissionsContainer {
     &self,
     specifier: &ModuleSpecifier,
   ) -> Result<(), AnyError> {
-    self.0.lock().check_specifier(specifier)
+    let permission = match specifier.scheme() {
+      "data" | "blob" => return Ok(()),
+      "file" => "read",
+      _ => "net",
+    };
+    let result = self.0.lock().check_specifier(specifier);
+    audit::record(
+      permission,
+      Some("import()"),
+      || Some(specifier.to_string()),
+      result,
+    )
   }
 
   #[inline(always)]
@@ -1614,7 +2348,8 @@ impl PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check(path, Some(api_name))
+    let result = self.0.lock().read.check(path, Some(api_name));
+    audit::record("read", Some(api_name), || display_path(path), result)
   }
 
   #[inline(always)]
@@ -1623,7 +2358,8 @@ impl PermissionsContainer {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check(path, api_name)
+    let result = self.0.lock().read.check(path, api_name);
+    audit::record("read", api_name, || display_path(path), result)
   }
 
   #[inline(always)]
@@ -1633,12 +2369,14 @@ impl PermissionsContainer {
     display: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check_blind(path, display, api_name)
+    let result = self.0.lock().read.check_blind(path, display, api_name);
+    audit::record("read", Some(api_name), || Some(display.to_string()), result)
   }
 
   #[inline(always)]
   pub fn check_read_all(&mut self, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().read.check_all(Some(api_name))
+    let result = self.0.lock().read.check_all(Some(api_name));
+    audit::record("read", Some(api_name), || None, result)
   }
 
   #[inline(always)]
@@ -1647,7 +2385,8 @@ impl PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().write.check(path, Some(api_name))
+    let result = self.0.lock().write.check(path, Some(api_name));
+    audit::record("write", Some(api_name), || display_path(path), result)
   }
 
   #[inline(always)]
@@ -1656,12 +2395,14 @@ impl PermissionsContainer {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    self.0.lock().write.check(path, api_name)
+    let result = self.0.lock().write.check(path, api_name);
+    audit::record("write", api_name, || display_path(path), result)
   }
 
   #[inline(always)]
   pub fn check_write_all(&mut self, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().write.check_all(Some(api_name))
+    let result = self.0.lock().write.check_all(Some(api_name));
+    audit::record("write", Some(api_name), || None, result)
   }
 
   #[inline(always)]
@@ -1671,7 +2412,46 @@ impl PermissionsContainer {
     display: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().write.check_blind(path, display, api_name)
+    let result = self.0.lock().write.check_blind(path, display, api_name);
+    audit::record(
+      "write",
+      Some(api_name),
+      || Some(display.to_string()),
+      result,
+    )
+  }
+
+  /// Whether `operation` is allowed on `path`, without recording the check.
+  #[inline(always)]
+  pub fn allows_write_operation(
+    &self,
+    path: &Path,
+    operation: WriteOperation,
+  ) -> bool {
+    self
+      .0
+      .lock()
+      .write_operations
+      .check(path, operation, None)
+      .is_ok()
+  }
+
+  /// Checks that `operation` is allowed on `path`, which must already have
+  /// passed [`PermissionsContainer::check_write`].
+  #[inline(always)]
+  pub fn check_write_operation(
+    &mut self,
+    path: &Path,
+    operation: WriteOperation,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    let result =
+      self
+        .0
+        .lock()
+        .write_operations
+        .check(path, operation, Some(api_name));
+    audit::record("write", Some(api_name), || display_path(path), result)
   }
 
   #[inline(always)]
@@ -1680,7 +2460,8 @@ impl PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().write.check_partial(path, Some(api_name))
+    let result = self.0.lock().write.check_partial(path, Some(api_name));
+    audit::record("write", Some(api_name), || display_path(path), result)
   }
 
   #[inline(always)]
@@ -1689,44 +2470,72 @@ impl PermissionsContainer {
     cmd: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().run.check(cmd, Some(api_name))
+    let result = self.0.lock().run.check(cmd, Some(api_name));
+    audit::record("run", Some(api_name), || Some(cmd.to_string()), result)
+  }
+
+  /// Checks that `cmd` may be run with `args`, `env` and `cwd`, which must
+  /// already have passed [`PermissionsContainer::check_run`].
+  #[inline(always)]
+  pub fn check_run_arguments(
+    &mut self,
+    cmd: &str,
+    args: &[String],
+    env: &[(String, String)],
+    cwd: Option<&str>,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    let result =
+      self
+        .0
+        .lock()
+        .run_arguments
+        .check(cmd, args, env, cwd, Some(api_name));
+    audit::record("run", Some(api_name), || Some(cmd.to_string()), result)
   }
 
   #[inline(always)]
   pub fn check_run_all(&mut self, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().run.check_all(Some(api_name))
+    let result = self.0.lock().run.check_all(Some(api_name));
+    audit::record("run", Some(api_name), || None, result)
   }
 
   #[inline(always)]
   pub fn check_sys(&self, kind: &str, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().sys.check(kind, Some(api_name))
+    let result = self.0.lock().sys.check(kind, Some(api_name));
+    audit::record("sys", Some(api_name), || Some(kind.to_string()), result)
   }
 
   #[inline(always)]
   pub fn check_env(&mut self, var: &str) -> Result<(), AnyError> {
-    self.0.lock().env.check(var, None)
+    let result = self.0.lock().env.check(var, None);
+    audit::record("env", None, || Some(var.to_string()), result)
   }
 
   #[inline(always)]
   pub fn check_env_all(&mut self) -> Result<(), AnyError> {
-    self.0.lock().env.check_all()
+    let result = self.0.lock().env.check_all();
+    audit::record("env", None, || None, result)
   }
 
   #[inline(always)]
   pub fn check_sys_all(&mut self) -> Result<(), AnyError> {
-    self.0.lock().sys.check_all()
+    let result = self.0.lock().sys.check_all();
+    audit::record("sys", None, || None, result)
   }
 
   #[inline(always)]
   pub fn check_ffi_all(&mut self) -> Result<(), AnyError> {
-    self.0.lock().ffi.check_all()
+    let result = self.0.lock().ffi.check_all();
+    audit::record("ffi", None, || None, result)
   }
 
   /// This checks to see if the allow-all flag was passed, not whether all
   /// permissions are enabled!
   #[inline(always)]
   pub fn check_was_allow_all_flag_passed(&mut self) -> Result<(), AnyError> {
-    self.0.lock().all.check()
+    let result = self.0.lock().all.check();
+    audit::record("all", None, || None, result)
   }
 
   /// Checks special file access, returning the failed permission type if
@@ -1839,7 +2648,8 @@ impl PermissionsContainer {
     url: &Url,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().net.check_url(url, Some(api_name))
+    let result = self.0.lock().net.check_url(url, Some(api_name));
+    audit::record("net", Some(api_name), || Some(url.to_string()), result)
   }
 
   #[inline(always)]
@@ -1849,13 +2659,85 @@ impl PermissionsContainer {
     api_name: &str,
   ) -> Result<(), AnyError> {
     let hostname = host.0.as_ref().parse::<Host>()?;
-    let descriptor = NetDescriptor(hostname, host.1);
-    self.0.lock().net.check(&descriptor, Some(api_name))
+    let descriptor = NetDescriptor(hostname, host.1.map(PortRange::from));
+    let result = self.0.lock().net.check(&descriptor, Some(api_name));
+    audit::record(
+      "net",
+      Some(api_name),
+      || Some(descriptor.to_string()),
+      result,
+    )
+  }
+
+  /// vsock addresses can't be expressed in `--allow-net` lists, so connecting
+  /// to or listening on one requires unrestricted network access.
+  #[inline(always)]
+  pub fn check_net_vsock(
+    &mut self,
+    cid: u32,
+    port: u32,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    let result =
+      self
+        .0
+        .lock()
+        .net
+        .check_desc(None, false, Some(api_name), || {
+          Some(format!("\"vsock:{cid}:{port}\""))
+        });
+    audit::record(
+      "net",
+      Some(api_name),
+      || Some(format!("vsock:{cid}:{port}")),
+      result,
+    )
+  }
+
+  /// Checks the `unix:<path>` entries of `--allow-net` and `--deny-net` for
+  /// a Unix domain socket. Returns whether an entry grants the socket, when
+  /// none applies the caller checks the file system permissions of the path
+  /// instead. A plain `--allow-net` doesn't grant any socket.
+  pub fn check_net_unix(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<bool, AnyError> {
+    let descriptor = NetDescriptor(Host::Unix(resolve_from_cwd(path)?), None);
+    let mut inner = self.0.lock();
+    let net = &mut inner.net;
+    let has_entry = |list: &HashSet<NetDescriptor>| {
+      list.iter().any(|entry| entry.stronger_than(&descriptor))
+    };
+    if !has_entry(&net.granted_list)
+      && !has_entry(&net.flag_denied_list)
+      && !has_entry(&net.prompt_denied_list)
+    {
+      return Ok(false);
+    }
+    let result = net.check(&descriptor, Some(api_name));
+    audit::record(
+      "net",
+      Some(api_name),
+      || Some(descriptor.to_string()),
+      result,
+    )?;
+    Ok(true)
+  }
+
+  /// Fails if `--enforce-wx` was given, for APIs that need memory that is
+  /// both writable and executable.
+  #[inline(always)]
+  pub fn check_wx_mapping(&mut self, api_name: &str) -> Result<(), AnyError> {
+    let result = wx::check(self.0.lock().enforce_wx, api_name);
+    audit::record("wx", Some(api_name), || None, result)
   }
 
   #[inline(always)]
   pub fn check_ffi(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
-    self.0.lock().ffi.check(path.unwrap(), None)
+    let path = path.unwrap();
+    let result = self.0.lock().ffi.check(path, None);
+    audit::record("ffi", None, || display_path(path), result)
   }
 
   #[inline(always)]
@@ -1863,7 +2745,32 @@ impl PermissionsContainer {
     &mut self,
     path: Option<&Path>,
   ) -> Result<(), AnyError> {
-    self.0.lock().ffi.check_partial(path)
+    let result = self.0.lock().ffi.check_partial(path);
+    audit::record("ffi", None, || path.and_then(display_path), result)
+  }
+
+  #[inline(always)]
+  pub fn check_ffi_symbol(
+    &mut self,
+    path: &Path,
+    symbol: &str,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    let result = self
+      .0
+      .lock()
+      .ffi_symbols
+      .check(path, symbol, Some(api_name));
+    audit::record("ffi", Some(api_name), || display_path(path), result)
+  }
+
+  #[inline(always)]
+  pub fn check_ffi_raw_pointers(
+    &mut self,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    let result = self.0.lock().ffi_symbols.check_raw_pointers(Some(api_name));
+    audit::record("ffi", Some(api_name), || None, result)
   }
 }
 
@@ -1985,13 +2892,26 @@ fn escalation_error() -> AnyError {
   )
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ChildUnitPermissionArg {
   Inherit,
   Granted,
   NotGranted,
 }
 
+impl Serialize for ChildUnitPermissionArg {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self {
+      ChildUnitPermissionArg::Inherit => serializer.serialize_str("inherit"),
+      ChildUnitPermissionArg::Granted => serializer.serialize_bool(true),
+      ChildUnitPermissionArg::NotGranted => serializer.serialize_bool(false),
+    }
+  }
+}
+
 impl<'de> Deserialize<'de> for ChildUnitPermissionArg {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -2037,7 +2957,7 @@ impl<'de> Deserialize<'de> for ChildUnitPermissionArg {
   }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ChildUnaryPermissionArg {
   Inherit,
   Granted,
@@ -2045,6 +2965,20 @@ pub enum ChildUnaryPermissionArg {
   GrantedList(Vec<String>),
 }
 
+impl Serialize for ChildUnaryPermissionArg {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self {
+      ChildUnaryPermissionArg::Inherit => serializer.serialize_str("inherit"),
+      ChildUnaryPermissionArg::Granted => serializer.serialize_bool(true),
+      ChildUnaryPermissionArg::NotGranted => serializer.serialize_bool(false),
+      ChildUnaryPermissionArg::GrantedList(list) => list.serialize(serializer),
+    }
+  }
+}
+
 impl<'de> Deserialize<'de> for ChildUnaryPermissionArg {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -2105,7 +3039,7 @@ impl<'de> Deserialize<'de> for ChildUnaryPermissionArg {
 }
 
 /// Directly deserializable from JS worker and test permission options.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct ChildPermissionsArg {
   env: ChildUnaryPermissionArg,
   hrtime: ChildUnitPermissionArg,
@@ -2118,6 +3052,13 @@ pub struct ChildPermissionsArg {
 }
 
 impl ChildPermissionsArg {
+  /// Whether the `sys` or `ffi` permissions are set rather than inherited,
+  /// which can only be enforced for JavaScript.
+  pub fn sets_sys_or_ffi(&self) -> bool {
+    self.sys != ChildUnaryPermissionArg::Inherit
+      || self.ffi != ChildUnaryPermissionArg::Inherit
+  }
+
   pub fn inherit() -> Self {
     ChildPermissionsArg {
       env: ChildUnaryPermissionArg::Inherit,
@@ -2248,6 +3189,15 @@ pub fn create_child_permissions(
   worker_perms.write = main_perms
     .write
     .create_child_permissions(child_permissions_arg.write)?;
+  // restrictions can't be lifted for workers
+  worker_perms
+    .write_operations
+    .clone_from(&main_perms.write_operations);
+  worker_perms
+    .run_arguments
+    .clone_from(&main_perms.run_arguments);
+  worker_perms.ffi_symbols.clone_from(&main_perms.ffi_symbols);
+  worker_perms.enforce_wx = main_perms.enforce_wx;
   worker_perms.net = main_perms
     .net
     .create_child_permissions(child_permissions_arg.net)?;
@@ -2280,6 +3230,7 @@ mod tests {
   use fqdn::fqdn;
   use prompter::tests::*;
   use std::net::Ipv4Addr;
+  use std::time::Duration;
 
   // Creates vector of strings, Vec<String>
   macro_rules! svec {
@@ -2434,7 +3385,7 @@ mod tests {
 
     for (host, port, is_ok) in domain_tests {
       let host = host.parse().unwrap();
-      let descriptor = NetDescriptor(host, Some(port));
+      let descriptor = NetDescriptor(host, Some(PortRange::from(port)));
       assert_eq!(
         is_ok,
         perms.net.check(&descriptor, None).is_ok(),
@@ -2476,7 +3427,7 @@ mod tests {
 
     for (host_str, port) in domain_tests {
       let host = host_str.parse().unwrap();
-      let descriptor = NetDescriptor(host, Some(port));
+      let descriptor = NetDescriptor(host, Some(PortRange::from(port)));
       assert!(
         perms.net.check(&descriptor, None).is_ok(),
         "expected {host_str}:{port} to pass"
@@ -2517,7 +3468,7 @@ mod tests {
 
     for (host_str, port) in domain_tests {
       let host = host_str.parse().unwrap();
-      let descriptor = NetDescriptor(host, Some(port));
+      let descriptor = NetDescriptor(host, Some(PortRange::from(port)));
       assert!(
         perms.net.check(&descriptor, None).is_err(),
         "expected {host_str}:{port} to fail"
@@ -2690,7 +3641,12 @@ mod tests {
         .unwrap(),
       run: Permissions::new_unary(&Some(svec!["deno"]), &None, false).unwrap(),
       all: Permissions::new_all(false),
+      write_operations: Default::default(),
+      run_arguments: Default::default(),
+      ffi_symbols: Default::default(),
+      enforce_wx: false,
       hrtime: Permissions::new_hrtime(false, false),
+      workers: Default::default(),
     };
     let perms3 = Permissions {
       read: Permissions::new_unary(
@@ -2718,7 +3674,12 @@ mod tests {
         .unwrap(),
       run: Permissions::new_unary(&None, &Some(svec!["deno"]), false).unwrap(),
       all: Permissions::new_all(false),
+      write_operations: Default::default(),
+      run_arguments: Default::default(),
+      ffi_symbols: Default::default(),
+      enforce_wx: false,
       hrtime: Permissions::new_hrtime(false, true),
+      workers: Default::default(),
     };
     let perms4 = Permissions {
       read: Permissions::new_unary(
@@ -2756,7 +3717,12 @@ mod tests {
       run: Permissions::new_unary(&Some(vec![]), &Some(svec!["deno"]), false)
         .unwrap(),
       all: Permissions::new_all(false),
+      write_operations: Default::default(),
+      run_arguments: Default::default(),
+      ffi_symbols: Default::default(),
+      enforce_wx: false,
       hrtime: Permissions::new_hrtime(true, true),
+      workers: Default::default(),
     };
     #[rustfmt::skip]
     {
@@ -2799,12 +3765,12 @@ mod tests {
       assert_eq!(perms1.net.query(None), PermissionState::Granted);
       assert_eq!(perms1.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), None))), PermissionState::Granted);
       assert_eq!(perms2.net.query(None), PermissionState::Prompt);
-      assert_eq!(perms2.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)))), PermissionState::Granted);
+      assert_eq!(perms2.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(PortRange::from(8000))))), PermissionState::Granted);
       assert_eq!(perms3.net.query(None), PermissionState::Prompt);
-      assert_eq!(perms3.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)))), PermissionState::Denied);
+      assert_eq!(perms3.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(PortRange::from(8000))))), PermissionState::Denied);
       assert_eq!(perms4.net.query(None), PermissionState::GrantedPartial);
-      assert_eq!(perms4.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)))), PermissionState::Denied);
-      assert_eq!(perms4.net.query(Some(&NetDescriptor("192.168.0.1".parse().unwrap(), Some(8000)))), PermissionState::Granted);
+      assert_eq!(perms4.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(PortRange::from(8000))))), PermissionState::Denied);
+      assert_eq!(perms4.net.query(Some(&NetDescriptor("192.168.0.1".parse().unwrap(), Some(PortRange::from(8000))))), PermissionState::Granted);
       assert_eq!(perms1.env.query(None), PermissionState::Granted);
       assert_eq!(perms1.env.query(Some("HOME")), PermissionState::Granted);
       assert_eq!(perms2.env.query(None), PermissionState::Prompt);
@@ -2864,7 +3830,7 @@ mod tests {
       prompt_value.set(true);
       assert_eq!(perms.net.request(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), None))), PermissionState::Granted);
       prompt_value.set(false);
-      assert_eq!(perms.net.request(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)))), PermissionState::Granted);
+      assert_eq!(perms.net.request(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(PortRange::from(8000))))), PermissionState::Granted);
       prompt_value.set(true);
       assert_eq!(perms.env.request(Some("HOME")), PermissionState::Granted);
       assert_eq!(perms.env.query(None), PermissionState::Prompt);
@@ -2887,6 +3853,39 @@ mod tests {
     };
   }
 
+  #[test]
+  fn test_temporary_grant() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::none_without_prompt();
+    let foo = ReadDescriptor(PathBuf::from("/foo"));
+
+    perms.read.insert_temporarily_granted(
+      Some(foo.clone()),
+      Instant::now() + Duration::from_secs(60),
+    );
+    assert_eq!(
+      perms.read.query(Some(Path::new("/foo/bar"))),
+      PermissionState::Granted
+    );
+    assert!(perms.read.check(Path::new("/foo/bar"), None).is_ok());
+    assert_eq!(perms.read.query(None), PermissionState::Prompt);
+    assert_eq!(
+      perms.read.revoke(Some(Path::new("/foo"))),
+      PermissionState::Prompt
+    );
+
+    // an expired grant is revoked by the next check
+    perms
+      .read
+      .insert_temporarily_granted(Some(foo), Instant::now());
+    assert_eq!(
+      perms.read.query(Some(Path::new("/foo/bar"))),
+      PermissionState::Prompt
+    );
+    assert!(perms.read.check(Path::new("/foo/bar"), None).is_err());
+    assert!(perms.read.temporary_grants.is_empty());
+  }
+
   #[test]
   fn test_revoke() {
     set_prompter(Box::new(TestPrompter));
@@ -2920,7 +3919,12 @@ mod tests {
         .unwrap(),
       run: Permissions::new_unary(&Some(svec!["deno"]), &None, false).unwrap(),
       all: Permissions::new_all(false),
+      write_operations: Default::default(),
+      run_arguments: Default::default(),
+      ffi_symbols: Default::default(),
+      enforce_wx: false,
       hrtime: Permissions::new_hrtime(false, true),
+      workers: Default::default(),
     };
     #[rustfmt::skip]
     {
@@ -2933,9 +3937,9 @@ mod tests {
       assert_eq!(perms.ffi.revoke(Some(Path::new("/foo/bar"))), PermissionState::Prompt);
       assert_eq!(perms.ffi.query(Some(Path::new("/foo"))), PermissionState::Prompt);
       assert_eq!(perms.ffi.query(Some(Path::new("/foo/baz"))), PermissionState::Granted);
-      assert_eq!(perms.net.revoke(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(9000)))), PermissionState::Prompt);
+      assert_eq!(perms.net.revoke(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(PortRange::from(9000))))), PermissionState::Prompt);
       assert_eq!(perms.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), None))), PermissionState::Prompt);
-      assert_eq!(perms.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)))), PermissionState::Granted);
+      assert_eq!(perms.net.query(Some(&NetDescriptor("127.0.0.1".parse().unwrap(), Some(PortRange::from(8000))))), PermissionState::Granted);
       assert_eq!(perms.env.revoke(Some("HOME")), PermissionState::Prompt);
       assert_eq!(perms.env.revoke(Some("hostname")), PermissionState::Prompt);
       assert_eq!(perms.run.revoke(Some("deno")), PermissionState::Prompt);
@@ -2971,7 +3975,10 @@ mod tests {
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_ok());
@@ -2979,14 +3986,20 @@ mod tests {
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_ok());
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8001)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8001))
+        ),
         None
       )
       .is_err());
@@ -2997,7 +4010,10 @@ mod tests {
     assert!(perms
       .net
       .check(
-        &NetDescriptor("deno.land".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "deno.land".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_err());
@@ -3061,7 +4077,10 @@ mod tests {
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_err());
@@ -3069,21 +4088,30 @@ mod tests {
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_err());
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8001)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8001))
+        ),
         None
       )
       .is_ok());
     assert!(perms
       .net
       .check(
-        &NetDescriptor("deno.land".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "deno.land".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_ok());
@@ -3091,14 +4119,20 @@ mod tests {
     assert!(perms
       .net
       .check(
-        &NetDescriptor("127.0.0.1".parse().unwrap(), Some(8001)),
+        &NetDescriptor(
+          "127.0.0.1".parse().unwrap(),
+          Some(PortRange::from(8001))
+        ),
         None
       )
       .is_ok());
     assert!(perms
       .net
       .check(
-        &NetDescriptor("deno.land".parse().unwrap(), Some(8000)),
+        &NetDescriptor(
+          "deno.land".parse().unwrap(),
+          Some(PortRange::from(8000))
+        ),
         None
       )
       .is_ok());
@@ -3153,6 +4187,86 @@ mod tests {
     assert_eq!(perms.env.revoke(Some("HomE")), PermissionState::Prompt);
   }
 
+  #[test]
+  fn test_env_patterns() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_env: Some(svec!["AWS_*", "HOME"]),
+      deny_env: Some(svec!["AWS_SECRET_*"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    assert!(perms.env.check("AWS_REGION", None).is_ok());
+    assert!(perms.env.check("AWS_", None).is_ok());
+    assert!(perms.env.check("HOME", None).is_ok());
+    assert!(perms.env.check("AWS_SECRET_ACCESS_KEY", None).is_err());
+    assert!(perms.env.check("AWS", None).is_err());
+    assert!(perms.env.check("HOMEPATH", None).is_err());
+
+    assert_eq!(
+      perms.env.query(Some("AWS_REGION")),
+      PermissionState::Granted
+    );
+    // part of it is denied
+    assert_eq!(
+      perms.env.query(Some("AWS_*")),
+      PermissionState::GrantedPartial
+    );
+    assert_eq!(perms.env.query(Some("AWS_S3_*")), PermissionState::Granted);
+    assert_eq!(
+      perms.env.query(Some("AWS_SECRET_KEY")),
+      PermissionState::Denied
+    );
+    assert_eq!(perms.env.query(Some("PATH")), PermissionState::Prompt);
+
+    assert_eq!(perms.env.revoke(Some("AWS_*")), PermissionState::Prompt);
+    assert!(perms.env.check("AWS_REGION", None).is_err());
+  }
+
+  #[test]
+  fn test_enforce_wx() {
+    let mut perms = PermissionsContainer::allow_all();
+    assert!(perms.check_wx_mapping("Deno.UnsafeCallback").is_ok());
+    let mut perms = PermissionsContainer::new(
+      Permissions::from_options(&PermissionsOptions {
+        allow_all: true,
+        enforce_wx: true,
+        ..Default::default()
+      })
+      .unwrap(),
+    );
+    assert!(perms.check_wx_mapping("Deno.UnsafeCallback").is_err());
+  }
+
+  #[test]
+  fn test_capability_tokens() {
+    let perms = PermissionsContainer::new(
+      Permissions::from_options(&PermissionsOptions {
+        allow_env: Some(svec!["HOME", "USER"]),
+        ..Default::default()
+      })
+      .unwrap(),
+    );
+    let arg = ChildPermissionsArg {
+      env: ChildUnaryPermissionArg::GrantedList(svec!["HOME"]),
+      ..ChildPermissionsArg::none()
+    };
+    let token = perms.create_capability_token(arg.clone(), "test").unwrap();
+    assert_eq!(perms.redeem_capability_token(&token, "test").unwrap(), arg);
+
+    let (claims, _) = token.rsplit_once('.').unwrap();
+    let forged = format!("{claims}.AAAA");
+    assert!(perms.redeem_capability_token(&forged, "test").is_err());
+    assert!(perms.redeem_capability_token("inherit", "test").is_err());
+
+    let escalation = ChildPermissionsArg {
+      env: ChildUnaryPermissionArg::Granted,
+      ..ChildPermissionsArg::none()
+    };
+    assert!(perms.create_capability_token(escalation, "test").is_err());
+  }
+
   #[test]
   fn test_check_partial_denied() {
     let mut perms = Permissions {
@@ -3498,6 +4612,275 @@ mod tests {
     .is_err());
   }
 
+  #[test]
+  fn test_write_operations() {
+    set_prompter(Box::new(TestPrompter));
+
+    let (allow_write, operations) = WriteOperations::extract(&Some(vec![
+      PathBuf::from("/logs:create,append"),
+      PathBuf::from("/logs/archive:delete"),
+      PathBuf::from("/data"),
+    ]))
+    .unwrap();
+    assert_eq!(
+      allow_write,
+      Some(vec![
+        PathBuf::from("/logs"),
+        PathBuf::from("/logs/archive"),
+        PathBuf::from("/data"),
+      ])
+    );
+
+    let cases = [
+      ("/logs/app.log", WriteOperation::Create, true),
+      ("/logs/app.log", WriteOperation::Append, true),
+      ("/logs/app.log", WriteOperation::Modify, false),
+      ("/logs/app.log", WriteOperation::Delete, false),
+      ("/logs/archive/old.log", WriteOperation::Delete, true),
+      ("/logs/archive/old.log", WriteOperation::Append, false),
+      ("/data/file.txt", WriteOperation::Modify, true),
+      ("/data/file.txt", WriteOperation::Chmod, true),
+    ];
+    for (path, operation, is_ok) in cases {
+      assert_eq!(
+        operations.check(Path::new(path), operation, None).is_ok(),
+        is_ok,
+        "{path} {}",
+        operation.as_str()
+      );
+    }
+
+    assert!(
+      WriteOperations::extract(&Some(vec![PathBuf::from(":create")])).is_err()
+    );
+    assert_eq!(split_write_operations("/logs:create,bogus"), None);
+  }
+
+  #[test]
+  fn test_run_arguments() {
+    let (allow_run, arguments) =
+      RunArguments::extract(&Some(svec!["git:status,log", "curl"])).unwrap();
+    assert_eq!(allow_run, Some(svec!["git", "curl"]));
+
+    let cases: [(&str, &[&str], bool); 5] = [
+      ("git", &["status"], true),
+      ("git", &["log", "-n", "1"], true),
+      ("git", &["commit"], false),
+      ("git", &[], false),
+      ("curl", &["https://deno.land"], true),
+    ];
+    for (cmd, args, is_ok) in cases {
+      let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+      assert_eq!(
+        arguments.check(cmd, &args, &[], None, None).is_ok(),
+        is_ok,
+        "{cmd} {args:?}"
+      );
+    }
+
+    let status = svec!["status"];
+    let config = [
+      ("GIT_CONFIG_COUNT".to_string(), "1".to_string()),
+      ("GIT_CONFIG_KEY_0".to_string(), "core.fsmonitor".to_string()),
+      ("GIT_CONFIG_VALUE_0".to_string(), "touch pwned".to_string()),
+    ];
+    assert!(arguments
+      .check("git", &status, &config, None, None)
+      .is_err());
+    assert!(arguments
+      .check("curl", &status, &config, None, None)
+      .is_ok());
+    let inherited: Vec<_> = std::env::vars().collect();
+    assert!(arguments
+      .check("git", &status, &inherited, None, None)
+      .is_ok());
+    assert!(arguments
+      .check("git", &status, &[], Some("."), None)
+      .is_ok());
+    assert!(arguments
+      .check("git", &status, &[], Some(".."), None)
+      .is_err());
+
+    assert_eq!(split_run_arguments("git"), None);
+    assert_eq!(split_run_arguments("C:\\git.exe"), None);
+    assert_eq!(split_run_arguments("git:status,"), None);
+  }
+
+  #[test]
+  fn test_ffi_symbols() {
+    let (allow_ffi, symbols) = FfiSymbols::extract(&Some(vec![
+      PathBuf::from("/lib/libcrypto.so:EVP_*,RAND_bytes"),
+      PathBuf::from("/lib/libfoo.so"),
+    ]))
+    .unwrap();
+    assert_eq!(
+      allow_ffi,
+      Some(vec![
+        PathBuf::from("/lib/libcrypto.so"),
+        PathBuf::from("/lib/libfoo.so"),
+      ])
+    );
+
+    let cases = [
+      ("/lib/libcrypto.so", "EVP_DigestInit", true),
+      ("/lib/libcrypto.so", "EVP_", true),
+      ("/lib/libcrypto.so", "RAND_bytes", true),
+      ("/lib/libcrypto.so", "RAND_bytes_ex", false),
+      ("/lib/libcrypto.so", "CRYPTO_free", false),
+      ("/lib/libfoo.so", "anything", true),
+    ];
+    for (path, symbol, is_ok) in cases {
+      assert_eq!(
+        symbols.check(Path::new(path), symbol, None).is_ok(),
+        is_ok,
+        "{path} {symbol}"
+      );
+    }
+
+    assert!(matches_wildcard("a*b*c", "axxbyyc"));
+    assert!(!matches_wildcard("a*b*c", "axxbyy"));
+    assert_eq!(split_ffi_symbols("C:\\libfoo.dll"), None);
+    assert_eq!(split_ffi_symbols("/lib/libfoo.so:"), None);
+
+    assert!(symbols.check_raw_pointers(None).is_err());
+    assert!(FfiSymbols::default().check_raw_pointers(None).is_ok());
+  }
+
+  #[test]
+  fn test_net_patterns() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["10.0.0.0/8:443", "*.internal:8000-8100"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let cases = [
+      ("10.0.0.1", 443, true),
+      ("10.255.255.255", 443, true),
+      ("11.0.0.1", 443, false),
+      ("10.0.0.1", 80, false),
+      ("api.internal", 8000, true),
+      ("a.b.internal", 8100, true),
+      ("api.internal", 8101, false),
+      ("internal", 8000, false),
+      ("notinternal", 8000, false),
+      ("[::ffff:10.0.0.1]", 443, true),
+      ("[::10.0.0.1]", 443, true),
+      ("[::ffff:11.0.0.1]", 443, false),
+      ("[::1]", 443, false),
+    ];
+    for (host, port, is_ok) in cases {
+      assert_eq!(
+        perms
+          .net
+          .check(
+            &NetDescriptor(host.parse().unwrap(), Some(PortRange::from(port))),
+            None
+          )
+          .is_ok(),
+        is_ok,
+        "{host}:{port}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_net_embedded_ipv4() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["10.0.0.0/8", "[::ffff:0:0/96]:80"]),
+      deny_net: Some(svec!["10.0.0.5", "[::ffff:10.0.0.6]"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let cases = [
+      ("10.0.0.1", 443, true),
+      ("[::ffff:10.0.0.1]", 443, true),
+      ("10.0.0.5", 443, false),
+      ("[::ffff:10.0.0.5]", 443, false),
+      ("[::10.0.0.5]", 443, false),
+      ("10.0.0.6", 443, false),
+      ("11.0.0.1", 80, true),
+      ("11.0.0.1", 443, false),
+    ];
+    for (host, port, is_ok) in cases {
+      assert_eq!(
+        perms
+          .net
+          .check(
+            &NetDescriptor(host.parse().unwrap(), Some(PortRange::from(port))),
+            None
+          )
+          .is_ok(),
+        is_ok,
+        "{host}:{port}"
+      );
+    }
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn test_net_unix_sockets() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["unix:/run/app.sock", "unix:/tmp/sockets"]),
+      deny_net: Some(svec!["unix:/tmp/sockets/private.sock"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let cases = [
+      ("/run/app.sock", true),
+      ("/run/other.sock", false),
+      ("/tmp/sockets/a.sock", true),
+      ("/tmp/sockets/private.sock", false),
+    ];
+    for (path, is_ok) in cases {
+      let descriptor = NetDescriptor(Host::Unix(PathBuf::from(path)), None);
+      assert_eq!(perms.net.check(&descriptor, None).is_ok(), is_ok, "{path}");
+    }
+    // host names never match a socket path
+    assert!(perms
+      .net
+      .check(&NetDescriptor("localhost".parse().unwrap(), None), None)
+      .is_err());
+
+    let descriptor = "unix:/tmp/sockets".parse::<NetDescriptor>().unwrap();
+    assert_eq!(descriptor.to_string(), "unix:/tmp/sockets");
+    assert_eq!(
+      perms.net.query(Some(&descriptor)),
+      PermissionState::GrantedPartial
+    );
+    assert!("unix:".parse::<NetDescriptor>().is_err());
+
+    let mut container = PermissionsContainer::new(perms);
+    let api_name = "Deno.connect()";
+    assert!(container
+      .check_net_unix(Path::new("/tmp/sockets/a.sock"), api_name)
+      .unwrap());
+    assert!(container
+      .check_net_unix(Path::new("/tmp/sockets/private.sock"), api_name)
+      .is_err());
+    // left to the file system permissions
+    assert!(!container
+      .check_net_unix(Path::new("/var/run/docker.sock"), api_name)
+      .unwrap());
+
+    // a plain --allow-net doesn't grant any socket
+    let mut container = PermissionsContainer::new(
+      Permissions::from_options(&PermissionsOptions {
+        allow_net: Some(vec![]),
+        ..Default::default()
+      })
+      .unwrap(),
+    );
+    assert!(!container
+      .check_net_unix(Path::new("/var/run/docker.sock"), api_name)
+      .unwrap());
+  }
+
   #[test]
   fn test_host_parse() {
     let hosts = &[
@@ -3550,7 +4933,10 @@ mod tests {
       ),
       (
         "deno.land:8000",
-        Some(NetDescriptor(Host::Fqdn(fqdn!("deno.land")), Some(8000))),
+        Some(NetDescriptor(
+          Host::Fqdn(fqdn!("deno.land")),
+          Some(PortRange::from(8000)),
+        )),
       ),
       ("deno.land:", None),
       ("deno.land:a", None),
@@ -3569,7 +4955,7 @@ mod tests {
         "1.1.1.1:8000",
         Some(NetDescriptor(
           Host::Ip(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1))),
-          Some(8000),
+          Some(PortRange::from(8000)),
         )),
       ),
       ("::", None),
@@ -3591,11 +4977,37 @@ mod tests {
         "[::1]:443",
         Some(NetDescriptor(
           Host::Ip(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1))),
-          Some(443),
+          Some(PortRange::from(443)),
         )),
       ),
       ("", None),
       ("deno.land..", None),
+      (
+        "10.1.2.3/8:443",
+        Some(NetDescriptor(
+          Host::Cidr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8),
+          Some(PortRange::from(443)),
+        )),
+      ),
+      (
+        "[fd00::/8]:8000-8100",
+        Some(NetDescriptor(
+          Host::Cidr(IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0)), 8),
+          Some(PortRange {
+            start: 8000,
+            end: 8100,
+          }),
+        )),
+      ),
+      (
+        "*.internal:any",
+        Some(NetDescriptor(Host::Wildcard(fqdn!("internal")), None)),
+      ),
+      ("10.0.0.0/33", None),
+      ("10.0.0.0/", None),
+      ("deno.land:8100-8000", None),
+      ("deno.land:8000-", None),
+      ("*.1.1.1.1", None),
     ];
 
     for (input, expected) in cases {
@@ -10,6 +10,7 @@ use std::io::IsTerminal;
 use std::io::StderrLock;
 use std::io::StdinLock;
 use std::io::Write as IoWrite;
+use std::time::Duration;
 
 /// Helper function to make control characters visible so users can see the underlying filename.
 fn escape_control_characters(s: &str) -> std::borrow::Cow<str> {
@@ -36,11 +37,17 @@ pub const PERMISSION_EMOJI: &str = "⚠️";
 // 10kB of permission prompting should be enough for anyone
 const MAX_PERMISSION_PROMPT_LENGTH: usize = 10 * 1024;
 
-#[derive(Debug, Eq, PartialEq)]
+/// How long a permission granted with [`PromptResponse::AllowTemporarily`]
+/// lasts before it is revoked again.
+pub const TEMPORARY_GRANT_DURATION: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum PromptResponse {
   Allow,
   Deny,
   AllowAll,
+  /// Allow for [`TEMPORARY_GRANT_DURATION`].
+  AllowTemporarily,
 }
 
 static PERMISSION_PROMPTER: Lazy<Mutex<Box<dyn PermissionPrompter>>> =
@@ -310,8 +317,9 @@ impl PermissionPrompter for TtyPrompter {
     let api_name = api_name.map(escape_control_characters);
 
     // print to stderr so that if stdout is piped this is still displayed.
+    let minutes = TEMPORARY_GRANT_DURATION.as_secs() / 60;
     let opts: String = if is_unary {
-      format!("[y/n/A] (y = yes, allow; n = no, deny; A = allow all {name} permissions)")
+      format!("[y/n/t/A] (y = yes, allow; n = no, deny; t = allow for {minutes} minutes; A = allow all {name} permissions)")
     } else {
       "[y/n] (y = yes, allow; n = no, deny)".to_string()
     };
@@ -370,6 +378,15 @@ impl PermissionPrompter for TtyPrompter {
           writeln!(stderr_lock, "❌ {}", colors::bold(&msg)).unwrap();
           break PromptResponse::Deny;
         }
+        't' | 'T' if is_unary => {
+          clear_n_lines(
+            &mut stderr_lock,
+            if api_name.is_some() { 4 } else { 3 },
+          );
+          let msg = format!("Granted {message} for {minutes} minutes.");
+          writeln!(stderr_lock, "✅ {}", colors::bold(&msg)).unwrap();
+          break PromptResponse::AllowTemporarily;
+        }
         'A' if is_unary => {
           clear_n_lines(
             &mut stderr_lock,
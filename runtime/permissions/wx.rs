@@ -0,0 +1,18 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+
+/// Denies `api_name` when `--enforce-wx` was given, for APIs that map memory
+/// both writable and executable, ex. `Deno.UnsafeCallback`.
+pub(crate) fn check(enforce_wx: bool, api_name: &str) -> Result<(), AnyError> {
+  if !enforce_wx {
+    return Ok(());
+  }
+  Err(custom_error(
+    "PermissionDenied",
+    format!(
+      "{api_name} requires writable and executable memory, which is denied by the --enforce-wx flag"
+    ),
+  ))
+}
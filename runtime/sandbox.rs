@@ -0,0 +1,694 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Confines the process to the permissions it was started with using
+//! Landlock and seccomp, so that the kernel enforces them as well should the
+//! permission checks in an op or the V8 sandbox be bypassed.
+//!
+//! Both are inherited by threads and child processes spawned afterwards, but
+//! Landlock rules only apply to the calling thread and its descendants, so
+//! the sandbox has to be applied before any thread that may perform ops for
+//! user code is started, including the blocking pool of the async runtime
+//! and the threads of the V8 platform. The filesystem rules can only cover
+//! paths that exist at that time and cannot express `--deny-*` exceptions
+//! inside allowed paths; those are still enforced by the regular permission
+//! checks.
+//!
+//! Subprocesses started with a sandbox profile are confined the same way
+//! right before their program is executed.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_permissions::split_ffi_symbols;
+use deno_permissions::split_write_operations;
+use deno_permissions::unix_socket_path;
+use deno_permissions::Host;
+use deno_permissions::Permissions;
+use deno_permissions::PermissionsOptions;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Paths the runtime reads on its own regardless of the granted permissions.
+const RUNTIME_READ_PATHS: &[&str] =
+  &["/dev/urandom", "/etc/localtime", "/usr/share/zoneinfo"];
+
+/// Paths the runtime writes to on its own regardless of the granted
+/// permissions.
+const RUNTIME_WRITE_PATHS: &[&str] = &["/dev/null"];
+
+/// Paths read to resolve host names and to load system root certificates.
+const NET_READ_PATHS: &[&str] = &[
+  "/etc/hosts",
+  "/etc/resolv.conf",
+  "/etc/nsswitch.conf",
+  "/etc/gai.conf",
+  "/etc/host.conf",
+  "/etc/ssl",
+  "/etc/pki",
+  "/etc/ca-certificates",
+  "/lib",
+  "/lib64",
+  "/usr/lib",
+  "/usr/lib64",
+];
+
+/// Paths holding the dynamic linker, which has to be executable to start a
+/// program.
+const LINKER_PATHS: &[&str] = &["/lib", "/lib64", "/usr/lib", "/usr/lib64"];
+
+/// Paths read by the dynamic linker when starting a subprocess.
+const RUN_READ_PATHS: &[&str] = &[
+  "/bin",
+  "/etc/ld.so.cache",
+  "/lib",
+  "/lib64",
+  "/usr/bin",
+  "/usr/lib",
+  "/usr/lib64",
+];
+
+/// Whether one of the `denied` paths is inside or contains one of the
+/// allowed `paths`, where `None` allows every path.
+fn overlaps<'a>(
+  paths: &Option<Vec<PathBuf>>,
+  mut denied: impl Iterator<Item = &'a PathBuf>,
+) -> bool {
+  denied.any(|denied| {
+    paths.as_ref().map_or(true, |paths| {
+      paths
+        .iter()
+        .any(|path| denied.starts_with(path) || path.starts_with(denied))
+    })
+  })
+}
+
+/// The limits the kernel enforces for `--sandbox=kernel`, derived from the
+/// permissions granted on startup.
+#[derive(Clone, Debug, Default)]
+pub struct KernelSandbox {
+  /// The paths that can be read, or `None` if any path can be read.
+  read_paths: Option<Vec<PathBuf>>,
+  /// The paths that can be written, or `None` if any path can be written.
+  write_paths: Option<Vec<PathBuf>>,
+  /// The programs that can be executed, or `None` if any program can be
+  /// executed.
+  execute_paths: Option<Vec<PathBuf>>,
+  allow_net: bool,
+}
+
+impl KernelSandbox {
+  pub fn from_permissions(options: &PermissionsOptions) -> Self {
+    fn allowed_paths(list: &Option<Vec<PathBuf>>) -> Option<Vec<PathBuf>> {
+      match list {
+        Some(paths) if paths.is_empty() => None,
+        Some(paths) => Some(paths.clone()),
+        None => Some(Vec::new()),
+      }
+    }
+
+    if options.allow_all {
+      return Self {
+        read_paths: None,
+        write_paths: None,
+        execute_paths: None,
+        allow_net: true,
+      };
+    }
+
+    let allow_run = options.allow_run.is_some();
+    // `unix:<path>` entries only grant Unix domain sockets
+    let allow_net = options.allow_net.as_ref().is_some_and(|hosts| {
+      hosts.is_empty()
+        || hosts.iter().any(|host| unix_socket_path(host).is_none())
+    });
+    let mut read_paths = allowed_paths(&options.allow_read);
+    let mut write_paths = allowed_paths(&options.allow_write).map(|paths| {
+      paths
+        .into_iter()
+        .map(
+          |path| match path.to_str().and_then(split_write_operations) {
+            Some((path, _)) => PathBuf::from(path),
+            None => path,
+          },
+        )
+        .collect::<Vec<_>>()
+    });
+
+    // Native libraries have to be readable to be loaded.
+    match &options.allow_ffi {
+      Some(paths) if paths.is_empty() => read_paths = None,
+      Some(paths) => {
+        if let Some(read_paths) = &mut read_paths {
+          read_paths.extend(paths.iter().map(|path| {
+            match path.to_str().and_then(split_ffi_symbols) {
+              Some((path, _)) => PathBuf::from(path),
+              None => path.clone(),
+            }
+          }));
+        }
+      }
+      None => {}
+    }
+
+    let socket_paths = options
+      .allow_net
+      .iter()
+      .flatten()
+      .filter_map(|host| unix_socket_path(host))
+      .map(Path::to_path_buf)
+      .collect::<Vec<_>>();
+    for paths in [&mut read_paths, &mut write_paths].into_iter().flatten() {
+      paths.extend(socket_paths.iter().cloned());
+    }
+
+    if let Some(read_paths) = &mut read_paths {
+      let mut system_paths = RUNTIME_READ_PATHS.to_vec();
+      if allow_net {
+        system_paths.extend(NET_READ_PATHS);
+      }
+      if allow_run {
+        system_paths.extend(RUN_READ_PATHS);
+      }
+      read_paths.extend(system_paths.into_iter().map(PathBuf::from));
+    }
+    if let Some(write_paths) = &mut write_paths {
+      write_paths.extend(RUNTIME_WRITE_PATHS.iter().map(PathBuf::from));
+    }
+
+    Self {
+      read_paths,
+      write_paths,
+      execute_paths: (!allow_run).then(Vec::new),
+      allow_net,
+    }
+  }
+
+  /// The sandbox for a subprocess that runs `program` with `permissions`,
+  /// which have to be a subset of the parent's. Unless running subprocesses
+  /// is fully granted, only `program` itself can be executed.
+  ///
+  /// Fails if `permissions` deny something that they would otherwise grant,
+  /// which the kernel can't enforce.
+  pub fn for_subprocess(
+    permissions: &Permissions,
+    program: &Path,
+  ) -> Result<Self, AnyError> {
+    let hosts = permissions.net.granted();
+    // `unix:<path>` entries only grant Unix domain sockets
+    let allow_net = hosts.map_or(true, |hosts| {
+      hosts.iter().any(|host| !matches!(host.0, Host::Unix(_)))
+    });
+    let socket_paths = hosts
+      .into_iter()
+      .flatten()
+      .filter_map(|host| match &host.0 {
+        Host::Unix(path) => Some(path.clone()),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    let mut read_paths = permissions.read.granted().map(|list| {
+      list
+        .iter()
+        .map(|descriptor| descriptor.0.clone())
+        .collect::<Vec<_>>()
+    });
+    let mut write_paths = permissions.write.granted().map(|list| {
+      list
+        .iter()
+        .map(|descriptor| descriptor.0.clone())
+        .collect::<Vec<_>>()
+    });
+
+    let denied_read = permissions.read.flag_denied().iter().map(|d| &d.0);
+    let denied_write = permissions.write.flag_denied().iter().map(|d| &d.0);
+    if overlaps(&read_paths, denied_read)
+      || overlaps(&write_paths, denied_write)
+      || (allow_net && !permissions.net.flag_denied().is_empty())
+      || (permissions.run.granted().is_none()
+        && !permissions.run.flag_denied().is_empty())
+    {
+      bail!("The sandbox of a subprocess can't enforce denied permissions, grant fewer permissions instead");
+    }
+
+    for paths in [&mut read_paths, &mut write_paths].into_iter().flatten() {
+      paths.extend(socket_paths.iter().cloned());
+    }
+    let execute_paths = permissions.run.granted().map(|_| {
+      std::iter::once(program.to_path_buf())
+        .chain(LINKER_PATHS.iter().map(PathBuf::from))
+        .collect()
+    });
+
+    if let Some(read_paths) = &mut read_paths {
+      let mut system_paths = RUNTIME_READ_PATHS.to_vec();
+      system_paths.extend(RUN_READ_PATHS);
+      if allow_net {
+        system_paths.extend(NET_READ_PATHS);
+      }
+      read_paths.extend(system_paths.into_iter().map(PathBuf::from));
+      read_paths.push(program.to_path_buf());
+    }
+    if let Some(write_paths) = &mut write_paths {
+      write_paths.extend(RUNTIME_WRITE_PATHS.iter().map(PathBuf::from));
+    }
+
+    Ok(Self {
+      read_paths,
+      write_paths,
+      execute_paths,
+      allow_net,
+    })
+  }
+
+  /// Allows reading below `path` in addition to the granted permissions,
+  /// ex. for the module cache.
+  pub fn allow_read(&mut self, path: PathBuf) {
+    if let Some(read_paths) = &mut self.read_paths {
+      read_paths.push(path);
+    }
+  }
+
+  /// Allows writing below `path` in addition to the granted permissions.
+  pub fn allow_write(&mut self, path: PathBuf) {
+    if let Some(write_paths) = &mut self.write_paths {
+      write_paths.push(path);
+    }
+  }
+
+  /// Restricts the current process. This cannot be undone.
+  pub fn apply(&self) -> Result<(), AnyError> {
+    self
+      .prepare()?
+      .restrict()
+      .context("Failed to apply the kernel sandbox")
+  }
+
+  /// Builds the Landlock ruleset and seccomp filter up front, so that they
+  /// can be applied in a child process between `fork` and `exec`.
+  pub fn prepare(&self) -> Result<PreparedKernelSandbox, AnyError> {
+    #[cfg(all(
+      target_os = "linux",
+      any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    {
+      Ok(PreparedKernelSandbox {
+        inner: linux::prepare(self)?,
+      })
+    }
+    #[cfg(not(all(
+      target_os = "linux",
+      any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    {
+      deno_core::anyhow::bail!(
+        "The kernel sandbox is only supported on Linux on x86_64 and aarch64"
+      )
+    }
+  }
+}
+
+/// A [`KernelSandbox`] that is ready to be applied.
+pub struct PreparedKernelSandbox {
+  #[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+  ))]
+  inner: linux::Prepared,
+  #[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+  )))]
+  inner: std::convert::Infallible,
+}
+
+impl PreparedKernelSandbox {
+  /// Restricts the current process. This cannot be undone. Only makes
+  /// syscalls and doesn't allocate, so it can be used in `pre_exec`.
+  pub fn restrict(&self) -> std::io::Result<()> {
+    #[cfg(all(
+      target_os = "linux",
+      any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    {
+      self.inner.restrict()
+    }
+    #[cfg(not(all(
+      target_os = "linux",
+      any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    {
+      match self.inner {}
+    }
+  }
+}
+
+#[cfg(all(
+  target_os = "linux",
+  any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod linux {
+  use super::KernelSandbox;
+  use deno_core::anyhow::bail;
+  use deno_core::anyhow::Context;
+  use deno_core::error::AnyError;
+  use std::fs::OpenOptions;
+  use std::io::Error;
+  use std::io::ErrorKind;
+  use std::os::fd::AsRawFd;
+  use std::os::fd::FromRawFd;
+  use std::os::fd::OwnedFd;
+  use std::os::unix::fs::OpenOptionsExt;
+  use std::path::Path;
+
+  // linux/landlock.h
+  const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+  const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+  const LANDLOCK_ACCESS_FS_EXECUTE: u64 = 1 << 0;
+  const LANDLOCK_ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+  const LANDLOCK_ACCESS_FS_READ_FILE: u64 = 1 << 2;
+  const LANDLOCK_ACCESS_FS_READ_DIR: u64 = 1 << 3;
+  const LANDLOCK_ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+  const LANDLOCK_ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+  const LANDLOCK_ACCESS_FS_MAKE_CHAR: u64 = 1 << 6;
+  const LANDLOCK_ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+  const LANDLOCK_ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+  const LANDLOCK_ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+  const LANDLOCK_ACCESS_FS_MAKE_FIFO: u64 = 1 << 10;
+  const LANDLOCK_ACCESS_FS_MAKE_BLOCK: u64 = 1 << 11;
+  const LANDLOCK_ACCESS_FS_MAKE_SYM: u64 = 1 << 12;
+  const LANDLOCK_ACCESS_FS_REFER: u64 = 1 << 13;
+  const LANDLOCK_ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+  /// The accesses that can be granted on a file rather than a directory.
+  const ACCESS_FILE: u64 = LANDLOCK_ACCESS_FS_EXECUTE
+    | LANDLOCK_ACCESS_FS_WRITE_FILE
+    | LANDLOCK_ACCESS_FS_READ_FILE
+    | LANDLOCK_ACCESS_FS_TRUNCATE;
+
+  #[repr(C)]
+  struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+  }
+
+  #[repr(C, packed)]
+  struct LandlockPathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+  }
+
+  // linux/audit.h
+  #[cfg(target_arch = "x86_64")]
+  const AUDIT_ARCH: u32 = 0xc000_003e;
+  #[cfg(target_arch = "aarch64")]
+  const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+  // offsets into `libc::seccomp_data`
+  const SECCOMP_DATA_NR: u32 = 0;
+  const SECCOMP_DATA_ARCH: u32 = 4;
+  const SECCOMP_DATA_ARG0: u32 = 16;
+
+  /// Syscalls that user code never needs and that could be used to escape
+  /// the sandbox or attack other processes.
+  const DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_add_key,
+    libc::SYS_bpf,
+    libc::SYS_chroot,
+    libc::SYS_delete_module,
+    libc::SYS_finit_module,
+    libc::SYS_init_module,
+    libc::SYS_io_uring_setup,
+    libc::SYS_kexec_load,
+    libc::SYS_keyctl,
+    libc::SYS_mount,
+    libc::SYS_perf_event_open,
+    libc::SYS_pivot_root,
+    libc::SYS_process_vm_readv,
+    libc::SYS_process_vm_writev,
+    libc::SYS_ptrace,
+    libc::SYS_reboot,
+    libc::SYS_request_key,
+    libc::SYS_setns,
+    libc::SYS_swapoff,
+    libc::SYS_swapon,
+    libc::SYS_umount2,
+    libc::SYS_unshare,
+    libc::SYS_userfaultfd,
+  ];
+
+  pub struct Prepared {
+    ruleset: Option<OwnedFd>,
+    filter: Vec<libc::sock_filter>,
+  }
+
+  pub fn prepare(sandbox: &KernelSandbox) -> Result<Prepared, AnyError> {
+    Ok(Prepared {
+      ruleset: create_ruleset(sandbox)?,
+      filter: seccomp_filter(sandbox),
+    })
+  }
+
+  impl Prepared {
+    pub fn restrict(&self) -> Result<(), Error> {
+      // Required to install a seccomp filter or a Landlock ruleset without
+      // CAP_SYS_ADMIN, and ensures that setuid binaries can't lift either.
+      // SAFETY: libc call with constant arguments.
+      if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(Error::last_os_error());
+      }
+
+      if let Some(ruleset) = &self.ruleset {
+        // SAFETY: `ruleset` is a valid Landlock ruleset file descriptor.
+        let result = unsafe {
+          libc::syscall(
+            libc::SYS_landlock_restrict_self,
+            ruleset.as_raw_fd(),
+            0u32,
+          )
+        };
+        if result != 0 {
+          return Err(Error::last_os_error());
+        }
+      }
+
+      let program = libc::sock_fprog {
+        len: self.filter.len() as libc::c_ushort,
+        filter: self.filter.as_ptr() as *mut libc::sock_filter,
+      };
+      // Synchronizing the filter to all threads also covers the threads that
+      // were started before the sandbox was applied.
+      // SAFETY: `program` and the filter outlive the call, which doesn't
+      // write to them.
+      let result = unsafe {
+        libc::syscall(
+          libc::SYS_seccomp,
+          libc::SECCOMP_SET_MODE_FILTER,
+          libc::SECCOMP_FILTER_FLAG_TSYNC,
+          &program as *const libc::sock_fprog,
+        )
+      };
+      if result < 0 {
+        return Err(Error::last_os_error());
+      }
+      if result > 0 {
+        // the id of a thread that couldn't be synchronized
+        return Err(Error::from(ErrorKind::Other));
+      }
+      Ok(())
+    }
+  }
+
+  fn create_ruleset(
+    sandbox: &KernelSandbox,
+  ) -> Result<Option<OwnedFd>, AnyError> {
+    // SAFETY: querying the ABI version doesn't read the attributes.
+    let abi = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_create_ruleset,
+        std::ptr::null::<LandlockRulesetAttr>(),
+        0usize,
+        LANDLOCK_CREATE_RULESET_VERSION,
+      )
+    };
+    if abi < 1 {
+      bail!("Landlock is not supported by this kernel, Linux 5.13 or newer with Landlock enabled is required");
+    }
+
+    let read_access =
+      LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_READ_DIR;
+    let mut write_access = LANDLOCK_ACCESS_FS_WRITE_FILE
+      | LANDLOCK_ACCESS_FS_REMOVE_DIR
+      | LANDLOCK_ACCESS_FS_REMOVE_FILE
+      | LANDLOCK_ACCESS_FS_MAKE_CHAR
+      | LANDLOCK_ACCESS_FS_MAKE_DIR
+      | LANDLOCK_ACCESS_FS_MAKE_REG
+      | LANDLOCK_ACCESS_FS_MAKE_SOCK
+      | LANDLOCK_ACCESS_FS_MAKE_FIFO
+      | LANDLOCK_ACCESS_FS_MAKE_BLOCK
+      | LANDLOCK_ACCESS_FS_MAKE_SYM;
+    if abi >= 2 {
+      write_access |= LANDLOCK_ACCESS_FS_REFER;
+    }
+    if abi >= 3 {
+      write_access |= LANDLOCK_ACCESS_FS_TRUNCATE;
+    }
+
+    let mut handled_access = 0;
+    if sandbox.read_paths.is_some() {
+      handled_access |= read_access;
+    }
+    if sandbox.write_paths.is_some() {
+      handled_access |= write_access;
+    }
+    if sandbox.execute_paths.is_some() {
+      handled_access |= LANDLOCK_ACCESS_FS_EXECUTE;
+    }
+    if handled_access == 0 {
+      return Ok(None);
+    }
+
+    let attr = LandlockRulesetAttr {
+      handled_access_fs: handled_access,
+    };
+    // SAFETY: `attr` outlives the call and its size is passed along.
+    let fd = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_create_ruleset,
+        &attr as *const LandlockRulesetAttr,
+        std::mem::size_of::<LandlockRulesetAttr>(),
+        0u32,
+      )
+    };
+    if fd < 0 {
+      return Err(Error::last_os_error().into());
+    }
+    // SAFETY: the kernel returned a new file descriptor owned by nobody else.
+    let ruleset = unsafe { OwnedFd::from_raw_fd(fd as i32) };
+
+    for path in sandbox.read_paths.iter().flatten() {
+      add_path_rule(&ruleset, path, read_access & handled_access)?;
+    }
+    for path in sandbox.write_paths.iter().flatten() {
+      add_path_rule(&ruleset, path, write_access & handled_access)?;
+    }
+    for path in sandbox.execute_paths.iter().flatten() {
+      add_path_rule(&ruleset, path, LANDLOCK_ACCESS_FS_EXECUTE)?;
+    }
+    Ok(Some(ruleset))
+  }
+
+  fn add_path_rule(
+    ruleset: &OwnedFd,
+    path: &Path,
+    access: u64,
+  ) -> Result<(), AnyError> {
+    let file = match OpenOptions::new()
+      .read(true)
+      .custom_flags(libc::O_PATH | libc::O_CLOEXEC)
+      .open(path)
+    {
+      Ok(file) => file,
+      // there is nothing to allow access to yet
+      Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed to open '{}' for the sandbox", path.display())
+        })
+      }
+    };
+    let access = if file.metadata()?.is_dir() {
+      access
+    } else {
+      access & ACCESS_FILE
+    };
+    if access == 0 {
+      return Ok(());
+    }
+
+    let attr = LandlockPathBeneathAttr {
+      allowed_access: access,
+      parent_fd: file.as_raw_fd(),
+    };
+    // SAFETY: `attr` and the file descriptors outlive the call.
+    let result = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_add_rule,
+        ruleset.as_raw_fd(),
+        LANDLOCK_RULE_PATH_BENEATH,
+        &attr as *const LandlockPathBeneathAttr,
+        0u32,
+      )
+    };
+    if result != 0 {
+      return Err(Error::last_os_error()).with_context(|| {
+        format!(
+          "Failed to allow access to '{}' in the sandbox",
+          path.display()
+        )
+      });
+    }
+    Ok(())
+  }
+
+  fn seccomp_filter(sandbox: &KernelSandbox) -> Vec<libc::sock_filter> {
+    let deny = libc::SECCOMP_RET_ERRNO | libc::EPERM as u32;
+
+    let mut filter = vec![
+      load(SECCOMP_DATA_ARCH),
+      jump(libc::BPF_JEQ, AUDIT_ARCH, 1, 0),
+      ret(libc::SECCOMP_RET_KILL_PROCESS),
+      load(SECCOMP_DATA_NR),
+    ];
+    // x32 syscalls share the x86_64 audit arch but use other numbers
+    #[cfg(target_arch = "x86_64")]
+    filter.extend([jump(libc::BPF_JGE, 0x4000_0000, 0, 1), ret(deny)]);
+
+    let mut denied_syscalls = DENIED_SYSCALLS.to_vec();
+    // otherwise the Landlock rules decide what can be executed
+    if matches!(&sandbox.execute_paths, Some(paths) if paths.is_empty()) {
+      denied_syscalls.extend([libc::SYS_execve, libc::SYS_execveat]);
+    }
+    for nr in denied_syscalls {
+      filter.extend([jump(libc::BPF_JEQ, nr as u32, 0, 1), ret(deny)]);
+    }
+    if !sandbox.allow_net {
+      // Only Unix domain sockets are allowed, the Landlock rules cover their
+      // paths. Other families, ex. AF_VSOCK or AF_PACKET, reach the network
+      // as well.
+      filter.extend([
+        jump(libc::BPF_JEQ, libc::SYS_socket as u32, 0, 3),
+        load(SECCOMP_DATA_ARG0),
+        jump(libc::BPF_JEQ, libc::AF_UNIX as u32, 1, 0),
+        ret(deny),
+      ]);
+    }
+    filter.push(ret(libc::SECCOMP_RET_ALLOW));
+    filter
+  }
+
+  fn load(offset: u32) -> libc::sock_filter {
+    libc::sock_filter {
+      code: (libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16,
+      jt: 0,
+      jf: 0,
+      k: offset,
+    }
+  }
+
+  fn jump(op: u32, value: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter {
+      code: (libc::BPF_JMP | op | libc::BPF_K) as u16,
+      jt,
+      jf,
+      k: value,
+    }
+  }
+
+  fn ret(value: u32) -> libc::sock_filter {
+    libc::sock_filter {
+      code: (libc::BPF_RET | libc::BPF_K) as u16,
+      jt: 0,
+      jf: 0,
+      k: value,
+    }
+  }
+}
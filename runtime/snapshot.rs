@@ -94,6 +94,14 @@ impl deno_node::NodePermissions for Permissions {
   ) -> Result<(), deno_core::error::AnyError> {
     unreachable!("snapshotting!")
   }
+  fn check_write_operation(
+    &mut self,
+    _p: &Path,
+    _operation: deno_permissions::WriteOperation,
+    _api_name: &str,
+  ) -> Result<(), deno_core::error::AnyError> {
+    unreachable!("snapshotting!")
+  }
   fn check_sys(
     &mut self,
     _kind: &str,
@@ -127,6 +135,23 @@ impl deno_net::NetPermissions for Permissions {
   ) -> Result<(), deno_core::error::AnyError> {
     unreachable!("snapshotting!")
   }
+
+  fn check_vsock(
+    &mut self,
+    _cid: u32,
+    _port: u32,
+    _api_name: &str,
+  ) -> Result<(), deno_core::error::AnyError> {
+    unreachable!("snapshotting!")
+  }
+
+  fn check_unix(
+    &mut self,
+    _p: &Path,
+    _api_name: &str,
+  ) -> Result<bool, deno_core::error::AnyError> {
+    unreachable!("snapshotting!")
+  }
 }
 
 impl deno_fs::FsPermissions for Permissions {
@@ -208,6 +233,15 @@ impl deno_kv::sqlite::SqliteDbHandlerPermissions for Permissions {
   ) -> Result<(), AnyError> {
     unreachable!("snapshotting!")
   }
+
+  fn check_write_operation(
+    &mut self,
+    _path: &Path,
+    _operation: deno_permissions::WriteOperation,
+    _api_name: &str,
+  ) -> Result<(), AnyError> {
+    unreachable!("snapshotting!")
+  }
 }
 
 pub fn create_runtime_snapshot(
@@ -236,7 +270,7 @@ pub fn create_runtime_snapshot(
       None,
       None,
     ),
-    deno_webstorage::deno_webstorage::init_ops_and_esm(None),
+    deno_webstorage::deno_webstorage::init_ops_and_esm(None, None),
     deno_crypto::deno_crypto::init_ops_and_esm(None),
     deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
       deno_broadcast_channel::InMemoryBroadcastChannel::default(),
@@ -244,9 +278,10 @@ pub fn create_runtime_snapshot(
     deno_ffi::deno_ffi::init_ops_and_esm::<Permissions>(),
     deno_net::deno_net::init_ops_and_esm::<Permissions>(None, None),
     deno_tls::deno_tls::init_ops_and_esm(),
-    deno_kv::deno_kv::init_ops_and_esm(deno_kv::sqlite::SqliteDbHandler::<
-      Permissions,
-    >::new(None, None)),
+    deno_kv::deno_kv::init_ops_and_esm(
+      deno_kv::sqlite::SqliteDbHandler::<Permissions>::new(None, None),
+      Default::default(),
+    ),
     deno_cron::deno_cron::init_ops_and_esm(
       deno_cron::local::LocalCronHandler::new(),
     ),
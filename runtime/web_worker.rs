@@ -11,7 +11,9 @@ use crate::worker::validate_import_attributes_callback;
 use crate::worker::FormatJsErrorFn;
 use crate::BootstrapOptions;
 use deno_broadcast_channel::InMemoryBroadcastChannel;
+use deno_cache::CacheBackend;
 use deno_cache::CreateCache;
+use deno_cache::RcDynamicCache;
 use deno_cache::SqliteBackedCache;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -42,6 +44,8 @@ use deno_cron::local::LocalCronHandler;
 use deno_fs::FileSystem;
 use deno_http::DefaultHttpPropertyExtractor;
 use deno_io::Stdio;
+use deno_kv::changes::KvChangeFeed;
+use deno_kv::dynamic::KvBackend;
 use deno_kv::dynamic::MultiBackendDbHandler;
 use deno_node::NodeResolver;
 use deno_permissions::PermissionsContainer;
@@ -376,6 +380,18 @@ pub struct WebWorkerOptions {
   pub shared_array_buffer_store: Option<SharedArrayBufferStore>,
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
+  /// The maximum total size of the entries in the Cache API storage, in
+  /// bytes. Least recently used entries are evicted to stay below it.
+  pub cache_storage_max_size: Option<u64>,
+  /// Backs the Cache API with storage provided by the embedder instead of
+  /// the SQLite database in `cache_storage_dir`.
+  pub cache_backend: Option<Arc<dyn CacheBackend>>,
+  /// Additional `Deno.openKv()` backends, which take precedence over the
+  /// built-in remote and SQLite backends for the paths they handle.
+  pub kv_backends: Vec<Arc<dyn KvBackend + Send + Sync>>,
+  /// Receives the atomic operations committed through `Deno.Kv`. Share it
+  /// between workers to observe the commits of all of them.
+  pub kv_change_feed: KvChangeFeed,
   pub stdio: Stdio,
   pub feature_checker: Arc<FeatureChecker>,
   pub strace_ops: Option<Vec<String>>,
@@ -418,10 +434,22 @@ impl WebWorker {
 
     // Permissions: many ops depend on this
     let enable_testing_features = options.bootstrap.enable_testing_features;
-    let create_cache = options.cache_storage_dir.map(|storage_dir| {
-      let create_cache_fn = move || SqliteBackedCache::new(storage_dir.clone());
-      CreateCache(Arc::new(create_cache_fn))
-    });
+    let cache_storage_max_size = options.cache_storage_max_size;
+    let create_cache = match options.cache_backend.clone() {
+      Some(backend) => {
+        let create_cache_fn = move || backend.create_cache();
+        Some(CreateCache(Arc::new(create_cache_fn)))
+      }
+      None => options.cache_storage_dir.map(|storage_dir| {
+        let create_cache_fn = move || {
+          RcDynamicCache::new(
+            SqliteBackedCache::new(storage_dir.clone())
+              .with_max_size(cache_storage_max_size),
+          )
+        };
+        CreateCache(Arc::new(create_cache_fn))
+      }),
+    };
 
     // NOTE(bartlomieju): ordering is important here, keep it in sync with
     // `runtime/worker.rs` and `runtime/snapshot.rs`!
@@ -448,15 +476,13 @@ impl WebWorker {
           ..Default::default()
         },
       ),
-      deno_cache::deno_cache::init_ops_and_esm::<SqliteBackedCache>(
-        create_cache,
-      ),
+      deno_cache::deno_cache::init_ops_and_esm::<RcDynamicCache>(create_cache),
       deno_websocket::deno_websocket::init_ops_and_esm::<PermissionsContainer>(
         options.bootstrap.user_agent.clone(),
         options.root_cert_store_provider.clone(),
         options.unsafely_ignore_certificate_errors.clone(),
       ),
-      deno_webstorage::deno_webstorage::init_ops_and_esm(None).disable(),
+      deno_webstorage::deno_webstorage::init_ops_and_esm(None, None).disable(),
       deno_crypto::deno_crypto::init_ops_and_esm(options.seed),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
         options.broadcast_channel.clone(),
@@ -480,7 +506,9 @@ impl WebWorker {
             client_cert_chain_and_key: TlsKeys::Null,
             proxy: None,
           },
-        ),
+        )
+        .with_backends(&options.kv_backends),
+        options.kv_change_feed.clone(),
       ),
       deno_cron::deno_cron::init_ops_and_esm(LocalCronHandler::new()),
       deno_napi::deno_napi::init_ops_and_esm::<PermissionsContainer>(),
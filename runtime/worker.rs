@@ -10,7 +10,9 @@ use std::time::Duration;
 use std::time::Instant;
 
 use deno_broadcast_channel::InMemoryBroadcastChannel;
+use deno_cache::CacheBackend;
 use deno_cache::CreateCache;
+use deno_cache::RcDynamicCache;
 use deno_cache::SqliteBackedCache;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -37,11 +39,14 @@ use deno_cron::local::LocalCronHandler;
 use deno_fs::FileSystem;
 use deno_http::DefaultHttpPropertyExtractor;
 use deno_io::Stdio;
+use deno_kv::changes::KvChangeFeed;
+use deno_kv::dynamic::KvBackend;
 use deno_kv::dynamic::MultiBackendDbHandler;
 use deno_permissions::PermissionsContainer;
 use deno_tls::RootCertStoreProvider;
 use deno_tls::TlsKeys;
 use deno_web::BlobStore;
+use deno_webstorage::LocalStorageLocation;
 use log::debug;
 
 use crate::code_cache::CodeCache;
@@ -176,7 +181,25 @@ pub struct WorkerOptions {
   /// error in JavaScript.
   pub get_error_class_fn: Option<GetErrorClassFn>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
+  /// The maximum total size of the entries in the Cache API storage, in
+  /// bytes. Least recently used entries are evicted to stay below it.
+  pub cache_storage_max_size: Option<u64>,
+  /// Backs the Cache API with storage provided by the embedder instead of
+  /// the SQLite database in `cache_storage_dir`.
+  pub cache_backend: Option<Arc<dyn CacheBackend>>,
   pub origin_storage_dir: Option<std::path::PathBuf>,
+  /// Where `localStorage` keeps its data. Defaults to the origin storage
+  /// directory.
+  pub local_storage_location: Option<LocalStorageLocation>,
+  /// The maximum size of `localStorage` and `sessionStorage` each, in bytes.
+  /// Defaults to 10MiB.
+  pub web_storage_max_size: Option<usize>,
+  /// Additional `Deno.openKv()` backends, which take precedence over the
+  /// built-in remote and SQLite backends for the paths they handle.
+  pub kv_backends: Vec<Arc<dyn KvBackend + Send + Sync>>,
+  /// Receives the atomic operations committed through `Deno.Kv`. Share it
+  /// between workers to observe the commits of all of them.
+  pub kv_change_feed: KvChangeFeed,
   pub blob_store: Arc<BlobStore>,
   pub broadcast_channel: InMemoryBroadcastChannel,
 
@@ -221,7 +244,13 @@ impl Default for WorkerOptions {
       format_js_error_fn: Default::default(),
       get_error_class_fn: Default::default(),
       origin_storage_dir: Default::default(),
+      local_storage_location: Default::default(),
+      web_storage_max_size: Default::default(),
+      kv_backends: Default::default(),
+      kv_change_feed: Default::default(),
       cache_storage_dir: Default::default(),
+      cache_storage_max_size: Default::default(),
+      cache_backend: Default::default(),
       broadcast_channel: Default::default(),
       root_cert_store_provider: Default::default(),
       node_resolver: Default::default(),
@@ -341,10 +370,22 @@ impl MainWorker {
     // Permissions: many ops depend on this
     let enable_testing_features = options.bootstrap.enable_testing_features;
     let exit_code = ExitCode(Arc::new(AtomicI32::new(0)));
-    let create_cache = options.cache_storage_dir.map(|storage_dir| {
-      let create_cache_fn = move || SqliteBackedCache::new(storage_dir.clone());
-      CreateCache(Arc::new(create_cache_fn))
-    });
+    let cache_storage_max_size = options.cache_storage_max_size;
+    let create_cache = match options.cache_backend.clone() {
+      Some(backend) => {
+        let create_cache_fn = move || backend.create_cache();
+        Some(CreateCache(Arc::new(create_cache_fn)))
+      }
+      None => options.cache_storage_dir.map(|storage_dir| {
+        let create_cache_fn = move || {
+          RcDynamicCache::new(
+            SqliteBackedCache::new(storage_dir.clone())
+              .with_max_size(cache_storage_max_size),
+          )
+        };
+        CreateCache(Arc::new(create_cache_fn))
+      }),
+    };
 
     // NOTE(bartlomieju): ordering is important here, keep it in sync with
     // `runtime/web_worker.rs` and `runtime/snapshot.rs`!
@@ -370,16 +411,20 @@ impl MainWorker {
           ..Default::default()
         },
       ),
-      deno_cache::deno_cache::init_ops_and_esm::<SqliteBackedCache>(
-        create_cache,
-      ),
+      deno_cache::deno_cache::init_ops_and_esm::<RcDynamicCache>(create_cache),
       deno_websocket::deno_websocket::init_ops_and_esm::<PermissionsContainer>(
         options.bootstrap.user_agent.clone(),
         options.root_cert_store_provider.clone(),
         options.unsafely_ignore_certificate_errors.clone(),
       ),
       deno_webstorage::deno_webstorage::init_ops_and_esm(
-        options.origin_storage_dir.clone(),
+        options.local_storage_location.clone().or_else(|| {
+          options
+            .origin_storage_dir
+            .clone()
+            .map(LocalStorageLocation::OriginStorageDir)
+        }),
+        options.web_storage_max_size,
       ),
       deno_crypto::deno_crypto::init_ops_and_esm(options.seed),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
@@ -404,7 +449,9 @@ impl MainWorker {
             client_cert_chain_and_key: TlsKeys::Null,
             proxy: None,
           },
-        ),
+        )
+        .with_backends(&options.kv_backends),
+        options.kv_change_feed.clone(),
       ),
       deno_cron::deno_cron::init_ops_and_esm(LocalCronHandler::new()),
       deno_napi::deno_napi::init_ops_and_esm::<PermissionsContainer>(),
@@ -490,7 +537,8 @@ impl MainWorker {
       extension_transpiler: Some(Rc::new(|specifier, source| {
         maybe_transpile_source(specifier, source)
       })),
-      inspector: options.maybe_inspector_server.is_some(),
+      // Always created, as `node:inspector` connects sessions to it.
+      inspector: true,
       is_main: true,
       feature_checker: Some(options.feature_checker.clone()),
       op_metrics_factory_fn,
@@ -565,9 +613,11 @@ impl MainWorker {
         options.should_break_on_first_statement
           || options.should_wait_for_inspector_session,
       );
+    }
 
-      // Put inspector handle into the op state so we can put a breakpoint when
-      // executing a CJS entrypoint.
+    // Put inspector handle into the op state so we can put a breakpoint when
+    // executing a CJS entrypoint, and so `node:inspector` can connect to it.
+    {
       let op_state = js_runtime.op_state();
       let inspector = js_runtime.inspector();
       op_state.borrow_mut().put(inspector);
@@ -846,6 +846,21 @@ fn compile_npm_file_system() {
   });
 }
 
+#[test]
+fn compile_npm_exports_imports_patterns() {
+  run_npm_bin_compile_test(RunNpmBinCompileOptions {
+    input_specifier: "compile/npm_exports_imports_patterns/main.ts",
+    copy_temp_dir: Some("compile/npm_exports_imports_patterns"),
+    compile_args: vec![],
+    run_args: vec![],
+    output_file: "compile/npm_exports_imports_patterns/main.out",
+    node_modules_dir: false,
+    input_name: Some("binary"),
+    expected_name: "binary",
+    exit_code: 0,
+  });
+}
+
 #[test]
 fn compile_npm_bin_esm() {
   run_npm_bin_compile_test(RunNpmBinCompileOptions {
@@ -25,6 +25,7 @@ util::unit_test_factory!(
     copy_file_test,
     custom_event_test,
     cron_test,
+    dir_handle_test,
     dir_test,
     dom_exception_test,
     error_stack_test,
@@ -113,6 +114,7 @@ util::unit_test_factory!(
     worker_test,
     write_file_test,
     write_text_file_test,
+    xattr_test,
   ]
 );
 
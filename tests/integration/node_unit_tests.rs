@@ -57,7 +57,9 @@ util::unit_test_factory!(
     assertion_error_test,
     buffer_test,
     child_process_test,
+    cluster_test,
     console_test,
+    crypto_argon2_test = crypto / crypto_argon2_test,
     crypto_cipher_gcm_test = crypto / crypto_cipher_gcm_test,
     crypto_cipher_test = crypto / crypto_cipher_test,
     crypto_hash_test = crypto / crypto_hash_test,
@@ -73,6 +75,7 @@ util::unit_test_factory!(
     fs_test,
     http_test,
     http2_test,
+    inspector_test,
     _randomBytes_test = internal / _randomBytes_test,
     _randomFill_test = internal / _randomFill_test,
     _randomInt_test = internal / _randomInt_test,
@@ -514,7 +514,7 @@ fn _090_run_permissions_request() {
         "┌ ⚠️  Deno requests run access to \"ls\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-run to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all run permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all run permissions)",
       ));
       console.human_delay();
       console.write_line_raw("y");
@@ -523,7 +523,7 @@ fn _090_run_permissions_request() {
         "┌ ⚠️  Deno requests run access to \"cat\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-run to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all run permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all run permissions)",
       ));
       console.human_delay();
       console.write_line_raw("n");
@@ -543,7 +543,7 @@ fn _090_run_permissions_request_sync() {
         "┌ ⚠️  Deno requests run access to \"ls\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-run to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all run permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all run permissions)",
       ));
       console.human_delay();
       console.write_line_raw("y");
@@ -552,7 +552,7 @@ fn _090_run_permissions_request_sync() {
         "┌ ⚠️  Deno requests run access to \"cat\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-run to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all run permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all run permissions)",
       ));
       console.human_delay();
       console.write_line_raw("n");
@@ -573,7 +573,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests run access to \"FOO\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-run to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all run permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all run permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -583,7 +583,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests read access to \"FOO\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-read to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -593,7 +593,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests write access to \"FOO\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-write to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all write permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all write permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -603,7 +603,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests net access to \"foo\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-net to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all net permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all net permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -613,7 +613,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests env access to \"FOO\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-env to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all env permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all env permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -623,7 +623,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests sys access to \"loadavg\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-sys to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all sys permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all sys permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -633,7 +633,7 @@ fn permissions_prompt_allow_all() {
         "┌ ⚠️  Deno requests ffi access to \"FOO\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-ffi to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all ffi permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all ffi permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -652,7 +652,7 @@ fn permissions_prompt_allow_all_2() {
       console.expect(concat!(
         "┌ ⚠️  Deno requests env access to \"FOO\".\r\n",
         "├ Run again with --allow-env to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all env permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all env permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -663,7 +663,7 @@ fn permissions_prompt_allow_all_2() {
         "┌ ⚠️  Deno requests sys access to \"loadavg\".\r\n",
         "├ Requested by `Deno.loadavg()` API.\r\n",
         "├ Run again with --allow-sys to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all sys permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all sys permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -674,7 +674,7 @@ fn permissions_prompt_allow_all_2() {
         "┌ ⚠️  Deno requests read access to <CWD>.\r\n",
         "├ Requested by `Deno.cwd()` API.\r\n",
         "├ Run again with --allow-read to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
       ));
       console.human_delay();
       console.write_line_raw("A");
@@ -693,7 +693,7 @@ fn permissions_prompt_allow_all_lowercase_a() {
         "┌ ⚠️  Deno requests run access to \"FOO\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-run to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all run permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all run permissions)",
       ));
       console.human_delay();
       console.write_line_raw("a");
@@ -736,7 +736,7 @@ fn permissions_cache() {
         "┌ ⚠️  Deno requests read access to \"foo\".\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-read to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
       ));
       console.human_delay();
       console.write_line_raw("y");
@@ -746,6 +746,26 @@ fn permissions_cache() {
     });
 }
 
+#[test]
+fn permissions_temporary_grant() {
+  TestContext::default()
+    .new_command()
+    .args_vec(["run", "--quiet", "run/permissions_temporary_grant.ts"])
+    .with_pty(|mut console| {
+      console.expect(concat!(
+        "┌ ⚠️  Deno requests read access to \"foo\".\r\n",
+        "├ Requested by `Deno.permissions.request()` API.\r\n",
+        "├ Run again with --allow-read to bypass this prompt.\r\n",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
+      ));
+      console.human_delay();
+      console.write_line_raw("t");
+      console.expect("✅ Granted read access to \"foo\" for 15 minutes.");
+      console.expect("granted");
+      console.expect("prompt");
+    });
+}
+
 itest!(env_file {
   args: "run --env=env --allow-env run/env_file.ts",
   output: "run/env_file.out",
@@ -2968,7 +2988,7 @@ mod permissions {
           "┌ ⚠️  Deno requests read access to \"foo\".\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
         ));
         console.human_delay();
         console.write_line_raw("y");
@@ -2976,7 +2996,7 @@ mod permissions {
           "┌ ⚠️  Deno requests read access to \"bar\".\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
         ));
         console.human_delay();
         console.write_line_raw("n");
@@ -2996,7 +3016,7 @@ mod permissions {
           "┌ ⚠️  Deno requests read access to \"foo\".\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
         ));
         console.human_delay();
         console.write_line_raw("y");
@@ -3004,7 +3024,7 @@ mod permissions {
           "┌ ⚠️  Deno requests read access to \"bar\".\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
         ));
         console.human_delay();
         console.write_line_raw("n");
@@ -3024,7 +3044,7 @@ mod permissions {
           "┌ ⚠️  Deno requests read access.\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
         ));
         console.human_delay();
         console.write_line_raw("y\n");
@@ -3047,7 +3067,7 @@ mod permissions {
           "┌ ⚠️  Deno requests read access.\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions)",
         ));
         console.human_delay();
         console.write_line_raw("y");
@@ -3203,7 +3223,7 @@ fn issue9750() {
         "┌ ⚠️  Deno requests env access.\r\n",
         "├ Requested by `Deno.permissions.request()` API.\r\n",
         "├ Run again with --allow-env to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all env permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all env permissions)",
       ));
       console.human_delay();
       console.write_line_raw("n");
@@ -3211,7 +3231,7 @@ fn issue9750() {
       console.expect(concat!(
         "┌ ⚠️  Deno requests env access to \"SECRET\".\r\n",
         "├ Run again with --allow-env to bypass this prompt.\r\n",
-        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all env permissions)",
+        "└ Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all env permissions)",
       ));
       console.human_delay();
       console.write_line_raw("n");
@@ -4608,7 +4628,7 @@ fn stdio_streams_are_locked_in_permission_prompt() {
       console.expect(malicious_output);
       console.write_line(r#"Deno.readTextFileSync('../Cargo.toml');"#);
       // We will get a permission prompt
-      console.expect("Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions) > ");
+      console.expect("Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions) > ");
       // The worker is blocked, so nothing else should get written here
       console.human_delay();
       console.write_line_raw("i");
@@ -4618,7 +4638,7 @@ fn stdio_streams_are_locked_in_permission_prompt() {
       } else {
         "\r\n"
       };
-      console.expect_raw_next(format!("i{newline}\u{1b}[1A\u{1b}[0J└ Unrecognized option. Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions) > "));
+      console.expect_raw_next(format!("i{newline}\u{1b}[1A\u{1b}[0J└ Unrecognized option. Allow? [y/n/t/A] (y = yes, allow; n = no, deny; t = allow for 15 minutes; A = allow all read permissions) > "));
       console.human_delay();
       console.write_line_raw("y");
       // We ensure that nothing gets written here between the permission prompt and this text, despire the delay
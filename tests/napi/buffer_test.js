@@ -0,0 +1,32 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+  loadTestLibrary,
+} from "./common.js";
+import { Buffer } from "node:buffer";
+
+const buffer = loadTestLibrary();
+
+Deno.test({
+  name: "napi buffer from arraybuffer",
+  // not declared by napi-sys, which the test library uses on Windows
+  ignore: Deno.build.os === "windows",
+  fn() {
+    const arrayBuffer = new Uint8Array([1, 2, 3, 4, 5]).buffer;
+    const buf = buffer.test_buffer_from_arraybuffer(arrayBuffer, 1, 3);
+    assert(Buffer.isBuffer(buf));
+    assertEquals([...buf], [2, 3, 4]);
+    // shares the memory of the array buffer
+    buf[0] = 42;
+    assertEquals(new Uint8Array(arrayBuffer)[1], 42);
+
+    assertThrows(
+      () => buffer.test_buffer_from_arraybuffer(arrayBuffer, 4, 2),
+      RangeError,
+      "The byte offset + length is out of range",
+    );
+  },
+});
@@ -0,0 +1,66 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use crate::assert_napi_ok;
+use crate::napi_get_callback_info;
+use crate::napi_new_property;
+use napi_sys::*;
+
+// Not declared by napi-sys, which can only load the functions it declares on
+// Windows, where this module isn't built.
+extern "C" {
+  fn node_api_create_buffer_from_arraybuffer(
+    env: napi_env,
+    arraybuffer: napi_value,
+    byte_offset: usize,
+    byte_length: usize,
+    result: *mut napi_value,
+  ) -> napi_status;
+}
+
+extern "C" fn test_buffer_from_arraybuffer(
+  env: napi_env,
+  info: napi_callback_info,
+) -> napi_value {
+  let (args, argc, _) = napi_get_callback_info!(env, info, 3);
+  assert_eq!(argc, 3);
+
+  let mut byte_offset = 0;
+  assert_napi_ok!(napi_get_value_uint32(env, args[1], &mut byte_offset));
+  let mut byte_length = 0;
+  assert_napi_ok!(napi_get_value_uint32(env, args[2], &mut byte_length));
+
+  let mut result = std::ptr::null_mut();
+  let status = unsafe {
+    node_api_create_buffer_from_arraybuffer(
+      env,
+      args[0],
+      byte_offset as usize,
+      byte_length as usize,
+      &mut result,
+    )
+  };
+  if status != napi_sys::Status::napi_ok {
+    // leaves the pending exception to the caller
+    return std::ptr::null_mut();
+  }
+
+  let mut is_buffer = false;
+  assert_napi_ok!(napi_is_buffer(env, result, &mut is_buffer));
+  assert!(is_buffer);
+  result
+}
+
+pub fn init(env: napi_env, exports: napi_value) {
+  let properties = &[napi_new_property!(
+    env,
+    "test_buffer_from_arraybuffer",
+    test_buffer_from_arraybuffer
+  )];
+
+  assert_napi_ok!(napi_define_properties(
+    env,
+    exports,
+    properties.len(),
+    properties.as_ptr()
+  ));
+}
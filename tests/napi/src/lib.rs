@@ -13,6 +13,8 @@ pub mod array;
 pub mod arraybuffer;
 pub mod r#async;
 pub mod bigint;
+#[cfg(not(windows))]
+pub mod buffer;
 pub mod callback;
 pub mod coerce;
 pub mod date;
@@ -166,6 +168,8 @@ unsafe extern "C" fn napi_register_module_v1(
   tsfn::init(env, exports);
   mem::init(env, exports);
   bigint::init(env, exports);
+  #[cfg(not(windows))]
+  buffer::init(env, exports);
   symbol::init(env, exports);
   make_callback::init(env, exports);
   object::init(env, exports);
@@ -6,6 +6,30 @@ use crate::napi_new_property;
 use napi_sys::ValueType::napi_string;
 use napi_sys::*;
 
+// Not declared by napi-sys, which can only load the functions it declares on
+// Windows.
+#[cfg(not(windows))]
+extern "C" {
+  fn node_api_create_property_key_latin1(
+    env: napi_env,
+    str: *const std::ffi::c_char,
+    length: usize,
+    result: *mut napi_value,
+  ) -> napi_status;
+  fn node_api_create_property_key_utf8(
+    env: napi_env,
+    str: *const std::ffi::c_char,
+    length: usize,
+    result: *mut napi_value,
+  ) -> napi_status;
+  fn node_api_create_property_key_utf16(
+    env: napi_env,
+    str: *const u16,
+    length: usize,
+    result: *mut napi_value,
+  ) -> napi_status;
+}
+
 extern "C" fn test_utf8(env: napi_env, info: napi_callback_info) -> napi_value {
   let (args, argc, _) = napi_get_callback_info!(env, info, 1);
   assert_eq!(argc, 1);
@@ -31,13 +55,59 @@ extern "C" fn test_utf16(
   args[0]
 }
 
+/// Returns an object with a property for each kind of property key.
+#[cfg(not(windows))]
+extern "C" fn test_property_keys(
+  env: napi_env,
+  _info: napi_callback_info,
+) -> napi_value {
+  let mut keys = [std::ptr::null_mut(); 4];
+  assert_napi_ok!(node_api_create_property_key_latin1(
+    env,
+    b"caf\xe9".as_ptr() as _,
+    4,
+    &mut keys[0],
+  ));
+  assert_napi_ok!(node_api_create_property_key_utf8(
+    env,
+    "🦕".as_ptr() as _,
+    "🦕".len(),
+    &mut keys[1],
+  ));
+  let utf16 = "ünïcödé".encode_utf16().collect::<Vec<_>>();
+  assert_napi_ok!(node_api_create_property_key_utf16(
+    env,
+    utf16.as_ptr(),
+    utf16.len(),
+    &mut keys[2],
+  ));
+  // NAPI_AUTO_LENGTH
+  assert_napi_ok!(node_api_create_property_key_utf8(
+    env,
+    b"auto\0".as_ptr() as _,
+    usize::MAX,
+    &mut keys[3],
+  ));
+
+  let mut object = std::ptr::null_mut();
+  assert_napi_ok!(napi_create_object(env, &mut object));
+  for (i, key) in keys.into_iter().enumerate() {
+    let mut value = std::ptr::null_mut();
+    assert_napi_ok!(napi_create_uint32(env, i as u32, &mut value));
+    assert_napi_ok!(napi_set_property(env, object, key, value));
+  }
+  object
+}
+
 pub fn init(env: napi_env, exports: napi_value) {
   let properties = &[
     // utf8
     napi_new_property!(env, "test_utf8", test_utf8),
     // utf16
     napi_new_property!(env, "test_utf16", test_utf16),
-    // latin1
+    // property keys
+    #[cfg(not(windows))]
+    napi_new_property!(env, "test_property_keys", test_property_keys),
   ];
 
   assert_napi_ok!(napi_define_properties(
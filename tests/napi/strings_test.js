@@ -13,3 +13,13 @@ Deno.test("napi string", function () {
   assertEquals(strings.test_utf16(""), "");
   assertEquals(strings.test_utf16("🦕"), "🦕");
 });
+
+Deno.test({
+  name: "napi property keys",
+  // not declared by napi-sys, which the test library uses on Windows
+  ignore: Deno.build.os === "windows",
+  fn() {
+    const object = strings.test_property_keys();
+    assertEquals(object, { "café": 0, "🦕": 1, "ünïcödé": 2, auto: 3 });
+  },
+});
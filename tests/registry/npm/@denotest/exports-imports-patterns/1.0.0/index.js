@@ -0,0 +1,4 @@
+import { helper } from "#internal/helper";
+import dep from "#dep";
+
+export default `${helper()} ${dep}`;
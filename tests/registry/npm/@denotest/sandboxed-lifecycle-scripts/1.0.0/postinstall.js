@@ -0,0 +1,32 @@
+const childProcess = require("child_process");
+const fs = require("fs");
+const path = require("path");
+
+fs.writeFileSync(path.join(__dirname, "built.txt"), "built in postinstall");
+
+try {
+  fs.writeFileSync(path.join(process.env.INIT_CWD, "outside.txt"), "");
+  console.log("wrote outside of node_modules");
+} catch {
+  console.log("could not write outside of node_modules");
+}
+
+try {
+  fs.readFileSync(path.join(process.env.INIT_CWD, "sandboxed.js"));
+  console.log("read outside of node_modules");
+} catch {
+  console.log("could not read outside of node_modules");
+}
+
+try {
+  childProcess.execFileSync("echo", ["escaped"]);
+  console.log("ran a subprocess");
+} catch {
+  console.log("could not run a subprocess");
+}
+
+console.log(
+  process.env.DENO_TEST_SECRET === undefined
+    ? "could not read the environment"
+    : "read the environment",
+);
@@ -0,0 +1,7 @@
+const linked = import.meta.dirname + "/node_modules/linked";
+console.log(Deno.readTextFileSync(linked + "/data.txt").trim());
+try {
+  Deno.statSync(linked + "/loop");
+} catch (err) {
+  console.log(err instanceof Deno.errors.NotFound);
+}
@@ -0,0 +1,4 @@
+const kv = await Deno.openKv("kv.sqlite3");
+await kv.set(["greeting"], "goodbye");
+await kv.set(["other"], 1);
+kv.close();
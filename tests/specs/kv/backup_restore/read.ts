@@ -0,0 +1,6 @@
+const kv = await Deno.openKv("kv.sqlite3");
+const entry = await kv.get(["greeting"]);
+console.log(entry.value);
+console.log(entry.versionstamp === Deno.readTextFileSync("versionstamp.txt"));
+console.log((await kv.get(["other"])).value);
+kv.close();
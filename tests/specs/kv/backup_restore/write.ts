@@ -0,0 +1,4 @@
+const kv = await Deno.openKv("kv.sqlite3");
+const { versionstamp } = await kv.set(["greeting"], "hello");
+kv.close();
+Deno.writeTextFileSync("versionstamp.txt", versionstamp);
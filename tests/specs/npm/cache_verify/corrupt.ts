@@ -0,0 +1,3 @@
+const packageDir = "deno_dir/npm/localhost_4260/@denotest/esm-basic/1.0.0";
+Deno.writeTextFileSync(`${packageDir}/other.mjs`, "export {};\n");
+Deno.removeSync(`${packageDir}/main.d.mts`);
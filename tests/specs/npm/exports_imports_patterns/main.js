@@ -0,0 +1,7 @@
+import main from "npm:@denotest/exports-imports-patterns";
+import featureA from "npm:@denotest/exports-imports-patterns/features/a.js";
+import twice from "npm:@denotest/exports-imports-patterns/twice/twice";
+
+console.log(main);
+console.log(featureA);
+console.log(twice);
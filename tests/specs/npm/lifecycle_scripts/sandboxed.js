@@ -0,0 +1,5 @@
+import { existsSync } from "node:fs";
+import built from "npm:@denotest/sandboxed-lifecycle-scripts";
+
+console.log(built);
+console.log(existsSync("outside.txt"));
@@ -0,0 +1,11 @@
+import { getKind } from "npm:@denotest/dual-cjs-esm-dep";
+
+console.log(getKind());
+
+// only packages in the node_modules directory of a package resolve from it
+try {
+  Deno.statSync("node_modules/.deno/node_modules/@denotest/dual-cjs-esm");
+  console.log("hoisted");
+} catch {
+  console.log("not hoisted");
+}
@@ -0,0 +1,3 @@
+export function sayHello() {
+  console.log("B: Hi!");
+}
@@ -0,0 +1,7 @@
+import * as a from "@denotest/a";
+import * as b from "@denotest/b";
+import * as c from "@denotest/c";
+
+a.sayHello();
+b.sayHello();
+c.sayHello();
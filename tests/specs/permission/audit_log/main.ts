@@ -0,0 +1,6 @@
+Deno.readTextFileSync("data.txt");
+try {
+  Deno.env.get("SECRET");
+} catch (err) {
+  console.log(err.name);
+}
@@ -0,0 +1,15 @@
+const entries = Deno.readTextFileSync("audit.jsonl")
+  .trim()
+  .split("\n")
+  .map((line) => JSON.parse(line))
+  .filter((entry) => entry.api !== "import()");
+for (const entry of entries) {
+  const descriptor = entry.descriptor?.split(/[\\/]/).pop() ?? null;
+  console.log(
+    entry.permission,
+    descriptor,
+    entry.decision,
+    entry.api,
+    typeof entry.timestamp,
+  );
+}
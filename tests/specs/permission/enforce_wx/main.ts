@@ -0,0 +1,6 @@
+try {
+  new Deno.UnsafeCallback({ parameters: [], result: "void" }, () => {});
+} catch (err) {
+  console.log(`${err.name}: ${err.message}`);
+}
+console.log(typeof WebAssembly);
@@ -0,0 +1,8 @@
+const entries = Deno.readTextFileSync("audit.jsonl")
+  .trim()
+  .split("\n")
+  .map((line) => JSON.parse(line))
+  .filter((entry) => entry.permission === "wx");
+for (const entry of entries) {
+  console.log(entry.permission, entry.api, entry.decision);
+}
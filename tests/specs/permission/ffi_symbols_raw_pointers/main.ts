@@ -0,0 +1,13 @@
+const ptr = Deno.UnsafePointer.of(new Uint8Array(8));
+
+try {
+  new Deno.UnsafePointerView(ptr!).getUint8();
+} catch (err) {
+  console.log(`${err.name}: ${err.message}`);
+}
+
+try {
+  new Deno.UnsafeFnPointer(ptr!, { parameters: [], result: "void" }).call();
+} catch (err) {
+  console.log(`${err.name}: ${err.message}`);
+}
@@ -0,0 +1,8 @@
+console.log(Deno.readTextFileSync("data/public.txt").trim());
+console.log(Deno.env.get("POLICY_TEST"));
+
+try {
+  Deno.readTextFileSync("data/secret.txt");
+} catch (err) {
+  console.log(err.message);
+}
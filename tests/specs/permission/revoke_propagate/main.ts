@@ -0,0 +1,18 @@
+const worker = new Worker(import.meta.resolve("./worker.js"), {
+  type: "module",
+  deno: { permissions: { read: true, env: true } },
+});
+
+function queryWorker() {
+  const { promise, resolve } = Promise.withResolvers();
+  worker.onmessage = (e) => resolve(e.data);
+  worker.postMessage(null);
+  return promise;
+}
+
+console.log(await queryWorker());
+await Deno.permissions.revoke({ name: "env" });
+console.log(await queryWorker());
+await Deno.permissions.revoke({ name: "read" }, { propagate: true });
+console.log(await queryWorker());
+worker.terminate();
@@ -0,0 +1,5 @@
+self.onmessage = async () => {
+  const read = await Deno.permissions.query({ name: "read" });
+  const env = await Deno.permissions.query({ name: "env" });
+  self.postMessage({ read: read.state, env: env.state });
+};
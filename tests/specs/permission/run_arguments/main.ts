@@ -0,0 +1,21 @@
+function run(options: Deno.CommandOptions) {
+  try {
+    const { stdout } = new Deno.Command("echo", options).outputSync();
+    console.log(new TextDecoder().decode(stdout).trim());
+  } catch (err) {
+    console.log(err.message);
+  }
+}
+
+run({ args: ["hello"] });
+run({ args: ["bye"] });
+// these variables make `git status` run `touch pwned`
+run({
+  args: ["hello"],
+  env: {
+    GIT_CONFIG_COUNT: "1",
+    GIT_CONFIG_KEY_0: "core.fsmonitor",
+    GIT_CONFIG_VALUE_0: "touch pwned",
+  },
+});
+run({ args: ["hello"], cwd: ".." });
@@ -0,0 +1,8 @@
+try {
+  await new Deno.Command("cat", {
+    args: ["allowed/file.txt"],
+    sandbox: { permissions: { net: false } },
+  }).output();
+} catch (err) {
+  console.log(err.message);
+}
@@ -0,0 +1,24 @@
+async function run(
+  [program, ...args]: string[],
+  permissions: Deno.PermissionOptions,
+  env?: Record<string, string>,
+) {
+  const { code, stdout } = await new Deno.Command(program, {
+    args,
+    env,
+    stdout: "piped",
+    stderr: "null",
+    sandbox: { permissions },
+  }).output();
+  const text = new TextDecoder().decode(stdout).trim();
+  console.log(code === 0 ? text : `exit code ${code}`);
+}
+
+await run(["cat", "allowed/file.txt"], { read: ["allowed"] });
+await run(["cat", "secret.txt"], { read: ["allowed"] });
+await run(["env"], { env: ["KEEP"] }, { KEEP: "1", DROP: "2" });
+try {
+  await run(["cat", "allowed/file.txt"], { sys: false });
+} catch (err) {
+  console.log(err.message);
+}
@@ -0,0 +1,7 @@
+try {
+  const listener = Deno.listen({ transport: "unix", path: "fs.sock" });
+  console.log(listener.addr.transport);
+  listener.close();
+} catch (error) {
+  console.log(error instanceof Deno.errors.PermissionDenied);
+}
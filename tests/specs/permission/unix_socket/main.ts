@@ -0,0 +1,17 @@
+const listener = Deno.listen({ transport: "unix", path: "app.sock" });
+const conn = await Deno.connect({ transport: "unix", path: "./app.sock" });
+console.log(conn.remoteAddr.transport);
+conn.close();
+listener.close();
+
+const status = await Deno.permissions.query({
+  name: "net",
+  host: "unix:app.sock",
+});
+console.log(status.state);
+
+try {
+  Deno.listen({ transport: "unix", path: "other.sock" });
+} catch (error) {
+  console.log(error instanceof Deno.errors.PermissionDenied);
+}
@@ -0,0 +1,33 @@
+Deno.mkdirSync("logs");
+Deno.writeTextFileSync("logs/app.log", "started\n");
+Deno.writeTextFileSync("logs/app.log", "stopped\n", { append: true });
+console.log(Deno.readTextFileSync("logs/app.log"));
+
+try {
+  Deno.writeTextFileSync("logs/app.log", "overwritten\n");
+} catch (err) {
+  console.log(err.name);
+}
+
+try {
+  Deno.removeSync("logs/app.log");
+} catch (err) {
+  console.log(err.message);
+}
+
+const file = Deno.openSync("logs/app.log", { append: true });
+file.writeSync(new TextEncoder().encode("appended\n"));
+try {
+  file.truncateSync(0);
+} catch (err) {
+  console.log(err.message);
+}
+file.close();
+
+Deno.copyFileSync("logs/app.log", "logs/copy.log");
+try {
+  Deno.copyFileSync("logs/app.log", "logs/copy.log");
+} catch (err) {
+  console.log(err.message);
+}
+console.log(Deno.readTextFileSync("logs/copy.log"));
@@ -0,0 +1,19 @@
+import fs from "node:fs";
+
+Deno.mkdirSync("logs", { recursive: true });
+Deno.writeTextFileSync("logs/node.log", "started\n");
+
+const attempts = {
+  cpSync: () => fs.cpSync("logs/node.log", "logs/node_copy.log"),
+  lutimesSync: () => fs.lutimesSync("logs/node.log", 0, 0),
+  lchownSync: () => fs.lchownSync("logs/node.log", Deno.uid()!, Deno.gid()!),
+  openKv: () => Deno.openKv("logs/kv.db"),
+};
+for (const [name, attempt] of Object.entries(attempts)) {
+  try {
+    await attempt();
+    console.log(`${name}: allowed`);
+  } catch (err) {
+    console.log(`${name}: ${err.message}`);
+  }
+}
@@ -0,0 +1,16 @@
+console.log(await Deno.readTextFile("allowed/file.txt"));
+
+for (
+  const read of [
+    () => Deno.readTextFile("allowed/link"),
+    () => Deno.readTextFileSync("allowed/link"),
+    () => Deno.open("allowed/link").then((file) => file.close()),
+  ]
+) {
+  try {
+    await read();
+    console.log("read secret.txt");
+  } catch (err) {
+    console.log(err.name);
+  }
+}
@@ -0,0 +1,20 @@
+await Deno.writeTextFile("a.txt", "Hello");
+await Deno.writeTextFile("a.txt", " world", { append: true });
+console.log(await Deno.readTextFile("a.txt"));
+console.log((await Deno.stat("a.txt")).size);
+
+using file = await Deno.open("a.txt", { read: true });
+const buf = new Uint8Array(5);
+await file.read(buf);
+console.log(new TextDecoder().decode(buf));
+
+try {
+  await Deno.open("a.txt", { write: true, createNew: true });
+} catch (err) {
+  console.log(err.name);
+}
+try {
+  await Deno.stat("missing.txt");
+} catch (err) {
+  console.log(err.name);
+}
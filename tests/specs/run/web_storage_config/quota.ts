@@ -0,0 +1,8 @@
+for (const storage of [localStorage, sessionStorage]) {
+  try {
+    storage.setItem("key", "v".repeat(2048));
+  } catch (err) {
+    console.log((err as DOMException).name);
+  }
+  console.log(storage.length);
+}
@@ -0,0 +1 @@
+console.log(localStorage.getItem("hello"));
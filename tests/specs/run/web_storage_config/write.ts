@@ -0,0 +1,2 @@
+localStorage.setItem("hello", "world");
+console.log(localStorage.getItem("hello"));
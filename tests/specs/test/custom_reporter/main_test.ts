@@ -0,0 +1,13 @@
+Deno.test("passes", (t) => {
+  t.attach("log.txt", "hello");
+});
+
+Deno.test("fails", () => {
+  throw new Error("boom");
+});
+
+Deno.test("has steps", async (t) => {
+  await t.step("step", () => {});
+});
+
+Deno.test.ignore("ignored", () => {});
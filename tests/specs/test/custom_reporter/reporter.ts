@@ -0,0 +1,31 @@
+export default async function reporter(
+  events: AsyncIterable<Deno.TestReporterEvent>,
+) {
+  for await (const event of events) {
+    switch (event.type) {
+      case "plan":
+        console.log(`plan ${event.origin} ${event.total}`);
+        break;
+      case "testResult": {
+        const { file, line, column } = event.test.location;
+        const location = `${file}:${line}:${column}`;
+        console.log(`${event.status} ${event.test.name} ${location}`);
+        if (event.error) {
+          console.log(`  ${event.error.message}`);
+        }
+        break;
+      }
+      case "attachment": {
+        const text = new TextDecoder().decode(event.data);
+        console.log(`  attachment ${event.name} ${event.contentType} ${text}`);
+        break;
+      }
+      case "stepResult":
+        console.log(`  ${event.status} ${event.step.name}`);
+        break;
+      case "end":
+        console.log("end");
+        break;
+    }
+  }
+}
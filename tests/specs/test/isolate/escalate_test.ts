@@ -0,0 +1,2 @@
+// deno-test-permissions --allow-net
+Deno.test("not run", () => {});
@@ -0,0 +1,4 @@
+// deno-test-permissions --allow-read
+Deno.test("reads with the permissions of its header", () => {
+  Deno.readTextFileSync("./trusted_test.ts");
+});
@@ -0,0 +1,11 @@
+Deno.test("has no permissions", () => {
+  try {
+    Deno.readTextFileSync("./trusted_test.ts");
+  } catch (error) {
+    if (error instanceof Deno.errors.PermissionDenied) {
+      return;
+    }
+    throw error;
+  }
+  throw new Error("The file was read");
+});
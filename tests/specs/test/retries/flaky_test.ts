@@ -0,0 +1,8 @@
+let attempts = 0;
+
+Deno.test("flaky", () => {
+  attempts++;
+  if (attempts < 3) {
+    throw new Error(`attempt ${attempts} failed`);
+  }
+});
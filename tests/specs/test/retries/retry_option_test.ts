@@ -0,0 +1,13 @@
+let attempts = 0;
+
+// the option takes precedence over --retries
+Deno.test({
+  name: "fails twice",
+  retry: 1,
+  fn() {
+    attempts++;
+    if (attempts < 3) {
+      throw new Error(`attempt ${attempts} failed`);
+    }
+  },
+});
@@ -0,0 +1,11 @@
+Deno.test("object", (t) => {
+  t.assertSnapshot({ b: [1, 2], a: "text" });
+  t.assertSnapshot("multi\nline `quoted`");
+});
+
+Deno.test("inline", async (t) => {
+  t.assertInlineSnapshot(new Map([["a", 1]]));
+  await t.step("step", (t) => {
+    t.assertSnapshot(1);
+  });
+});
@@ -0,0 +1,6 @@
+import featureA from "npm:@denotest/exports-imports-patterns/features/a.js";
+import twice from "npm:@denotest/exports-imports-patterns/twice/twice";
+
+console.log(main);
+console.log(featureA);
+console.log(twice);
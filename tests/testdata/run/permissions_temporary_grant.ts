@@ -0,0 +1,5 @@
+await Deno.permissions.request({ name: "read", path: "foo" }); // t
+const descriptor = { name: "read", path: "foo" } as const;
+console.log((await Deno.permissions.query(descriptor)).state);
+await Deno.permissions.revoke(descriptor);
+console.log((await Deno.permissions.query(descriptor)).state);
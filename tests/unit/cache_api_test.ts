@@ -205,3 +205,19 @@ Deno.test(async function cachePutResource() {
   const res = await cache.match(request);
   assertEquals(await res?.text(), "Contents".repeat(1024));
 });
+
+Deno.test(async function cacheStorageUsage() {
+  const cacheName = "cache-usage";
+  const cache = await caches.open(cacheName);
+  const before = await caches.usage();
+
+  const request = new Request("https://example.com/usage");
+  await cache.put(request, new Response("a".repeat(1024)));
+  const after = await caches.usage();
+  assertEquals(after.entries, before.entries + 1);
+  assert(after.usage >= before.usage + 1024);
+
+  assert(await cache.delete(request));
+  assertEquals((await caches.usage()).entries, before.entries);
+  assert(await caches.delete(cacheName));
+});
@@ -975,3 +975,35 @@ Deno.test(
     );
   },
 );
+
+Deno.test(
+  {
+    ignore: Deno.build.os === "windows",
+    permissions: { run: true, read: true },
+  },
+  function commandSandboxLimits() {
+    const { stdout } = new Deno.Command("sh", {
+      args: ["-c", "ulimit -n"],
+      sandbox: { openFiles: 64 },
+    }).outputSync();
+    assertEquals(new TextDecoder().decode(stdout).trim(), "64");
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os === "windows",
+    permissions: { run: true, read: true },
+  },
+  function commandSandboxPermissionsEscalation() {
+    assertThrows(
+      () =>
+        new Deno.Command("sh", {
+          args: ["-c", "true"],
+          sandbox: { permissions: { write: true } },
+        }).outputSync(),
+      Deno.errors.PermissionDenied,
+      "Can't escalate parent thread permissions",
+    );
+  },
+);
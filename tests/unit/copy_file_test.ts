@@ -247,3 +247,27 @@ Deno.test(
     }, TypeError);
   },
 );
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function copyFileLargeOverwrite() {
+    // Large copies may share the data of the source file, make sure the
+    // destination is independent of it afterwards.
+    const tempDir = Deno.makeTempDirSync();
+    const fromFilename = tempDir + "/from.bin";
+    const toFilename = tempDir + "/to.bin";
+    const data = new Uint8Array(1024 * 1024);
+    for (let i = 0; i < data.length; i += 65536) {
+      crypto.getRandomValues(data.subarray(i, i + 65536));
+    }
+    Deno.writeFileSync(fromFilename, data);
+    writeFileString(toFilename, "Goodbye!");
+    await Deno.copyFile(fromFilename, toFilename);
+    assertSameContent(fromFilename, toFilename);
+
+    Deno.writeFileSync(fromFilename, new Uint8Array([1, 2, 3]));
+    assertEquals(Deno.readFileSync(toFilename), data);
+
+    Deno.removeSync(tempDir, { recursive: true });
+  },
+);
@@ -1,8 +1,9 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
-import { assertEquals, assertThrows } from "./test_util.ts";
+import { assert, assertEquals, assertThrows } from "./test_util.ts";
 
 // @ts-ignore This is not publicly typed namespace, but it's there for sure.
 const {
+  expandBackoffSchedule,
   formatToCronSchedule,
   parseScheduleToString,
   // @ts-expect-error TypeScript (as of 3.7) does not support indexing namespaces by symbol
@@ -317,6 +318,128 @@ Deno.test(async function retriesWithBackoffScheduleOldApi() {
   assertEquals(count, 3);
 });
 
+Deno.test(function invalidOverlapPolicy() {
+  assertThrows(
+    () =>
+      Deno.cron(
+        "abc",
+        "*/20 * * * *",
+        // @ts-expect-error invalid overlap policy
+        { overlap: "sometimes" },
+        () => {},
+      ),
+    TypeError,
+    "Invalid overlap policy: sometimes",
+  );
+  assertThrows(
+    () =>
+      Deno.cron(
+        "abc",
+        "*/20 * * * *",
+        { overlap: "concurrent", backoffSchedule: [10] },
+        () => {},
+      ),
+    TypeError,
+    "backoffSchedule cannot be used with the concurrent overlap policy",
+  );
+});
+
+Deno.test(async function overlapConcurrent() {
+  Deno.env.set("DENO_CRON_TEST_SCHEDULE_OFFSET", "100");
+
+  let active = 0;
+  let maxActive = 0;
+  const ac = new AbortController();
+  const c = Deno.cron("overlap-concurrent", "*/20 * * * *", {
+    signal: ac.signal,
+    overlap: "concurrent",
+  }, async () => {
+    active++;
+    maxActive = Math.max(maxActive, active);
+    await sleep(350);
+    active--;
+  });
+  try {
+    await sleep(1000);
+  } finally {
+    ac.abort();
+    await c;
+    // let the runs that are still active finish
+    await sleep(400);
+  }
+
+  assert(maxActive > 1);
+});
+
+Deno.test(async function overlapQueue() {
+  Deno.env.set("DENO_CRON_TEST_SCHEDULE_OFFSET", "100");
+
+  let count = 0;
+  let active = 0;
+  let maxActive = 0;
+  const ac = new AbortController();
+  const c = Deno.cron("overlap-queue", "*/20 * * * *", {
+    signal: ac.signal,
+    overlap: "queue",
+  }, async () => {
+    count++;
+    active++;
+    maxActive = Math.max(maxActive, active);
+    await sleep(250);
+    active--;
+  });
+  try {
+    await sleep(1000);
+  } finally {
+    ac.abort();
+    await c;
+  }
+
+  assert(count >= 2);
+  assertEquals(maxActive, 1);
+});
+
+Deno.test(async function retriesWithExponentialBackoff() {
+  Deno.env.set("DENO_CRON_TEST_SCHEDULE_OFFSET", "5000");
+
+  let count = 0;
+  const ac = new AbortController();
+  const c = Deno.cron("exponential-backoff", "*/20 * * * *", {
+    signal: ac.signal,
+    backoffSchedule: { retries: 2, initialDelay: 10 },
+  }, () => {
+    count += 1;
+    throw new TypeError("cron error");
+  });
+  try {
+    await sleep(6000);
+  } finally {
+    ac.abort();
+    await c;
+  }
+
+  // The cron should have executed 3 times (1st attempt and 2 retries).
+  assertEquals(count, 3);
+});
+
+Deno.test("expandBackoffSchedule", () => {
+  assertEquals(expandBackoffSchedule({ retries: 3, initialDelay: 100 }), [
+    100,
+    200,
+    400,
+  ]);
+  assertEquals(
+    expandBackoffSchedule({
+      retries: 4,
+      initialDelay: 1000,
+      factor: 3,
+      maxDelay: 5000,
+    }),
+    [1000, 3000, 5000, 5000],
+  );
+  assertEquals(expandBackoffSchedule({ retries: 0, initialDelay: 100 }), []);
+});
+
 Deno.test("formatToCronSchedule - undefined value", () => {
   const result = formatToCronSchedule();
   assertEquals(result, "*");
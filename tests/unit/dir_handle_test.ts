@@ -0,0 +1,149 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertRejects,
+  assertThrows,
+} from "./test_util.ts";
+
+const isWindows = Deno.build.os === "windows";
+
+Deno.test(
+  { ignore: isWindows, permissions: { read: true, write: true } },
+  function dirHandleSyncSuccess() {
+    const testDir = Deno.makeTempDirSync();
+    Deno.mkdirSync(testDir + "/sub");
+    using dir = Deno.openDirSync(testDir);
+
+    using file = dir.openAtSync("sub/file.txt", {
+      write: true,
+      createNew: true,
+    });
+    file.writeSync(new TextEncoder().encode("hello"));
+    assertEquals(Deno.readTextFileSync(testDir + "/sub/file.txt"), "hello");
+
+    const info = dir.statAtSync("sub/file.txt");
+    assert(info.isFile);
+    assertEquals(info.size, 5);
+    assert(dir.statAtSync("sub").isDirectory);
+
+    using sub = dir.openDirAtSync("sub");
+    sub.renameAtSync("file.txt", "moved.txt", dir);
+    assertEquals(Deno.readTextFileSync(testDir + "/moved.txt"), "hello");
+    assertThrows(() => sub.statAtSync("file.txt"), Deno.errors.NotFound);
+
+    dir.unlinkAtSync("moved.txt");
+    dir.unlinkAtSync("sub", { directory: true });
+    assertEquals([...Deno.readDirSync(testDir)], []);
+  },
+);
+
+Deno.test(
+  { ignore: isWindows, permissions: { read: true, write: true } },
+  async function dirHandleSuccess() {
+    const testDir = await Deno.makeTempDir();
+    await Deno.writeTextFile(testDir + "/file.txt", "hello");
+    await Deno.symlink("file.txt", testDir + "/link");
+    using dir = await Deno.openDir(testDir);
+
+    using file = await dir.openAt("link");
+    const buf = new Uint8Array(5);
+    await file.read(buf);
+    assertEquals(new TextDecoder().decode(buf), "hello");
+
+    assert((await dir.statAt("link")).isFile);
+    assert((await dir.lstatAt("link")).isSymlink);
+    assert(dir.lstatAtSync("link").isSymlink);
+
+    await dir.renameAt("file.txt", "renamed.txt");
+    assertEquals(
+      await Deno.readTextFile(testDir + "/renamed.txt"),
+      "hello",
+    );
+    await dir.unlinkAt("link");
+    await dir.unlinkAt("renamed.txt");
+    await assertRejects(() => dir.statAt("link"), Deno.errors.NotFound);
+  },
+);
+
+Deno.test(
+  { ignore: isWindows, permissions: { read: true, write: true } },
+  async function dirHandleFollowsMovedDirectory() {
+    const testDir = await Deno.makeTempDir();
+    await Deno.mkdir(testDir + "/a");
+    await Deno.writeTextFile(testDir + "/a/file.txt", "hello");
+    using dir = await Deno.openDir(testDir + "/a");
+
+    await Deno.rename(testDir + "/a", testDir + "/b");
+    await Deno.mkdir(testDir + "/a");
+    assert((await dir.statAt("file.txt")).isFile);
+    await dir.unlinkAt("file.txt");
+    await assertRejects(
+      () => Deno.stat(testDir + "/b/file.txt"),
+      Deno.errors.NotFound,
+    );
+  },
+);
+
+Deno.test(
+  { ignore: isWindows, permissions: { read: true, write: true } },
+  function dirHandleErrors() {
+    const testDir = Deno.makeTempDirSync();
+    Deno.writeTextFileSync(testDir + "/file.txt", "hello");
+    assertThrows(
+      () => Deno.openDirSync(testDir + "/file.txt"),
+      Error,
+      "Not a directory",
+    );
+    using dir = Deno.openDirSync(testDir);
+    assertThrows(
+      () => dir.unlinkAtSync("missing.txt"),
+      Deno.errors.NotFound,
+    );
+    assertThrows(
+      () => dir.openAtSync("file.txt", { write: true, createNew: true }),
+      Deno.errors.AlreadyExists,
+    );
+    dir.close();
+    assertThrows(() => dir.statAtSync("file.txt"), Deno.errors.BadResource);
+  },
+);
+
+Deno.test(
+  { ignore: !isWindows, permissions: { read: true } },
+  async function dirHandleNotSupported() {
+    await assertRejects(() => Deno.openDir("."), Deno.errors.NotSupported);
+  },
+);
+
+Deno.test({ permissions: { read: false } }, function openDirPerm() {
+  assertThrows(() => Deno.openDirSync("."), Deno.errors.PermissionDenied);
+});
+
+Deno.test(
+  { ignore: isWindows, permissions: { read: ["."], write: false } },
+  function dirHandlePerm() {
+    using dir = Deno.openDirSync(".");
+    assertThrows(
+      () => dir.unlinkAtSync("README.md"),
+      Deno.errors.PermissionDenied,
+    );
+    assertThrows(
+      () => dir.openAtSync("README.md", { write: true }),
+      Deno.errors.PermissionDenied,
+    );
+    assertThrows(
+      () => dir.statAtSync("../README.md"),
+      Deno.errors.PermissionDenied,
+    );
+  },
+);
+
+Deno.test(function dirHandleConstructor() {
+  assertThrows(
+    // @ts-expect-error constructor is not part of the public API
+    () => new Deno.DirHandle(),
+    TypeError,
+    "cannot be constructed",
+  );
+});
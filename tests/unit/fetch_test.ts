@@ -1171,6 +1171,99 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { net: true } },
+  async function fetchCustomClientRetry() {
+    let requests = 0;
+    const server = Deno.serve({ port: listenPort }, () => {
+      requests++;
+      return requests === 1
+        ? new Response(null, { status: 503, headers: { "retry-after": "0" } })
+        : new Response("ok");
+    });
+    const client = Deno.createHttpClient({
+      retry: { maxAttempts: 2, initialBackoff: 10, maxBackoff: 100 },
+    });
+    const response = await fetch(`http://127.0.0.1:${listenPort}/`, {
+      client,
+    });
+    assertEquals(response.status, 200);
+    assertEquals(await response.text(), "ok");
+    assertEquals(requests, 2);
+    client.close();
+    await server.shutdown();
+  },
+);
+
+Deno.test(function createHttpClientInvalidRetry() {
+  assertThrows(
+    () => Deno.createHttpClient({ retry: { maxAttempts: 0 } }),
+    TypeError,
+    "Invalid 'retry.maxAttempts'",
+  );
+});
+
+Deno.test(
+  { permissions: { net: true } },
+  async function fetchUploadProgress() {
+    const server = Deno.serve({ port: listenPort }, async (req) => {
+      const body = await req.arrayBuffer();
+      return new Response(String(body.byteLength));
+    });
+    const progress: { loaded: number; total?: number }[] = [];
+    const onUploadProgress = (p: { loaded: number; total?: number }) => {
+      progress.push(p);
+    };
+
+    const body = new Uint8Array(256 * 1024);
+    let response = await fetch(`http://127.0.0.1:${listenPort}/`, {
+      method: "POST",
+      body,
+      duplex: "half",
+      onUploadProgress,
+    });
+    assertEquals(await response.text(), String(body.byteLength));
+    assertEquals(progress.at(-1), {
+      loaded: body.byteLength,
+      total: body.byteLength,
+    });
+
+    progress.length = 0;
+    const stream = ReadableStream.from([
+      new Uint8Array(1024),
+      new Uint8Array(2048),
+    ]);
+    response = await fetch(`http://127.0.0.1:${listenPort}/`, {
+      method: "POST",
+      body: stream,
+      duplex: "half",
+      onUploadProgress,
+    });
+    assertEquals(await response.text(), "3072");
+    assertEquals(progress.at(-1), { loaded: 3072, total: undefined });
+
+    await server.shutdown();
+  },
+);
+
+Deno.test(function requestInvalidUploadOptions() {
+  assertThrows(
+    () =>
+      // @ts-expect-error testing invalid input
+      new Request("http://localhost", { duplex: "quarter" }),
+    TypeError,
+  );
+  assertThrows(
+    () =>
+      new Request("http://localhost", {
+        // @ts-expect-error testing invalid input
+        onUploadProgress: 1,
+      }),
+    TypeError,
+    "`onUploadProgress` must be a function",
+  );
+});
+
 Deno.test(
   { permissions: { net: true } },
   async function fetchCustomClientUserAgent(): Promise<
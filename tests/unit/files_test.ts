@@ -715,6 +715,74 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { read: true, write: true } },
+  function fileAllocateSyncSuccess() {
+    const filename = Deno.makeTempDirSync() + "/test_fileAllocateSync.txt";
+    using file = Deno.openSync(filename, {
+      create: true,
+      read: true,
+      write: true,
+    });
+
+    file.allocateSync(20);
+    assertEquals(Deno.readFileSync(filename), new Uint8Array(20));
+    // Allocating less than the size of the file does not shrink it.
+    file.allocateSync(5);
+    assertEquals(Deno.readFileSync(filename).byteLength, 20);
+
+    Deno.removeSync(filename);
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function fileAllocateSuccess() {
+    const filename = Deno.makeTempDirSync() + "/test_fileAllocate.txt";
+    using file = await Deno.open(filename, {
+      create: true,
+      read: true,
+      write: true,
+    });
+
+    await file.write(new Uint8Array([1, 2, 3]));
+    await file.allocate(1024 * 1024);
+    const data = await Deno.readFile(filename);
+    assertEquals(data.byteLength, 1024 * 1024);
+    assertEquals(data.subarray(0, 4), new Uint8Array([1, 2, 3, 0]));
+
+    await Deno.remove(filename);
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function filePunchHoleSuccess() {
+    const filename = Deno.makeTempDirSync() + "/test_filePunchHole.txt";
+    using file = await Deno.open(filename, {
+      create: true,
+      read: true,
+      write: true,
+    });
+
+    const blockSize = 64 * 1024;
+    await file.write(new Uint8Array(blockSize * 3).fill(1));
+    await file.punchHole(blockSize, blockSize);
+    const data = await Deno.readFile(filename);
+    assertEquals(data.byteLength, blockSize * 3);
+    const ones = new Uint8Array(blockSize).fill(1);
+    const zeros = new Uint8Array(blockSize);
+    assertEquals(data.subarray(0, blockSize), ones);
+    assertEquals(data.subarray(blockSize, blockSize * 2), zeros);
+    assertEquals(data.subarray(blockSize * 2), ones);
+
+    file.punchHoleSync(0, blockSize);
+    assertEquals(Deno.readFileSync(filename).subarray(0, blockSize), zeros);
+
+    await Deno.remove(filename);
+  },
+);
+
 Deno.test({ permissions: { read: true } }, function fileStatSyncSuccess() {
   using file = Deno.openSync("README.md");
   const fileInfo = file.statSync();
@@ -137,3 +137,70 @@ Deno.test(
     assert(done);
   },
 );
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function watchFsExclude() {
+    const testDir = await makeTempDir();
+    using iter = Deno.watchFs(testDir, { exclude: ["*.tmp"] });
+
+    Deno.writeFileSync(testDir + "/file1.tmp", new Uint8Array([0, 1, 2]));
+    Deno.writeFileSync(testDir + "/file2.txt", new Uint8Array([0, 1, 2]));
+
+    const { value } = await iter[Symbol.asyncIterator]().next();
+    assertEquals(value!.paths, [testDir + "/file2.txt"]);
+  },
+);
+
+Deno.test({ permissions: { read: true } }, function watchFsInvalidExclude() {
+  assertThrows(
+    () => {
+      Deno.watchFs(".", { exclude: ["[a"] });
+    },
+    TypeError,
+    "Invalid exclude pattern '[a'",
+  );
+});
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function watchFsDebounce() {
+    const testDir = await makeTempDir();
+    using iter = Deno.watchFs(testDir, { debounce: 200 });
+
+    const file = testDir + "/file.txt";
+    for (let i = 0; i < 5; i++) {
+      Deno.writeFileSync(file, new Uint8Array([i]));
+    }
+
+    // Every event of the batch is yielded at once, collect them all.
+    const events = [(await iter[Symbol.asyncIterator]().next()).value];
+    setTimeout(() => iter.close(), 500);
+    for await (const event of iter) {
+      events.push(event);
+    }
+    const keys = events.map((event) => JSON.stringify(event));
+    assertEquals(new Set(keys).size, keys.length);
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os !== "linux",
+    permissions: { read: true, write: true },
+  },
+  async function watchFsPairRenames() {
+    const testDir = await makeTempDir();
+    const oldPath = testDir + "/old.txt";
+    const newPath = testDir + "/new.txt";
+    Deno.writeFileSync(oldPath, new Uint8Array([0, 1, 2]));
+    await delay(100);
+    using iter = Deno.watchFs(testDir, { pairRenames: true });
+
+    Deno.renameSync(oldPath, newPath);
+
+    const { value } = await iter[Symbol.asyncIterator]().next();
+    assertEquals(value!.kind, "rename");
+    assertEquals(value!.paths, [oldPath, newPath]);
+  },
+);
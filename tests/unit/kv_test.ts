@@ -47,6 +47,123 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "openKv sqlite options",
+  async fn() {
+    const dir = await Deno.makeTempDir();
+    const path = `${dir}/kv.sqlite3`;
+    const db = await Deno.openKv(path, {
+      sqlite: {
+        journalMode: "delete",
+        mmapSize: 1024 * 1024,
+        busyTimeout: 1000,
+      },
+    });
+    await db.set(["a"], 1);
+    assertEquals((await db.get(["a"])).value, 1);
+    // no write-ahead log is used with the "delete" journal mode
+    assertThrows(() => Deno.statSync(`${path}-wal`), Deno.errors.NotFound);
+    db.close();
+    await Deno.remove(dir, { recursive: true });
+  },
+});
+
+Deno.test({
+  name: "openKv sqlite expiry sweeps",
+  async fn() {
+    const dir = await Deno.makeTempDir();
+    const path = `${dir}/kv.sqlite3`;
+    const db = await Deno.openKv(path, {
+      sqlite: { expirySweepInterval: 50 },
+    });
+    await db.set(["expiring"], "a", { expireIn: 10 });
+    await db.set(["kept"], "b");
+    await sleep(200);
+    assertEquals((await db.get(["expiring"])).value, null);
+    assertEquals((await db.get(["kept"])).value, "b");
+    db.close();
+
+    const disabled = await Deno.openKv(path, {
+      sqlite: { expirySweepInterval: 0 },
+    });
+    assertEquals((await disabled.get(["kept"])).value, "b");
+    disabled.close();
+    await Deno.remove(dir, { recursive: true });
+  },
+});
+
+Deno.test({
+  name: "openKv invalid sqlite options",
+  permissions: {},
+  async fn() {
+    await assertRejects(
+      async () =>
+        await Deno.openKv(":memory:", {
+          // @ts-expect-error invalid journal mode
+          sqlite: { journalMode: "wall" },
+        }),
+      TypeError,
+      "Invalid journal mode: wall",
+    );
+    await assertRejects(
+      async () =>
+        await Deno.openKv(":memory:", { sqlite: { mmapSize: -1 } }),
+      TypeError,
+      "mmapSize cannot be negative",
+    );
+  },
+});
+
+Deno.test({
+  name: "openKv limits options",
+  permissions: {},
+  async fn() {
+    using db = await Deno.openKv(":memory:", {
+      limits: { maxMutations: 2000, maxValueSize: 128 * 1024 },
+    });
+    await db.set(["large"], new Uint8Array(100_000));
+    await assertRejects(
+      async () => await db.set(["larger"], new Uint8Array(200_000)),
+      TypeError,
+      "value too large (max 131072 bytes)",
+    );
+
+    const atomic = db.atomic();
+    for (let i = 0; i < 1500; i++) {
+      atomic.set(["key", i], i);
+    }
+    assert((await atomic.commit()).ok);
+    const tooLarge = db.atomic();
+    for (let i = 0; i < 2001; i++) {
+      tooLarge.set(["key", i], i);
+    }
+    await assertRejects(
+      async () => await tooLarge.commit(),
+      TypeError,
+      "too many mutations (max 2000)",
+    );
+  },
+});
+
+Deno.test({
+  name: "openKv invalid limits options",
+  permissions: {},
+  async fn() {
+    await assertRejects(
+      async () =>
+        await Deno.openKv(":memory:", { limits: { maxMutations: 20_000 } }),
+      TypeError,
+      "maxMutations must be at most 10000 (got 20000)",
+    );
+    await assertRejects(
+      async () =>
+        await Deno.openKv(":memory:", { limits: { maxValueSize: 0 } }),
+      TypeError,
+      "maxValueSize must be greater than 0",
+    );
+  },
+});
+
 function dbTest(name: string, fn: (db: Deno.Kv) => Promise<void> | void) {
   Deno.test({
     name,
@@ -1669,6 +1786,99 @@ queueTest("queue retries with backoffSchedule", async (db) => {
   assertEquals(2, count);
 });
 
+queueTest("queue retries with maxAttempts", async (db) => {
+  let count = 0;
+  const listener = db.listenQueue((_msg) => {
+    count += 1;
+    throw new TypeError("dequeue error");
+  });
+  try {
+    await db.enqueue("test", {
+      backoffSchedule: [1, 1, 1, 1],
+      maxAttempts: 2,
+      keysIfUndelivered: [["dead_letter"]],
+    });
+    await sleep(2000);
+    assertEquals((await db.get(["dead_letter"])).value, "test");
+  } finally {
+    db.close();
+    await listener;
+  }
+
+  assertEquals(2, count);
+});
+
+dbTest("queue invalid maxAttempts", async (db) => {
+  for (const maxAttempts of [0, 7, 1.5, NaN]) {
+    await assertRejects(
+      () => db.enqueue("test", { maxAttempts }),
+      TypeError,
+      "invalid maxAttempts",
+    );
+  }
+  assertThrows(
+    () => db.atomic().enqueue("test", { maxAttempts: 0 }),
+    TypeError,
+    "invalid maxAttempts",
+  );
+});
+
+queueTest("queue retries after visibilityTimeout", async (db) => {
+  let count = 0;
+  const listener = db.listenQueue(async (_msg) => {
+    count += 1;
+    await sleep(500);
+  }, { visibilityTimeout: 50 });
+  try {
+    await db.enqueue("test", { backoffSchedule: [1] });
+    await sleep(2000);
+  } finally {
+    db.close();
+    await listener;
+  }
+
+  // Both attempts took longer than the visibility timeout
+  assertEquals(2, count);
+});
+
+queueTest("listenQueue with batchSize", async (db) => {
+  let handling = 0;
+  let maxHandling = 0;
+  const handled: unknown[] = [];
+  const { promise, resolve } = Promise.withResolvers<void>();
+  const listener = db.listenQueue(async (msg) => {
+    handling += 1;
+    maxHandling = Math.max(maxHandling, handling);
+    await sleep(50);
+    handling -= 1;
+    handled.push(msg);
+    if (handled.length === 4) resolve();
+  }, { batchSize: 2 });
+  try {
+    for (let i = 0; i < 4; i++) {
+      await db.enqueue(i);
+    }
+    await promise;
+    assert(maxHandling <= 2);
+  } finally {
+    db.close();
+    await listener;
+  }
+});
+
+dbTest("listenQueue invalid options", async (db) => {
+  await assertRejects(
+    () => db.listenQueue(() => {}, { batchSize: 0 }),
+    TypeError,
+    "invalid batchSize",
+  );
+  await assertRejects(
+    () => db.listenQueue(() => {}, { visibilityTimeout: -1 }),
+    TypeError,
+    "invalid visibilityTimeout",
+  );
+});
+
 queueTest("multiple listenQueues", async (db) => {
   const numListens = 10;
   let count = 0;
@@ -2268,6 +2478,267 @@ dbTest("key watch", async (db) => {
   await reader.cancel();
 });
 
+dbTest("chunked values", async (db) => {
+  const value = new Uint8Array(200_000);
+  for (let i = 0; i < value.length; i++) value[i] = i % 251;
+
+  const { versionstamp } = await db.setChunked(["blob"], value);
+  assertEquals(await db.getChunked(["blob"]), {
+    key: ["blob"],
+    value,
+    versionstamp,
+  });
+  // 200,000 bytes are stored in chunks of at most 65,536 bytes
+  const chunks = await Array.fromAsync(db.list({ prefix: ["blob"] }));
+  assertEquals(chunks.map((entry) => entry.key), [
+    ["blob", 0n],
+    ["blob", 1n],
+    ["blob", 2n],
+    ["blob", 3n],
+  ]);
+
+  // overwriting with a smaller value removes the chunks that are not needed
+  const small = new Uint8Array([1, 2, 3]);
+  await db.setChunked(["blob"], small);
+  assertEquals((await db.getChunked(["blob"])).value, small);
+  const remaining = await Array.fromAsync(db.list({ prefix: ["blob"] }));
+  assertEquals(remaining.length, 1);
+
+  await db.deleteChunked(["blob"]);
+  assertEquals(await db.getChunked(["blob"]), {
+    key: ["blob"],
+    value: null,
+    versionstamp: null,
+  });
+  assertEquals(await Array.fromAsync(db.list({ prefix: ["blob"] })), []);
+
+  await assertRejects(
+    // @ts-expect-error value must be a Uint8Array
+    async () => await db.setChunked(["blob"], "foo"),
+    TypeError,
+    "value must be a Uint8Array",
+  );
+});
+
+dbTest("blob values", async (db) => {
+  const value = new Uint8Array(200_000);
+  for (let i = 0; i < value.length; i++) value[i] = i % 251;
+
+  const { versionstamp } = await db.setBlob(["file"], new Blob([value]));
+  assertEquals(await db.getBlob(["file"]), {
+    key: ["file"],
+    value,
+    versionstamp,
+  });
+  // blobs are stored as chunked values
+  assertEquals((await db.getChunked(["file"])).value, value);
+  const stream = await db.getBlobStream(["file"]);
+  assert(stream !== null);
+  assertEquals(new Uint8Array(await new Response(stream).arrayBuffer()), value);
+
+  // replacing a blob removes the chunks of the previous one
+  await db.setBlob(["file"], new Blob(["hello ", "world"]));
+  const text = new TextDecoder().decode((await db.getBlob(["file"])).value!);
+  assertEquals(text, "hello world");
+  const entries = await Array.fromAsync(db.list({ prefix: ["file"] }));
+  assertEquals(entries.length, 1);
+
+  const { readable, writable } = new TransformStream<Uint8Array>();
+  const writer = writable.getWriter();
+  const write = (async () => {
+    await writer.write(new Uint8Array([1, 2]));
+    await writer.write(new Uint8Array([3]));
+    await writer.close();
+  })();
+  await db.setBlob(["file"], readable);
+  await write;
+  assertEquals(
+    (await db.getBlob(["file"])).value,
+    new Uint8Array([1, 2, 3]),
+  );
+
+  await db.setBlob(["file"], new Uint8Array([4, 5]));
+  assertEquals(
+    (await db.getChunked(["file"])).value,
+    new Uint8Array([4, 5]),
+  );
+
+  await db.deleteBlob(["file"]);
+  assertEquals(await db.getBlob(["file"]), {
+    key: ["file"],
+    value: null,
+    versionstamp: null,
+  });
+  assertEquals(await db.getBlobStream(["file"]), null);
+  assertEquals(await Array.fromAsync(db.list({ prefix: ["file"] })), []);
+
+  await assertRejects(
+    // @ts-expect-error value must be binary
+    async () => await db.setBlob(["file"], "foo"),
+    TypeError,
+    "value must be a Uint8Array, Blob or ReadableStream",
+  );
+});
+
+dbTest("prefix watch", async (db) => {
+  await db.set(["feed", "a"], 1);
+  await db.set(["other"], 1);
+
+  const reader = db.watch<number>({ prefix: ["feed"] }).getReader();
+
+  const initial = await reader.read();
+  assert(!initial.done);
+  assertEquals(initial.value.changes.map((entry) => entry.key), [
+    ["feed", "a"],
+  ]);
+
+  const { versionstamp } = await db.atomic()
+    .set(["feed", "b"], 2)
+    .set(["feed", "c"], 3)
+    .delete(["feed", "a"])
+    .commit() as Deno.KvCommitResult;
+  const batch = await reader.read();
+  assert(!batch.done);
+  assertEquals(batch.value.changes, [
+    { key: ["feed", "b"], value: 2, versionstamp },
+    { key: ["feed", "c"], value: 3, versionstamp },
+    { key: ["feed", "a"], value: null, versionstamp: null },
+  ]);
+  assertEquals(batch.value.resumeToken, versionstamp);
+
+  await reader.cancel();
+});
+
+dbTest("prefix watch resumes from token", async (db) => {
+  await db.set(["feed", "a"], 1);
+
+  const first = db.watch({ prefix: ["feed"] }).getReader();
+  const initial = await first.read();
+  assert(!initial.done);
+  const { resumeToken } = initial.value;
+  await first.cancel();
+
+  const { versionstamp } = await db.set(["feed", "b"], 2);
+
+  const second = db.watch<number>({ prefix: ["feed"] }, { resumeToken })
+    .getReader();
+  const resumed = await second.read();
+  assert(!resumed.done);
+  assertEquals(resumed.value.changes, [
+    { key: ["feed", "b"], value: 2, versionstamp },
+  ]);
+  assertEquals(resumed.value.resumeToken, versionstamp);
+  await second.cancel();
+
+  assertThrows(
+    () => db.watch({ prefix: ["feed"] }, { resumeToken: "nope" }),
+    TypeError,
+    "invalid resume token",
+  );
+});
+
+dbTest("changes stream emits committed mutations", async (db) => {
+  const reader = db.changes().getReader();
+  const other = await Deno.openKv(":memory:");
+  try {
+    // commits to other databases are not included
+    await other.set(["other"], 1);
+
+    const { versionstamp: vs1 } = await db.set(["a"], "x", { expireIn: 1000 });
+    const failed = await db.atomic()
+      .check({ key: ["a"], versionstamp: null })
+      .set(["b"], 1)
+      .commit();
+    assert(!failed.ok);
+    const res = await db.atomic()
+      .sum(["count"], 2n)
+      .delete(["a"])
+      .commit();
+    assert(res.ok);
+
+    const first = await reader.read();
+    assert(!first.done);
+    assertEquals(first.value.versionstamp, vs1);
+    assertEquals(first.value.changes.length, 1);
+    assertEquals(first.value.changes[0].key, ["a"]);
+    assertEquals(first.value.changes[0].type, "set");
+    assertEquals(first.value.changes[0].value, "x");
+    assert(typeof first.value.changes[0].expireAt === "number");
+
+    const second = await reader.read();
+    assert(!second.done);
+    assertEquals(second.value, {
+      versionstamp: res.versionstamp,
+      changes: [
+        {
+          key: ["count"],
+          type: "sum",
+          value: new Deno.KvU64(2n),
+          expireAt: undefined,
+        },
+        { key: ["a"], type: "delete", value: null, expireAt: undefined },
+      ],
+    });
+  } finally {
+    other.close();
+    await reader.cancel();
+  }
+});
+
+dbTest("snapshot and restore", async (db) => {
+  const res1 = await db.set(["a"], 1);
+  assert(res1.ok);
+  await db.set(["b"], "b");
+  using snapshot = await db.snapshot();
+
+  await db.set(["a"], 2);
+  await db.delete(["b"]);
+  await db.set(["c"], 3);
+  await db.restore(snapshot);
+
+  const a = await db.get(["a"]);
+  assertEquals(a.value, 1);
+  assertEquals(a.versionstamp, res1.versionstamp);
+  assertEquals((await db.get(["b"])).value, "b");
+  assertEquals((await db.get(["c"])).value, null);
+
+  // a snapshot can be restored more than once, and into other databases
+  await db.delete(["a"]);
+  await db.restore(snapshot);
+  assertEquals((await db.get(["a"])).value, 1);
+  const other = await Deno.openKv(":memory:");
+  try {
+    await other.restore(snapshot);
+    assertEquals((await other.get(["b"])).value, "b");
+  } finally {
+    other.close();
+  }
+
+  // commits after a restore continue from the restored versionstamp
+  const res2 = await db.set(["d"], 4);
+  assert(res2.ok);
+  assert(res2.versionstamp > res1.versionstamp);
+});
+
+Deno.test({
+  name: "snapshot rejects for file databases",
+  // https://github.com/denoland/deno/issues/18363
+  ignore: Deno.build.os === "darwin" && isCI,
+  async fn() {
+    const filename = await Deno.makeTempFile({ prefix: "snapshot_db" });
+    try {
+      using db = await Deno.openKv(filename);
+      await assertRejects(
+        () => db.snapshot(),
+        TypeError,
+        "Snapshots are only supported for in-memory databases",
+      );
+    } finally {
+      await Deno.remove(filename);
+    }
+  },
+});
+
 dbTest("set with key versionstamp suffix", async (db) => {
   const result1 = await Array.fromAsync(db.list({ prefix: ["a"] }));
   assertEquals(result1, []);
@@ -177,3 +177,47 @@ Deno.test(
     }
   },
 );
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function makeTempFileAnonymous() {
+    const dir = await Deno.makeTempDir();
+    using file = await Deno.makeTempFile({ dir, anonymous: true });
+    assert(file instanceof Deno.FsFile);
+    // The file has no name in the directory. On Windows the name is only
+    // removed once the file is closed.
+    if (Deno.build.os !== "windows") {
+      assertEquals(Array.from(Deno.readDirSync(dir)), []);
+    }
+
+    await file.write(new TextEncoder().encode("Hello World"));
+    const path = dir + "/file.txt";
+    await Deno.writeTextFile(path, "old contents");
+    await file.persist(path);
+    assertEquals(await Deno.readTextFile(path), "Hello World");
+    if (Deno.build.os !== "windows") {
+      assertEquals(Array.from(Deno.readDirSync(dir)).length, 1);
+    }
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  function makeTempFileSyncAnonymous() {
+    const dir = Deno.makeTempDirSync();
+    using file = Deno.makeTempFileSync({ dir, anonymous: true });
+    file.writeSync(new TextEncoder().encode("Hello World"));
+    const path = dir + "/file.txt";
+    file.persistSync(path);
+    assertEquals(Deno.readTextFileSync(path), "Hello World");
+  },
+);
+
+Deno.test(
+  { permissions: { write: false } },
+  async function makeTempFileAnonymousPerm() {
+    await assertRejects(async () => {
+      await Deno.makeTempFile({ anonymous: true });
+    }, Deno.errors.PermissionDenied);
+  },
+);
@@ -209,6 +209,34 @@ Deno.test(
   },
 );
 
+Deno.test(
+  {
+    ignore: Deno.build.os === "windows",
+    permissions: { read: true, write: true, sys: true },
+  },
+  async function netUnixPeerCredentials() {
+    const filePath = tmpUnixSocketPath();
+    const listener = Deno.listen({ transport: "unix", path: filePath });
+    const [server, client] = await Promise.all([
+      listener.accept(),
+      Deno.connect({ transport: "unix", path: filePath }),
+    ]);
+
+    for (const conn of [server, client]) {
+      const credentials = conn.peerCredentials();
+      assertEquals(credentials.uid, Deno.uid());
+      assertEquals(credentials.gid, Deno.gid());
+      if (Deno.build.os === "linux") {
+        assertEquals(credentials.pid, Deno.pid);
+      }
+    }
+
+    server.close();
+    client.close();
+    listener.close();
+  },
+);
+
 Deno.test({ permissions: { net: true } }, async function netTcpDialListen() {
   const listener = Deno.listen({ port: listenPort });
   listener.accept().then(
@@ -981,6 +1009,33 @@ Deno.test({ permissions: { net: true } }, async function whatwgStreams() {
   await server;
 });
 
+Deno.test(
+  { permissions: { net: true } },
+  async function whatwgStreamsHalfClose() {
+    const listener = Deno.listen({ hostname: "127.0.0.1", port: listenPort });
+    const server = (async () => {
+      const conn = await listener.accept();
+      // Read the whole request, then respond after the client's EOF.
+      const request = await new Response(conn.readable).text();
+      const writer = conn.writable.getWriter();
+      await writer.write(new TextEncoder().encode(request.toUpperCase()));
+      await writer.close();
+    })();
+
+    const conn = await Deno.connect({
+      hostname: "127.0.0.1",
+      port: listenPort,
+    });
+    const writer = conn.writable.getWriter();
+    await writer.write(new TextEncoder().encode("hello"));
+    await writer.close();
+    assertEquals(await new Response(conn.readable).text(), "HELLO");
+
+    await server;
+    listener.close();
+  },
+);
+
 Deno.test(
   { permissions: { read: true } },
   async function readableStreamTextEncoderPipe() {
@@ -1336,3 +1391,51 @@ Deno.test(
     // calling [Symbol.dispose] after manual close is a no-op
   },
 );
+
+Deno.test(
+  { permissions: { net: ["127.0.0.1"] } },
+  function netIcmpSocketRequiresNetPermission() {
+    assertThrows(() => {
+      Deno.icmpSocket();
+    }, Deno.errors.PermissionDenied);
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os !== "linux" || Deno.uid() !== 0,
+    permissions: { net: true },
+  },
+  async function netIcmpSocketEcho() {
+    using socket = Deno.icmpSocket({ ttl: 64 });
+    assertEquals(socket.family, "ipv4");
+    // ICMP echo request with identifier 0x1234 and sequence number 1
+    const request = new Uint8Array([8, 0, 0xe5, 0xca, 0x12, 0x34, 0, 1]);
+    assertEquals(await socket.send(request, "127.0.0.1"), request.length);
+    // The socket sees both the request and the reply on the loopback device
+    for await (const [message, addr] of socket) {
+      assertEquals(addr, { transport: "icmp", hostname: "127.0.0.1" });
+      // Skip the IPv4 header
+      const icmp = message.subarray((message[0] & 0x0f) * 4);
+      if (icmp[0] === 0) {
+        assertEquals(icmp.subarray(4, 8), request.subarray(4, 8));
+        break;
+      }
+    }
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os !== "linux",
+    permissions: { net: ["127.0.0.1"] },
+  },
+  async function netVsockRequiresFullNetPermission() {
+    assertThrows(() => {
+      Deno.listen({ transport: "vsock", cid: -1, port: 1234 });
+    }, Deno.errors.PermissionDenied);
+    await assertRejects(async () => {
+      await Deno.connect({ transport: "vsock", cid: 2, port: 1234 });
+    }, Deno.errors.PermissionDenied);
+  },
+);
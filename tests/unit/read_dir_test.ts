@@ -111,3 +111,30 @@ Deno.test({ permissions: { read: true } }, async function readDirNotFound() {
     `readdir 'bad_dir_name'`,
   );
 });
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function readDirLargeDirectory() {
+    const testDir = await Deno.makeTempDir();
+    const expected = [];
+    for (let i = 0; i < 2500; i++) {
+      const name = `file${i}.txt`;
+      await Deno.writeFile(`${testDir}/${name}`, new Uint8Array());
+      expected.push(name);
+    }
+    const names = [];
+    for await (const dirEntry of Deno.readDir(testDir)) {
+      assert(dirEntry.isFile);
+      names.push(dirEntry.name);
+    }
+    assertEquals(names.sort(), expected.sort());
+  },
+);
+
+Deno.test({ permissions: { read: true } }, async function readDirBreak() {
+  // Breaking out of the loop closes the directory, which the resource
+  // sanitizer checks.
+  for await (const _ of Deno.readDir("tests/testdata")) {
+    break;
+  }
+});
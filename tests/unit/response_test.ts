@@ -100,3 +100,31 @@ Deno.test(async function responseBodyUsed() {
   response.body;
   assert(response.bodyUsed);
 });
+
+Deno.test(
+  { permissions: { read: true } },
+  async function responseFsFileBody() {
+    const file = await Deno.open("tests/testdata/assets/hello.txt");
+    const response = new Response(file);
+    assert(response.body instanceof ReadableStream);
+    assertEquals(
+      await response.text(),
+      await Deno.readTextFile("tests/testdata/assets/hello.txt"),
+    );
+  },
+);
+
+Deno.test(async function responseTrailers() {
+  assertEquals([...await new Response("body").trailers], []);
+
+  const response = new Response("body", {
+    trailers: { "grpc-status": "0" },
+  });
+  assertEquals([...await response.trailers], [["grpc-status", "0"]]);
+  assertEquals([...await response.clone().trailers], [["grpc-status", "0"]]);
+
+  const deferred = new Response("body", {
+    trailers: Promise.resolve<HeadersInit>([["grpc-status", "1"]]),
+  });
+  assertEquals((await deferred.trailers).get("grpc-status"), "1");
+});
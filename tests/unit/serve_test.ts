@@ -3818,6 +3818,56 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerResponseTrailers() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+
+    const server = Deno.serve({
+      handler: (request) => {
+        const { promise: trailers, resolve: resolveTrailers } = Promise
+          .withResolvers<HeadersInit>();
+        const body = new ReadableStream({
+          start(controller) {
+            controller.enqueue(new TextEncoder().encode("Hello World"));
+            controller.close();
+            resolveTrailers({ "grpc-status": "0" });
+          },
+        });
+        return new Response(
+          request.url.endsWith("/stream") ? body : "Hello World",
+          {
+            headers: { "trailer": "grpc-status" },
+            trailers: request.url.endsWith("/stream")
+              ? trailers
+              : [["grpc-status", "1"]],
+          },
+        );
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    for (const [path, status] of [["/stream", "0"], ["/text", "1"]]) {
+      const resp = await fetch(`http://127.0.0.1:${servePort}${path}`, {
+        headers: { "te": "trailers" },
+      });
+      // trailers can be requested both before and after reading the body
+      const trailersBeforeBody = path === "/stream" ? resp.trailers : null;
+      assertEquals(await resp.text(), "Hello World");
+      const trailers = await (trailersBeforeBody ?? resp.trailers);
+      assertEquals(trailers.get("grpc-status"), status);
+    }
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
 // TODO(mmastrac): curl on CI stopped supporting --http2?
 Deno.test(
   {
@@ -4059,3 +4109,362 @@ Deno.test({
     'Operation `"op_net_listen_unix"` not supported on non-unix platforms.',
   );
 });
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerIdleTimeoutClosesConnection() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      timeouts: { idle: 100 },
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    // A client that never sends a request is disconnected once idle.
+    const conn = await Deno.connect({ port: servePort });
+    const buf = new Uint8Array(16);
+    assertEquals(await conn.read(buf), null);
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerHeaderReadTimeout() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      timeouts: { headerRead: 100 },
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    // Send a partial request head and stall.
+    const conn = await Deno.connect({ port: servePort });
+    await conn.write(new TextEncoder().encode("GET / HTTP/1.1\r\nHost: "));
+    const buf = new Uint8Array(16);
+    assertEquals(await conn.read(buf), null);
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerBodyReadTimeout() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const errored = Promise.withResolvers<unknown>();
+    const server = Deno.serve({
+      handler: async (req) => {
+        try {
+          await req.text();
+        } catch (e) {
+          errored.resolve(e);
+        }
+        return new Response("ok");
+      },
+      port: servePort,
+      signal: ac.signal,
+      timeouts: { bodyRead: 100 },
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    const conn = await Deno.connect({ port: servePort });
+    await conn.write(
+      new TextEncoder().encode(
+        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\n12345",
+      ),
+    );
+    assert(await errored.promise instanceof Deno.errors.TimedOut);
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(function httpServerInvalidTimeouts() {
+  assertThrows(
+    () =>
+      Deno.serve({
+        handler: () => new Response("ok"),
+        port: servePort,
+        timeouts: { idle: -1 },
+      }),
+    TypeError,
+    "Invalid 'timeouts.idle'",
+  );
+});
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerRequestPriority() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const priorities: Deno.ServeRequestPriority[] = [];
+    const server = Deno.serve({
+      handler: (_req, info) => {
+        priorities.push(info.priority);
+        return new Response("ok");
+      },
+      port: servePort,
+      signal: ac.signal,
+      http2: { maxConcurrentStreams: 10, maxFrameSize: 32768 },
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    let resp = await fetch(`http://localhost:${servePort}/`);
+    await resp.text();
+    resp = await fetch(`http://localhost:${servePort}/`, {
+      headers: { "priority": "u=1, i" },
+    });
+    await resp.text();
+
+    assertEquals(priorities, [
+      { urgency: 3, incremental: false },
+      { urgency: 1, incremental: true },
+    ]);
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(function httpServerInvalidHttp2Settings() {
+  assertThrows(
+    () =>
+      Deno.serve({
+        handler: () => new Response("ok"),
+        port: servePort,
+        http2: { maxFrameSize: 1.5 },
+      }),
+    TypeError,
+    "Invalid 'http2.maxFrameSize'",
+  );
+  assertThrows(
+    () =>
+      Deno.serve({
+        handler: () => new Response("ok"),
+        port: servePort,
+        http2: { initialStreamWindowSize: 2 ** 31 },
+      }),
+    TypeError,
+    "Invalid 'http2.initialStreamWindowSize'",
+  );
+});
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerRequestLimits() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    let handled = 0;
+    const server = Deno.serve({
+      handler: () => {
+        handled++;
+        return new Response("ok");
+      },
+      port: servePort,
+      signal: ac.signal,
+      limits: { maxHeaderBytes: 1024, maxUriLength: 64, maxHeaderCount: 20 },
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    let resp = await fetch(`http://localhost:${servePort}/`);
+    assertEquals(resp.status, 200);
+    await resp.text();
+
+    resp = await fetch(`http://localhost:${servePort}/${"a".repeat(64)}`);
+    assertEquals(resp.status, 414);
+    await resp.body?.cancel();
+
+    resp = await fetch(`http://localhost:${servePort}/`, {
+      headers: { "x-large": "a".repeat(1024) },
+    });
+    assertEquals(resp.status, 431);
+    await resp.body?.cancel();
+
+    const headers = new Headers();
+    for (let i = 0; i < 20; i++) {
+      headers.set(`x-header-${i}`, "value");
+    }
+    resp = await fetch(`http://localhost:${servePort}/`, { headers });
+    assertEquals(resp.status, 431);
+    await resp.body?.cancel();
+
+    assertEquals(handled, 1);
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(function httpServerInvalidLimits() {
+  assertThrows(
+    () =>
+      Deno.serve({
+        handler: () => new Response("ok"),
+        port: servePort,
+        limits: { maxHeaderCount: 0 },
+      }),
+    TypeError,
+    "Invalid 'limits.maxHeaderCount'",
+  );
+});
+
+Deno.test(
+  { permissions: { net: true, read: true, write: true } },
+  async function httpServerFileResponse() {
+    const tmpFile = await Deno.makeTempFile();
+    await Deno.writeTextFile(tmpFile, "hello from a file");
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const server = Deno.serve({
+      handler: async () => {
+        const file = await Deno.open(tmpFile);
+        await file.seek(6, Deno.SeekMode.Start);
+        return new Response(file);
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+    });
+    await promise;
+
+    const resp = await fetch(`http://localhost:${servePort}/`);
+    assertEquals(resp.headers.get("content-length"), "11");
+    assertEquals(await resp.text(), "from a file");
+
+    ac.abort();
+    await server.finished;
+    await Deno.remove(tmpFile);
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerOnErrorReceivesRequest() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    let errorRequest: Request | undefined;
+    const server = Deno.serve({
+      handler: () => {
+        throw new Error("boom");
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(resolve),
+      onError: (_error, request) => {
+        errorRequest = request;
+        return new Response("handled", { status: 500 });
+      },
+    });
+    await promise;
+
+    const resp = await fetch(`http://localhost:${servePort}/path`);
+    assertEquals(resp.status, 500);
+    assertEquals(await resp.text(), "handled");
+    assertEquals(errorRequest?.url, `http://localhost:${servePort}/path`);
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerConnectionInfo() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const connections: Deno.ServeConnectionInfo[] = [];
+    const closed = Promise.withResolvers<Deno.ServeConnectionCloseInfo>();
+    const server = Deno.serve({
+      handler: (_req, info) => {
+        connections.push(info.connection);
+        return new Response("ok");
+      },
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+      onConnectionClose: closed.resolve,
+    });
+    await promise;
+
+    const conn = await Deno.connect({ port: servePort });
+    const request = "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    await conn.write(new TextEncoder().encode(request + request));
+    const decoder = new TextDecoder();
+    const buf = new Uint8Array(1024);
+    let text = "";
+    while (text.split("HTTP/1.1 200 OK").length < 3) {
+      const n = await conn.read(buf);
+      assert(n !== null);
+      text += decoder.decode(buf.subarray(0, n));
+    }
+    conn.close();
+
+    assertEquals(connections.length, 2);
+    assertEquals(connections[0].id, connections[1].id);
+    assertEquals(connections[0].tls, null);
+    assertEquals(
+      (connections[0].remoteAddr as Deno.NetAddr).hostname,
+      "127.0.0.1",
+    );
+
+    const info = await closed.promise;
+    assertEquals(info.id, connections[0].id);
+    assertEquals(info.error, undefined);
+
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true } },
+  async function httpServerOnConnectionCloseProtocolError() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const closed = Promise.withResolvers<Deno.ServeConnectionCloseInfo>();
+    const server = Deno.serve({
+      handler: () => new Response("ok"),
+      port: servePort,
+      signal: ac.signal,
+      onListen: onListen(resolve),
+      onError: createOnErrorCb(ac),
+      onConnectionClose: closed.resolve,
+    });
+    await promise;
+
+    const conn = await Deno.connect({ port: servePort });
+    await conn.write(new TextEncoder().encode("NOT HTTP\r\n\r\n"));
+    const info = await closed.promise;
+    assert(info.error instanceof Deno.errors.Http);
+    conn.close();
+
+    ac.abort();
+    await server.finished;
+  },
+);
@@ -343,3 +343,35 @@ Deno.test(
     assert(!s.isSocket);
   },
 );
+
+Deno.test({ permissions: { read: true } }, async function statfsSuccess() {
+  for (const info of [Deno.statfsSync("."), await Deno.statfs(".")]) {
+    assert(info.type.length > 0);
+    assert(info.blockSize > 0);
+    assert(info.totalBytes > 0);
+    assert(info.freeBytes <= info.totalBytes);
+    assert(info.availableBytes <= info.totalBytes);
+    if (Deno.build.os === "windows") {
+      assertEquals(info.files, null);
+    } else {
+      assert(info.freeFiles! <= info.files!);
+    }
+  }
+});
+
+Deno.test({ permissions: { read: true } }, async function statfsNotFound() {
+  assertThrows(
+    () => Deno.statfsSync("bad_dir_name/file"),
+    Deno.errors.NotFound,
+    "statfs 'bad_dir_name/file'",
+  );
+  await assertRejects(
+    () => Deno.statfs("bad_dir_name/file"),
+    Deno.errors.NotFound,
+  );
+});
+
+Deno.test({ permissions: { read: false } }, async function statfsPerm() {
+  assertThrows(() => Deno.statfsSync("."), Deno.errors.PermissionDenied);
+  await assertRejects(() => Deno.statfs("."), Deno.errors.PermissionDenied);
+});
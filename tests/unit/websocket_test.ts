@@ -805,3 +805,92 @@ Deno.test("Close connection", async () => {
   await server.finished;
   conn.close();
 });
+
+Deno.test(async function websocketCustomHeadersAndClient() {
+  const ac = new AbortController();
+  const listeningDeferred = Promise.withResolvers<void>();
+  const server = Deno.serve({
+    handler: (req) => {
+      const { socket, response } = Deno.upgradeWebSocket(req, {
+        protocol: "custom",
+      });
+      socket.onopen = () => socket.send(req.headers.get("x-custom") ?? "");
+      socket.onclose = () => ac.abort();
+      return response;
+    },
+    signal: ac.signal,
+    onListen: () => listeningDeferred.resolve(),
+    hostname: "localhost",
+    port: servePort,
+  });
+  await listeningDeferred.promise;
+
+  using client = Deno.createHttpClient({});
+  const { promise, resolve, reject } = Promise.withResolvers<string>();
+  const ws = new WebSocket(serveUrl, {
+    protocols: ["custom"],
+    headers: { "x-custom": "hello" },
+    client,
+  });
+  ws.onerror = (e) => reject(e);
+  ws.onmessage = (e) => {
+    resolve(e.data);
+    ws.close();
+  };
+  assertEquals(await promise, "hello");
+  assertEquals(ws.protocol, "custom");
+  await server.finished;
+});
+
+Deno.test(async function websocketCustomAndResponseHeaders() {
+  const ac = new AbortController();
+  const listeningDeferred = Promise.withResolvers<void>();
+  const server = Deno.serve({
+    handler: (req) => {
+      const { socket, response } = Deno.upgradeWebSocket(req, {
+        protocol: "custom",
+      });
+      socket.onopen = () => {
+        socket.send(
+          `${req.headers.get("x-custom")} ${req.headers.get("authorization")}`,
+        );
+      };
+      socket.onclose = () => ac.abort();
+      return response;
+    },
+    signal: ac.signal,
+    onListen: () => listeningDeferred.resolve(),
+    hostname: "localhost",
+    port: servePort,
+  });
+  await listeningDeferred.promise;
+
+  const { promise, resolve, reject } = Promise.withResolvers<string>();
+  const ws = new WebSocket(serveUrl, {
+    protocols: "custom",
+    headers: new Headers({
+      "x-custom": "hello",
+      "authorization": "Bearer token",
+    }),
+  });
+  assertEquals(ws.headers, null);
+  ws.onerror = (e) => reject(e);
+  ws.onmessage = (e) => {
+    resolve(e.data);
+    ws.close();
+  };
+  assertEquals(await promise, "hello Bearer token");
+  assertEquals(ws.headers?.get("upgrade"), "websocket");
+  assertEquals(ws.headers?.get("sec-websocket-protocol"), "custom");
+  assert(ws.headers?.has("sec-websocket-accept"));
+  await server.finished;
+});
+
+Deno.test(function websocketInvalidClient() {
+  assertThrows(
+    // deno-lint-ignore no-explicit-any
+    () => new WebSocket(serveUrl, { client: {} as any }),
+    TypeError,
+    "`client` must be a Deno.HttpClient",
+  );
+});
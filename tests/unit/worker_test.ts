@@ -619,6 +619,60 @@ Deno.test("Worker permissions are not inherited with single specified permission
   worker.terminate();
 });
 
+Deno.test("Worker permissions from a capability token", async function () {
+  const token = Deno.createCapabilityToken({ read: true, net: ["foo"] });
+  const worker = new Worker(
+    resolveWorker("permission_echo.js"),
+    { type: "module", deno: { permissions: token } },
+  );
+
+  // deno-lint-ignore no-explicit-any
+  const { promise, resolve } = Promise.withResolvers<any>();
+  worker.onmessage = (e) => {
+    resolve(e.data);
+  };
+
+  worker.postMessage(null);
+  assertEquals(await promise, {
+    env: "prompt",
+    hrtime: "prompt",
+    net: "prompt",
+    ffi: "prompt",
+    read: "granted",
+    run: "prompt",
+    write: "prompt",
+  });
+  worker.terminate();
+});
+
+Deno.test("Worker with forged capability token", function () {
+  const token = Deno.createCapabilityToken({ read: true });
+  const [prefix, claims] = token.split(".");
+  const forged = `${prefix}.${claims}.${btoa("forged")}`;
+  assertThrows(
+    () =>
+      new Worker(`data:,close();`, {
+        type: "module",
+        // @ts-expect-error not created by Deno.createCapabilityToken()
+        deno: { permissions: forged },
+      }),
+    Deno.errors.PermissionDenied,
+    "Invalid capability token",
+  );
+});
+
+Deno.test({
+  name: "Capability token can't escalate permissions",
+  permissions: { env: false },
+  fn: function () {
+    assertThrows(
+      () => Deno.createCapabilityToken({ env: true }),
+      Deno.errors.PermissionDenied,
+      "Can't escalate parent thread permissions",
+    );
+  },
+});
+
 Deno.test("Worker with invalid permission arg", function () {
   assertThrows(
     () =>
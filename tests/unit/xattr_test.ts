@@ -0,0 +1,149 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertRejects,
+  assertThrows,
+} from "./test_util.ts";
+
+const xattrSupported = Deno.build.os === "linux" ||
+  Deno.build.os === "darwin";
+
+// Not every file system supports extended attributes, e.g. older kernels
+// don't support them on tmpfs.
+function trySetXattr(path: string): boolean {
+  try {
+    Deno.setXattrSync(path, "user.probe", "1");
+    Deno.removeXattrSync(path, "user.probe");
+    return true;
+  } catch (err) {
+    if (err instanceof Deno.errors.NotSupported) return false;
+    throw err;
+  }
+}
+
+Deno.test(
+  { ignore: !xattrSupported, permissions: { read: true, write: true } },
+  function xattrSyncSuccess() {
+    const filename = Deno.makeTempFileSync();
+    if (!trySetXattr(filename)) return;
+
+    assertEquals(Deno.getXattrSync(filename, "user.deno"), null);
+    Deno.setXattrSync(filename, "user.deno", "hello");
+    Deno.setXattrSync(filename, "user.bytes", new Uint8Array([0, 1, 2]));
+    assertEquals(
+      new TextDecoder().decode(Deno.getXattrSync(filename, "user.deno")!),
+      "hello",
+    );
+    assertEquals(
+      Deno.getXattrSync(filename, "user.bytes"),
+      new Uint8Array([0, 1, 2]),
+    );
+    const names = Deno.listXattrsSync(filename);
+    assert(names.includes("user.deno"));
+    assert(names.includes("user.bytes"));
+
+    Deno.removeXattrSync(filename, "user.deno");
+    assertEquals(Deno.getXattrSync(filename, "user.deno"), null);
+    assertThrows(
+      () => Deno.removeXattrSync(filename, "user.deno"),
+      Deno.errors.NotFound,
+    );
+  },
+);
+
+Deno.test(
+  { ignore: !xattrSupported, permissions: { read: true, write: true } },
+  async function xattrSuccess() {
+    const filename = await Deno.makeTempFile();
+    if (!trySetXattr(filename)) return;
+
+    await Deno.setXattr(filename, "user.deno", "hello");
+    assertEquals(
+      await Deno.getXattr(filename, "user.deno"),
+      new TextEncoder().encode("hello"),
+    );
+    assert((await Deno.listXattrs(filename)).includes("user.deno"));
+    await Deno.removeXattr(filename, "user.deno");
+    assertEquals(await Deno.getXattr(filename, "user.deno"), null);
+    await assertRejects(
+      () => Deno.removeXattr(filename, "user.deno"),
+      Deno.errors.NotFound,
+    );
+  },
+);
+
+Deno.test(
+  { ignore: !xattrSupported, permissions: { read: true, write: true } },
+  async function fsFileXattrSuccess() {
+    const filename = await Deno.makeTempFile();
+    if (!trySetXattr(filename)) return;
+
+    using file = await Deno.open(filename, { read: true, write: true });
+    await file.setXattr("user.deno", "hello");
+    file.setXattrSync("user.other", "world");
+    assertEquals(
+      file.getXattrSync("user.deno"),
+      new TextEncoder().encode("hello"),
+    );
+    assertEquals(
+      await file.getXattr("user.other"),
+      new TextEncoder().encode("world"),
+    );
+    assertEquals(
+      Deno.getXattrSync(filename, "user.deno"),
+      new TextEncoder().encode("hello"),
+    );
+    const names = await file.listXattrs();
+    assert(names.includes("user.deno"));
+    assert(file.listXattrsSync().includes("user.other"));
+
+    await file.removeXattr("user.deno");
+    file.removeXattrSync("user.other");
+    assertEquals(await file.getXattr("user.deno"), null);
+    assertThrows(
+      () => file.removeXattrSync("user.other"),
+      Deno.errors.NotFound,
+    );
+  },
+);
+
+Deno.test(
+  { ignore: xattrSupported, permissions: { read: true, write: true } },
+  function xattrNotSupported() {
+    const filename = Deno.makeTempFileSync();
+    assertThrows(
+      () => Deno.getXattrSync(filename, "user.deno"),
+      Deno.errors.NotSupported,
+    );
+    assertThrows(
+      () => Deno.setXattrSync(filename, "user.deno", "hello"),
+      Deno.errors.NotSupported,
+    );
+  },
+);
+
+Deno.test({ permissions: { read: false } }, async function xattrPerm() {
+  assertThrows(
+    () => Deno.getXattrSync("README.md", "user.deno"),
+    Deno.errors.PermissionDenied,
+  );
+  await assertRejects(
+    () => Deno.listXattrs("README.md"),
+    Deno.errors.PermissionDenied,
+  );
+});
+
+Deno.test(
+  { permissions: { read: true, write: false } },
+  function xattrWritePerm() {
+    assertThrows(
+      () => Deno.setXattrSync("README.md", "user.deno", "hello"),
+      Deno.errors.PermissionDenied,
+    );
+    assertThrows(
+      () => Deno.removeXattrSync("README.md", "user.deno"),
+      Deno.errors.PermissionDenied,
+    );
+  },
+);
@@ -1,5 +1,13 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
-import { AsyncLocalStorage, AsyncResource } from "node:async_hooks";
+import {
+  AsyncLocalStorage,
+  AsyncResource,
+  createHook,
+  executionAsyncId,
+  triggerAsyncId,
+} from "node:async_hooks";
+import { readFile } from "node:fs";
+import { setImmediate, setTimeout } from "node:timers";
 import { assert, assertEquals } from "@std/assert";
 
 Deno.test(async function foo() {
@@ -92,7 +100,7 @@ Deno.test(async function enterWith() {
   });
 
   assertEquals(await deferred.promise, { x: 2 });
-  assertEquals(await deferred1.promise, { x: 1 });
+  assertEquals(await deferred1.promise, null);
 });
 
 Deno.test(async function snapshot() {
@@ -135,3 +143,69 @@ Deno.test(function emitDestroyStub() {
   const resource = new AsyncResource("foo");
   assert(typeof resource.emitDestroy === "function");
 });
+
+Deno.test(async function contextAcrossTicksTimersAndFs() {
+  const als = new AsyncLocalStorage<number>();
+  const stores = await als.run(1, () =>
+    Promise.all([
+      new Promise((resolve) => process.nextTick(() => resolve(als.getStore()))),
+      new Promise((resolve) => setTimeout(() => resolve(als.getStore()), 1)),
+      new Promise((resolve) => setImmediate(() => resolve(als.getStore()))),
+      new Promise((resolve) =>
+        readFile(import.meta.filename!, () => resolve(als.getStore()))
+      ),
+    ]));
+  assertEquals(stores, [1, 1, 1, 1]);
+  assertEquals(als.getStore(), undefined);
+});
+
+Deno.test(async function createHookTracksNextTick() {
+  const events: [string, number, string?, number?][] = [];
+  const hook = createHook({
+    init(asyncId, type, triggerAsyncId) {
+      if (type === "TickObject") {
+        events.push(["init", asyncId, type, triggerAsyncId]);
+      }
+    },
+    before(asyncId) {
+      events.push(["before", asyncId]);
+    },
+    after(asyncId) {
+      events.push(["after", asyncId]);
+    },
+  }).enable();
+
+  const outerId = executionAsyncId();
+  const { promise, resolve } = Promise.withResolvers<[number, number]>();
+  process.nextTick(() => resolve([executionAsyncId(), triggerAsyncId()]));
+  const [tickId, tickTriggerId] = await promise;
+  hook.disable();
+
+  assertEquals(tickTriggerId, outerId);
+  assertEquals(events.filter(([, id]) => id === tickId), [
+    ["init", tickId, "TickObject", outerId],
+    ["before", tickId],
+    ["after", tickId],
+  ]);
+});
+
+Deno.test(async function createHookTracksPromises() {
+  const types: string[] = [];
+  const hook = createHook({
+    init(_asyncId, type) {
+      types.push(type);
+    },
+  }).enable();
+  await Promise.resolve();
+  hook.disable();
+  assert(types.includes("PROMISE"));
+});
+
+Deno.test(function asyncResourceTriggerAsyncId() {
+  const resource = new AsyncResource("foo", { triggerAsyncId: 42 });
+  assertEquals(resource.triggerAsyncId(), 42);
+  resource.runInAsyncScope(() => {
+    assertEquals(executionAsyncId(), resource.asyncId());
+    assertEquals(triggerAsyncId(), 42);
+  });
+});
@@ -0,0 +1,32 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+import cluster from "node:cluster";
+import * as path from "@std/path";
+import { assertEquals, assertStrictEquals } from "@std/assert";
+
+Deno.test("[node/cluster] the main process is the primary", () => {
+  assertStrictEquals(cluster.isPrimary, true);
+  assertStrictEquals(cluster.isWorker, false);
+  assertStrictEquals(cluster.isMaster, true);
+  assertStrictEquals(cluster.worker, undefined);
+  assertEquals(cluster.workers, {});
+  assertEquals(typeof cluster.fork, "function");
+});
+
+Deno.test({
+  name: "[node/cluster] workers share a round-robin http server",
+  ignore: Deno.build.os === "windows",
+  async fn() {
+    const script = path.join(
+      path.dirname(path.fromFileUrl(import.meta.url)),
+      "testdata/cluster_http.mjs",
+    );
+    const { code, stdout } = await new Deno.Command(Deno.execPath(), {
+      args: ["run", "-A", script],
+      env: { NO_COLOR: "1", NODE_CLUSTER_SCHED_POLICY: "rr" },
+    }).output();
+    assertEquals(code, 0);
+    // The requests are handed out to both workers in turn.
+    assertEquals(new TextDecoder().decode(stdout).trim(), "2");
+  },
+});
@@ -0,0 +1,48 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// @ts-ignore: argon2 is not part of @types/node yet
+import { argon2, argon2Sync } from "node:crypto";
+import { Buffer } from "node:buffer";
+import { assertEquals, assertThrows } from "@std/assert";
+
+// https://www.rfc-editor.org/rfc/rfc9106#section-5.3
+const rfcParameters = {
+  message: Buffer.alloc(32, 0x01),
+  nonce: Buffer.alloc(16, 0x02),
+  secret: Buffer.alloc(8, 0x03),
+  associatedData: Buffer.alloc(12, 0x04),
+  parallelism: 4,
+  tagLength: 32,
+  memory: 32,
+  passes: 3,
+};
+const rfcArgon2idTag =
+  "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659";
+
+Deno.test("argon2Sync matches the RFC 9106 test vector", () => {
+  const tag = argon2Sync("argon2id", rfcParameters);
+  assertEquals(tag.toString("hex"), rfcArgon2idTag);
+});
+
+Deno.test("argon2 matches the RFC 9106 test vector", async () => {
+  const { promise, resolve, reject } = Promise.withResolvers<Buffer>();
+  argon2("argon2id", rfcParameters, (err: Error | null, tag?: Buffer) => {
+    if (err) reject(err);
+    else resolve(tag!);
+  });
+  assertEquals((await promise).toString("hex"), rfcArgon2idTag);
+});
+
+Deno.test("argon2Sync validates parameters", () => {
+  assertThrows(
+    () => argon2Sync("argon2x", rfcParameters),
+    TypeError,
+  );
+  assertThrows(
+    () => argon2Sync("argon2id", { ...rfcParameters, nonce: "short" }),
+    RangeError,
+  );
+  assertThrows(
+    () => argon2Sync("argon2id", { ...rfcParameters, memory: 8 }),
+    RangeError,
+  );
+});
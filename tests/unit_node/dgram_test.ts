@@ -1,8 +1,9 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
-import { assertEquals } from "@std/assert";
+import { assertEquals, assertThrows } from "@std/assert";
 import { execCode } from "../unit/test_util.ts";
 import { createSocket } from "node:dgram";
+import { Buffer } from "node:buffer";
 
 const listenPort = 4503;
 const listenPort2 = 4504;
@@ -57,3 +58,85 @@ Deno.test("[node/dgram] udp unref", {
     `);
   assertEquals(statusCode, 0);
 });
+
+Deno.test("[node/dgram] udp socket options", {
+  permissions: { net: true },
+}, async () => {
+  const { promise, resolve } = Promise.withResolvers<void>();
+
+  const socket = createSocket("udp4");
+  assertThrows(() => socket.setBroadcast(true), Error, "EBADF");
+
+  socket.bind(0, () => {
+    socket.setBroadcast(true);
+    socket.setBroadcast(false);
+    assertEquals(socket.setTTL(64), 64);
+    assertEquals(socket.setMulticastTTL(2), 2);
+    assertEquals(socket.setMulticastLoopback(true), true);
+    socket.setMulticastInterface("127.0.0.1");
+    assertThrows(() => socket.setTTL(0), Error, "EINVAL");
+    socket.close(resolve);
+  });
+
+  await promise;
+});
+
+Deno.test("[node/dgram] udp multicast membership", {
+  permissions: { net: true },
+}, async () => {
+  const { promise, resolve } = Promise.withResolvers<Buffer>();
+  const group = "224.0.0.114";
+
+  const socket = createSocket({ type: "udp4", reuseAddr: true });
+  socket.on("message", (message) => resolve(message));
+
+  socket.bind(0, () => {
+    socket.addMembership(group, "127.0.0.1");
+    socket.setMulticastInterface("127.0.0.1");
+    socket.setMulticastLoopback(true);
+    socket.send("multicast", socket.address().port, group);
+  });
+
+  assertEquals((await promise).toString(), "multicast");
+  socket.dropMembership(group, "127.0.0.1");
+  socket.close();
+});
+
+Deno.test("[node/dgram] udp connected socket", {
+  permissions: { net: true },
+}, async () => {
+  const { promise, resolve } = Promise.withResolvers<string[]>();
+  const messages: string[] = [];
+
+  const server = createSocket("udp4");
+  server.on("message", (message, rinfo) => {
+    messages.push(message.toString());
+    if (messages.length === 2) {
+      resolve(messages);
+    } else {
+      server.send(message, rinfo.port, rinfo.address);
+    }
+  });
+
+  const other = createSocket("udp4");
+
+  server.bind(0, "127.0.0.1", () => {
+    const client = createSocket("udp4");
+    client.on("message", (message) => {
+      assertEquals(message.toString(), "hello");
+      client.send([Buffer.from("bye"), Buffer.from("!")], () => {
+        client.close();
+      });
+    });
+    client.connect(server.address().port, "127.0.0.1", () => {
+      // Messages from other sockets are not received when connected.
+      other.send("ignored", client.address().port, "127.0.0.1", () => {
+        client.send("hello");
+      });
+    });
+  });
+
+  assertEquals(await promise, ["hello", "bye!"]);
+  other.close();
+  server.close();
+});
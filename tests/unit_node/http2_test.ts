@@ -5,7 +5,7 @@ import { Buffer } from "node:buffer";
 import { readFile } from "node:fs/promises";
 import { join } from "node:path";
 import * as net from "node:net";
-import { assert, assertEquals } from "@std/assert";
+import { assert, assertEquals, assertThrows } from "@std/assert";
 import { curlRequest } from "../unit/test_util.ts";
 
 for (const url of ["http://127.0.0.1:4246", "https://127.0.0.1:4247"]) {
@@ -243,3 +243,91 @@ Deno.test("[node/http2 client] write 512kb buffer on request stream works", asyn
   await endPromise.promise;
   assertEquals(receivedData!, buffer);
 });
+
+Deno.test("[node/http2 settings] pack and unpack", () => {
+  const defaults = http2.getDefaultSettings();
+  assertEquals(defaults.enablePush, true);
+  assertEquals(defaults.maxFrameSize, 16384);
+
+  const packed = http2.getPackedSettings({
+    headerTableSize: 100,
+    enablePush: false,
+    maxHeaderSize: 2048,
+  });
+  assertEquals(
+    [...packed],
+    [0, 1, 0, 0, 0, 100, 0, 2, 0, 0, 0, 0, 0, 6, 0, 0, 8, 0],
+  );
+  assertEquals(http2.getUnpackedSettings(packed), {
+    headerTableSize: 100,
+    enablePush: false,
+    maxHeaderListSize: 2048,
+    maxHeaderSize: 2048,
+  });
+
+  assertThrows(
+    () => http2.getPackedSettings({ maxFrameSize: 1 }),
+    RangeError,
+    'Invalid value for setting "maxFrameSize": 1',
+  );
+  assertThrows(
+    () => http2.getUnpackedSettings(new Uint8Array(5)),
+    RangeError,
+    "Packed settings length must be a multiple of six",
+  );
+});
+
+Deno.test("[node/http2.createServer()] stream respond", {
+  ignore: Deno.build.os === "windows",
+}, async () => {
+  const server = http2.createServer();
+  server.on("stream", (stream, headers) => {
+    stream.respond({
+      ":status": 201,
+      "content-type": "text/plain",
+      "x-values": ["a", "b"],
+    });
+    stream.end(`${headers[":method"]} ${headers[":path"]}`);
+  });
+  server.listen(0);
+  const port = (<net.AddressInfo> server.address()).port;
+
+  const response = await curlRequest([
+    `http://localhost:${port}/foo?bar=baz`,
+    "--http2-prior-knowledge",
+    "--include",
+  ]);
+  assert(response.startsWith("HTTP/2 201"), response);
+  assert(response.includes("content-type: text/plain"), response);
+  assert(response.includes("x-values: a, b"), response);
+  assert(response.endsWith("GET /foo?bar=baz"), response);
+  server.close();
+  await new Promise<void>((resolve) => server.on("close", resolve));
+});
+
+Deno.test("[node/http2.createSecureServer()]", {
+  ignore: Deno.build.os === "windows",
+}, async () => {
+  const tlsDir = join(import.meta.dirname!, "..", "testdata", "tls");
+  const server = http2.createSecureServer({
+    cert: await readFile(join(tlsDir, "localhost.crt")),
+    key: await readFile(join(tlsDir, "localhost.key")),
+  }, (_req, res) => {
+    res.end("Hello, TLS!");
+  });
+  const { promise, resolve } = Promise.withResolvers<void>();
+  server.listen(0, resolve);
+  await promise;
+  const port = (<net.AddressInfo> server.address()).port;
+
+  const response = await curlRequest([
+    `https://localhost:${port}`,
+    "--http2",
+    "--cacert",
+    join(tlsDir, "RootCA.pem"),
+    "--write-out",
+    " %{http_version}",
+  ]);
+  assertEquals(response, "Hello, TLS! 2");
+  server.close();
+});
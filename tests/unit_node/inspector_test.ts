@@ -0,0 +1,77 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+import inspector, { Session } from "node:inspector";
+import { assertEquals, assertThrows } from "@std/assert";
+
+Deno.test("[node/inspector] Session.post evaluates expressions", async () => {
+  const session = new Session();
+  session.connect();
+
+  // deno-lint-ignore no-explicit-any
+  const { promise, resolve, reject } = Promise.withResolvers<any>();
+  session.post(
+    "Runtime.evaluate",
+    { expression: "1 + 2", returnByValue: true },
+    (err, result) => err ? reject(err) : resolve(result),
+  );
+  const { result } = await promise;
+  assertEquals(result.type, "number");
+  assertEquals(result.value, 3);
+
+  session.disconnect();
+});
+
+Deno.test("[node/inspector] Session.post reports protocol errors", async () => {
+  const session = new Session();
+  session.connect();
+
+  const { promise, resolve } = Promise.withResolvers<Error | null>();
+  session.post("Runtime.notAMethod", (err) => resolve(err));
+  const err = await promise;
+  // deno-lint-ignore no-explicit-any
+  assertEquals((err as any)?.code, "ERR_INSPECTOR_COMMAND");
+
+  session.disconnect();
+});
+
+Deno.test("[node/inspector] Session emits notifications", async () => {
+  const session = new Session();
+  session.connect();
+
+  const { promise, resolve } = Promise.withResolvers<
+    // deno-lint-ignore no-explicit-any
+    { method: string; params: any }
+  >();
+  session.once("Runtime.executionContextCreated", resolve);
+  session.post("Runtime.enable");
+  const notification = await promise;
+  assertEquals(notification.method, "Runtime.executionContextCreated");
+  assertEquals(typeof notification.params.context.id, "number");
+
+  session.post("Runtime.disable");
+  session.disconnect();
+});
+
+Deno.test("[node/inspector] Session requires a connection", () => {
+  const session = new Session();
+  assertThrows(
+    () => session.post("Runtime.evaluate"),
+    Error,
+    "Session is not connected",
+  );
+  session.connect();
+  assertThrows(
+    () => session.connect(),
+    Error,
+    "The inspector session is already connected",
+  );
+  session.disconnect();
+  // disconnecting twice is a no-op
+  session.disconnect();
+  assertThrows(() => session.connectToMainThread(), Error, "not a worker");
+});
+
+Deno.test("[node/inspector] default export", () => {
+  assertEquals(inspector.Session, Session);
+  assertEquals(inspector.url(), undefined);
+});
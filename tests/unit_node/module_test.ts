@@ -99,3 +99,71 @@ Deno.test("[node/module builtinModules] has 'module' in builtins", () => {
 Deno.test("[node/module findSourceMap] is a function", () => {
   assertEquals(findSourceMap("foo"), undefined);
 });
+
+Deno.test("[node/module wrap] matches the Node.js module wrapper", () => {
+  // deno-lint-ignore no-explicit-any
+  const { wrap, wrapper } = Module as any;
+  assertEquals(wrapper, [
+    "(function (exports, require, module, __filename, __dirname) { ",
+    "\n});",
+  ]);
+  assertEquals(wrap("foo"), `${wrapper[0]}foo${wrapper[1]}`);
+});
+
+Deno.test("[node/module wrap] patched wrapper is used to compile modules", () => {
+  // deno-lint-ignore no-explicit-any
+  const mod = Module as any;
+  const originalWrap = mod.wrap;
+  mod.wrap = (script: string) =>
+    originalWrap(`${script}\nmodule.exports.instrumented = true;`);
+  try {
+    const require = createRequire(import.meta.url);
+    const path = require.resolve("./testdata/cjs_load_counter.js");
+    delete require.cache[path];
+    const exports = require(path);
+    assert(exports.instrumented);
+    // node globals are still available to the wrapped code
+    assert(exports.hasBuffer);
+    delete require.cache[path];
+  } finally {
+    mod.wrap = originalWrap;
+  }
+});
+
+Deno.test("[node/module require.cache] entries can be removed and replaced", () => {
+  const require = createRequire(import.meta.url);
+  const path = require.resolve("./testdata/cjs_load_counter.js");
+  const first = require(path);
+  assertEquals(require(path), first);
+  assert(require.cache[path]);
+
+  delete require.cache[path];
+  const second = require(path);
+  assertEquals(second.loadCount, first.loadCount + 1);
+
+  const stub = { stubbed: true };
+  // deno-lint-ignore no-explicit-any
+  require.cache[path] = { id: path, exports: stub, loaded: true } as any;
+  assertEquals(require(path), stub);
+  delete require.cache[path];
+});
+
+Deno.test("[node/module _resolveFilename] can be patched", () => {
+  // deno-lint-ignore no-explicit-any
+  const mod = Module as any;
+  const require = createRequire(import.meta.url);
+  const target = require.resolve("./testdata/cjs_load_counter.js");
+  const originalResolveFilename = mod._resolveFilename;
+  mod._resolveFilename = function (request: string, ...args: unknown[]) {
+    if (request === "virtual-module") {
+      return target;
+    }
+    return originalResolveFilename.call(this, request, ...args);
+  };
+  try {
+    assertEquals(require.resolve("virtual-module"), target);
+    assertEquals(require("virtual-module"), require(target));
+  } finally {
+    mod._resolveFilename = originalResolveFilename;
+  }
+});
@@ -971,6 +971,65 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "process.binding('fs') and process.binding('constants')",
+  fn() {
+    // @ts-ignore: untyped internal binding
+    const fs = process.binding("fs");
+    assertEquals(fs.internalModuleStat(import.meta.dirname), 1);
+    assertEquals(fs.internalModuleStat(import.meta.filename), 0);
+    assert(fs.internalModuleStat("./does_not_exist") < 0);
+    assertEquals(typeof fs.FSReqCallback, "function");
+
+    // @ts-ignore: untyped internal binding
+    const constants = process.binding("constants");
+    assertEquals(constants.os.signals.SIGINT, 2);
+  },
+});
+
+Deno.test({
+  name: "process.binding() allows the bindings Node.js allows",
+  fn() {
+    for (
+      const name of [
+        "http_parser",
+        "natives",
+        "os",
+        "stream_wrap",
+        "tcp_wrap",
+        "tty_wrap",
+        "zlib",
+      ]
+    ) {
+      // @ts-ignore: untyped internal binding
+      assert(process.binding(name), name);
+    }
+  },
+});
+
+Deno.test({
+  name: "process.binding() rejects internal-only bindings",
+  fn() {
+    assertThrows(
+      // @ts-ignore: untyped internal binding
+      () => process.binding("worker"),
+      Error,
+      "No such module: worker",
+    );
+  },
+});
+
+Deno.test({
+  name: "process.report lists requested process bindings",
+  fn() {
+    // @ts-ignore: untyped internal binding
+    process.binding("uv");
+    // deno-lint-ignore no-explicit-any
+    const report = process.report!.getReport() as any;
+    assert(report.processBindings.includes("uv"));
+  },
+});
+
 Deno.test({
   name: "process.report",
   fn() {
@@ -2,7 +2,8 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 import repl from "node:repl";
-import { assert } from "@std/assert";
+import { PassThrough } from "node:stream";
+import { assert, assertEquals, assertStringIncludes } from "@std/assert";
 
 Deno.test({
   name: "repl module exports",
@@ -15,3 +16,120 @@ Deno.test({
     assert(typeof repl._builtinLibs !== "undefined");
   },
 });
+
+function startRepl(options: repl.ReplOptions = {}) {
+  const input = new PassThrough();
+  const output = new PassThrough();
+  let text = "";
+  output.setEncoding("utf8");
+  output.on("data", (chunk: string) => text += chunk);
+  const server = repl.start({ input, output, terminal: false, ...options });
+  return {
+    server,
+    /** Sends a line and resolves to what was written while evaluating it. */
+    async send(line: string) {
+      const start = text.length;
+      input.write(line + "\n");
+      // let the evaluation and the asynchronous writes finish
+      await new Promise((resolve) => setTimeout(resolve, 10));
+      return text.slice(start);
+    },
+  };
+}
+
+Deno.test("[node/repl] evaluates input", async () => {
+  const { server, send } = startRepl({ prompt: "> " });
+  assertEquals(await send("1 + 2"), "3\n> ");
+  assertEquals(await send("const a = { b: 1 }"), "undefined\n> ");
+  assertEquals(await send("a.b"), "1\n> ");
+  assertEquals(await send("{ c: 2 }"), "{ c: 2 }\n> ");
+  assertEquals(await send("_"), "{ c: 2 }\n> ");
+  assertEquals(await send("await Promise.resolve(4)"), "4\n> ");
+  server.close();
+});
+
+Deno.test("[node/repl] continues incomplete input", async () => {
+  const { server, send } = startRepl({ prompt: "> " });
+  assertEquals(await send("function add(a, b) {"), "... ");
+  assertEquals(await send("  return a + b;"), "... ");
+  assertEquals(await send("}"), "undefined\n> ");
+  assertEquals(await send("add(1, 2)"), "3\n> ");
+  server.close();
+});
+
+Deno.test("[node/repl] continues by brackets and templates", async () => {
+  const { server, send } = startRepl({ prompt: "> " });
+  assertEquals(await send("[1,"), "... ");
+  assertEquals(await send("  `a${"), "... ");
+  assertEquals(await send("  ')'}`]"), "[ 1, 'a)' ]\n> ");
+  assertEquals(await send("/* {"), "... ");
+  assertEquals(await send("*/ /[(]/.test('(')"), "true\n> ");
+  // syntax errors that more input can't fix aren't continued
+  assertStringIncludes(await send("'a"), "Uncaught SyntaxError");
+  assertStringIncludes(await send("(1))"), "Uncaught SyntaxError");
+  assertStringIncludes(await send("f(1]"), "Uncaught SyntaxError");
+  server.close();
+});
+
+Deno.test("[node/repl] reports errors", async () => {
+  const { server, send } = startRepl({ prompt: "> " });
+  const output = await send("throw new Error('boom')");
+  assertStringIncludes(output, "Uncaught Error: boom");
+  assertEquals(await send("_error.message"), "'boom'\n> ");
+  server.close();
+});
+
+Deno.test("[node/repl] commands", async () => {
+  const { server, send } = startRepl({ prompt: "> " });
+  server.defineCommand("hello", {
+    help: "Say hello",
+    action(name) {
+      this.output.write(`Hello, ${name}!\n`);
+      this.displayPrompt();
+    },
+  });
+  assertEquals(await send(".hello Deno"), "Hello, Deno!\n> ");
+  assertStringIncludes(await send(".help"), ".hello   Say hello\n");
+  assertEquals(await send(".nope"), "Invalid REPL keyword\n> ");
+
+  await send("const x = 1");
+  assertEquals(await send(".clear"), "Clearing context...\n> ");
+  assertStringIncludes(await send("x"), "Uncaught ReferenceError");
+
+  const { promise, resolve } = Promise.withResolvers<void>();
+  server.on("exit", resolve);
+  await send(".exit");
+  await promise;
+});
+
+Deno.test("[node/repl] completes input", async () => {
+  const { server } = startRepl();
+  const complete = (line: string) =>
+    new Promise<[string[], string]>((resolve, reject) => {
+      // deno-lint-ignore no-explicit-any
+      (server as any).completer(
+        line,
+        (err: Error | null, result: [string[], string]) =>
+          err ? reject(err) : resolve(result),
+      );
+    });
+
+  assertEquals(await complete("Math.ma"), [["Math.max"], "Math.ma"]);
+  assertEquals(await complete(".ex"), [[".exit"], ".ex"]);
+  const [completions] = await complete("JSO");
+  assert(completions.includes("JSON"));
+  server.close();
+});
+
+Deno.test("[node/repl] loads the history", async () => {
+  const historyPath = Deno.makeTempFileSync();
+  Deno.writeTextFileSync(historyPath, "b\na\n");
+  const { server } = startRepl();
+  const { promise, resolve, reject } = Promise.withResolvers<void>();
+  server.setupHistory(historyPath, (err) => err ? reject(err) : resolve());
+  await promise;
+  // deno-lint-ignore no-explicit-any
+  assertEquals((server as any).history, ["b", "a"]);
+  server.close();
+  Deno.removeSync(historyPath);
+});
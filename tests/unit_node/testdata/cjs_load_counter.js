@@ -0,0 +1,6 @@
+globalThis.cjsLoadCount = (globalThis.cjsLoadCount ?? 0) + 1;
+
+module.exports = {
+  loadCount: globalThis.cjsLoadCount,
+  hasBuffer: typeof Buffer === "function",
+};
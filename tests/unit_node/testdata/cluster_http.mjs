@@ -0,0 +1,31 @@
+import cluster from "node:cluster";
+import http from "node:http";
+import process from "node:process";
+
+if (cluster.isPrimary) {
+  let port;
+  let listening = 0;
+  cluster.on("listening", async (_worker, address) => {
+    port = address.port;
+    if (++listening < 2) {
+      return;
+    }
+    // Every request is made on a new connection, so that it is handed to
+    // the next worker.
+    const pids = new Set();
+    for (let i = 0; i < 4; i++) {
+      const conn = await Deno.connect({ hostname: "127.0.0.1", port });
+      await conn.write(new TextEncoder().encode("GET / HTTP/1.0\r\n\r\n"));
+      const res = await new Response(conn.readable).text();
+      pids.add(res.slice(res.indexOf("\r\n\r\n") + 4));
+    }
+    console.log(pids.size);
+    cluster.disconnect();
+  });
+  cluster.fork();
+  cluster.fork();
+} else {
+  http.createServer((_req, res) => {
+    res.end(`${process.pid}`);
+  }).listen(0, "127.0.0.1");
+}
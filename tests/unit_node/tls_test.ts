@@ -6,6 +6,7 @@ import { fromFileUrl, join } from "@std/path";
 import * as tls from "node:tls";
 import * as net from "node:net";
 import * as stream from "node:stream";
+import { X509Certificate } from "node:crypto";
 
 const tlsTestdataDir = fromFileUrl(
   new URL("../testdata/tls", import.meta.url),
@@ -191,3 +192,79 @@ Deno.test("tlssocket._handle._parentWrap is set", () => {
       ._parentWrap;
   assertInstanceOf(parentWrap, stream.PassThrough);
 });
+
+Deno.test("tls.createServer picks certificates with SNICallback", async () => {
+  const servernames: string[] = [];
+  const server = tls.createServer({
+    SNICallback(servername, callback) {
+      servernames.push(servername);
+      callback(null, tls.createSecureContext({ key, cert }));
+    },
+  }, (socket) => {
+    socket.end("hello");
+  });
+
+  const { promise, resolve } = Promise.withResolvers<void>();
+  server.listen(0, resolve);
+  await promise;
+
+  const conn = await Deno.connectTls({
+    hostname: "localhost",
+    // deno-lint-ignore no-explicit-any
+    port: (server.address() as any).port,
+    caCerts: [rootCaCert],
+  });
+  const text = await new Response(conn.readable).text();
+  assertEquals(text, "hello");
+  assertEquals(servernames, ["localhost"]);
+  server.close();
+});
+
+Deno.test("tls.Server addContext matches server names", async () => {
+  const server = tls.createServer({}, (socket) => {
+    assertEquals((socket as tls.TLSSocket).encrypted, true);
+    socket.end("hello");
+  });
+  server.addContext("*.example.com", { key: "invalid", cert: "invalid" });
+  server.addContext("localhost", { key, cert });
+
+  const { promise, resolve } = Promise.withResolvers<void>();
+  server.listen(0, resolve);
+  await promise;
+
+  const conn = await Deno.connectTls({
+    hostname: "localhost",
+    // deno-lint-ignore no-explicit-any
+    port: (server.address() as any).port,
+    caCerts: [rootCaCert],
+  });
+  assertEquals(await new Response(conn.readable).text(), "hello");
+  server.close();
+});
+
+Deno.test("tls.connect gets the peer certificate", async () => {
+  const listener = Deno.listenTls({ port: 0, key, cert });
+  const socket = tls.connect({
+    host: "localhost",
+    port: listener.addr.port,
+    secureContext: tls.createSecureContext({ ca: rootCaCert }),
+  });
+
+  const conn = await listener.accept();
+  await conn.handshake();
+  await new Promise((resolve) => socket.once("secureConnect", resolve));
+
+  const certificate = socket.getPeerCertificate(true);
+  assertEquals(certificate.subject.CN, "localhost.local");
+  assertEquals(certificate.issuer.CN, "Example-Root-CA");
+  assertEquals(certificate.subjectaltname, "DNS:localhost");
+  assertEquals(
+    certificate.fingerprint256,
+    new X509Certificate(cert).fingerprint256,
+  );
+
+  conn.close();
+  socket.destroy();
+  listener.close();
+  await new Promise((resolve) => socket.on("close", resolve));
+});
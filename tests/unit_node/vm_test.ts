@@ -1,12 +1,19 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
-import { assertEquals, assertThrows } from "@std/assert";
 import {
+  assert,
+  assertEquals,
+  assertRejects,
+  assertThrows,
+} from "@std/assert";
+import {
+  compileFunction,
   createContext,
   isContext,
   runInContext,
   runInNewContext,
   runInThisContext,
   Script,
+  SourceTextModule,
 } from "node:vm";
 
 Deno.test({
@@ -179,3 +186,68 @@ Deno.test({
     assertEquals(result, 1);
   },
 });
+
+Deno.test({
+  name: "vm compileFunction",
+  fn() {
+    const add = compileFunction("return a + b", ["a", "b"]);
+    assertEquals(add(1, 2), 3);
+
+    const context = createContext({ x: 2 });
+    const getX = compileFunction("return [x, globalThis.Array]", [], {
+      parsingContext: context,
+    });
+    const [x, ContextArray] = getX();
+    assertEquals(x, 2);
+    assert(ContextArray !== Array);
+  },
+});
+
+Deno.test({
+  name: "vm SourceTextModule",
+  async fn() {
+    const context = createContext({ secret: 42 });
+    const dependency = new SourceTextModule(
+      "export const value = secret; export const realm = globalThis.Array;",
+      { context },
+    );
+    const root = new SourceTextModule(
+      "import { realm, value } from 'dep'; export default [value, realm];",
+      { context, identifier: "root.mjs" },
+    );
+    assertEquals(root.identifier, "root.mjs");
+    assertEquals(root.dependencySpecifiers, ["dep"]);
+    assertEquals(root.status, "unlinked");
+
+    const specifiers: string[] = [];
+    await root.link((specifier: string) => {
+      specifiers.push(specifier);
+      return dependency;
+    });
+    assertEquals(specifiers, ["dep"]);
+    assertEquals(root.status, "linked");
+    assertEquals(dependency.status, "linked");
+
+    await root.evaluate();
+    assertEquals(root.status, "evaluated");
+    const [value, ContextArray] = root.namespace.default;
+    assertEquals(value, 42);
+    assert(ContextArray !== Array);
+  },
+});
+
+Deno.test({
+  name: "vm SourceTextModule errors",
+  async fn() {
+    assertThrows(() => new SourceTextModule("export {"), SyntaxError);
+
+    const module = new SourceTextModule("throw new Error('boom')");
+    await assertRejects(() => module.evaluate(), Error, "Module status");
+    await module.link(() => {
+      throw new Error("no imports to link");
+    });
+    await assertRejects(() => module.evaluate(), Error, "boom");
+    assertEquals(module.status, "errored");
+    assertEquals((module.error as Error).message, "boom");
+  },
+});
@@ -11,6 +11,7 @@ import { fromFileUrl, relative, SEPARATOR } from "@std/path";
 import * as workerThreads from "node:worker_threads";
 import { EventEmitter, once } from "node:events";
 import process from "node:process";
+import { Readable } from "node:stream";
 
 Deno.test("[node/worker_threads] BroadcastChannel is exported", () => {
   assertEquals<unknown>(workerThreads.BroadcastChannel, BroadcastChannel);
@@ -590,3 +591,100 @@ Deno.test({
     channel.port2.close();
   },
 });
+
+Deno.test({
+  name: "[node/worker_threads] MessagePort received from a worker is patched",
+  async fn() {
+    const worker = new workerThreads.Worker(
+      `
+      import { MessageChannel, parentPort } from "node:worker_threads";
+      const { port1, port2 } = new MessageChannel();
+      parentPort.postMessage({ port: port2 }, [port2]);
+      port1.close();
+      `,
+      {
+        eval: true,
+      },
+    );
+    const [{ port }] = await once(worker, "message");
+    assertEquals(typeof port.on, "function");
+    assertEquals(typeof port.once, "function");
+    port.close();
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "[node/worker_threads] Worker emits exit after its messages",
+  async fn() {
+    const messages: unknown[] = [];
+    const worker = new workerThreads.Worker(
+      `
+      import { parentPort } from "node:worker_threads";
+      parentPort.postMessage("ok");
+      `,
+      {
+        eval: true,
+      },
+    );
+    worker.on("message", (message) => messages.push(message));
+    const [code] = await once(worker, "exit");
+    assertEquals(code, 0);
+    assertEquals(messages, ["ok"]);
+  },
+});
+
+Deno.test({
+  name: "[node/worker_threads] Worker stdout and stderr",
+  async fn() {
+    const worker = new workerThreads.Worker(
+      `
+      import process from "node:process";
+      console.log("hello");
+      process.stdout.write("from stdout\\n");
+      console.error("from stderr");
+      `,
+      {
+        eval: true,
+        stdout: true,
+        stderr: true,
+      },
+    );
+    const read = async (stream: Readable) => {
+      let output = "";
+      for await (const chunk of stream) {
+        output += new TextDecoder().decode(chunk);
+      }
+      return output;
+    };
+    const [stdout, stderr] = await Promise.all([
+      read(worker.stdout),
+      read(worker.stderr),
+    ]);
+    assertEquals(stdout, "hello\nfrom stdout\n");
+    assertEquals(stderr, "from stderr\n");
+  },
+});
+
+Deno.test({
+  name: "[node/worker_threads] markAsUntransferable",
+  fn() {
+    // Not part of the Node 20 types yet.
+    const { isMarkedAsUntransferable } = workerThreads as unknown as {
+      isMarkedAsUntransferable(object: unknown): boolean;
+    };
+    const buffer = new ArrayBuffer(8);
+    assert(!isMarkedAsUntransferable(buffer));
+    workerThreads.markAsUntransferable(buffer);
+    assert(isMarkedAsUntransferable(buffer));
+
+    const { port1, port2 } = new workerThreads.MessageChannel();
+    port1.postMessage(buffer, [buffer]);
+    // The buffer is copied instead of being detached.
+    assertEquals(buffer.byteLength, 8);
+    const { message } = workerThreads.receiveMessageOnPort(port2)!;
+    assertEquals((message as ArrayBuffer).byteLength, 8);
+    port1.close();
+    port2.close();
+  },
+});
@@ -3,9 +3,13 @@
 import { assert, assertEquals } from "@std/assert";
 import { fromFileUrl, relative } from "@std/path";
 import {
+  BrotliCompress,
   brotliCompress,
   brotliCompressSync,
+  BrotliDecompress,
+  brotliDecompress,
   brotliDecompressSync,
+  constants,
   createBrotliCompress,
   createBrotliDecompress,
   createDeflate,
@@ -177,3 +181,37 @@ Deno.test("brotli decompress flush restore size", async () => {
   );
   assertEquals(output.length, input.length);
 });
+
+Deno.test("brotli stream classes", async () => {
+  const input = Buffer.from("hello brotli ".repeat(10000));
+  const compress = new BrotliCompress({
+    params: {
+      [constants.BROTLI_PARAM_QUALITY]: 4,
+      [constants.BROTLI_PARAM_SIZE_HINT]: input.length,
+    },
+  });
+  const output = await buffer(
+    Readable.from([input.subarray(0, 100), input.subarray(100)])
+      .pipe(compress)
+      .pipe(new BrotliDecompress()),
+  );
+  assert(input.equals(output));
+});
+
+Deno.test("brotli decompress async", async () => {
+  const compressed = brotliCompressSync("hello world");
+  const decompressed = await new Promise<Buffer>((resolve, reject) => {
+    brotliDecompress(compressed, (err, res) => {
+      if (err) reject(err);
+      else resolve(res);
+    });
+  });
+  assertEquals(decompressed.toString(), "hello world");
+});
+
+Deno.test("brotli compress Buffer view", () => {
+  // Only the viewed bytes must be compressed, not the whole ArrayBuffer.
+  const buf = Buffer.from("xxpooledxx").subarray(2, 8);
+  const decompressed = brotliDecompressSync(brotliCompressSync(buf));
+  assertEquals(decompressed.toString(), "pooled");
+});